@@ -0,0 +1,114 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use serenity::all::{ChannelId, GuildId, UserId};
+use songbird::input::codecs::{get_codec_registry, get_probe};
+
+use soundboard_bot::config::Config;
+use soundboard_bot::db::UniqueAudioTableCol;
+use soundboard_bot::opus_cache::{self, OPUS_CACHE};
+use soundboard_bot::playback::{self, PlaySource};
+use soundboard_bot::test_utils::{AudioRowFixture, MockVoice, TempDir, TestDb};
+
+/// Time from "play requested" (track lookup by name or id) until the audio file is handed
+/// to the voice driver. `MockVoice` stands in for songbird, so only bot-side latency is measured
+fn play_startup(c: &mut Criterion) {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+
+    let db = TestDb::new();
+    // plays are repeated far faster than any rate limit allows
+    let data = db.user_data_with_config(Config {
+        play_rate_limit_user: 0,
+        play_rate_limit_guild: 0,
+        ..Config::default()
+    });
+    let row = AudioRowFixture::new()
+        .name("Air Horn")
+        .insert(&data.audio_table());
+    let voice = MockVoice::new();
+
+    let guild_id = GuildId::new(1);
+    let channel_id = ChannelId::new(2);
+    let user_id = UserId::new(3);
+
+    let mut group = c.benchmark_group("play_startup");
+
+    group.bench_function("by_name", |b| {
+        b.to_async(&runtime).iter(|| {
+            playback::play_audio_track(
+                &data,
+                &voice,
+                guild_id,
+                channel_id,
+                user_id,
+                UniqueAudioTableCol::Name("Air Horn".into()),
+                PlaySource::Slash,
+            )
+        })
+    });
+
+    group.bench_function("by_id", |b| {
+        b.to_async(&runtime).iter(|| {
+            playback::play_audio_track(
+                &data,
+                &voice,
+                guild_id,
+                channel_id,
+                user_id,
+                UniqueAudioTableCol::Id(row.id),
+                PlaySource::Slash,
+            )
+        })
+    });
+
+    group.finish();
+}
+
+/// One second of 48kHz mono 16-bit PCM silence as a WAV file
+fn write_wav(path: &std::path::Path) {
+    let samples = vec![0u8; 48_000 * 2];
+    let mut wav = Vec::with_capacity(44 + samples.len());
+    wav.extend_from_slice(b"RIFF");
+    wav.extend_from_slice(&(36 + samples.len() as u32).to_le_bytes());
+    wav.extend_from_slice(b"WAVEfmt ");
+    wav.extend_from_slice(&16u32.to_le_bytes());
+    wav.extend_from_slice(&1u16.to_le_bytes()); // PCM
+    wav.extend_from_slice(&1u16.to_le_bytes()); // mono
+    wav.extend_from_slice(&48_000u32.to_le_bytes());
+    wav.extend_from_slice(&(48_000u32 * 2).to_le_bytes());
+    wav.extend_from_slice(&2u16.to_le_bytes());
+    wav.extend_from_slice(&16u16.to_le_bytes());
+    wav.extend_from_slice(b"data");
+    wav.extend_from_slice(&(samples.len() as u32).to_le_bytes());
+    wav.extend_from_slice(&samples);
+    std::fs::write(path, wav).unwrap();
+}
+
+/// Time until songbird's input of a sound is playable, i.e. its first frame can be mixed,
+/// with the Opus cache on (sound transcoded by the warm-up) and off (decoded from its file)
+fn first_frame(c: &mut Criterion) {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+
+    let dir = TempDir::new();
+    let uncached = dir.path().join("uncached.wav");
+    let cached = dir.path().join("cached.wav");
+    write_wav(&uncached);
+    write_wav(&cached);
+    runtime.block_on(OPUS_CACHE.insert(&cached)).unwrap();
+
+    let mut group = c.benchmark_group("first_frame");
+
+    for (name, path) in [("opus_cache_off", &uncached), ("opus_cache_on", &cached)] {
+        group.bench_function(name, |b| {
+            b.to_async(&runtime).iter(|| async {
+                opus_cache::audio_input(path)
+                    .make_playable_async(get_codec_registry(), get_probe())
+                    .await
+                    .unwrap()
+            })
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, play_startup, first_frame);
+criterion_main!(benches);
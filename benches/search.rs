@@ -0,0 +1,64 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+
+use soundboard_bot::db::AudioTablePaginator;
+use soundboard_bot::test_utils::{AudioRowFixture, TestDb};
+use soundboard_bot::vars;
+
+const LIBRARY_SIZES: [usize; 2] = [10_000, 100_000];
+
+fn make_library(rows: usize) -> TestDb {
+    let db = TestDb::new();
+    let audio_rows = (0..rows).map(|i| {
+        AudioRowFixture::new()
+            .name(format!("Sound Effect {i}"))
+            .tags(format!("tag{} meme-{}", i % 100, i % 7))
+            .build()
+    });
+
+    db.audio_table().insert_many(audio_rows, true).unwrap();
+    db
+}
+
+fn fts_search(c: &mut Criterion) {
+    let mut group = c.benchmark_group("fts_search");
+    group.sample_size(20);
+
+    let limit = vars::AUTOCOMPLETE_LIMIT as usize;
+    let min_query_len = vars::AUTOCOMPLETE_MIN_QUERY_LEN as usize;
+
+    for rows in LIBRARY_SIZES {
+        let db = make_library(rows);
+        let table = db.audio_table();
+
+        group.bench_with_input(BenchmarkId::new("partial", rows), &rows, |b, _| {
+            b.iter(|| table.autocomplete_tracks("effect 42", None, limit, min_query_len))
+        });
+
+        group.bench_with_input(BenchmarkId::new("low_char_ranked", rows), &rows, |b, _| {
+            b.iter(|| table.autocomplete_tracks("so", Some(1), limit, min_query_len))
+        });
+    }
+
+    group.finish();
+}
+
+fn paginator_throughput(c: &mut Criterion) {
+    let mut group = c.benchmark_group("paginator");
+    group.sample_size(10);
+
+    for rows in LIBRARY_SIZES {
+        let db = make_library(rows);
+
+        group.bench_with_input(BenchmarkId::new("all_pages", rows), &rows, |b, _| {
+            b.iter(|| {
+                let paginator = AudioTablePaginator::builder(db.connection()).build();
+                paginator.map(|page| page.unwrap().len()).sum::<usize>()
+            })
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, fts_search, paginator_throughput);
+criterion_main!(benches);
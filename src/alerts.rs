@@ -0,0 +1,160 @@
+//! Error alerts, so hosts notice breakage without tailing logs. Errors recorded by
+//! `diagnostics::record_error` (download failures, database errors, voice join failures, ...)
+//! are posted to `Config::error_alert_channel_id` and `Config::error_alert_webhook_url`.
+//! Errors raised within `vars::ERROR_ALERT_BATCH_SECS` of each other are posted as one message
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+
+use serenity::all::{ChannelId, CreateMessage, ExecuteWebhook, Http, Webhook};
+use tokio::sync::mpsc;
+
+use crate::commands::PoiseError;
+use crate::config::Config;
+use crate::diagnostics::RecentError;
+use crate::helpers;
+use crate::vars;
+
+/// Sender of the alert job. Unset until the job is spawned, or when no destination is configured
+static ALERTS: OnceLock<mpsc::UnboundedSender<RecentError>> = OnceLock::new();
+
+/// Queue error to be alerted, if alerts are enabled
+pub fn send(error: &RecentError) {
+    if let Some(alerts) = ALERTS.get() {
+        alerts.send(error.clone()).ok();
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct AlertDestinations {
+    pub channel_id: Option<ChannelId>,
+    pub webhook_url: Option<String>,
+}
+
+impl AlertDestinations {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            channel_id: config.error_alert_channel_id.map(ChannelId::new),
+            webhook_url: config.error_alert_webhook_url.clone(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.channel_id.is_none() && self.webhook_url.is_none()
+    }
+}
+
+/// Start posting errors to the configured destinations. Only the first call starts the job
+pub fn spawn_alert_job(http: Arc<Http>, config: &Config) {
+    let destinations = AlertDestinations::from_config(config);
+    if destinations.is_empty() {
+        return;
+    }
+
+    let (sender, mut receiver) = mpsc::unbounded_channel();
+    if ALERTS.set(sender).is_err() {
+        return;
+    }
+
+    log::info!("Alerting errors to {destinations:?}");
+    tokio::spawn(async move {
+        while let Some(error) = receiver.recv().await {
+            tokio::time::sleep(Duration::from_secs(vars::ERROR_ALERT_BATCH_SECS)).await;
+
+            let mut errors = vec![error];
+            while let Ok(error) = receiver.try_recv() {
+                errors.push(error);
+            }
+
+            post_alert(&http, &destinations, &alert_message(&errors)).await;
+        }
+    });
+}
+
+/// Alert of errors, listing at most `vars::ERROR_ALERT_MAX_ERRORS` of the latest ones
+pub fn alert_message(errors: &[RecentError]) -> String {
+    let skipped = errors.len().saturating_sub(vars::ERROR_ALERT_MAX_ERRORS);
+    let mut lines = vec![format!("⚠️ {} bot error(s)", errors.len())];
+    lines.extend(
+        errors[skipped..]
+            .iter()
+            .map(|error| format!("- <t:{}:T> {}", error.at.timestamp(), error.message)),
+    );
+    if skipped > 0 {
+        lines.push(format!("...and {skipped} earlier error(s)"));
+    }
+
+    helpers::truncate_message(lines.join("\n"))
+}
+
+/// Failures are logged as warnings. Errors would be alerted again, and could loop forever
+async fn post_alert(http: &Http, destinations: &AlertDestinations, content: &str) {
+    if let Some(channel_id) = destinations.channel_id {
+        if let Err(err) = channel_id
+            .send_message(http, CreateMessage::new().content(content))
+            .await
+        {
+            log::warn!("Failed to post error alert to channel {channel_id} - {err}");
+        }
+    }
+
+    if let Some(webhook_url) = &destinations.webhook_url {
+        if let Err(err) = execute_webhook(http, webhook_url, content).await {
+            log::warn!("Failed to post error alert to webhook - {err}");
+        }
+    }
+}
+
+async fn execute_webhook(http: &Http, webhook_url: &str, content: &str) -> Result<(), PoiseError> {
+    let webhook = Webhook::from_url(http, webhook_url).await?;
+    webhook
+        .execute(http, false, ExecuteWebhook::new().content(content))
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+
+    use super::*;
+
+    fn error(message: &str) -> RecentError {
+        RecentError {
+            at: Utc.with_ymd_and_hms(2024, 1, 2, 4, 30, 0).unwrap(),
+            message: message.into(),
+        }
+    }
+
+    #[test]
+    fn alert_message_test() {
+        assert_eq!(
+            "⚠️ 1 bot error(s)\n- <t:1704169800:T> Failed to download audio",
+            alert_message(&[error("Failed to download audio")])
+        );
+
+        let errors: Vec<_> = (0..vars::ERROR_ALERT_MAX_ERRORS + 2)
+            .map(|index| error(&index.to_string()))
+            .collect();
+        let message = alert_message(&errors);
+        assert!(!message.contains("> 1\n"));
+        assert!(message.contains("> 2\n"));
+        assert!(message.ends_with("...and 2 earlier error(s)"));
+    }
+
+    #[test]
+    fn alert_destinations_test() {
+        assert!(AlertDestinations::from_config(&Config::default()).is_empty());
+
+        let config = Config {
+            error_alert_channel_id: Some(1234),
+            ..Default::default()
+        };
+        assert_eq!(
+            AlertDestinations {
+                channel_id: Some(ChannelId::new(1234)),
+                webhook_url: None,
+            },
+            AlertDestinations::from_config(&config)
+        );
+    }
+}
@@ -0,0 +1,243 @@
+//! Sound approval workflow. In guilds with a review channel (`/settings approval`), sounds added
+//! by members who may not approve sounds (see `permissions::is_approver`) are kept as pending
+//! sounds and posted in the review channel with Listen/Approve/Reject buttons. Only approved
+//! sounds are added to the soundboard, rejected sounds' files are deleted
+use serenity::all::{
+    ButtonStyle, ChannelId, CreateActionRow, CreateButton, CreateMessage, GuildId, Http,
+};
+
+use crate::commands::{PoiseContext, PoiseError};
+use crate::common::UserData;
+use crate::db::{AudioTableRowInsert, PendingSoundRow, SoundName, UniqueAudioTableCol};
+use crate::errors::InsertAudioError;
+use crate::helpers::{self, ButtonAction, ButtonCustomId};
+use crate::i18n::{self, tr, Language};
+use crate::permissions;
+use crate::temp_files::TempFileGuard;
+
+/// Review channel sounds added by the command author go to. None if the guild doesn't review
+/// sounds, or the author may approve sounds themselves
+pub async fn review_channel_id(ctx: PoiseContext<'_>) -> Result<Option<u64>, PoiseError> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return Ok(None);
+    };
+    let settings = ctx
+        .data()
+        .guild_settings_table()
+        .get_guild_settings(guild_id.get())?;
+    let Some(review_channel_id) = settings.review_channel_id else {
+        return Ok(None);
+    };
+
+    let is_approver = match ctx.author_member().await {
+        Some(member) => permissions::is_member_approver(ctx.data(), &member)?,
+        None => false,
+    };
+
+    Ok((!is_approver).then_some(review_channel_id))
+}
+
+/// Review message of pending sound, visible to everyone in the review channel. Sounds flagged
+/// by moderation (see `moderation`) show why they were flagged
+pub fn review_message(
+    lang: Language,
+    sound: &PendingSoundRow,
+    flag_reason: Option<&str>,
+) -> CreateMessage {
+    let author = match (sound.author_id, &sound.author_hash) {
+        (_, Some(_)) => tr(lang, "sounds.anonymous", &[]),
+        (Some(author_id), None) => format!("<@{author_id}>"),
+        (None, None) => "?".into(),
+    };
+    let duration = sound
+        .duration_ms
+        .map(helpers::format_duration_ms)
+        .unwrap_or_default();
+
+    let mut content = tr(
+        lang,
+        "approval.review",
+        &[
+            ("track", &sound.name),
+            ("author", &author),
+            ("duration", &duration),
+        ],
+    );
+    if let Some(reason) = flag_reason {
+        content.push('\n');
+        content.push_str(&tr(lang, "approval.flagged", &[("reason", &reason)]));
+    }
+
+    CreateMessage::new()
+        .content(content)
+        .components(vec![review_buttons(
+            lang,
+            sound.id,
+            sound.guild_id.map(GuildId::new),
+        )])
+}
+
+fn review_buttons(lang: Language, id: i64, guild_id: Option<GuildId>) -> CreateActionRow {
+    let button = |action, key, style| {
+        CreateButton::new(ButtonCustomId::new(guild_id, action))
+            .label(tr(lang, key, &[]))
+            .style(style)
+    };
+
+    CreateActionRow::Buttons(vec![
+        button(
+            ButtonAction::Listen(id),
+            "approval.listen_button",
+            ButtonStyle::Secondary,
+        ),
+        button(
+            ButtonAction::Approve(id),
+            "approval.approve_button",
+            ButtonStyle::Success,
+        ),
+        button(
+            ButtonAction::Reject(id),
+            "approval.reject_button",
+            ButtonStyle::Danger,
+        ),
+    ])
+}
+
+/// Add sound as pending sound and post it in the review channel, with the reason it was flagged
+/// by moderation if it was. The placed audio file is only kept once both succeed
+#[tracing::instrument(skip_all)]
+pub async fn submit(
+    http: &Http,
+    data: &UserData,
+    review_channel_id: u64,
+    sound: AudioTableRowInsert,
+    placed_file: TempFileGuard,
+    flag_reason: Option<&str>,
+) -> Result<i64, PoiseError> {
+    let table = data.pending_sounds_table();
+    let id = table.insert_pending_sound(&sound)?;
+    let pending = table
+        .find_pending_sound(id)?
+        .ok_or("Inserted pending sound not found")?;
+
+    let lang = i18n::guild_language(data, pending.guild_id.map(GuildId::new), None);
+    let sent = ChannelId::new(review_channel_id)
+        .send_message(http, review_message(lang, &pending, flag_reason))
+        .await;
+    if let Err(err) = sent {
+        log::error!("Failed to post pending sound for review - {err}");
+        table.delete_pending_sound(id)?;
+        return Err(err.into());
+    }
+
+    placed_file.keep();
+    Ok(id)
+}
+
+/// Add pending sound to the audio table, numbering its name if it was taken while it waited
+/// for review. Returns the added sound's name, None if it was already reviewed
+pub fn approve(data: &UserData, id: i64) -> Result<Option<SoundName>, PoiseError> {
+    let pending_table = data.pending_sounds_table();
+    let Some(pending) = pending_table.find_pending_sound(id)? else {
+        return Ok(None);
+    };
+    log::info!("Approving pending sound. Id: {id}, Name: {}", pending.name);
+
+    let audio_table = data.audio_table();
+    let mut row_insert = pending.into_audio_row_insert();
+    loop {
+        match audio_table.insert_audio_row(&row_insert) {
+            Ok(()) => break,
+            Err(InsertAudioError::NameTaken { name }) => {
+                let numbered = helpers::numbered_name(&name, |name| {
+                    audio_table
+                        .find_audio_row(UniqueAudioTableCol::Name(name.into()))
+                        .is_some()
+                });
+                row_insert.name = SoundName::new(numbered)?;
+            }
+            Err(err) => return Err(err.into()),
+        }
+    }
+    pending_table.delete_pending_sound(id)?;
+    data.autocomplete_cache.invalidate();
+
+    Ok(Some(row_insert.name))
+}
+
+/// Delete pending sound and its audio file. Returns the rejected sound's name, None if it was
+/// already reviewed
+pub fn reject(data: &UserData, id: i64) -> Result<Option<SoundName>, PoiseError> {
+    let table = data.pending_sounds_table();
+    let Some(pending) = table.find_pending_sound(id)? else {
+        return Ok(None);
+    };
+    log::info!("Rejecting pending sound. Id: {id}, Name: {}", pending.name);
+
+    table.delete_pending_sound(id)?;
+    pending.audio_file.delete();
+
+    Ok(Some(pending.name))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::{AudioRowFixture, TempDir, TestDb};
+
+    use super::*;
+
+    #[test]
+    fn approve_test() {
+        let db = TestDb::new();
+        let data = db.user_data();
+        AudioRowFixture::new()
+            .name("Beep")
+            .insert(&db.audio_table());
+
+        let id = db
+            .pending_sounds_table()
+            .insert_pending_sound(&AudioRowFixture::new().name("Beep").author_id(1).build())
+            .unwrap();
+
+        // name taken while pending, sound is numbered
+        let name = approve(&data, id).unwrap().unwrap();
+        assert_eq!("Beep-2", name.as_str());
+        let row = db
+            .audio_table()
+            .find_audio_row(UniqueAudioTableCol::Name("Beep-2".into()))
+            .unwrap();
+        assert_eq!(Some(1), row.author_id);
+
+        // already reviewed
+        assert!(db
+            .pending_sounds_table()
+            .find_pending_sound(id)
+            .unwrap()
+            .is_none());
+        assert!(approve(&data, id).unwrap().is_none());
+        assert!(reject(&data, id).unwrap().is_none());
+    }
+
+    #[test]
+    fn reject_test() {
+        let db = TestDb::new();
+        let data = db.user_data();
+        let dir = TempDir::new();
+        let audio_file = dir.audio_file("boop.mp3");
+
+        let mut sound = AudioRowFixture::new().name("Boop").build();
+        sound.audio_file = audio_file;
+        let id = db
+            .pending_sounds_table()
+            .insert_pending_sound(&sound)
+            .unwrap();
+
+        assert_eq!("Boop", reject(&data, id).unwrap().unwrap().as_str());
+        assert!(!dir.path().join("boop.mp3").exists());
+        assert!(db
+            .audio_table()
+            .find_audio_row(UniqueAudioTableCol::Name("Boop".into()))
+            .is_none());
+        assert!(reject(&data, id).unwrap().is_none());
+    }
+}
@@ -1,15 +1,14 @@
 use std::ffi::OsStr;
 use std::fs;
-use std::io::Write;
+use std::future::Future;
 use std::ops::Deref;
 use std::path;
+use std::sync::RwLock;
 
 use futures::StreamExt;
 use rusqlite::types::FromSql;
 use rusqlite::ToSql;
-use serenity::async_trait;
-
-use songbird::tracks::{PlayMode, TrackHandle};
+use tokio::io::AsyncWriteExt;
 
 use symphonia::core::codecs;
 use symphonia::core::formats::FormatOptions;
@@ -20,33 +19,8 @@ use symphonia::core::probe::Hint;
 use crate::commands::PoiseError;
 use crate::common::LogResult;
 use crate::helpers::{self};
-
-pub async fn wait_for_audio_track_end(track_handle: &TrackHandle) {
-    loop {
-        match track_handle.get_info().await {
-            Ok(state) => match state.playing {
-                PlayMode::Play => tokio::time::sleep(tokio::time::Duration::from_millis(250)).await,
-                _ => {}
-            },
-            Err(err) => {
-                log::error!("Error waiting for audio track end - {err}");
-                break;
-            }
-        }
-    }
-}
-
-#[async_trait]
-pub trait TrackHandleHelper {
-    async fn wait_for_end(&self);
-}
-
-#[async_trait]
-impl TrackHandleHelper for TrackHandle {
-    async fn wait_for_end(&self) {
-        wait_for_audio_track_end(&self).await;
-    }
-}
+use crate::temp_files::TempFileGuard;
+use crate::vars;
 
 pub struct AudioDir(path::PathBuf);
 
@@ -102,7 +76,9 @@ impl AudioFileValidator {
         self
     }
 
-    pub fn validate(&self, path: impl AsRef<path::Path>) -> Result<(), PoiseError> {
+    /// Returns the probed track info of valid audio files
+    #[tracing::instrument(name = "validate_audio_file", skip_all)]
+    pub fn validate(&self, path: impl AsRef<path::Path>) -> Result<AudioTrackInfo, PoiseError> {
         let path = path.as_ref();
         log::info!("Validating audio file: {}", path.to_string_lossy());
 
@@ -134,7 +110,7 @@ impl AudioFileValidator {
             return Err(format!("Audio track is {track_dur:.2}s long. This exceeds the max duration of {max_dur:.2}s").into()).log_err();
         }
 
-        Ok(())
+        Ok(track_info)
     }
 }
 
@@ -154,6 +130,45 @@ impl std::iter::Iterator for AudioDirIter {
     }
 }
 
+/// Dirs audio files loaded from the db must be in, see `set_storage_roots`
+static STORAGE_ROOTS: RwLock<Vec<path::PathBuf>> = RwLock::new(Vec::new());
+
+/// Restrict audio files loaded from the db to files under roots, rejecting all until set
+pub fn set_storage_roots(roots: impl IntoIterator<Item = path::PathBuf>) {
+    let roots: Vec<_> = roots.into_iter().filter_map(normalize_path).collect();
+    log::info!("Audio file storage roots: {roots:?}");
+    *STORAGE_ROOTS.write().unwrap() = roots;
+}
+
+/// Absolute path with `.` components removed. None if path has `..` components
+fn normalize_path(path: impl AsRef<path::Path>) -> Option<path::PathBuf> {
+    let path = path.as_ref();
+    let path = match path.is_absolute() {
+        true => path.to_path_buf(),
+        false => std::env::current_dir().ok()?.join(path),
+    };
+
+    let mut normalized = path::PathBuf::new();
+    for component in path.components() {
+        match component {
+            path::Component::CurDir => {}
+            path::Component::ParentDir => return None,
+            component => normalized.push(component),
+        }
+    }
+
+    Some(normalized)
+}
+
+/// Whether path is under one of roots. Paths with `..` components never are
+pub fn is_sandboxed(path: impl AsRef<path::Path>, roots: &[path::PathBuf]) -> bool {
+    match normalize_path(path) {
+        Some(path) => roots.iter().any(|root| path.starts_with(root)),
+        None => false,
+    }
+}
+
+/// Audio file of a sound, only loaded from the db if under the storage roots
 #[derive(Debug, PartialEq)]
 pub struct AudioFile(path::PathBuf);
 
@@ -184,6 +199,15 @@ impl AudioFile {
             .into()
     }
 
+    /// Name for copies of the file named after its sound, the sound's slug (see
+    /// `SoundName::slug`) with the file's extension
+    pub fn slug_file_name(&self, slug: &str) -> String {
+        match self.0.extension() {
+            Some(extension) => format!("{slug}.{}", extension.to_string_lossy()),
+            None => format!("{slug}.mp3"),
+        }
+    }
+
     pub fn audio_title(&self) -> String {
         let stem = self.file_stem();
         let stem = stem.replace("_", " ").replace("-", " ");
@@ -211,6 +235,14 @@ impl FromSql for AudioFile {
             rusqlite::types::ValueRef::Text(val) => {
                 let val = String::from_utf8_lossy(val);
                 let p = path::PathBuf::from(val.to_string());
+
+                if !is_sandboxed(&p, &STORAGE_ROOTS.read().unwrap()) {
+                    log::warn!("Rejected audio file outside storage roots - {val}");
+                    return Err(rusqlite::types::FromSqlError::Other(
+                        format!("Audio file outside storage roots - {val}").into(),
+                    ));
+                }
+
                 Ok(AudioFile(p))
             }
             _ => Err(rusqlite::types::FromSqlError::InvalidType),
@@ -238,8 +270,17 @@ impl RemoveAudioFile for Vec<AudioFile> {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct AudioTrackInfo {
     pub duration: std::time::Duration,
+    /// File size in bytes
+    pub file_size: u64,
+}
+
+impl AudioTrackInfo {
+    pub fn duration_ms(&self) -> u64 {
+        self.duration.as_millis() as u64
+    }
 }
 
 pub fn probe_audio_track(audio_file: impl AsRef<path::Path>) -> Result<AudioTrackInfo, PoiseError> {
@@ -248,6 +289,7 @@ pub fn probe_audio_track(audio_file: impl AsRef<path::Path>) -> Result<AudioTrac
     log::info!("Probing audio-track: {}", path.to_string_lossy());
 
     let file: fs::File = std::fs::File::open(path).log_err()?;
+    let file_size = file.metadata().log_err()?.len();
     let mss = MediaSourceStream::new(Box::new(file), Default::default());
     let mut hint = Hint::default();
     hint.with_extension("mp3");
@@ -296,16 +338,34 @@ pub fn probe_audio_track(audio_file: impl AsRef<path::Path>) -> Result<AudioTrac
     log::info!("Audio track duration = {duration_s:.2}s");
     Ok(AudioTrackInfo {
         duration: std::time::Duration::from_secs_f64(duration_s),
+        file_size,
     })
 }
 
-/// download audio url to temp dir (audio file is uuid4 name)
-pub async fn download_audio_url_temp(url: impl AsRef<str>) -> Result<path::PathBuf, PoiseError> {
+/// download audio url to temp files dir (audio file is uuid4 name). The download is deleted
+/// when the returned guard is dropped
+pub async fn download_audio_url_temp(
+    client: &reqwest::Client,
+    url: impl AsRef<str>,
+) -> Result<TempFileGuard, PoiseError> {
+    download_audio_url_temp_with_progress(client, url, |_| async {}).await
+}
+
+/// Like `download_audio_url_temp`, awaiting `on_progress` as the download progresses (see
+/// `write_response_to_file_with_progress`)
+#[tracing::instrument(name = "download_audio_url_temp", skip_all)]
+pub async fn download_audio_url_temp_with_progress<F, Fut>(
+    client: &reqwest::Client,
+    url: impl AsRef<str>,
+    on_progress: F,
+) -> Result<TempFileGuard, PoiseError>
+where
+    F: FnMut(DownloadProgress) -> Fut,
+    Fut: Future<Output = ()>,
+{
     let url = url.as_ref();
     log::info!("Downloading audio url - {url}");
 
-    let client = reqwest::Client::new();
-
     // HEAD request to ensure Content-Type == 'audio/mpeg'
     let response = client
         .head(url)
@@ -328,30 +388,114 @@ pub async fn download_audio_url_temp(url: impl AsRef<str>) -> Result<path::PathB
         }
     }
 
-    let uuid = helpers::uuid_v4_str();
-    let file_name = format!("{uuid}.mp3");
-    let audio_file_path = std::env::temp_dir().join(file_name.as_str());
+    let audio_file = TempFileGuard::with_extension("mp3")?;
+    let part_file = TempFileGuard::new(audio_file.with_extension("mp3.part"));
 
     // Download audio file
-    let mut file = std::fs::File::create(audio_file_path.as_path())?;
     let response = client
         .get(url)
         .send()
         .await
         .log_err_msg("Failed HTTP GET on url")?;
 
+    // Write to .part file, only renamed to audio file once download completes
+    write_response_to_file_with_progress(response, &part_file, on_progress).await?;
+
+    tokio::fs::rename(&*part_file, &*audio_file)
+        .await
+        .log_err_msg("Failed to rename partial download file")?;
+
+    Ok(audio_file)
+}
+
+/// Bytes downloaded so far, of the response's content length when it has one
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DownloadProgress {
+    pub downloaded: u64,
+    pub total: Option<u64>,
+}
+
+impl DownloadProgress {
+    pub fn percent(&self) -> Option<u64> {
+        self.total
+            .filter(|total| *total > 0)
+            .map(|total| (self.downloaded * 100 / total).min(100))
+    }
+
+    /// Progress is reported each time this changes, i.e. every
+    /// `vars::DOWNLOAD_PROGRESS_STEP_PERCENT` percent, or `vars::DOWNLOAD_PROGRESS_STEP_BYTES`
+    /// bytes without a content length
+    fn step(&self) -> u64 {
+        match self.percent() {
+            Some(percent) => percent / vars::DOWNLOAD_PROGRESS_STEP_PERCENT,
+            None => self.downloaded / vars::DOWNLOAD_PROGRESS_STEP_BYTES,
+        }
+    }
+}
+
+impl std::fmt::Display for DownloadProgress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.percent() {
+            Some(percent) => write!(f, "{percent}%"),
+            None => write!(f, "{}", helpers::format_file_size(self.downloaded)),
+        }
+    }
+}
+
+/// Stream response body to file with buffered async writes
+pub async fn write_response_to_file(
+    response: reqwest::Response,
+    path: &path::Path,
+) -> Result<(), PoiseError> {
+    write_response_to_file_with_progress(response, path, |_| async {}).await
+}
+
+/// Like `write_response_to_file`, awaiting `on_progress` whenever the download progresses by a
+/// step (see `DownloadProgress::step`)
+pub async fn write_response_to_file_with_progress<F, Fut>(
+    response: reqwest::Response,
+    path: &path::Path,
+    mut on_progress: F,
+) -> Result<(), PoiseError>
+where
+    F: FnMut(DownloadProgress) -> Fut,
+    Fut: Future<Output = ()>,
+{
+    let file = tokio::fs::File::create(path)
+        .await
+        .log_err_msg("Failed to create download file")?;
+    let mut writer = tokio::io::BufWriter::new(file);
+
+    let mut progress = DownloadProgress {
+        downloaded: 0,
+        total: response.content_length(),
+    };
     let mut stream = response.bytes_stream();
     while let Some(item) = stream.next().await {
         let chunk = item
             .or(Err(format!("Error while downloading file")))
             .log_err()?;
 
-        file.write_all(&chunk)
+        writer
+            .write_all(&chunk)
+            .await
             .or(Err(format!("Error while writing to file")))
             .log_err()?;
+
+        let step = progress.step();
+        progress.downloaded += chunk.len() as u64;
+        if progress.step() != step {
+            on_progress(progress).await;
+        }
     }
 
-    Ok(audio_file_path)
+    writer
+        .flush()
+        .await
+        .or(Err("Error while flushing file".to_string()))
+        .log_err()?;
+
+    Ok(())
 }
 
 #[cfg(test)]
@@ -362,5 +506,46 @@ mod tests {
     fn audio_file_test() {
         let f = AudioFile::new(path::PathBuf::from("/tmp/once-Upon a_time.mp3"));
         assert_eq!("Once Upon A Time", f.audio_title());
+        assert_eq!("once-upon.mp3", f.slug_file_name("once-upon"));
+
+        let f = AudioFile::new(path::PathBuf::from("/tmp/bruh"));
+        assert_eq!("bruh.mp3", f.slug_file_name("bruh"));
+    }
+
+    #[test]
+    fn download_progress_test() {
+        let progress = DownloadProgress {
+            downloaded: 450,
+            total: Some(1000),
+        };
+        assert_eq!(Some(45), progress.percent());
+        assert_eq!("45%", progress.to_string());
+        assert_eq!(45 / vars::DOWNLOAD_PROGRESS_STEP_PERCENT, progress.step());
+
+        let progress = DownloadProgress {
+            downloaded: 2048,
+            total: None,
+        };
+        assert_eq!(None, progress.percent());
+        assert_eq!(helpers::format_file_size(2048), progress.to_string());
+    }
+
+    #[test]
+    fn is_sandboxed_test() {
+        let roots = vec![path::PathBuf::from("/srv/audio")];
+
+        assert!(is_sandboxed("/srv/audio/bruh.mp3", &roots));
+        assert!(is_sandboxed("/srv/audio/./bruh.mp3", &roots));
+        assert!(!is_sandboxed("/etc/passwd", &roots));
+        assert!(!is_sandboxed("/srv/audio/../../etc/passwd", &roots));
+        assert!(!is_sandboxed("/srv/audio-old/bruh.mp3", &roots));
+
+        // relative paths are relative to the working dir
+        let cwd = std::env::current_dir().unwrap();
+        assert!(is_sandboxed("./bruh.mp3", std::slice::from_ref(&cwd)));
+
+        // without roots, every path is rejected
+        assert!(!is_sandboxed("/etc/passwd", &[]));
+        assert!(!is_sandboxed(cwd.join("bruh.mp3"), &[]));
     }
 }
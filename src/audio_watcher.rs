@@ -0,0 +1,130 @@
+//! Watches the audio dir for files the host adds or deletes while the bot is running. Changes
+//! are reconciled with the audio table (see `UserData::reconcile_audio_dir`) once the dir was
+//! quiet for `vars::AUDIO_DIR_WATCH_DEBOUNCE_SECS`, skipping the uuid named files of the bot
+use std::path;
+use std::sync::Once;
+use std::time::Duration;
+
+use notify::event::ModifyKind;
+use notify::{Event, EventKind, RecursiveMode, Watcher};
+use tokio::sync::mpsc;
+
+use crate::commands::PoiseError;
+use crate::common::{LogResult, UserData};
+use crate::vars;
+
+/// Whether event adds, removes, renames or writes an mp3 file, i.e. may change the audio dir's
+/// sounds
+pub fn is_audio_dir_change(event: &Event) -> bool {
+    let is_change = match event.kind {
+        EventKind::Create(_) | EventKind::Remove(_) => true,
+        EventKind::Modify(ModifyKind::Metadata(_)) => false,
+        EventKind::Modify(_) => true,
+        _ => false,
+    };
+    is_change && event.paths.iter().any(|path| is_mp3(path))
+}
+
+fn is_mp3(path: &path::Path) -> bool {
+    path.extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("mp3"))
+}
+
+/// Start watching the audio dir. Only the first call starts it, so it's safe to call on every
+/// ready event
+pub fn spawn_audio_dir_watcher(data: UserData) {
+    static STARTED: Once = Once::new();
+
+    STARTED.call_once(|| {
+        if let Err(err) = start_watcher(data) {
+            log::error!("Failed to watch audio dir - {err}");
+        }
+    });
+}
+
+fn start_watcher(data: UserData) -> Result<(), PoiseError> {
+    let audio_dir = data.config().audio_dir;
+    let (sender, mut receiver) = mpsc::unbounded_channel();
+
+    let mut watcher =
+        notify::recommended_watcher(move |event: notify::Result<Event>| match event {
+            Ok(event) if is_audio_dir_change(&event) => {
+                log::debug!("Audio dir changed - {event:?}");
+                sender.send(()).ok();
+            }
+            Ok(_) => {}
+            Err(err) => log::warn!("Audio dir watch error - {err}"),
+        })?;
+    watcher.watch(&audio_dir, RecursiveMode::NonRecursive)?;
+    log::info!("Watching audio dir {}", audio_dir.to_string_lossy());
+
+    tokio::spawn(async move {
+        // watching stops once the watcher is dropped
+        let _watcher = watcher;
+
+        while receiver.recv().await.is_some() {
+            // changes are usually a batch of files copied or deleted at once
+            loop {
+                tokio::time::sleep(Duration::from_secs(vars::AUDIO_DIR_WATCH_DEBOUNCE_SECS)).await;
+                let mut changed = false;
+                while receiver.try_recv().is_ok() {
+                    changed = true;
+                }
+                if !changed {
+                    break;
+                }
+            }
+
+            if let Ok((added, removed)) = data
+                .reconcile_audio_dir()
+                .await
+                .log_err_msg("Failed to reconcile audio dir")
+            {
+                log::info!("Reconciled audio dir - added {added}, removed {removed} sounds");
+            }
+        }
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use notify::event::{AccessKind, CreateKind, DataChange, MetadataKind, RemoveKind, RenameMode};
+
+    use super::*;
+
+    #[test]
+    fn is_audio_dir_change_test() {
+        let event = |kind, path: &str| Event::new(kind).add_path(path.into());
+
+        assert!(is_audio_dir_change(&event(
+            EventKind::Create(CreateKind::File),
+            "/audio/bruh.mp3"
+        )));
+        assert!(is_audio_dir_change(&event(
+            EventKind::Remove(RemoveKind::File),
+            "/audio/BRUH.MP3"
+        )));
+        assert!(is_audio_dir_change(&event(
+            EventKind::Modify(ModifyKind::Name(RenameMode::To)),
+            "/audio/bruh.mp3"
+        )));
+        assert!(is_audio_dir_change(&event(
+            EventKind::Modify(ModifyKind::Data(DataChange::Content)),
+            "/audio/bruh.mp3"
+        )));
+        assert!(!is_audio_dir_change(&event(
+            EventKind::Modify(ModifyKind::Metadata(MetadataKind::Any)),
+            "/audio/bruh.mp3"
+        )));
+        assert!(!is_audio_dir_change(&event(
+            EventKind::Access(AccessKind::Any),
+            "/audio/bruh.mp3"
+        )));
+        assert!(!is_audio_dir_change(&event(
+            EventKind::Create(CreateKind::File),
+            "/audio/.bruh.mp3.part"
+        )));
+    }
+}
@@ -0,0 +1,290 @@
+//! Database backups. `/owner backup` backs up the database right away, and when
+//! `Config::backup_dir` is set, a backup is made every day at `Config::backup_time`. The schedule
+//! is a single UTC time of day, not a cron expression. Only the latest `Config::backup_keep`
+//! backups are kept. Whether today's backup is done is read from the backup files' timestamps,
+//! so restarts don't back up twice and failed backups are retried until one succeeds. The first
+//! failure of a day is sent to the bot owners (`Config::owner_ids`)
+use std::path;
+use std::sync::Once;
+use std::time::Duration;
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use serenity::all::{Context, CreateMessage, UserId};
+
+use crate::commands::PoiseError;
+use crate::common::LogResult;
+use crate::config::Config;
+use crate::db::DbConnection;
+use crate::vars;
+
+const BACKUP_FILE_PREFIX: &str = "bot-backup-";
+const BACKUP_FILE_EXTENSION: &str = "db3";
+const BACKUP_FILE_TIME_FORMAT: &str = "%Y%m%d-%H%M%S";
+
+/// Backup file name, timestamped so backups sort oldest first
+pub fn backup_file_name(now: DateTime<Utc>) -> String {
+    format!(
+        "{BACKUP_FILE_PREFIX}{}.{BACKUP_FILE_EXTENSION}",
+        now.format(BACKUP_FILE_TIME_FORMAT)
+    )
+}
+
+/// Time a backup file was made at, from its file name
+fn backup_file_time(path: &path::Path) -> Option<DateTime<Utc>> {
+    let file_name = path.file_name()?.to_str()?;
+    let timestamp = file_name
+        .strip_prefix(BACKUP_FILE_PREFIX)?
+        .strip_suffix(&format!(".{BACKUP_FILE_EXTENSION}"))?;
+    NaiveDateTime::parse_from_str(timestamp, BACKUP_FILE_TIME_FORMAT)
+        .ok()
+        .map(|time| time.and_utc())
+}
+
+fn is_backup_file(path: &path::Path) -> bool {
+    let file_name = path
+        .file_name()
+        .map(|file_name| file_name.to_string_lossy())
+        .unwrap_or_default();
+    path.is_file()
+        && file_name.starts_with(BACKUP_FILE_PREFIX)
+        && file_name.ends_with(&format!(".{BACKUP_FILE_EXTENSION}"))
+}
+
+/// Scheduled backup is due once a day, at or after `backup_time`, unless a backup was already
+/// made since then
+pub fn is_due(
+    backup_time: NaiveTime,
+    last_backup: Option<DateTime<Utc>>,
+    now: DateTime<Utc>,
+) -> bool {
+    let scheduled = now.date_naive().and_time(backup_time).and_utc();
+    now >= scheduled && last_backup.is_none_or(|last_backup| last_backup < scheduled)
+}
+
+/// Time of the latest backup in backup dir, if any
+pub fn latest_backup(backup_dir: &path::Path) -> Option<DateTime<Utc>> {
+    std::fs::read_dir(backup_dir)
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| is_backup_file(path))
+        .filter_map(|path| backup_file_time(&path))
+        .max()
+}
+
+/// Write a consistent copy of the database to backup dir. Returns the backup file path
+pub fn backup_database(
+    conn: &DbConnection,
+    backup_dir: &path::Path,
+    now: DateTime<Utc>,
+) -> Result<path::PathBuf, PoiseError> {
+    std::fs::create_dir_all(backup_dir).log_err_msg("Failed to create backup dir")?;
+
+    let backup_file = backup_dir.join(backup_file_name(now));
+    log::info!("Backing up database to {}", backup_file.to_string_lossy());
+
+    let result = conn.execute(
+        "VACUUM INTO ?1",
+        [backup_file.to_string_lossy().to_string()],
+    );
+    if result.is_err() {
+        // a partial backup would pass as today's backup
+        std::fs::remove_file(&backup_file).ok();
+    }
+    result.log_err_msg("Failed to back up database")?;
+
+    Ok(backup_file)
+}
+
+/// Delete all but the latest `keep` backups of backup dir. Returns deleted backup files
+pub fn prune_backups(
+    backup_dir: &path::Path,
+    keep: usize,
+) -> Result<Vec<path::PathBuf>, PoiseError> {
+    let mut backups: Vec<_> = std::fs::read_dir(backup_dir)
+        .log_err_msg("Failed to read backup dir")?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| is_backup_file(path))
+        .collect();
+    backups.sort();
+
+    let prune_count = backups.len().saturating_sub(keep);
+    let pruned: Vec<_> = backups.into_iter().take(prune_count).collect();
+    for backup in &pruned {
+        log::info!("Deleting old backup {}", backup.to_string_lossy());
+        std::fs::remove_file(backup).log_err_msg("Failed to delete old backup")?;
+    }
+
+    Ok(pruned)
+}
+
+/// Back up database to the configured backup dir and prune old backups
+pub fn run_backup(
+    conn: &DbConnection,
+    config: &Config,
+    now: DateTime<Utc>,
+) -> Result<path::PathBuf, PoiseError> {
+    let backup_dir = config
+        .backup_dir
+        .as_deref()
+        .ok_or("No backup dir configured")?;
+
+    let backup_file = backup_database(conn, backup_dir, now)?;
+    prune_backups(backup_dir, config.backup_keep.max(1))?;
+
+    Ok(backup_file)
+}
+
+/// Start the scheduled backup job, if a backup dir is configured. Only the first call starts
+/// it, so it's safe to call on every ready event. The schedule is read from `config` once,
+/// so changing it needs a restart
+pub fn spawn_backup_job(
+    ctx: Context,
+    db_pool: r2d2::Pool<r2d2_sqlite::SqliteConnectionManager>,
+    config: Config,
+) {
+    static STARTED: Once = Once::new();
+
+    if config.backup_dir.is_none() {
+        return;
+    }
+
+    STARTED.call_once(|| {
+        log::info!("Starting backup job. Backup time: {}", config.backup_time);
+        tokio::spawn(async move {
+            let mut interval =
+                tokio::time::interval(Duration::from_secs(vars::BACKUP_JOB_INTERVAL_SECS));
+            let backup_dir = config.backup_dir.clone().unwrap_or_default();
+            let mut alerted_date: Option<NaiveDate> = None;
+
+            loop {
+                interval.tick().await;
+                let now = Utc::now();
+                if !is_due(config.backup_time, latest_backup(&backup_dir), now) {
+                    continue;
+                }
+
+                // failed backups are retried every tick, owners are alerted once a day
+                let result = db_pool
+                    .get()
+                    .map_err(PoiseError::from)
+                    .and_then(|conn| run_backup(&conn, &config, now))
+                    .log_err_msg("Scheduled backup failed");
+
+                match result {
+                    Ok(_) => alerted_date = None,
+                    Err(err) if alerted_date != Some(now.date_naive()) => {
+                        alerted_date = Some(now.date_naive());
+                        alert_owners(&ctx, &config, &err).await;
+                    }
+                    Err(_) => {}
+                }
+            }
+        });
+    });
+}
+
+async fn alert_owners(ctx: &Context, config: &Config, err: &PoiseError) {
+    let content = format!("⚠️ Scheduled database backup failed - {err}");
+
+    for owner_id in &config.owner_ids {
+        UserId::new(*owner_id)
+            .direct_message(ctx, CreateMessage::new().content(&content))
+            .await
+            .log_err_msg(format!("Failed to alert owner {owner_id} of failed backup"))
+            .ok();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use crate::test_utils::{AudioRowFixture, TempDir, TestDb};
+
+    use super::*;
+
+    #[test]
+    fn is_due_test() {
+        let backup_time = NaiveTime::from_hms_opt(4, 0, 0).unwrap();
+        let now = Utc.with_ymd_and_hms(2024, 1, 2, 4, 30, 0).unwrap();
+        let yesterday = Utc.with_ymd_and_hms(2024, 1, 1, 4, 0, 0).single();
+
+        assert!(is_due(backup_time, None, now));
+        assert!(is_due(backup_time, yesterday, now));
+        assert!(!is_due(backup_time, Some(now), now));
+
+        // backups made earlier today, before backup time, don't count
+        let early = Utc.with_ymd_and_hms(2024, 1, 2, 1, 0, 0).single();
+        assert!(is_due(backup_time, early, now));
+
+        let before = Utc.with_ymd_and_hms(2024, 1, 2, 3, 59, 0).unwrap();
+        assert!(!is_due(backup_time, yesterday, before));
+    }
+
+    #[test]
+    fn latest_backup_test() {
+        let db = TestDb::new();
+        let dir = TempDir::new();
+        assert_eq!(None, latest_backup(dir.path()));
+
+        dir.file("notes.txt");
+        let now = Utc.with_ymd_and_hms(2024, 1, 2, 4, 0, 0).unwrap();
+        for time in [now - chrono::Duration::days(1), now] {
+            backup_database(&db.connection(), dir.path(), time).unwrap();
+        }
+        assert_eq!(Some(now), latest_backup(dir.path()));
+
+        // survives restarts, today's backup is done
+        let backup_time = NaiveTime::from_hms_opt(4, 0, 0).unwrap();
+        let later = now + chrono::Duration::hours(2);
+        assert!(!is_due(backup_time, latest_backup(dir.path()), later));
+        assert!(is_due(
+            backup_time,
+            latest_backup(dir.path()),
+            later + chrono::Duration::days(1)
+        ));
+    }
+
+    #[test]
+    fn run_backup_test() {
+        let db = TestDb::new();
+        AudioRowFixture::new()
+            .name("Beep")
+            .insert(&db.audio_table());
+
+        let dir = TempDir::new();
+        dir.file("notes.txt");
+        let config = Config {
+            backup_dir: Some(dir.path().join("backups")),
+            backup_keep: 2,
+            ..Default::default()
+        };
+
+        let now = Utc.with_ymd_and_hms(2024, 1, 1, 4, 0, 0).unwrap();
+        let backups: Vec<_> = (0..3)
+            .map(|day| {
+                run_backup(&db.connection(), &config, now + chrono::Duration::days(day)).unwrap()
+            })
+            .collect();
+
+        // oldest backup is pruned
+        assert!(!backups[0].exists());
+        assert!(backups[1].exists());
+        assert!(backups[2].exists());
+
+        let backup = rusqlite::Connection::open(&backups[2]).unwrap();
+        let name: String = backup
+            .query_row("SELECT name FROM audio", (), |row| row.get(0))
+            .unwrap();
+        assert_eq!("Beep", name);
+
+        // only backup files are pruned
+        assert!(prune_backups(dir.path(), 0).unwrap().is_empty());
+        assert!(dir.path().join("notes.txt").exists());
+
+        // no backup dir configured
+        assert!(run_backup(&db.connection(), &Config::default(), now).is_err());
+    }
+}
@@ -0,0 +1,118 @@
+//! Per-guild blocklist of words and regex patterns, checked when sounds are named or renamed
+//! and when text-to-speech text is submitted
+use regex::{Regex, RegexBuilder};
+use serenity::all::GuildId;
+
+use crate::commands::PoiseError;
+use crate::common::UserData;
+use crate::db::BlocklistTableRow;
+use crate::i18n::{self, tr};
+
+/// Blocklist patterns compiled to case insensitive regexes. Words only match whole words
+pub struct Blocklist {
+    patterns: Vec<(String, Regex)>,
+}
+
+impl Blocklist {
+    pub fn new(rows: impl IntoIterator<Item = BlocklistTableRow>) -> Self {
+        let patterns = rows
+            .into_iter()
+            .filter_map(|row| match compile_pattern(&row) {
+                Ok(regex) => Some((row.pattern, regex)),
+                Err(err) => {
+                    log::error!(
+                        "Skipping invalid blocklist pattern '{}' - {err}",
+                        row.pattern
+                    );
+                    None
+                }
+            })
+            .collect();
+
+        Self { patterns }
+    }
+
+    pub fn load(data: &UserData, guild_id: u64) -> Result<Self, PoiseError> {
+        Ok(Self::new(data.blocklist_table().patterns(guild_id)?))
+    }
+
+    /// First blocklist pattern matching text
+    pub fn find_match(&self, text: impl AsRef<str>) -> Option<&str> {
+        let text = text.as_ref();
+        self.patterns
+            .iter()
+            .find(|(_, regex)| regex.is_match(text))
+            .map(|(pattern, _)| pattern.as_str())
+    }
+}
+
+pub fn compile_pattern(row: &BlocklistTableRow) -> Result<Regex, regex::Error> {
+    let pattern = match row.is_regex {
+        true => row.pattern.clone(),
+        false => format!(r"\b{}\b", regex::escape(row.pattern.trim())),
+    };
+
+    RegexBuilder::new(&pattern).case_insensitive(true).build()
+}
+
+/// Check text isn't blocked in guild. Returns a reply for the user if it is
+pub fn check_text(data: &UserData, guild_id: Option<u64>, text: &str) -> Result<(), String> {
+    let Some(guild_id) = guild_id else {
+        return Ok(());
+    };
+
+    let blocklist = match Blocklist::load(data, guild_id) {
+        Ok(blocklist) => blocklist,
+        Err(err) => {
+            log::error!("Failed to load blocklist - {err}");
+            return Ok(());
+        }
+    };
+
+    match blocklist.find_match(text) {
+        Some(pattern) => {
+            log::info!("Blocked text '{text}'. Guild Id: {guild_id}, Pattern: {pattern}");
+            let lang = i18n::guild_language(data, Some(GuildId::new(guild_id)), None);
+            Err(tr(lang, "blocklist.blocked", &[]))
+        }
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(pattern: &str, is_regex: bool) -> BlocklistTableRow {
+        BlocklistTableRow {
+            pattern: pattern.into(),
+            is_regex,
+        }
+    }
+
+    #[test]
+    fn blocklist_word_test() {
+        let blocklist = Blocklist::new([row("heck", false)]);
+
+        assert_eq!(Some("heck"), blocklist.find_match("What the HECK"));
+        assert_eq!(Some("heck"), blocklist.find_match("heck!"));
+        // words only match whole words
+        assert_eq!(None, blocklist.find_match("checkmate"));
+    }
+
+    #[test]
+    fn blocklist_regex_test() {
+        let blocklist = Blocklist::new([row("d[a4]rn", true), row("(invalid", true)]);
+
+        assert_eq!(Some("d[a4]rn"), blocklist.find_match("D4rnit"));
+        assert_eq!(None, blocklist.find_match("dern"));
+    }
+
+    #[test]
+    fn blocklist_word_escaped_test() {
+        let blocklist = Blocklist::new([row("a.b", false)]);
+
+        assert_eq!(Some("a.b"), blocklist.find_match("a.b"));
+        assert_eq!(None, blocklist.find_match("axb"));
+    }
+}
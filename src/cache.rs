@@ -0,0 +1,194 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serenity::all::GuildId;
+
+use crate::db::AutocompleteTrack;
+
+struct AutocompleteEntry {
+    tracks: Vec<AutocompleteTrack>,
+    created_at: Instant,
+}
+
+/// In-memory cache of autocomplete tracks per guild, keyed by normalized partial text.
+/// Entries expire after `ttl` and are dropped whenever audio tracks are added, edited or removed
+pub struct AutocompleteCache {
+    ttl: Duration,
+    guilds: Mutex<HashMap<GuildId, HashMap<String, AutocompleteEntry>>>,
+}
+
+impl AutocompleteCache {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            guilds: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Make cache key from partial text. Queries shorter than the guild's minimum query length
+    /// are ranked per user (favorites), so their key is scoped to the user
+    pub fn key(partial: impl AsRef<str>, user_id: Option<u64>, min_query_len: usize) -> String {
+        let partial = partial
+            .as_ref()
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+            .to_lowercase();
+
+        match user_id {
+            Some(user_id) if partial.chars().count() < min_query_len => {
+                format!("{user_id}:{partial}")
+            }
+            _ => format!(":{partial}"),
+        }
+    }
+
+    pub fn get(&self, guild_id: GuildId, key: &str) -> Option<Vec<AutocompleteTrack>> {
+        let mut guilds = self.guilds.lock().expect("AutocompleteCache lock poisoned");
+        let entries = guilds.get_mut(&guild_id)?;
+
+        match entries.get(key) {
+            Some(entry) if entry.created_at.elapsed() < self.ttl => Some(entry.tracks.clone()),
+            Some(_) => {
+                entries.remove(key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    pub fn insert(&self, guild_id: GuildId, key: String, tracks: Vec<AutocompleteTrack>) {
+        let mut guilds = self.guilds.lock().expect("AutocompleteCache lock poisoned");
+        let entries = guilds.entry(guild_id).or_default();
+
+        // drop expired entries so the cache can't grow unbounded
+        let ttl = self.ttl;
+        entries.retain(|_, entry| entry.created_at.elapsed() < ttl);
+
+        entries.insert(
+            key,
+            AutocompleteEntry {
+                tracks,
+                created_at: Instant::now(),
+            },
+        );
+    }
+
+    /// Return cached tracks, or call op and cache its results
+    pub fn get_or_insert_with(
+        &self,
+        guild_id: GuildId,
+        key: String,
+        op: impl FnOnce() -> Vec<AutocompleteTrack>,
+    ) -> Vec<AutocompleteTrack> {
+        if let Some(tracks) = self.get(guild_id, &key) {
+            log::debug!("Autocomplete cache hit - guild_id: {guild_id}, key: '{key}'");
+            return tracks;
+        }
+
+        let tracks = op();
+        self.insert(guild_id, key, tracks.clone());
+        tracks
+    }
+
+    /// Number of cached entries of all guilds, expired ones included
+    pub fn entry_count(&self) -> usize {
+        self.guilds
+            .lock()
+            .expect("AutocompleteCache lock poisoned")
+            .values()
+            .map(HashMap::len)
+            .sum()
+    }
+
+    /// Drop guild's cached entries, e.g. when its autocomplete settings change
+    pub fn invalidate_guild(&self, guild_id: GuildId) {
+        log::debug!("Invalidating autocomplete cache of guild {guild_id}");
+        self.guilds
+            .lock()
+            .expect("AutocompleteCache lock poisoned")
+            .remove(&guild_id);
+    }
+
+    /// Drop all cached entries. The audio library is shared by all guilds,
+    /// so any insert/update/delete invalidates every guild's cache
+    pub fn invalidate(&self) {
+        log::debug!("Invalidating autocomplete cache");
+        self.guilds
+            .lock()
+            .expect("AutocompleteCache lock poisoned")
+            .clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tracks(names: &[&str]) -> Vec<AutocompleteTrack> {
+        names
+            .iter()
+            .map(|name| AutocompleteTrack {
+                id: 1,
+                name: name.to_string(),
+                duration_ms: None,
+                matched_tag: None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn key_test() {
+        assert_eq!(
+            ":beep boop",
+            AutocompleteCache::key("  Beep \t BOOP ", Some(1), 3)
+        );
+        assert_eq!(":beep", AutocompleteCache::key("beep", None, 3));
+        assert_eq!("1:be", AutocompleteCache::key("Be", Some(1), 3));
+        assert_eq!(":be", AutocompleteCache::key("be", None, 3));
+        assert_eq!(":be", AutocompleteCache::key("be", Some(1), 1));
+        assert_eq!("1:beep", AutocompleteCache::key("beep", Some(1), 5));
+    }
+
+    #[test]
+    fn get_or_insert_with_test() {
+        let cache = AutocompleteCache::new(Duration::from_secs(30));
+        let guild_id = GuildId::new(1);
+
+        let names = cache.get_or_insert_with(guild_id, ":beep".into(), || tracks(&["Beep"]));
+        assert_eq!(tracks(&["Beep"]), names);
+
+        // cached value is returned, op isn't called
+        let names = cache.get_or_insert_with(guild_id, ":beep".into(), || unreachable!());
+        assert_eq!(tracks(&["Beep"]), names);
+
+        // guilds are cached separately
+        assert!(cache.get(GuildId::new(2), ":beep").is_none());
+    }
+
+    #[test]
+    fn expired_entry_test() {
+        let cache = AutocompleteCache::new(Duration::ZERO);
+        let guild_id = GuildId::new(1);
+
+        cache.insert(guild_id, ":beep".into(), tracks(&["Beep"]));
+        assert!(cache.get(guild_id, ":beep").is_none());
+    }
+
+    #[test]
+    fn invalidate_test() {
+        let cache = AutocompleteCache::new(Duration::from_secs(30));
+        let guild_id = GuildId::new(1);
+
+        cache.insert(guild_id, ":beep".into(), tracks(&["Beep"]));
+        cache.invalidate();
+        assert!(cache.get(guild_id, ":beep").is_none());
+
+        cache.insert(guild_id, ":beep".into(), tracks(&["Beep"]));
+        cache.insert(GuildId::new(2), ":beep".into(), tracks(&["Beep"]));
+        cache.invalidate_guild(guild_id);
+        assert!(cache.get(guild_id, ":beep").is_none());
+        assert!(cache.get(GuildId::new(2), ":beep").is_some());
+    }
+}
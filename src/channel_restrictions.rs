@@ -0,0 +1,147 @@
+//! Per-guild channel restrictions for the soundboard. Guilds can restrict sound playback to
+//! some voice channels and soundboard commands to some text channels, keeping AFK or meeting
+//! channels free of sound spam. A channel kind without any allowed channels is unrestricted
+use poise::CreateReply;
+use serenity::all::{ChannelId, ChannelType};
+
+use crate::commands::{PoiseContext, PoiseError};
+use crate::helpers::{self, poise_check_msg};
+use crate::i18n::{self, tr};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ChannelKind {
+    Voice,
+    Text,
+}
+
+impl ChannelKind {
+    pub const ALL: [ChannelKind; 2] = [Self::Voice, Self::Text];
+
+    /// Key stored in the channel restrictions table
+    pub fn key(&self) -> &'static str {
+        match self {
+            Self::Voice => "voice",
+            Self::Text => "text",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|kind| kind.key() == key)
+    }
+
+    /// Voice and stage channels restrict playback, every other channel restricts commands
+    pub fn of(channel_type: ChannelType) -> Self {
+        match channel_type {
+            ChannelType::Voice | ChannelType::Stage => Self::Voice,
+            _ => Self::Text,
+        }
+    }
+}
+
+/// Channel is allowed if no channels of its kind are allowed, or it's one of them
+pub fn is_channel_allowed(allowed_channel_ids: &[u64], channel_id: u64) -> bool {
+    allowed_channel_ids.is_empty() || allowed_channel_ids.contains(&channel_id)
+}
+
+/// Check channel is allowed in command's guild, replying with the allowed channels if not
+pub async fn check_channel(
+    ctx: PoiseContext<'_>,
+    kind: ChannelKind,
+    channel_id: ChannelId,
+) -> Result<bool, PoiseError> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return Ok(false);
+    };
+
+    let allowed_channel_ids = ctx
+        .data()
+        .channel_restrictions_table()
+        .channel_ids(guild_id.get(), kind.key())?;
+
+    let allowed = is_channel_allowed(&allowed_channel_ids, channel_id.get());
+    if !allowed {
+        log::info!("Channel not allowed. Channel Id: {channel_id}, Kind: {kind:?}");
+
+        let channels: Vec<_> = allowed_channel_ids
+            .iter()
+            .map(|channel_id| format!("<#{channel_id}>"))
+            .collect();
+        let key = match kind {
+            ChannelKind::Voice => "channels.voice_denied",
+            ChannelKind::Text => "channels.text_denied",
+        };
+        poise_check_msg(
+            ctx.send(CreateReply::default().ephemeral(true).content(tr(
+                i18n::language(ctx),
+                key,
+                &[("channels", &channels.join(", "))],
+            )))
+            .await,
+        );
+    }
+
+    Ok(allowed)
+}
+
+/// Soundboard commands must be used in an allowed text channel, and play in the voice
+/// channel the bot is in, which must be allowed
+pub async fn can_use_soundboard(ctx: PoiseContext<'_>) -> Result<bool, PoiseError> {
+    if !check_channel(ctx, ChannelKind::Text, ctx.channel_id()).await? {
+        return Ok(false);
+    }
+
+    let Some(guild_id) = ctx.guild_id() else {
+        return Ok(false);
+    };
+    let manager = helpers::poise_songbird_get(&ctx).await;
+    let voice_channel_id = match manager.get(guild_id) {
+        Some(call) => call.lock().await.current_channel(),
+        None => None,
+    };
+
+    match voice_channel_id {
+        Some(channel_id) => {
+            check_channel(ctx, ChannelKind::Voice, ChannelId::new(channel_id.0.get())).await
+        }
+        None => Ok(true),
+    }
+}
+
+/// The bot only joins the command author's voice channel when it's allowed
+pub async fn can_join_voice_channel(ctx: PoiseContext<'_>) -> Result<bool, PoiseError> {
+    if !check_channel(ctx, ChannelKind::Text, ctx.channel_id()).await? {
+        return Ok(false);
+    }
+
+    match helpers::get_author_voice_channel(&ctx) {
+        Ok((_, channel_id)) => check_channel(ctx, ChannelKind::Voice, channel_id).await,
+        // join reports the author isn't in a voice channel
+        Err(_) => Ok(true),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn is_channel_allowed_test() {
+        // no channels allowed, every channel allowed
+        assert!(is_channel_allowed(&[], 1));
+
+        assert!(is_channel_allowed(&[1, 2], 2));
+        assert!(!is_channel_allowed(&[1, 2], 3));
+    }
+
+    #[test]
+    fn channel_kind_test() {
+        for kind in ChannelKind::ALL {
+            assert_eq!(Some(kind), ChannelKind::from_key(kind.key()));
+        }
+        assert_eq!(None, ChannelKind::from_key("forum"));
+
+        assert_eq!(ChannelKind::Voice, ChannelKind::of(ChannelType::Voice));
+        assert_eq!(ChannelKind::Voice, ChannelKind::of(ChannelType::Stage));
+        assert_eq!(ChannelKind::Text, ChannelKind::of(ChannelType::Text));
+    }
+}
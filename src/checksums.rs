@@ -0,0 +1,404 @@
+//! Content hashes of audio files, verified before playback so files that rotted or were
+//! tampered with in the audio dir aren't played. A file's hash is recorded the first time it's
+//! verified (or by the startup backfill), and files that no longer match it are moved to
+//! `Config::quarantine_dir` and their sound archived, with an error alerted to hosts. Read-only
+//! mirrors neither record hashes nor quarantine files, they only refuse to play files that don't
+//! match.
+//! Verified files aren't hashed again for `vars::CHECKSUM_CACHE_TTL_SECS` unless their size or
+//! modified time changes
+use std::collections::HashMap;
+use std::path;
+use std::sync::{Mutex, Once};
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::commands::PoiseError;
+use crate::common::{LogResult, UserData};
+use crate::db::{self, AudioTable, AudioTableRow};
+use crate::packs::file_sha256;
+use crate::vars;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Verification {
+    /// File matches its recorded hash
+    Verified,
+    /// File was verified recently and hasn't changed since
+    Cached,
+    /// Sound had no hash yet, so the file's hash was recorded
+    Recorded,
+    /// File doesn't match its recorded hash and was quarantined
+    Quarantined,
+    /// File doesn't match its recorded hash, but the database is read-only so it wasn't
+    /// quarantined
+    Mismatched,
+    /// File is missing or unreadable, so couldn't be verified
+    Unverifiable,
+}
+
+struct VerifiedFile {
+    path: path::PathBuf,
+    len: u64,
+    modified: Option<SystemTime>,
+    verified_at: Instant,
+}
+
+/// Recently verified audio files, keyed by audio row id
+pub struct ChecksumCache {
+    ttl: Duration,
+    files: Mutex<HashMap<i64, VerifiedFile>>,
+}
+
+impl ChecksumCache {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            files: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn is_verified(&self, audio_id: i64, path: &path::Path, metadata: &std::fs::Metadata) -> bool {
+        let files = self.files.lock().expect("ChecksumCache lock poisoned");
+        files.get(&audio_id).is_some_and(|file| {
+            file.path == path
+                && file.len == metadata.len()
+                && file.modified == metadata.modified().ok()
+                && file.verified_at.elapsed() < self.ttl
+        })
+    }
+
+    fn insert(&self, audio_id: i64, path: &path::Path, metadata: &std::fs::Metadata) {
+        let mut files = self.files.lock().expect("ChecksumCache lock poisoned");
+
+        // drop expired files so the cache can't grow unbounded
+        let ttl = self.ttl;
+        files.retain(|_, file| file.verified_at.elapsed() < ttl);
+
+        files.insert(
+            audio_id,
+            VerifiedFile {
+                path: path.to_path_buf(),
+                len: metadata.len(),
+                modified: metadata.modified().ok(),
+                verified_at: Instant::now(),
+            },
+        );
+    }
+
+    fn forget(&self, audio_id: i64) {
+        let mut files = self.files.lock().expect("ChecksumCache lock poisoned");
+        files.remove(&audio_id);
+    }
+}
+
+/// Verify sound's audio file against its recorded hash before it's played, quarantining it if
+/// it doesn't match
+pub async fn verify_audio_file(
+    data: &UserData,
+    row: &AudioTableRow,
+) -> Result<Verification, PoiseError> {
+    let path = row.audio_file.as_path_buf();
+    let Ok(metadata) = std::fs::metadata(&path) else {
+        return Ok(Verification::Unverifiable);
+    };
+    if data.checksum_cache.is_verified(row.id, &path, &metadata) {
+        return Ok(Verification::Cached);
+    }
+    // read-only mirrors leave recording hashes to the primary instance
+    let read_only = db::is_read_only(&data.db_connection());
+    if read_only && row.content_hash.is_none() {
+        return Ok(Verification::Unverifiable);
+    }
+
+    let hash_path = path.clone();
+    let Ok(hash) = tokio::task::spawn_blocking(move || file_sha256(&hash_path)).await? else {
+        return Ok(Verification::Unverifiable);
+    };
+
+    let verification = match &row.content_hash {
+        None => {
+            data.audio_table().set_content_hash(row.id, &hash)?;
+            Verification::Recorded
+        }
+        Some(content_hash) if content_hash.eq_ignore_ascii_case(&hash) => Verification::Verified,
+        Some(content_hash) => {
+            if read_only {
+                log::error!(
+                    "Audio file of `{}` doesn't match its checksum. Expected: {content_hash}, Got: {hash}",
+                    row.name
+                );
+                return Ok(Verification::Mismatched);
+            }
+            log::error!(
+                "Audio file of `{}` doesn't match its checksum, quarantining it. Expected: {content_hash}, Got: {hash}",
+                row.name
+            );
+            quarantine(data, row)?;
+            return Ok(Verification::Quarantined);
+        }
+    };
+
+    data.checksum_cache.insert(row.id, &path, &metadata);
+    Ok(verification)
+}
+
+/// Move sound's audio file to the quarantine dir and archive the sound, hiding it until the
+/// file is replaced (e.g. with `/sounds refetch`) and the sound unarchived. The file is moved
+/// back if the sound can't be archived
+pub fn quarantine(data: &UserData, row: &AudioTableRow) -> Result<path::PathBuf, PoiseError> {
+    let quarantine_dir = data.config().quarantine_dir;
+    std::fs::create_dir_all(&quarantine_dir).log_err_msg("Failed to create quarantine dir")?;
+
+    let quarantined_file = quarantined_file(&quarantine_dir, row);
+    let audio_file = row.audio_file.as_path_buf();
+    move_file(&audio_file, &quarantined_file)?;
+
+    if let Err(err) = data.audio_table().set_archived(row.id, true) {
+        move_file(&quarantined_file, &audio_file)
+            .log_err_msg("Failed to restore quarantined file")
+            .ok();
+        return Err(err);
+    }
+    log::warn!(
+        "Quarantined audio file of `{}` - {}",
+        row.name,
+        quarantined_file.to_string_lossy()
+    );
+
+    data.checksum_cache.forget(row.id);
+    data.autocomplete_cache.invalidate();
+
+    Ok(quarantined_file)
+}
+
+/// Rename file, or copy and remove it when renaming fails (e.g. across file systems)
+fn move_file(from: &path::Path, to: &path::Path) -> Result<(), PoiseError> {
+    if std::fs::rename(from, to).is_err() {
+        std::fs::copy(from, to).log_err_msg("Failed to copy audio file")?;
+        std::fs::remove_file(from).log_err_msg("Failed to remove moved audio file")?;
+    }
+    Ok(())
+}
+
+/// Where sound's audio file is moved to when quarantined
+pub fn quarantined_file(quarantine_dir: &path::Path, row: &AudioTableRow) -> path::PathBuf {
+    let file_name = row.audio_file.slug_file_name(&row.slug);
+    quarantine_dir.join(format!("{}-{file_name}", row.id))
+}
+
+/// Hashes audio files of rows without a content hash, i.e. rows added before hashes were
+/// stored or since re-fetched. Rows whose audio file can't be read are skipped. Returns number
+/// of rows backfilled
+pub fn backfill_content_hashes(table: &AudioTable) -> Result<usize, PoiseError> {
+    let mut backfilled = 0;
+    let mut after_id = 0;
+
+    loop {
+        let rows = table
+            .audio_rows_missing_content_hash(after_id, vars::CONTENT_HASH_BACKFILL_BATCH_LIMIT)?;
+        let Some(last_row) = rows.last() else {
+            break;
+        };
+        after_id = last_row.id;
+
+        for row in rows {
+            // missing files would be logged as errors by `file_sha256`
+            if !row.audio_file.is_file() {
+                log::warn!(
+                    "Skipping content hash backfill of audio track - {}",
+                    row.name
+                );
+                continue;
+            }
+
+            if let Ok(hash) = file_sha256(row.audio_file.as_path()) {
+                table.set_content_hash(row.id, &hash)?;
+                backfilled += 1;
+            }
+        }
+    }
+
+    Ok(backfilled)
+}
+
+/// Start backfilling content hashes on a blocking thread. Only the first call starts it, so
+/// it's safe to call on every ready event
+pub fn spawn_content_hash_backfill(db_pool: r2d2::Pool<r2d2_sqlite::SqliteConnectionManager>) {
+    static STARTED: Once = Once::new();
+
+    STARTED.call_once(|| {
+        log::info!("Starting audio content hash backfill");
+        tokio::task::spawn_blocking(move || {
+            let result: Result<usize, PoiseError> = db_pool
+                .get()
+                .map_err(Into::into)
+                .and_then(|conn| backfill_content_hashes(&AudioTable::new(conn)));
+
+            if let Ok(backfilled) = result.log_err_msg("Failed to backfill content hashes") {
+                log::info!(
+                    "Audio content hash backfill complete - hashed {backfilled} audio tracks"
+                );
+            }
+        });
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::Config;
+    use crate::db::UniqueAudioTableCol;
+    use crate::test_utils::{AudioRowFixture, TempDir, TestDb};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn verify_audio_file_test() {
+        let audio_dir = TempDir::new();
+        let quarantine_dir = TempDir::new();
+        let db = TestDb::new();
+        let data = db.user_data_with_config(Config {
+            quarantine_dir: quarantine_dir.path().to_path_buf(),
+            ..Default::default()
+        });
+        let table = data.audio_table();
+
+        let audio_file = audio_dir.audio_file("bruh.mp3");
+        std::fs::write(audio_file.as_path(), b"bruh").unwrap();
+        let row = AudioRowFixture::new().insert(&table);
+        table
+            .replace_audio_file(row.id, &audio_file, 900, 4)
+            .unwrap();
+        let find = || {
+            table
+                .find_audio_row(UniqueAudioTableCol::Id(row.id))
+                .unwrap()
+        };
+
+        let row = find();
+        assert_eq!(
+            Verification::Recorded,
+            verify_audio_file(&data, &row).await.unwrap()
+        );
+        assert_eq!(
+            Verification::Cached,
+            verify_audio_file(&data, &row).await.unwrap()
+        );
+
+        let row = find();
+        assert!(row.content_hash.is_some());
+        data.checksum_cache.forget(row.id);
+        assert_eq!(
+            Verification::Verified,
+            verify_audio_file(&data, &row).await.unwrap()
+        );
+
+        // changed size invalidates the cached verification
+        std::fs::write(audio_file.as_path(), b"tampered").unwrap();
+        assert_eq!(
+            Verification::Quarantined,
+            verify_audio_file(&data, &row).await.unwrap()
+        );
+        assert!(!audio_file.exists());
+        assert!(quarantine_dir
+            .path()
+            .join(format!("{}-{}.mp3", row.id, row.slug))
+            .exists());
+        assert!(find().archived_at.is_some());
+
+        assert_eq!(
+            Verification::Unverifiable,
+            verify_audio_file(&data, &find()).await.unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn verify_audio_file_read_only_test() {
+        let audio_dir = TempDir::new();
+        let quarantine_dir = TempDir::new();
+        let db_file = audio_dir.path().join("bot.db3");
+        let table = TestDb::file(&db_file, false).audio_table();
+
+        let unhashed_file = audio_dir.audio_file("bruh.mp3");
+        std::fs::write(unhashed_file.as_path(), b"bruh").unwrap();
+        let unhashed = AudioRowFixture::new().insert(&table);
+        table
+            .replace_audio_file(unhashed.id, &unhashed_file, 900, 4)
+            .unwrap();
+        let audio_file = audio_dir.audio_file("tampered.mp3");
+        std::fs::write(audio_file.as_path(), b"tampered").unwrap();
+        let tampered = AudioRowFixture::new().insert(&table);
+        table
+            .replace_audio_file(tampered.id, &audio_file, 900, 8)
+            .unwrap();
+        table.set_content_hash(tampered.id, "ab").unwrap();
+
+        let data = TestDb::file(&db_file, true).user_data_with_config(Config {
+            quarantine_dir: quarantine_dir.path().to_path_buf(),
+            ..Default::default()
+        });
+        let find = |id| {
+            data.audio_table()
+                .find_audio_row(UniqueAudioTableCol::Id(id))
+                .unwrap()
+        };
+
+        assert_eq!(
+            Verification::Unverifiable,
+            verify_audio_file(&data, &find(unhashed.id)).await.unwrap()
+        );
+        assert_eq!(None, find(unhashed.id).content_hash);
+
+        assert_eq!(
+            Verification::Mismatched,
+            verify_audio_file(&data, &find(tampered.id)).await.unwrap()
+        );
+        assert!(audio_file.exists());
+        assert!(find(tampered.id).archived_at.is_none());
+    }
+
+    #[test]
+    fn quarantine_read_only_test() {
+        let audio_dir = TempDir::new();
+        let quarantine_dir = TempDir::new();
+        let db_file = audio_dir.path().join("bot.db3");
+        let table = TestDb::file(&db_file, false).audio_table();
+        let audio_file = audio_dir.audio_file("bruh.mp3");
+        std::fs::write(audio_file.as_path(), b"bruh").unwrap();
+        let row = AudioRowFixture::new().insert(&table);
+        table
+            .replace_audio_file(row.id, &audio_file, 900, 4)
+            .unwrap();
+
+        let data = TestDb::file(&db_file, true).user_data_with_config(Config {
+            quarantine_dir: quarantine_dir.path().to_path_buf(),
+            ..Default::default()
+        });
+        let row = data
+            .audio_table()
+            .find_audio_row(UniqueAudioTableCol::Id(row.id))
+            .unwrap();
+
+        // archiving fails, so the file is moved back
+        assert!(quarantine(&data, &row).is_err());
+        assert!(audio_file.exists());
+        assert!(!quarantined_file(quarantine_dir.path(), &row).exists());
+    }
+
+    #[test]
+    fn backfill_content_hashes_test() {
+        let audio_dir = TempDir::new();
+        let table = TestDb::new().audio_table();
+        let missing = AudioRowFixture::new().insert(&table);
+        let row = AudioRowFixture::new().insert(&table);
+        let audio_file = audio_dir.audio_file("bruh.mp3");
+        table.replace_audio_file(row.id, &audio_file, 0, 0).unwrap();
+
+        // fixture audio files don't exist, so can't be hashed
+        assert_eq!(1, backfill_content_hashes(&table).unwrap());
+
+        let find = |id| table.find_audio_row(UniqueAudioTableCol::Id(id)).unwrap();
+        assert_eq!(None, find(missing.id).content_hash);
+        assert_eq!(
+            Some(file_sha256(audio_file.as_path()).unwrap()),
+            find(row.id).content_hash
+        );
+    }
+}
@@ -0,0 +1,89 @@
+//! Per-command usage stats, so hosts know which features get used. Commands are timed from the
+//! framework's `pre_command` hook and recorded in the command stats table once they return or
+//! fail, counted per qualified name across every guild. Shown by `/stats commands`
+use std::time::Instant;
+
+use crate::commands::PoiseContext;
+use crate::common::LogResult;
+use crate::db::{self, CommandStatsRow};
+use crate::i18n::{tr, Language};
+
+/// When the running command was invoked, kept in its invocation data
+struct CommandStart(Instant);
+
+/// Runs before every command. Starts timing it
+pub async fn start_command(ctx: PoiseContext<'_>) {
+    ctx.set_invocation_data(CommandStart(Instant::now())).await;
+}
+
+/// Record usage of command that returned or failed. Commands rejected before they started
+/// (checks, cooldowns) aren't counted
+pub async fn record_command(ctx: PoiseContext<'_>, failed: bool) {
+    let Some(elapsed) = ctx
+        .invocation_data::<CommandStart>()
+        .await
+        .map(|start| start.0.elapsed())
+    else {
+        return;
+    };
+
+    let data = ctx.data();
+    // read-only mirrors leave recording usage to the primary instance
+    if db::is_read_only(&data.db_connection()) {
+        return;
+    }
+
+    data.command_stats_table()
+        .record_command(&ctx.command().qualified_name, elapsed, failed)
+        .log_err()
+        .ok();
+}
+
+/// One line per command, most used first
+pub fn render_command_stats(lang: Language, stats: &[CommandStatsRow]) -> String {
+    stats
+        .iter()
+        .map(|row| {
+            tr(
+                lang,
+                "stats.command_usage",
+                &[
+                    ("command", &row.command),
+                    ("count", &row.invocations),
+                    ("errors", &format!("{:.1}", row.error_rate())),
+                    ("average", &row.average_ms()),
+                ],
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_command_stats_test() {
+        let row = CommandStatsRow {
+            command: "sounds add".into(),
+            invocations: 8,
+            failures: 1,
+            total_ms: 4000,
+            last_used_at: chrono::Utc::now(),
+        };
+        let play = CommandStatsRow {
+            command: "play".into(),
+            invocations: 3,
+            failures: 0,
+            total_ms: 90,
+            last_used_at: chrono::Utc::now(),
+        };
+
+        assert_eq!(
+            "- `/sounds add`: 8 uses, 12.5% errors, 500 ms average\n- `/play`: 3 uses, 0.0% errors, 30 ms average",
+            render_command_stats(Language::English, &[row, play])
+        );
+        assert_eq!("", render_command_stats(Language::English, &[]));
+    }
+}
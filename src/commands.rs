@@ -1,13 +1,46 @@
-use poise::Modal;
-use serenity::{all::CreateMessage, async_trait};
-use songbird::{Event, EventContext, EventHandler as VoiceEventHandler, TrackEvent};
+use futures::StreamExt;
+use poise::{ChoiceParameter, CreateReply, Modal};
+use serenity::{
+    all::{CreateMessage, GuildChannel, Role},
+    async_trait,
+};
+use songbird::{CoreEvent, Event, EventContext, EventHandler as VoiceEventHandler, TrackEvent};
 
 use crate::{
-    audio::{self, AudioFile, RemoveAudioFile},
+    approval, audio, blocklist,
+    channel_restrictions::{self, ChannelKind},
+    command_stats,
     common::{LogResult, UserData},
-    db::{self, AudioTable, AudioTableRowInsert, Tags},
-    helpers::{self, check_msg, poise_check_msg, PoiseContextHelper, SongbirdHelper},
+    config::Config,
+    db::{
+        self, AudioTableRowInsert, BlocklistTableRow, HistogramBucket, NativeSoundsTableRow,
+        PlayEventsFilter, SoundName, Tags,
+    },
+    diagnostics::Diagnostics,
+    disk, doctor, download_policy,
+    errors::{AudioError, InsertAudioError},
+    guild_export::{self, GuildExport},
+    helpers::{
+        self, check_msg, poise_check_msg, Confirmation, PoiseContextHelper, SongbirdHelper,
+        SoundButtonStyle,
+    },
+    http_client,
+    i18n::{self, tr, Language},
+    ingest,
+    jobs::{self, Job},
+    loudness::{self, LoudnessAction},
+    moderation::{self, ModeratedSound},
+    owner, packs,
+    permissions::{self, Permission},
+    playback::{self, PriorityMode},
+    privacy, prune, sessions,
+    sharing::{self, ImportOutcome},
+    sotd::SotdMode,
+    soundboard::{self, SoundboardOrder},
+    stats,
+    trash::{self, RestoreOutcome},
     vars,
+    voice_reconnect::ReconnectHandler,
 };
 
 pub type GenericError = Box<dyn std::error::Error + Send + Sync>;
@@ -16,6 +49,42 @@ pub type PoiseContext<'a> = poise::Context<'a, UserData, PoiseError>;
 pub type PoiseResult = Result<(), PoiseError>;
 pub type PoiseAppContext<'a> = poise::ApplicationContext<'a, UserData, PoiseError>;
 
+/// Every command the bot registers. Their qualified names are set already, the framework
+/// only sets them after `i18n::localize_commands` needs them
+pub fn all_commands() -> Vec<poise::Command<UserData, PoiseError>> {
+    let mut commands = vec![
+        echo(),
+        join(),
+        leave(),
+        sounds(),
+        soundboard(),
+        settings(),
+        stats(),
+        trending(),
+        forgotten(),
+        user_history(),
+        privacy(),
+        play(),
+        play_id(),
+        random(),
+        search(),
+        replay(),
+        stop(),
+        scan(),
+        admin(),
+        owner(),
+        packs(),
+        debug(),
+        doctor(),
+        jobs(),
+        playlist(),
+        record(),
+        register(),
+    ];
+    poise::set_qualified_names(&mut commands);
+    commands
+}
+
 #[poise::command(prefix_command, guild_only)]
 pub async fn deafen(_ctx: PoiseContext<'_>) -> PoiseResult {
     Ok(())
@@ -27,19 +96,31 @@ pub async fn ping(ctx: PoiseContext<'_>) -> PoiseResult {
     Ok(())
 }
 
-#[poise::command(prefix_command, guild_only)]
+#[poise::command(
+    prefix_command,
+    guild_only,
+    check = "channel_restrictions::can_join_voice_channel"
+)]
 pub async fn join(ctx: PoiseContext<'_>) -> PoiseResult {
     log::info!("Bot joining voice channel...");
     let (guild_id, connect_to) = helpers::get_author_voice_channel(&ctx)?;
 
     log::info!("Bot will join Guild ID: {guild_id}, Voice Channel: {connect_to}");
     let manager = helpers::poise_songbird_get(&ctx).await;
+    let already_joined = manager.get(guild_id).is_some();
 
     match manager.join(guild_id, connect_to).await {
         Ok(handler_lock) => {
-            // Attach an event handler to see notifications of all track errors
-            let mut handler = handler_lock.lock().await;
-            handler.add_global_event(TrackEvent::Error.into(), TrackErrorNotifier);
+            // global event handlers outlive the call's connections, so only attach them once
+            if !already_joined {
+                let mut handler = handler_lock.lock().await;
+                // Attach an event handler to see notifications of all track errors
+                handler.add_global_event(TrackEvent::Error.into(), TrackErrorNotifier);
+                handler.add_global_event(
+                    CoreEvent::DriverDisconnect.into(),
+                    ReconnectHandler::new(manager.clone()),
+                );
+            }
             log::info!("Bot joined Guild ID: {guild_id}, Voice Channel ID: {connect_to}");
         }
         Err(err) => {
@@ -56,7 +137,7 @@ pub async fn join(ctx: PoiseContext<'_>) -> PoiseResult {
             match ctx
                 .data()
                 .audio_table()
-                .find_audio_row(db::UniqueAudioTableCol::Name(join_audio.clone()))
+                .find_audio_row(db::UniqueAudioTableCol::from_input(join_audio))
             {
                 Some(row) => {
                     log::debug!("bot join audio playing: {}", row.name);
@@ -83,6 +164,7 @@ pub async fn leave(ctx: PoiseContext<'_>) -> PoiseResult {
 
     let handler = manager.get(guild_id);
     let channel_id = ctx.channel_id();
+    let lang = i18n::language(ctx);
 
     match handler {
         Some(_handler) => {
@@ -93,7 +175,7 @@ pub async fn leave(ctx: PoiseContext<'_>) -> PoiseResult {
                     match ctx
                         .data()
                         .audio_table()
-                        .find_audio_row(db::UniqueAudioTableCol::Name(leave_audio.clone()))
+                        .find_audio_row(db::UniqueAudioTableCol::from_input(leave_audio))
                     {
                         Some(row) => {
                             log::debug!("bot leave audio playing: {}", row.name);
@@ -109,312 +191,4668 @@ pub async fn leave(ctx: PoiseContext<'_>) -> PoiseResult {
             }
 
             match manager.remove(guild_id).await {
-                Ok(_) => poise_check_msg(ctx.say("Left voice channel").await),
+                Ok(_) => poise_check_msg(ctx.say(tr(lang, "voice.left", &[])).await),
                 Err(e) => poise_check_msg(ctx.say(format!("Failed {:?}", e)).await),
             }
         }
-        None => poise_check_msg(ctx.reply("Not in a voice channel").await),
+        None => poise_check_msg(ctx.reply(tr(lang, "voice.not_in_channel", &[])).await),
     }
 
     Ok(())
 }
 
-#[poise::command(slash_command, prefix_command, guild_only)]
+#[poise::command(
+    slash_command,
+    prefix_command,
+    guild_only,
+    check = "channel_restrictions::can_use_soundboard"
+)]
+#[tracing::instrument(name = "/play", skip_all)]
 pub async fn play(
     ctx: PoiseContext<'_>,
     #[rename = "track"]
     #[description = "Track to play"]
     #[autocomplete = "helpers::autocomplete_audio_track_name"]
     audio_track_name: String,
+    #[rename = "loop"]
+    #[description = "Times to play the track, or `infinite` to repeat it until stopped"]
+    loop_count: Option<String>,
+    #[description = "Stop or pause sounds playing to play this one right away"] priority: Option<
+        bool,
+    >,
+    #[description = "Only play the sound, without posting a message. Defaults to /settings silent"]
+    silent: Option<bool>,
+) -> PoiseResult {
+    play_sound(ctx, audio_track_name, loop_count, priority, silent).await
+}
+
+#[poise::command(
+    slash_command,
+    prefix_command,
+    guild_only,
+    rename = "play-id",
+    check = "channel_restrictions::can_use_soundboard"
+)]
+#[tracing::instrument(name = "/play-id", skip_all)]
+pub async fn play_id(
+    ctx: PoiseContext<'_>,
+    #[description = "Id of the sound to play, as shown by /sounds info"]
+    #[min = 1]
+    id: i64,
+) -> PoiseResult {
+    play_sound(
+        ctx,
+        db::UniqueAudioTableCol::Id(id).to_input(),
+        None,
+        None,
+        None,
+    )
+    .await
+}
+
+/// Play sound of track option, an id or a name (see `UniqueAudioTableCol::from_input`).
+/// Shared by `/play` and `/play-id`
+async fn play_sound(
+    ctx: PoiseContext<'_>,
+    audio_track_name: String,
+    loop_count: Option<String>,
+    priority: Option<bool>,
+    silent: Option<bool>,
 ) -> PoiseResult {
     log::info!("Playing audio track {audio_track_name}...");
 
-    let table = ctx.data().audio_table();
     let guild_id = ctx.guild_id().ok_or("No guild id found")?;
     let channel_id = ctx.channel_id();
-    let manager = ctx.songbird().await;
+    let lang = i18n::language(ctx);
 
-    let row = table.find_audio_row(db::UniqueAudioTableCol::Name(audio_track_name.clone()));
-    match row {
-        Some(row) => {
+    let loop_mode = match loop_count.as_deref().map(playback::LoopMode::parse) {
+        None => None,
+        Some(Some(loop_mode)) => Some(loop_mode),
+        Some(None) => {
+            poise_check_msg(
+                ctx.send(CreateReply::default().ephemeral(true).content(tr(
+                    lang,
+                    "play.loop_invalid",
+                    &[("max", &vars::PLAY_LOOP_MAX)],
+                )))
+                .await,
+            );
+            return Ok(());
+        }
+    };
+    if loop_mode == Some(playback::LoopMode::Infinite)
+        && !permissions::check_permission(ctx, Permission::LoopForever).await?
+    {
+        return Ok(());
+    }
+    let priority = priority.unwrap_or(false);
+    if priority && !permissions::check_permission(ctx, Permission::PriorityPlay).await? {
+        return Ok(());
+    }
+
+    let table = ctx.data().audio_table();
+    let col = db::UniqueAudioTableCol::from_input(&audio_track_name);
+    let row = match table.find_audio_row(&col) {
+        Some(row) => row,
+        None if audio_track_name.contains(',') => {
+            let names = playback::split_sound_names(&audio_track_name);
+            return play_sounds_in_sequence(ctx, names).await;
+        }
+        None if matches!(col, db::UniqueAudioTableCol::Id(_)) => {
             poise_check_msg(
-                ctx.reply(format!("Playing track `{audio_track_name}`"))
+                ctx.reply(tr(lang, "play.not_found", &[("track", &audio_track_name)]))
                     .await,
             );
-            manager
-                .play_audio(guild_id, channel_id, &row.audio_file)
-                .await?;
+            return Ok(());
+        }
+        None => {
+            let candidates =
+                table.search_audio_rows(&audio_track_name, false, vars::FUZZY_MATCH_CANDIDATES)?;
+            match playback::fuzzy_match(&audio_track_name, candidates) {
+                playback::FuzzyMatch::Best(row) => {
+                    log::info!(
+                        "No exact match for {audio_track_name}, playing {}",
+                        row.name
+                    );
+                    *row
+                }
+                playback::FuzzyMatch::Suggestions(rows) => {
+                    let components = rows
+                        .chunks(5)
+                        .map(|row| helpers::make_action_row(row, ctx.guild_id()))
+                        .collect();
+                    poise_check_msg(
+                        ctx.send(
+                            CreateReply::default()
+                                .ephemeral(true)
+                                .content(tr(
+                                    lang,
+                                    "play.did_you_mean",
+                                    &[("track", &audio_track_name)],
+                                ))
+                                .components(components),
+                        )
+                        .await,
+                    );
+                    return Ok(());
+                }
+                playback::FuzzyMatch::None => {
+                    poise_check_msg(
+                        ctx.reply(tr(lang, "play.not_found", &[("track", &audio_track_name)]))
+                            .await,
+                    );
+                    return Ok(());
+                }
+            }
+        }
+    };
+
+    let silent = playback::is_silent(ctx.data(), guild_id, silent);
+    let voice = ctx.voice().await;
+    let result = playback::play_audio_track_with_options(
+        ctx.data(),
+        voice.as_ref(),
+        guild_id,
+        channel_id,
+        ctx.author().id,
+        db::UniqueAudioTableCol::Id(row.id),
+        playback::PlaySource::command(ctx),
+        playback::PlayOptions {
+            loop_mode,
+            priority,
+            silent: Some(silent),
+        },
+    )
+    .await;
+
+    match result {
+        // slash commands must be responded to, so only the member playing the sound sees it
+        Ok(_) if silent => {
+            if let poise::Context::Application(_) = ctx {
+                poise_check_msg(
+                    ctx.send(CreateReply::default().ephemeral(true).content(tr(
+                        lang,
+                        "play.playing",
+                        &[("track", &row.name)],
+                    )))
+                    .await,
+                );
+            }
+        }
+        Ok(_) => {
+            let content = match loop_mode {
+                Some(playback::LoopMode::Times(times)) if times > 1 => tr(
+                    lang,
+                    "play.playing_times",
+                    &[("track", &row.name), ("times", &times)],
+                ),
+                Some(playback::LoopMode::Infinite) => {
+                    tr(lang, "play.playing_forever", &[("track", &row.name)])
+                }
+                _ => tr(lang, "play.playing", &[("track", &row.name)]),
+            };
+            poise_check_msg(ctx.reply(content).await)
         }
-        None => poise_check_msg(
-            ctx.reply(format!("Audio Track '{audio_track_name}' not found"))
+        Err(AudioError::AudioTrackNotFound { .. }) => poise_check_msg(
+            ctx.reply(tr(lang, "play.not_found", &[("track", &audio_track_name)]))
+                .await,
+        ),
+        Err(AudioError::Quarantined { track }) => poise_check_msg(
+            ctx.reply(tr(lang, "play.quarantined", &[("track", &track)]))
+                .await,
+        ),
+        Err(AudioError::ChecksumMismatch { track }) => poise_check_msg(
+            ctx.reply(tr(lang, "play.checksum_mismatch", &[("track", &track)]))
                 .await,
         ),
+        Err(AudioError::RateLimited { retry_after }) => poise_check_msg(
+            ctx.send(
+                CreateReply::default()
+                    .ephemeral(true)
+                    .content(helpers::slow_down_message(lang, retry_after)),
+            )
+            .await,
+        ),
+        Err(err) => return Err(err.into()),
     }
 
     Ok(())
 }
 
+#[poise::command(slash_command, guild_only, subcommands("playlist_quickplay"))]
+pub async fn playlist(_ctx: PoiseContext<'_>) -> PoiseResult {
+    log::warn!(
+        "/playlist command shouldn't be invoked directly. It should just house sub commands"
+    );
+    Ok(())
+}
+
 #[poise::command(
     slash_command,
-    prefix_command,
     guild_only,
-    subcommands(
-        "add_sound",
-        "remove_sound",
-        "display_sounds",
-        "edit_sound",
-        "set_join_audio",
-        "set_leave_audio",
-        "display_help"
-    )
+    rename = "quickplay",
+    check = "channel_restrictions::can_use_soundboard"
 )]
-pub async fn sounds(_ctx: PoiseContext<'_>) -> PoiseResult {
-    log::warn!("/sounds command shouldn't be invoked direclty. It should just house sub commands");
-    Ok(())
-}
-
-#[poise::command(prefix_command, guild_only)]
-pub async fn scan(ctx: PoiseContext<'_>) -> PoiseResult {
-    log::info!("Scanning audio files...");
-
-    let audio_validator = audio::AudioFileValidator::new()
-        .max_audio_duration(ctx.data().config.max_audio_file_duration);
-
-    let mut audio_files: Vec<AudioFile> = ctx
-        .data()
-        .read_audio_dir()
+pub async fn playlist_quickplay(
+    ctx: PoiseContext<'_>,
+    #[description = "First sound to play"]
+    #[autocomplete = "helpers::autocomplete_audio_track_name"]
+    sound1: String,
+    #[description = "Sound played after the first"]
+    #[autocomplete = "helpers::autocomplete_audio_track_name"]
+    sound2: String,
+    #[description = "Sound played last"]
+    #[autocomplete = "helpers::autocomplete_audio_track_name"]
+    sound3: Option<String>,
+) -> PoiseResult {
+    let names = [Some(sound1), Some(sound2), sound3]
         .into_iter()
-        .filter(|f| audio_validator.validate(f.as_path()).is_ok())
+        .flatten()
         .collect();
+    play_sounds_in_sequence(ctx, names).await
+}
 
-    let paginator = db::AudioTablePaginator::builder(ctx.data().db_connection()).build();
+/// Resolve sound names and play them one after another, reporting names without a sound.
+/// Shared by `/playlist quickplay` and `/play` with comma separated names
+async fn play_sounds_in_sequence(ctx: PoiseContext<'_>, names: Vec<String>) -> PoiseResult {
+    log::info!("Playing audio tracks in sequence - {names:?}");
 
-    // ignore audio files already in database
-    for page in paginator {
-        let page = page.log_err()?;
-        for row in page {
-            audio_files.remove_audio_file(&row.audio_file);
-        }
+    let guild_id = ctx.guild_id().ok_or("No guild id found")?;
+    let lang = i18n::language(ctx);
+
+    if names.len() > vars::PLAY_SEQUENCE_MAX_LEN {
+        poise_check_msg(
+            ctx.send(CreateReply::default().ephemeral(true).content(tr(
+                lang,
+                "play.sequence_too_long",
+                &[("max", &vars::PLAY_SEQUENCE_MAX_LEN)],
+            )))
+            .await,
+        );
+        return Ok(());
     }
 
-    // add remaining audio files not in database
-    log::info!(
-        "Scan found {} audio files to add to databse",
-        audio_files.len()
-    );
-
-    let mut inserted = 0;
-    let table = AudioTable::new(ctx.data().db_connection());
-    for audio_file in audio_files {
-        let new_audio = AudioTableRowInsert {
-            name: audio_file.audio_title(),
-            tags: Tags::new(),
-            audio_file: audio_file,
-            created_at: chrono::Utc::now(),
-            author_id: None,
-            author_name: None,
-            author_global_name: None,
-        };
+    let (rows, not_found) = playback::resolve_sound_names(&ctx.data().audio_table(), &names)?;
+    let quoted = |names: Vec<&str>| {
+        names
+            .iter()
+            .map(|name| format!("`{name}`"))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
 
-        table
-            .insert_audio_row(new_audio)
-            .log_err()
-            .and_then(|_| {
-                inserted += 1;
-                Ok(())
-            })
-            .ok();
+    let mut lines = vec![];
+    if !rows.is_empty() {
+        lines.push(tr(
+            lang,
+            "play.sequence",
+            &[
+                ("count", &rows.len()),
+                (
+                    "tracks",
+                    &quoted(rows.iter().map(|row| row.name.as_str()).collect()),
+                ),
+            ],
+        ));
     }
+    if !not_found.is_empty() {
+        lines.push(tr(
+            lang,
+            "play.sequence_not_found",
+            &[(
+                "tracks",
+                &quoted(not_found.iter().map(String::as_str).collect()),
+            )],
+        ));
+    }
+    poise_check_msg(ctx.reply(lines.join("\n")).await);
 
-    log::info!("Scan complete - added {inserted} new audio files");
-    Ok(())
-}
+    if rows.is_empty() {
+        return Ok(());
+    }
 
-#[poise::command(slash_command, prefix_command, guild_only)]
-pub async fn echo(
-    ctx: PoiseContext<'_>,
-    #[description = "Text to echo back"] text: Option<String>,
-) -> PoiseResult {
-    let response = format!("Echo: '{}'", text.unwrap_or("".into()));
-    poise_check_msg(ctx.say(response).await);
+    // sounds are played to their end, so play them off the interaction
+    let data = ctx.data().clone();
+    let voice = ctx.voice().await;
+    let channel_id = ctx.channel_id();
+    let user_id = ctx.author().id;
+    let source = playback::PlaySource::command(ctx);
+    let audio_ids = rows.iter().map(|row| row.id).collect();
+    tokio::spawn(async move {
+        playback::play_sequence(
+            &data,
+            voice.as_ref(),
+            guild_id,
+            channel_id,
+            user_id,
+            audio_ids,
+            source,
+        )
+        .await
+        .log_err_msg("Failed to play sounds in sequence")
+        .ok();
+    });
 
     Ok(())
 }
 
-#[poise::command(prefix_command, guild_only)]
-pub async fn register(ctx: PoiseContext<'_>) -> PoiseResult {
-    poise::builtins::register_application_commands_buttons(ctx).await?;
+#[poise::command(
+    slash_command,
+    guild_only,
+    subcommands("record_start", "record_stop", "record_list", "record_play")
+)]
+pub async fn record(_ctx: PoiseContext<'_>) -> PoiseResult {
+    log::warn!("/record command shouldn't be invoked directly. It should just house sub commands");
     Ok(())
 }
 
-#[derive(Debug, poise::Modal)]
-#[name = "Add Sound"]
-struct AddSoundModal {
-    #[name = "Name"] // Field name by default
-    #[placeholder = "Use The Force Luke"] // No placeholder by default
-    #[min_length = 3] // No length restriction by default (so, 1-4000 chars)
-    #[max_length = 80] // Same as max button label len (crate::vars::BTN_LABEL_MAX_LEN)
-    name: String,
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "start",
+    check = "channel_restrictions::can_use_soundboard"
+)]
+pub async fn record_start(ctx: PoiseContext<'_>) -> PoiseResult {
+    let guild_id = ctx.guild_id().ok_or("No guild id found")?;
+    let lang = i18n::language(ctx);
 
-    #[name = "Tags"] // Field name by default
-    #[placeholder = "star Wars jedi luke skywalker force episode iv"] // No placeholder by default
-    #[max_length = 1024]
-    tags: Option<String>,
+    let table = ctx.data().sessions_table();
+    let content = match table.active_session(guild_id.get())? {
+        Some(session) => tr(lang, "record.already", &[("id", &session.id)]),
+        None => {
+            let id =
+                table.start_session(guild_id.get(), ctx.author().id.get(), chrono::Utc::now())?;
+            tr(lang, "record.started", &[("id", &id)])
+        }
+    };
 
-    #[name = "Audio URL"]
-    #[placeholder = "www.example.com/use-the-force.mp3"]
-    #[max_length = 2048]
-    url: String,
+    poise_check_msg(ctx.say(content).await);
+    Ok(())
 }
 
-#[poise::command(slash_command, guild_only, rename = "add")]
-pub async fn add_sound(ctx: PoiseAppContext<'_>) -> PoiseResult {
-    let data = AddSoundModal::execute(ctx)
-        .await?
-        .ok_or("AddSoundModal not set")
-        .log_err()?;
-
-    log::info!("Adding sound. Name: {}, Url: {}", data.name, data.url);
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "stop",
+    check = "channel_restrictions::can_use_soundboard"
+)]
+pub async fn record_stop(ctx: PoiseContext<'_>) -> PoiseResult {
+    let guild_id = ctx.guild_id().ok_or("No guild id found")?;
+    let lang = i18n::language(ctx);
 
-    let table = ctx.data.audio_table();
-    let row = table.find_audio_row(db::UniqueAudioTableCol::Name(data.name.clone()));
+    let table = ctx.data().sessions_table();
+    let Some(session) = table.active_session(guild_id.get())? else {
+        poise_check_msg(
+            ctx.send(CreateReply::default().ephemeral(true).content(tr(
+                lang,
+                "record.not_recording",
+                &[],
+            )))
+            .await,
+        );
+        return Ok(());
+    };
 
-    match row {
-        Some(_) => {
-            return Err("Can't add sound. It already exists".into()).log_err();
-        }
-        None => {
-            let temp_audio_file = audio::download_audio_url_temp(&data.url).await?;
+    let now = chrono::Utc::now();
+    table.end_session(session.id, now)?;
+    let plays = table.count_session_plays(session.id)?;
+    let duration = sessions::format_duration(sessions::session_duration(&session, now));
 
-            // validate audio track (codec type, length, etc)
-            audio::AudioFileValidator::default()
-                .max_audio_duration(ctx.data().config.max_audio_file_duration)
-                .reject_uuid_files(false)
-                .validate(&temp_audio_file)?;
+    poise_check_msg(
+        ctx.say(tr(
+            lang,
+            "record.stopped",
+            &[
+                ("id", &session.id),
+                ("plays", &plays),
+                ("duration", &duration),
+            ],
+        ))
+        .await,
+    );
+    Ok(())
+}
 
-            // move track to sounds dir
-            let audio_file = ctx.data().move_file_to_audio_dir(&temp_audio_file)?;
-            let tags: Tags = match data.tags {
-                Some(val) => Tags::from(val),
-                None => Tags::new(),
-            };
+#[poise::command(slash_command, guild_only, rename = "list")]
+pub async fn record_list(ctx: PoiseContext<'_>) -> PoiseResult {
+    let guild_id = ctx.guild_id().ok_or("No guild id found")?;
+    let lang = i18n::language(ctx);
 
-            table
-                .insert_audio_row(AudioTableRowInsert {
-                    name: data.name.clone(),
-                    audio_file: audio_file,
-                    author_global_name: ctx.author().global_name.clone(),
-                    author_id: Some(ctx.author().id.into()),
-                    author_name: Some(ctx.author().name.clone()),
-                    tags: tags,
-                    created_at: chrono::Utc::now(),
-                })
-                .log_err()?;
-        }
+    let table = ctx.data().sessions_table();
+    let now = chrono::Utc::now();
+    let mut lines = vec![];
+    for session in table.list_sessions(guild_id.get(), vars::SESSION_LIST_LEN)? {
+        let plays = table.count_session_plays(session.id)?;
+        lines.push(sessions::list_entry(lang, &session, plays, now));
     }
 
+    let content = match lines.is_empty() {
+        true => tr(lang, "record.list_empty", &[]),
+        false => format!("{}\n{}", tr(lang, "record.list", &[]), lines.join("\n")),
+    };
     poise_check_msg(
-        ctx.reply(format!("Added sound `{}` to soundboard", data.name))
+        ctx.send(CreateReply::default().ephemeral(true).content(content))
             .await,
     );
-
     Ok(())
 }
 
-#[poise::command(slash_command, guild_only, rename = "remove")]
-pub async fn remove_sound(
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "play",
+    check = "channel_restrictions::can_use_soundboard"
+)]
+pub async fn record_play(
     ctx: PoiseContext<'_>,
-    #[rename = "track"]
-    #[description = "Track to play"]
-    #[autocomplete = "helpers::autocomplete_audio_track_name"]
-    audio_track_name: String,
+    #[description = "Session number, see /record list"]
+    #[min = 1]
+    session: i64,
 ) -> PoiseResult {
-    log::info!("Removing audio track - {audio_track_name}");
-    let table = ctx.data().audio_table();
+    let guild_id = ctx.guild_id().ok_or("No guild id found")?;
+    let lang = i18n::language(ctx);
+    log::info!("Replaying session. Guild Id: {guild_id}, Session Id: {session}");
 
-    table.delete_audio_row(db::UniqueAudioTableCol::Name(audio_track_name.clone()))?;
-    poise_check_msg(
-        ctx.reply(format!("Removed audio track `{audio_track_name}`"))
+    let table = ctx.data().sessions_table();
+    let plays = match table.find_session(session, guild_id.get())? {
+        Some(session) => table.session_plays(session.id)?,
+        None => vec![],
+    };
+    if plays.is_empty() {
+        poise_check_msg(
+            ctx.send(CreateReply::default().ephemeral(true).content(tr(
+                lang,
+                "record.not_found",
+                &[("id", &session)],
+            )))
             .await,
+        );
+        return Ok(());
+    }
+
+    poise_check_msg(
+        ctx.say(tr(
+            lang,
+            "record.replaying",
+            &[("id", &session), ("plays", &plays.len())],
+        ))
+        .await,
     );
 
-    log::info!("Audio track removed {audio_track_name}");
+    // sessions can run for minutes, so replay them off the interaction
+    let data = ctx.data().clone();
+    let voice = ctx.voice().await;
+    let channel_id = ctx.channel_id();
+    let user_id = ctx.author().id;
+    let source = playback::PlaySource::command(ctx);
+    tokio::spawn(async move {
+        playback::play_timed(
+            &data,
+            voice.as_ref(),
+            guild_id,
+            channel_id,
+            user_id,
+            sessions::timeline(&plays),
+            source,
+        )
+        .await
+        .log_err_msg("Failed to replay session")
+        .ok();
+    });
+
     Ok(())
 }
 
-#[poise::command(slash_command, guild_only, rename = "display")]
-pub async fn display_sounds(ctx: PoiseContext<'_>) -> PoiseResult {
-    log::info!("List sounds buttons as ActionRows grid...");
+#[poise::command(
+    slash_command,
+    prefix_command,
+    guild_only,
+    check = "channel_restrictions::can_use_soundboard"
+)]
+pub async fn random(
+    ctx: PoiseContext<'_>,
+    #[description = "Only play sounds with this tag"]
+    #[autocomplete = "helpers::autocomplete_tag"]
+    tag: Option<String>,
+) -> PoiseResult {
+    log::info!("Playing random audio track... Tag: {tag:?}");
 
-    poise_check_msg(ctx.reply("Displaying sounds...").await);
+    let lang = i18n::language(ctx);
+    let tag = tag.map(Tags::normalize_tag);
+    let Some(row) = ctx.data().audio_table().random_audio_row(tag.as_deref()) else {
+        let reply = match tag {
+            Some(tag) => tr(lang, "play.no_tagged_sounds", &[("tag", &tag)]),
+            None => tr(lang, "play.no_sounds", &[]),
+        };
+        poise_check_msg(ctx.reply(reply).await);
+        return Ok(());
+    };
 
-    let paginator = db::AudioTablePaginator::builder(ctx.data().db_connection())
-        .page_limit(vars::ACTION_ROWS_LIMIT)
-        .build();
+    let guild_id = ctx.guild_id().ok_or("No guild id found")?;
+    let voice = ctx.voice().await;
 
-    for audio_rows in paginator {
-        let audio_rows = audio_rows.log_err()?;
+    let result = playback::play_audio_track(
+        ctx.data(),
+        voice.as_ref(),
+        guild_id,
+        ctx.channel_id(),
+        ctx.author().id,
+        db::UniqueAudioTableCol::Id(row.id),
+        playback::PlaySource::command(ctx),
+    )
+    .await;
 
-        // ActionRows: Have a 5x5 grid limit
-        // (https://discordjs.guide/message-components/action-rows.html#action-rows)
-        let btn_grid: Vec<_> = audio_rows.chunks(5).map(helpers::make_action_row).collect();
-        let builder = CreateMessage::new().components(btn_grid);
-        check_msg(ctx.channel_id().send_message(&ctx.http(), builder).await);
+    match result {
+        Ok(row) => poise_check_msg(
+            ctx.reply(tr(lang, "play.playing", &[("track", &row.name)]))
+                .await,
+        ),
+        Err(AudioError::RateLimited { retry_after }) => poise_check_msg(
+            ctx.send(
+                CreateReply::default()
+                    .ephemeral(true)
+                    .content(helpers::slow_down_message(lang, retry_after)),
+            )
+            .await,
+        ),
+        Err(err) => return Err(err.into()),
     }
 
     Ok(())
 }
 
-#[derive(Debug, poise::Modal)]
-#[name = "Edit Sound"]
-struct EditSoundModal {
-    #[name = "Name"]
-    #[min_length = 3] // No length restriction by default (so, 1-4000 chars)
-    #[max_length = 80] // Same as max button label len (crate::vars::BTN_LABEL_MAX_LEN)
-    name: String,
-    #[name = "Tags"]
-    #[max_length = 1024]
-    tags: Option<String>,
-}
-
-#[poise::command(slash_command, guild_only, rename = "edit")]
-pub async fn edit_sound(
-    ctx: PoiseAppContext<'_>,
-    #[description = "Audio track to edit"]
-    #[rename = "track"]
-    #[autocomplete = "helpers::autocomplete_audio_track_name"]
-    audio_track_name: String,
+#[poise::command(
+    slash_command,
+    prefix_command,
+    guild_only,
+    check = "channel_restrictions::can_use_soundboard"
+)]
+pub async fn replay(
+    ctx: PoiseContext<'_>,
+    #[description = "Replay the last sound you played, instead of the last sound played"]
+    mine: Option<bool>,
 ) -> PoiseResult {
-    log::info!("Editing audio track - {audio_track_name}");
-
-    let table = ctx.data().audio_table();
+    log::info!("Replaying last audio track...");
 
-    let mut row = table
-        .find_audio_row(db::UniqueAudioTableCol::Name(audio_track_name.clone()))
-        .ok_or(format!("Unable to locate audio track '{audio_track_name}'"))
-        .log_err()?;
+    let guild_id = ctx.guild_id().ok_or("No guild id found")?;
+    let lang = i18n::language(ctx);
+    let voice = ctx.voice().await;
 
-    let data = EditSoundModal::execute_with_defaults(
-        ctx,
-        EditSoundModal {
-            name: audio_track_name.clone(),
-            tags: Some(row.tags.to_string()),
-        },
+    let result = playback::replay_last_track(
+        ctx.data(),
+        voice.as_ref(),
+        guild_id,
+        ctx.channel_id(),
+        ctx.author().id,
+        mine.unwrap_or(false),
+        playback::PlaySource::command(ctx),
     )
-    .await?;
+    .await;
 
-    match data {
-        Some(data) => {
-            log::debug!("{data:?}");
-            let tags: Tags = match data.tags {
-                Some(val) => Tags::from(val),
+    match result {
+        Ok(row) => poise_check_msg(
+            ctx.reply(tr(lang, "play.playing", &[("track", &row.name)]))
+                .await,
+        ),
+        Err(AudioError::NothingToReplay) => poise_check_msg(
+            ctx.send(CreateReply::default().ephemeral(true).content(tr(
+                lang,
+                "replay.nothing",
+                &[],
+            )))
+            .await,
+        ),
+        Err(AudioError::RateLimited { retry_after }) => poise_check_msg(
+            ctx.send(
+                CreateReply::default()
+                    .ephemeral(true)
+                    .content(helpers::slow_down_message(lang, retry_after)),
+            )
+            .await,
+        ),
+        Err(err) => return Err(err.into()),
+    }
+
+    Ok(())
+}
+
+#[poise::command(
+    slash_command,
+    prefix_command,
+    guild_only,
+    check = "channel_restrictions::can_use_soundboard"
+)]
+pub async fn stop(ctx: PoiseContext<'_>) -> PoiseResult {
+    log::info!("Stopping audio tracks...");
+
+    let guild_id = ctx.guild_id().ok_or("No guild id found")?;
+    let lang = i18n::language(ctx);
+
+    ctx.data().playback_queue.clear(guild_id);
+    match ctx.voice().await.stop_audio(guild_id).await {
+        Ok(_) => poise_check_msg(ctx.reply(tr(lang, "play.stopped", &[])).await),
+        Err(AudioError::NotInVoiceChannel) => {
+            poise_check_msg(ctx.reply(tr(lang, "voice.not_in_channel", &[])).await)
+        }
+        Err(err) => return Err(err.into()),
+    }
+
+    Ok(())
+}
+
+#[poise::command(slash_command, prefix_command)]
+pub async fn search(
+    ctx: PoiseContext<'_>,
+    #[description = "Name or tags to search for"] query: String,
+    #[rename = "include-archived"]
+    #[description = "Include archived sounds"]
+    include_archived: Option<bool>,
+) -> PoiseResult {
+    let include_archived = include_archived.unwrap_or(false);
+    log::info!("Searching audio tracks - '{query}', Include archived: {include_archived}");
+
+    let lang = i18n::language(ctx);
+    let rows = ctx.data().audio_table().search_audio_rows(
+        &query,
+        include_archived,
+        vars::SEARCH_RESULTS_LIMIT,
+    )?;
+
+    let content = match rows.is_empty() {
+        true => tr(lang, "search.none", &[("query", &query)]),
+        false => {
+            let archived = tr(lang, "search.archived", &[]);
+            let lines: Vec<_> = rows
+                .iter()
+                .map(|row| {
+                    let details: Vec<_> = [
+                        row.duration_ms.map(helpers::format_duration_ms),
+                        row.archived_at.map(|_| archived.clone()),
+                    ]
+                    .into_iter()
+                    .flatten()
+                    .collect();
+                    match details.is_empty() {
+                        true => format!("- `{}`", row.name),
+                        false => format!("- `{}` ({})", row.name, details.join(", ")),
+                    }
+                })
+                .collect();
+            format!(
+                "{}\n{}",
+                tr(lang, "search.results", &[("query", &query)]),
+                lines.join("\n")
+            )
+        }
+    };
+
+    poise_check_msg(
+        ctx.send(CreateReply::default().ephemeral(true).content(content))
+            .await,
+    );
+
+    Ok(())
+}
+
+#[poise::command(
+    slash_command,
+    prefix_command,
+    subcommands(
+        "add_sound",
+        "upload_sounds",
+        "remove_sound",
+        "archive_sound",
+        "unarchive_sound",
+        "restore_sound",
+        "transfer_sound",
+        "share_sound",
+        "import_shared_sound",
+        "set_sound_emoji",
+        "set_sound_category",
+        "set_sound_button_style",
+        "refetch_sound",
+        "sound_info",
+        "display_sounds",
+        "edit_sound",
+        "set_join_audio",
+        "set_leave_audio",
+        "toggle_favorite",
+        "display_help"
+    )
+)]
+pub async fn sounds(_ctx: PoiseContext<'_>) -> PoiseResult {
+    log::warn!("/sounds command shouldn't be invoked direclty. It should just house sub commands");
+    Ok(())
+}
+
+#[poise::command(prefix_command, guild_only)]
+pub async fn scan(ctx: PoiseContext<'_>) -> PoiseResult {
+    log::info!("Scanning audio files...");
+    ctx.data().sync_audio_dir(|_| async {}).await?;
+    Ok(())
+}
+
+#[poise::command(
+    slash_command,
+    prefix_command,
+    guild_only,
+    required_permissions = "ADMINISTRATOR",
+    subcommands(
+        "admin_scan",
+        "admin_bulk",
+        "admin_resync_commands",
+        "admin_prune_suggestions",
+        "admin_categories"
+    )
+)]
+pub async fn admin(_ctx: PoiseContext<'_>) -> PoiseResult {
+    log::warn!("/admin command shouldn't be invoked directly. It should just house sub commands");
+    Ok(())
+}
+
+/// List sounds not played for months, with buttons to archive or keep each
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "prune-suggestions",
+    required_permissions = "ADMINISTRATOR"
+)]
+pub async fn admin_prune_suggestions(
+    ctx: PoiseContext<'_>,
+    #[description = "Suggest sounds not played for this many months (default 6)"]
+    #[min = 1]
+    #[max = 120]
+    months: Option<u32>,
+) -> PoiseResult {
+    let guild_id = ctx.guild_id().ok_or("No guild id found")?;
+    let months = months.unwrap_or(vars::PRUNE_DEFAULT_MONTHS);
+    log::info!("Listing prune suggestions. Guild Id: {guild_id}, Months: {months}");
+
+    let rows = ctx.data().audio_table().unused_audio_rows(
+        guild_id.get(),
+        prune::unused_since(chrono::Utc::now(), months),
+        vars::PRUNE_SUGGESTIONS_LIMIT,
+    )?;
+
+    poise_check_msg(
+        ctx.send(prune::suggestions_reply(
+            i18n::language(ctx),
+            guild_id,
+            months,
+            &rows,
+        ))
+        .await,
+    );
+    Ok(())
+}
+
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "categories",
+    required_permissions = "ADMINISTRATOR",
+    subcommands("add_category", "edit_category", "remove_category", "list_categories")
+)]
+pub async fn admin_categories(_ctx: PoiseContext<'_>) -> PoiseResult {
+    log::warn!(
+        "/admin categories command shouldn't be invoked directly. It should just house sub commands"
+    );
+    Ok(())
+}
+
+/// Reply only the command author can see
+async fn reply_ephemeral(ctx: PoiseContext<'_>, content: String) {
+    poise_check_msg(
+        ctx.send(CreateReply::default().ephemeral(true).content(content))
+            .await,
+    );
+}
+
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "add",
+    required_permissions = "ADMINISTRATOR"
+)]
+pub async fn add_category(
+    ctx: PoiseContext<'_>,
+    #[description = "Category name, shown as a soundboard section header"]
+    #[max_length = 32]
+    name: String,
+    #[description = "Emoji shown before the category name"] emoji: Option<String>,
+    #[description = "Position of the category on the soundboard, lowest first. Defaults to last"]
+    order: Option<i64>,
+) -> PoiseResult {
+    log::info!("Adding category - {name}, Emoji: {emoji:?}, Order: {order:?}");
+
+    let lang = i18n::language(ctx);
+    let table = ctx.data().categories_table();
+    let name = name.trim();
+
+    if name.is_empty() || name.chars().count() > vars::CATEGORY_NAME_MAX_LEN {
+        let content = tr(
+            lang,
+            "categories.invalid_name",
+            &[("max", &vars::CATEGORY_NAME_MAX_LEN)],
+        );
+        reply_ephemeral(ctx, content).await;
+        return Ok(());
+    }
+    if let Some(existing) = table.find_category(name)? {
+        let content = tr(
+            lang,
+            "categories.exists",
+            &[("category", &existing.label())],
+        );
+        reply_ephemeral(ctx, content).await;
+        return Ok(());
+    }
+    let emoji = match emoji {
+        Some(emoji) => match helpers::parse_emoji(&emoji) {
+            Some(emoji) => Some(emoji),
+            None => {
+                let content = tr(lang, "sounds.invalid_emoji", &[("emoji", &emoji)]);
+                reply_ephemeral(ctx, content).await;
+                return Ok(());
+            }
+        },
+        None => None,
+    };
+
+    // new categories go last
+    let order = match order {
+        Some(order) => order,
+        None => table
+            .list_categories()?
+            .iter()
+            .map(|category| category.sort_order + 1)
+            .max()
+            .unwrap_or(0),
+    };
+    let id = table.insert_category(name, emoji.as_deref(), order)?;
+    let category = table
+        .find_category_by_id(id)?
+        .ok_or("Added category missing")?;
+
+    let content = tr(lang, "categories.added", &[("category", &category.label())]);
+    reply_ephemeral(ctx, content).await;
+    refresh_published_soundboard(ctx).await;
+
+    Ok(())
+}
+
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "edit",
+    required_permissions = "ADMINISTRATOR"
+)]
+pub async fn edit_category(
+    ctx: PoiseContext<'_>,
+    #[description = "Category to edit"]
+    #[autocomplete = "helpers::autocomplete_category"]
+    category: String,
+    #[description = "New category name"]
+    #[max_length = 32]
+    name: Option<String>,
+    #[description = "New emoji shown before the category name"] emoji: Option<String>,
+    #[rename = "remove-emoji"]
+    #[description = "Remove the category's emoji"]
+    remove_emoji: Option<bool>,
+    #[description = "New position of the category on the soundboard, lowest first"] order: Option<
+        i64,
+    >,
+) -> PoiseResult {
+    log::info!("Editing category - {category}, Name: {name:?}, Emoji: {emoji:?}, Order: {order:?}");
+
+    let lang = i18n::language(ctx);
+    let table = ctx.data().categories_table();
+    let Some(mut row) = table.find_category(&category)? else {
+        let content = tr(lang, "categories.not_found", &[("category", &category)]);
+        reply_ephemeral(ctx, content).await;
+        return Ok(());
+    };
+
+    if let Some(name) = name {
+        let name = name.trim();
+        if name.is_empty() || name.chars().count() > vars::CATEGORY_NAME_MAX_LEN {
+            let content = tr(
+                lang,
+                "categories.invalid_name",
+                &[("max", &vars::CATEGORY_NAME_MAX_LEN)],
+            );
+            reply_ephemeral(ctx, content).await;
+            return Ok(());
+        }
+        if let Some(existing) = table.find_category(name)?.filter(|c| c.id != row.id) {
+            let content = tr(
+                lang,
+                "categories.exists",
+                &[("category", &existing.label())],
+            );
+            reply_ephemeral(ctx, content).await;
+            return Ok(());
+        }
+        row.name = name.into();
+    }
+    if let Some(emoji) = emoji {
+        match helpers::parse_emoji(&emoji) {
+            Some(emoji) => row.emoji = Some(emoji),
+            None => {
+                let content = tr(lang, "sounds.invalid_emoji", &[("emoji", &emoji)]);
+                reply_ephemeral(ctx, content).await;
+                return Ok(());
+            }
+        }
+    }
+    if remove_emoji == Some(true) {
+        row.emoji = None;
+    }
+    if let Some(order) = order {
+        row.sort_order = order;
+    }
+    table.update_category(&row)?;
+
+    let content = tr(lang, "categories.edited", &[("category", &row.label())]);
+    reply_ephemeral(ctx, content).await;
+    refresh_published_soundboard(ctx).await;
+
+    Ok(())
+}
+
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "remove",
+    required_permissions = "ADMINISTRATOR"
+)]
+pub async fn remove_category(
+    ctx: PoiseContext<'_>,
+    #[description = "Category to remove. Its sounds are kept, without a category"]
+    #[autocomplete = "helpers::autocomplete_category"]
+    category: String,
+) -> PoiseResult {
+    log::info!("Removing category - {category}");
+
+    let lang = i18n::language(ctx);
+    let table = ctx.data().categories_table();
+    let content = match table.find_category(&category)? {
+        Some(row) => {
+            table.delete_category(row.id)?;
+            tr(lang, "categories.removed", &[("category", &row.label())])
+        }
+        None => tr(lang, "categories.not_found", &[("category", &category)]),
+    };
+
+    reply_ephemeral(ctx, content).await;
+    refresh_published_soundboard(ctx).await;
+
+    Ok(())
+}
+
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "list",
+    required_permissions = "ADMINISTRATOR"
+)]
+pub async fn list_categories(ctx: PoiseContext<'_>) -> PoiseResult {
+    log::info!("Listing categories");
+
+    let lang = i18n::language(ctx);
+    let categories = ctx.data().categories_table().list_categories()?;
+    let counts = ctx.data().audio_table().category_counts(None)?;
+
+    let content = match categories.is_empty() {
+        true => tr(lang, "categories.none", &[]),
+        false => categories
+            .iter()
+            .map(|category| {
+                let sounds = counts
+                    .iter()
+                    .find(|(id, _)| *id == Some(category.id))
+                    .map(|(_, count)| *count)
+                    .unwrap_or(0);
+                tr(
+                    lang,
+                    "categories.list_entry",
+                    &[
+                        ("order", &category.sort_order),
+                        ("category", &category.label()),
+                        ("sounds", &sounds),
+                    ],
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+    };
+
+    reply_ephemeral(ctx, helpers::truncate_message(content)).await;
+    Ok(())
+}
+
+#[poise::command(slash_command, prefix_command, guild_only, rename = "scan")]
+pub async fn admin_scan(ctx: PoiseContext<'_>) -> PoiseResult {
+    log::info!("Admin scanning audio dir...");
+    enqueue_job(ctx, Job::ScanAudioDir).await
+}
+
+/// Queue job, replying with its job id. Its outcome is posted to the command's channel
+async fn enqueue_job(ctx: PoiseContext<'_>, job: Job) -> PoiseResult {
+    let id = ctx.data().jobs.enqueue(
+        ctx.data(),
+        &job,
+        ctx.guild_id(),
+        Some(ctx.channel_id()),
+        ctx.author().id.get(),
+    )?;
+
+    poise_check_msg(
+        ctx.reply(tr(
+            i18n::language(ctx),
+            "jobs.queued",
+            &[("id", &id), ("job", &job.describe(i18n::language(ctx)))],
+        ))
+        .await,
+    );
+    Ok(())
+}
+
+#[poise::command(
+    slash_command,
+    guild_only,
+    subcommands("view_soundboard", "publish_soundboard", "personal_soundboard")
+)]
+pub async fn soundboard(_ctx: PoiseContext<'_>) -> PoiseResult {
+    log::warn!(
+        "/soundboard command shouldn't be invoked directly. It should just house sub commands"
+    );
+    Ok(())
+}
+
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "view",
+    check = "channel_restrictions::can_use_soundboard"
+)]
+pub async fn view_soundboard(
+    ctx: PoiseContext<'_>,
+    #[description = "Only show sounds with this tag"]
+    #[autocomplete = "helpers::autocomplete_tag"]
+    tag: Option<String>,
+) -> PoiseResult {
+    log::info!("Displaying soundboard. Tag: {tag:?}");
+
+    if let Some(ref tag) = tag {
+        if tag.len() > vars::SOUNDBOARD_TAG_MAX_LEN {
+            poise_check_msg(
+                ctx.send(CreateReply::default().ephemeral(true).content(tr(
+                    i18n::language(ctx),
+                    "soundboard.tag_too_long",
+                    &[("max", &vars::SOUNDBOARD_TAG_MAX_LEN)],
+                )))
+                .await,
+            );
+            return Ok(());
+        }
+    }
+
+    let page = soundboard::SoundboardPage::load(ctx.data(), ctx.guild_id(), 0, tag.as_deref())?;
+    poise_check_msg(ctx.send(page.create_reply(ctx.guild_id())).await);
+
+    Ok(())
+}
+
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "publish",
+    required_permissions = "MANAGE_MESSAGES",
+    check = "permissions::can_manage_settings"
+)]
+pub async fn publish_soundboard(
+    ctx: PoiseContext<'_>,
+    #[description = "Text channel to pin the soundboard in"]
+    #[channel_types("Text")]
+    channel: GuildChannel,
+) -> PoiseResult {
+    let guild_id = ctx.guild_id().ok_or("No guild id found")?;
+    soundboard::publish_soundboard(ctx.http(), ctx.data(), guild_id, channel.id).await?;
+
+    poise_check_msg(
+        ctx.send(CreateReply::default().ephemeral(true).content(tr(
+            i18n::language(ctx),
+            "soundboard.published",
+            &[("channel", &format!("<#{}>", channel.id))],
+        )))
+        .await,
+    );
+
+    Ok(())
+}
+
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "mine",
+    check = "channel_restrictions::can_use_soundboard"
+)]
+pub async fn personal_soundboard(ctx: PoiseContext<'_>) -> PoiseResult {
+    log::info!(
+        "Displaying personal soundboard. User Id: {}",
+        ctx.author().id
+    );
+
+    let user_id = ctx.author().id.get();
+    let audio_rows = ctx
+        .data()
+        .audio_table()
+        .personal_audio_rows(user_id, vars::ACTION_ROWS_LIMIT)?;
+
+    let lang = i18n::language(ctx);
+    let reply = match audio_rows.is_empty() {
+        true => CreateReply::default().content(tr(lang, "soundboard.mine_empty", &[])),
+        false => {
+            let btn_grid: Vec<_> = audio_rows
+                .chunks(5)
+                .map(|row| helpers::make_action_row(row, ctx.guild_id()))
+                .collect();
+            CreateReply::default()
+                .content(tr(lang, "soundboard.mine_title", &[]))
+                .components(btn_grid)
+        }
+    };
+
+    poise_check_msg(ctx.send(reply.ephemeral(true)).await);
+    Ok(())
+}
+
+/// Keep guild's pinned soundboard message in sync after sounds are added, removed or renamed
+async fn refresh_published_soundboard(ctx: PoiseContext<'_>) {
+    if let Some(guild_id) = ctx.guild_id() {
+        soundboard::refresh_soundboard(ctx.http(), ctx.data(), guild_id)
+            .await
+            .log_err_msg("Failed to refresh published soundboard")
+            .ok();
+    }
+}
+
+#[poise::command(
+    slash_command,
+    guild_only,
+    subcommands(
+        "settings_language",
+        "settings_prefix",
+        "settings_sotd",
+        "settings_permissions",
+        "settings_blocklist",
+        "settings_native_sounds",
+        "settings_channels",
+        "settings_auto_archive",
+        "settings_approval",
+        "settings_digest",
+        "settings_priority",
+        "settings_autocomplete",
+        "settings_silent",
+        "settings_soundboard_order",
+        "settings_play_history",
+        "settings_loudness",
+        "settings_max_length",
+        "settings_export",
+        "settings_import"
+    )
+)]
+pub async fn settings(_ctx: PoiseContext<'_>) -> PoiseResult {
+    log::warn!(
+        "/settings command shouldn't be invoked directly. It should just house sub commands"
+    );
+    Ok(())
+}
+
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "language",
+    check = "permissions::can_manage_settings"
+)]
+pub async fn settings_language(
+    ctx: PoiseContext<'_>,
+    #[description = "Language of bot replies. Leave empty to use this server's Discord locale"]
+    language: Option<Language>,
+) -> PoiseResult {
+    let guild_id = ctx.guild_id().ok_or("No guild id found")?;
+    log::info!("Setting language. Guild Id: {guild_id}, Language: {language:?}");
+
+    let table = ctx.data().guild_settings_table();
+    let mut settings = table.get_guild_settings(guild_id.get())?;
+    settings.language = language.map(|language| language.code().into());
+    table.update_guild_settings(&settings)?;
+
+    let lang = i18n::language(ctx);
+    let content = match language {
+        Some(language) => tr(
+            lang,
+            "settings.language_set",
+            &[("language", &language.name())],
+        ),
+        None => tr(lang, "settings.language_reset", &[]),
+    };
+
+    poise_check_msg(
+        ctx.send(CreateReply::default().ephemeral(true).content(content))
+            .await,
+    );
+    Ok(())
+}
+
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "auto-archive",
+    check = "permissions::can_manage_settings"
+)]
+pub async fn settings_auto_archive(
+    ctx: PoiseContext<'_>,
+    #[description = "Archive sounds not played for this many months. Leave empty to disable"]
+    #[min = 1]
+    #[max = 120]
+    months: Option<u32>,
+) -> PoiseResult {
+    let guild_id = ctx.guild_id().ok_or("No guild id found")?;
+    log::info!("Setting auto-archive. Guild Id: {guild_id}, Months: {months:?}");
+
+    let table = ctx.data().guild_settings_table();
+    let mut settings = table.get_guild_settings(guild_id.get())?;
+    settings.auto_archive_months = months;
+    table.update_guild_settings(&settings)?;
+
+    let lang = i18n::language(ctx);
+    let content = match months {
+        Some(months) => tr(lang, "settings.auto_archive_set", &[("months", &months)]),
+        None => tr(lang, "settings.auto_archive_disabled", &[]),
+    };
+
+    poise_check_msg(
+        ctx.send(CreateReply::default().ephemeral(true).content(content))
+            .await,
+    );
+    Ok(())
+}
+
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "approval",
+    check = "permissions::can_manage_settings"
+)]
+pub async fn settings_approval(
+    ctx: PoiseContext<'_>,
+    #[description = "Channel new sounds are reviewed in before they're added. Leave empty to disable"]
+    #[channel_types("Text")]
+    #[rename = "review-channel"]
+    review_channel: Option<GuildChannel>,
+) -> PoiseResult {
+    let guild_id = ctx.guild_id().ok_or("No guild id found")?;
+    let review_channel_id = review_channel.map(|channel| channel.id.get());
+    log::info!(
+        "Setting sound approval. Guild Id: {guild_id}, Review Channel Id: {review_channel_id:?}"
+    );
+
+    let table = ctx.data().guild_settings_table();
+    let mut settings = table.get_guild_settings(guild_id.get())?;
+    settings.review_channel_id = review_channel_id;
+    table.update_guild_settings(&settings)?;
+
+    let lang = i18n::language(ctx);
+    let content = match review_channel_id {
+        Some(channel_id) => tr(
+            lang,
+            "settings.approval_set",
+            &[("channel", &format!("<#{channel_id}>"))],
+        ),
+        None => tr(lang, "settings.approval_disabled", &[]),
+    };
+
+    poise_check_msg(
+        ctx.send(CreateReply::default().ephemeral(true).content(content))
+            .await,
+    );
+    Ok(())
+}
+
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "digest",
+    check = "permissions::can_manage_settings"
+)]
+pub async fn settings_digest(
+    ctx: PoiseContext<'_>,
+    #[description = "Text channel to post the weekly digest in. Leave empty to disable"]
+    #[channel_types("Text")]
+    channel: Option<GuildChannel>,
+) -> PoiseResult {
+    let guild_id = ctx.guild_id().ok_or("No guild id found")?;
+    let channel_id = channel.map(|channel| channel.id.get());
+    log::info!("Setting weekly digest. Guild Id: {guild_id}, Channel Id: {channel_id:?}");
+
+    let table = ctx.data().guild_settings_table();
+    let mut settings = table.get_guild_settings(guild_id.get())?;
+    settings.digest_channel_id = channel_id;
+    table.update_guild_settings(&settings)?;
+
+    let lang = i18n::language(ctx);
+    let content = match channel_id {
+        Some(channel_id) => tr(
+            lang,
+            "settings.digest_set",
+            &[("channel", &format!("<#{channel_id}>"))],
+        ),
+        None => tr(lang, "settings.digest_disabled", &[]),
+    };
+
+    poise_check_msg(
+        ctx.send(CreateReply::default().ephemeral(true).content(content))
+            .await,
+    );
+    Ok(())
+}
+
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "priority",
+    check = "permissions::can_manage_settings"
+)]
+pub async fn settings_priority(
+    ctx: PoiseContext<'_>,
+    #[description = "What sounds played with priority do to sounds playing"] mode: PriorityMode,
+) -> PoiseResult {
+    let guild_id = ctx.guild_id().ok_or("No guild id found")?;
+    log::info!("Setting priority mode. Guild Id: {guild_id}, Mode: {mode:?}");
+
+    let table = ctx.data().guild_settings_table();
+    let mut settings = table.get_guild_settings(guild_id.get())?;
+    settings.priority_mode = Some(mode.code().into());
+    table.update_guild_settings(&settings)?;
+
+    let key = match mode {
+        PriorityMode::Stop => "settings.priority_stop",
+        PriorityMode::Pause => "settings.priority_pause",
+    };
+    poise_check_msg(
+        ctx.send(
+            CreateReply::default()
+                .ephemeral(true)
+                .content(tr(i18n::language(ctx), key, &[])),
+        )
+        .await,
+    );
+    Ok(())
+}
+
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "autocomplete",
+    check = "permissions::can_manage_settings"
+)]
+pub async fn settings_autocomplete(
+    ctx: PoiseContext<'_>,
+    #[description = "Sounds suggested as you type a sound name. Leave empty for the default"]
+    #[min = 1]
+    #[max = 25]
+    limit: Option<u32>,
+    #[rename = "min-chars"]
+    #[description = "Characters typed before sounds are searched. Leave empty for the default"]
+    #[min = 1]
+    #[max = 10]
+    min_chars: Option<u32>,
+) -> PoiseResult {
+    let guild_id = ctx.guild_id().ok_or("No guild id found")?;
+    log::info!(
+        "Setting autocomplete. Guild Id: {guild_id}, Limit: {limit:?}, Min chars: {min_chars:?}"
+    );
+
+    let table = ctx.data().guild_settings_table();
+    let mut settings = table.get_guild_settings(guild_id.get())?;
+    settings.autocomplete_limit = limit;
+    settings.autocomplete_min_query_len = min_chars;
+    table.update_guild_settings(&settings)?;
+    ctx.data().autocomplete_cache.invalidate_guild(guild_id);
+
+    let (limit, min_chars) = helpers::autocomplete_settings(ctx.data(), Some(guild_id));
+    let content = tr(
+        i18n::language(ctx),
+        "settings.autocomplete_set",
+        &[("limit", &limit), ("min_chars", &min_chars)],
+    );
+    poise_check_msg(
+        ctx.send(CreateReply::default().ephemeral(true).content(content))
+            .await,
+    );
+    Ok(())
+}
+
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "silent",
+    check = "permissions::can_manage_settings"
+)]
+pub async fn settings_silent(
+    ctx: PoiseContext<'_>,
+    #[description = "Only play sounds, without posting now playing messages or /play replies"]
+    enabled: bool,
+) -> PoiseResult {
+    let guild_id = ctx.guild_id().ok_or("No guild id found")?;
+    log::info!("Setting silent play. Guild Id: {guild_id}, Enabled: {enabled}");
+
+    let table = ctx.data().guild_settings_table();
+    let mut settings = table.get_guild_settings(guild_id.get())?;
+    settings.silent_play = enabled;
+    table.update_guild_settings(&settings)?;
+
+    let key = match enabled {
+        true => "settings.silent_on",
+        false => "settings.silent_off",
+    };
+    poise_check_msg(
+        ctx.send(
+            CreateReply::default()
+                .ephemeral(true)
+                .content(tr(i18n::language(ctx), key, &[])),
+        )
+        .await,
+    );
+    Ok(())
+}
+
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "soundboard-order",
+    check = "permissions::can_manage_settings"
+)]
+pub async fn settings_soundboard_order(
+    ctx: PoiseContext<'_>,
+    #[description = "Order of soundboard sounds"] order: SoundboardOrder,
+) -> PoiseResult {
+    let guild_id = ctx.guild_id().ok_or("No guild id found")?;
+    log::info!("Setting soundboard order. Guild Id: {guild_id}, Order: {order:?}");
+
+    let table = ctx.data().guild_settings_table();
+    let mut settings = table.get_guild_settings(guild_id.get())?;
+    settings.soundboard_order = Some(order.code().into());
+    table.update_guild_settings(&settings)?;
+
+    let key = match order {
+        SoundboardOrder::Added => "settings.soundboard_order_added",
+        SoundboardOrder::Trending => "settings.soundboard_order_trending",
+    };
+    poise_check_msg(
+        ctx.send(
+            CreateReply::default()
+                .ephemeral(true)
+                .content(tr(i18n::language(ctx), key, &[])),
+        )
+        .await,
+    );
+    refresh_published_soundboard(ctx).await;
+    Ok(())
+}
+
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "play-history",
+    check = "permissions::can_manage_settings"
+)]
+pub async fn settings_play_history(
+    ctx: PoiseContext<'_>,
+    #[description = "Let members see each other's play history with /history"] public: bool,
+) -> PoiseResult {
+    let guild_id = ctx.guild_id().ok_or("No guild id found")?;
+    log::info!("Setting play history visibility. Guild Id: {guild_id}, Public: {public}");
+
+    let table = ctx.data().guild_settings_table();
+    let mut settings = table.get_guild_settings(guild_id.get())?;
+    settings.play_history_public = public;
+    table.update_guild_settings(&settings)?;
+
+    let key = match public {
+        true => "settings.play_history_public",
+        false => "settings.play_history_private",
+    };
+    poise_check_msg(
+        ctx.send(
+            CreateReply::default()
+                .ephemeral(true)
+                .content(tr(i18n::language(ctx), key, &[])),
+        )
+        .await,
+    );
+    Ok(())
+}
+
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "loudness",
+    check = "permissions::can_manage_settings"
+)]
+pub async fn settings_loudness(
+    ctx: PoiseContext<'_>,
+    #[description = "What happens to added sounds that are too loud"] action: LoudnessAction,
+) -> PoiseResult {
+    let guild_id = ctx.guild_id().ok_or("No guild id found")?;
+    log::info!("Setting loudness action. Guild Id: {guild_id}, Action: {action:?}");
+
+    let table = ctx.data().guild_settings_table();
+    let mut settings = table.get_guild_settings(guild_id.get())?;
+    settings.loudness_action = Some(action.code().into());
+    table.update_guild_settings(&settings)?;
+
+    let key = match action {
+        LoudnessAction::Allow => "settings.loudness_allow",
+        LoudnessAction::Attenuate => "settings.loudness_attenuate",
+        LoudnessAction::Review => "settings.loudness_review",
+        LoudnessAction::Reject => "settings.loudness_reject",
+    };
+    poise_check_msg(
+        ctx.send(
+            CreateReply::default()
+                .ephemeral(true)
+                .content(tr(i18n::language(ctx), key, &[])),
+        )
+        .await,
+    );
+    Ok(())
+}
+
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "max-length",
+    check = "permissions::can_manage_settings"
+)]
+pub async fn settings_max_length(
+    ctx: PoiseContext<'_>,
+    #[description = "Max length of added sounds in seconds. Leave empty for the bot's limit"]
+    #[min = 0.5]
+    seconds: Option<f64>,
+) -> PoiseResult {
+    let guild_id = ctx.guild_id().ok_or("No guild id found")?;
+    log::info!("Setting max sound length. Guild Id: {guild_id}, Seconds: {seconds:?}");
+
+    let lang = i18n::language(ctx);
+    let ceiling_ms = ctx.data().config().max_audio_file_duration.as_millis() as u64;
+    let max_ms = seconds.map(|seconds| (seconds * 1000.0).round() as u64);
+    if max_ms.is_some_and(|max_ms| max_ms > ceiling_ms) {
+        reply_ephemeral(
+            ctx,
+            tr(
+                lang,
+                "settings.max_length_too_long",
+                &[("max", &helpers::format_duration_ms(ceiling_ms))],
+            ),
+        )
+        .await;
+        return Ok(());
+    }
+
+    let table = ctx.data().guild_settings_table();
+    let mut settings = table.get_guild_settings(guild_id.get())?;
+    settings.max_duration_ms = max_ms;
+    table.update_guild_settings(&settings)?;
+
+    let max = helpers::format_duration_ms(max_ms.unwrap_or(ceiling_ms));
+    let content = match max_ms {
+        Some(_) => tr(lang, "settings.max_length_set", &[("max", &max)]),
+        None => tr(lang, "settings.max_length_reset", &[("max", &max)]),
+    };
+    reply_ephemeral(ctx, content).await;
+    Ok(())
+}
+
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "export",
+    check = "permissions::can_manage_settings"
+)]
+pub async fn settings_export(ctx: PoiseContext<'_>) -> PoiseResult {
+    let guild_id = ctx.guild_id().ok_or("No guild id found")?;
+    log::info!("Exporting settings. Guild Id: {guild_id}");
+
+    let json = guild_export::export_guild(ctx.data(), guild_id.get())?.to_json()?;
+    let attachment = serenity::all::CreateAttachment::bytes(
+        json.into_bytes(),
+        format!("soundboard-settings-{guild_id}.json"),
+    );
+
+    poise_check_msg(
+        ctx.send(
+            CreateReply::default()
+                .ephemeral(true)
+                .content(tr(i18n::language(ctx), "settings.exported", &[]))
+                .attachment(attachment),
+        )
+        .await,
+    );
+    Ok(())
+}
+
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "import",
+    check = "permissions::can_manage_settings"
+)]
+pub async fn settings_import(
+    ctx: PoiseContext<'_>,
+    #[description = "Settings file made by /settings export"] file: serenity::all::Attachment,
+) -> PoiseResult {
+    let guild_id = ctx.guild_id().ok_or("No guild id found")?;
+    log::info!(
+        "Importing settings. Guild Id: {guild_id}, File: {}",
+        file.filename
+    );
+
+    let lang = i18n::language(ctx);
+    let export = match file.size as u64 <= vars::GUILD_EXPORT_MAX_SIZE {
+        true => String::from_utf8(file.download().await?)
+            .map_err(PoiseError::from)
+            .and_then(|json| GuildExport::from_json(&json)),
+        false => Err("Settings file is too big".into()),
+    };
+    let export = match export {
+        Ok(export) => export,
+        Err(err) => {
+            let content = tr(lang, "settings.import_invalid", &[("error", &err)]);
+            poise_check_msg(
+                ctx.send(CreateReply::default().ephemeral(true).content(content))
+                    .await,
+            );
+            return Ok(());
+        }
+    };
+
+    let confirmation = helpers::confirm(ctx, tr(lang, "settings.confirm_import", &[])).await?;
+    if confirmation != Confirmation::Confirmed {
+        log::info!("Settings import not confirmed - {confirmation:?}");
+        return Ok(());
+    }
+
+    let summary = guild_export::import_guild(ctx.data(), guild_id.get(), &export)?;
+    poise_check_msg(
+        ctx.send(
+            CreateReply::default()
+                .ephemeral(true)
+                .content(guild_export::summary_message(lang, &summary)),
+        )
+        .await,
+    );
+    Ok(())
+}
+
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "prefix",
+    check = "permissions::can_manage_settings"
+)]
+pub async fn settings_prefix(
+    ctx: PoiseContext<'_>,
+    #[description = "Prefix for prefix commands (e.g. !play). Leave empty to use the default"]
+    #[max_length = 8]
+    prefix: Option<String>,
+) -> PoiseResult {
+    let guild_id = ctx.guild_id().ok_or("No guild id found")?;
+    let lang = i18n::language(ctx);
+    log::info!("Setting command prefix. Guild Id: {guild_id}, Prefix: {prefix:?}");
+
+    let prefix = prefix.map(|prefix| prefix.trim().to_string());
+    if let Some(ref prefix) = prefix {
+        if prefix.is_empty() || prefix.contains(char::is_whitespace) {
+            poise_check_msg(
+                ctx.send(CreateReply::default().ephemeral(true).content(tr(
+                    lang,
+                    "settings.prefix_invalid",
+                    &[],
+                )))
+                .await,
+            );
+            return Ok(());
+        }
+    }
+
+    let table = ctx.data().guild_settings_table();
+    let mut settings = table.get_guild_settings(guild_id.get())?;
+    settings.prefix = prefix;
+    table.update_guild_settings(&settings)?;
+
+    let prefix = ctx.data().command_prefix(Some(guild_id));
+    poise_check_msg(
+        ctx.send(CreateReply::default().ephemeral(true).content(tr(
+            lang,
+            "settings.prefix_set",
+            &[("prefix", &prefix)],
+        )))
+        .await,
+    );
+    Ok(())
+}
+
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "sotd",
+    check = "permissions::can_manage_settings"
+)]
+pub async fn settings_sotd(
+    ctx: PoiseContext<'_>,
+    #[description = "Text channel to post the sound of the day in. Leave empty to disable"]
+    #[channel_types("Text")]
+    channel: Option<GuildChannel>,
+    #[description = "UTC time of day to post the sound of the day at, e.g. 18:30. Defaults to midnight"]
+    time: Option<String>,
+    #[description = "Voice channel to also play the sound of the day in"]
+    #[channel_types("Voice")]
+    #[rename = "voice-channel"]
+    voice_channel: Option<GuildChannel>,
+    #[description = "How the sound of the day is picked. Defaults to random"] mode: Option<
+        SotdMode,
+    >,
+) -> PoiseResult {
+    let guild_id = ctx.guild_id().ok_or("No guild id found")?;
+    let lang = i18n::language(ctx);
+    log::info!(
+        "Setting sound of the day. Guild Id: {guild_id}, Channel: {:?}, Time: {time:?}, Voice Channel: {:?}, Mode: {mode:?}",
+        channel.as_ref().map(|channel| channel.id),
+        voice_channel.as_ref().map(|channel| channel.id),
+    );
+
+    let time = match time.as_deref().map(str::trim) {
+        Some(time) => match chrono::NaiveTime::parse_from_str(time, "%H:%M") {
+            Ok(time) => Some(time),
+            Err(_) => {
+                poise_check_msg(
+                    ctx.send(CreateReply::default().ephemeral(true).content(tr(
+                        lang,
+                        "settings.sotd_time_invalid",
+                        &[],
+                    )))
+                    .await,
+                );
+                return Ok(());
+            }
+        },
+        None => None,
+    };
+
+    let table = ctx.data().guild_settings_table();
+    let mut settings = table.get_guild_settings(guild_id.get())?;
+    settings.sotd_channel_id = channel.as_ref().map(|channel| channel.id.get());
+    settings.sotd_voice_channel_id = voice_channel.as_ref().map(|channel| channel.id.get());
+    settings.sotd_time = time;
+    settings.sotd_mode = mode.map(|mode| mode.code().into());
+    table.update_guild_settings(&settings)?;
+
+    let time = time
+        .unwrap_or(chrono::NaiveTime::MIN)
+        .format("%H:%M")
+        .to_string();
+    let content = match (channel, voice_channel) {
+        (None, _) => tr(lang, "settings.sotd_disabled", &[]),
+        (Some(channel), None) => tr(
+            lang,
+            "settings.sotd_set",
+            &[("channel", &format!("<#{}>", channel.id)), ("time", &time)],
+        ),
+        (Some(channel), Some(voice_channel)) => tr(
+            lang,
+            "settings.sotd_voice_set",
+            &[
+                ("channel", &format!("<#{}>", channel.id)),
+                ("voice_channel", &format!("<#{}>", voice_channel.id)),
+                ("time", &time),
+            ],
+        ),
+    };
+
+    poise_check_msg(
+        ctx.send(CreateReply::default().ephemeral(true).content(content))
+            .await,
+    );
+    Ok(())
+}
+
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "permissions",
+    required_permissions = "ADMINISTRATOR",
+    subcommands("grant_permission", "revoke_permission", "list_permissions")
+)]
+pub async fn settings_permissions(_ctx: PoiseContext<'_>) -> PoiseResult {
+    log::warn!(
+        "/settings permissions command shouldn't be invoked directly. It should just house sub commands"
+    );
+    Ok(())
+}
+
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "grant",
+    required_permissions = "ADMINISTRATOR"
+)]
+pub async fn grant_permission(
+    ctx: PoiseContext<'_>,
+    #[description = "Permission to grant"] permission: Permission,
+    #[description = "Role to grant permission to"] role: Role,
+) -> PoiseResult {
+    let guild_id = ctx.guild_id().ok_or("No guild id found")?;
+
+    ctx.data()
+        .permissions_table()
+        .grant(guild_id.get(), permission.key(), role.id.get())?;
+
+    poise_check_msg(
+        ctx.send(CreateReply::default().ephemeral(true).content(tr(
+            i18n::language(ctx),
+            "permissions.granted",
+            &[
+                ("permission", &permission.name()),
+                ("role", &format!("<@&{}>", role.id)),
+            ],
+        )))
+        .await,
+    );
+    Ok(())
+}
+
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "revoke",
+    required_permissions = "ADMINISTRATOR"
+)]
+pub async fn revoke_permission(
+    ctx: PoiseContext<'_>,
+    #[description = "Permission to revoke"] permission: Permission,
+    #[description = "Role to revoke permission from"] role: Role,
+) -> PoiseResult {
+    let guild_id = ctx.guild_id().ok_or("No guild id found")?;
+
+    let table = ctx.data().permissions_table();
+    table.revoke(guild_id.get(), permission.key(), role.id.get())?;
+
+    let no_roles_left = table.role_ids(guild_id.get(), permission.key())?.is_empty();
+    let key = match no_roles_left && !permission.is_admin_only_by_default() {
+        true => "permissions.revoked_last",
+        false => "permissions.revoked",
+    };
+    let content = tr(
+        i18n::language(ctx),
+        key,
+        &[
+            ("permission", &permission.name()),
+            ("role", &format!("<@&{}>", role.id)),
+        ],
+    );
+
+    poise_check_msg(
+        ctx.send(CreateReply::default().ephemeral(true).content(content))
+            .await,
+    );
+    Ok(())
+}
+
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "list",
+    required_permissions = "ADMINISTRATOR"
+)]
+pub async fn list_permissions(ctx: PoiseContext<'_>) -> PoiseResult {
+    let guild_id = ctx.guild_id().ok_or("No guild id found")?;
+    let table = ctx.data().permissions_table();
+    let lang = i18n::language(ctx);
+
+    let mut lines = vec![tr(lang, "permissions.title", &[])];
+    for permission in Permission::ALL {
+        let roles: Vec<_> = table
+            .role_ids(guild_id.get(), permission.key())?
+            .iter()
+            .map(|role_id| format!("<@&{role_id}>"))
+            .collect();
+
+        let roles = match roles.is_empty() {
+            true if permission.is_admin_only_by_default() => tr(lang, "permissions.admins", &[]),
+            true => tr(lang, "permissions.everyone", &[]),
+            false => roles.join(", "),
+        };
+        lines.push(format!("- `{}` - {roles}", permission.name()));
+    }
+
+    poise_check_msg(
+        ctx.send(
+            CreateReply::default()
+                .ephemeral(true)
+                .content(lines.join("\n")),
+        )
+        .await,
+    );
+    Ok(())
+}
+
+/// Register slash commands in every guild the bot is in
+#[poise::command(
+    slash_command,
+    prefix_command,
+    guild_only,
+    owners_only,
+    rename = "resync-commands"
+)]
+pub async fn admin_resync_commands(ctx: PoiseContext<'_>) -> PoiseResult {
+    log::info!("Resyncing guild slash commands...");
+
+    let guild_ids = ctx.cache().guilds();
+    let commands = &ctx.framework().options().commands;
+    let mut synced = 0;
+
+    for guild_id in &guild_ids {
+        if register_guild_commands(ctx.http(), commands, *guild_id)
+            .await
+            .is_ok()
+        {
+            synced += 1;
+        }
+    }
+
+    poise_check_msg(
+        ctx.send(CreateReply::default().ephemeral(true).content(tr(
+            i18n::language(ctx),
+            "admin.resynced",
+            &[("synced", &synced), ("guilds", &guild_ids.len())],
+        )))
+        .await,
+    );
+    Ok(())
+}
+
+/// Register slash commands in guild, on join and by `/admin resync-commands`
+pub async fn register_guild_commands(
+    http: &serenity::all::Http,
+    commands: &[poise::Command<UserData, PoiseError>],
+    guild_id: serenity::all::GuildId,
+) -> PoiseResult {
+    poise::builtins::register_in_guild(http, commands, guild_id)
+        .await
+        .log_err_msg(format!(
+            "Failed to register slash commands in guild {guild_id}"
+        ))?;
+
+    log::info!("Registered slash commands in guild {guild_id}");
+    Ok(())
+}
+
+#[poise::command(
+    slash_command,
+    prefix_command,
+    owners_only,
+    default_member_permissions = "ADMINISTRATOR",
+    subcommands(
+        "owner_guilds",
+        "owner_leave_guild",
+        "owner_sql",
+        "owner_reload_config",
+        "owner_backup",
+        "owner_transcode",
+        "owner_purge_user",
+        "owner_search",
+        "owner_purge_by_hash"
+    )
+)]
+pub async fn owner(_ctx: PoiseContext<'_>) -> PoiseResult {
+    log::warn!("/owner command shouldn't be invoked directly. It should just house sub commands");
+    Ok(())
+}
+
+#[poise::command(slash_command, prefix_command, owners_only, rename = "guilds")]
+pub async fn owner_guilds(ctx: PoiseContext<'_>) -> PoiseResult {
+    let guild_ids = ctx.cache().guilds();
+    let lines: Vec<_> = guild_ids
+        .iter()
+        .map(|guild_id| match ctx.cache().guild(*guild_id) {
+            Some(guild) => format!(
+                "- {} (`{guild_id}`) - {} members",
+                guild.name, guild.member_count
+            ),
+            None => format!("- `{guild_id}`"),
+        })
+        .collect();
+
+    let content = format!(
+        "{}\n{}",
+        tr(
+            i18n::language(ctx),
+            "owner.guilds_title",
+            &[("count", &guild_ids.len())]
+        ),
+        lines.join("\n")
+    );
+
+    poise_check_msg(
+        ctx.send(
+            CreateReply::default()
+                .ephemeral(true)
+                .content(helpers::truncate_message(content)),
+        )
+        .await,
+    );
+    Ok(())
+}
+
+#[poise::command(slash_command, prefix_command, owners_only, rename = "leave-guild")]
+pub async fn owner_leave_guild(
+    ctx: PoiseContext<'_>,
+    #[description = "Id of server for bot to leave"]
+    #[rename = "guild-id"]
+    guild_id: String,
+) -> PoiseResult {
+    let lang = i18n::language(ctx);
+    let Some(guild_id) = guild_id
+        .trim()
+        .parse()
+        .ok()
+        .filter(|id| *id != 0)
+        .map(serenity::all::GuildId::new)
+    else {
+        poise_check_msg(
+            ctx.send(CreateReply::default().ephemeral(true).content(tr(
+                lang,
+                "owner.invalid_guild_id",
+                &[("guild_id", &guild_id)],
+            )))
+            .await,
+        );
+        return Ok(());
+    };
+
+    let guild = ctx
+        .cache()
+        .guild(guild_id)
+        .map(|guild| guild.name.clone())
+        .unwrap_or_else(|| guild_id.to_string());
+
+    let prompt = tr(lang, "owner.confirm_leave", &[("guild", &guild)]);
+    let confirmation = helpers::confirm(ctx, prompt).await?;
+    if confirmation != Confirmation::Confirmed {
+        log::info!("Leaving guild not confirmed - {confirmation:?}");
+        return Ok(());
+    }
+
+    log::info!("Owner leaving guild. Guild Id: {guild_id}");
+    guild_id.leave(ctx.http()).await?;
+
+    poise_check_msg(
+        ctx.send(CreateReply::default().ephemeral(true).content(tr(
+            lang,
+            "owner.left_guild",
+            &[("guild", &guild)],
+        )))
+        .await,
+    );
+    Ok(())
+}
+
+#[poise::command(slash_command, prefix_command, owners_only, rename = "sql")]
+pub async fn owner_sql(
+    ctx: PoiseContext<'_>,
+    #[description = "Read-only SQL query, e.g. SELECT name, play_count FROM audio"]
+    #[rest]
+    query: String,
+) -> PoiseResult {
+    log::info!("Owner running sql query - {query}");
+
+    let content = match owner::read_only_query(
+        &ctx.data().db_connection(),
+        &query,
+        vars::OWNER_SQL_ROW_LIMIT,
+    ) {
+        Ok(result) => helpers::truncate_message(result.to_markdown()),
+        Err(err) => tr(
+            i18n::language(ctx),
+            "owner.sql_error",
+            &[("error", &err.to_string())],
+        ),
+    };
+
+    poise_check_msg(
+        ctx.send(CreateReply::default().ephemeral(true).content(content))
+            .await,
+    );
+    Ok(())
+}
+
+#[poise::command(slash_command, prefix_command, owners_only, rename = "transcode")]
+pub async fn owner_transcode(ctx: PoiseContext<'_>) -> PoiseResult {
+    log::info!("Owner transcoding most played sounds...");
+    enqueue_job(ctx, Job::Transcode).await
+}
+
+#[poise::command(slash_command, prefix_command, owners_only, rename = "backup")]
+pub async fn owner_backup(ctx: PoiseContext<'_>) -> PoiseResult {
+    log::info!("Owner backing up database...");
+    enqueue_job(ctx, Job::Backup).await
+}
+
+#[poise::command(slash_command, prefix_command, owners_only, rename = "purge-user")]
+pub async fn owner_purge_user(
+    ctx: PoiseContext<'_>,
+    #[description = "Id of user to forget"]
+    #[rename = "user-id"]
+    user_id: String,
+) -> PoiseResult {
+    let lang = i18n::language(ctx);
+    let Some(user_id) = user_id.trim().parse::<u64>().ok().filter(|id| *id != 0) else {
+        poise_check_msg(
+            ctx.send(CreateReply::default().ephemeral(true).content(tr(
+                lang,
+                "owner.invalid_user_id",
+                &[("user_id", &user_id)],
+            )))
+            .await,
+        );
+        return Ok(());
+    };
+
+    let prompt = tr(lang, "owner.confirm_purge_user", &[("user_id", &user_id)]);
+    let confirmation = helpers::confirm(ctx, prompt).await?;
+    if confirmation != Confirmation::Confirmed {
+        log::info!("Purging user not confirmed - {confirmation:?}");
+        return Ok(());
+    }
+
+    log::info!("Owner purging user. User Id: {user_id}");
+    let summary = privacy::purge_user(
+        &ctx.data().db_connection(),
+        &ctx.data().config().trash_dir,
+        user_id,
+    )?;
+    ctx.data().autocomplete_cache.invalidate();
+
+    poise_check_msg(
+        ctx.send(
+            CreateReply::default()
+                .ephemeral(true)
+                .content(privacy::summary_message(lang, &summary)),
+        )
+        .await,
+    );
+    Ok(())
+}
+
+#[poise::command(slash_command, prefix_command, owners_only, rename = "search")]
+pub async fn owner_search(
+    ctx: PoiseContext<'_>,
+    #[description = "Sound name, uploader name or id, server id or audio file hash"] query: String,
+) -> PoiseResult {
+    let lang = i18n::language(ctx);
+    let query = query.trim();
+    let hash_prefix = owner::hash_prefix(query);
+    let rows = ctx.data().audio_table().search_all_audio_rows(
+        query,
+        hash_prefix.as_deref(),
+        vars::OWNER_SEARCH_LIMIT,
+    )?;
+
+    let content = match rows.is_empty() {
+        true => tr(lang, "owner.search_none", &[("query", &query)]),
+        false => {
+            let lines: Vec<_> = rows
+                .iter()
+                .map(|row| {
+                    let guild_name = row
+                        .guild_id
+                        .and_then(|guild_id| {
+                            ctx.cache().guild(serenity::all::GuildId::new(guild_id))
+                        })
+                        .map(|guild| guild.name.clone());
+                    owner::search_result_line(row, guild_name.as_deref())
+                })
+                .collect();
+            format!(
+                "{}\n{}",
+                tr(lang, "owner.search_title", &[("count", &rows.len())]),
+                lines.join("\n")
+            )
+        }
+    };
+
+    poise_check_msg(
+        ctx.send(
+            CreateReply::default()
+                .ephemeral(true)
+                .content(helpers::truncate_message(content)),
+        )
+        .await,
+    );
+    Ok(())
+}
+
+#[poise::command(slash_command, prefix_command, owners_only, rename = "purge-by-hash")]
+pub async fn owner_purge_by_hash(
+    ctx: PoiseContext<'_>,
+    #[description = "SHA-256 of the audio file, as listed by /owner search"] hash: String,
+) -> PoiseResult {
+    let lang = i18n::language(ctx);
+    let Some(content_hash) = owner::parse_content_hash(&hash) else {
+        reply_ephemeral(
+            ctx,
+            tr(lang, "owner.invalid_hash", &[("hash", &hash.trim())]),
+        )
+        .await;
+        return Ok(());
+    };
+
+    let count = ctx
+        .data()
+        .audio_table()
+        .audio_rows_by_content_hash(&content_hash)?
+        .len();
+    let prompt = tr(
+        lang,
+        "owner.confirm_purge_hash",
+        &[("count", &count), ("hash", &content_hash)],
+    );
+    let confirmation = helpers::confirm(ctx, prompt).await?;
+    if confirmation != Confirmation::Confirmed {
+        log::info!("Purging by hash not confirmed - {confirmation:?}");
+        return Ok(());
+    }
+
+    log::warn!("Owner purging audio by hash. Hash: {content_hash}");
+    let summary = owner::purge_by_hash(ctx.data(), &content_hash)?;
+
+    reply_ephemeral(
+        ctx,
+        tr(
+            lang,
+            "owner.purged_hash",
+            &[("sounds", &summary.sounds), ("trashed", &summary.trashed)],
+        ),
+    )
+    .await;
+    Ok(())
+}
+
+#[poise::command(slash_command, prefix_command, owners_only, rename = "reload-config")]
+pub async fn owner_reload_config(ctx: PoiseContext<'_>) -> PoiseResult {
+    log::info!("Owner reloading config...");
+
+    let lang = i18n::language(ctx);
+    let content = match Config::reload() {
+        Ok(config) => {
+            audio::set_storage_roots([config.audio_dir.clone()]);
+            ctx.data().reload_config(config);
+            ctx.data().autocomplete_cache.invalidate();
+            tr(lang, "owner.config_reloaded", &[])
+        }
+        Err(err) => {
+            log::error!("Failed to reload config - {err}");
+            tr(lang, "owner.config_error", &[("error", &err)])
+        }
+    };
+
+    poise_check_msg(
+        ctx.send(CreateReply::default().ephemeral(true).content(content))
+            .await,
+    );
+    Ok(())
+}
+
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "bulk",
+    subcommands("bulk_delete", "bulk_retag")
+)]
+pub async fn admin_bulk(_ctx: PoiseContext<'_>) -> PoiseResult {
+    log::warn!(
+        "/admin bulk command shouldn't be invoked directly. It should just house sub commands"
+    );
+    Ok(())
+}
+
+/// Build bulk operation filter from command options. Err is a reply for the user
+fn bulk_filter(
+    lang: Language,
+    tag: Option<String>,
+    author: Option<serenity::all::User>,
+    older_than: Option<String>,
+) -> Result<db::AudioTableFilter, String> {
+    let created_before = match older_than {
+        Some(older_than) => {
+            let age = helpers::parse_age(&older_than)
+                .ok_or_else(|| tr(lang, "bulk.invalid_age", &[("age", &older_than)]))?;
+            Some(chrono::Utc::now() - age)
+        }
+        None => None,
+    };
+
+    let filter = db::AudioTableFilter {
+        tag: tag.map(Tags::normalize_tag),
+        author_id: author.map(|author| author.id.get()),
+        created_before,
+    };
+
+    match filter.is_empty() {
+        true => Err(tr(lang, "bulk.no_filter", &[])),
+        false => Ok(filter),
+    }
+}
+
+/// Dry run preview of sounds a bulk operation affects
+fn bulk_preview(lang: Language, rows: &[db::AudioTableRow]) -> String {
+    let mut names: Vec<String> = rows
+        .iter()
+        .take(vars::BULK_PREVIEW_LIMIT)
+        .map(|row| format!("`{}`", row.name))
+        .collect();
+
+    if rows.len() > vars::BULK_PREVIEW_LIMIT {
+        let count = rows.len() - vars::BULK_PREVIEW_LIMIT;
+        names.push(tr(lang, "bulk.and_more", &[("count", &count)]));
+    }
+
+    names.join(", ")
+}
+
+/// Load rows matching bulk filter and ask for confirmation with a preview of them.
+/// The prompt message gets `count` and `preview` args besides `prompt_args`.
+/// None when there's nothing to do or the operation wasn't confirmed
+async fn confirm_bulk(
+    ctx: PoiseContext<'_>,
+    filter: Result<db::AudioTableFilter, String>,
+    prompt_key: &str,
+    prompt_args: &[(&str, &(dyn std::fmt::Display + Sync))],
+) -> Result<Option<db::AudioTableFilter>, PoiseError> {
+    let lang = i18n::language(ctx);
+    let filter = match filter {
+        Ok(filter) => filter,
+        Err(content) => {
+            poise_check_msg(
+                ctx.send(CreateReply::default().ephemeral(true).content(content))
+                    .await,
+            );
+            return Ok(None);
+        }
+    };
+
+    let rows = ctx.data().audio_table().filtered_audio_rows(&filter)?;
+    if rows.is_empty() {
+        poise_check_msg(
+            ctx.send(CreateReply::default().ephemeral(true).content(tr(
+                lang,
+                "bulk.no_matches",
+                &[],
+            )))
+            .await,
+        );
+        return Ok(None);
+    }
+
+    let count = rows.len();
+    let preview = bulk_preview(lang, &rows);
+    let mut args: Vec<(&str, &(dyn std::fmt::Display + Sync))> =
+        vec![("count", &count), ("preview", &preview)];
+    args.extend_from_slice(prompt_args);
+
+    let prompt = tr(lang, prompt_key, &args);
+    let confirmation = helpers::confirm(ctx, prompt).await?;
+    if confirmation != Confirmation::Confirmed {
+        log::info!("Bulk operation not confirmed - {confirmation:?}");
+        return Ok(None);
+    }
+
+    Ok(Some(filter))
+}
+
+#[poise::command(slash_command, guild_only, rename = "delete")]
+pub async fn bulk_delete(
+    ctx: PoiseContext<'_>,
+    #[description = "Delete sounds with tag"]
+    #[autocomplete = "helpers::autocomplete_tag"]
+    tag: Option<String>,
+    #[description = "Delete sounds uploaded by user"] author: Option<serenity::all::User>,
+    #[rename = "older-than"]
+    #[description = "Delete sounds older than age, e.g. 30d, 2w, 6mo or 1y"]
+    older_than: Option<String>,
+) -> PoiseResult {
+    let lang = i18n::language(ctx);
+    let filter = bulk_filter(lang, tag, author, older_than);
+    let Some(filter) = confirm_bulk(ctx, filter, "bulk.confirm_delete", &[]).await? else {
+        return Ok(());
+    };
+
+    let deleted = trash::trash_filtered_audio_rows(
+        &ctx.data().audio_table(),
+        &ctx.data().config().trash_dir,
+        &filter,
+        chrono::Utc::now(),
+    )?;
+    ctx.data().autocomplete_cache.invalidate();
+
+    poise_check_msg(
+        ctx.send(CreateReply::default().ephemeral(true).content(tr(
+            lang,
+            "bulk.deleted",
+            &[("count", &deleted)],
+        )))
+        .await,
+    );
+
+    refresh_published_soundboard(ctx).await;
+    Ok(())
+}
+
+#[poise::command(slash_command, guild_only, rename = "retag")]
+pub async fn bulk_retag(
+    ctx: PoiseContext<'_>,
+    #[description = "New space separated tags, replacing existing tags"]
+    #[rename = "new-tags"]
+    #[max_length = 1024]
+    new_tags: String,
+    #[description = "Retag sounds with tag"]
+    #[autocomplete = "helpers::autocomplete_tag"]
+    tag: Option<String>,
+    #[description = "Retag sounds uploaded by user"] author: Option<serenity::all::User>,
+    #[rename = "older-than"]
+    #[description = "Retag sounds older than age, e.g. 30d, 2w, 6mo or 1y"]
+    older_than: Option<String>,
+) -> PoiseResult {
+    let lang = i18n::language(ctx);
+    let filter = bulk_filter(lang, tag, author, older_than);
+    let new_tags = Tags::from(new_tags.as_str());
+    let tags = new_tags.to_string();
+    let Some(filter) = confirm_bulk(ctx, filter, "bulk.confirm_retag", &[("tags", &tags)]).await?
+    else {
+        return Ok(());
+    };
+
+    let updated = ctx
+        .data()
+        .audio_table()
+        .retag_filtered_audio_rows(&filter, &new_tags)?;
+    ctx.data().autocomplete_cache.invalidate();
+
+    poise_check_msg(
+        ctx.send(CreateReply::default().ephemeral(true).content(tr(
+            lang,
+            "bulk.retagged",
+            &[("count", &updated)],
+        )))
+        .await,
+    );
+
+    refresh_published_soundboard(ctx).await;
+    Ok(())
+}
+
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "blocklist",
+    check = "permissions::can_manage_settings",
+    subcommands(
+        "add_blocklist_pattern",
+        "remove_blocklist_pattern",
+        "list_blocklist_patterns"
+    )
+)]
+pub async fn settings_blocklist(_ctx: PoiseContext<'_>) -> PoiseResult {
+    log::warn!(
+        "/settings blocklist command shouldn't be invoked directly. It should just house sub commands"
+    );
+    Ok(())
+}
+
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "add",
+    check = "permissions::can_manage_settings"
+)]
+pub async fn add_blocklist_pattern(
+    ctx: PoiseContext<'_>,
+    #[description = "Word (or regex pattern) to block in sound names and text-to-speech"]
+    #[max_length = 200]
+    pattern: String,
+    #[description = "Treat pattern as a regex instead of a whole word"] regex: Option<bool>,
+) -> PoiseResult {
+    let guild_id = ctx.guild_id().ok_or("No guild id found")?;
+    let row = BlocklistTableRow {
+        pattern: pattern.trim().into(),
+        is_regex: regex.unwrap_or(false),
+    };
+
+    let lang = i18n::language(ctx);
+    let content = match blocklist::compile_pattern(&row) {
+        Ok(_) => {
+            ctx.data()
+                .blocklist_table()
+                .add_pattern(guild_id.get(), &row)?;
+            tr(lang, "blocklist.added", &[("pattern", &row.pattern)])
+        }
+        Err(err) => tr(
+            lang,
+            "blocklist.invalid_regex",
+            &[("pattern", &row.pattern), ("error", &err.to_string())],
+        ),
+    };
+
+    poise_check_msg(
+        ctx.send(CreateReply::default().ephemeral(true).content(content))
+            .await,
+    );
+    Ok(())
+}
+
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "remove",
+    check = "permissions::can_manage_settings"
+)]
+pub async fn remove_blocklist_pattern(
+    ctx: PoiseContext<'_>,
+    #[description = "Blocked word or regex pattern to remove"] pattern: String,
+) -> PoiseResult {
+    let guild_id = ctx.guild_id().ok_or("No guild id found")?;
+    let pattern = pattern.trim();
+
+    let content = match ctx
+        .data()
+        .blocklist_table()
+        .remove_pattern(guild_id.get(), pattern)?
+    {
+        true => tr(
+            i18n::language(ctx),
+            "blocklist.removed",
+            &[("pattern", &pattern)],
+        ),
+        false => tr(
+            i18n::language(ctx),
+            "blocklist.not_found",
+            &[("pattern", &pattern)],
+        ),
+    };
+
+    poise_check_msg(
+        ctx.send(CreateReply::default().ephemeral(true).content(content))
+            .await,
+    );
+    Ok(())
+}
+
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "list",
+    check = "permissions::can_manage_settings"
+)]
+pub async fn list_blocklist_patterns(ctx: PoiseContext<'_>) -> PoiseResult {
+    let guild_id = ctx.guild_id().ok_or("No guild id found")?;
+    let rows = ctx.data().blocklist_table().patterns(guild_id.get())?;
+
+    let lang = i18n::language(ctx);
+    let content = match rows.is_empty() {
+        true => tr(lang, "blocklist.empty", &[]),
+        false => {
+            let lines: Vec<_> = rows
+                .iter()
+                .map(|row| match row.is_regex {
+                    true => format!("- `{}` (regex)", row.pattern),
+                    false => format!("- `{}`", row.pattern),
+                })
+                .collect();
+            format!("{}\n{}", tr(lang, "blocklist.title", &[]), lines.join("\n"))
+        }
+    };
+
+    poise_check_msg(
+        ctx.send(CreateReply::default().ephemeral(true).content(content))
+            .await,
+    );
+    Ok(())
+}
+
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "native-sounds",
+    check = "permissions::can_manage_settings",
+    subcommands(
+        "record_native_sounds",
+        "map_native_sound",
+        "unmap_native_sound",
+        "list_native_sounds"
+    )
+)]
+pub async fn settings_native_sounds(_ctx: PoiseContext<'_>) -> PoiseResult {
+    log::warn!(
+        "/settings native-sounds command shouldn't be invoked directly. It should just house sub commands"
+    );
+    Ok(())
+}
+
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "record",
+    check = "permissions::can_manage_settings"
+)]
+pub async fn record_native_sounds(
+    ctx: PoiseContext<'_>,
+    #[description = "Record plays of mapped Discord soundboard sounds in play statistics"]
+    enabled: bool,
+) -> PoiseResult {
+    let guild_id = ctx.guild_id().ok_or("No guild id found")?;
+    log::info!("Setting record native sounds. Guild Id: {guild_id}, Enabled: {enabled}");
+
+    let table = ctx.data().guild_settings_table();
+    let mut settings = table.get_guild_settings(guild_id.get())?;
+    settings.record_native_sounds = enabled;
+    table.update_guild_settings(&settings)?;
+
+    let content = match enabled {
+        true => tr(i18n::language(ctx), "native_sounds.recording", &[]),
+        false => tr(i18n::language(ctx), "native_sounds.not_recording", &[]),
+    };
+
+    poise_check_msg(
+        ctx.send(CreateReply::default().ephemeral(true).content(content))
+            .await,
+    );
+    Ok(())
+}
+
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "map",
+    check = "permissions::can_manage_settings"
+)]
+pub async fn map_native_sound(
+    ctx: PoiseContext<'_>,
+    #[description = "Discord soundboard sound id (Copy Sound ID with developer mode enabled)"]
+    #[rename = "sound-id"]
+    #[max_length = 32]
+    sound_id: String,
+    #[rename = "track"]
+    #[description = "Bot sound the Discord soundboard sound counts as"]
+    #[autocomplete = "helpers::autocomplete_audio_track_name"]
+    audio_track_name: String,
+) -> PoiseResult {
+    let guild_id = ctx.guild_id().ok_or("No guild id found")?;
+    let lang = i18n::language(ctx);
+    let sound_id = sound_id.trim();
+
+    let content = if sound_id.is_empty() || !sound_id.chars().all(|c| c.is_ascii_digit()) {
+        tr(lang, "native_sounds.invalid_id", &[])
+    } else {
+        match ctx
+            .data()
+            .audio_table()
+            .find_audio_row(db::UniqueAudioTableCol::from_input(&audio_track_name))
+        {
+            Some(row) => {
+                ctx.data().native_sounds_table().map_sound(
+                    guild_id.get(),
+                    &NativeSoundsTableRow {
+                        sound_id: sound_id.into(),
+                        audio_id: row.id,
+                    },
+                )?;
+                tr(
+                    lang,
+                    "native_sounds.mapped",
+                    &[("sound_id", &sound_id), ("track", &row.name)],
+                )
+            }
+            None => tr(lang, "play.not_found", &[("track", &audio_track_name)]),
+        }
+    };
+
+    poise_check_msg(
+        ctx.send(CreateReply::default().ephemeral(true).content(content))
+            .await,
+    );
+    Ok(())
+}
+
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "unmap",
+    check = "permissions::can_manage_settings"
+)]
+pub async fn unmap_native_sound(
+    ctx: PoiseContext<'_>,
+    #[description = "Discord soundboard sound id to unmap"]
+    #[rename = "sound-id"]
+    sound_id: String,
+) -> PoiseResult {
+    let guild_id = ctx.guild_id().ok_or("No guild id found")?;
+    let sound_id = sound_id.trim();
+
+    let content = match ctx
+        .data()
+        .native_sounds_table()
+        .unmap_sound(guild_id.get(), sound_id)?
+    {
+        true => tr(
+            i18n::language(ctx),
+            "native_sounds.unmapped",
+            &[("sound_id", &sound_id)],
+        ),
+        false => tr(
+            i18n::language(ctx),
+            "native_sounds.not_mapped",
+            &[("sound_id", &sound_id)],
+        ),
+    };
+
+    poise_check_msg(
+        ctx.send(CreateReply::default().ephemeral(true).content(content))
+            .await,
+    );
+    Ok(())
+}
+
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "list",
+    check = "permissions::can_manage_settings"
+)]
+pub async fn list_native_sounds(ctx: PoiseContext<'_>) -> PoiseResult {
+    let guild_id = ctx.guild_id().ok_or("No guild id found")?;
+    let rows = ctx.data().native_sounds_table().mappings(guild_id.get())?;
+
+    let lang = i18n::language(ctx);
+    let content = match rows.is_empty() {
+        true => tr(lang, "native_sounds.empty", &[]),
+        false => {
+            let audio_table = ctx.data().audio_table();
+            let lines: Vec<_> = rows
+                .iter()
+                .filter_map(|row| {
+                    audio_table
+                        .find_audio_row(db::UniqueAudioTableCol::Id(row.audio_id))
+                        .map(|audio_row| format!("- `{}` → `{}`", row.sound_id, audio_row.name))
+                })
+                .collect();
+            format!(
+                "{}\n{}",
+                tr(lang, "native_sounds.title", &[]),
+                lines.join("\n")
+            )
+        }
+    };
+
+    poise_check_msg(
+        ctx.send(CreateReply::default().ephemeral(true).content(content))
+            .await,
+    );
+    Ok(())
+}
+
+#[poise::command(slash_command, subcommands("privacy_forget_me"))]
+pub async fn privacy(_ctx: PoiseContext<'_>) -> PoiseResult {
+    log::warn!("/privacy command shouldn't be invoked directly. It should just house sub commands");
+    Ok(())
+}
+
+#[poise::command(slash_command, rename = "forget-me")]
+pub async fn privacy_forget_me(ctx: PoiseContext<'_>) -> PoiseResult {
+    let lang = i18n::language(ctx);
+    let confirmation = helpers::confirm(ctx, tr(lang, "privacy.confirm_forget_me", &[])).await?;
+    if confirmation != Confirmation::Confirmed {
+        log::info!("Forget me not confirmed - {confirmation:?}");
+        return Ok(());
+    }
+
+    let summary = privacy::purge_user(
+        &ctx.data().db_connection(),
+        &ctx.data().config().trash_dir,
+        ctx.author().id.get(),
+    )?;
+    ctx.data().autocomplete_cache.invalidate();
+
+    poise_check_msg(
+        ctx.send(
+            CreateReply::default()
+                .ephemeral(true)
+                .content(privacy::summary_message(lang, &summary)),
+        )
+        .await,
+    );
+    Ok(())
+}
+
+/// Runtime diagnostics for bot owners, e.g. voice connections and recent errors
+#[poise::command(
+    slash_command,
+    prefix_command,
+    owners_only,
+    default_member_permissions = "ADMINISTRATOR"
+)]
+pub async fn debug(ctx: PoiseContext<'_>) -> PoiseResult {
+    let diagnostics = Diagnostics::collect(ctx).await;
+
+    poise_check_msg(
+        ctx.send(
+            CreateReply::default()
+                .ephemeral(true)
+                .embed(diagnostics.to_embed(i18n::language(ctx))),
+        )
+        .await,
+    );
+    Ok(())
+}
+
+/// Pre-flight checks of the bot's setup, e.g. token, intents and voice permissions
+#[poise::command(
+    slash_command,
+    prefix_command,
+    owners_only,
+    default_member_permissions = "ADMINISTRATOR"
+)]
+pub async fn doctor(ctx: PoiseContext<'_>) -> PoiseResult {
+    let report = doctor::run(
+        &ctx.serenity_context().http,
+        &ctx.data().db_pool,
+        &ctx.data().config().audio_dir,
+        ctx.guild_id(),
+        i18n::language(ctx),
+    )
+    .await;
+
+    poise_check_msg(
+        ctx.send(
+            CreateReply::default()
+                .ephemeral(true)
+                .embed(report.to_embed()),
+        )
+        .await,
+    );
+    Ok(())
+}
+
+#[poise::command(
+    slash_command,
+    guild_only,
+    subcommands("packs_browse", "packs_install")
+)]
+pub async fn packs(_ctx: PoiseContext<'_>) -> PoiseResult {
+    log::warn!("/packs command shouldn't be invoked directly. It should just house sub commands");
+    Ok(())
+}
+
+#[poise::command(slash_command, guild_only, rename = "browse")]
+pub async fn packs_browse(ctx: PoiseContext<'_>) -> PoiseResult {
+    log::info!("Browsing sound packs...");
+
+    let lang = i18n::language(ctx);
+    let content = match ctx.data().config().packs_index_url {
+        Some(index_url) => {
+            let index = packs::fetch_index(&ctx.data().http_client, &index_url).await?;
+            packs::render_pack_list(lang, &index)
+        }
+        None => tr(lang, "packs.not_configured", &[]),
+    };
+
+    poise_check_msg(
+        ctx.send(CreateReply::default().ephemeral(true).content(content))
+            .await,
+    );
+    Ok(())
+}
+
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "install",
+    required_permissions = "ADMINISTRATOR"
+)]
+pub async fn packs_install(
+    ctx: PoiseContext<'_>,
+    #[description = "Id of the pack, as listed by /packs browse"] pack: String,
+) -> PoiseResult {
+    let guild_id = ctx.guild_id().ok_or("No guild id found")?;
+    log::info!("Installing sound pack. Guild Id: {guild_id}, Pack: {pack}");
+
+    let lang = i18n::language(ctx);
+    if ctx.data().config().packs_index_url.is_none() {
+        poise_check_msg(ctx.reply(tr(lang, "packs.not_configured", &[])).await);
+        return Ok(());
+    }
+    if !check_free_disk_space(ctx).await {
+        return Ok(());
+    }
+
+    enqueue_job(ctx, Job::InstallPack { pack_id: pack }).await
+}
+
+#[poise::command(slash_command, guild_only, subcommands("jobs_status"))]
+pub async fn jobs(_ctx: PoiseContext<'_>) -> PoiseResult {
+    log::warn!("/jobs command shouldn't be invoked directly. It should just house sub commands");
+    Ok(())
+}
+
+#[poise::command(slash_command, guild_only, rename = "status")]
+pub async fn jobs_status(
+    ctx: PoiseContext<'_>,
+    #[description = "Id of job to show. Defaults to the server's latest jobs"] id: Option<i64>,
+) -> PoiseResult {
+    let guild_id = ctx.guild_id().ok_or("No guild id found")?;
+    let lang = i18n::language(ctx);
+    let table = ctx.data().jobs_table();
+
+    let rows = match id {
+        Some(id) => table
+            .find_job(id)?
+            .filter(|row| row.guild_id == Some(guild_id.get()))
+            .into_iter()
+            .collect(),
+        None => table.recent_jobs(guild_id.get(), vars::JOBS_STATUS_LIMIT)?,
+    };
+
+    let content = match rows.is_empty() {
+        true => tr(lang, "jobs.none", &[]),
+        false => rows
+            .iter()
+            .map(|row| jobs::render_job(lang, row))
+            .collect::<Vec<_>>()
+            .join("\n"),
+    };
+
+    poise_check_msg(
+        ctx.send(
+            CreateReply::default()
+                .ephemeral(true)
+                .content(helpers::truncate_message(content)),
+        )
+        .await,
+    );
+    Ok(())
+}
+
+#[poise::command(
+    slash_command,
+    guild_only,
+    subcommands("stats_history", "stats_heatmap", "stats_disk", "stats_commands")
+)]
+pub async fn stats(_ctx: PoiseContext<'_>) -> PoiseResult {
+    log::warn!("/stats command shouldn't be invoked directly. It should just house sub commands");
+    Ok(())
+}
+
+#[poise::command(slash_command, guild_only)]
+pub async fn trending(ctx: PoiseContext<'_>) -> PoiseResult {
+    let guild_id = ctx.guild_id().ok_or("No guild id found")?;
+    let lang = i18n::language(ctx);
+
+    let trending = stats::trending_sounds(
+        &ctx.data().play_events_table(),
+        chrono::Utc::now(),
+        &PlayEventsFilter {
+            audio_id: None,
+            guild_id: Some(guild_id.get()),
+        },
+    )?;
+
+    let table = ctx.data().audio_table();
+    let lines: Vec<_> = trending
+        .into_iter()
+        .filter_map(|(audio_id, score)| {
+            table
+                .find_audio_row(db::UniqueAudioTableCol::Id(audio_id))
+                .filter(|row| row.archived_at.is_none())
+                .map(|row| (row, score))
+        })
+        .take(vars::TRENDING_LIMIT)
+        .enumerate()
+        .map(|(index, (row, score))| format!("{}. `{}` - {score:.1}", index + 1, row.name))
+        .collect();
+
+    let content = match lines.is_empty() {
+        true => tr(lang, "trending.none", &[]),
+        false => format!(
+            "{}\n{}",
+            tr(
+                lang,
+                "trending.title",
+                &[("days", &vars::TRENDING_HALF_LIFE_DAYS)]
+            ),
+            lines.join("\n")
+        ),
+    };
+    poise_check_msg(ctx.say(content).await);
+    Ok(())
+}
+
+#[poise::command(
+    slash_command,
+    guild_only,
+    check = "channel_restrictions::can_use_soundboard"
+)]
+pub async fn forgotten(ctx: PoiseContext<'_>) -> PoiseResult {
+    let lang = i18n::language(ctx);
+    let audio_rows = ctx
+        .data()
+        .audio_table()
+        .forgotten_audio_rows(vars::FORGOTTEN_LIMIT)?;
+
+    if audio_rows.is_empty() {
+        poise_check_msg(ctx.say(tr(lang, "forgotten.none", &[])).await);
+        return Ok(());
+    }
+
+    let never = tr(lang, "prune.never_played", &[]);
+    let lines: Vec<_> = audio_rows
+        .iter()
+        .map(|row| {
+            let last_played = row
+                .last_played_at
+                .map(|last_played_at| format!("<t:{}:R>", last_played_at.timestamp()))
+                .unwrap_or_else(|| never.clone());
+            format!("- `{}` - {last_played}", row.name)
+        })
+        .collect();
+    let btn_grid: Vec<_> = audio_rows
+        .chunks(5)
+        .map(|row| helpers::make_action_row(row, ctx.guild_id()))
+        .collect();
+
+    poise_check_msg(
+        ctx.send(
+            CreateReply::default()
+                .content(format!(
+                    "{}\n{}",
+                    tr(lang, "forgotten.title", &[]),
+                    lines.join("\n")
+                ))
+                .components(btn_grid),
+        )
+        .await,
+    );
+    Ok(())
+}
+
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "history",
+    check = "channel_restrictions::can_use_soundboard"
+)]
+pub async fn user_history(
+    ctx: PoiseContext<'_>,
+    #[description = "Member whose plays to list. Defaults to you"] user: Option<
+        serenity::all::User,
+    >,
+) -> PoiseResult {
+    let guild_id = ctx.guild_id().ok_or("No guild id found")?;
+    let lang = i18n::language(ctx);
+    let user = user.unwrap_or_else(|| ctx.author().clone());
+
+    if user.id != ctx.author().id
+        && !ctx
+            .data()
+            .guild_settings_table()
+            .get_guild_settings(guild_id.get())?
+            .play_history_public
+    {
+        let content = tr(lang, "history.private", &[("user", &user.name)]);
+        reply_ephemeral(ctx, content).await;
+        return Ok(());
+    }
+
+    let history = ctx.data().play_events_table().user_play_history(
+        user.id.get(),
+        Some(guild_id.get()),
+        vars::PLAY_HISTORY_LIMIT,
+    )?;
+    if history.is_empty() {
+        let content = tr(lang, "history.none", &[("user", &user.name)]);
+        reply_ephemeral(ctx, content).await;
+        return Ok(());
+    }
+
+    let lines: Vec<_> = history
+        .iter()
+        .map(|(row, played_at)| format!("- `{}` <t:{}:R>", row.name, played_at.timestamp()))
+        .collect();
+
+    // one replay button per sound, as custom ids must be unique within a message
+    let mut replayable: Vec<db::AudioTableRow> = vec![];
+    for (row, _) in history {
+        if row.archived_at.is_none() && !replayable.iter().any(|other| other.id == row.id) {
+            replayable.push(row);
+        }
+    }
+    let btn_grid: Vec<_> = replayable
+        .chunks(5)
+        .map(|row| helpers::make_action_row(row, ctx.guild_id()))
+        .collect();
+
+    poise_check_msg(
+        ctx.send(
+            CreateReply::default()
+                .ephemeral(true)
+                .content(format!(
+                    "{}\n{}",
+                    tr(lang, "history.title", &[("user", &user.name)]),
+                    lines.join("\n")
+                ))
+                .components(btn_grid),
+        )
+        .await,
+    );
+    Ok(())
+}
+
+#[poise::command(slash_command, guild_only, rename = "history")]
+pub async fn stats_history(
+    ctx: PoiseContext<'_>,
+    #[rename = "track"]
+    #[description = "Only count plays of this sound"]
+    #[autocomplete = "helpers::autocomplete_audio_track_name"]
+    audio_track_name: Option<String>,
+    #[description = "Count plays per day or per week"] period: Option<HistogramBucket>,
+) -> PoiseResult {
+    let guild_id = ctx.guild_id().ok_or("No guild id found")?;
+    let lang = i18n::language(ctx);
+    let bucket = period.unwrap_or_default();
+
+    let row = match audio_track_name {
+        Some(ref audio_track_name) => match ctx
+            .data()
+            .audio_table()
+            .find_audio_row(db::UniqueAudioTableCol::from_input(audio_track_name))
+        {
+            Some(row) => Some(row),
+            None => {
+                poise_check_msg(
+                    ctx.send(CreateReply::default().ephemeral(true).content(tr(
+                        lang,
+                        "play.not_found",
+                        &[("track", audio_track_name)],
+                    )))
+                    .await,
+                );
+                return Ok(());
+            }
+        },
+        None => None,
+    };
+
+    let history = stats::play_history(
+        &ctx.data().play_events_table(),
+        bucket,
+        stats::history_len(bucket),
+        chrono::Utc::now(),
+        &PlayEventsFilter {
+            audio_id: row.as_ref().map(|row| row.id),
+            guild_id: Some(guild_id.get()),
+        },
+    )?;
+
+    let title = match row {
+        Some(ref row) => tr(lang, "stats.history_track", &[("track", &row.name)]),
+        None => tr(lang, "stats.history", &[]),
+    };
+    let total: i64 = history.iter().map(|(_, plays)| plays).sum();
+
+    poise_check_msg(
+        ctx.say(format!(
+            "{title}\n```\n{}\n```\n{}",
+            stats::render_chart(&history),
+            tr(lang, "stats.total", &[("plays", &total)])
+        ))
+        .await,
+    );
+    Ok(())
+}
+
+#[poise::command(slash_command, guild_only, rename = "heatmap")]
+pub async fn stats_heatmap(ctx: PoiseContext<'_>) -> PoiseResult {
+    let guild_id = ctx.guild_id().ok_or("No guild id found")?;
+    let lang = i18n::language(ctx);
+
+    let heatmap = stats::play_heatmap(
+        &ctx.data().play_events_table(),
+        vars::STATS_HEATMAP_DAYS,
+        chrono::Utc::now(),
+        &PlayEventsFilter {
+            audio_id: None,
+            guild_id: Some(guild_id.get()),
+        },
+    )?;
+
+    let day_names = tr(lang, "stats.heatmap_days", &[]);
+    let day_names: Vec<_> = day_names.split_whitespace().collect();
+    let total: i64 = heatmap.iter().flatten().sum();
+
+    poise_check_msg(
+        ctx.say(format!(
+            "{}\n```\n{}\n```\n{}",
+            tr(
+                lang,
+                "stats.heatmap",
+                &[("days", &vars::STATS_HEATMAP_DAYS)]
+            ),
+            stats::render_heatmap(&heatmap, &day_names),
+            tr(lang, "stats.total", &[("plays", &total)])
+        ))
+        .await,
+    );
+    Ok(())
+}
+
+#[poise::command(slash_command, guild_only, rename = "disk")]
+pub async fn stats_disk(ctx: PoiseContext<'_>) -> PoiseResult {
+    let lang = i18n::language(ctx);
+    let config = ctx.data().config();
+
+    let content = match disk::disk_usage(&config.audio_dir) {
+        Ok(usage) => tr(
+            lang,
+            "stats.disk",
+            &[
+                ("free", &helpers::format_file_size(usage.free)),
+                ("total", &helpers::format_file_size(usage.total)),
+                ("used", &usage.used_percent()),
+                ("files", &usage.audio_dir.files),
+                ("size", &helpers::format_file_size(usage.audio_dir.bytes)),
+            ],
+        ),
+        Err(err) => {
+            log::warn!("Failed to read disk usage of audio dir - {err}");
+            tr(lang, "stats.disk_unknown", &[])
+        }
+    };
+    let content = match disk::min_free_bytes(&config) {
+        Some(min_free) => format!(
+            "{content}\n{}",
+            tr(
+                lang,
+                "stats.disk_min_free",
+                &[("min", &helpers::format_file_size(min_free))]
+            )
+        ),
+        None => content,
+    };
+
+    poise_check_msg(ctx.say(content).await);
+    Ok(())
+}
+
+#[poise::command(slash_command, guild_only, owners_only, rename = "commands")]
+pub async fn stats_commands(ctx: PoiseContext<'_>) -> PoiseResult {
+    let lang = i18n::language(ctx);
+    let stats = ctx.data().command_stats_table().command_stats()?;
+
+    let content = match stats.is_empty() {
+        true => tr(lang, "stats.no_commands", &[]),
+        false => format!(
+            "{}\n{}",
+            tr(lang, "stats.commands", &[]),
+            command_stats::render_command_stats(lang, &stats)
+        ),
+    };
+
+    poise_check_msg(
+        ctx.send(
+            CreateReply::default()
+                .ephemeral(true)
+                .content(helpers::truncate_message(content)),
+        )
+        .await,
+    );
+    Ok(())
+}
+
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "channels",
+    check = "permissions::can_manage_settings",
+    subcommands("allow_channel", "disallow_channel", "list_channels")
+)]
+pub async fn settings_channels(_ctx: PoiseContext<'_>) -> PoiseResult {
+    log::warn!(
+        "/settings channels command shouldn't be invoked directly. It should just house sub commands"
+    );
+    Ok(())
+}
+
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "allow",
+    check = "permissions::can_manage_settings"
+)]
+pub async fn allow_channel(
+    ctx: PoiseContext<'_>,
+    #[description = "Voice channel to allow playback in, or text channel to allow commands in"]
+    #[channel_types("Text", "Voice", "Stage")]
+    channel: GuildChannel,
+) -> PoiseResult {
+    let guild_id = ctx.guild_id().ok_or("No guild id found")?;
+    let kind = ChannelKind::of(channel.kind);
+
+    ctx.data()
+        .channel_restrictions_table()
+        .allow(guild_id.get(), kind.key(), channel.id.get())?;
+
+    let key = match kind {
+        ChannelKind::Voice => "channels.voice_allowed",
+        ChannelKind::Text => "channels.text_allowed",
+    };
+    poise_check_msg(
+        ctx.send(CreateReply::default().ephemeral(true).content(tr(
+            i18n::language(ctx),
+            key,
+            &[("channel", &format!("<#{}>", channel.id))],
+        )))
+        .await,
+    );
+    Ok(())
+}
+
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "disallow",
+    check = "permissions::can_manage_settings"
+)]
+pub async fn disallow_channel(
+    ctx: PoiseContext<'_>,
+    #[description = "Channel to remove from the allowed channels"]
+    #[channel_types("Text", "Voice", "Stage")]
+    channel: GuildChannel,
+) -> PoiseResult {
+    let guild_id = ctx.guild_id().ok_or("No guild id found")?;
+    let kind = ChannelKind::of(channel.kind);
+
+    let key = match ctx.data().channel_restrictions_table().disallow(
+        guild_id.get(),
+        kind.key(),
+        channel.id.get(),
+    )? {
+        true => "channels.disallowed",
+        false => "channels.not_allowed",
+    };
+    poise_check_msg(
+        ctx.send(CreateReply::default().ephemeral(true).content(tr(
+            i18n::language(ctx),
+            key,
+            &[("channel", &format!("<#{}>", channel.id))],
+        )))
+        .await,
+    );
+    Ok(())
+}
+
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "list",
+    check = "permissions::can_manage_settings"
+)]
+pub async fn list_channels(ctx: PoiseContext<'_>) -> PoiseResult {
+    let guild_id = ctx.guild_id().ok_or("No guild id found")?;
+    let table = ctx.data().channel_restrictions_table();
+    let lang = i18n::language(ctx);
+
+    let mut lines = vec![tr(lang, "channels.title", &[])];
+    for kind in ChannelKind::ALL {
+        let channels: Vec<_> = table
+            .channel_ids(guild_id.get(), kind.key())?
+            .iter()
+            .map(|channel_id| format!("<#{channel_id}>"))
+            .collect();
+
+        let channels = match channels.is_empty() {
+            true => tr(lang, "channels.any", &[]),
+            false => channels.join(", "),
+        };
+        let key = match kind {
+            ChannelKind::Voice => "channels.voice",
+            ChannelKind::Text => "channels.text",
+        };
+        lines.push(format!("- {} - {channels}", tr(lang, key, &[])));
+    }
+
+    poise_check_msg(
+        ctx.send(
+            CreateReply::default()
+                .ephemeral(true)
+                .content(lines.join("\n")),
+        )
+        .await,
+    );
+    Ok(())
+}
+
+#[poise::command(slash_command, prefix_command, guild_only)]
+pub async fn echo(
+    ctx: PoiseContext<'_>,
+    #[description = "Text to echo back"] text: Option<String>,
+) -> PoiseResult {
+    let response = format!("Echo: '{}'", text.unwrap_or("".into()));
+    poise_check_msg(ctx.say(response).await);
+
+    Ok(())
+}
+
+#[poise::command(prefix_command, guild_only)]
+pub async fn register(ctx: PoiseContext<'_>) -> PoiseResult {
+    poise::builtins::register_application_commands_buttons(ctx).await?;
+    Ok(())
+}
+
+#[derive(Debug, poise::Modal)]
+#[name = "Add Sound"]
+struct AddSoundModal {
+    #[name = "Name"] // Field name by default
+    #[placeholder = "Use The Force Luke"] // No placeholder by default
+    #[min_length = 3] // No length restriction by default (so, 1-4000 chars)
+    #[max_length = 80] // Same as max sound name len (crate::vars::SOUND_NAME_MAX_LEN)
+    name: String,
+
+    #[name = "Tags"] // Field name by default
+    #[placeholder = "star Wars jedi luke skywalker force episode iv"] // No placeholder by default
+    #[max_length = 1024]
+    tags: Option<String>,
+
+    #[name = "Audio URL"]
+    #[placeholder = "www.example.com/use-the-force.mp3"]
+    #[max_length = 2048]
+    url: String,
+}
+
+/// Let the author know their sound is waiting in line when all ingest slots are taken
+async fn send_ingest_queued_reply(ctx: PoiseContext<'_>) {
+    let limiter = &ctx.data().ingest_limiter;
+    if !limiter.is_full() {
+        return;
+    }
+
+    let position = limiter.queued() + 1;
+    log::info!("Ingest queued. Position: {position}");
+    poise_check_msg(
+        ctx.send(CreateReply::default().ephemeral(true).content(tr(
+            i18n::language(ctx),
+            "sounds.queued",
+            &[("position", &position)],
+        )))
+        .await,
+    );
+}
+
+/// Refuse new sounds while the audio dir is low on disk space, replying why. Returns whether
+/// there's room for them
+async fn check_free_disk_space(ctx: PoiseContext<'_>) -> bool {
+    let config = ctx.data().config();
+    match disk::check_free_space(&config) {
+        Ok(()) => true,
+        Err(free) => {
+            reply_ephemeral(
+                ctx,
+                disk::low_space_message(i18n::language(ctx), &config, free),
+            )
+            .await;
+            false
+        }
+    }
+}
+
+/// Validate sound name, replying why it's invalid if it isn't
+async fn validate_sound_name(ctx: PoiseContext<'_>, name: &str) -> Option<SoundName> {
+    match SoundName::new(name) {
+        Ok(name) => Some(name),
+        Err(err) => {
+            log::info!("Invalid sound name '{name}' - {err}");
+            poise_check_msg(
+                ctx.send(
+                    CreateReply::default()
+                        .ephemeral(true)
+                        .content(helpers::sound_name_error_message(i18n::language(ctx), &err)),
+                )
+                .await,
+            );
+            None
+        }
+    }
+}
+
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "add",
+    check = "permissions::can_add_sounds"
+)]
+#[tracing::instrument(name = "/sounds add", skip_all)]
+pub async fn add_sound(ctx: PoiseAppContext<'_>) -> PoiseResult {
+    if !check_free_disk_space(ctx.into()).await {
+        return Ok(());
+    }
+
+    let data = AddSoundModal::execute(ctx)
+        .await?
+        .ok_or("AddSoundModal not set")
+        .log_err()?;
+
+    log::info!("Adding sound. Name: {}, Url: {}", data.name, data.url);
+
+    let guild_id = ctx.guild_id().map(|guild_id| guild_id.get());
+    if let Err(reply) = blocklist::check_text(ctx.data(), guild_id, &data.name) {
+        poise_check_msg(
+            ctx.send(CreateReply::default().ephemeral(true).content(reply))
+                .await,
+        );
+        return Ok(());
+    }
+
+    let Some(name) = validate_sound_name(ctx.into(), &data.name).await else {
+        return Ok(());
+    };
+
+    if let Err(refusal) = download_policy::check_url(&ctx.data().config(), &data.url) {
+        let content = refusal.message(i18n::language(ctx.into()));
+        poise_check_msg(
+            ctx.send(CreateReply::default().ephemeral(true).content(content))
+                .await,
+        );
+        return Ok(());
+    }
+
+    let table = ctx.data.audio_table();
+    send_ingest_queued_reply(ctx.into()).await;
+    let permit = ctx.data().ingest_limiter.acquire().await;
+
+    let lang = i18n::language(ctx.into());
+    let progress_reply = ctx
+        .send(CreateReply::default().ephemeral(true).content(tr(
+            lang,
+            "sounds.downloading",
+            &[("track", &name), ("progress", &"")],
+        )))
+        .await?;
+    let edit_progress = |content: String| {
+        let progress_reply = &progress_reply;
+        async move {
+            progress_reply
+                .edit(ctx.into(), CreateReply::default().content(content))
+                .await
+                .log_err_msg("Failed to edit add sound progress reply")
+                .ok();
+        }
+    };
+
+    let download_client = http_client::download_client(&ctx.data().config())?;
+    let temp_audio_file =
+        audio::download_audio_url_temp_with_progress(&download_client, &data.url, |progress| {
+            edit_progress(tr(
+                lang,
+                "sounds.downloading",
+                &[("track", &name), ("progress", &progress)],
+            ))
+        })
+        .await?;
+
+    // validate audio track (codec type, length, etc)
+    edit_progress(tr(lang, "sounds.validating", &[("track", &name)])).await;
+    let track_info = audio::AudioFileValidator::default()
+        .max_audio_duration(
+            ctx.data()
+                .max_audio_duration(ctx.guild_id().map(|guild_id| guild_id.get())),
+        )
+        .reject_uuid_files(false)
+        .validate(&temp_audio_file)?;
+    // free the slot before waiting on the author to pick a name
+    drop(permit);
+    progress_reply
+        .delete(ctx.into())
+        .await
+        .log_err_msg("Failed to delete add sound progress reply")
+        .ok();
+
+    if let Some(duplicate) =
+        table.find_duplicate_audio_row(track_info.duration_ms(), track_info.file_size)
+    {
+        poise_check_msg(
+            ctx.send(CreateReply::default().ephemeral(true).content(tr(
+                i18n::language(ctx.into()),
+                "sounds.duplicate",
+                &[("track", &duplicate.name)],
+            )))
+            .await,
+        );
+        return Ok(());
+    }
+
+    let sound = ModeratedSound {
+        name: &name,
+        audio_file: &temp_audio_file,
+        guild_id: ctx.guild_id().map(|guild_id| guild_id.get()),
+        duration_ms: track_info.duration_ms(),
+    };
+    let flagged = match loudness::check_upload(ctx.data(), &sound).await {
+        Ok(None) => moderation::check_upload(ctx.data(), &sound).await,
+        checked => checked,
+    };
+    let flagged = match flagged {
+        Ok(flagged) => flagged,
+        Err(reply) => {
+            poise_check_msg(
+                ctx.send(CreateReply::default().ephemeral(true).content(reply))
+                    .await,
+            );
+            return Ok(());
+        }
+    };
+
+    // move track to sounds dir, deleted again unless its row is inserted
+    let placed_file = ctx.data().move_file_to_audio_dir(&temp_audio_file)?;
+    let tags: Tags = match data.tags {
+        Some(val) => Tags::from(val),
+        None => Tags::new(),
+    };
+
+    let row_insert = AudioTableRowInsert {
+        name,
+        audio_file: audio::AudioFile::new(placed_file.to_path_buf()),
+        author_global_name: ctx.author().global_name.clone(),
+        author_id: Some(ctx.author().id.into()),
+        author_name: Some(ctx.author().name.clone()),
+        author_hash: None,
+        guild_id: ctx.guild_id().map(|guild_id| guild_id.get()),
+        tags,
+        created_at: chrono::Utc::now(),
+        duration_ms: Some(track_info.duration_ms()),
+        file_size: Some(track_info.file_size),
+        source_url: Some(data.url.clone()),
+    };
+
+    if let Some(flagged) = flagged {
+        let track = row_insert.name.to_string();
+        approval::submit(
+            &ctx.serenity_context.http,
+            ctx.data(),
+            flagged.review_channel_id,
+            row_insert,
+            placed_file,
+            Some(&flagged.reason),
+        )
+        .await?;
+
+        poise_check_msg(
+            ctx.reply(tr(
+                i18n::language(ctx.into()),
+                "moderation.flagged",
+                &[("track", &track), ("reason", &flagged.reason)],
+            ))
+            .await,
+        );
+        return Ok(());
+    }
+
+    if let Some(review_channel_id) = approval::review_channel_id(ctx.into()).await? {
+        let track = row_insert.name.to_string();
+        approval::submit(
+            &ctx.serenity_context.http,
+            ctx.data(),
+            review_channel_id,
+            row_insert,
+            placed_file,
+            None,
+        )
+        .await?;
+
+        poise_check_msg(
+            ctx.reply(tr(
+                i18n::language(ctx.into()),
+                "approval.submitted",
+                &[("track", &track)],
+            ))
+            .await,
+        );
+        return Ok(());
+    }
+
+    // offer untaken names until one is picked, or the author gives up
+    let mut row_insert = row_insert;
+    loop {
+        match table.insert_audio_row(&row_insert) {
+            Ok(()) => break,
+            Err(InsertAudioError::NameTaken { name }) => {
+                let uploader_name = ctx.author().global_name.as_ref();
+                let suggestions = helpers::name_suggestions(
+                    &name,
+                    uploader_name.unwrap_or(&ctx.author().name),
+                    |name| {
+                        table
+                            .find_audio_row(db::UniqueAudioTableCol::Name(name.into()))
+                            .is_some()
+                    },
+                )
+                .into_iter()
+                .filter(|suggestion| SoundName::new(suggestion).is_ok())
+                .collect();
+
+                match helpers::pick_name(ctx.into(), &name, suggestions).await? {
+                    Some(picked) => row_insert.name = SoundName::new(picked)?,
+                    None => return Ok(()),
+                }
+            }
+            Err(err) => return Err(err.into()),
+        }
+    }
+    placed_file.keep();
+
+    ctx.data().autocomplete_cache.invalidate();
+
+    poise_check_msg(
+        ctx.reply(tr(
+            i18n::language(ctx.into()),
+            "sounds.added",
+            &[("track", &row_insert.name)],
+        ))
+        .await,
+    );
+
+    refresh_published_soundboard(ctx.into()).await;
+
+    Ok(())
+}
+
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "upload",
+    check = "permissions::can_add_sounds"
+)]
+#[tracing::instrument(name = "/sounds upload", skip_all)]
+pub async fn upload_sounds(
+    ctx: PoiseContext<'_>,
+    #[rename = "file-1"]
+    #[description = "Audio file"]
+    file_1: serenity::all::Attachment,
+    #[rename = "file-2"]
+    #[description = "Audio file"]
+    file_2: Option<serenity::all::Attachment>,
+    #[rename = "file-3"]
+    #[description = "Audio file"]
+    file_3: Option<serenity::all::Attachment>,
+    #[rename = "file-4"]
+    #[description = "Audio file"]
+    file_4: Option<serenity::all::Attachment>,
+    #[rename = "file-5"]
+    #[description = "Audio file"]
+    file_5: Option<serenity::all::Attachment>,
+    #[description = "Hide your name on the uploaded sounds. Defaults to false"] anonymous: Option<
+        bool,
+    >,
+) -> PoiseResult {
+    if !check_free_disk_space(ctx).await {
+        return Ok(());
+    }
+
+    let anonymous = anonymous.unwrap_or(false);
+    let uploads: Vec<_> = [Some(file_1), file_2, file_3, file_4, file_5]
+        .into_iter()
+        .flatten()
+        .map(|attachment| ingest::Upload {
+            file_name: attachment.filename,
+            url: attachment.url,
+        })
+        .collect();
+
+    log::info!("Uploading {} sounds. Anonymous: {anonymous}", uploads.len());
+    // replies would show who uploaded the anonymous sounds
+    match anonymous {
+        true => ctx.defer_ephemeral().await?,
+        false => ctx.defer().await?,
+    }
+
+    let author_hash = match anonymous {
+        true => Some(privacy::user_author_hash(
+            ctx.data(),
+            ctx.author().id.get(),
+        )?),
+        false => None,
+    };
+    let uploader = ingest::Uploader {
+        id: ctx.author().id.get(),
+        name: ctx.author().name.clone(),
+        global_name: ctx.author().global_name.clone(),
+        guild_id: ctx.guild_id().map(|guild_id| guild_id.get()),
+        review_channel_id: approval::review_channel_id(ctx).await?,
+        author_hash,
+    };
+    let job = Job::Ingest { uploads, uploader };
+    // outcomes of anonymous uploads aren't posted, only shown by `/jobs status`
+    let channel_id = (!anonymous).then(|| ctx.channel_id());
+    let id = ctx.data().jobs.enqueue(
+        ctx.data(),
+        &job,
+        ctx.guild_id(),
+        channel_id,
+        ctx.author().id.get(),
+    )?;
+
+    let lang = i18n::language(ctx);
+    let key = match anonymous {
+        true => "jobs.queued_private",
+        false => "jobs.queued",
+    };
+    poise_check_msg(
+        ctx.send(CreateReply::default().ephemeral(anonymous).content(tr(
+            lang,
+            key,
+            &[("id", &id), ("job", &job.describe(lang))],
+        )))
+        .await,
+    );
+
+    Ok(())
+}
+
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "remove",
+    check = "permissions::can_delete_sounds"
+)]
+pub async fn remove_sound(
+    ctx: PoiseContext<'_>,
+    #[rename = "track"]
+    #[description = "Track to play"]
+    #[autocomplete = "helpers::autocomplete_audio_track_name"]
+    audio_track_name: String,
+) -> PoiseResult {
+    log::info!("Removing audio track - {audio_track_name}");
+
+    let lang = i18n::language(ctx);
+    let confirmation = helpers::confirm(
+        ctx,
+        tr(
+            lang,
+            "sounds.confirm_remove",
+            &[("track", &audio_track_name)],
+        ),
+    )
+    .await?;
+    if confirmation != Confirmation::Confirmed {
+        log::info!("Removing audio track not confirmed - {confirmation:?}");
+        return Ok(());
+    }
+
+    let table = ctx.data().audio_table();
+    let Some(row) = table.find_audio_row(db::UniqueAudioTableCol::from_input(&audio_track_name))
+    else {
+        poise_check_msg(
+            ctx.reply(tr(lang, "play.not_found", &[("track", &audio_track_name)]))
+                .await,
+        );
+        return Ok(());
+    };
+
+    trash::trash_audio_row(
+        &table,
+        &ctx.data().config().trash_dir,
+        &row,
+        chrono::Utc::now(),
+    )?;
+    ctx.data().autocomplete_cache.invalidate();
+
+    poise_check_msg(
+        ctx.reply(tr(lang, "sounds.removed", &[("track", &audio_track_name)]))
+            .await,
+    );
+
+    refresh_published_soundboard(ctx).await;
+
+    log::info!("Audio track removed {audio_track_name}");
+    Ok(())
+}
+
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "archive",
+    check = "permissions::can_delete_sounds"
+)]
+pub async fn archive_sound(
+    ctx: PoiseContext<'_>,
+    #[rename = "track"]
+    #[description = "Track to archive"]
+    #[autocomplete = "helpers::autocomplete_audio_track_name"]
+    audio_track_name: String,
+) -> PoiseResult {
+    set_sound_archived(ctx, audio_track_name, true).await
+}
+
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "unarchive",
+    check = "permissions::can_delete_sounds"
+)]
+pub async fn unarchive_sound(
+    ctx: PoiseContext<'_>,
+    #[rename = "track"]
+    #[description = "Archived track to restore"]
+    #[autocomplete = "helpers::autocomplete_archived_audio_track_name"]
+    audio_track_name: String,
+) -> PoiseResult {
+    set_sound_archived(ctx, audio_track_name, false).await
+}
+
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "restore",
+    check = "permissions::can_delete_sounds"
+)]
+pub async fn restore_sound(
+    ctx: PoiseContext<'_>,
+    #[rename = "track"]
+    #[description = "Removed track to restore"]
+    #[autocomplete = "helpers::autocomplete_trashed_audio_track_name"]
+    audio_track_name: String,
+) -> PoiseResult {
+    log::info!("Restoring removed audio track - {audio_track_name}");
+
+    let lang = i18n::language(ctx);
+    let config = ctx.data().config();
+    let Some(entry) = trash::find_trashed_sound(&config.trash_dir, &audio_track_name) else {
+        poise_check_msg(
+            ctx.reply(tr(
+                lang,
+                "sounds.not_trashed",
+                &[("track", &audio_track_name)],
+            ))
+            .await,
+        );
+        return Ok(());
+    };
+
+    let outcome = trash::restore(&ctx.data().audio_table(), &config.audio_dir, &entry)?;
+    let key = match outcome {
+        RestoreOutcome::Restored => "sounds.restored",
+        RestoreOutcome::NameTaken => "sounds.restore_name_taken",
+        RestoreOutcome::FileExists => "sounds.restore_file_exists",
+        RestoreOutcome::FileMissing => "sounds.restore_file_missing",
+    };
+    if outcome == RestoreOutcome::Restored {
+        ctx.data().autocomplete_cache.invalidate();
+    }
+
+    poise_check_msg(
+        ctx.reply(tr(lang, key, &[("track", &audio_track_name)]))
+            .await,
+    );
+
+    if outcome == RestoreOutcome::Restored {
+        refresh_published_soundboard(ctx).await;
+    }
+
+    Ok(())
+}
+
+#[poise::command(slash_command, prefix_command, rename = "info")]
+pub async fn sound_info(
+    ctx: PoiseContext<'_>,
+    #[rename = "track"]
+    #[description = "Audio track name"]
+    #[autocomplete = "helpers::autocomplete_audio_track_name"]
+    audio_track_name: String,
+) -> PoiseResult {
+    log::info!("Showing audio track info - {audio_track_name}");
+
+    let lang = i18n::language(ctx);
+    let content = match ctx
+        .data()
+        .audio_table()
+        .find_audio_row(db::UniqueAudioTableCol::from_input(&audio_track_name))
+    {
+        Some(row) => helpers::sound_info(lang, &row),
+        None => tr(lang, "play.not_found", &[("track", &audio_track_name)]),
+    };
+
+    poise_check_msg(
+        ctx.send(CreateReply::default().ephemeral(true).content(content))
+            .await,
+    );
+
+    Ok(())
+}
+
+#[poise::command(slash_command, guild_only, rename = "share")]
+pub async fn share_sound(
+    ctx: PoiseContext<'_>,
+    #[rename = "track"]
+    #[description = "Track to share with other servers"]
+    #[autocomplete = "helpers::autocomplete_audio_track_name"]
+    audio_track_name: String,
+) -> PoiseResult {
+    log::info!("Sharing audio track - {audio_track_name}");
+
+    let guild_id = ctx.guild_id().ok_or("No guild id found")?;
+    let lang = i18n::language(ctx);
+    let content = match ctx
+        .data()
+        .audio_table()
+        .find_audio_row(db::UniqueAudioTableCol::from_input(&audio_track_name))
+    {
+        Some(row) => {
+            let code =
+                sharing::share_sound(ctx.data(), row.id, guild_id.get(), ctx.author().id.get())?;
+            tr(
+                lang,
+                "sounds.shared",
+                &[("track", &row.name), ("code", &code)],
+            )
+        }
+        None => tr(lang, "play.not_found", &[("track", &audio_track_name)]),
+    };
+
+    poise_check_msg(
+        ctx.send(CreateReply::default().ephemeral(true).content(content))
+            .await,
+    );
+
+    Ok(())
+}
+
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "import-code",
+    check = "permissions::can_add_sounds"
+)]
+pub async fn import_shared_sound(
+    ctx: PoiseContext<'_>,
+    #[description = "Share code of a sound shared from another server"] code: String,
+) -> PoiseResult {
+    log::info!("Importing shared audio track - {code}");
+    if !check_free_disk_space(ctx).await {
+        return Ok(());
+    }
+
+    let guild_id = ctx.guild_id().ok_or("No guild id found")?;
+    let lang = i18n::language(ctx);
+    let review_channel_id = approval::review_channel_id(ctx).await?;
+    let outcome = sharing::import_shared_sound(
+        ctx.http(),
+        ctx.data(),
+        &code,
+        guild_id.get(),
+        review_channel_id,
+    )
+    .await?;
+    let content = match &outcome {
+        ImportOutcome::Imported(name) => tr(lang, "sounds.added", &[("track", name)]),
+        ImportOutcome::AlreadyAdded(name) => tr(lang, "sounds.duplicate", &[("track", name)]),
+        ImportOutcome::Submitted(name) => tr(lang, "approval.submitted", &[("track", name)]),
+        ImportOutcome::Flagged { name, reason } => tr(
+            lang,
+            "moderation.flagged",
+            &[("track", name), ("reason", reason)],
+        ),
+        ImportOutcome::Refused(reply) => reply.clone(),
+        ImportOutcome::NotFound => tr(lang, "sounds.share_code_not_found", &[("code", &code)]),
+        ImportOutcome::SameGuild => tr(lang, "sounds.share_code_same_guild", &[]),
+        ImportOutcome::TooLong {
+            duration_ms,
+            max_ms,
+        } => tr(
+            lang,
+            "sounds.too_long",
+            &[
+                ("duration", &helpers::format_duration_ms(*duration_ms)),
+                ("max", &helpers::format_duration_ms(*max_ms)),
+            ],
+        ),
+    };
+
+    poise_check_msg(ctx.reply(content).await);
+    if let ImportOutcome::Imported(_) = outcome {
+        refresh_published_soundboard(ctx).await;
+    }
+
+    Ok(())
+}
+
+#[poise::command(slash_command, guild_only, rename = "transfer")]
+pub async fn transfer_sound(
+    ctx: PoiseContext<'_>,
+    #[rename = "track"]
+    #[description = "Track to transfer"]
+    #[autocomplete = "helpers::autocomplete_audio_track_name"]
+    audio_track_name: String,
+    #[description = "New owner of the sound"] user: serenity::all::User,
+) -> PoiseResult {
+    log::info!(
+        "Transferring audio track - {audio_track_name}, User Id: {}",
+        user.id
+    );
+
+    let lang = i18n::language(ctx);
+    let table = ctx.data().audio_table();
+    let Some(row) = table.find_audio_row(db::UniqueAudioTableCol::from_input(&audio_track_name))
+    else {
+        poise_check_msg(
+            ctx.reply(tr(lang, "play.not_found", &[("track", &audio_track_name)]))
+                .await,
+        );
+        return Ok(());
+    };
+
+    if !permissions::check_sound_owner(
+        ctx,
+        row.author_id,
+        row.author_hash.as_deref(),
+        Permission::DeleteSounds,
+    )
+    .await?
+    {
+        return Ok(());
+    }
+
+    let mention = format!("<@{}>", user.id);
+    let reply = if user.bot {
+        tr(lang, "sounds.transfer_bot", &[])
+    } else if permissions::is_sound_owner(row.author_id, user.id.get()) {
+        tr(
+            lang,
+            "sounds.already_owner",
+            &[("track", &row.name), ("user", &mention)],
+        )
+    } else {
+        table.transfer_audio_row(row.id, user.id.get(), &user.name, user.global_name.as_ref())?;
+        tr(
+            lang,
+            "sounds.transferred",
+            &[("track", &row.name), ("user", &mention)],
+        )
+    };
+
+    poise_check_msg(ctx.reply(reply).await);
+
+    Ok(())
+}
+
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "emoji",
+    check = "permissions::can_add_sounds"
+)]
+pub async fn set_sound_emoji(
+    ctx: PoiseContext<'_>,
+    #[rename = "track"]
+    #[description = "Audio track name"]
+    #[autocomplete = "helpers::autocomplete_audio_track_name"]
+    audio_track_name: String,
+    #[description = "Emoji shown on the sound's button. Leave empty to remove"] emoji: Option<
+        String,
+    >,
+) -> PoiseResult {
+    log::info!("Setting audio track emoji - {audio_track_name}, Emoji: {emoji:?}");
+
+    let lang = i18n::language(ctx);
+    let table = ctx.data().audio_table();
+    let Some(row) = table.find_audio_row(db::UniqueAudioTableCol::from_input(&audio_track_name))
+    else {
+        poise_check_msg(
+            ctx.reply(tr(lang, "play.not_found", &[("track", &audio_track_name)]))
+                .await,
+        );
+        return Ok(());
+    };
+
+    let reply = match emoji {
+        None => {
+            table.set_emoji(row.id, None)?;
+            tr(lang, "sounds.emoji_cleared", &[("track", &row.name)])
+        }
+        Some(emoji) => match helpers::parse_emoji(&emoji) {
+            Some(emoji) => {
+                table.set_emoji(row.id, Some(&emoji))?;
+                tr(
+                    lang,
+                    "sounds.emoji_set",
+                    &[("track", &row.name), ("emoji", &emoji)],
+                )
+            }
+            None => {
+                poise_check_msg(
+                    ctx.send(CreateReply::default().ephemeral(true).content(tr(
+                        lang,
+                        "sounds.invalid_emoji",
+                        &[("emoji", &emoji)],
+                    )))
+                    .await,
+                );
+                return Ok(());
+            }
+        },
+    };
+
+    poise_check_msg(ctx.reply(reply).await);
+    refresh_published_soundboard(ctx).await;
+
+    Ok(())
+}
+
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "style",
+    check = "permissions::can_add_sounds"
+)]
+pub async fn set_sound_button_style(
+    ctx: PoiseContext<'_>,
+    #[rename = "track"]
+    #[description = "Audio track name"]
+    #[autocomplete = "helpers::autocomplete_audio_track_name"]
+    audio_track_name: String,
+    #[description = "Color of the sound's button. Leave empty to use the default"] style: Option<
+        SoundButtonStyle,
+    >,
+) -> PoiseResult {
+    log::info!("Setting audio track button style - {audio_track_name}, Style: {style:?}");
+
+    let lang = i18n::language(ctx);
+    let table = ctx.data().audio_table();
+    let Some(row) = table.find_audio_row(db::UniqueAudioTableCol::from_input(&audio_track_name))
+    else {
+        poise_check_msg(
+            ctx.reply(tr(lang, "play.not_found", &[("track", &audio_track_name)]))
+                .await,
+        );
+        return Ok(());
+    };
+
+    table.set_button_style(row.id, style.map(|style| style.code()))?;
+    let reply = match style {
+        Some(style) => tr(
+            lang,
+            "sounds.button_style_set",
+            &[("track", &row.name), ("style", &style.name())],
+        ),
+        None => tr(lang, "sounds.button_style_cleared", &[("track", &row.name)]),
+    };
+
+    poise_check_msg(ctx.reply(reply).await);
+    refresh_published_soundboard(ctx).await;
+
+    Ok(())
+}
+
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "refetch",
+    check = "permissions::can_add_sounds"
+)]
+#[tracing::instrument(name = "/sounds refetch", skip_all)]
+pub async fn refetch_sound(
+    ctx: PoiseContext<'_>,
+    #[rename = "track"]
+    #[description = "Audio track name"]
+    #[autocomplete = "helpers::autocomplete_audio_track_name"]
+    audio_track_name: String,
+) -> PoiseResult {
+    log::info!("Re-fetching audio track - {audio_track_name}");
+
+    let lang = i18n::language(ctx);
+    let table = ctx.data().audio_table();
+    let Some(row) = table.find_audio_row(db::UniqueAudioTableCol::from_input(&audio_track_name))
+    else {
+        poise_check_msg(
+            ctx.reply(tr(lang, "play.not_found", &[("track", &audio_track_name)]))
+                .await,
+        );
+        return Ok(());
+    };
+
+    let Some(source_url) = row.source_url.clone() else {
+        reply_ephemeral(
+            ctx,
+            tr(lang, "sounds.refetch_no_source", &[("track", &row.name)]),
+        )
+        .await;
+        return Ok(());
+    };
+
+    if let Err(refusal) = download_policy::check_url(&ctx.data().config(), &source_url) {
+        reply_ephemeral(ctx, refusal.message(lang)).await;
+        return Ok(());
+    }
+
+    if !check_free_disk_space(ctx).await {
+        return Ok(());
+    }
+
+    send_ingest_queued_reply(ctx).await;
+    let permit = ctx.data().ingest_limiter.acquire().await;
+
+    let progress_reply = ctx
+        .send(CreateReply::default().ephemeral(true).content(tr(
+            lang,
+            "sounds.downloading",
+            &[("track", &row.name), ("progress", &"")],
+        )))
+        .await?;
+    let edit_progress = |content: String| {
+        let progress_reply = &progress_reply;
+        async move {
+            progress_reply
+                .edit(ctx, CreateReply::default().content(content))
+                .await
+                .log_err_msg("Failed to edit refetch sound progress reply")
+                .ok();
+        }
+    };
+
+    let download_client = http_client::download_client(&ctx.data().config())?;
+    let temp_audio_file =
+        audio::download_audio_url_temp_with_progress(&download_client, &source_url, |progress| {
+            edit_progress(tr(
+                lang,
+                "sounds.downloading",
+                &[("track", &row.name), ("progress", &progress)],
+            ))
+        })
+        .await?;
+
+    edit_progress(tr(lang, "sounds.validating", &[("track", &row.name)])).await;
+    let track_info = audio::AudioFileValidator::default()
+        .max_audio_duration(
+            ctx.data()
+                .max_audio_duration(ctx.guild_id().map(|guild_id| guild_id.get())),
+        )
+        .reject_uuid_files(false)
+        .validate(&temp_audio_file)?;
+    drop(permit);
+
+    // new file replaces the sound's file once its row points at it
+    let placed_file = ctx.data().move_file_to_audio_dir(&temp_audio_file)?;
+    let audio_file = audio::AudioFile::new(placed_file.to_path_buf());
+    table.replace_audio_file(
+        row.id,
+        &audio_file,
+        track_info.duration_ms(),
+        track_info.file_size,
+    )?;
+    placed_file.keep();
+    if audio_file != row.audio_file {
+        row.audio_file.delete();
+    }
+
+    edit_progress(tr(
+        lang,
+        "sounds.refetched",
+        &[
+            ("track", &row.name),
+            (
+                "duration",
+                &helpers::format_duration_ms(track_info.duration_ms()),
+            ),
+            ("size", &helpers::format_file_size(track_info.file_size)),
+        ],
+    ))
+    .await;
+    refresh_published_soundboard(ctx).await;
+
+    Ok(())
+}
+
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "category",
+    check = "permissions::can_add_sounds"
+)]
+pub async fn set_sound_category(
+    ctx: PoiseContext<'_>,
+    #[rename = "track"]
+    #[description = "Audio track name"]
+    #[autocomplete = "helpers::autocomplete_audio_track_name"]
+    audio_track_name: String,
+    #[description = "Soundboard section the sound is shown in. Leave empty to remove"]
+    #[autocomplete = "helpers::autocomplete_category"]
+    category: Option<String>,
+) -> PoiseResult {
+    log::info!("Setting audio track category - {audio_track_name}, Category: {category:?}");
+
+    let lang = i18n::language(ctx);
+    let Some(row) = ctx
+        .data()
+        .audio_table()
+        .find_audio_row(db::UniqueAudioTableCol::from_input(&audio_track_name))
+    else {
+        poise_check_msg(
+            ctx.reply(tr(lang, "play.not_found", &[("track", &audio_track_name)]))
+                .await,
+        );
+        return Ok(());
+    };
+
+    let reply = match category {
+        None => {
+            ctx.data().audio_table().set_category(row.id, None)?;
+            tr(lang, "sounds.category_cleared", &[("track", &row.name)])
+        }
+        Some(category) => match ctx.data().categories_table().find_category(&category)? {
+            Some(category) => {
+                ctx.data()
+                    .audio_table()
+                    .set_category(row.id, Some(category.id))?;
+                tr(
+                    lang,
+                    "sounds.category_set",
+                    &[("track", &row.name), ("category", &category.label())],
+                )
+            }
+            None => {
+                let content = tr(lang, "categories.not_found", &[("category", &category)]);
+                reply_ephemeral(ctx, content).await;
+                return Ok(());
+            }
+        },
+    };
+
+    poise_check_msg(ctx.reply(reply).await);
+    refresh_published_soundboard(ctx).await;
+
+    Ok(())
+}
+
+/// Archived sounds are hidden from the soundboard and autocomplete, but not deleted
+async fn set_sound_archived(
+    ctx: PoiseContext<'_>,
+    audio_track_name: String,
+    archived: bool,
+) -> PoiseResult {
+    log::info!("Setting audio track archived - {audio_track_name}, Archived: {archived}");
+
+    let lang = i18n::language(ctx);
+    let table = ctx.data().audio_table();
+    let Some(row) = table.find_audio_row(db::UniqueAudioTableCol::from_input(&audio_track_name))
+    else {
+        poise_check_msg(
+            ctx.reply(tr(lang, "play.not_found", &[("track", &audio_track_name)]))
+                .await,
+        );
+        return Ok(());
+    };
+
+    let key = match (archived, row.archived_at.is_some()) {
+        (true, true) => "sounds.already_archived",
+        (false, false) => "sounds.not_archived",
+        (true, false) => "sounds.archived",
+        (false, true) => "sounds.unarchived",
+    };
+    let changed = archived != row.archived_at.is_some();
+    if changed {
+        table.set_archived(row.id, archived)?;
+        ctx.data().autocomplete_cache.invalidate();
+    }
+
+    poise_check_msg(ctx.reply(tr(lang, key, &[("track", &row.name)])).await);
+
+    if changed {
+        refresh_published_soundboard(ctx).await;
+    }
+
+    Ok(())
+}
+
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "display",
+    check = "channel_restrictions::can_use_soundboard"
+)]
+pub async fn display_sounds(ctx: PoiseContext<'_>) -> PoiseResult {
+    log::info!("List sounds buttons as ActionRows grid...");
+
+    let lang = i18n::language(ctx);
+    poise_check_msg(ctx.reply(tr(lang, "sounds.displaying", &[])).await);
+
+    // one run of messages per soundboard section, the first under the section's header
+    let sections = soundboard::load_sections(ctx.data(), None)?;
+    let ranked_ids = SoundboardOrder::of_guild(ctx.data(), ctx.guild_id())
+        .ranked_ids(ctx.data(), ctx.guild_id())?;
+    for section in &sections {
+        // pages are queried off the runtime, one message of buttons each
+        let pages = db::AudioTablePaginator::builder(ctx.data().db_connection())
+            .page_limit(vars::ACTION_ROWS_LIMIT)
+            .category(Some(section.filter()))
+            .ranked_first(ranked_ids.clone())
+            .build()
+            .into_stream()
+            .chunks(vars::ACTION_ROWS_LIMIT as usize)
+            .enumerate();
+        let mut pages = std::pin::pin!(pages);
+
+        while let Some((index, audio_rows)) = pages.next().await {
+            let audio_rows = audio_rows
+                .into_iter()
+                .collect::<Result<Vec<_>, _>>()
+                .log_err()?;
+
+            // ActionRows: Have a 5x5 grid limit
+            // (https://discordjs.guide/message-components/action-rows.html#action-rows)
+            let btn_grid: Vec<_> = audio_rows
+                .chunks(5)
+                .map(|row| helpers::make_action_row(row, ctx.guild_id()))
+                .collect();
+            let mut builder = CreateMessage::new().components(btn_grid);
+            if let (0, Some(header)) = (index, section.header(lang, sections.len())) {
+                builder = builder.content(format!("### {header}"));
+            }
+            check_msg(ctx.channel_id().send_message(&ctx.http(), builder).await);
+        }
+    }
+
+    Ok(())
+}
+
+#[derive(Debug, poise::Modal)]
+#[name = "Edit Sound"]
+struct EditSoundModal {
+    #[name = "Name"]
+    #[min_length = 3] // No length restriction by default (so, 1-4000 chars)
+    #[max_length = 80] // Same as max sound name len (crate::vars::SOUND_NAME_MAX_LEN)
+    name: String,
+    #[name = "Tags"]
+    #[max_length = 1024]
+    tags: Option<String>,
+}
+
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "edit",
+    check = "permissions::can_add_sounds"
+)]
+pub async fn edit_sound(
+    ctx: PoiseAppContext<'_>,
+    #[description = "Audio track to edit"]
+    #[rename = "track"]
+    #[autocomplete = "helpers::autocomplete_audio_track_name"]
+    audio_track_name: String,
+) -> PoiseResult {
+    log::info!("Editing audio track - {audio_track_name}");
+
+    let table = ctx.data().audio_table();
+
+    let mut row = table
+        .find_audio_row(db::UniqueAudioTableCol::from_input(&audio_track_name))
+        .ok_or(format!("Unable to locate audio track '{audio_track_name}'"))
+        .log_err()?;
+
+    let data = EditSoundModal::execute_with_defaults(
+        ctx,
+        EditSoundModal {
+            name: audio_track_name.clone(),
+            tags: Some(row.tags.to_string()),
+        },
+    )
+    .await?;
+
+    match data {
+        Some(data) => {
+            log::debug!("{data:?}");
+
+            let guild_id = ctx.guild_id().map(|guild_id| guild_id.get());
+            if let Err(reply) = blocklist::check_text(ctx.data(), guild_id, &data.name) {
+                poise_check_msg(
+                    ctx.send(CreateReply::default().ephemeral(true).content(reply))
+                        .await,
+                );
+                return Ok(());
+            }
+
+            let tags: Tags = match data.tags {
+                Some(val) => Tags::from(val),
                 None => Tags::new(),
             };
 
-            row.name = data.name.clone();
+            let Some(name) = validate_sound_name(ctx.into(), &data.name).await else {
+                return Ok(());
+            };
+
+            row.name = name;
             row.tags = tags;
 
             table.update_audio_row(&row).log_err()?;
+            ctx.data().autocomplete_cache.invalidate();
+            refresh_published_soundboard(ctx.into()).await;
         }
         None => log::info!("No audo track to update"),
     }
@@ -422,7 +4860,12 @@ pub async fn edit_sound(
     Ok(())
 }
 
-#[poise::command(slash_command, guild_only, rename = "join-audio")]
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "join-audio",
+    check = "permissions::can_manage_settings"
+)]
 pub async fn set_join_audio(
     ctx: PoiseContext<'_>,
     #[description = "Audio track name"]
@@ -434,23 +4877,47 @@ pub async fn set_join_audio(
 
     let table = ctx.data().settings_table();
     let mut settings = table.get_settings().log_err()?;
+    let lang = i18n::language(ctx);
 
     match audio_track_name.as_str() {
         "NONE" => {
             settings.join_audio = None;
             table.update_settings(&settings).log_err()?;
-            poise_check_msg(ctx.reply(format!("Bot join audio disabled")).await);
+            poise_check_msg(
+                ctx.reply(tr(lang, "settings.join_audio_disabled", &[]))
+                    .await,
+            );
         }
         val => {
-            settings.join_audio = Some(val.into());
+            // stored by id, so the sound is still found after renames
+            let Some(row) = ctx
+                .data()
+                .audio_table()
+                .find_audio_row(db::UniqueAudioTableCol::from_input(val))
+            else {
+                poise_check_msg(
+                    ctx.reply(tr(lang, "play.not_found", &[("track", &val)]))
+                        .await,
+                );
+                return Ok(());
+            };
+            settings.join_audio = Some(db::UniqueAudioTableCol::Id(row.id).to_input());
             table.update_settings(&settings).log_err()?;
-            poise_check_msg(ctx.reply(format!("Bot join audio set to {val}")).await);
+            poise_check_msg(
+                ctx.reply(tr(lang, "settings.join_audio_set", &[("track", &row.name)]))
+                    .await,
+            );
         }
     }
     Ok(())
 }
 
-#[poise::command(slash_command, guild_only, rename = "leave-audio")]
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "leave-audio",
+    check = "permissions::can_manage_settings"
+)]
 pub async fn set_leave_audio(
     ctx: PoiseContext<'_>,
     #[description = "Audio track name"]
@@ -462,46 +4929,189 @@ pub async fn set_leave_audio(
 
     let table = ctx.data().settings_table();
     let mut settings = table.get_settings().log_err()?;
+    let lang = i18n::language(ctx);
 
     match audio_track_name.as_str() {
         "NONE" => {
             settings.leave_audio = None;
             table.update_settings(&settings).log_err()?;
-            poise_check_msg(ctx.reply(format!("Bot leave audio disabled")).await);
+            poise_check_msg(
+                ctx.reply(tr(lang, "settings.leave_audio_disabled", &[]))
+                    .await,
+            );
         }
         val => {
-            settings.leave_audio = Some(val.into());
+            // stored by id, so the sound is still found after renames
+            let Some(row) = ctx
+                .data()
+                .audio_table()
+                .find_audio_row(db::UniqueAudioTableCol::from_input(val))
+            else {
+                poise_check_msg(
+                    ctx.reply(tr(lang, "play.not_found", &[("track", &val)]))
+                        .await,
+                );
+                return Ok(());
+            };
+            settings.leave_audio = Some(db::UniqueAudioTableCol::Id(row.id).to_input());
             table.update_settings(&settings).log_err()?;
-            poise_check_msg(ctx.reply(format!("Bot leave audio set to {val}")).await);
+            poise_check_msg(
+                ctx.reply(tr(
+                    lang,
+                    "settings.leave_audio_set",
+                    &[("track", &row.name)],
+                ))
+                .await,
+            );
         }
     }
 
     Ok(())
 }
 
-#[poise::command(slash_command, guild_only, rename = "help")]
+#[poise::command(slash_command, guild_only, rename = "favorite")]
+pub async fn toggle_favorite(
+    ctx: PoiseContext<'_>,
+    #[description = "Audio track name"]
+    #[rename = "track"]
+    #[autocomplete = "helpers::autocomplete_audio_track_name"]
+    audio_track_name: String,
+) -> PoiseResult {
+    log::info!("Toggling favorite audio track - {audio_track_name}");
+
+    let row = ctx
+        .data()
+        .audio_table()
+        .find_audio_row(db::UniqueAudioTableCol::from_input(&audio_track_name))
+        .ok_or(format!("Unable to locate audio track '{audio_track_name}'"))
+        .log_err()?;
+
+    let is_favorite = ctx
+        .data()
+        .favorites_table()
+        .toggle_favorite(ctx.author().id.get(), row.id)?;
+    ctx.data().autocomplete_cache.invalidate();
+
+    let key = match is_favorite {
+        true => "favorites.added",
+        false => "favorites.removed",
+    };
+    poise_check_msg(
+        ctx.reply(tr(
+            i18n::language(ctx),
+            key,
+            &[("track", &audio_track_name)],
+        ))
+        .await,
+    );
+
+    Ok(())
+}
+
+#[poise::command(slash_command, prefix_command, rename = "help")]
 pub async fn display_help(ctx: PoiseContext<'_>) -> PoiseResult {
     let version = vars::VERSION;
-    let prefix = ctx.data().config.command_prefix.as_str();
+    let prefix = ctx.data().command_prefix(ctx.guild_id());
     let text = format!(
         "\
 # Soundboard Bot v{version}
 [GitHub Repo](https://github.com/krywicki/discord-soundboard-bot)
 Bot for playing sounds in voice chat.
 ## Slash Commands
-- `/play {{track}}` - Play sound track in voice channel
+- `/play {{track}} {{loop}} {{priority}} {{silent}}` - Play sound track in voice channel, optionally looped a number of times or until stopped (`infinite`), with `priority` over the sounds playing, or `silent` without posting a message. Separate names with commas to play several in sequence, or enter sound ids like `#12`
+- `/play-id {{id}}` - Play sound by its id, as shown by `/sounds info`
+- `/playlist quickplay {{sound1}} {{sound2}} {{sound3}}` - Play sounds one after another
+- `/record start|stop|list` - Record sounds played in this server as a session
+- `/record play {{session}}` - Replay a recorded session with its original timing
+- `/random {{tag}}` - Play a random sound track in voice channel, optionally with tag
+- `/search {{query}} {{include-archived}}` - Search sounds by name or tags
+- `/replay {{mine}}` - Replay the last sound played, or the last sound you played
+- `/stop` - Stop sounds playing in voice channel
 - `/sounds`
   - `/sounds add` - Opens form to add sounds
-  - `/sounds remove {{track}}` - Removes sound
+  - `/sounds upload {{file-1}} ... {{file-5}} {{anonymous}}` - Add up to 5 attached mp3 files at once, optionally without your name
+  - `/sounds remove {{track}}` - Removes sound, moving it to the trash
+  - `/sounds archive {{track}}` - Hides sound from the soundboard without deleting it
+  - `/sounds unarchive {{track}}` - Restores archived sound
+  - `/sounds restore {{track}}` - Restores removed sound from the trash
+  - `/sounds emoji {{track}} {{emoji}}` - Show an emoji on the sound's button
+  - `/sounds category {{track}} {{category}}` - Show a sound in a soundboard category. Leave category empty to remove it
+  - `/sounds style {{track}} {{style}}` - Color the sound's button, e.g. red for loud sounds
+  - `/sounds refetch {{track}}` - Download a sound again from the link it was added from
+  - `/sounds info {{track}}` - Show a sound's tags, uploader, plays, duration, file size and source
+  - `/sounds share {{track}}` - Get a share code of a sound, to import it in another server
+  - `/sounds import-code {{code}}` - Copy a sound shared from another server
+  - `/sounds transfer {{track}} {{user}}` - Reassigns sound to another member
   - `/sounds edit {{track}}` - Opens form to edit sound track
   - `/sounds display` - Displays a button grid of sounds that can be played in voice channel
   - `/sounds join-audio {{track}}` - Set/Unset sound track to play when bot joins voice channel
   - `/sounds leave-audio {{track}}` - Set/Unset sound track to play when bot leaves voice channel
+  - `/sounds favorite {{track}}` - Add/Remove sound track from your favorites
+- `/soundboard`
+  - `/soundboard view {{tag}}` - Display a paged soundboard, optionally only sounds with tag. Switch tags with the select menu
+  - `/soundboard publish {{channel}}` - Pin a soundboard in text channel, kept up to date as sounds change
+  - `/soundboard mine` - Privately display your uploads and favorites, most played first
+- `/stats`
+  - `/stats history {{track}} {{period}}` - Chart plays per day or week, optionally of one sound
+  - `/stats heatmap` - Plays by hour of day and day of week
+  - `/stats disk` - Free disk space and size of the sounds
+  - `/stats commands` - How often each command is used, its error rate and latency (bot owners)
+- `/trending` - Sounds played the most lately
+- `/forgotten` - Sounds not played in the longest time, with play buttons
+- `/history {{user}}` - Sounds you or another member played lately, with replay buttons
+- `/settings`
+  - `/settings language {{language}}` - Set language of bot replies. Defaults to the server's Discord locale
+  - `/settings prefix {{prefix}}` - Set prefix of prefix commands for this server
+  - `/settings sotd {{channel}} {{time}} {{voice-channel}} {{mode}}` - Post (and play) a sound of the day
+  - `/settings permissions grant {{permission}} {{role}}` - Only allow roles to add/delete sounds, manage settings or use text-to-speech
+  - `/settings permissions revoke {{permission}} {{role}}` - Revoke permission from role. Permissions without roles are open to everyone
+  - `/settings permissions list` - List roles granted each permission
+  - `/settings blocklist add {{pattern}} {{regex}}` - Block word (or regex) in sound names and text-to-speech
+  - `/settings blocklist remove {{pattern}}` - Unblock word (or regex)
+  - `/settings blocklist list` - List blocked words and regexes
+  - `/settings native-sounds record {{enabled}}` - Record plays of Discord soundboard sounds mapped to bot sounds
+  - `/settings native-sounds map {{sound-id}} {{track}}` - Count Discord soundboard sound as bot sound
+  - `/settings native-sounds unmap {{sound-id}}` - Unmap Discord soundboard sound
+  - `/settings native-sounds list` - List Discord soundboard sounds mapped to bot sounds
+  - `/settings channels allow {{channel}}` - Only play sounds in allowed voice channels and use soundboard commands in allowed text channels
+  - `/settings channels disallow {{channel}}` - Remove channel from allowed channels
+  - `/settings channels list` - List allowed voice and text channels
+  - `/settings auto-archive {{months}}` - Archive sounds unused for months
+  - `/settings approval {{review-channel}}` - Review new sounds in a channel before they're added
+  - `/settings digest {{channel}}` - Post a weekly digest of the soundboard's activity
+  - `/settings priority {{mode}}` - Stop or pause sounds playing when a sound is played with priority
+  - `/settings autocomplete {{limit}} {{min-chars}}` - How many sounds are suggested as you type a sound name, and after how many characters they're searched
+  - `/settings silent {{enabled}}` - Only play sounds, without posting now playing messages or `/play` replies
+  - `/settings play-history {{public}}` - Let members see each other's `/history`
+  - `/settings loudness {{action}}` - Allow, turn down, review or reject sounds that are too loud
+  - `/settings max-length {{seconds}}` - Max length of sounds added to this server
+  - `/settings soundboard-order {{order}}` - Show the oldest or trending sounds first on soundboards
+  - `/settings export` - Download this server's settings as a file
+  - `/settings import {{file}}` - Replace this server's settings with an exported settings file
+- `/admin`
+  - `/admin scan` - Scan local audio directory and add sound tracks not in database
+  - `/admin resync-commands` - Re-register slash commands in every server (bot owners only)
+  - `/admin prune-suggestions {{months}}` - List unused sounds to archive or keep
+  - `/admin bulk delete {{tag}} {{author}} {{older-than}}` - Delete all sounds matching filter, after previewing them
+  - `/admin bulk retag {{new-tags}} {{tag}} {{author}} {{older-than}}` - Replace tags of all sounds matching filter, after previewing them
+  - `/admin categories add|edit|remove|list` - Manage soundboard categories, their emoji and order
+- `/packs browse` - List sound packs that can be installed
+- `/packs install {{pack}}` - Add the sounds of a sound pack to this server (administrators only)
+- `/privacy forget-me` - Delete your favorites and play history, and remove your name from sounds you added
+- `/owner guilds|leave-guild|sql|backup|purge-user|search|purge-by-hash|reload-config` - Bot maintenance (bot owners only)
+- `/jobs status {{id}}` - Status of background jobs (scans, sound pack installs, backups)
+- `/debug` - Runtime diagnostics and recent errors (bot owners only)
+- `/doctor` - Check token, intents, voice permissions, database, disk space and tools (bot owners only)
 ## Prefix Commands
+- `{prefix}play {{track}}` - Play sound track in voice channel
+- `{prefix}random` - Play a random sound track in voice channel
+- `{prefix}replay` - Replay the last sound played
+- `{prefix}stop` - Stop sounds playing in voice channel
 - `{prefix}join` - Have bot join the voice channel
 - `{prefix}leave` - Have bot leave the voice channel
 - `{prefix}register` - [`dev use`] Register/UnRegister slash commands for guild or globally
 - `{prefix}scan` - [`dev use`] Scan local audio directory and add sound tracks not in database
+- `{prefix}search {{query}}`, `{prefix}sounds info {{track}}`, `{prefix}sounds help` - Browse sounds, also in DMs with the bot
 "
     );
 
@@ -1,18 +1,84 @@
+use std::future::Future;
+use std::ops::Deref;
 use std::path;
+use std::sync::{Arc, RwLock};
 
-use crate::audio::{AudioDir, AudioFile};
+use serenity::all::GuildId;
+
+use crate::audio::{AudioDir, AudioFile, AudioFileValidator, RemoveAudioFile};
+use crate::cache::AutocompleteCache;
+use crate::checksums::ChecksumCache;
 use crate::commands::PoiseError;
 use crate::config::Config;
-use crate::db::{AudioTable, DbConnection, SettingsTable};
+use crate::db::{
+    self, AudioTable, AudioTableRowInsert, BlocklistTable, CategoriesTable,
+    ChannelRestrictionsTable, CommandStatsTable, DbConnection, FavoritesTable, GuildSettingsTable,
+    JobsTable, NativeSoundsTable, PendingSoundsTable, PermissionsTable, PlayEventsTable,
+    SessionsTable, SettingsTable, ShareCodesTable, SoundName, SoundboardMessagesTable,
+    SoundboardPagesTable, Tags, UserPlaysTable,
+};
+use crate::helpers::PendingConfirmations;
+use crate::ingest::{self, IngestProgress};
+use crate::jobs::Jobs;
+use crate::moderation::Moderator;
+use crate::now_playing::NowPlaying;
+use crate::playback::{LastPlayed, PlaybackQueue};
+use crate::rate_limit::{IngestLimiter, PlayRateLimiter};
+use crate::temp_files::TempFileGuard;
+use crate::vars;
+
+/// Bot state shared by commands, event handlers and background jobs. Cheap to clone, so jobs
+/// can hold on to it
+#[derive(Clone)]
+pub struct UserData(Arc<UserDataFields>);
+
+impl From<UserDataFields> for UserData {
+    fn from(fields: UserDataFields) -> Self {
+        Self(Arc::new(fields))
+    }
+}
+
+impl Deref for UserData {
+    type Target = UserDataFields;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
 
-pub struct UserData {
-    pub config: Config,
+pub struct UserDataFields {
+    /// Reloadable with `/owner reload-config`, see `UserData::config`
+    pub config: RwLock<Config>,
     pub db_pool: r2d2::Pool<r2d2_sqlite::SqliteConnectionManager>,
+    pub autocomplete_cache: AutocompleteCache,
+    pub checksum_cache: ChecksumCache,
+    pub play_rate_limiter: RwLock<PlayRateLimiter>,
+    pub ingest_limiter: IngestLimiter,
+    pub pending_confirmations: PendingConfirmations,
+    pub now_playing: NowPlaying,
+    pub last_played: LastPlayed,
+    pub playback_queue: PlaybackQueue,
+    pub jobs: Jobs,
+    /// Checks uploaded sounds before they're added (see `moderation`)
+    pub moderator: Arc<dyn Moderator>,
+    /// Client of the bot's own HTTP requests, through the configured proxy (see `http_client`)
+    pub http_client: reqwest::Client,
 }
 
 impl UserData {
+    /// Current config
+    pub fn config(&self) -> Config {
+        self.config.read().unwrap().clone()
+    }
+
+    /// Replace config, resetting play rate limits to the new config's limits
+    pub fn reload_config(&self, config: Config) {
+        *self.play_rate_limiter.write().unwrap() = PlayRateLimiter::new(&config);
+        *self.config.write().unwrap() = config;
+    }
+
     pub fn read_audio_dir(&self) -> AudioDir {
-        read_audio_dir(&self.config.audio_dir)
+        read_audio_dir(&self.config().audio_dir)
     }
 
     pub fn db_connection(&self) -> DbConnection {
@@ -29,14 +95,237 @@ impl UserData {
         SettingsTable::new(self.db_connection())
     }
 
-    /// Attempts to move file to audio dir. Will attempt copy if move fails
-    /// Moves can fail if target file and destination audio directory are on separate partitions of file systems
+    pub fn favorites_table(&self) -> FavoritesTable {
+        FavoritesTable::new(self.db_connection())
+    }
+
+    pub fn soundboard_messages_table(&self) -> SoundboardMessagesTable {
+        SoundboardMessagesTable::new(self.db_connection())
+    }
+
+    pub fn soundboard_pages_table(&self) -> SoundboardPagesTable {
+        SoundboardPagesTable::new(self.db_connection())
+    }
+
+    pub fn user_plays_table(&self) -> UserPlaysTable {
+        UserPlaysTable::new(self.db_connection())
+    }
+
+    pub fn permissions_table(&self) -> PermissionsTable {
+        PermissionsTable::new(self.db_connection())
+    }
+
+    pub fn blocklist_table(&self) -> BlocklistTable {
+        BlocklistTable::new(self.db_connection())
+    }
+
+    pub fn guild_settings_table(&self) -> GuildSettingsTable {
+        GuildSettingsTable::new(self.db_connection())
+    }
+
+    pub fn native_sounds_table(&self) -> NativeSoundsTable {
+        NativeSoundsTable::new(self.db_connection())
+    }
+
+    pub fn channel_restrictions_table(&self) -> ChannelRestrictionsTable {
+        ChannelRestrictionsTable::new(self.db_connection())
+    }
+
+    pub fn play_events_table(&self) -> PlayEventsTable {
+        PlayEventsTable::new(self.db_connection())
+    }
+
+    pub fn pending_sounds_table(&self) -> PendingSoundsTable {
+        PendingSoundsTable::new(self.db_connection())
+    }
+
+    pub fn share_codes_table(&self) -> ShareCodesTable {
+        ShareCodesTable::new(self.db_connection())
+    }
+
+    pub fn sessions_table(&self) -> SessionsTable {
+        SessionsTable::new(self.db_connection())
+    }
+
+    pub fn categories_table(&self) -> CategoriesTable {
+        CategoriesTable::new(self.db_connection())
+    }
+
+    pub fn command_stats_table(&self) -> CommandStatsTable {
+        CommandStatsTable::new(self.db_connection())
+    }
+
+    pub fn jobs_table(&self) -> JobsTable {
+        JobsTable::new(self.db_connection())
+    }
+
+    /// Prefix command prefix of guild, falling back to `Config::command_prefix`
+    pub fn command_prefix(&self, guild_id: Option<GuildId>) -> String {
+        guild_id
+            .and_then(|guild_id| {
+                self.guild_settings_table()
+                    .get_guild_settings(guild_id.get())
+                    .log_err()
+                    .ok()
+            })
+            .and_then(|settings| settings.prefix)
+            .unwrap_or_else(|| self.config().command_prefix)
+    }
+
+    /// Max length of sounds added to guild: its `/settings max-length`, capped by
+    /// `Config::max_audio_file_duration`. Sounds not added to a guild only have the cap
+    pub fn max_audio_duration(&self, guild_id: Option<u64>) -> std::time::Duration {
+        let ceiling = self.config().max_audio_file_duration;
+        guild_id
+            .and_then(|guild_id| {
+                self.guild_settings_table()
+                    .get_guild_settings(guild_id)
+                    .log_err()
+                    .ok()
+            })
+            .and_then(|settings| settings.max_duration_ms)
+            .map(|max_ms| std::time::Duration::from_millis(max_ms).min(ceiling))
+            .unwrap_or(ceiling)
+    }
+
+    /// Walks the audio dir and inserts rows for audio files not yet in the audio table.
+    /// The file name is used as the default track name. Returns number of tracks added
+    ///
+    /// Audio files are probed concurrently (see `Config::ingest_concurrency`) and `on_progress`
+    /// is awaited periodically while probing
+    pub async fn sync_audio_dir<F, Fut>(&self, on_progress: F) -> Result<usize, PoiseError>
+    where
+        F: FnMut(IngestProgress) -> Fut,
+        Fut: Future<Output = ()>,
+    {
+        log::info!("Syncing audio dir...");
+
+        let mut audio_files: Vec<AudioFile> = self.read_audio_dir().into_iter().collect();
+
+        // ignore audio files already in database, archived, pending approval or not
+        for audio_file in self.pending_sounds_table().pending_audio_files()? {
+            audio_files.remove_audio_file(&audio_file);
+        }
+        {
+            let paginator = db::AudioTablePaginator::builder(self.db_connection())
+                .include_archived(true)
+                .build();
+            for page in paginator {
+                let page = page.log_err()?;
+                for row in page {
+                    audio_files.remove_audio_file(&row.audio_file);
+                }
+            }
+        }
+
+        log::info!(
+            "Sync found {} untracked audio files to probe",
+            audio_files.len()
+        );
+
+        // probe remaining audio files (codec type, length, etc)
+        let config = self.config();
+        let audio_validator =
+            AudioFileValidator::new().max_audio_duration(config.max_audio_file_duration);
+        let audio_files = ingest::validate_audio_files(
+            audio_files,
+            audio_validator,
+            config.ingest_concurrency,
+            on_progress,
+        )
+        .await;
+
+        let new_audio_rows = audio_files
+            .into_iter()
+            .filter_map(|(audio_file, track_info)| {
+                let name = SoundName::new(audio_file.audio_title())
+                    .log_err_msg(format!(
+                        "Skipping audio file with invalid name - {}",
+                        audio_file.to_string_lossy()
+                    ))
+                    .ok()?;
+
+                Some(AudioTableRowInsert {
+                    name,
+                    tags: Tags::new(),
+                    audio_file,
+                    created_at: chrono::Utc::now(),
+                    author_id: None,
+                    author_name: None,
+                    author_global_name: None,
+                    author_hash: None,
+                    guild_id: None,
+                    duration_ms: Some(track_info.duration_ms()),
+                    file_size: Some(track_info.file_size),
+                    source_url: None,
+                })
+            });
+
+        let new_audio_rows: Vec<_> = new_audio_rows.collect();
+        let rebuild_fts = new_audio_rows.len() >= vars::FTS_REBUILD_MIN_ROWS;
+        let inserted = self
+            .audio_table()
+            .insert_many(new_audio_rows, rebuild_fts)?;
+
+        if inserted > 0 {
+            self.autocomplete_cache.invalidate();
+        }
+
+        log::info!("Sync complete - added {inserted} new audio files");
+        Ok(inserted)
+    }
+
+    /// Deletes rows of sounds whose audio file was deleted from the audio dir by the host.
+    /// Archived sounds are kept, as quarantined sounds (see `checksums`) are archived without
+    /// their file. Nothing is deleted while the audio dir itself is missing, e.g. when its
+    /// volume isn't mounted. Returns number of rows deleted
+    pub fn remove_missing_audio_rows(&self) -> Result<usize, PoiseError> {
+        let audio_dir = self.config().audio_dir;
+        if !audio_dir.is_dir() {
+            log::warn!("Audio dir is missing, not removing sounds of missing audio files");
+            return Ok(0);
+        }
+
+        let mut missing = vec![];
+        let paginator = db::AudioTablePaginator::builder(self.db_connection()).build();
+        for page in paginator {
+            let page = page.log_err()?;
+            missing.extend(page.into_iter().filter(|row| {
+                row.audio_file.parent() == Some(audio_dir.as_path()) && !row.audio_file.exists()
+            }));
+        }
+
+        let table = self.audio_table();
+        for row in &missing {
+            log::info!("Removing sound of missing audio file. Name: {}", row.name);
+            table.remove_audio_row(row.id)?;
+        }
+
+        if !missing.is_empty() {
+            self.autocomplete_cache.invalidate();
+        }
+        Ok(missing.len())
+    }
+
+    /// Add untracked audio files of the audio dir and delete sounds whose file is gone.
+    /// Returns number of sounds added and removed
+    pub async fn reconcile_audio_dir(&self) -> Result<(usize, usize), PoiseError> {
+        let added = self.sync_audio_dir(|_| async {}).await?;
+        let removed = self.remove_missing_audio_rows()?;
+        Ok((added, removed))
+    }
+
+    /// Move file into the audio dir atomically. Files on another filesystem are copied next to
+    /// their destination first and then renamed into place, so the audio dir never has partial
+    /// files. The placed file is deleted when the returned guard is dropped, so it should only be
+    /// kept (`TempFileGuard::keep`) once its audio row is inserted
+    #[tracing::instrument(skip_all)]
     pub fn move_file_to_audio_dir(
         &self,
         path: impl AsRef<path::Path>,
-    ) -> Result<AudioFile, PoiseError> {
+    ) -> Result<TempFileGuard, PoiseError> {
         let target_file = path.as_ref();
-        let audio_dir = &self.config.audio_dir;
+        let audio_dir = self.config().audio_dir;
 
         log::info!(
             "Move file: {} to audio dir: {}",
@@ -58,24 +347,26 @@ impl UserData {
             .log_err()?;
         let dest_file = audio_dir.join(target_file_name);
 
-        match std::fs::rename(target_file, &dest_file) {
-            Ok(_) => Ok(AudioFile::new(dest_file)),
-            Err(err) => {
-                log::error!(
-                    "Failed to move target file to audio dir - {err}. Attempting copy instead."
-                );
-                std::fs::copy(target_file, &dest_file)
-                    .log_err_msg("Failed to copy target file to audio dir")?;
-
-                log::info!(
-                    "Copied target file: {} to destination: {}",
-                    target_file.to_string_lossy(),
-                    dest_file.to_string_lossy()
-                );
-
-                Ok(AudioFile::new(dest_file))
-            }
+        if let Err(err) = std::fs::rename(target_file, &dest_file) {
+            log::info!("Failed to move target file to audio dir - {err}. Attempting copy instead.");
+
+            // .part files aren't picked up by audio dir scans until renamed into place
+            let part_file = TempFileGuard::new(
+                audio_dir.join(format!(".{}.part", target_file_name.to_string_lossy())),
+            );
+            std::fs::copy(target_file, &*part_file)
+                .log_err_msg("Failed to copy target file to audio dir")?;
+            std::fs::rename(&*part_file, &dest_file)
+                .log_err_msg("Failed to rename copied file in audio dir")?;
+
+            log::info!(
+                "Copied target file: {} to destination: {}",
+                target_file.to_string_lossy(),
+                dest_file.to_string_lossy()
+            );
         }
+
+        Ok(TempFileGuard::new(dest_file))
     }
 }
 
@@ -158,34 +449,139 @@ where
 
 #[cfg(test)]
 mod tests {
-    use super::*;
+    use crate::test_utils::{AudioRowFixture, TempDir, TestDb};
 
-    fn make_temp_dir() -> path::PathBuf {
-        let dir = std::env::temp_dir();
-        let uuid = uuid::Uuid::new_v4();
-        let mut encode_buf = uuid::Uuid::encode_buffer();
-        let uuid = uuid.hyphenated().encode_lower(&mut encode_buf);
-        let temp_dir = dir.join(uuid);
-        std::fs::create_dir(temp_dir.as_path()).expect("Failed creating temp directory");
-        temp_dir
-    }
+    use super::*;
 
     #[test]
     fn read_audio_dir_test() {
-        let dir = make_temp_dir();
-        std::fs::File::create(dir.join("a.mp3")).unwrap();
-        std::fs::File::create(dir.join("b.mp3")).unwrap();
-        std::fs::File::create(dir.join("c.txt")).unwrap();
+        let dir = TempDir::new();
+        dir.file("a.mp3");
+        dir.file("b.mp3");
+        let c_txt = dir.file("c.txt");
 
-        let audio_dir = read_audio_dir(&dir);
+        let audio_dir = read_audio_dir(&dir.path().to_path_buf());
 
         let audio_tracks: Vec<_> = audio_dir.into_iter().collect();
 
         assert_eq!(audio_tracks.len(), 2);
 
-        let c_txt = audio_tracks
-            .iter()
-            .find(|i| i.as_path() == dir.join("c.txt"));
+        let c_txt = audio_tracks.iter().find(|i| i.as_path() == c_txt);
         assert_eq!(c_txt, None);
     }
+
+    #[test]
+    fn max_audio_duration_test() {
+        let db = TestDb::new();
+        let data = db.user_data_with_config(Config {
+            max_audio_file_duration: std::time::Duration::from_secs(10),
+            ..Default::default()
+        });
+        let table = data.guild_settings_table();
+        let set_max_ms = |guild_id, max_ms| {
+            let mut settings = table.get_guild_settings(guild_id).unwrap();
+            settings.max_duration_ms = Some(max_ms);
+            table.update_guild_settings(&settings).unwrap();
+        };
+        set_max_ms(1, 5000);
+        // capped by the config
+        set_max_ms(2, 30_000);
+
+        let secs = std::time::Duration::from_secs;
+        assert_eq!(secs(5), data.max_audio_duration(Some(1)));
+        assert_eq!(secs(10), data.max_audio_duration(Some(2)));
+        assert_eq!(secs(10), data.max_audio_duration(Some(3)));
+        assert_eq!(secs(10), data.max_audio_duration(None));
+    }
+
+    #[test]
+    fn remove_missing_audio_rows_test() {
+        let audio_dir = TempDir::new();
+        let db = TestDb::new();
+        let data = db.user_data_with_config(Config {
+            audio_dir: audio_dir.path().to_path_buf(),
+            ..Default::default()
+        });
+        let table = data.audio_table();
+
+        let insert = |file_name: &str| {
+            let row = AudioRowFixture::new().insert(&table);
+            let audio_file = AudioFile::new(audio_dir.path().join(file_name));
+            table.replace_audio_file(row.id, &audio_file, 0, 0).unwrap();
+            row.id
+        };
+        let kept = insert("kept.mp3");
+        audio_dir.file("kept.mp3");
+        let deleted = insert("deleted.mp3");
+        let archived = insert("archived.mp3");
+        table.set_archived(archived, true).unwrap();
+        // fixture files outside the audio dir are left alone
+        let outside = AudioRowFixture::new().insert(&table).id;
+
+        assert_eq!(1, data.remove_missing_audio_rows().unwrap());
+
+        let exists = |id| {
+            table
+                .find_audio_row(db::UniqueAudioTableCol::Id(id))
+                .is_some()
+        };
+        assert!(exists(kept));
+        assert!(!exists(deleted));
+        assert!(exists(archived));
+        assert!(exists(outside));
+
+        let unmounted = db.user_data_with_config(Config {
+            audio_dir: audio_dir.path().join("missing"),
+            ..Default::default()
+        });
+        assert_eq!(0, unmounted.remove_missing_audio_rows().unwrap());
+    }
+
+    #[test]
+    fn move_file_to_audio_dir_test() {
+        let audio_dir = TempDir::new();
+        let upload_dir = TempDir::new();
+        let data = TestDb::new().user_data_with_config(Config {
+            audio_dir: audio_dir.path().to_path_buf(),
+            ..Default::default()
+        });
+
+        // placed file is rolled back when its row isn't inserted
+        let placed = data
+            .move_file_to_audio_dir(upload_dir.file("beep.mp3"))
+            .unwrap();
+        assert_eq!(audio_dir.path().join("beep.mp3"), placed.path());
+        assert!(!upload_dir.path().join("beep.mp3").exists());
+        drop(placed);
+        assert!(!audio_dir.path().join("beep.mp3").exists());
+
+        let placed = data
+            .move_file_to_audio_dir(upload_dir.file("boop.mp3"))
+            .unwrap()
+            .keep();
+        assert!(placed.exists());
+
+        assert!(data
+            .move_file_to_audio_dir(upload_dir.path().join("missing.mp3"))
+            .is_err());
+    }
+
+    #[test]
+    fn command_prefix_test() {
+        let db = TestDb::new();
+        let data = db.user_data();
+        let default_prefix = data.config().command_prefix;
+
+        assert_eq!(default_prefix, data.command_prefix(None));
+        assert_eq!(default_prefix, data.command_prefix(Some(GuildId::new(1))));
+
+        let mut settings = db.guild_settings_table().get_guild_settings(1).unwrap();
+        settings.prefix = Some("?".into());
+        db.guild_settings_table()
+            .update_guild_settings(&settings)
+            .unwrap();
+
+        assert_eq!("?", data.command_prefix(Some(GuildId::new(1))));
+        assert_eq!(default_prefix, data.command_prefix(Some(GuildId::new(2))));
+    }
 }
@@ -2,6 +2,8 @@ use std::{env, path, str::FromStr};
 
 use serde::{Deserialize, Deserializer};
 
+use crate::http_client;
+
 #[derive(Debug, Deserialize, Clone)]
 pub struct Config {
     pub application_id: u64,
@@ -12,42 +14,193 @@ pub struct Config {
     pub command_prefix: String,
     #[serde(default = "default_sqlite_db_file")]
     pub sqlite_db_file: path::PathBuf,
+    /// SQLCipher key the database is encrypted with. Needs the `sqlcipher` feature
+    #[serde(default)]
+    pub sqlite_key: Option<String>,
+    /// Max length of added sounds. Guilds can lower it for their sounds with
+    /// `/settings max-length`, but not raise it
     #[serde(
         default = "default_max_audio_file_duration",
         deserialize_with = "de_max_audio_file_duration"
     )]
     pub max_audio_file_duration: std::time::Duration,
+    #[serde(default = "default_ingest_concurrency")]
+    pub ingest_concurrency: usize,
+    /// Max sounds downloaded and validated at once across all guilds. Read once on startup
+    #[serde(default = "default_max_concurrent_ingests")]
+    pub max_concurrent_ingests: usize,
+    /// New sounds are refused while the audio dir's volume has less free space (see `disk`).
+    /// 0 disables the check
+    #[serde(default = "default_min_free_disk_mb")]
+    pub min_free_disk_mb: u64,
+    /// Max plays per user within `play_rate_limit_user_window`. 0 disables the limit
+    #[serde(default = "default_play_rate_limit_user")]
+    pub play_rate_limit_user: usize,
+    #[serde(
+        default = "default_play_rate_limit_user_window",
+        deserialize_with = "de_duration_secs"
+    )]
+    pub play_rate_limit_user_window: std::time::Duration,
+    /// Max plays per guild within `play_rate_limit_guild_window`. 0 disables the limit
+    #[serde(default = "default_play_rate_limit_guild")]
+    pub play_rate_limit_guild: usize,
+    #[serde(
+        default = "default_play_rate_limit_guild_window",
+        deserialize_with = "de_duration_secs"
+    )]
+    pub play_rate_limit_guild_window: std::time::Duration,
+    /// Custom status included in the bot's rotating presence
+    #[serde(default)]
+    pub status: Option<String>,
+    /// How often the bot's presence is rotated
+    #[serde(
+        default = "default_presence_interval",
+        deserialize_with = "de_duration_secs"
+    )]
+    pub presence_interval: std::time::Duration,
+    /// Users allowed to use owner commands, alongside the bot application's owner.
+    /// Comma separated user ids
+    #[serde(default, deserialize_with = "de_id_list")]
+    pub owner_ids: Vec<u64>,
+    /// Directory of mp3 files guilds can import as starter sounds when onboarding
+    #[serde(default)]
+    pub starter_sounds_dir: Option<path::PathBuf>,
+    /// Directory daily database backups are written to. None disables scheduled backups
+    #[serde(default)]
+    pub backup_dir: Option<path::PathBuf>,
+    /// UTC time of day scheduled backups run at, once a day (`HH:MM`). Cron schedules aren't
+    /// supported
+    #[serde(default = "default_backup_time", deserialize_with = "de_time_of_day")]
+    pub backup_time: chrono::NaiveTime,
+    /// Number of latest backups kept
+    #[serde(default = "default_backup_keep")]
+    pub backup_keep: usize,
+    /// Days play events are kept before being rolled up into daily play counts. 0 keeps them
+    /// forever
+    #[serde(default = "default_play_events_retention_days")]
+    pub play_events_retention_days: u32,
+    /// Directory removed sounds are moved to, restorable with `/sounds restore` (see `trash`)
+    #[serde(default = "default_trash_dir")]
+    pub trash_dir: path::PathBuf,
+    /// Days removed sounds are kept in the trash dir before being purged. 0 keeps them forever
+    #[serde(default = "default_trash_retention_days")]
+    pub trash_retention_days: u32,
+    /// Register audio files added to the audio dir and remove sounds whose file is deleted
+    /// while the bot runs (see `audio_watcher`). Read once on startup
+    #[serde(default = "default_watch_audio_dir")]
+    pub watch_audio_dir: bool,
+    /// Directory audio files failing checksum verification are moved to (see `checksums`)
+    #[serde(default = "default_quarantine_dir")]
+    pub quarantine_dir: path::PathBuf,
+    /// Lower the volume of sounds playing while the sound of the day is played over them,
+    /// instead of mixing it over them at full volume. Read once on startup
+    #[serde(default)]
+    pub duck_announcements: bool,
+    /// Index url of a sound pack repository, browsed with `/packs browse`. None disables packs
+    #[serde(default)]
+    pub packs_index_url: Option<String>,
+    /// Serve playback and search only, from a database the primary instance writes to. The
+    /// database is opened read-only and commands that write are refused. Read once on startup
+    #[serde(default)]
+    pub read_only: bool,
+    /// OTLP (gRPC) endpoint traces are exported to, e.g. `http://localhost:4317`. Needs the
+    /// `otel` feature
+    #[serde(default)]
+    pub otlp_endpoint: Option<String>,
+    /// Sentry DSN panics and ERROR-level events are reported to (see `error_reporting`). Needs
+    /// the `sentry` feature. Read once on startup
+    #[serde(default)]
+    pub sentry_dsn: Option<String>,
+    /// Text channel ERROR-level events are posted to (see `alerts`). Read once on startup
+    #[serde(default)]
+    pub error_alert_channel_id: Option<u64>,
+    /// Discord webhook url ERROR-level events are posted to (see `alerts`). Read once on startup
+    #[serde(default)]
+    pub error_alert_webhook_url: Option<String>,
+    /// Url of a moderation service uploaded sounds are checked with (see `moderation`). None
+    /// adds uploads without checking them. Read once on startup
+    #[serde(default)]
+    pub moderation_url: Option<String>,
+    /// Proxy url of the bot's own HTTP requests (see `http_client`). Read once on startup
+    #[serde(default)]
+    pub http_proxy: Option<String>,
+    /// PEM file of extra CA certificates of HTTP requests. Read once on startup
+    #[serde(default)]
+    pub http_ca_bundle: Option<path::PathBuf>,
+    /// Domains sounds may be downloaded from (see `download_policy`). Empty allows all
+    #[serde(default, deserialize_with = "de_domain_list")]
+    pub download_allowed_domains: Vec<String>,
+    /// Domains sounds may never be downloaded from
+    #[serde(default, deserialize_with = "de_domain_list")]
+    pub download_blocked_domains: Vec<String>,
+    /// RMS level in dBFS added sounds may not exceed (see `loudness`)
+    #[serde(default = "default_loudness_ceiling_db")]
+    pub loudness_ceiling_db: f64,
 }
 
 impl Config {
     pub fn new() -> Self {
-        let env_file = env::var("DISCORD_BOT_DOTENV_FILE");
-        let env_file = env_file.unwrap_or("./.env".into());
-        dotenv::from_filename(env_file).ok();
+        dotenv::from_filename(Self::env_file()).ok();
+
+        Self::load().unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Re-read config, with the dotenv file overriding environment variables so edits to it
+    /// are picked up
+    pub fn reload() -> Result<Self, String> {
+        // the iterator is the only dotenv api overriding variables that are already set
+        #[allow(deprecated)]
+        if let Ok(vars) = dotenv::from_filename_iter(Self::env_file()) {
+            for (key, value) in vars.flatten() {
+                env::set_var(key, value);
+            }
+        }
 
+        Self::load()
+    }
+
+    fn env_file() -> String {
+        env::var("DISCORD_BOT_DOTENV_FILE").unwrap_or("./.env".into())
+    }
+
+    fn load() -> Result<Self, String> {
         let c = config::Config::builder()
             .add_source(config::Environment::with_prefix("discord_bot"))
             .build()
-            .unwrap_or_else(|err| panic!("Missing/Incorrect environment variables - {err}"));
+            .map_err(|err| format!("Missing/Incorrect environment variables - {err}"))?;
 
         let cfg: Config = c
             .try_deserialize()
-            .unwrap_or_else(|err| panic!("Failed deserializing config - {err}"));
+            .map_err(|err| format!("Failed deserializing config - {err}"))?;
 
-        cfg.validate();
+        cfg.validate()?;
 
-        cfg
+        Ok(cfg)
     }
 
-    pub fn validate(&self) {
+    pub fn validate(&self) -> Result<(), String> {
         let mut errs: Vec<String> = vec![];
 
         self.validate_audio_dir().map_err(|err| errs.push(err)).ok();
+        self.validate_sqlite_key()
+            .map_err(|err| errs.push(err))
+            .ok();
+        self.validate_otlp_endpoint()
+            .map_err(|err| errs.push(err))
+            .ok();
+        self.validate_sentry_dsn()
+            .map_err(|err| errs.push(err))
+            .ok();
+        self.validate_http_client()
+            .map_err(|err| errs.push(err))
+            .ok();
 
         if errs.len() > 0 {
             let err_msg: String = errs.iter().map(|err| format!("{err}\n")).collect();
-            panic!("{}", err_msg);
+            return Err(err_msg);
         }
+
+        Ok(())
     }
 
     fn validate_audio_dir(&self) -> Result<(), String> {
@@ -67,6 +220,42 @@ impl Config {
 
         Ok(())
     }
+
+    fn validate_sqlite_key(&self) -> Result<(), String> {
+        match (&self.sqlite_key, cfg!(feature = "sqlcipher")) {
+            (Some(key), _) if key.is_empty() => Err("SQLite key is empty".into()),
+            (Some(_), false) => {
+                Err("SQLite key is set, but the bot was built without the sqlcipher feature".into())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn validate_otlp_endpoint(&self) -> Result<(), String> {
+        match (&self.otlp_endpoint, cfg!(feature = "otel")) {
+            (Some(endpoint), _) if endpoint.is_empty() => Err("OTLP endpoint is empty".into()),
+            (Some(_), false) => {
+                Err("OTLP endpoint is set, but the bot was built without the otel feature".into())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn validate_sentry_dsn(&self) -> Result<(), String> {
+        match (&self.sentry_dsn, cfg!(feature = "sentry")) {
+            (Some(dsn), _) if dsn.is_empty() => Err("Sentry DSN is empty".into()),
+            (Some(_), false) => {
+                Err("Sentry DSN is set, but the bot was built without the sentry feature".into())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn validate_http_client(&self) -> Result<(), String> {
+        http_client::client(self)
+            .map(|_| ())
+            .map_err(|err| format!("Invalid HTTP proxy or CA bundle - {err}"))
+    }
 }
 
 impl Default for Config {
@@ -77,7 +266,40 @@ impl Default for Config {
             audio_dir: default_audio_dir(),
             command_prefix: default_command_prefix(),
             sqlite_db_file: default_sqlite_db_file(),
+            sqlite_key: None,
             max_audio_file_duration: default_max_audio_file_duration(),
+            ingest_concurrency: default_ingest_concurrency(),
+            max_concurrent_ingests: default_max_concurrent_ingests(),
+            min_free_disk_mb: default_min_free_disk_mb(),
+            play_rate_limit_user: default_play_rate_limit_user(),
+            play_rate_limit_user_window: default_play_rate_limit_user_window(),
+            play_rate_limit_guild: default_play_rate_limit_guild(),
+            play_rate_limit_guild_window: default_play_rate_limit_guild_window(),
+            status: None,
+            presence_interval: default_presence_interval(),
+            owner_ids: vec![],
+            starter_sounds_dir: None,
+            backup_dir: None,
+            backup_time: default_backup_time(),
+            backup_keep: default_backup_keep(),
+            play_events_retention_days: default_play_events_retention_days(),
+            trash_dir: default_trash_dir(),
+            trash_retention_days: default_trash_retention_days(),
+            watch_audio_dir: default_watch_audio_dir(),
+            quarantine_dir: default_quarantine_dir(),
+            duck_announcements: false,
+            packs_index_url: None,
+            read_only: false,
+            otlp_endpoint: None,
+            sentry_dsn: None,
+            error_alert_channel_id: None,
+            error_alert_webhook_url: None,
+            moderation_url: None,
+            http_proxy: None,
+            http_ca_bundle: None,
+            download_allowed_domains: vec![],
+            download_blocked_domains: vec![],
+            loudness_ceiling_db: default_loudness_ceiling_db(),
         }
     }
 }
@@ -98,6 +320,72 @@ pub fn default_max_audio_file_duration() -> std::time::Duration {
     std::time::Duration::from_secs(7)
 }
 
+fn default_ingest_concurrency() -> usize {
+    std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(4)
+}
+
+fn default_max_concurrent_ingests() -> usize {
+    2
+}
+
+fn default_min_free_disk_mb() -> u64 {
+    500
+}
+
+fn default_play_rate_limit_user() -> usize {
+    3
+}
+
+fn default_play_rate_limit_user_window() -> std::time::Duration {
+    std::time::Duration::from_secs(10)
+}
+
+fn default_play_rate_limit_guild() -> usize {
+    10
+}
+
+fn default_play_rate_limit_guild_window() -> std::time::Duration {
+    std::time::Duration::from_secs(60)
+}
+
+fn default_presence_interval() -> std::time::Duration {
+    std::time::Duration::from_secs(300)
+}
+
+fn default_backup_time() -> chrono::NaiveTime {
+    chrono::NaiveTime::from_hms_opt(4, 0, 0).unwrap()
+}
+
+fn default_backup_keep() -> usize {
+    7
+}
+
+fn default_play_events_retention_days() -> u32 {
+    90
+}
+
+fn default_trash_dir() -> path::PathBuf {
+    path::PathBuf::from_str("./trash").unwrap()
+}
+
+fn default_trash_retention_days() -> u32 {
+    30
+}
+
+fn default_watch_audio_dir() -> bool {
+    true
+}
+
+fn default_quarantine_dir() -> path::PathBuf {
+    path::PathBuf::from_str("./quarantine").unwrap()
+}
+
+fn default_loudness_ceiling_db() -> f64 {
+    -8.0
+}
+
 pub fn de_max_audio_file_duration<'de, D>(deserializer: D) -> Result<std::time::Duration, D::Error>
 where
     D: Deserializer<'de>,
@@ -105,3 +393,49 @@ where
     let value = u64::deserialize(deserializer)?;
     Ok(std::time::Duration::from_millis(value))
 }
+
+/// Comma separated ids, e.g. `1234,5678`
+pub fn de_id_list<'de, D>(deserializer: D) -> Result<Vec<u64>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value = String::deserialize(deserializer)?;
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|id| !id.is_empty())
+        .map(|id| id.parse().map_err(serde::de::Error::custom))
+        .collect()
+}
+
+/// Comma separated domains, e.g. `example.com,*.example.org`. Lowercased, with wildcards and
+/// leading dots dropped
+pub fn de_domain_list<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value = String::deserialize(deserializer)?;
+    Ok(value
+        .split(',')
+        .map(|domain| domain.trim().trim_start_matches("*.").trim_matches('.'))
+        .filter(|domain| !domain.is_empty())
+        .map(str::to_lowercase)
+        .collect())
+}
+
+/// Time of day like `04:30`
+pub fn de_time_of_day<'de, D>(deserializer: D) -> Result<chrono::NaiveTime, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value = String::deserialize(deserializer)?;
+    chrono::NaiveTime::parse_from_str(value.trim(), "%H:%M").map_err(serde::de::Error::custom)
+}
+
+pub fn de_duration_secs<'de, D>(deserializer: D) -> Result<std::time::Duration, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value = u64::deserialize(deserializer)?;
+    Ok(std::time::Duration::from_secs(value))
+}
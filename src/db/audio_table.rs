@@ -1,21 +1,54 @@
 use std::ops::Deref;
 
 use regex::Regex;
-use rusqlite::{params, types::FromSql, ToSql};
+use rusqlite::{params, types::FromSql, OptionalExtension, ToSql};
 
-use crate::{audio, commands::PoiseError, common::LogResult};
+use crate::{
+    audio,
+    commands::PoiseError,
+    common::LogResult,
+    errors::{InsertAudioError, SoundNameError},
+    vars,
+};
 
-use super::{DbConnection, Table};
+use super::{DbConnection, FavoritesTable, PlayEventsTable, Table, UserPlaysTable};
 
 pub struct AudioTableRow {
     pub id: i64,
-    pub name: String,
+    pub name: SoundName,
     pub tags: Tags,
     pub audio_file: audio::AudioFile,
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub author_id: Option<u64>,
     pub author_name: Option<String>,
     pub author_global_name: Option<String>,
+    /// Salted hash of the author id of anonymous uploads (see `privacy::author_hash`), whose
+    /// author fields are left empty
+    pub author_hash: Option<String>,
+    pub guild_id: Option<u64>,
+    pub play_count: i64,
+    pub last_played_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// When sound was archived. None if it isn't archived
+    pub archived_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// Emoji shown on the sound's button (see `helpers::parse_emoji`)
+    pub emoji: Option<String>,
+    /// Track duration in milliseconds. None until probed (see `ingest::backfill_audio_metadata`)
+    pub duration_ms: Option<u64>,
+    /// Audio file size in bytes. None until probed
+    pub file_size: Option<u64>,
+    /// Soundboard section of the sound (see `CategoriesTable`). None if it isn't categorized
+    pub category_id: Option<i64>,
+    /// Style of the sound's button (see `helpers::SoundButtonStyle::code`). None uses the
+    /// default style
+    pub button_style: Option<String>,
+    /// Url the sound was downloaded from (video link or attachment url), used to re-fetch it.
+    /// None for sounds without a known source
+    pub source_url: Option<String>,
+    /// Hex encoded SHA-256 of the audio file, verified before playback (see `checksums`). None
+    /// until hashed
+    pub content_hash: Option<String>,
+    /// File safe form of the name (see `SoundName::slug`)
+    pub slug: String,
 }
 
 pub struct Tags(Vec<String>);
@@ -27,13 +60,19 @@ impl Tags {
 
     pub fn clean_tag(value: impl AsRef<str>) -> String {
         let text = value.as_ref();
-        let re = Regex::new(r"[^a-zA-Z0-9-_\s]").unwrap();
+        let re = Regex::new(r"[^\p{L}\p{M}\p{N}\-_\s]").unwrap();
         let text = re.replace_all(text, " ");
         let text = text.trim();
 
         text.into()
     }
 
+    /// Tag as stored in the normalized `audio_tags` table. Only ASCII is lowercased, like
+    /// sqlite's `lower()` the table is filled with
+    pub fn normalize_tag(value: impl AsRef<str>) -> String {
+        Self::clean_tag(value).to_ascii_lowercase()
+    }
+
     #[allow(dead_code)]
     pub fn inner(&self) -> &Vec<String> {
         &self.0
@@ -91,6 +130,113 @@ impl FromSql for Tags {
     }
 }
 
+/// Validated sound name. Names are trimmed with inner whitespace collapsed to single spaces,
+/// and limited to `vars::SOUND_NAME_MAX_LEN` chars, so they fit button labels. Control chars
+/// and backticks (which break names quoted in replies) aren't allowed
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SoundName(String);
+
+impl SoundName {
+    pub fn new(value: impl AsRef<str>) -> Result<Self, SoundNameError> {
+        let name = value
+            .as_ref()
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        if name.is_empty() {
+            return Err(SoundNameError::Empty);
+        }
+
+        let len = name.chars().count();
+        if len > vars::SOUND_NAME_MAX_LEN {
+            return Err(SoundNameError::TooLong {
+                len,
+                max: vars::SOUND_NAME_MAX_LEN,
+            });
+        }
+
+        match name.chars().find(|c| c.is_control() || *c == '`') {
+            Some(c) => Err(SoundNameError::InvalidChar(c)),
+            None => Ok(Self(name)),
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// File safe form of the name, its lowercase letters and digits separated by single dashes
+    /// (e.g. `Don't Stop 🎉` is `don-t-stop`). Names without letters or digits are `sound`
+    pub fn slug(&self) -> String {
+        let mut slug = String::new();
+        for c in self.0.chars().flat_map(char::to_lowercase) {
+            if c.is_alphanumeric() {
+                slug.push(c);
+            } else if !slug.is_empty() && !slug.ends_with('-') {
+                slug.push('-');
+            }
+        }
+
+        let slug: String = slug
+            .trim_end_matches('-')
+            .chars()
+            .take(vars::SOUND_NAME_MAX_LEN)
+            .collect();
+        match slug.trim_end_matches('-') {
+            "" => "sound".into(),
+            slug => slug.into(),
+        }
+    }
+}
+
+impl Deref for SoundName {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl AsRef<str> for SoundName {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl std::fmt::Display for SoundName {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl From<SoundName> for String {
+    fn from(value: SoundName) -> Self {
+        value.0
+    }
+}
+
+impl TryFrom<&str> for SoundName {
+    type Error = SoundNameError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        Self::new(value)
+    }
+}
+
+impl ToSql for SoundName {
+    fn to_sql(&self) -> rusqlite::Result<rusqlite::types::ToSqlOutput<'_>> {
+        self.0.to_sql()
+    }
+}
+
+impl FromSql for SoundName {
+    /// Names stored before names were validated are read as is
+    fn column_result(value: rusqlite::types::ValueRef<'_>) -> rusqlite::types::FromSqlResult<Self> {
+        Ok(Self(value.as_str()?.into()))
+    }
+}
+
 impl AsRef<AudioTableRow> for AudioTableRow {
     fn as_ref(&self) -> &AudioTableRow {
         &self
@@ -101,9 +247,16 @@ impl TryFrom<&rusqlite::Row<'_>> for AudioTableRow {
     type Error = rusqlite::Error;
 
     fn try_from(row: &rusqlite::Row) -> Result<Self, Self::Error> {
+        let name: SoundName = row.get("name").log_err_msg("From row.name fail")?;
+        // rows are slugged on insert and rename, and when migrated
+        let slug = row
+            .get::<_, Option<String>>("slug")
+            .log_err_msg("From row.slug fail")?
+            .unwrap_or_else(|| name.slug());
+
         Ok(Self {
             id: row.get("id").log_err_msg("From row.id fail")?,
-            name: row.get("name").log_err_msg("From row.name fail")?,
+            name,
             tags: row.get("tags").log_err_msg("From row.tags fail")?,
             audio_file: row
                 .get("audio_file")
@@ -120,18 +273,77 @@ impl TryFrom<&rusqlite::Row<'_>> for AudioTableRow {
             author_global_name: row
                 .get("author_global_name")
                 .log_err_msg("From row.author_global_name fail")?,
+            author_hash: row
+                .get("author_hash")
+                .log_err_msg("From row.author_hash fail")?,
+            guild_id: row.get("guild_id").log_err_msg("From row.guild_id fail")?,
+            play_count: row
+                .get("play_count")
+                .log_err_msg("From row.play_count fail")?,
+            last_played_at: row
+                .get("last_played_at")
+                .log_err_msg("From row.last_played_at fail")?,
+            archived_at: row
+                .get("archived_at")
+                .log_err_msg("From row.archived_at fail")?,
+            emoji: row.get("emoji").log_err_msg("From row.emoji fail")?,
+            duration_ms: row
+                .get("duration_ms")
+                .log_err_msg("From row.duration_ms fail")?,
+            file_size: row
+                .get("file_size")
+                .log_err_msg("From row.file_size fail")?,
+            category_id: row
+                .get("category_id")
+                .log_err_msg("From row.category_id fail")?,
+            button_style: row
+                .get("button_style")
+                .log_err_msg("From row.button_style fail")?,
+            source_url: row
+                .get("source_url")
+                .log_err_msg("From row.source_url fail")?,
+            content_hash: row
+                .get("content_hash")
+                .log_err_msg("From row.content_hash fail")?,
+            slug,
         })
     }
 }
 
 pub struct AudioTableRowInsert {
-    pub name: String,
+    pub name: SoundName,
     pub tags: Tags,
     pub audio_file: audio::AudioFile,
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub author_id: Option<u64>,
     pub author_name: Option<String>,
     pub author_global_name: Option<String>,
+    /// Set instead of the author fields for anonymous uploads
+    pub author_hash: Option<String>,
+    pub guild_id: Option<u64>,
+    pub duration_ms: Option<u64>,
+    pub file_size: Option<u64>,
+    pub source_url: Option<String>,
+}
+
+impl AudioTableRowInsert {
+    fn sql_params(&self) -> impl rusqlite::Params + '_ {
+        (
+            &self.name,
+            &self.tags,
+            &self.audio_file,
+            &self.created_at,
+            &self.author_id,
+            &self.author_name,
+            &self.author_global_name,
+            &self.guild_id,
+            &self.duration_ms,
+            &self.file_size,
+            &self.author_hash,
+            &self.source_url,
+            self.name.slug(),
+        )
+    }
 }
 
 impl AsRef<AudioTableRowInsert> for AudioTableRowInsert {
@@ -141,7 +353,7 @@ impl AsRef<AudioTableRowInsert> for AudioTableRowInsert {
 }
 
 #[allow(unused)]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum UniqueAudioTableCol {
     Id(i64),
     Name(String),
@@ -149,6 +361,30 @@ pub enum UniqueAudioTableCol {
 }
 
 impl UniqueAudioTableCol {
+    /// Prefix telling sound ids from names in inputs, like `#12`
+    pub const ID_PREFIX: &'static str = "#";
+
+    /// Sound of track option, an autocompleted sound id (see `AutocompleteTrack::value`) or a
+    /// name typed out
+    pub fn from_input(input: impl AsRef<str>) -> Self {
+        let input = input.as_ref().trim();
+        match input
+            .strip_prefix(Self::ID_PREFIX)
+            .and_then(|id| id.parse().ok())
+        {
+            Some(id) => Self::Id(id),
+            None => Self::Name(input.into()),
+        }
+    }
+
+    /// Input `from_input` resolves back to this column, e.g. to store a sound by id
+    pub fn to_input(&self) -> String {
+        match self {
+            Self::Id(id) => format!("{}{id}", Self::ID_PREFIX),
+            _ => self.value(),
+        }
+    }
+
     pub fn value(&self) -> String {
         match &self {
             Self::Id(val) => val.to_string(),
@@ -174,6 +410,97 @@ impl UniqueAudioTableCol {
     }
 }
 
+/// Filter for bulk operations on audio rows. All set conditions must match
+#[derive(Debug, Default, Clone)]
+pub struct AudioTableFilter {
+    /// Normalized tag
+    pub tag: Option<String>,
+    pub author_id: Option<u64>,
+    pub created_before: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl AudioTableFilter {
+    pub fn is_empty(&self) -> bool {
+        self.tag.is_none() && self.author_id.is_none() && self.created_before.is_none()
+    }
+
+    /// SQL condition on audio table columns, binding `?1` (tag), `?2` (author_id)
+    /// and `?3` (created_before). Unset conditions match everything
+    fn sql_condition() -> String {
+        let tags_table_name = AudioTable::TAGS_TABLE_NAME;
+        format!(
+            "(?1 IS NULL OR id IN (SELECT audio_id FROM {tags_table_name} WHERE tag = ?1))
+            AND (?2 IS NULL OR author_id = ?2)
+            AND (?3 IS NULL OR created_at < ?3)"
+        )
+    }
+
+    fn sql_params(&self) -> [&dyn ToSql; 3] {
+        [&self.tag, &self.author_id, &self.created_before]
+    }
+}
+
+/// Sound suggested by track option autocomplete
+#[derive(Debug, Clone, PartialEq)]
+pub struct AutocompleteTrack {
+    pub id: i64,
+    pub name: String,
+    pub duration_ms: Option<u64>,
+    /// Tag the query matched, for tracks whose name doesn't contain the query
+    pub matched_tag: Option<String>,
+}
+
+impl AutocompleteTrack {
+    /// Choice label length limit of Discord
+    const LABEL_MAX_LEN: usize = 100;
+
+    pub fn new(id: i64, name: String, duration_ms: Option<u64>, tags: &Tags, query: &str) -> Self {
+        let words: Vec<_> = query
+            .split_whitespace()
+            .map(|word| word.to_lowercase())
+            .collect();
+        let contains_word = |text: &str| {
+            let text = text.to_lowercase();
+            words.iter().any(|word| text.contains(word.as_str()))
+        };
+
+        let matched_tag = match contains_word(&name) {
+            true => None,
+            false => tags.iter().find(|tag| contains_word(tag)).cloned(),
+        };
+        Self {
+            id,
+            name,
+            duration_ms,
+            matched_tag,
+        }
+    }
+
+    /// Choice label like `bruh (1.5s) — memes`. Duration is left out until probed
+    pub fn label(&self) -> String {
+        let duration = self
+            .duration_ms
+            .map(|duration_ms| format!(" ({:.1}s)", duration_ms as f64 / 1000.0))
+            .unwrap_or_default();
+        let tag = self
+            .matched_tag
+            .as_ref()
+            .map(|tag| format!(" — {tag}"))
+            .unwrap_or_default();
+
+        format!("{}{duration}{tag}", self.name)
+            .chars()
+            .take(Self::LABEL_MAX_LEN)
+            .collect()
+    }
+
+    /// Choice value, the sound's id so renames between autocomplete and running the command
+    /// can't resolve another sound
+    pub fn value(&self) -> String {
+        UniqueAudioTableCol::Id(self.id).to_input()
+    }
+}
+
 pub struct AudioTable {
     conn: DbConnection,
 }
@@ -181,55 +508,87 @@ pub struct AudioTable {
 impl AudioTable {
     pub const TABLE_NAME: &'static str = "audio";
     pub const FTS5_TABLE_NAME: &'static str = "fts5_audio";
+    pub const TAGS_TABLE_NAME: &'static str = "audio_tags";
+    /// Minimum partial text length for full text search (trigram tokenizer)
+    pub const FTS_MIN_QUERY_LEN: usize = 3;
 
     pub fn new(connection: DbConnection) -> Self {
         Self { conn: connection }
     }
 
-    /// Return list of audio tracks by name that are most similiar to partial string
-    /// **note**: If few than 3 chars entered, list of latest sounds added are returned
-    pub fn fts_autocomplete_track_names(
+    /// Full text search query matching rows containing every whitespace separated term of text.
+    /// Terms are quoted, so quotes, dashes and other FTS5 syntax in sound names are searched
+    /// literally. Terms shorter than `FTS_MIN_QUERY_LEN` chars can't match trigrams and are
+    /// left out, returning None when no term is long enough
+    pub(crate) fn fts_query(text: &str) -> Option<String> {
+        let terms: Vec<String> = text
+            .split_whitespace()
+            .filter(|term| term.chars().count() >= Self::FTS_MIN_QUERY_LEN)
+            .map(|term| format!("\"{}\"", term.replace('"', "\"\"")))
+            .collect();
+
+        match terms.is_empty() {
+            true => None,
+            false => Some(terms.join(" ")),
+        }
+    }
+
+    /// Return audio tracks with name or tags most similiar to partial string, leaving out
+    /// archived tracks. Tracks found by a tag but not their name come with the matched tag
+    /// **note**: If fewer than `min_query_len` chars entered, list of top ranked sounds are
+    /// returned (see `ranked_tracks`). Queries too short for full text search match names
+    /// and tags containing them
+    pub fn autocomplete_tracks(
         &self,
         partial: impl AsRef<str>,
-        limit: Option<usize>,
-    ) -> Vec<String> {
+        user_id: Option<u64>,
+        limit: usize,
+        min_query_len: usize,
+    ) -> Vec<AutocompleteTrack> {
         let text = partial.as_ref();
 
-        let limit = limit.unwrap_or(5);
-
         // low char query
-        if text.len() < 3 {
+        if text.chars().count() < min_query_len {
             log::debug!("low character auto complete: '{text}'");
-            let table_name = Self::TABLE_NAME;
-            let sql =
-                format!("SELECT name FROM {table_name} ORDER BY created_at DESC LIMIT {limit}");
-            let mut stmt = self
-                .conn
-                .prepare(sql.as_str())
-                .expect("Autocomplete low-char sql invalid");
-
-            let rows = stmt.query_map((), |row| row.get("name"));
-            match rows {
-                Ok(rows) => {
-                    let rows: Vec<String> = rows.filter_map(|row| row.ok()).collect();
-                    return rows;
-                }
-                Err(err) => {
-                    log::error!("Autocomplete low-char sql query error - {err}");
-                    return vec![];
-                }
-            }
+            return self.ranked_tracks(user_id, limit);
         }
 
         log::debug!("Auto complete partial search on {text}");
+        let table_name = Self::TABLE_NAME;
         let fts5_table_name = Self::FTS5_TABLE_NAME;
-        let sql = format!("SELECT name FROM {fts5_table_name}(?) LIMIT {limit}");
+        let fts_query = Self::fts_query(text);
+        let sql = match fts_query {
+            None => format!(
+                "
+                SELECT id, name, duration_ms, tags FROM {table_name}
+                WHERE archived_at IS NULL
+                    AND (name LIKE '%' || ?1 || '%' OR tags LIKE '%' || ?1 || '%')
+                ORDER BY name
+                LIMIT {limit}"
+            ),
+            Some(_) => format!(
+                "
+                SELECT a.id, a.name, a.duration_ms, a.tags FROM {fts5_table_name}(?1) f
+                JOIN {table_name} a ON a.id = f.rowid
+                WHERE a.archived_at IS NULL
+                LIMIT {limit}"
+            ),
+        };
         let mut stmt = self
             .conn
             .prepare(sql.as_str())
             .expect("Autocomplete sql invalid");
 
-        let rows = stmt.query_map(params![&text], |row| row.get("name"));
+        let rows = stmt.query_map(params![fts_query.as_deref().unwrap_or(text)], |row| {
+            let tags: Tags = row.get("tags")?;
+            Ok(AutocompleteTrack::new(
+                row.get("id")?,
+                row.get("name")?,
+                row.get("duration_ms")?,
+                &tags,
+                text,
+            ))
+        });
         match rows {
             Ok(rows) => rows.filter_map(|row| row.ok()).collect(),
             Err(err) => {
@@ -239,168 +598,1211 @@ impl AudioTable {
         }
     }
 
-    pub fn find_audio_row(&self, col: impl AsRef<UniqueAudioTableCol>) -> Option<AudioTableRow> {
-        let col = col.as_ref();
-        let col_value = col.value();
+    /// Return list of audio tracks, ranked by a blend of the user's favorites,
+    /// play count (decayed by time since last played) and how recently the track was added.
+    /// **note**: Only the user's favorites, most played and most recent tracks are ranked,
+    /// so the `created_at` and `play_count` indexes avoid a full table scan
+    pub fn ranked_tracks(&self, user_id: Option<u64>, limit: usize) -> Vec<AutocompleteTrack> {
         let table_name = Self::TABLE_NAME;
+        let favorites_table_name = FavoritesTable::TABLE_NAME;
+        let candidates_limit = limit * 10;
+        let sql = format!(
+            "
+            WITH candidates AS (
+                SELECT id FROM (
+                    SELECT id FROM {table_name} ORDER BY created_at DESC LIMIT {candidates_limit}
+                )
+                UNION
+                SELECT id FROM (
+                    SELECT id FROM {table_name} ORDER BY play_count DESC LIMIT {candidates_limit}
+                )
+                UNION
+                SELECT audio_id FROM {favorites_table_name} WHERE user_id = ?1
+            )
+            SELECT
+                a.id,
+                a.name,
+                a.duration_ms,
+                (CASE WHEN f.audio_id IS NULL THEN 0.0 ELSE 100.0 END)
+                + a.play_count / (1.0 + (julianday('now') - julianday(COALESCE(a.last_played_at, a.created_at))) / 7.0)
+                + 10.0 / (1.0 + julianday('now') - julianday(a.created_at)) AS rank
+            FROM candidates c
+            JOIN {table_name} a ON a.id = c.id
+            LEFT JOIN {favorites_table_name} f
+                ON f.audio_id = a.id AND f.user_id = ?1
+            WHERE a.archived_at IS NULL
+            ORDER BY rank DESC, a.created_at DESC
+            LIMIT {limit}"
+        );
 
-        let sql_condition = col.sql_condition();
-        let sql = format!("SELECT * FROM {table_name} WHERE {sql_condition}");
-
-        self.conn
-            .query_row(sql.as_str(), params![&col_value], |row| {
-                AudioTableRow::try_from(row)
+        let mut stmt = self
+            .conn
+            .prepare(sql.as_str())
+            .expect("Ranked track names sql invalid");
+
+        let rows = stmt.query_map(params![&user_id], |row| {
+            Ok(AutocompleteTrack {
+                id: row.get("id")?,
+                name: row.get("name")?,
+                duration_ms: row.get("duration_ms")?,
+                matched_tag: None,
             })
-            .log_err_msg(format!("Failed to find audio row - {col:?}"))
-            .ok()
+        });
+        match rows {
+            Ok(rows) => rows.filter_map(|row| row.ok()).collect(),
+            Err(err) => {
+                log::error!("Ranked track names sql query error - {err}");
+                vec![]
+            }
+        }
     }
 
-    pub fn insert_audio_row(
+    /// Return user's unarchived uploads and favorites, ordered by how often the user played them
+    pub fn personal_audio_rows(
         &self,
-        audio_row: impl AsRef<AudioTableRowInsert>,
-    ) -> Result<(), String> {
-        let audio_row = audio_row.as_ref();
-
-        log::info!(
-            "Inserting audio row. Name: {}, File: {}",
-            audio_row.name,
-            audio_row.audio_file.to_string_lossy()
-        );
+        user_id: u64,
+        limit: u64,
+    ) -> Result<Vec<AudioTableRow>, PoiseError> {
         let table_name = Self::TABLE_NAME;
+        let favorites_table_name = FavoritesTable::TABLE_NAME;
+        let user_plays_table_name = UserPlaysTable::TABLE_NAME;
         let sql = format!(
             "
-            INSERT INTO {table_name}
-                (name, tags, audio_file, created_at, author_id, author_name, author_global_name)
-            VALUES
-                (?1, ?2, ?3, ?4, ?5, ?6, ?7)"
+            SELECT a.* FROM {table_name} a
+            LEFT JOIN {user_plays_table_name} up
+                ON up.audio_id = a.id AND up.user_id = ?1
+            WHERE
+                a.archived_at IS NULL
+                AND (
+                    a.author_id = ?1
+                    OR a.id IN (SELECT audio_id FROM {favorites_table_name} WHERE user_id = ?1)
+                )
+            ORDER BY COALESCE(up.play_count, 0) DESC, a.name
+            LIMIT {limit}"
         );
 
-        self.connection()
-            .execute(
-                sql.as_str(),
-                (
-                    &audio_row.name,
-                    &audio_row.tags,
-                    &audio_row.audio_file,
-                    &audio_row.created_at,
-                    &audio_row.author_id,
-                    &audio_row.author_name,
-                    &audio_row.author_global_name,
-                ),
-            )
-            .map_err(|err| {
-                log::error!("Failed to insert audio row - {err}");
-                err.to_string()
-            })?;
+        let mut stmt = self
+            .conn
+            .prepare(sql.as_str())
+            .log_err_msg("Personal audio rows sql invalid")?;
 
-        Ok(())
+        let rows = stmt
+            .query_map(params![&user_id], |row| AudioTableRow::try_from(row))
+            .log_err_msg("Personal audio rows sql query error")?;
+
+        Ok(rows.filter_map(|row| row.log_err().ok()).collect())
     }
 
-    pub fn update_audio_row(&self, audio_row: impl AsRef<AudioTableRow>) -> Result<(), String> {
-        let audio_row = audio_row.as_ref();
-        log::info!("Updating audio row. Name: {}", audio_row.name);
+    /// Count unarchived audio rows, optionally only those carrying (normalized) tag
+    pub fn count_audio_rows(&self, tag: Option<&str>) -> Result<u64, PoiseError> {
+        let table_name = Self::TABLE_NAME;
+        let tags_table_name = Self::TAGS_TABLE_NAME;
+        let sql = format!(
+            "
+            SELECT COUNT(*) FROM {table_name}
+            WHERE archived_at IS NULL
+                AND (?1 IS NULL OR id IN (SELECT audio_id FROM {tags_table_name} WHERE tag = ?1))"
+        );
 
+        Ok(self
+            .conn
+            .query_row(sql.as_str(), params![&tag], |row| row.get(0))
+            .log_err_msg("Failed counting audio rows")?)
+    }
+
+    /// Count audio rows added to guild since `since`
+    pub fn count_audio_rows_added_since(
+        &self,
+        since: chrono::DateTime<chrono::Utc>,
+        guild_id: u64,
+    ) -> Result<u64, PoiseError> {
         let table_name = Self::TABLE_NAME;
-        let name = &audio_row.name;
-        let tags = &audio_row.tags;
-        let row_id = audio_row.id;
+        let sql =
+            format!("SELECT COUNT(*) FROM {table_name} WHERE created_at >= ?1 AND guild_id = ?2");
+
+        Ok(self
+            .conn
+            .query_row(sql.as_str(), params![&since, &guild_id], |row| row.get(0))
+            .log_err_msg("Failed counting added audio rows")?)
+    }
 
+    /// Author of the most audio rows added to guild since `since`, with their count.
+    /// Anonymous uploads aren't attributed to anyone
+    pub fn top_author_since(
+        &self,
+        since: chrono::DateTime<chrono::Utc>,
+        guild_id: u64,
+    ) -> Result<Option<(u64, u64)>, PoiseError> {
+        let table_name = Self::TABLE_NAME;
         let sql = format!(
             "
-            UPDATE {table_name}
-            SET
-                name = ?,
-                tags = ?
-            WHERE
-                id = ?;
-        "
+            SELECT author_id, COUNT(*) AS sounds FROM {table_name}
+            WHERE created_at >= ?1 AND guild_id = ?2 AND author_id IS NOT NULL
+            GROUP BY author_id
+            ORDER BY sounds DESC, MIN(created_at)
+            LIMIT 1"
         );
 
-        self.conn
-            .execute(sql.as_str(), params![&name, &tags, &row_id])
-            .log_err_msg("Failed updating audio track")
-            .map_err(|err| err.to_string())?;
-
-        log::info!("Updated audio row. Name: {name}");
-        Ok(())
+        Ok(self
+            .conn
+            .query_row(sql.as_str(), params![&since, &guild_id], |row| {
+                Ok((row.get("author_id")?, row.get("sounds")?))
+            })
+            .optional()
+            .log_err_msg("Failed to find top author")?)
     }
 
-    pub fn delete_audio_row(&self, col: impl AsRef<UniqueAudioTableCol>) -> Result<(), PoiseError> {
-        let column = col.as_ref();
-        match self.find_audio_row(&col) {
-            None => log::info!("Can't delete non-existent audio track. {column:?}"),
-            Some(row) => {
-                row.audio_file.delete();
-                let table_name = Self::TABLE_NAME;
-                let row_id = row.id;
-                let sql = format!("DELETE FROM {table_name} WHERE id = {row_id}");
+    /// Tags of unarchived sounds containing partial (case insensitive), most used first
+    pub fn tag_names(&self, partial: impl AsRef<str>, limit: usize) -> Vec<String> {
+        let table_name = Self::TABLE_NAME;
+        let tags_table_name = Self::TAGS_TABLE_NAME;
+        let sql = format!(
+            "
+            SELECT tag FROM {tags_table_name}
+            WHERE instr(tag, ?1) > 0
+                AND audio_id NOT IN (SELECT id FROM {table_name} WHERE archived_at IS NOT NULL)
+            GROUP BY tag
+            ORDER BY COUNT(*) DESC, tag
+            LIMIT {limit}"
+        );
+
+        let mut stmt = self
+            .conn
+            .prepare(sql.as_str())
+            .expect("Tag names sql invalid");
 
-                self.conn
-                    .execute(sql.as_str(), ())
-                    .log_err_msg("Failed to delete audio row")?;
+        let partial = Tags::normalize_tag(partial);
+        let rows = stmt.query_map(params![&partial], |row| row.get("tag"));
+        match rows {
+            Ok(rows) => rows.filter_map(|row| row.ok()).collect(),
+            Err(err) => {
+                log::error!("Tag names sql query error - {err}");
+                vec![]
             }
         }
-        Ok(())
-    }
-}
-
-impl Table for AudioTable {
-    fn connection(&self) -> &DbConnection {
-        &self.conn
     }
 
-    fn create_table(&self) {
+    /// Most used tags with their unarchived sound counts
+    pub fn tag_counts(&self, limit: u64) -> Result<Vec<(String, u64)>, PoiseError> {
         let table_name = Self::TABLE_NAME;
-        let fts5_table_name = Self::FTS5_TABLE_NAME;
+        let tags_table_name = Self::TAGS_TABLE_NAME;
+        let sql = format!(
+            "
+            SELECT tag, COUNT(*) AS sound_count FROM {tags_table_name}
+            WHERE audio_id NOT IN (SELECT id FROM {table_name} WHERE archived_at IS NOT NULL)
+            GROUP BY tag
+            ORDER BY sound_count DESC, tag
+            LIMIT {limit}"
+        );
+
+        let mut stmt = self
+            .conn
+            .prepare(sql.as_str())
+            .log_err_msg("Tag counts sql invalid")?;
+
+        let rows = stmt
+            .query_map((), |row| Ok((row.get("tag")?, row.get("sound_count")?)))
+            .log_err_msg("Tag counts sql query error")?;
 
-        log::info!("Creating tables {table_name}, {fts5_table_name}...");
+        Ok(rows.filter_map(|row| row.log_err().ok()).collect())
+    }
 
+    /// Unarchived sound counts per category id (None for uncategorized sounds), optionally
+    /// only counting sounds with tag
+    pub fn category_counts(
+        &self,
+        tag: Option<&str>,
+    ) -> Result<Vec<(Option<i64>, u64)>, PoiseError> {
+        let table_name = Self::TABLE_NAME;
+        let tags_table_name = Self::TAGS_TABLE_NAME;
         let sql = format!(
             "
-            BEGIN;
-                CREATE TABLE IF NOT EXISTS {table_name} (
-                    id INTEGER PRIMARY KEY,
-                    name VARCHAR(80) NOT NULL UNIQUE,
-                    tags VARCHAR(2048),
-                    audio_file VARCHAR(500) NOT NULL UNIQUE,
-                    created_at VARCHAR(25) NOT NULL,
-                    author_id INTEGER,
-                    author_name VARCHAR(256),
-                    author_global_name VARCHAR(256)
-                );
-
-                CREATE VIRTUAL TABLE IF NOT EXISTS {fts5_table_name} USING FTS5(
-                    name, tags, content={table_name}, content_rowid=id, tokenize='trigram remove_diacritics 1'
-                );
+            SELECT category_id, COUNT(*) AS sound_count FROM {table_name}
+            WHERE archived_at IS NULL
+                AND (?1 IS NULL OR id IN (SELECT audio_id FROM {tags_table_name} WHERE tag = ?1))
+            GROUP BY category_id"
+        );
 
-                CREATE TRIGGER IF NOT EXISTS {table_name}_insert AFTER INSERT ON {table_name} BEGIN
-                    INSERT INTO {fts5_table_name}(rowid, name, tags)
-                        VALUES (new.id, new.name, new.tags);
-                END;
+        let mut stmt = self
+            .conn
+            .prepare(sql.as_str())
+            .log_err_msg("Category counts sql invalid")?;
 
-                CREATE TRIGGER IF NOT EXISTS {table_name}_delete AFTER DELETE ON {table_name} BEGIN
-                    INSERT INTO {fts5_table_name}({fts5_table_name}, rowid, name, tags)
-                        VALUES('delete', old.id, old.name, old.tags);
-                END;
+        let rows = stmt
+            .query_map(params![&tag], |row| {
+                Ok((row.get("category_id")?, row.get("sound_count")?))
+            })
+            .log_err_msg("Category counts sql query error")?;
 
-                CREATE TRIGGER IF NOT EXISTS {table_name}_update AFTER UPDATE ON {table_name} BEGIN
-                    INSERT INTO {fts5_table_name}({fts5_table_name}, rowid, name, tags)
-                        VALUES('delete', old.id, old.name, old.tags);
+        Ok(rows.filter_map(|row| row.log_err().ok()).collect())
+    }
 
-                    INSERT INTO {fts5_table_name}(rowid, name, tags)
-                        VALUES (new.id, new.name, new.tags);
-                END;
-            COMMIT;"
+    /// Increment play count of audio track and mark it as played now
+    pub fn increment_play_count(&self, row_id: i64) -> Result<(), PoiseError> {
+        let table_name = Self::TABLE_NAME;
+        let sql = format!(
+            "
+            UPDATE {table_name}
+            SET
+                play_count = play_count + 1,
+                last_played_at = ?
+            WHERE
+                id = ?;
+        "
         );
 
         self.conn
-            .execute_batch(sql.as_str())
-            .log_err_msg(format!("Failed creating table:{table_name}"))
-            .unwrap();
+            .execute(sql.as_str(), params![&chrono::Utc::now(), &row_id])
+            .log_err_msg("Failed incrementing audio track play count")?;
 
-        log::info!("Created tables {table_name}, {fts5_table_name}!");
+        Ok(())
     }
-}
+
+    #[tracing::instrument(name = "db.find_audio_row", skip_all)]
+    pub fn find_audio_row(&self, col: impl AsRef<UniqueAudioTableCol>) -> Option<AudioTableRow> {
+        let col = col.as_ref();
+        let col_value = col.value();
+        let table_name = Self::TABLE_NAME;
+
+        let sql_condition = col.sql_condition();
+        let sql = format!("SELECT * FROM {table_name} WHERE {sql_condition}");
+
+        self.conn
+            .query_row(sql.as_str(), params![&col_value], |row| {
+                AudioTableRow::try_from(row)
+            })
+            .log_err_msg(format!("Failed to find audio row - {col:?}"))
+            .ok()
+    }
+
+    /// Random unarchived audio row, with normalized tag if set. None when there are no such
+    /// audio rows
+    pub fn random_audio_row(&self, tag: Option<&str>) -> Option<AudioTableRow> {
+        let table_name = Self::TABLE_NAME;
+        let tags_table_name = Self::TAGS_TABLE_NAME;
+        let sql = format!(
+            "
+            SELECT * FROM {table_name}
+            WHERE archived_at IS NULL
+                AND (?1 IS NULL OR id IN (SELECT audio_id FROM {tags_table_name} WHERE tag = ?1))
+            ORDER BY RANDOM()
+            LIMIT 1"
+        );
+
+        self.conn
+            .query_row(sql.as_str(), params![&tag], |row| {
+                AudioTableRow::try_from(row)
+            })
+            .optional()
+            .log_err_msg("Failed to find random audio row")
+            .ok()
+            .flatten()
+    }
+
+    /// Most played audio row among rows played since `since`. Plays aren't timestamped
+    /// individually, so this is the all-time most played sound that was played recently
+    pub fn most_played_audio_row_since(
+        &self,
+        since: chrono::DateTime<chrono::Utc>,
+    ) -> Option<AudioTableRow> {
+        let table_name = Self::TABLE_NAME;
+        let sql = format!(
+            "
+            SELECT * FROM {table_name}
+            WHERE last_played_at >= ?1 AND archived_at IS NULL
+            ORDER BY play_count DESC, last_played_at DESC
+            LIMIT 1"
+        );
+
+        self.conn
+            .query_row(sql.as_str(), params![&since], |row| {
+                AudioTableRow::try_from(row)
+            })
+            .optional()
+            .log_err_msg("Failed to find most played audio row")
+            .ok()
+            .flatten()
+    }
+
+    /// Unarchived audio row played least recently (never played first), excluding `exclude_id`.
+    /// None when there are no other unarchived audio rows
+    pub fn least_recently_played_audio_row(
+        &self,
+        exclude_id: Option<i64>,
+    ) -> Option<AudioTableRow> {
+        let table_name = Self::TABLE_NAME;
+        let sql = format!(
+            "
+            SELECT * FROM {table_name}
+            WHERE archived_at IS NULL AND (?1 IS NULL OR id != ?1)
+            ORDER BY last_played_at ASC, play_count ASC, RANDOM()
+            LIMIT 1"
+        );
+
+        self.conn
+            .query_row(sql.as_str(), params![exclude_id], |row| {
+                AudioTableRow::try_from(row)
+            })
+            .optional()
+            .log_err_msg("Failed to find least recently played audio row")
+            .ok()
+            .flatten()
+    }
+
+    /// Unarchived audio rows played at least once, most played first
+    pub fn most_played_audio_rows(&self, limit: u64) -> Result<Vec<AudioTableRow>, PoiseError> {
+        let table_name = Self::TABLE_NAME;
+        let sql = format!(
+            "
+            SELECT * FROM {table_name}
+            WHERE archived_at IS NULL AND play_count > 0
+            ORDER BY play_count DESC, last_played_at DESC
+            LIMIT ?1"
+        );
+
+        let mut stmt = self
+            .conn
+            .prepare(sql.as_str())
+            .log_err_msg("Most played audio rows sql invalid")?;
+
+        let rows = stmt
+            .query_map(params![&limit], |row| AudioTableRow::try_from(row))
+            .log_err_msg("Most played audio rows sql query error")?;
+
+        Ok(rows.filter_map(|row| row.log_err().ok()).collect())
+    }
+
+    /// Unarchived audio rows played least recently, never played first (oldest first among them)
+    pub fn forgotten_audio_rows(&self, limit: u64) -> Result<Vec<AudioTableRow>, PoiseError> {
+        let table_name = Self::TABLE_NAME;
+        let sql = format!(
+            "
+            SELECT * FROM {table_name}
+            WHERE archived_at IS NULL
+            ORDER BY last_played_at ASC, created_at ASC, id
+            LIMIT ?1"
+        );
+
+        let mut stmt = self
+            .conn
+            .prepare(sql.as_str())
+            .log_err_msg("Forgotten audio rows sql invalid")?;
+
+        let rows = stmt
+            .query_map(params![&limit], |row| AudioTableRow::try_from(row))
+            .log_err_msg("Forgotten audio rows sql query error")?;
+
+        Ok(rows.filter_map(|row| row.log_err().ok()).collect())
+    }
+
+    /// Insert audio row. Taken names are reported as `InsertAudioError::NameTaken`, so callers
+    /// can offer another name
+    #[tracing::instrument(name = "db.insert_audio_row", skip_all)]
+    pub fn insert_audio_row(
+        &self,
+        audio_row: impl AsRef<AudioTableRowInsert>,
+    ) -> Result<(), InsertAudioError> {
+        let audio_row = audio_row.as_ref();
+
+        log::info!(
+            "Inserting audio row. Name: {}, File: {}",
+            audio_row.name,
+            audio_row.audio_file.to_string_lossy()
+        );
+        let sql = Self::insert_sql();
+
+        match self
+            .connection()
+            .execute(sql.as_str(), audio_row.sql_params())
+        {
+            Ok(_) => Ok(()),
+            Err(err) if Self::is_name_taken_err(&err) => {
+                log::info!("Audio row name taken. Name: {}", audio_row.name);
+                Err(InsertAudioError::NameTaken {
+                    name: audio_row.name.to_string(),
+                })
+            }
+            Err(err) => {
+                log::error!("Failed to insert audio row - {err}");
+                Err(err.into())
+            }
+        }
+    }
+
+    fn is_name_taken_err(err: &rusqlite::Error) -> bool {
+        let table_name = Self::TABLE_NAME;
+        match err {
+            rusqlite::Error::SqliteFailure(err, Some(msg)) => {
+                err.extended_code == rusqlite::ffi::SQLITE_CONSTRAINT_UNIQUE
+                    && msg.ends_with(&format!("{table_name}.name"))
+            }
+            _ => false,
+        }
+    }
+
+    /// Insert audio rows in a single transaction. Rows that fail to insert (i.e. duplicate names)
+    /// are logged and skipped. Returns number of rows inserted
+    /// **note**: With `rebuild_fts`, the full text search index isn't updated per row but rebuilt
+    /// once all rows are inserted, which is much faster for thousands of rows but slower for a
+    /// few rows in a large table (see `vars::FTS_REBUILD_MIN_ROWS`)
+    pub fn insert_many(
+        &self,
+        audio_rows: impl IntoIterator<Item = AudioTableRowInsert>,
+        rebuild_fts: bool,
+    ) -> Result<usize, PoiseError> {
+        let table_name = Self::TABLE_NAME;
+        let fts5_table_name = Self::FTS5_TABLE_NAME;
+        let tx = self
+            .conn
+            .unchecked_transaction()
+            .log_err_msg("Failed to begin insert_many transaction")?;
+
+        // the trigger is dropped within the transaction, so other connections never miss it
+        if rebuild_fts {
+            tx.execute_batch(&format!("DROP TRIGGER IF EXISTS {table_name}_insert;"))
+                .log_err_msg("Failed to drop full text search insert trigger")?;
+        }
+
+        let mut inserted = 0;
+        {
+            let sql = Self::insert_sql();
+            let mut stmt = tx
+                .prepare(sql.as_str())
+                .log_err_msg("Failed to prepare insert_many sql")?;
+
+            for audio_row in audio_rows {
+                log::info!(
+                    "Inserting audio row. Name: {}, File: {}",
+                    audio_row.name,
+                    audio_row.audio_file.to_string_lossy()
+                );
+
+                stmt.execute(audio_row.sql_params())
+                    .log_err_msg(format!("Failed to insert audio row '{}'", audio_row.name))
+                    .map(|_| inserted += 1)
+                    .ok();
+            }
+        }
+
+        if rebuild_fts {
+            log::info!("Rebuilding full text search index. Inserted: {inserted}");
+            tx.execute_batch(&format!(
+                "
+                INSERT INTO {fts5_table_name}({fts5_table_name}) VALUES('rebuild');
+                {}",
+                Self::fts_insert_trigger_sql()
+            ))
+            .log_err_msg("Failed to rebuild full text search index")?;
+        }
+
+        tx.commit()
+            .log_err_msg("Failed to commit insert_many transaction")?;
+
+        Ok(inserted)
+    }
+
+    /// Trigger adding inserted rows to the full text search index
+    fn fts_insert_trigger_sql() -> String {
+        let table_name = Self::TABLE_NAME;
+        let fts5_table_name = Self::FTS5_TABLE_NAME;
+        format!(
+            "
+            CREATE TRIGGER IF NOT EXISTS {table_name}_insert AFTER INSERT ON {table_name} BEGIN
+                INSERT INTO {fts5_table_name}(rowid, name, tags)
+                    VALUES (new.id, new.name, new.tags);
+            END;"
+        )
+    }
+
+    /// Table-valued `json_each` over the space separated tags of `row` (`new` in triggers,
+    /// or an audio table alias). Tags are cleaned to letters, digits, `-` and `_`, so can be
+    /// split as a json array
+    pub(crate) fn json_each_tags_sql(row: &str) -> String {
+        let tags_json = format!("'[\"' || replace(trim({row}.tags), ' ', '\",\"') || '\"]'");
+        format!("json_each(CASE WHEN json_valid({tags_json}) THEN {tags_json} ELSE '[]' END)")
+    }
+
+    fn insert_sql() -> String {
+        let table_name = Self::TABLE_NAME;
+        format!(
+            "
+            INSERT INTO {table_name}
+                (name, tags, audio_file, created_at, author_id, author_name, author_global_name, guild_id, duration_ms, file_size, author_hash, source_url, slug)
+            VALUES
+                (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)"
+        )
+    }
+
+    pub fn update_audio_row(&self, audio_row: impl AsRef<AudioTableRow>) -> Result<(), String> {
+        let audio_row = audio_row.as_ref();
+        log::info!("Updating audio row. Name: {}", audio_row.name);
+
+        let table_name = Self::TABLE_NAME;
+        let name = &audio_row.name;
+        let tags = &audio_row.tags;
+        let row_id = audio_row.id;
+
+        let sql = format!(
+            "
+            UPDATE {table_name}
+            SET
+                name = ?,
+                slug = ?,
+                tags = ?
+            WHERE
+                id = ?;
+        "
+        );
+
+        self.conn
+            .execute(sql.as_str(), params![&name, name.slug(), &tags, &row_id])
+            .log_err_msg("Failed updating audio track")
+            .map_err(|err| err.to_string())?;
+
+        log::info!("Updated audio row. Name: {name}");
+        Ok(())
+    }
+
+    /// Reassign audio row to a new author, e.g. when the uploader left the guild
+    pub fn transfer_audio_row(
+        &self,
+        row_id: i64,
+        author_id: u64,
+        author_name: impl AsRef<str>,
+        author_global_name: Option<impl AsRef<str>>,
+    ) -> Result<(), PoiseError> {
+        log::info!("Transferring audio row. Id: {row_id}, Author Id: {author_id}");
+
+        let table_name = Self::TABLE_NAME;
+        let sql = format!(
+            "
+            UPDATE {table_name}
+            SET
+                author_id = ?1,
+                author_name = ?2,
+                author_global_name = ?3,
+                author_hash = NULL
+            WHERE id = ?4"
+        );
+        let author_global_name = author_global_name.as_ref().map(AsRef::as_ref);
+
+        self.conn
+            .execute(
+                sql.as_str(),
+                params![
+                    &author_id,
+                    author_name.as_ref(),
+                    &author_global_name,
+                    &row_id
+                ],
+            )
+            .log_err_msg("Failed to transfer audio row")?;
+
+        Ok(())
+    }
+
+    /// Audio rows matching filter, by name
+    pub fn filtered_audio_rows(
+        &self,
+        filter: &AudioTableFilter,
+    ) -> Result<Vec<AudioTableRow>, PoiseError> {
+        let table_name = Self::TABLE_NAME;
+        let sql_condition = AudioTableFilter::sql_condition();
+        let sql = format!("SELECT * FROM {table_name} WHERE {sql_condition} ORDER BY name");
+
+        let mut stmt = self
+            .conn
+            .prepare(sql.as_str())
+            .log_err_msg("Filtered audio rows sql invalid")?;
+
+        let rows = stmt
+            .query_map(filter.sql_params(), |row| AudioTableRow::try_from(row))
+            .log_err_msg("Filtered audio rows sql query error")?;
+
+        Ok(rows.filter_map(|row| row.log_err().ok()).collect())
+    }
+
+    /// Delete audio rows (and their audio files) matching filter. Returns number of rows deleted.
+    /// An empty filter is rejected, so a missing filter can't wipe the whole library
+    pub fn delete_filtered_audio_rows(
+        &self,
+        filter: &AudioTableFilter,
+    ) -> Result<usize, PoiseError> {
+        if filter.is_empty() {
+            log::error!("Refusing to bulk delete audio rows without a filter");
+            return Err("Refusing to bulk delete without a filter".into());
+        }
+
+        log::info!("Bulk deleting audio rows - {filter:?}");
+
+        let rows = self.filtered_audio_rows(filter)?;
+        let table_name = Self::TABLE_NAME;
+        let sql_condition = AudioTableFilter::sql_condition();
+        let sql = format!("DELETE FROM {table_name} WHERE {sql_condition}");
+
+        let deleted = self
+            .conn
+            .execute(sql.as_str(), filter.sql_params())
+            .log_err_msg("Failed to bulk delete audio rows")?;
+
+        for row in rows {
+            row.audio_file.delete();
+        }
+
+        Ok(deleted)
+    }
+
+    /// Replace tags of audio rows matching filter. Returns number of rows updated.
+    /// An empty filter is rejected
+    pub fn retag_filtered_audio_rows(
+        &self,
+        filter: &AudioTableFilter,
+        tags: &Tags,
+    ) -> Result<usize, PoiseError> {
+        if filter.is_empty() {
+            log::error!("Refusing to bulk retag audio rows without a filter");
+            return Err("Refusing to bulk retag without a filter".into());
+        }
+
+        log::info!(
+            "Bulk retagging audio rows - {filter:?}, Tags: {}",
+            tags.to_string()
+        );
+
+        let table_name = Self::TABLE_NAME;
+        let sql_condition = AudioTableFilter::sql_condition();
+        let sql = format!("UPDATE {table_name} SET tags = ?4 WHERE {sql_condition}");
+
+        let updated = self
+            .conn
+            .execute(
+                sql.as_str(),
+                params![&filter.tag, &filter.author_id, &filter.created_before, tags],
+            )
+            .log_err_msg("Failed to bulk retag audio rows")?;
+
+        Ok(updated)
+    }
+
+    /// Unarchived audio rows of guild (or without a guild) that haven't been played since
+    /// `unused_since`, least recently played first. Rows added or kept (see
+    /// `keep_audio_row`) since then are left out
+    pub fn unused_audio_rows(
+        &self,
+        guild_id: u64,
+        unused_since: chrono::DateTime<chrono::Utc>,
+        limit: u64,
+    ) -> Result<Vec<AudioTableRow>, PoiseError> {
+        let table_name = Self::TABLE_NAME;
+        let play_events_table_name = PlayEventsTable::TABLE_NAME;
+        let sql = format!(
+            "
+            SELECT * FROM {table_name} a
+            WHERE a.archived_at IS NULL
+                AND (a.guild_id IS NULL OR a.guild_id = ?1)
+                AND a.created_at < ?2
+                AND (a.last_played_at IS NULL OR a.last_played_at < ?2)
+                AND (a.prune_kept_at IS NULL OR a.prune_kept_at < ?2)
+                AND NOT EXISTS (
+                    SELECT 1 FROM {play_events_table_name} e
+                    WHERE e.audio_id = a.id AND e.played_at >= ?2
+                )
+            ORDER BY COALESCE(a.last_played_at, a.created_at) ASC, a.name
+            LIMIT ?3"
+        );
+
+        let mut stmt = self
+            .conn
+            .prepare(sql.as_str())
+            .log_err_msg("Unused audio rows sql invalid")?;
+
+        let rows = stmt
+            .query_map(params![&guild_id, &unused_since, &limit], |row| {
+                AudioTableRow::try_from(row)
+            })
+            .log_err_msg("Unused audio rows sql query error")?;
+
+        Ok(rows.filter_map(|row| row.log_err().ok()).collect())
+    }
+
+    /// Archive or unarchive audio row. Unarchived rows are also kept (see `keep_audio_row`),
+    /// so auto-archive doesn't archive them again right away
+    pub fn set_archived(&self, row_id: i64, archived: bool) -> Result<(), PoiseError> {
+        log::info!("Setting audio row archived. Id: {row_id}, Archived: {archived}");
+
+        let table_name = Self::TABLE_NAME;
+        let sql = format!(
+            "
+            UPDATE {table_name}
+            SET
+                archived_at = ?1,
+                prune_kept_at = COALESCE(?2, prune_kept_at)
+            WHERE id = ?3"
+        );
+        let now = chrono::Utc::now();
+        let archived_at = archived.then_some(now);
+        let kept_at = (!archived).then_some(now);
+
+        self.conn
+            .execute(sql.as_str(), params![&archived_at, &kept_at, &row_id])
+            .log_err_msg("Failed to set audio row archived")?;
+
+        Ok(())
+    }
+
+    /// Set or clear (None) emoji shown on the sound's button
+    pub fn set_emoji(&self, row_id: i64, emoji: Option<&str>) -> Result<(), PoiseError> {
+        log::info!("Setting audio row emoji. Id: {row_id}, Emoji: {emoji:?}");
+
+        let table_name = Self::TABLE_NAME;
+        let sql = format!("UPDATE {table_name} SET emoji = ?1 WHERE id = ?2");
+
+        self.conn
+            .execute(sql.as_str(), params![&emoji, &row_id])
+            .log_err_msg("Failed to set audio row emoji")?;
+
+        Ok(())
+    }
+
+    /// Set or clear (None) style of the sound's button
+    pub fn set_button_style(&self, row_id: i64, style: Option<&str>) -> Result<(), PoiseError> {
+        log::info!("Setting audio row button style. Id: {row_id}, Style: {style:?}");
+
+        let table_name = Self::TABLE_NAME;
+        let sql = format!("UPDATE {table_name} SET button_style = ?1 WHERE id = ?2");
+
+        self.conn
+            .execute(sql.as_str(), params![&style, &row_id])
+            .log_err_msg("Failed to set audio row button style")?;
+
+        Ok(())
+    }
+
+    /// Set or clear (None) category of the sound
+    pub fn set_category(&self, row_id: i64, category_id: Option<i64>) -> Result<(), PoiseError> {
+        log::info!("Setting audio row category. Id: {row_id}, Category Id: {category_id:?}");
+
+        let table_name = Self::TABLE_NAME;
+        let sql = format!("UPDATE {table_name} SET category_id = ?1 WHERE id = ?2");
+
+        self.conn
+            .execute(sql.as_str(), params![&category_id, &row_id])
+            .log_err_msg("Failed to set audio row category")?;
+
+        Ok(())
+    }
+
+    /// Set probed track duration and file size
+    pub fn set_metadata(
+        &self,
+        row_id: i64,
+        duration_ms: u64,
+        file_size: u64,
+    ) -> Result<(), PoiseError> {
+        log::info!(
+            "Setting audio row metadata. Id: {row_id}, Duration: {duration_ms}ms, Size: {file_size}"
+        );
+
+        let table_name = Self::TABLE_NAME;
+        let sql = format!("UPDATE {table_name} SET duration_ms = ?1, file_size = ?2 WHERE id = ?3");
+
+        self.conn
+            .execute(sql.as_str(), params![&duration_ms, &file_size, &row_id])
+            .log_err_msg("Failed to set audio row metadata")?;
+
+        Ok(())
+    }
+
+    /// Point sound at a re-fetched audio file, updating its duration and file size. Its content
+    /// hash is cleared, to be recorded again on its next play
+    pub fn replace_audio_file(
+        &self,
+        row_id: i64,
+        audio_file: &audio::AudioFile,
+        duration_ms: u64,
+        file_size: u64,
+    ) -> Result<(), PoiseError> {
+        log::info!("Replacing audio row file. Id: {row_id}, File: {audio_file:?}");
+
+        let table_name = Self::TABLE_NAME;
+        let sql = format!(
+            "UPDATE {table_name} SET audio_file = ?1, duration_ms = ?2, file_size = ?3, content_hash = NULL WHERE id = ?4"
+        );
+
+        self.conn
+            .execute(
+                sql.as_str(),
+                params![audio_file, &duration_ms, &file_size, &row_id],
+            )
+            .log_err_msg("Failed to replace audio row file")?;
+
+        Ok(())
+    }
+
+    /// Set hex encoded SHA-256 of sound's audio file
+    pub fn set_content_hash(&self, row_id: i64, content_hash: &str) -> Result<(), PoiseError> {
+        log::info!("Setting audio row content hash. Id: {row_id}, Hash: {content_hash}");
+
+        let table_name = Self::TABLE_NAME;
+        let sql = format!("UPDATE {table_name} SET content_hash = ?1 WHERE id = ?2");
+
+        self.conn
+            .execute(sql.as_str(), params![&content_hash, &row_id])
+            .log_err_msg("Failed to set audio row content hash")?;
+
+        Ok(())
+    }
+
+    /// Audio rows, archived or not, without a content hash, ordered by id. Only rows with an
+    /// id after `after_id` are included, so rows that fail to hash can be skipped
+    pub fn audio_rows_missing_content_hash(
+        &self,
+        after_id: i64,
+        limit: u64,
+    ) -> Result<Vec<AudioTableRow>, PoiseError> {
+        let table_name = Self::TABLE_NAME;
+        let sql = format!(
+            "
+            SELECT * FROM {table_name}
+            WHERE id > ?1 AND content_hash IS NULL
+            ORDER BY id
+            LIMIT ?2"
+        );
+
+        let mut stmt = self
+            .conn
+            .prepare(sql.as_str())
+            .log_err_msg("Audio rows missing content hash sql invalid")?;
+
+        let rows = stmt
+            .query_map(params![&after_id, &limit], |row| {
+                AudioTableRow::try_from(row)
+            })
+            .log_err_msg("Audio rows missing content hash sql query error")?;
+
+        Ok(rows.filter_map(|row| row.log_err().ok()).collect())
+    }
+
+    /// Audio rows, archived or not, without a probed duration or file size, ordered by id.
+    /// Only rows with an id after `after_id` are included, so rows that fail to probe can be
+    /// skipped
+    pub fn audio_rows_missing_metadata(
+        &self,
+        after_id: i64,
+        limit: u64,
+    ) -> Result<Vec<AudioTableRow>, PoiseError> {
+        let table_name = Self::TABLE_NAME;
+        let sql = format!(
+            "
+            SELECT * FROM {table_name}
+            WHERE id > ?1 AND (duration_ms IS NULL OR file_size IS NULL)
+            ORDER BY id
+            LIMIT ?2"
+        );
+
+        let mut stmt = self
+            .conn
+            .prepare(sql.as_str())
+            .log_err_msg("Audio rows missing metadata sql invalid")?;
+
+        let rows = stmt
+            .query_map(params![&after_id, &limit], |row| {
+                AudioTableRow::try_from(row)
+            })
+            .log_err_msg("Audio rows missing metadata sql query error")?;
+
+        Ok(rows.filter_map(|row| row.log_err().ok()).collect())
+    }
+
+    /// Audio row, archived or not, with the same probed duration and file size. Re-uploads of
+    /// a file are found this way, even under another name
+    #[tracing::instrument(name = "db.find_duplicate_audio_row", skip_all)]
+    pub fn find_duplicate_audio_row(
+        &self,
+        duration_ms: u64,
+        file_size: u64,
+    ) -> Option<AudioTableRow> {
+        let table_name = Self::TABLE_NAME;
+        let sql =
+            format!("SELECT * FROM {table_name} WHERE duration_ms = ?1 AND file_size = ?2 LIMIT 1");
+
+        self.conn
+            .query_row(sql.as_str(), params![&duration_ms, &file_size], |row| {
+                AudioTableRow::try_from(row)
+            })
+            .optional()
+            .log_err_msg("Failed to find duplicate audio row")
+            .ok()
+            .flatten()
+    }
+
+    /// Keep audio row, so it isn't suggested for pruning until it's unused for a while again
+    pub fn keep_audio_row(&self, row_id: i64) -> Result<(), PoiseError> {
+        log::info!("Keeping audio row. Id: {row_id}");
+
+        let table_name = Self::TABLE_NAME;
+        let sql = format!("UPDATE {table_name} SET prune_kept_at = ?1 WHERE id = ?2");
+
+        self.conn
+            .execute(sql.as_str(), params![&chrono::Utc::now(), &row_id])
+            .log_err_msg("Failed to keep audio row")?;
+
+        Ok(())
+    }
+
+    /// Audio rows matching query by name or tags, best match first. Archived rows are only
+    /// included when `include_archived` is set
+    /// **note**: Queries without a term of at least 3 chars match names containing the query
+    /// instead
+    pub fn search_audio_rows(
+        &self,
+        query: impl AsRef<str>,
+        include_archived: bool,
+        limit: u64,
+    ) -> Result<Vec<AudioTableRow>, PoiseError> {
+        let query = query.as_ref();
+        let table_name = Self::TABLE_NAME;
+        let fts5_table_name = Self::FTS5_TABLE_NAME;
+        let fts_query = Self::fts_query(query);
+        let sql = match fts_query {
+            None => format!(
+                "
+                SELECT * FROM {table_name}
+                WHERE name LIKE '%' || ?1 || '%'
+                    AND (?2 OR archived_at IS NULL)
+                ORDER BY name
+                LIMIT ?3"
+            ),
+            Some(_) => format!(
+                "
+                SELECT a.* FROM {fts5_table_name}(?1) f
+                JOIN {table_name} a ON a.id = f.rowid
+                WHERE ?2 OR a.archived_at IS NULL
+                ORDER BY f.rank
+                LIMIT ?3"
+            ),
+        };
+
+        let mut stmt = self
+            .conn
+            .prepare(sql.as_str())
+            .log_err_msg("Search audio rows sql invalid")?;
+
+        let rows = stmt
+            .query_map(
+                params![
+                    fts_query.as_deref().unwrap_or(query),
+                    &include_archived,
+                    &limit
+                ],
+                |row| AudioTableRow::try_from(row),
+            )
+            .log_err_msg("Search audio rows sql query error")?;
+
+        Ok(rows.filter_map(|row| row.log_err().ok()).collect())
+    }
+
+    /// Audio rows of every guild, archived included, whose name or uploader's name contains
+    /// query, uploaded by or in the guild of the id query is, or with a content hash starting
+    /// with `hash_prefix`. For owners finding content across guilds
+    pub fn search_all_audio_rows(
+        &self,
+        query: impl AsRef<str>,
+        hash_prefix: Option<&str>,
+        limit: u64,
+    ) -> Result<Vec<AudioTableRow>, PoiseError> {
+        let table_name = Self::TABLE_NAME;
+        let sql = format!(
+            "
+            SELECT * FROM {table_name}
+            WHERE name LIKE '%' || ?1 || '%'
+                OR author_name LIKE '%' || ?1 || '%'
+                OR author_global_name LIKE '%' || ?1 || '%'
+                OR CAST(author_id AS TEXT) = ?1
+                OR CAST(guild_id AS TEXT) = ?1
+                OR (?2 IS NOT NULL AND content_hash LIKE ?2 || '%')
+            ORDER BY id
+            LIMIT ?3"
+        );
+
+        let mut stmt = self
+            .conn
+            .prepare(sql.as_str())
+            .log_err_msg("Search all audio rows sql invalid")?;
+
+        let rows = stmt
+            .query_map(params![query.as_ref(), &hash_prefix, &limit], |row| {
+                AudioTableRow::try_from(row)
+            })
+            .log_err_msg("Search all audio rows sql query error")?;
+
+        Ok(rows.filter_map(|row| row.log_err().ok()).collect())
+    }
+
+    /// Audio rows of every guild, archived included, whose audio file has content hash
+    pub fn audio_rows_by_content_hash(
+        &self,
+        content_hash: &str,
+    ) -> Result<Vec<AudioTableRow>, PoiseError> {
+        let table_name = Self::TABLE_NAME;
+        let sql = format!("SELECT * FROM {table_name} WHERE content_hash = ?1 ORDER BY id");
+
+        let mut stmt = self
+            .conn
+            .prepare(sql.as_str())
+            .log_err_msg("Audio rows by content hash sql invalid")?;
+
+        let rows = stmt
+            .query_map(params![content_hash], |row| AudioTableRow::try_from(row))
+            .log_err_msg("Audio rows by content hash sql query error")?;
+
+        Ok(rows.filter_map(|row| row.log_err().ok()).collect())
+    }
+
+    /// Names of archived audio tracks containing partial string, most recently archived first
+    pub fn archived_track_names(&self, partial: impl AsRef<str>, limit: usize) -> Vec<String> {
+        let table_name = Self::TABLE_NAME;
+        let sql = format!(
+            "
+            SELECT name FROM {table_name}
+            WHERE archived_at IS NOT NULL AND name LIKE '%' || ?1 || '%'
+            ORDER BY archived_at DESC
+            LIMIT {limit}"
+        );
+
+        let mut stmt = self
+            .conn
+            .prepare(sql.as_str())
+            .expect("Archived track names sql invalid");
+
+        let rows = stmt.query_map(params![partial.as_ref()], |row| row.get("name"));
+        match rows {
+            Ok(rows) => rows.filter_map(|row| row.ok()).collect(),
+            Err(err) => {
+                log::error!("Archived track names sql query error - {err}");
+                vec![]
+            }
+        }
+    }
+
+    pub fn delete_audio_row(&self, col: impl AsRef<UniqueAudioTableCol>) -> Result<(), PoiseError> {
+        let column = col.as_ref();
+        match self.find_audio_row(&col) {
+            None => log::info!("Can't delete non-existent audio track. {column:?}"),
+            Some(row) => {
+                row.audio_file.delete();
+                self.remove_audio_row(row.id)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Delete audio row, leaving its audio file in place (e.g. for `trash` to move it)
+    pub fn remove_audio_row(&self, row_id: i64) -> Result<(), PoiseError> {
+        let table_name = Self::TABLE_NAME;
+        let sql = format!("DELETE FROM {table_name} WHERE id = ?1");
+
+        self.conn
+            .execute(sql.as_str(), params![&row_id])
+            .log_err_msg("Failed to delete audio row")?;
+
+        Ok(())
+    }
+}
+
+impl Table for AudioTable {
+    fn connection(&self) -> &DbConnection {
+        &self.conn
+    }
+
+    fn create_table(&self) {
+        let table_name = Self::TABLE_NAME;
+        let fts5_table_name = Self::FTS5_TABLE_NAME;
+        let tags_table_name = Self::TAGS_TABLE_NAME;
+
+        log::info!("Creating tables {table_name}, {fts5_table_name}, {tags_table_name}...");
+
+        let sql = format!(
+            "
+            BEGIN;
+                CREATE TABLE IF NOT EXISTS {table_name} (
+                    id INTEGER PRIMARY KEY,
+                    name VARCHAR(80) NOT NULL UNIQUE,
+                    tags VARCHAR(2048),
+                    audio_file VARCHAR(500) NOT NULL UNIQUE,
+                    created_at VARCHAR(25) NOT NULL,
+                    author_id INTEGER,
+                    author_name VARCHAR(256),
+                    author_global_name VARCHAR(256),
+                    guild_id INTEGER,
+                    play_count INTEGER NOT NULL DEFAULT 0,
+                    last_played_at VARCHAR(25),
+                    archived_at VARCHAR(25),
+                    prune_kept_at VARCHAR(25),
+                    emoji VARCHAR(64),
+                    duration_ms INTEGER,
+                    file_size INTEGER,
+                    author_hash VARCHAR(64),
+                    category_id INTEGER,
+                    button_style VARCHAR(16),
+                    source_url VARCHAR(2048),
+                    content_hash VARCHAR(64),
+                    slug VARCHAR(256)
+                );
+
+                CREATE VIRTUAL TABLE IF NOT EXISTS {fts5_table_name} USING FTS5(
+                    name, tags, content={table_name}, content_rowid=id, tokenize='trigram remove_diacritics 1'
+                );
+
+                {fts_insert_trigger}
+
+                CREATE TRIGGER IF NOT EXISTS {table_name}_delete AFTER DELETE ON {table_name} BEGIN
+                    INSERT INTO {fts5_table_name}({fts5_table_name}, rowid, name, tags)
+                        VALUES('delete', old.id, old.name, old.tags);
+                END;
+
+                CREATE TRIGGER IF NOT EXISTS {table_name}_update AFTER UPDATE ON {table_name} BEGIN
+                    INSERT INTO {fts5_table_name}({fts5_table_name}, rowid, name, tags)
+                        VALUES('delete', old.id, old.name, old.tags);
+
+                    INSERT INTO {fts5_table_name}(rowid, name, tags)
+                        VALUES (new.id, new.name, new.tags);
+                END;
+
+                -- normalized (lowercase, one row per tag) copy of audio tags
+                CREATE TABLE IF NOT EXISTS {tags_table_name} (
+                    audio_id INTEGER NOT NULL,
+                    tag VARCHAR(80) NOT NULL,
+                    PRIMARY KEY (tag, audio_id)
+                );
+
+                CREATE TRIGGER IF NOT EXISTS {table_name}_insert_{tags_table_name} AFTER INSERT ON {table_name} BEGIN
+                    INSERT OR IGNORE INTO {tags_table_name}(audio_id, tag)
+                        SELECT new.id, lower(value) FROM {json_each_new_tags} WHERE value != '';
+                END;
+
+                CREATE TRIGGER IF NOT EXISTS {table_name}_delete_{tags_table_name} AFTER DELETE ON {table_name} BEGIN
+                    DELETE FROM {tags_table_name} WHERE audio_id = old.id;
+                END;
+
+                CREATE TRIGGER IF NOT EXISTS {table_name}_update_{tags_table_name} AFTER UPDATE OF tags ON {table_name} BEGIN
+                    DELETE FROM {tags_table_name} WHERE audio_id = old.id;
+                    INSERT OR IGNORE INTO {tags_table_name}(audio_id, tag)
+                        SELECT new.id, lower(value) FROM {json_each_new_tags} WHERE value != '';
+                END;
+            COMMIT;",
+            json_each_new_tags = Self::json_each_tags_sql("new"),
+            fts_insert_trigger = Self::fts_insert_trigger_sql()
+        );
+
+        self.conn
+            .execute_batch(sql.as_str())
+            .log_err_msg(format!("Failed creating table:{table_name}"))
+            .unwrap();
+
+        log::info!("Created tables {table_name}, {fts5_table_name}!");
+    }
+}
 
 #[allow(unused)]
 #[derive(Debug)]
@@ -422,128 +1824,983 @@ impl AudioTableOrderBy {
 
 #[cfg(test)]
 mod tests {
-    use crate::helpers::{self, uuid_v4_str};
-    use audio::AudioFile;
-    use r2d2_sqlite::SqliteConnectionManager;
+    use crate::db::PlayEventInsert;
+    use crate::test_utils::{AudioRowFixture, TestDb};
 
     use super::*;
 
-    fn get_db_connection() -> DbConnection {
-        let db_manager = SqliteConnectionManager::memory();
-        let db_pool = r2d2::Pool::new(db_manager).unwrap();
-        db_pool.get().unwrap()
-    }
-
-    fn get_audio_table() -> AudioTable {
-        AudioTable::new(get_db_connection())
-    }
-
-    fn make_audio_table_row_insert() -> AudioTableRowInsert {
-        AudioTableRowInsert {
-            audio_file: AudioFile::new(
-                std::path::Path::new(&format!("/tmp/{}.mp3", helpers::uuid_v4_str())).to_path_buf(),
-            ),
-            author_global_name: None,
-            name: format!("{}{}", uuid_v4_str(), "#!@#$%^&*()_-+=?/.\"\\'"),
-            tags: uuid_v4_str().into(),
-            created_at: chrono::Utc::now(),
-            author_id: None,
-            author_name: None,
-        }
-    }
-
     #[test]
     fn table_create_test() {
-        let table = get_audio_table();
+        let table = TestDb::empty().audio_table();
         table.create_table(); // create table(s) & trigger(s)
         table.create_table(); // ignore table(s) & triggers(s) already created
     }
 
     #[test]
     fn table_insert_row_test() {
-        let table = get_audio_table();
-
-        table.create_table();
+        let table = TestDb::new().audio_table();
         table
-            .insert_audio_row(make_audio_table_row_insert())
+            .insert_audio_row(AudioRowFixture::new().build())
             .unwrap();
     }
 
+    #[test]
+    fn table_insert_row_name_taken_test() {
+        let table = TestDb::new().audio_table();
+        let row = AudioRowFixture::new().insert(&table);
+
+        let err = table
+            .insert_audio_row(AudioRowFixture::new().name(row.name.clone()).build())
+            .unwrap_err();
+        assert!(matches!(err, InsertAudioError::NameTaken { name } if name == row.name.as_str()));
+
+        // other constraint violations aren't name conflicts
+        let mut row_insert = AudioRowFixture::new().build();
+        row_insert.audio_file = audio::AudioFile::new(row.audio_file.to_path_buf());
+        let err = table.insert_audio_row(row_insert).unwrap_err();
+        assert!(matches!(err, InsertAudioError::Sql(_)));
+    }
+
     #[test]
     fn table_find_row_test() {
-        let table = get_audio_table();
-        table.create_table();
+        let table = TestDb::new().audio_table();
 
-        let row_insert = make_audio_table_row_insert();
+        let row_insert = AudioRowFixture::new().build();
         table.insert_audio_row(&row_insert).unwrap();
 
-        let row = table.find_audio_row(UniqueAudioTableCol::Name(row_insert.name.clone()));
+        let row = table.find_audio_row(UniqueAudioTableCol::Name(row_insert.name.to_string()));
         let row = row.unwrap();
         assert_eq!(row.name, row_insert.name);
     }
 
     #[test]
-    fn table_update_row_test() {
-        let table = get_audio_table();
-        table.create_table();
+    fn table_insert_many_test() {
+        let table = TestDb::new().audio_table();
 
-        let row_insert = make_audio_table_row_insert();
-        table.insert_audio_row(&row_insert).unwrap();
+        let duplicate = AudioRowFixture::new().build();
+        let duplicate_name = AudioRowFixture::new().name(duplicate.name.clone()).build();
 
-        let mut row = table
-            .find_audio_row(UniqueAudioTableCol::Name(row_insert.name.clone()))
-            .unwrap();
+        let rows = vec![
+            AudioRowFixture::new().build(),
+            AudioRowFixture::new().build(),
+            duplicate,
+            duplicate_name,
+        ];
+
+        // duplicate name is skipped, remaining rows are committed
+        let inserted = table.insert_many(rows, false).unwrap();
+        assert_eq!(3, inserted);
+
+        let results = table.ranked_tracks(None, 10);
+        assert_eq!(3, results.len());
+    }
+
+    #[test]
+    fn table_insert_many_rebuild_fts_test() {
+        let table = TestDb::new().audio_table();
+        AudioRowFixture::new().name("Beep Boop").insert(&table);
+
+        let rows = vec![
+            AudioRowFixture::new().name("Beep Bop").build(),
+            AudioRowFixture::new().name("Droid Beep").build(),
+        ];
+        assert_eq!(2, table.insert_many(rows, true).unwrap());
+
+        let names = |query| -> Vec<String> {
+            table
+                .search_audio_rows(query, false, 10)
+                .unwrap()
+                .into_iter()
+                .map(|row| row.name.into())
+                .collect()
+        };
+        assert_eq!(3, names("beep").len());
+        assert_eq!(vec!["Droid Beep"], names("droid"));
+
+        // insert trigger is restored for rows inserted afterwards
+        AudioRowFixture::new().name("Gonk").insert(&table);
+        assert_eq!(vec!["Gonk"], names("gonk"));
+    }
+
+    #[test]
+    fn table_update_row_test() {
+        let table = TestDb::new().audio_table();
+
+        let mut row = AudioRowFixture::new().insert(&table);
+        let old_name = row.name.clone();
 
         let new_name = String::from("New Name");
-        row.name = new_name.clone();
+        row.name = SoundName::new(&new_name).unwrap();
         table.update_audio_row(&row).unwrap();
 
-        let old_row = table.find_audio_row(UniqueAudioTableCol::Name(row_insert.name.clone()));
+        let old_row = table.find_audio_row(UniqueAudioTableCol::Name(old_name.into()));
         assert!(old_row.is_none());
 
         let updated_row = table
             .find_audio_row(UniqueAudioTableCol::Name(new_name.clone()))
             .unwrap();
 
-        assert_eq!(updated_row.name, new_name);
+        assert_eq!(updated_row.name.as_str(), new_name);
+    }
+
+    #[test]
+    fn table_set_emoji_test() {
+        let table = TestDb::new().audio_table();
+        let row = AudioRowFixture::new().name("日本語 🔊").insert(&table);
+        assert_eq!(None, row.emoji);
+
+        let find = || {
+            table
+                .find_audio_row(UniqueAudioTableCol::Id(row.id))
+                .unwrap()
+        };
+
+        table.set_emoji(row.id, Some("👨‍👩‍👧")).unwrap();
+        assert_eq!(Some("👨‍👩‍👧".to_string()), find().emoji);
+        assert_eq!("日本語 🔊", find().name.as_str());
+
+        table.set_emoji(row.id, None).unwrap();
+        assert_eq!(None, find().emoji);
+    }
+
+    #[test]
+    fn table_set_button_style_test() {
+        let table = TestDb::new().audio_table();
+        let row = AudioRowFixture::new().insert(&table);
+        assert_eq!(None, row.button_style);
+
+        let find = || {
+            table
+                .find_audio_row(UniqueAudioTableCol::Id(row.id))
+                .unwrap()
+        };
+
+        table.set_button_style(row.id, Some("danger")).unwrap();
+        assert_eq!(Some("danger".to_string()), find().button_style);
+
+        table.set_button_style(row.id, None).unwrap();
+        assert_eq!(None, find().button_style);
+    }
+
+    #[test]
+    fn table_replace_audio_file_test() {
+        let table = TestDb::new().audio_table();
+        let row = AudioRowFixture::new()
+            .source_url("https://youtu.be/abc")
+            .metadata(1500, 24_000)
+            .insert(&table);
+        assert_eq!(Some("https://youtu.be/abc".to_string()), row.source_url);
+
+        let audio_file = audio::AudioFile::new(std::env::temp_dir().join("refetched.mp3"));
+        table
+            .replace_audio_file(row.id, &audio_file, 900, 12_000)
+            .unwrap();
+
+        let row = table
+            .find_audio_row(UniqueAudioTableCol::Id(row.id))
+            .unwrap();
+        assert_eq!(audio_file, row.audio_file);
+        assert_eq!(Some(900), row.duration_ms);
+        assert_eq!(Some(12_000), row.file_size);
+        assert_eq!(Some("https://youtu.be/abc".to_string()), row.source_url);
+    }
+
+    #[test]
+    fn table_content_hash_test() {
+        let table = TestDb::new().audio_table();
+        let row = AudioRowFixture::new().insert(&table);
+        let hashed = AudioRowFixture::new().insert(&table);
+        table.set_content_hash(hashed.id, "ab").unwrap();
+
+        let missing = table.audio_rows_missing_content_hash(0, 10).unwrap();
+        assert_eq!(
+            vec![row.id],
+            missing.iter().map(|row| row.id).collect::<Vec<_>>()
+        );
+        assert!(table
+            .audio_rows_missing_content_hash(row.id, 10)
+            .unwrap()
+            .is_empty());
+
+        // re-fetched files are hashed again
+        table
+            .replace_audio_file(hashed.id, &hashed.audio_file, 900, 12_000)
+            .unwrap();
+        let find = |id| table.find_audio_row(UniqueAudioTableCol::Id(id)).unwrap();
+        assert_eq!(None, find(hashed.id).content_hash);
+    }
+
+    #[test]
+    fn table_category_counts_test() {
+        let table = TestDb::new().audio_table();
+        let categorized = AudioRowFixture::new().tags("droid").insert(&table);
+        table.set_category(categorized.id, Some(1)).unwrap();
+        AudioRowFixture::new().tags("droid").insert(&table);
+        let archived = AudioRowFixture::new().insert(&table);
+        table.set_category(archived.id, Some(1)).unwrap();
+        table.set_archived(archived.id, true).unwrap();
+        AudioRowFixture::new().insert(&table);
+
+        let mut counts = table.category_counts(None).unwrap();
+        counts.sort();
+        assert_eq!(vec![(None, 2), (Some(1), 1)], counts);
+
+        let mut counts = table.category_counts(Some("droid")).unwrap();
+        counts.sort();
+        assert_eq!(vec![(None, 1), (Some(1), 1)], counts);
+    }
+
+    #[test]
+    fn table_metadata_test() {
+        let table = TestDb::new().audio_table();
+        let row = AudioRowFixture::new().insert(&table);
+        let probed = AudioRowFixture::new().metadata(1500, 24_000).insert(&table);
+        assert_eq!(None, row.duration_ms);
+        assert_eq!(Some(1500), probed.duration_ms);
+
+        let missing = table.audio_rows_missing_metadata(0, 10).unwrap();
+        assert_eq!(
+            vec![row.id],
+            missing.iter().map(|row| row.id).collect::<Vec<_>>()
+        );
+        assert!(table
+            .audio_rows_missing_metadata(row.id, 10)
+            .unwrap()
+            .is_empty());
+
+        table.set_metadata(row.id, 900, 12_000).unwrap();
+        assert!(table.audio_rows_missing_metadata(0, 10).unwrap().is_empty());
+
+        let duplicate = table.find_duplicate_audio_row(900, 12_000).unwrap();
+        assert_eq!(row.id, duplicate.id);
+        assert_eq!(Some(12_000), duplicate.file_size);
+        assert!(table.find_duplicate_audio_row(900, 12_001).is_none());
     }
 
     #[test]
-    fn table_autocomplete_track_names_test() {
-        let table = get_audio_table();
-        table.create_table();
+    fn table_transfer_row_test() {
+        let table = TestDb::new().audio_table();
+
+        let row = AudioRowFixture::new().author_id(1).insert(&table);
+        let other = AudioRowFixture::new().author_id(1).insert(&table);
 
-        let mut row_insert = make_audio_table_row_insert();
-        row_insert.name = "Beep Boop".into();
-        row_insert.tags = Tags::from("r2d2 star wars droid");
-        table.insert_audio_row(row_insert).unwrap();
+        table
+            .transfer_audio_row(row.id, 2, "new_owner", Some("New Owner"))
+            .unwrap();
 
-        let mut row_insert = make_audio_table_row_insert();
-        row_insert.name = "Beep Bop".into();
-        row_insert.tags = Tags::from("gonk star wars droid");
-        table.insert_audio_row(row_insert).unwrap();
+        let row = table
+            .find_audio_row(UniqueAudioTableCol::Id(row.id))
+            .unwrap();
+        assert_eq!(Some(2), row.author_id);
+        assert_eq!(Some("new_owner".to_string()), row.author_name);
+        assert_eq!(Some("New Owner".to_string()), row.author_global_name);
 
-        let mut row_insert = make_audio_table_row_insert();
-        row_insert.name = "Beez's Biz".into();
-        row_insert.tags = Tags::from("random sound-effect");
-        table.insert_audio_row(row_insert).unwrap();
+        let other = table
+            .find_audio_row(UniqueAudioTableCol::Id(other.id))
+            .unwrap();
+        assert_eq!(Some(1), other.author_id);
+
+        // new owner's personal soundboard includes the sound
+        let ids: Vec<_> = table
+            .personal_audio_rows(2, 10)
+            .unwrap()
+            .iter()
+            .map(|row| row.id)
+            .collect();
+        assert_eq!(vec![row.id], ids);
+    }
 
-        let results = table.fts_autocomplete_track_names("bee", None);
+    #[test]
+    fn table_autocomplete_tracks_test() {
+        let table = TestDb::new().audio_table();
+
+        AudioRowFixture::new()
+            .name("Beep Boop")
+            .tags("r2d2 star wars droid")
+            .insert(&table);
+        AudioRowFixture::new()
+            .name("Beep Bop")
+            .tags("gonk star wars droid")
+            .insert(&table);
+        AudioRowFixture::new()
+            .name("Beez's Biz")
+            .tags("random sound-effect")
+            .insert(&table);
+
+        let results = table.autocomplete_tracks("bee", None, 5, 3);
         assert_eq!(3, results.len());
 
-        let results = table.fts_autocomplete_track_names("bee", Some(2));
+        let results = table.autocomplete_tracks("bee", None, 2, 3);
         assert_eq!(2, results.len());
 
-        let results = table.fts_autocomplete_track_names("r2d2", None);
-        assert_eq!("Beep Boop", results[0]);
+        let results = table.autocomplete_tracks("r2d2", None, 5, 3);
+        assert_eq!("Beep Boop", results[0].name);
+        assert_eq!("Beep Boop — r2d2", results[0].label());
 
-        let results = table.fts_autocomplete_track_names("droid", None);
+        let results = table.autocomplete_tracks("droid", None, 5, 3);
         assert_eq!(2, results.len());
-        assert_eq!("Beep Boop", results[0]);
-        assert_eq!("Beep Bop", results[1]);
+        assert_eq!("Beep Boop", results[0].name);
+        assert_eq!("Beep Bop", results[1].name);
+        assert_eq!(Some("droid".to_string()), results[1].matched_tag);
+
+        let results = table.autocomplete_tracks("RaN", None, 5, 3);
+        assert_eq!("Beez's Biz", results[0].name);
+        assert_eq!("Beez's Biz — random", results[0].label());
+
+        // name matches aren't labeled with a tag
+        let results = table.autocomplete_tracks("beep boop", None, 5, 3);
+        assert_eq!("Beep Boop", results[0].label());
+
+        // queries too short for full text search match names and tags containing them
+        let results = table.autocomplete_tracks("go", None, 5, 1);
+        assert_eq!(1, results.len());
+        assert_eq!("Beep Bop — gonk", results[0].label());
+
+        // queries shorter than the minimum query length are ranked instead
+        let results = table.autocomplete_tracks("droid", None, 5, 6);
+        assert_eq!(3, results.len());
+        assert_eq!(None, results[0].matched_tag);
+    }
+
+    #[test]
+    fn table_autocomplete_tracks_unicode_test() {
+        let table = TestDb::new().audio_table();
+
+        AudioRowFixture::new()
+            .name("Café Olé 🎉")
+            .tags("fiesta música")
+            .insert(&table);
+        AudioRowFixture::new()
+            .name("\"Don't\" - Stop")
+            .tags("日本語")
+            .insert(&table);
+
+        let results = table.autocomplete_tracks("olé", None, 5, 1);
+        assert_eq!("Café Olé 🎉", results[0].name);
+
+        // diacritics are removed by the tokenizer
+        let results = table.autocomplete_tracks("cafe", None, 5, 1);
+        assert_eq!("Café Olé 🎉", results[0].name);
+
+        let results = table.autocomplete_tracks("músi", None, 5, 1);
+        assert_eq!("Café Olé 🎉 — música", results[0].label());
+
+        // 2 chars, but more than 3 bytes
+        let results = table.autocomplete_tracks("日本", None, 5, 1);
+        assert_eq!("\"Don't\" - Stop — 日本語", results[0].label());
+        assert_eq!(vec!["日本語"], table.tag_names("日本", 5));
+        assert_eq!(vec!["música"], table.tag_names("Músi", 5));
+
+        // full text search syntax is searched literally
+        let results = table.autocomplete_tracks("\"don't\" - stop", None, 5, 1);
+        assert_eq!("\"Don't\" - Stop", results[0].name);
+    }
+
+    #[test]
+    fn unique_col_from_input_test() {
+        assert_eq!(
+            UniqueAudioTableCol::Id(12),
+            UniqueAudioTableCol::from_input("#12")
+        );
+        assert_eq!(
+            UniqueAudioTableCol::Name("bruh".into()),
+            UniqueAudioTableCol::from_input(" bruh ")
+        );
+        assert_eq!(
+            UniqueAudioTableCol::Name("#1 hit".into()),
+            UniqueAudioTableCol::from_input("#1 hit")
+        );
+        assert_eq!("#12", UniqueAudioTableCol::Id(12).to_input());
+        assert_eq!("bruh", UniqueAudioTableCol::Name("bruh".into()).to_input());
+    }
+
+    #[test]
+    fn autocomplete_track_test() {
+        let tags = Tags::from("memes funny");
+        let track = AutocompleteTrack::new(12, "bruh".into(), Some(1500), &tags, "mem");
+        assert_eq!("bruh (1.5s) — memes", track.label());
+        assert_eq!("#12", track.value());
+        assert_eq!(
+            UniqueAudioTableCol::Id(12),
+            UniqueAudioTableCol::from_input(track.value())
+        );
+
+        let track = AutocompleteTrack::new(12, "bruh".into(), None, &tags, "bru");
+        assert_eq!("bruh", track.label());
+    }
+
+    #[test]
+    fn table_ranked_tracks_test() {
+        let db = TestDb::new();
+        let table = db.audio_table();
+        let favorites = db.favorites_table();
+
+        AudioRowFixture::new().name("Oldest").insert(&table);
+        let played = AudioRowFixture::new().name("Played").insert(&table);
+        let favorite = AudioRowFixture::new().name("Favorite").insert(&table);
+        AudioRowFixture::new().name("Newest").insert(&table);
+
+        // newest added track ranks first when nothing has been played
+        let results = table.ranked_tracks(None, 5);
+        assert_eq!(4, results.len());
+        assert_eq!("Newest", results[0].name);
+
+        for _ in 0..10 {
+            table.increment_play_count(played.id).unwrap();
+        }
+
+        let results = table.ranked_tracks(None, 5);
+        assert_eq!("Played", results[0].name);
+
+        // caller's favorites rank above popular tracks
+        favorites.add_favorite(1234, favorite.id).unwrap();
+
+        let results = table.ranked_tracks(Some(1234), 5);
+        assert_eq!("Favorite", results[0].name);
+        assert_eq!("Played", results[1].name);
+
+        // other users don't see the favorite boost
+        let results = table.ranked_tracks(Some(5678), 5);
+        assert_eq!("Played", results[0].name);
+    }
+
+    #[test]
+    fn table_tag_counts_test() {
+        let table = TestDb::new().audio_table();
 
-        let results = table.fts_autocomplete_track_names("RaN", None);
-        assert_eq!("Beez's Biz", results[0]);
+        let mut row = AudioRowFixture::new().tags("Droid r2d2").insert(&table);
+        AudioRowFixture::new().tags("droid").insert(&table);
+
+        assert_eq!(
+            vec![("droid".to_string(), 2), ("r2d2".into(), 1)],
+            table.tag_counts(10).unwrap()
+        );
+        assert_eq!(2, table.count_audio_rows(Some("droid")).unwrap());
+        assert_eq!(2, table.count_audio_rows(None).unwrap());
+
+        // normalized tags follow updates and deletes
+        row.tags = Tags::from("jedi");
+        table.update_audio_row(&row).unwrap();
+        assert_eq!(1, table.count_audio_rows(Some("droid")).unwrap());
+        assert_eq!(1, table.count_audio_rows(Some("jedi")).unwrap());
+
+        table
+            .delete_audio_row(UniqueAudioTableCol::Id(row.id))
+            .unwrap();
+        assert_eq!(0, table.count_audio_rows(Some("jedi")).unwrap());
+    }
+
+    #[test]
+    fn table_tag_names_test() {
+        let table = TestDb::new().audio_table();
+        AudioRowFixture::new().tags("droid r2d2").insert(&table);
+        AudioRowFixture::new().tags("droid").insert(&table);
+        let archived = AudioRowFixture::new().tags("dragon").insert(&table);
+        table.set_archived(archived.id, true).unwrap();
+
+        assert_eq!(vec!["droid", "r2d2"], table.tag_names("", 10));
+        assert_eq!(vec!["droid"], table.tag_names("DR", 10));
+        assert_eq!(vec!["r2d2"], table.tag_names("2d", 10));
+        assert_eq!(vec!["droid"], table.tag_names("", 1));
+    }
+
+    #[test]
+    fn table_random_audio_row_test() {
+        let table = TestDb::new().audio_table();
+        assert!(table.random_audio_row(None).is_none());
+
+        let row = AudioRowFixture::new().insert(&table);
+        assert_eq!(Some(row.id), table.random_audio_row(None).map(|row| row.id));
+
+        let tagged = AudioRowFixture::new().tags("Droid").insert(&table);
+        for _ in 0..5 {
+            assert_eq!(
+                Some(tagged.id),
+                table.random_audio_row(Some("droid")).map(|row| row.id)
+            );
+        }
+        assert!(table.random_audio_row(Some("jedi")).is_none());
+    }
+
+    #[test]
+    fn table_most_played_audio_row_since_test() {
+        let table = TestDb::new().audio_table();
+        let week_ago = chrono::Utc::now() - chrono::Duration::days(7);
+        assert!(table.most_played_audio_row_since(week_ago).is_none());
+
+        let once = AudioRowFixture::new().insert(&table);
+        let twice = AudioRowFixture::new().insert(&table);
+        AudioRowFixture::new().insert(&table);
+        table.increment_play_count(once.id).unwrap();
+        table.increment_play_count(twice.id).unwrap();
+        table.increment_play_count(twice.id).unwrap();
+
+        assert_eq!(
+            Some(twice.id),
+            table
+                .most_played_audio_row_since(week_ago)
+                .map(|row| row.id)
+        );
+        assert!(table
+            .most_played_audio_row_since(chrono::Utc::now() + chrono::Duration::days(1))
+            .is_none());
+    }
+
+    #[test]
+    fn table_least_recently_played_audio_row_test() {
+        let table = TestDb::new().audio_table();
+        assert!(table.least_recently_played_audio_row(None).is_none());
+
+        let played = AudioRowFixture::new().insert(&table);
+        let unplayed = AudioRowFixture::new().insert(&table);
+        table.increment_play_count(played.id).unwrap();
+
+        assert_eq!(
+            Some(unplayed.id),
+            table
+                .least_recently_played_audio_row(None)
+                .map(|row| row.id)
+        );
+        assert_eq!(
+            Some(played.id),
+            table
+                .least_recently_played_audio_row(Some(unplayed.id))
+                .map(|row| row.id)
+        );
+    }
+
+    #[test]
+    fn table_forgotten_audio_rows_test() {
+        let table = TestDb::new().audio_table();
+        let long_ago = chrono::Utc::now() - chrono::Duration::days(365);
+
+        let played = AudioRowFixture::new().created_at(long_ago).insert(&table);
+        let unplayed = AudioRowFixture::new().insert(&table);
+        let old_unplayed = AudioRowFixture::new().created_at(long_ago).insert(&table);
+        let archived = AudioRowFixture::new().created_at(long_ago).insert(&table);
+        table.increment_play_count(played.id).unwrap();
+        table.set_archived(archived.id, true).unwrap();
+
+        let ids = |limit| {
+            table
+                .forgotten_audio_rows(limit)
+                .unwrap()
+                .iter()
+                .map(|row| row.id)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(vec![old_unplayed.id, unplayed.id, played.id], ids(10));
+        assert_eq!(vec![old_unplayed.id], ids(1));
+    }
+
+    #[test]
+    fn table_most_played_audio_rows_test() {
+        let table = TestDb::new().audio_table();
+        let played = AudioRowFixture::new().insert(&table);
+        let most_played = AudioRowFixture::new().insert(&table);
+        let archived = AudioRowFixture::new().insert(&table);
+        AudioRowFixture::new().insert(&table);
+        for id in [played.id, most_played.id, most_played.id, archived.id] {
+            table.increment_play_count(id).unwrap();
+        }
+        table.set_archived(archived.id, true).unwrap();
+
+        let ids = |limit| {
+            table
+                .most_played_audio_rows(limit)
+                .unwrap()
+                .iter()
+                .map(|row| row.id)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(vec![most_played.id, played.id], ids(10));
+        assert_eq!(vec![most_played.id], ids(1));
+    }
+
+    #[test]
+    fn table_bulk_delete_test() {
+        let table = TestDb::new().audio_table();
+
+        AudioRowFixture::new()
+            .tags("droid")
+            .author_id(1)
+            .insert(&table);
+        AudioRowFixture::new()
+            .tags("droid")
+            .author_id(2)
+            .insert(&table);
+        AudioRowFixture::new()
+            .tags("jedi")
+            .author_id(1)
+            .insert(&table);
+
+        assert!(table
+            .delete_filtered_audio_rows(&AudioTableFilter::default())
+            .is_err());
+
+        let filter = AudioTableFilter {
+            tag: Some("droid".into()),
+            author_id: Some(1),
+            ..Default::default()
+        };
+        assert_eq!(1, table.filtered_audio_rows(&filter).unwrap().len());
+        assert_eq!(1, table.delete_filtered_audio_rows(&filter).unwrap());
+        assert_eq!(2, table.count_audio_rows(None).unwrap());
+
+        // nothing created before the epoch
+        let filter = AudioTableFilter {
+            created_before: Some(chrono::DateTime::UNIX_EPOCH),
+            ..Default::default()
+        };
+        assert_eq!(0, table.delete_filtered_audio_rows(&filter).unwrap());
+    }
+
+    #[test]
+    fn table_bulk_retag_test() {
+        let table = TestDb::new().audio_table();
+
+        AudioRowFixture::new().tags("droid r2d2").insert(&table);
+        AudioRowFixture::new().tags("droid").insert(&table);
+        AudioRowFixture::new().tags("jedi").insert(&table);
+
+        let filter = AudioTableFilter {
+            tag: Some("droid".into()),
+            ..Default::default()
+        };
+        let updated = table
+            .retag_filtered_audio_rows(&filter, &Tags::from("robot"))
+            .unwrap();
+
+        assert_eq!(2, updated);
+        assert_eq!(0, table.count_audio_rows(Some("droid")).unwrap());
+        assert_eq!(0, table.count_audio_rows(Some("r2d2")).unwrap());
+        assert_eq!(2, table.count_audio_rows(Some("robot")).unwrap());
+    }
+
+    #[test]
+    fn table_personal_audio_rows_test() {
+        let db = TestDb::new();
+        let table = db.audio_table();
+        let user_plays = db.user_plays_table();
+
+        let upload = AudioRowFixture::new()
+            .name("Upload")
+            .author_id(1234)
+            .insert(&table);
+        let favorite = AudioRowFixture::new().name("Favorite").insert(&table);
+        AudioRowFixture::new()
+            .name("Other")
+            .author_id(5678)
+            .insert(&table);
+
+        db.favorites_table()
+            .add_favorite(1234, favorite.id)
+            .unwrap();
+        user_plays
+            .increment_user_play_count(1234, favorite.id)
+            .unwrap();
+
+        let names: Vec<String> = table
+            .personal_audio_rows(1234, 25)
+            .unwrap()
+            .into_iter()
+            .map(|row| row.name.into())
+            .collect();
+        assert_eq!(vec!["Favorite", "Upload"], names);
+
+        // plays by other users don't affect ordering
+        for _ in 0..3 {
+            user_plays
+                .increment_user_play_count(5678, upload.id)
+                .unwrap();
+        }
+        let rows = table.personal_audio_rows(1234, 25).unwrap();
+        assert_eq!("Favorite", rows[0].name.as_str());
+
+        assert_eq!(1, table.personal_audio_rows(1234, 1).unwrap().len());
+        assert!(table.personal_audio_rows(9999, 25).unwrap().is_empty());
+    }
+
+    #[test]
+    fn table_increment_play_count_test() {
+        let table = TestDb::new().audio_table();
+
+        let row = AudioRowFixture::new().insert(&table);
+        assert_eq!(0, row.play_count);
+        assert!(row.last_played_at.is_none());
+
+        table.increment_play_count(row.id).unwrap();
+        table.increment_play_count(row.id).unwrap();
+
+        let row = table
+            .find_audio_row(UniqueAudioTableCol::Id(row.id))
+            .unwrap();
+        assert_eq!(2, row.play_count);
+        assert!(row.last_played_at.is_some());
+    }
+
+    #[test]
+    fn table_unused_audio_rows_test() {
+        let db = TestDb::new();
+        let table = db.audio_table();
+        let long_ago = chrono::Utc::now() - chrono::Duration::days(365);
+        let unused_since = chrono::Utc::now() - chrono::Duration::days(30);
+
+        let unused = AudioRowFixture::new().created_at(long_ago).insert(&table);
+        let played = AudioRowFixture::new().created_at(long_ago).insert(&table);
+        let other_guild = AudioRowFixture::new()
+            .created_at(long_ago)
+            .guild_id(2)
+            .insert(&table);
+        let archived = AudioRowFixture::new().created_at(long_ago).insert(&table);
+        let kept = AudioRowFixture::new().created_at(long_ago).insert(&table);
+        AudioRowFixture::new().insert(&table);
+
+        db.play_events_table()
+            .insert_play_event(&PlayEventInsert {
+                audio_id: played.id,
+                user_id: 1,
+                guild_id: Some(1),
+                source: "slash".into(),
+            })
+            .unwrap();
+        table.set_archived(archived.id, true).unwrap();
+        table.keep_audio_row(kept.id).unwrap();
+
+        let ids: Vec<_> = table
+            .unused_audio_rows(1, unused_since, 10)
+            .unwrap()
+            .iter()
+            .map(|row| row.id)
+            .collect();
+        assert_eq!(vec![unused.id], ids);
+        assert!(!ids.contains(&other_guild.id));
+
+        let row = table
+            .find_audio_row(UniqueAudioTableCol::Id(archived.id))
+            .unwrap();
+        assert!(row.archived_at.is_some());
+
+        // unarchived rows are kept, so they aren't suggested again right away
+        table.set_archived(archived.id, false).unwrap();
+        assert_eq!(
+            1,
+            table.unused_audio_rows(1, unused_since, 10).unwrap().len()
+        );
+
+        let later = chrono::Utc::now() + chrono::Duration::days(1);
+        assert_eq!(5, table.unused_audio_rows(1, later, 10).unwrap().len());
+        assert_eq!(1, table.unused_audio_rows(1, later, 1).unwrap().len());
+    }
+
+    #[test]
+    fn table_archived_audio_rows_test() {
+        let table = TestDb::new().audio_table();
+
+        let beep = AudioRowFixture::new()
+            .name("Beep Boop")
+            .tags("droid")
+            .insert(&table);
+        let archived = AudioRowFixture::new()
+            .name("Beep Bop")
+            .tags("droid")
+            .insert(&table);
+        table.set_archived(archived.id, true).unwrap();
+
+        // archived rows are hidden from the soundboard and autocomplete
+        assert_eq!(
+            vec!["Beep Boop".to_string()],
+            table
+                .autocomplete_tracks("beep", None, 5, 3)
+                .into_iter()
+                .map(|track| track.name)
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec!["Beep Boop".to_string()],
+            table
+                .ranked_tracks(None, 5)
+                .into_iter()
+                .map(|track| track.name)
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(1, table.count_audio_rows(None).unwrap());
+        assert_eq!(
+            vec![("droid".to_string(), 1)],
+            table.tag_counts(10).unwrap()
+        );
+        for _ in 0..5 {
+            assert_eq!(
+                Some(beep.id),
+                table.random_audio_row(None).map(|row| row.id)
+            );
+        }
+
+        // but can be found and unarchived
+        assert_eq!(
+            vec!["Beep Bop".to_string()],
+            table.archived_track_names("bop", 5)
+        );
+        assert!(table.archived_track_names("boop", 5).is_empty());
+
+        table.set_archived(archived.id, false).unwrap();
+        assert_eq!(2, table.count_audio_rows(None).unwrap());
+        assert!(table.archived_track_names("bop", 5).is_empty());
+    }
+
+    #[test]
+    fn table_search_audio_rows_test() {
+        let table = TestDb::new().audio_table();
+
+        AudioRowFixture::new()
+            .name("Beep Boop")
+            .tags("r2d2 droid")
+            .insert(&table);
+        let archived = AudioRowFixture::new()
+            .name("Beep Bop")
+            .tags("gonk droid")
+            .insert(&table);
+        table.set_archived(archived.id, true).unwrap();
+
+        let names = |query, include_archived| -> Vec<String> {
+            table
+                .search_audio_rows(query, include_archived, 10)
+                .unwrap()
+                .into_iter()
+                .map(|row| row.name.into())
+                .collect()
+        };
+
+        assert_eq!(vec!["Beep Boop"], names("droid", false));
+        assert_eq!(2, names("droid", true).len());
+        assert_eq!(vec!["Beep Bop"], names("gonk", true));
+        assert!(names("gonk", false).is_empty());
+
+        // short queries match names
+        assert_eq!(vec!["Beep Boop", "Beep Bop"], names("Bo", true));
+        assert_eq!(1, table.search_audio_rows("droid", true, 1).unwrap().len());
+    }
+
+    #[test]
+    fn fts_query_test() {
+        assert_eq!(
+            Some("\"beep\" \"boop\"".to_string()),
+            AudioTable::fts_query(" beep  boop ")
+        );
+        assert_eq!(
+            Some("\"\"\"don't\"\"\"".to_string()),
+            AudioTable::fts_query("\"don't\" - a")
+        );
+        assert_eq!(
+            Some("\"日本語\"".to_string()),
+            AudioTable::fts_query("日本語")
+        );
+        assert_eq!(None, AudioTable::fts_query("日本"));
+        assert_eq!(None, AudioTable::fts_query(""));
+    }
+
+    #[test]
+    fn table_search_all_audio_rows_test() {
+        let table = TestDb::new().audio_table();
+        let beep = AudioRowFixture::new()
+            .name("Beep Boop")
+            .author_id(7)
+            .guild_id(1)
+            .insert(&table);
+        let archived = AudioRowFixture::new()
+            .name("Beep Bop")
+            .guild_id(2)
+            .insert(&table);
+        table.set_archived(archived.id, true).unwrap();
+        table.set_content_hash(archived.id, "abcdef0123").unwrap();
+
+        let ids = |query, hash_prefix| -> Vec<i64> {
+            table
+                .search_all_audio_rows(query, hash_prefix, 10)
+                .unwrap()
+                .iter()
+                .map(|row| row.id)
+                .collect()
+        };
+
+        assert_eq!(vec![beep.id, archived.id], ids("beep", None));
+        assert_eq!(vec![beep.id], ids("7", None));
+        assert_eq!(vec![archived.id], ids("2", None));
+        assert_eq!(vec![archived.id], ids("abcdef01", Some("abcdef01")));
+        assert!(ids("abcdef01", None).is_empty());
+
+        assert_eq!(
+            vec![archived.id],
+            table
+                .audio_rows_by_content_hash("abcdef0123")
+                .unwrap()
+                .iter()
+                .map(|row| row.id)
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn sound_name_test() {
+        assert_eq!(
+            "Beep Boop",
+            SoundName::new("  Beep \t  Boop ").unwrap().as_str()
+        );
+        assert_eq!("Señor 🔊", SoundName::new("Señor 🔊").unwrap().as_str());
+
+        assert_eq!(Err(SoundNameError::Empty), SoundName::new(" \n "));
+        assert_eq!(
+            Err(SoundNameError::InvalidChar('`')),
+            SoundName::new("Beep `Boop`")
+        );
+        assert_eq!(
+            Err(SoundNameError::InvalidChar('\u{7}')),
+            SoundName::new("Beep\u{7}")
+        );
+
+        let max_len = "é".repeat(vars::SOUND_NAME_MAX_LEN);
+        assert!(SoundName::new(&max_len).is_ok());
+        assert_eq!(
+            Err(SoundNameError::TooLong {
+                len: vars::SOUND_NAME_MAX_LEN + 1,
+                max: vars::SOUND_NAME_MAX_LEN
+            }),
+            SoundName::new(format!("{max_len}e"))
+        );
+    }
+
+    #[test]
+    fn sound_name_slug_test() {
+        let slug = |name| SoundName::new(name).unwrap().slug();
+        assert_eq!("beep-boop", slug("Beep Boop"));
+        assert_eq!("don-t-stop", slug("\"Don't\" - Stop 🎉"));
+        assert_eq!("señor-café", slug("Señor  Café!"));
+        assert_eq!("日本語", slug("日本語"));
+        assert_eq!("sound", slug("🎉 !?"));
+
+        let max_len = "é".repeat(vars::SOUND_NAME_MAX_LEN);
+        assert_eq!(max_len, slug(&max_len.to_uppercase()));
+    }
+
+    #[test]
+    fn table_slug_test() {
+        let table = TestDb::new().audio_table();
+        let row = AudioRowFixture::new().name("Beep Boop!").insert(&table);
+        assert_eq!("beep-boop", row.slug);
+
+        let mut renamed = table
+            .find_audio_row(UniqueAudioTableCol::Id(row.id))
+            .unwrap();
+        renamed.name = SoundName::new("Beep Bop 🎉").unwrap();
+        table.update_audio_row(&renamed).unwrap();
+
+        let slug: String = table
+            .conn
+            .query_row("SELECT slug FROM audio WHERE id = ?1", [row.id], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!("beep-bop", slug);
     }
 
     #[test]
@@ -554,5 +2811,10 @@ mod tests {
             &vec!["tag-1", "tag_2", "tag3", "tag4", "tag5"],
             tags.inner()
         );
+
+        let tags = Tags::from("日本語 café, música🎵");
+        assert_eq!(&vec!["日本語", "café", "música"], tags.inner());
+        assert_eq!("日本語", Tags::normalize_tag("日本語"));
+        assert_eq!("Éte", Tags::normalize_tag("ÉTE!"));
     }
 }
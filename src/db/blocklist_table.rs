@@ -0,0 +1,154 @@
+use rusqlite::params;
+
+use crate::{commands::PoiseError, common::LogResult};
+
+use super::{DbConnection, Table};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlocklistTableRow {
+    pub pattern: String,
+    pub is_regex: bool,
+}
+
+impl TryFrom<&rusqlite::Row<'_>> for BlocklistTableRow {
+    type Error = rusqlite::Error;
+
+    fn try_from(row: &rusqlite::Row<'_>) -> Result<Self, Self::Error> {
+        Ok(Self {
+            pattern: row.get("pattern")?,
+            is_regex: row.get("is_regex")?,
+        })
+    }
+}
+
+/// Per-guild blocked words and regex patterns for sound names and text-to-speech
+pub struct BlocklistTable {
+    conn: DbConnection,
+}
+
+impl BlocklistTable {
+    pub const TABLE_NAME: &'static str = "blocklist";
+
+    pub fn new(connection: DbConnection) -> Self {
+        Self { conn: connection }
+    }
+
+    pub fn patterns(&self, guild_id: u64) -> Result<Vec<BlocklistTableRow>, PoiseError> {
+        let table_name = Self::TABLE_NAME;
+        let sql = format!(
+            "SELECT pattern, is_regex FROM {table_name} WHERE guild_id = ?1 ORDER BY pattern"
+        );
+
+        let mut stmt = self
+            .conn
+            .prepare(sql.as_str())
+            .log_err_msg("Blocklist patterns sql invalid")?;
+
+        let rows = stmt
+            .query_map(params![&guild_id], |row| BlocklistTableRow::try_from(row))
+            .log_err_msg("Blocklist patterns sql query error")?;
+
+        Ok(rows.filter_map(|row| row.log_err().ok()).collect())
+    }
+
+    pub fn add_pattern(&self, guild_id: u64, row: &BlocklistTableRow) -> Result<(), PoiseError> {
+        log::info!("Adding blocklist pattern. Guild Id: {guild_id}, Pattern: {row:?}");
+
+        let table_name = Self::TABLE_NAME;
+        let sql = format!(
+            "
+            INSERT OR REPLACE INTO {table_name}
+                (guild_id, pattern, is_regex, created_at)
+            VALUES
+                (?1, ?2, ?3, ?4)"
+        );
+
+        self.conn
+            .execute(
+                sql.as_str(),
+                params![&guild_id, &row.pattern, &row.is_regex, &chrono::Utc::now()],
+            )
+            .log_err_msg("Failed to add blocklist pattern")?;
+
+        Ok(())
+    }
+
+    /// Returns true if pattern was in the blocklist
+    pub fn remove_pattern(&self, guild_id: u64, pattern: &str) -> Result<bool, PoiseError> {
+        log::info!("Removing blocklist pattern. Guild Id: {guild_id}, Pattern: {pattern}");
+
+        let table_name = Self::TABLE_NAME;
+        let sql = format!("DELETE FROM {table_name} WHERE guild_id = ?1 AND pattern = ?2");
+
+        let deleted = self
+            .conn
+            .execute(sql.as_str(), params![&guild_id, &pattern])
+            .log_err_msg("Failed to remove blocklist pattern")?;
+
+        Ok(deleted > 0)
+    }
+}
+
+impl Table for BlocklistTable {
+    fn connection(&self) -> &DbConnection {
+        &self.conn
+    }
+
+    fn create_table(&self) {
+        let table_name = Self::TABLE_NAME;
+
+        log::info!("Creating table: {table_name}");
+        let sql = format!(
+            "
+            CREATE TABLE IF NOT EXISTS {table_name} (
+                guild_id INTEGER NOT NULL,
+                pattern VARCHAR(200) NOT NULL,
+                is_regex INTEGER NOT NULL DEFAULT 0,
+                created_at VARCHAR(25) NOT NULL,
+                PRIMARY KEY (guild_id, pattern)
+            );"
+        );
+
+        self.conn
+            .execute_batch(sql.as_str())
+            .log_err_msg("Failed create table")
+            .log_ok_msg(format!("Created table {table_name}"))
+            .unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::TestDb;
+
+    use super::*;
+
+    #[test]
+    fn table_create_test() {
+        let table = TestDb::new().blocklist_table();
+        table.create_table();
+    }
+
+    #[test]
+    fn add_remove_pattern_test() {
+        let table = TestDb::new().blocklist_table();
+        let word = BlocklistTableRow {
+            pattern: "heck".into(),
+            is_regex: false,
+        };
+        let regex = BlocklistTableRow {
+            pattern: "d[a4]rn".into(),
+            is_regex: true,
+        };
+
+        table.add_pattern(1, &word).unwrap();
+        table.add_pattern(1, &regex).unwrap();
+        table.add_pattern(2, &word).unwrap();
+
+        assert_eq!(vec![regex, word.clone()], table.patterns(1).unwrap());
+
+        assert!(table.remove_pattern(1, "d[a4]rn").unwrap());
+        assert!(!table.remove_pattern(1, "d[a4]rn").unwrap());
+        assert_eq!(vec![word], table.patterns(1).unwrap());
+    }
+}
@@ -0,0 +1,260 @@
+use rusqlite::{params, OptionalExtension};
+
+use crate::{commands::PoiseError, common::LogResult};
+
+use super::{AudioTable, DbConnection, Table};
+
+/// Soundboard category. Sounds are assigned at most one, and the soundboard is grouped into
+/// sections by category, ordered by `sort_order`
+#[derive(Debug, Clone, PartialEq)]
+pub struct CategoryRow {
+    pub id: i64,
+    /// Unique, ignoring case
+    pub name: String,
+    /// Emoji shown before the category name (see `helpers::parse_emoji`)
+    pub emoji: Option<String>,
+    /// Lower sorts first. Categories with the same sort order are sorted by name
+    pub sort_order: i64,
+}
+
+impl CategoryRow {
+    /// Category name, after its emoji when set
+    pub fn label(&self) -> String {
+        match self.emoji {
+            Some(ref emoji) => format!("{emoji} {}", self.name),
+            None => self.name.clone(),
+        }
+    }
+}
+
+impl TryFrom<&rusqlite::Row<'_>> for CategoryRow {
+    type Error = rusqlite::Error;
+
+    fn try_from(row: &rusqlite::Row<'_>) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: row.get("id")?,
+            name: row.get("name")?,
+            emoji: row.get("emoji")?,
+            sort_order: row.get("sort_order")?,
+        })
+    }
+}
+
+pub struct CategoriesTable {
+    conn: DbConnection,
+}
+
+impl CategoriesTable {
+    pub const TABLE_NAME: &'static str = "categories";
+
+    pub fn new(connection: DbConnection) -> Self {
+        Self { conn: connection }
+    }
+
+    /// Add category, returning its id
+    pub fn insert_category(
+        &self,
+        name: &str,
+        emoji: Option<&str>,
+        sort_order: i64,
+    ) -> Result<i64, PoiseError> {
+        log::info!("Adding category. Name: {name}, Emoji: {emoji:?}, Sort order: {sort_order}");
+
+        let table_name = Self::TABLE_NAME;
+        let sql = format!(
+            "
+            INSERT INTO {table_name}
+                (name, emoji, sort_order)
+            VALUES
+                (?1, ?2, ?3)"
+        );
+
+        self.conn
+            .execute(sql.as_str(), params![&name, &emoji, &sort_order])
+            .log_err_msg("Failed to insert category")?;
+
+        Ok(self.conn.last_insert_rowid())
+    }
+
+    pub fn update_category(&self, category: &CategoryRow) -> Result<(), PoiseError> {
+        log::info!("Updating category - {category:?}");
+
+        let table_name = Self::TABLE_NAME;
+        let sql =
+            format!("UPDATE {table_name} SET name = ?1, emoji = ?2, sort_order = ?3 WHERE id = ?4");
+
+        self.conn
+            .execute(
+                sql.as_str(),
+                params![
+                    &category.name,
+                    &category.emoji,
+                    &category.sort_order,
+                    &category.id
+                ],
+            )
+            .log_err_msg("Failed to update category")?;
+
+        Ok(())
+    }
+
+    /// Delete category. Its sounds are left without a category
+    pub fn delete_category(&self, category_id: i64) -> Result<(), PoiseError> {
+        log::info!("Deleting category. Id: {category_id}");
+
+        let table_name = Self::TABLE_NAME;
+        let audio_table_name = AudioTable::TABLE_NAME;
+
+        self.conn
+            .execute(
+                &format!("UPDATE {audio_table_name} SET category_id = NULL WHERE category_id = ?1"),
+                params![&category_id],
+            )
+            .log_err_msg("Failed to uncategorize sounds of category")?;
+        self.conn
+            .execute(
+                &format!("DELETE FROM {table_name} WHERE id = ?1"),
+                params![&category_id],
+            )
+            .log_err_msg("Failed to delete category")?;
+
+        Ok(())
+    }
+
+    /// Category by name, ignoring case
+    pub fn find_category(&self, name: &str) -> Result<Option<CategoryRow>, PoiseError> {
+        let table_name = Self::TABLE_NAME;
+        let sql = format!("SELECT * FROM {table_name} WHERE name = ?1 COLLATE NOCASE");
+
+        let row = self
+            .conn
+            .query_row(sql.as_str(), params![&name.trim()], |row| {
+                CategoryRow::try_from(row)
+            })
+            .optional()
+            .log_err_msg("Failed to find category")?;
+
+        Ok(row)
+    }
+
+    pub fn find_category_by_id(&self, category_id: i64) -> Result<Option<CategoryRow>, PoiseError> {
+        let table_name = Self::TABLE_NAME;
+        let sql = format!("SELECT * FROM {table_name} WHERE id = ?1");
+
+        let row = self
+            .conn
+            .query_row(sql.as_str(), params![&category_id], |row| {
+                CategoryRow::try_from(row)
+            })
+            .optional()
+            .log_err_msg("Failed to find category by id")?;
+
+        Ok(row)
+    }
+
+    /// Categories in soundboard order
+    pub fn list_categories(&self) -> Result<Vec<CategoryRow>, PoiseError> {
+        let table_name = Self::TABLE_NAME;
+        let sql = format!("SELECT * FROM {table_name} ORDER BY sort_order, name COLLATE NOCASE");
+
+        let mut stmt = self
+            .conn
+            .prepare(sql.as_str())
+            .log_err_msg("Failed to prepare list categories query")?;
+        let rows = stmt
+            .query_map((), |row| CategoryRow::try_from(row))
+            .log_err_msg("Failed to query categories")?;
+
+        Ok(rows.filter_map(|row| row.log_err().ok()).collect())
+    }
+}
+
+impl Table for CategoriesTable {
+    fn connection(&self) -> &DbConnection {
+        &self.conn
+    }
+
+    fn create_table(&self) {
+        let table_name = Self::TABLE_NAME;
+
+        log::info!("Creating table: {table_name}");
+        let sql = format!(
+            "
+            CREATE TABLE IF NOT EXISTS {table_name} (
+                id INTEGER PRIMARY KEY,
+                name VARCHAR(32) NOT NULL UNIQUE COLLATE NOCASE,
+                emoji VARCHAR(64),
+                sort_order INTEGER NOT NULL DEFAULT 0
+            );"
+        );
+
+        self.conn
+            .execute_batch(sql.as_str())
+            .log_err_msg("Failed create table")
+            .log_ok_msg(format!("Created table {table_name}"))
+            .unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::{AudioRowFixture, TestDb};
+
+    use super::*;
+
+    #[test]
+    fn table_create_test() {
+        let table = TestDb::new().categories_table();
+        table.create_table();
+    }
+
+    #[test]
+    fn category_test() {
+        let table = TestDb::new().categories_table();
+        let memes = table.insert_category("Memes", Some("🐸"), 2).unwrap();
+        let movies = table.insert_category("movies", None, 1).unwrap();
+        table.insert_category("Anime", None, 2).unwrap();
+
+        // names are unique, ignoring case
+        assert!(table.insert_category("MEMES", None, 0).is_err());
+        assert_eq!(memes, table.find_category(" memes ").unwrap().unwrap().id);
+        assert_eq!(None, table.find_category("games").unwrap());
+
+        let names = |table: &CategoriesTable| {
+            table
+                .list_categories()
+                .unwrap()
+                .into_iter()
+                .map(|category| category.label())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(vec!["movies", "Anime", "🐸 Memes"], names(&table));
+
+        let mut category = table.find_category_by_id(movies).unwrap().unwrap();
+        category.name = "Movies".into();
+        category.emoji = Some("🎬".into());
+        category.sort_order = 3;
+        table.update_category(&category).unwrap();
+        assert_eq!(vec!["Anime", "🐸 Memes", "🎬 Movies"], names(&table));
+    }
+
+    #[test]
+    fn delete_category_test() {
+        let db = TestDb::new();
+        let table = db.categories_table();
+        let audio_table = db.audio_table();
+        let id = table.insert_category("Memes", None, 0).unwrap();
+        let row = AudioRowFixture::new().insert(&audio_table);
+        audio_table.set_category(row.id, Some(id)).unwrap();
+
+        table.delete_category(id).unwrap();
+        assert_eq!(None, table.find_category_by_id(id).unwrap());
+        assert_eq!(
+            None,
+            audio_table
+                .find_audio_row(crate::db::UniqueAudioTableCol::Id(row.id))
+                .unwrap()
+                .category_id
+        );
+    }
+}
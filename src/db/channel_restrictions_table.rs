@@ -0,0 +1,161 @@
+use rusqlite::params;
+
+use crate::{commands::PoiseError, common::LogResult};
+
+use super::{DbConnection, Table};
+
+/// Per-guild channels soundboard use is restricted to, by channel kind
+/// (see `crate::channel_restrictions::ChannelKind`)
+pub struct ChannelRestrictionsTable {
+    conn: DbConnection,
+}
+
+impl ChannelRestrictionsTable {
+    pub const TABLE_NAME: &'static str = "channel_restrictions";
+
+    pub fn new(connection: DbConnection) -> Self {
+        Self { conn: connection }
+    }
+
+    /// Channels of kind allowed in guild. Empty if the guild doesn't restrict the kind
+    pub fn channel_ids(&self, guild_id: u64, kind: &str) -> Result<Vec<u64>, PoiseError> {
+        let table_name = Self::TABLE_NAME;
+        let sql = format!(
+            "SELECT channel_id FROM {table_name} WHERE guild_id = ?1 AND kind = ?2 ORDER BY channel_id"
+        );
+
+        let mut stmt = self
+            .conn
+            .prepare(sql.as_str())
+            .log_err_msg("Channel ids sql invalid")?;
+
+        let rows = stmt
+            .query_map(params![&guild_id, &kind], |row| row.get("channel_id"))
+            .log_err_msg("Channel ids sql query error")?;
+
+        Ok(rows.filter_map(|row| row.log_err().ok()).collect())
+    }
+
+    /// Guild and channel ids of channels of kind allowed in any guild
+    pub fn all_channel_ids(&self, kind: &str) -> Result<Vec<(u64, u64)>, PoiseError> {
+        let table_name = Self::TABLE_NAME;
+        let sql = format!(
+            "SELECT guild_id, channel_id FROM {table_name} WHERE kind = ?1 ORDER BY guild_id, channel_id"
+        );
+
+        let mut stmt = self
+            .conn
+            .prepare(sql.as_str())
+            .log_err_msg("All channel ids sql invalid")?;
+
+        let rows = stmt
+            .query_map(params![&kind], |row| {
+                Ok((row.get("guild_id")?, row.get("channel_id")?))
+            })
+            .log_err_msg("All channel ids sql query error")?;
+
+        Ok(rows.filter_map(|row| row.log_err().ok()).collect())
+    }
+
+    pub fn allow(&self, guild_id: u64, kind: &str, channel_id: u64) -> Result<(), PoiseError> {
+        log::info!(
+            "Allowing channel. Guild Id: {guild_id}, Kind: {kind}, Channel Id: {channel_id}"
+        );
+
+        let table_name = Self::TABLE_NAME;
+        let sql = format!(
+            "
+            INSERT OR IGNORE INTO {table_name}
+                (guild_id, kind, channel_id)
+            VALUES
+                (?1, ?2, ?3)"
+        );
+
+        self.conn
+            .execute(sql.as_str(), params![&guild_id, &kind, &channel_id])
+            .log_err_msg("Failed to allow channel")?;
+
+        Ok(())
+    }
+
+    /// Returns true if channel was allowed
+    pub fn disallow(&self, guild_id: u64, kind: &str, channel_id: u64) -> Result<bool, PoiseError> {
+        log::info!(
+            "Disallowing channel. Guild Id: {guild_id}, Kind: {kind}, Channel Id: {channel_id}"
+        );
+
+        let table_name = Self::TABLE_NAME;
+        let sql = format!(
+            "DELETE FROM {table_name} WHERE guild_id = ?1 AND kind = ?2 AND channel_id = ?3"
+        );
+
+        let deleted = self
+            .conn
+            .execute(sql.as_str(), params![&guild_id, &kind, &channel_id])
+            .log_err_msg("Failed to disallow channel")?;
+
+        Ok(deleted > 0)
+    }
+}
+
+impl Table for ChannelRestrictionsTable {
+    fn connection(&self) -> &DbConnection {
+        &self.conn
+    }
+
+    fn create_table(&self) {
+        let table_name = Self::TABLE_NAME;
+
+        log::info!("Creating table: {table_name}");
+        let sql = format!(
+            "
+            CREATE TABLE IF NOT EXISTS {table_name} (
+                guild_id INTEGER NOT NULL,
+                kind VARCHAR(10) NOT NULL,
+                channel_id INTEGER NOT NULL,
+                PRIMARY KEY (guild_id, kind, channel_id)
+            );"
+        );
+
+        self.conn
+            .execute_batch(sql.as_str())
+            .log_err_msg("Failed create table")
+            .log_ok_msg(format!("Created table {table_name}"))
+            .unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::TestDb;
+
+    use super::*;
+
+    #[test]
+    fn table_create_test() {
+        let table = TestDb::new().channel_restrictions_table();
+        table.create_table();
+    }
+
+    #[test]
+    fn allow_disallow_test() {
+        let table = TestDb::new().channel_restrictions_table();
+
+        table.allow(1, "voice", 20).unwrap();
+        table.allow(1, "voice", 10).unwrap();
+        table.allow(1, "voice", 10).unwrap();
+        table.allow(1, "text", 30).unwrap();
+        table.allow(2, "voice", 40).unwrap();
+
+        assert_eq!(vec![10, 20], table.channel_ids(1, "voice").unwrap());
+        assert_eq!(vec![30], table.channel_ids(1, "text").unwrap());
+        assert_eq!(
+            vec![(1, 10), (1, 20), (2, 40)],
+            table.all_channel_ids("voice").unwrap()
+        );
+
+        assert!(table.disallow(1, "voice", 20).unwrap());
+        assert!(!table.disallow(1, "voice", 20).unwrap());
+        assert_eq!(vec![10], table.channel_ids(1, "voice").unwrap());
+    }
+}
@@ -0,0 +1,183 @@
+use std::time::Duration;
+
+use rusqlite::params;
+
+use crate::{commands::PoiseError, common::LogResult};
+
+use super::{DbConnection, Table};
+
+/// Usage of a command across every guild, by its qualified name (e.g. `sounds add`)
+#[derive(Debug, Clone, PartialEq)]
+pub struct CommandStatsRow {
+    pub command: String,
+    pub invocations: i64,
+    pub failures: i64,
+    /// Total time spent running the command
+    pub total_ms: i64,
+    pub last_used_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl CommandStatsRow {
+    pub fn average_ms(&self) -> i64 {
+        match self.invocations {
+            0 => 0,
+            invocations => self.total_ms / invocations,
+        }
+    }
+
+    /// Percentage of invocations that failed
+    pub fn error_rate(&self) -> f64 {
+        match self.invocations {
+            0 => 0.0,
+            invocations => self.failures as f64 * 100.0 / invocations as f64,
+        }
+    }
+}
+
+impl TryFrom<&rusqlite::Row<'_>> for CommandStatsRow {
+    type Error = rusqlite::Error;
+
+    fn try_from(row: &rusqlite::Row<'_>) -> Result<Self, Self::Error> {
+        Ok(Self {
+            command: row.get("command")?,
+            invocations: row.get("invocations")?,
+            failures: row.get("failures")?,
+            total_ms: row.get("total_ms")?,
+            last_used_at: row.get("last_used_at")?,
+        })
+    }
+}
+
+pub struct CommandStatsTable {
+    conn: DbConnection,
+}
+
+impl CommandStatsTable {
+    pub const TABLE_NAME: &'static str = "command_stats";
+
+    pub fn new(connection: DbConnection) -> Self {
+        Self { conn: connection }
+    }
+
+    pub fn record_command(
+        &self,
+        command: &str,
+        elapsed: Duration,
+        failed: bool,
+    ) -> Result<(), PoiseError> {
+        let table_name = Self::TABLE_NAME;
+        let sql = format!(
+            "
+            INSERT INTO {table_name}
+                (command, invocations, failures, total_ms, last_used_at)
+            VALUES
+                (?1, 1, ?2, ?3, ?4)
+            ON CONFLICT(command) DO UPDATE SET
+                invocations = invocations + 1,
+                failures = failures + excluded.failures,
+                total_ms = total_ms + excluded.total_ms,
+                last_used_at = excluded.last_used_at"
+        );
+
+        self.conn
+            .execute(
+                sql.as_str(),
+                params![
+                    &command,
+                    &i64::from(failed),
+                    &(elapsed.as_millis() as i64),
+                    &chrono::Utc::now()
+                ],
+            )
+            .log_err_msg("Failed recording command stats")?;
+
+        Ok(())
+    }
+
+    /// Usage of every command used so far, most used first
+    pub fn command_stats(&self) -> Result<Vec<CommandStatsRow>, PoiseError> {
+        let table_name = Self::TABLE_NAME;
+        let sql = format!("SELECT * FROM {table_name} ORDER BY invocations DESC, command ASC");
+
+        let mut stmt = self
+            .conn
+            .prepare(sql.as_str())
+            .log_err_msg("Command stats sql invalid")?;
+
+        let rows = stmt
+            .query_map((), |row| CommandStatsRow::try_from(row))
+            .log_err_msg("Command stats sql query error")?;
+
+        Ok(rows.filter_map(|row| row.log_err().ok()).collect())
+    }
+}
+
+impl Table for CommandStatsTable {
+    fn connection(&self) -> &DbConnection {
+        &self.conn
+    }
+
+    fn create_table(&self) {
+        let table_name = Self::TABLE_NAME;
+
+        log::info!("Creating table: {table_name}");
+        let sql = format!(
+            "
+            CREATE TABLE IF NOT EXISTS {table_name} (
+                command VARCHAR(128) PRIMARY KEY,
+                invocations INTEGER NOT NULL DEFAULT 0,
+                failures INTEGER NOT NULL DEFAULT 0,
+                total_ms INTEGER NOT NULL DEFAULT 0,
+                last_used_at VARCHAR(25) NOT NULL
+            );"
+        );
+
+        self.conn
+            .execute_batch(sql.as_str())
+            .log_err_msg("Failed create table")
+            .log_ok_msg(format!("Created table {table_name}"))
+            .unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::TestDb;
+
+    use super::*;
+
+    #[test]
+    fn table_create_test() {
+        let table = TestDb::new().command_stats_table();
+        table.create_table();
+    }
+
+    #[test]
+    fn record_command_test() {
+        let table = TestDb::new().command_stats_table();
+        assert!(table.command_stats().unwrap().is_empty());
+
+        table
+            .record_command("play", Duration::from_millis(30), false)
+            .unwrap();
+        table
+            .record_command("play", Duration::from_millis(10), true)
+            .unwrap();
+        table
+            .record_command("sounds add", Duration::from_millis(500), false)
+            .unwrap();
+
+        let stats = table.command_stats().unwrap();
+        assert_eq!(2, stats.len());
+
+        let play = &stats[0];
+        assert_eq!("play", play.command);
+        assert_eq!(2, play.invocations);
+        assert_eq!(1, play.failures);
+        assert_eq!(20, play.average_ms());
+        assert_eq!(50.0, play.error_rate());
+
+        assert_eq!("sounds add", stats[1].command);
+        assert_eq!(0.0, stats[1].error_rate());
+    }
+}
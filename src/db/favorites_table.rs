@@ -0,0 +1,150 @@
+use rusqlite::{params, OptionalExtension};
+
+use crate::{commands::PoiseError, common::LogResult};
+
+use super::{AudioTable, DbConnection, Table};
+
+pub struct FavoritesTable {
+    conn: DbConnection,
+}
+
+impl FavoritesTable {
+    pub const TABLE_NAME: &'static str = "favorites";
+
+    pub fn new(connection: DbConnection) -> Self {
+        Self { conn: connection }
+    }
+
+    pub fn is_favorite(&self, user_id: u64, audio_id: i64) -> Result<bool, PoiseError> {
+        let table_name = Self::TABLE_NAME;
+        let sql = format!("SELECT audio_id FROM {table_name} WHERE user_id = ?1 AND audio_id = ?2");
+
+        let row: Option<i64> = self
+            .conn
+            .query_row(sql.as_str(), params![&user_id, &audio_id], |row| {
+                row.get("audio_id")
+            })
+            .optional()
+            .log_err_msg("Failed to query favorite")?;
+
+        Ok(row.is_some())
+    }
+
+    pub fn add_favorite(&self, user_id: u64, audio_id: i64) -> Result<(), PoiseError> {
+        log::info!("Adding favorite. User Id: {user_id}, Audio Id: {audio_id}");
+
+        let table_name = Self::TABLE_NAME;
+        let sql = format!(
+            "
+            INSERT OR IGNORE INTO {table_name}
+                (user_id, audio_id, created_at)
+            VALUES
+                (?1, ?2, ?3)"
+        );
+
+        self.conn
+            .execute(
+                sql.as_str(),
+                params![&user_id, &audio_id, &chrono::Utc::now()],
+            )
+            .log_err_msg("Failed to add favorite")?;
+
+        Ok(())
+    }
+
+    pub fn remove_favorite(&self, user_id: u64, audio_id: i64) -> Result<(), PoiseError> {
+        log::info!("Removing favorite. User Id: {user_id}, Audio Id: {audio_id}");
+
+        let table_name = Self::TABLE_NAME;
+        let sql = format!("DELETE FROM {table_name} WHERE user_id = ?1 AND audio_id = ?2");
+
+        self.conn
+            .execute(sql.as_str(), params![&user_id, &audio_id])
+            .log_err_msg("Failed to remove favorite")?;
+
+        Ok(())
+    }
+
+    /// Adds or removes favorite. Returns true if audio track is now a favorite
+    pub fn toggle_favorite(&self, user_id: u64, audio_id: i64) -> Result<bool, PoiseError> {
+        if self.is_favorite(user_id, audio_id)? {
+            self.remove_favorite(user_id, audio_id)?;
+            Ok(false)
+        } else {
+            self.add_favorite(user_id, audio_id)?;
+            Ok(true)
+        }
+    }
+}
+
+impl Table for FavoritesTable {
+    fn connection(&self) -> &DbConnection {
+        &self.conn
+    }
+
+    fn create_table(&self) {
+        let table_name = Self::TABLE_NAME;
+        let audio_table_name = AudioTable::TABLE_NAME;
+
+        log::info!("Creating table: {table_name}");
+        let sql = format!(
+            "
+            BEGIN;
+                CREATE TABLE IF NOT EXISTS {table_name} (
+                    user_id INTEGER NOT NULL,
+                    audio_id INTEGER NOT NULL,
+                    created_at VARCHAR(25) NOT NULL,
+                    PRIMARY KEY (user_id, audio_id)
+                );
+
+                CREATE TRIGGER IF NOT EXISTS {audio_table_name}_delete_{table_name} AFTER DELETE ON {audio_table_name} BEGIN
+                    DELETE FROM {table_name} WHERE audio_id = old.id;
+                END;
+            COMMIT;"
+        );
+
+        self.conn
+            .execute_batch(sql.as_str())
+            .log_err_msg("Failed create table")
+            .log_ok_msg(format!("Created table {table_name}"))
+            .unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::TestDb;
+
+    use super::*;
+
+    fn get_favorites_table() -> FavoritesTable {
+        TestDb::new().favorites_table()
+    }
+
+    #[test]
+    fn table_create_test() {
+        let table = get_favorites_table();
+        table.create_table();
+    }
+
+    #[test]
+    fn toggle_favorite_test() {
+        let table = get_favorites_table();
+
+        assert!(!table.is_favorite(1, 10).unwrap());
+        assert!(table.toggle_favorite(1, 10).unwrap());
+        assert!(table.is_favorite(1, 10).unwrap());
+        assert!(!table.is_favorite(2, 10).unwrap());
+
+        assert!(!table.toggle_favorite(1, 10).unwrap());
+        assert!(!table.is_favorite(1, 10).unwrap());
+    }
+
+    #[test]
+    fn add_favorite_twice_test() {
+        let table = get_favorites_table();
+        table.add_favorite(1, 10).unwrap();
+        table.add_favorite(1, 10).unwrap();
+        assert!(table.is_favorite(1, 10).unwrap());
+    }
+}
@@ -0,0 +1,384 @@
+use rusqlite::{params, OptionalExtension};
+
+use crate::{commands::PoiseError, common::LogResult};
+
+use super::{DbConnection, Table};
+
+/// Per-guild settings. Guilds without a row use defaults
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GuildSettingsRow {
+    pub guild_id: u64,
+    /// Language code (see `i18n::Language::code`). None uses the guild's Discord locale
+    pub language: Option<String>,
+    /// Prefix command prefix. None uses `Config::command_prefix`
+    pub prefix: Option<String>,
+    /// Text channel sound of the day is posted in. None disables sound of the day
+    pub sotd_channel_id: Option<u64>,
+    /// Voice channel sound of the day is played in. None only posts it
+    pub sotd_voice_channel_id: Option<u64>,
+    /// UTC time of day sound of the day is posted. None posts it at midnight
+    pub sotd_time: Option<chrono::NaiveTime>,
+    /// How sound of the day is picked (see `sotd::SotdMode::code`)
+    pub sotd_mode: Option<String>,
+    /// UTC date sound of the day was last posted
+    pub sotd_last_date: Option<chrono::NaiveDate>,
+    /// Last sound of the day, not picked again the next day
+    pub sotd_last_audio_id: Option<i64>,
+    /// Record plays of Discord native soundboard sounds mapped to bot sounds
+    pub record_native_sounds: bool,
+    /// Archive sounds unused for this many months. None disables auto-archiving
+    pub auto_archive_months: Option<u32>,
+    /// Channel sounds added by members without the approve sounds permission are posted in for
+    /// review (see `approval`). None adds sounds right away
+    pub review_channel_id: Option<u64>,
+    /// Text channel the weekly digest is posted in. None disables the digest
+    pub digest_channel_id: Option<u64>,
+    /// UTC date the weekly digest was last posted
+    pub digest_last_date: Option<chrono::NaiveDate>,
+    /// Missing permissions the guild owner was last warned about (see
+    /// `self_check::fingerprint`). None when nothing was missing
+    pub self_check_warning: Option<String>,
+    /// What sounds played with priority do to playing sounds (see
+    /// `playback::PriorityMode::code`). None stops them
+    pub priority_mode: Option<String>,
+    /// Sounds suggested by track option autocomplete. None uses `vars::AUTOCOMPLETE_LIMIT`
+    pub autocomplete_limit: Option<u32>,
+    /// Shorter track option input suggests top ranked sounds instead of searching. None uses
+    /// `vars::AUTOCOMPLETE_MIN_QUERY_LEN`
+    pub autocomplete_min_query_len: Option<u32>,
+    /// Play sounds without posting now playing messages and `/play` replies, unless `/play`'s
+    /// `silent` option says otherwise
+    pub silent_play: bool,
+    /// Order of soundboard sounds (see `soundboard::SoundboardOrder::code`). None orders them
+    /// by when they were added
+    pub soundboard_order: Option<String>,
+    /// Members can see each other's play history with `/history`. Otherwise members only see
+    /// their own
+    pub play_history_public: bool,
+    /// What happens to added sounds louder than `Config::loudness_ceiling_db` (see
+    /// `loudness::LoudnessAction::code`). None adds them anyway
+    pub loudness_action: Option<String>,
+    /// Max length of added sounds in milliseconds, capped by `Config::max_audio_file_duration`
+    /// (see `UserData::max_audio_duration`). None uses the cap
+    pub max_duration_ms: Option<u64>,
+}
+
+impl TryFrom<&rusqlite::Row<'_>> for GuildSettingsRow {
+    type Error = rusqlite::Error;
+
+    fn try_from(row: &rusqlite::Row<'_>) -> Result<Self, Self::Error> {
+        Ok(Self {
+            guild_id: row.get("guild_id")?,
+            language: row.get("language")?,
+            prefix: row.get("prefix")?,
+            sotd_channel_id: row.get("sotd_channel_id")?,
+            sotd_voice_channel_id: row.get("sotd_voice_channel_id")?,
+            sotd_time: row.get("sotd_time")?,
+            sotd_mode: row.get("sotd_mode")?,
+            sotd_last_date: row.get("sotd_last_date")?,
+            sotd_last_audio_id: row.get("sotd_last_audio_id")?,
+            record_native_sounds: row.get("record_native_sounds")?,
+            auto_archive_months: row.get("auto_archive_months")?,
+            review_channel_id: row.get("review_channel_id")?,
+            digest_channel_id: row.get("digest_channel_id")?,
+            digest_last_date: row.get("digest_last_date")?,
+            self_check_warning: row.get("self_check_warning")?,
+            priority_mode: row.get("priority_mode")?,
+            autocomplete_limit: row.get("autocomplete_limit")?,
+            autocomplete_min_query_len: row.get("autocomplete_min_query_len")?,
+            silent_play: row.get("silent_play")?,
+            soundboard_order: row.get("soundboard_order")?,
+            play_history_public: row.get("play_history_public")?,
+            loudness_action: row.get("loudness_action")?,
+            max_duration_ms: row.get("max_duration_ms")?,
+        })
+    }
+}
+
+pub struct GuildSettingsTable {
+    conn: DbConnection,
+}
+
+impl GuildSettingsTable {
+    pub const TABLE_NAME: &'static str = "guild_settings";
+
+    pub fn new(connection: DbConnection) -> Self {
+        Self { conn: connection }
+    }
+
+    pub fn get_guild_settings(&self, guild_id: u64) -> Result<GuildSettingsRow, PoiseError> {
+        let table_name = Self::TABLE_NAME;
+        let sql = format!("SELECT * FROM {table_name} WHERE guild_id = ?1");
+
+        let row = self
+            .conn
+            .query_row(sql.as_str(), params![&guild_id], |row| {
+                GuildSettingsRow::try_from(row)
+            })
+            .optional()
+            .log_err_msg("Failed to get guild settings")?;
+
+        Ok(row.unwrap_or(GuildSettingsRow {
+            guild_id,
+            ..Default::default()
+        }))
+    }
+
+    /// Settings of guilds with sound of the day enabled
+    pub fn sotd_guild_settings(&self) -> Result<Vec<GuildSettingsRow>, PoiseError> {
+        let table_name = Self::TABLE_NAME;
+        let sql = format!("SELECT * FROM {table_name} WHERE sotd_channel_id IS NOT NULL");
+
+        let mut stmt = self
+            .conn
+            .prepare(sql.as_str())
+            .log_err_msg("Failed to prepare sotd guild settings query")?;
+        let rows = stmt
+            .query_map((), |row| GuildSettingsRow::try_from(row))
+            .log_err_msg("Failed to query sotd guild settings")?;
+
+        Ok(rows.filter_map(|row| row.log_err().ok()).collect())
+    }
+
+    /// Settings of guilds with the weekly digest enabled
+    pub fn digest_guild_settings(&self) -> Result<Vec<GuildSettingsRow>, PoiseError> {
+        let table_name = Self::TABLE_NAME;
+        let sql = format!("SELECT * FROM {table_name} WHERE digest_channel_id IS NOT NULL");
+
+        let mut stmt = self
+            .conn
+            .prepare(sql.as_str())
+            .log_err_msg("Failed to prepare digest guild settings query")?;
+        let rows = stmt
+            .query_map((), |row| GuildSettingsRow::try_from(row))
+            .log_err_msg("Failed to query digest guild settings")?;
+
+        Ok(rows.filter_map(|row| row.log_err().ok()).collect())
+    }
+
+    /// Settings of guilds with auto-archiving enabled
+    pub fn auto_archive_guild_settings(&self) -> Result<Vec<GuildSettingsRow>, PoiseError> {
+        let table_name = Self::TABLE_NAME;
+        let sql = format!("SELECT * FROM {table_name} WHERE auto_archive_months IS NOT NULL");
+
+        let mut stmt = self
+            .conn
+            .prepare(sql.as_str())
+            .log_err_msg("Failed to prepare auto archive guild settings query")?;
+        let rows = stmt
+            .query_map((), |row| GuildSettingsRow::try_from(row))
+            .log_err_msg("Failed to query auto archive guild settings")?;
+
+        Ok(rows.filter_map(|row| row.log_err().ok()).collect())
+    }
+
+    pub fn update_guild_settings(&self, settings: &GuildSettingsRow) -> Result<(), PoiseError> {
+        log::info!("Saving guild settings - {settings:?}");
+
+        let table_name = Self::TABLE_NAME;
+        let sql = format!(
+            "
+            INSERT INTO {table_name}
+                (guild_id, language, prefix, sotd_channel_id, sotd_voice_channel_id,
+                sotd_time, sotd_mode, sotd_last_date, sotd_last_audio_id, record_native_sounds,
+                auto_archive_months, review_channel_id, digest_channel_id, digest_last_date,
+                self_check_warning, priority_mode, autocomplete_limit,
+                autocomplete_min_query_len, silent_play, soundboard_order, play_history_public,
+                loudness_action, max_duration_ms)
+            VALUES
+                (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18,
+                ?19, ?20, ?21, ?22, ?23)
+            ON CONFLICT(guild_id) DO UPDATE SET
+                language = excluded.language,
+                prefix = excluded.prefix,
+                sotd_channel_id = excluded.sotd_channel_id,
+                sotd_voice_channel_id = excluded.sotd_voice_channel_id,
+                sotd_time = excluded.sotd_time,
+                sotd_mode = excluded.sotd_mode,
+                sotd_last_date = excluded.sotd_last_date,
+                sotd_last_audio_id = excluded.sotd_last_audio_id,
+                record_native_sounds = excluded.record_native_sounds,
+                auto_archive_months = excluded.auto_archive_months,
+                review_channel_id = excluded.review_channel_id,
+                digest_channel_id = excluded.digest_channel_id,
+                digest_last_date = excluded.digest_last_date,
+                self_check_warning = excluded.self_check_warning,
+                priority_mode = excluded.priority_mode,
+                autocomplete_limit = excluded.autocomplete_limit,
+                autocomplete_min_query_len = excluded.autocomplete_min_query_len,
+                silent_play = excluded.silent_play,
+                soundboard_order = excluded.soundboard_order,
+                play_history_public = excluded.play_history_public,
+                loudness_action = excluded.loudness_action,
+                max_duration_ms = excluded.max_duration_ms"
+        );
+
+        self.conn
+            .execute(
+                sql.as_str(),
+                params![
+                    &settings.guild_id,
+                    &settings.language,
+                    &settings.prefix,
+                    &settings.sotd_channel_id,
+                    &settings.sotd_voice_channel_id,
+                    &settings.sotd_time,
+                    &settings.sotd_mode,
+                    &settings.sotd_last_date,
+                    &settings.sotd_last_audio_id,
+                    &settings.record_native_sounds,
+                    &settings.auto_archive_months,
+                    &settings.review_channel_id,
+                    &settings.digest_channel_id,
+                    &settings.digest_last_date,
+                    &settings.self_check_warning,
+                    &settings.priority_mode,
+                    &settings.autocomplete_limit,
+                    &settings.autocomplete_min_query_len,
+                    &settings.silent_play,
+                    &settings.soundboard_order,
+                    &settings.play_history_public,
+                    &settings.loudness_action,
+                    &settings.max_duration_ms
+                ],
+            )
+            .log_err_msg("Failed to save guild settings")?;
+
+        Ok(())
+    }
+}
+
+impl Table for GuildSettingsTable {
+    fn connection(&self) -> &DbConnection {
+        &self.conn
+    }
+
+    fn create_table(&self) {
+        let table_name = Self::TABLE_NAME;
+
+        log::info!("Creating table: {table_name}");
+        let sql = format!(
+            "
+            CREATE TABLE IF NOT EXISTS {table_name} (
+                guild_id INTEGER PRIMARY KEY,
+                language VARCHAR(8),
+                prefix VARCHAR(8),
+                sotd_channel_id INTEGER,
+                sotd_voice_channel_id INTEGER,
+                sotd_time VARCHAR(16),
+                sotd_mode VARCHAR(16),
+                sotd_last_date VARCHAR(10),
+                sotd_last_audio_id INTEGER,
+                record_native_sounds INTEGER NOT NULL DEFAULT 0,
+                auto_archive_months INTEGER,
+                review_channel_id INTEGER,
+                digest_channel_id INTEGER,
+                digest_last_date VARCHAR(10),
+                self_check_warning VARCHAR(1024),
+                priority_mode VARCHAR(16),
+                autocomplete_limit INTEGER,
+                autocomplete_min_query_len INTEGER,
+                silent_play INTEGER NOT NULL DEFAULT 0,
+                soundboard_order VARCHAR(16),
+                play_history_public INTEGER NOT NULL DEFAULT 0,
+                loudness_action VARCHAR(16),
+                max_duration_ms INTEGER
+            );"
+        );
+
+        self.conn
+            .execute_batch(sql.as_str())
+            .log_err_msg("Failed create table")
+            .log_ok_msg(format!("Created table {table_name}"))
+            .unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::TestDb;
+
+    use super::*;
+
+    #[test]
+    fn table_create_test() {
+        let table = TestDb::new().guild_settings_table();
+        table.create_table();
+    }
+
+    #[test]
+    fn get_update_guild_settings_test() {
+        let table = TestDb::new().guild_settings_table();
+
+        let mut settings = table.get_guild_settings(1).unwrap();
+        assert_eq!(1, settings.guild_id);
+        assert_eq!(None, settings.language);
+
+        settings.language = Some("de".into());
+        settings.prefix = Some("?".into());
+        settings.record_native_sounds = true;
+        settings.auto_archive_months = Some(6);
+        settings.review_channel_id = Some(3);
+        settings.digest_channel_id = Some(4);
+        settings.digest_last_date = chrono::NaiveDate::from_ymd_opt(2024, 1, 1);
+        settings.self_check_warning = Some("intent".into());
+        settings.priority_mode = Some("pause".into());
+        settings.autocomplete_limit = Some(25);
+        settings.autocomplete_min_query_len = Some(1);
+        settings.silent_play = true;
+        settings.soundboard_order = Some("trending".into());
+        settings.play_history_public = true;
+        settings.loudness_action = Some("attenuate".into());
+        settings.max_duration_ms = Some(5000);
+        table.update_guild_settings(&settings).unwrap();
+        assert_eq!(settings, table.get_guild_settings(1).unwrap());
+        assert_eq!(None, table.get_guild_settings(2).unwrap().language);
+
+        settings.language = None;
+        table.update_guild_settings(&settings).unwrap();
+        assert_eq!(None, table.get_guild_settings(1).unwrap().language);
+    }
+
+    #[test]
+    fn sotd_guild_settings_test() {
+        let table = TestDb::new().guild_settings_table();
+        table
+            .update_guild_settings(&GuildSettingsRow {
+                guild_id: 1,
+                ..Default::default()
+            })
+            .unwrap();
+        assert!(table.sotd_guild_settings().unwrap().is_empty());
+
+        let settings = GuildSettingsRow {
+            guild_id: 2,
+            sotd_channel_id: Some(3),
+            sotd_time: chrono::NaiveTime::from_hms_opt(18, 30, 0),
+            sotd_last_date: chrono::NaiveDate::from_ymd_opt(2024, 1, 1),
+            ..Default::default()
+        };
+        table.update_guild_settings(&settings).unwrap();
+        assert_eq!(vec![settings], table.sotd_guild_settings().unwrap());
+    }
+
+    #[test]
+    fn digest_guild_settings_test() {
+        let table = TestDb::new().guild_settings_table();
+        table
+            .update_guild_settings(&GuildSettingsRow {
+                guild_id: 1,
+                sotd_channel_id: Some(3),
+                ..Default::default()
+            })
+            .unwrap();
+        assert!(table.digest_guild_settings().unwrap().is_empty());
+
+        let settings = GuildSettingsRow {
+            guild_id: 2,
+            digest_channel_id: Some(3),
+            ..Default::default()
+        };
+        table.update_guild_settings(&settings).unwrap();
+        assert_eq!(vec![settings], table.digest_guild_settings().unwrap());
+    }
+}
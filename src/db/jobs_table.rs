@@ -0,0 +1,311 @@
+use rusqlite::types::{FromSql, FromSqlError, ToSql, ToSqlOutput, ValueRef};
+use rusqlite::{params, OptionalExtension};
+
+use crate::{commands::PoiseError, common::LogResult};
+
+use super::{DbConnection, Table};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JobStatus {
+    Queued,
+    Running,
+    Done,
+    Failed,
+}
+
+impl JobStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Queued => "queued",
+            Self::Running => "running",
+            Self::Done => "done",
+            Self::Failed => "failed",
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        matches!(self, Self::Done | Self::Failed)
+    }
+}
+
+impl ToSql for JobStatus {
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+        Ok(ToSqlOutput::from(self.as_str()))
+    }
+}
+
+impl FromSql for JobStatus {
+    fn column_result(value: ValueRef<'_>) -> rusqlite::types::FromSqlResult<Self> {
+        match value.as_str()? {
+            "queued" => Ok(Self::Queued),
+            "running" => Ok(Self::Running),
+            "done" => Ok(Self::Done),
+            "failed" => Ok(Self::Failed),
+            _ => Err(FromSqlError::InvalidType),
+        }
+    }
+}
+
+/// Background job (see `jobs`). `job` is the json encoded `jobs::Job` to run
+#[derive(Debug, Clone, PartialEq)]
+pub struct JobRow {
+    pub id: i64,
+    pub job: String,
+    pub guild_id: Option<u64>,
+    /// Text channel the job's outcome is posted to
+    pub channel_id: Option<u64>,
+    pub user_id: u64,
+    pub status: JobStatus,
+    /// Latest progress of a running job, e.g. `45%`
+    pub progress: Option<String>,
+    /// Outcome of a finished job
+    pub result: Option<String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl TryFrom<&rusqlite::Row<'_>> for JobRow {
+    type Error = rusqlite::Error;
+
+    fn try_from(row: &rusqlite::Row<'_>) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: row.get("id")?,
+            job: row.get("job")?,
+            guild_id: row.get("guild_id")?,
+            channel_id: row.get("channel_id")?,
+            user_id: row.get("user_id")?,
+            status: row.get("status")?,
+            progress: row.get("progress")?,
+            result: row.get("result")?,
+            created_at: row.get("created_at")?,
+            updated_at: row.get("updated_at")?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct JobInsert {
+    pub job: String,
+    pub guild_id: Option<u64>,
+    pub channel_id: Option<u64>,
+    pub user_id: u64,
+}
+
+pub struct JobsTable {
+    conn: DbConnection,
+}
+
+impl JobsTable {
+    pub const TABLE_NAME: &'static str = "jobs";
+
+    pub fn new(connection: DbConnection) -> Self {
+        Self { conn: connection }
+    }
+
+    /// Queue job. Returns its job id
+    pub fn insert_job(&self, job: &JobInsert) -> Result<i64, PoiseError> {
+        log::info!("Inserting job - {}", job.job);
+
+        let table_name = Self::TABLE_NAME;
+        let sql = format!(
+            "
+            INSERT INTO {table_name}
+                (job, guild_id, channel_id, user_id, status, created_at, updated_at)
+            VALUES
+                (?1, ?2, ?3, ?4, ?5, ?6, ?6)"
+        );
+
+        self.conn
+            .execute(
+                sql.as_str(),
+                params![
+                    &job.job,
+                    &job.guild_id,
+                    &job.channel_id,
+                    &job.user_id,
+                    JobStatus::Queued,
+                    &chrono::Utc::now()
+                ],
+            )
+            .log_err_msg("Failed to insert job")?;
+
+        Ok(self.conn.last_insert_rowid())
+    }
+
+    pub fn find_job(&self, id: i64) -> Result<Option<JobRow>, PoiseError> {
+        let table_name = Self::TABLE_NAME;
+        let sql = format!("SELECT * FROM {table_name} WHERE id = ?1");
+
+        let row = self
+            .conn
+            .query_row(sql.as_str(), params![&id], |row| JobRow::try_from(row))
+            .optional()
+            .log_err_msg("Failed to find job")?;
+
+        Ok(row)
+    }
+
+    /// Latest jobs of guild, newest first
+    pub fn recent_jobs(&self, guild_id: u64, limit: usize) -> Result<Vec<JobRow>, PoiseError> {
+        let table_name = Self::TABLE_NAME;
+        let sql =
+            format!("SELECT * FROM {table_name} WHERE guild_id = ?1 ORDER BY id DESC LIMIT ?2");
+
+        let mut stmt = self.conn.prepare(sql.as_str())?;
+        let rows = stmt
+            .query_map(params![&guild_id, &limit], |row| JobRow::try_from(row))
+            .log_err_msg("Failed to query recent jobs")?;
+
+        Ok(rows.filter_map(|row| row.log_err().ok()).collect())
+    }
+
+    /// Queued and running jobs, oldest first. Running jobs were interrupted when read on startup
+    pub fn unfinished_jobs(&self) -> Result<Vec<JobRow>, PoiseError> {
+        let table_name = Self::TABLE_NAME;
+        let sql = format!("SELECT * FROM {table_name} WHERE status IN (?1, ?2) ORDER BY id");
+
+        let mut stmt = self.conn.prepare(sql.as_str())?;
+        let rows = stmt
+            .query_map(params![JobStatus::Queued, JobStatus::Running], |row| {
+                JobRow::try_from(row)
+            })
+            .log_err_msg("Failed to query unfinished jobs")?;
+
+        Ok(rows.filter_map(|row| row.log_err().ok()).collect())
+    }
+
+    pub fn set_status(&self, id: i64, status: JobStatus) -> Result<(), PoiseError> {
+        let table_name = Self::TABLE_NAME;
+        let sql = format!("UPDATE {table_name} SET status = ?2, updated_at = ?3 WHERE id = ?1");
+
+        self.conn
+            .execute(sql.as_str(), params![&id, status, &chrono::Utc::now()])
+            .log_err_msg("Failed to set job status")?;
+
+        Ok(())
+    }
+
+    pub fn set_progress(&self, id: i64, progress: &str) -> Result<(), PoiseError> {
+        let table_name = Self::TABLE_NAME;
+        let sql = format!("UPDATE {table_name} SET progress = ?2, updated_at = ?3 WHERE id = ?1");
+
+        self.conn
+            .execute(sql.as_str(), params![&id, &progress, &chrono::Utc::now()])
+            .log_err_msg("Failed to set job progress")?;
+
+        Ok(())
+    }
+
+    /// Mark job done or failed, with its outcome
+    pub fn finish_job(&self, id: i64, status: JobStatus, result: &str) -> Result<(), PoiseError> {
+        let table_name = Self::TABLE_NAME;
+        let sql = format!(
+            "UPDATE {table_name} SET status = ?2, result = ?3, updated_at = ?4 WHERE id = ?1"
+        );
+
+        self.conn
+            .execute(
+                sql.as_str(),
+                params![&id, status, &result, &chrono::Utc::now()],
+            )
+            .log_err_msg("Failed to finish job")?;
+
+        Ok(())
+    }
+}
+
+impl Table for JobsTable {
+    fn connection(&self) -> &DbConnection {
+        &self.conn
+    }
+
+    fn create_table(&self) {
+        let table_name = Self::TABLE_NAME;
+
+        log::info!("Creating table: {table_name}");
+        let sql = format!(
+            "
+            BEGIN;
+                CREATE TABLE IF NOT EXISTS {table_name} (
+                    id INTEGER PRIMARY KEY,
+                    job TEXT NOT NULL,
+                    guild_id INTEGER,
+                    channel_id INTEGER,
+                    user_id INTEGER NOT NULL,
+                    status VARCHAR(16) NOT NULL,
+                    progress TEXT,
+                    result TEXT,
+                    created_at VARCHAR(25) NOT NULL,
+                    updated_at VARCHAR(25) NOT NULL
+                );
+
+                CREATE INDEX IF NOT EXISTS {table_name}_status ON {table_name} (status);
+            COMMIT;"
+        );
+
+        self.conn
+            .execute_batch(sql.as_str())
+            .log_err_msg("Failed create table")
+            .log_ok_msg(format!("Created table {table_name}"))
+            .unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::TestDb;
+
+    use super::*;
+
+    #[test]
+    fn table_create_test() {
+        let table = TestDb::new().jobs_table();
+        table.create_table();
+    }
+
+    #[test]
+    fn jobs_test() {
+        let table = TestDb::new().jobs_table();
+        let insert = JobInsert {
+            job: r#"{"kind":"backup"}"#.into(),
+            guild_id: Some(1),
+            channel_id: Some(2),
+            user_id: 3,
+        };
+
+        let id = table.insert_job(&insert).unwrap();
+        let other_id = table.insert_job(&insert).unwrap();
+        let job = table.find_job(id).unwrap().unwrap();
+        assert_eq!(JobStatus::Queued, job.status);
+        assert_eq!(insert.job, job.job);
+
+        table.set_status(id, JobStatus::Running).unwrap();
+        table.set_progress(id, "45%").unwrap();
+        let job = table.find_job(id).unwrap().unwrap();
+        assert_eq!(JobStatus::Running, job.status);
+        assert_eq!(Some("45%".into()), job.progress);
+
+        let unfinished: Vec<_> = table
+            .unfinished_jobs()
+            .unwrap()
+            .iter()
+            .map(|job| job.id)
+            .collect();
+        assert_eq!(vec![id, other_id], unfinished);
+
+        table.finish_job(id, JobStatus::Done, "Backed up").unwrap();
+        let job = table.find_job(id).unwrap().unwrap();
+        assert!(job.status.is_finished());
+        assert_eq!(Some("Backed up".into()), job.result);
+        assert_eq!(1, table.unfinished_jobs().unwrap().len());
+
+        let recent: Vec<_> = table
+            .recent_jobs(1, 10)
+            .unwrap()
+            .iter()
+            .map(|job| job.id)
+            .collect();
+        assert_eq!(vec![other_id, id], recent);
+        assert!(table.recent_jobs(2, 10).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,519 @@
+use rusqlite::params;
+
+use crate::{commands::PoiseError, common::LogResult};
+
+use super::{
+    AudioTable, DbConnection, FavoritesTable, GuildSettingsTable, PendingSoundsTable,
+    SettingsTable, SoundName,
+};
+
+type Migration = fn(&DbConnection) -> rusqlite::Result<()>;
+
+/// Schema changes for databases created by older bot versions, applied in order.
+/// Tables are created with their latest schema, so each migration must be idempotent.
+const MIGRATIONS: &[Migration] = &[
+    add_audio_play_count,
+    add_indexes,
+    add_audio_tags,
+    add_guild_settings_prefix,
+    add_guild_settings_sotd,
+    add_guild_settings_record_native_sounds,
+    add_audio_archive,
+    add_audio_emoji,
+    add_audio_metadata,
+    add_guild_settings_review_channel,
+    add_author_hash,
+    add_guild_settings_digest,
+    add_guild_settings_self_check,
+    add_guild_settings_priority_mode,
+    add_guild_settings_autocomplete,
+    settings_join_leave_audio_ids,
+    add_guild_settings_silent_play,
+    add_audio_category,
+    add_audio_button_style,
+    add_guild_settings_soundboard_order,
+    add_guild_settings_play_history_public,
+    add_audio_source_url,
+    add_audio_content_hash,
+    add_audio_slug,
+    add_guild_settings_loudness_action,
+    add_guild_settings_max_duration,
+];
+
+/// Schema version of databases with every migration applied
+pub fn latest_schema_version() -> i64 {
+    MIGRATIONS.len() as i64
+}
+
+/// Migrations applied to the database, as recorded in its `user_version`
+pub fn schema_version(conn: &DbConnection) -> Result<i64, PoiseError> {
+    Ok(conn
+        .query_row("PRAGMA user_version", (), |row| row.get(0))
+        .log_err_msg("Failed to get database user_version")?)
+}
+
+/// Apply migrations not yet recorded in the database `user_version`
+pub fn run_migrations(conn: &DbConnection) -> Result<(), PoiseError> {
+    let version = schema_version(conn)?;
+
+    for (index, migration) in MIGRATIONS.iter().enumerate().skip(version as usize) {
+        let next_version = index + 1;
+        log::info!("Applying database migration v{next_version}");
+
+        migration(conn).log_err_msg(format!("Failed database migration v{next_version}"))?;
+        conn.execute_batch(format!("PRAGMA user_version = {next_version};").as_str())
+            .log_err_msg("Failed to set database user_version")?;
+    }
+
+    Ok(())
+}
+
+fn column_exists(conn: &DbConnection, table_name: &str, column: &str) -> rusqlite::Result<bool> {
+    let sql = format!("SELECT COUNT(*) FROM pragma_table_info('{table_name}') WHERE name = ?");
+    let count: i64 = conn.query_row(sql.as_str(), [column], |row| row.get(0))?;
+    Ok(count > 0)
+}
+
+fn table_exists(conn: &DbConnection, table_name: &str) -> rusqlite::Result<bool> {
+    let sql = "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = ?";
+    let count: i64 = conn.query_row(sql, [table_name], |row| row.get(0))?;
+    Ok(count > 0)
+}
+
+fn add_column(
+    conn: &DbConnection,
+    table_name: &str,
+    column: &str,
+    definition: &str,
+) -> rusqlite::Result<()> {
+    if !column_exists(conn, table_name, column)? {
+        let sql = format!("ALTER TABLE {table_name} ADD COLUMN {column} {definition}");
+        conn.execute_batch(sql.as_str())?;
+    }
+    Ok(())
+}
+
+fn add_audio_play_count(conn: &DbConnection) -> rusqlite::Result<()> {
+    let table_name = AudioTable::TABLE_NAME;
+    add_column(conn, table_name, "play_count", "INTEGER NOT NULL DEFAULT 0")?;
+    add_column(conn, table_name, "last_played_at", "VARCHAR(25)")?;
+    Ok(())
+}
+
+/// Indexes for recent sounds (autocomplete, listing), most played sounds and uploader/guild lookups
+fn add_indexes(conn: &DbConnection) -> rusqlite::Result<()> {
+    let table_name = AudioTable::TABLE_NAME;
+    let favorites_table_name = FavoritesTable::TABLE_NAME;
+
+    add_column(conn, table_name, "guild_id", "INTEGER")?;
+
+    let sql = format!(
+        "
+        CREATE INDEX IF NOT EXISTS {table_name}_created_at_idx ON {table_name}(created_at);
+        CREATE INDEX IF NOT EXISTS {table_name}_author_id_idx ON {table_name}(author_id);
+        CREATE INDEX IF NOT EXISTS {table_name}_guild_id_idx ON {table_name}(guild_id);
+        CREATE INDEX IF NOT EXISTS {table_name}_play_count_idx ON {table_name}(play_count);
+        CREATE INDEX IF NOT EXISTS {favorites_table_name}_audio_id_idx ON {favorites_table_name}(audio_id);
+        "
+    );
+    conn.execute_batch(sql.as_str())
+}
+
+/// Normalized audio tags table, backfilled from the audio table's space separated tags
+fn add_audio_tags(conn: &DbConnection) -> rusqlite::Result<()> {
+    let table_name = AudioTable::TABLE_NAME;
+    let tags_table_name = AudioTable::TAGS_TABLE_NAME;
+
+    let sql = format!(
+        "
+        CREATE TABLE IF NOT EXISTS {tags_table_name} (
+            audio_id INTEGER NOT NULL,
+            tag VARCHAR(80) NOT NULL,
+            PRIMARY KEY (tag, audio_id)
+        );
+        CREATE INDEX IF NOT EXISTS {tags_table_name}_audio_id_idx ON {tags_table_name}(audio_id);
+        "
+    );
+    conn.execute_batch(sql.as_str())?;
+
+    if column_exists(conn, table_name, "tags")? {
+        let json_each_tags = AudioTable::json_each_tags_sql("a");
+        let sql = format!(
+            "
+            INSERT OR IGNORE INTO {tags_table_name}(audio_id, tag)
+                SELECT a.id, lower(j.value) FROM {table_name} a, {json_each_tags} j
+                WHERE j.value != ''"
+        );
+        conn.execute_batch(sql.as_str())?;
+    }
+
+    Ok(())
+}
+
+/// Per-guild prefix command prefix. Guild settings tables predating it lack the column
+fn add_guild_settings_prefix(conn: &DbConnection) -> rusqlite::Result<()> {
+    let table_name = GuildSettingsTable::TABLE_NAME;
+    if table_exists(conn, table_name)? {
+        add_column(conn, table_name, "prefix", "VARCHAR(8)")?;
+    }
+    Ok(())
+}
+
+fn add_guild_settings_sotd(conn: &DbConnection) -> rusqlite::Result<()> {
+    let table_name = GuildSettingsTable::TABLE_NAME;
+    if table_exists(conn, table_name)? {
+        add_column(conn, table_name, "sotd_channel_id", "INTEGER")?;
+        add_column(conn, table_name, "sotd_voice_channel_id", "INTEGER")?;
+        add_column(conn, table_name, "sotd_time", "VARCHAR(16)")?;
+        add_column(conn, table_name, "sotd_mode", "VARCHAR(16)")?;
+        add_column(conn, table_name, "sotd_last_date", "VARCHAR(10)")?;
+        add_column(conn, table_name, "sotd_last_audio_id", "INTEGER")?;
+    }
+    Ok(())
+}
+
+fn add_guild_settings_record_native_sounds(conn: &DbConnection) -> rusqlite::Result<()> {
+    let table_name = GuildSettingsTable::TABLE_NAME;
+    if table_exists(conn, table_name)? {
+        add_column(
+            conn,
+            table_name,
+            "record_native_sounds",
+            "INTEGER NOT NULL DEFAULT 0",
+        )?;
+    }
+    Ok(())
+}
+
+/// Archived sounds and pruning suggestion state, plus the per-guild auto-archive policy
+fn add_audio_archive(conn: &DbConnection) -> rusqlite::Result<()> {
+    let table_name = AudioTable::TABLE_NAME;
+    add_column(conn, table_name, "archived_at", "VARCHAR(25)")?;
+    add_column(conn, table_name, "prune_kept_at", "VARCHAR(25)")?;
+
+    let guild_settings_table_name = GuildSettingsTable::TABLE_NAME;
+    if table_exists(conn, guild_settings_table_name)? {
+        add_column(
+            conn,
+            guild_settings_table_name,
+            "auto_archive_months",
+            "INTEGER",
+        )?;
+    }
+    Ok(())
+}
+
+/// Emoji shown on sound buttons
+fn add_audio_emoji(conn: &DbConnection) -> rusqlite::Result<()> {
+    add_column(conn, AudioTable::TABLE_NAME, "emoji", "VARCHAR(64)")
+}
+
+/// Probed track duration and file size. Existing rows are backfilled on startup by
+/// `ingest::backfill_audio_metadata`, as probing needs the audio files
+fn add_audio_metadata(conn: &DbConnection) -> rusqlite::Result<()> {
+    let table_name = AudioTable::TABLE_NAME;
+    add_column(conn, table_name, "duration_ms", "INTEGER")?;
+    add_column(conn, table_name, "file_size", "INTEGER")?;
+    Ok(())
+}
+
+/// Channel of the sound approval workflow
+fn add_guild_settings_review_channel(conn: &DbConnection) -> rusqlite::Result<()> {
+    let table_name = GuildSettingsTable::TABLE_NAME;
+    if table_exists(conn, table_name)? {
+        add_column(conn, table_name, "review_channel_id", "INTEGER")?;
+    }
+    Ok(())
+}
+
+/// Anonymous uploads, plus the secret salt their author hashes are salted with
+fn add_author_hash(conn: &DbConnection) -> rusqlite::Result<()> {
+    add_column(conn, AudioTable::TABLE_NAME, "author_hash", "VARCHAR(64)")?;
+
+    let pending_sounds_table_name = PendingSoundsTable::TABLE_NAME;
+    if table_exists(conn, pending_sounds_table_name)? {
+        add_column(
+            conn,
+            pending_sounds_table_name,
+            "author_hash",
+            "VARCHAR(64)",
+        )?;
+    }
+
+    let settings_table_name = SettingsTable::TABLE_NAME;
+    if table_exists(conn, settings_table_name)? {
+        add_column(conn, settings_table_name, "author_hash_salt", "VARCHAR(64)")?;
+    }
+    Ok(())
+}
+
+/// Weekly digest channel
+fn add_guild_settings_digest(conn: &DbConnection) -> rusqlite::Result<()> {
+    let table_name = GuildSettingsTable::TABLE_NAME;
+    if table_exists(conn, table_name)? {
+        add_column(conn, table_name, "digest_channel_id", "INTEGER")?;
+        add_column(conn, table_name, "digest_last_date", "VARCHAR(10)")?;
+    }
+    Ok(())
+}
+
+/// Missing permissions guild owners were last warned about
+fn add_guild_settings_self_check(conn: &DbConnection) -> rusqlite::Result<()> {
+    let table_name = GuildSettingsTable::TABLE_NAME;
+    if table_exists(conn, table_name)? {
+        add_column(conn, table_name, "self_check_warning", "VARCHAR(1024)")?;
+    }
+    Ok(())
+}
+
+/// What sounds played with priority do to playing sounds
+fn add_guild_settings_priority_mode(conn: &DbConnection) -> rusqlite::Result<()> {
+    let table_name = GuildSettingsTable::TABLE_NAME;
+    if table_exists(conn, table_name)? {
+        add_column(conn, table_name, "priority_mode", "VARCHAR(16)")?;
+    }
+    Ok(())
+}
+
+/// Per-guild autocomplete limit and minimum query length
+fn add_guild_settings_autocomplete(conn: &DbConnection) -> rusqlite::Result<()> {
+    let table_name = GuildSettingsTable::TABLE_NAME;
+    if table_exists(conn, table_name)? {
+        add_column(conn, table_name, "autocomplete_limit", "INTEGER")?;
+        add_column(conn, table_name, "autocomplete_min_query_len", "INTEGER")?;
+    }
+    Ok(())
+}
+
+/// Join and leave audio stored by sound id (see `UniqueAudioTableCol::to_input`) instead of
+/// name, so they still play after the sound is renamed
+fn settings_join_leave_audio_ids(conn: &DbConnection) -> rusqlite::Result<()> {
+    let table_name = SettingsTable::TABLE_NAME;
+    let audio_table_name = AudioTable::TABLE_NAME;
+    if table_exists(conn, table_name)? && table_exists(conn, audio_table_name)? {
+        for col in ["join_audio", "leave_audio"] {
+            conn.execute_batch(&format!(
+                "
+                UPDATE {table_name}
+                SET {col} = '#' || (SELECT id FROM {audio_table_name} a WHERE a.name = {table_name}.{col})
+                WHERE {col} IN (SELECT name FROM {audio_table_name});"
+            ))?;
+        }
+    }
+    Ok(())
+}
+
+/// Per-guild silent play default
+fn add_guild_settings_silent_play(conn: &DbConnection) -> rusqlite::Result<()> {
+    let table_name = GuildSettingsTable::TABLE_NAME;
+    if table_exists(conn, table_name)? {
+        add_column(
+            conn,
+            table_name,
+            "silent_play",
+            "INTEGER NOT NULL DEFAULT 0",
+        )?;
+    }
+    Ok(())
+}
+
+/// Soundboard category of sounds (see `CategoriesTable`)
+fn add_audio_category(conn: &DbConnection) -> rusqlite::Result<()> {
+    add_column(conn, AudioTable::TABLE_NAME, "category_id", "INTEGER")
+}
+
+/// Style of sound buttons (see `helpers::SoundButtonStyle`)
+fn add_audio_button_style(conn: &DbConnection) -> rusqlite::Result<()> {
+    add_column(conn, AudioTable::TABLE_NAME, "button_style", "VARCHAR(16)")
+}
+
+/// Order of soundboard sounds (see `soundboard::SoundboardOrder`)
+fn add_guild_settings_soundboard_order(conn: &DbConnection) -> rusqlite::Result<()> {
+    let table_name = GuildSettingsTable::TABLE_NAME;
+    if table_exists(conn, table_name)? {
+        add_column(conn, table_name, "soundboard_order", "VARCHAR(16)")?;
+    }
+    Ok(())
+}
+
+/// Per-guild visibility of `/history`
+fn add_guild_settings_play_history_public(conn: &DbConnection) -> rusqlite::Result<()> {
+    let table_name = GuildSettingsTable::TABLE_NAME;
+    if table_exists(conn, table_name)? {
+        add_column(
+            conn,
+            table_name,
+            "play_history_public",
+            "INTEGER NOT NULL DEFAULT 0",
+        )?;
+    }
+    Ok(())
+}
+
+/// Url sounds were downloaded from, for `/sounds refetch`
+fn add_audio_source_url(conn: &DbConnection) -> rusqlite::Result<()> {
+    add_column(conn, AudioTable::TABLE_NAME, "source_url", "VARCHAR(2048)")?;
+
+    let pending_sounds_table_name = PendingSoundsTable::TABLE_NAME;
+    if table_exists(conn, pending_sounds_table_name)? {
+        add_column(
+            conn,
+            pending_sounds_table_name,
+            "source_url",
+            "VARCHAR(2048)",
+        )?;
+    }
+    Ok(())
+}
+
+/// Hashes of audio files, verified before playback (see `checksums`)
+fn add_audio_content_hash(conn: &DbConnection) -> rusqlite::Result<()> {
+    add_column(conn, AudioTable::TABLE_NAME, "content_hash", "VARCHAR(64)")
+}
+
+/// File safe slugs of sound names (see `SoundName::slug`), backfilled for existing sounds
+fn add_audio_slug(conn: &DbConnection) -> rusqlite::Result<()> {
+    let table_name = AudioTable::TABLE_NAME;
+    add_column(conn, table_name, "slug", "VARCHAR(256)")?;
+
+    let names: Vec<(i64, SoundName)> = conn
+        .prepare(&format!(
+            "SELECT id, name FROM {table_name} WHERE slug IS NULL"
+        ))?
+        .query_map((), |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<rusqlite::Result<_>>()?;
+
+    let mut stmt = conn.prepare(&format!("UPDATE {table_name} SET slug = ?1 WHERE id = ?2"))?;
+    for (id, name) in names {
+        stmt.execute(params![name.slug(), id])?;
+    }
+    Ok(())
+}
+
+/// Per-guild handling of sounds exceeding the loudness ceiling
+fn add_guild_settings_loudness_action(conn: &DbConnection) -> rusqlite::Result<()> {
+    let table_name = GuildSettingsTable::TABLE_NAME;
+    if table_exists(conn, table_name)? {
+        add_column(conn, table_name, "loudness_action", "VARCHAR(16)")?;
+    }
+    Ok(())
+}
+
+/// Per-guild max sound length
+fn add_guild_settings_max_duration(conn: &DbConnection) -> rusqlite::Result<()> {
+    let table_name = GuildSettingsTable::TABLE_NAME;
+    if table_exists(conn, table_name)? {
+        add_column(conn, table_name, "max_duration_ms", "INTEGER")?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::TestDb;
+
+    use super::*;
+
+    #[test]
+    fn run_migrations_test() {
+        let conn = TestDb::empty().connection();
+
+        // tables as created by older bot versions
+        conn.execute_batch(
+            "CREATE TABLE audio (
+                id INTEGER PRIMARY KEY,
+                name VARCHAR(80) NOT NULL UNIQUE,
+                tags VARCHAR(2048),
+                created_at VARCHAR(25) NOT NULL,
+                author_id INTEGER
+            );
+            CREATE TABLE favorites (
+                user_id INTEGER NOT NULL,
+                audio_id INTEGER NOT NULL
+            );
+            CREATE TABLE guild_settings (
+                guild_id INTEGER PRIMARY KEY,
+                language VARCHAR(8)
+            );
+            CREATE TABLE settings (
+                id INTEGER PRIMARY KEY,
+                join_audio VARCHAR(80),
+                leave_audio VARCHAR(80)
+            );
+            INSERT INTO audio (name, tags, created_at) VALUES ('Beep', 'Droid r2d2', '2024-01-01');
+            INSERT INTO audio (name, tags, created_at) VALUES ('Boop', NULL, '2024-01-01');
+            INSERT INTO settings (join_audio, leave_audio) VALUES ('Boop', 'Missing');",
+        )
+        .unwrap();
+
+        run_migrations(&conn).unwrap();
+        run_migrations(&conn).unwrap();
+
+        assert!(column_exists(&conn, "audio", "play_count").unwrap());
+        assert!(column_exists(&conn, "audio", "last_played_at").unwrap());
+        assert!(column_exists(&conn, "audio", "guild_id").unwrap());
+        assert!(column_exists(&conn, "guild_settings", "prefix").unwrap());
+        assert!(column_exists(&conn, "guild_settings", "sotd_channel_id").unwrap());
+        assert!(column_exists(&conn, "guild_settings", "sotd_last_audio_id").unwrap());
+        assert!(column_exists(&conn, "guild_settings", "record_native_sounds").unwrap());
+        assert!(column_exists(&conn, "audio", "archived_at").unwrap());
+        assert!(column_exists(&conn, "audio", "prune_kept_at").unwrap());
+        assert!(column_exists(&conn, "guild_settings", "auto_archive_months").unwrap());
+        assert!(column_exists(&conn, "audio", "emoji").unwrap());
+        assert!(column_exists(&conn, "audio", "duration_ms").unwrap());
+        assert!(column_exists(&conn, "audio", "file_size").unwrap());
+        assert!(column_exists(&conn, "guild_settings", "review_channel_id").unwrap());
+        assert!(column_exists(&conn, "audio", "author_hash").unwrap());
+        assert!(column_exists(&conn, "settings", "author_hash_salt").unwrap());
+        assert!(column_exists(&conn, "guild_settings", "digest_channel_id").unwrap());
+        assert!(column_exists(&conn, "guild_settings", "self_check_warning").unwrap());
+        assert!(column_exists(&conn, "guild_settings", "autocomplete_limit").unwrap());
+        assert!(column_exists(&conn, "guild_settings", "silent_play").unwrap());
+        assert!(column_exists(&conn, "audio", "category_id").unwrap());
+        assert!(column_exists(&conn, "audio", "button_style").unwrap());
+        assert!(column_exists(&conn, "guild_settings", "soundboard_order").unwrap());
+        assert!(column_exists(&conn, "guild_settings", "play_history_public").unwrap());
+        assert!(column_exists(&conn, "audio", "source_url").unwrap());
+        assert!(column_exists(&conn, "audio", "content_hash").unwrap());
+        assert!(column_exists(&conn, "audio", "slug").unwrap());
+        assert!(column_exists(&conn, "guild_settings", "loudness_action").unwrap());
+        assert!(column_exists(&conn, "guild_settings", "max_duration_ms").unwrap());
+
+        let (join_audio, leave_audio): (String, String) = conn
+            .query_row("SELECT join_audio, leave_audio FROM settings", (), |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })
+            .unwrap();
+        assert_eq!("#2", join_audio);
+        assert_eq!("Missing", leave_audio);
+
+        let index_count: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM sqlite_master WHERE type = 'index' AND name LIKE '%_idx'",
+                (),
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(6, index_count);
+
+        let tags: Vec<String> = conn
+            .prepare("SELECT tag FROM audio_tags ORDER BY tag")
+            .unwrap()
+            .query_map((), |row| row.get(0))
+            .unwrap()
+            .map(|tag| tag.unwrap())
+            .collect();
+        assert_eq!(vec!["droid", "r2d2"], tags);
+
+        let slugs: Vec<String> = conn
+            .prepare("SELECT slug FROM audio ORDER BY id")
+            .unwrap()
+            .query_map((), |row| row.get(0))
+            .unwrap()
+            .map(|slug| slug.unwrap())
+            .collect();
+        assert_eq!(vec!["beep", "boop"], slugs);
+
+        assert_eq!(latest_schema_version(), schema_version(&conn).unwrap());
+    }
+}
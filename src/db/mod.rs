@@ -1,13 +1,82 @@
 pub mod audio_table;
+pub mod blocklist_table;
+pub mod categories_table;
+pub mod channel_restrictions_table;
+pub mod command_stats_table;
+pub mod favorites_table;
+pub mod guild_settings_table;
+pub mod jobs_table;
+pub mod migrations;
+pub mod native_sounds_table;
 pub mod paginators;
+pub mod pending_sounds_table;
+pub mod permissions_table;
+pub mod play_events_table;
+pub mod sessions_table;
 pub mod settings_table;
+pub mod share_codes_table;
+pub mod soundboard_messages_table;
+pub mod soundboard_pages_table;
+pub mod user_plays_table;
 
-pub use audio_table::{AudioTable, AudioTableRow, AudioTableRowInsert, Tags, UniqueAudioTableCol};
-pub use paginators::AudioTablePaginator;
+pub use audio_table::{
+    AudioTable, AudioTableFilter, AudioTableRow, AudioTableRowInsert, AutocompleteTrack, SoundName,
+    Tags, UniqueAudioTableCol,
+};
+pub use blocklist_table::{BlocklistTable, BlocklistTableRow};
+pub use categories_table::{CategoriesTable, CategoryRow};
+pub use channel_restrictions_table::ChannelRestrictionsTable;
+pub use command_stats_table::{CommandStatsRow, CommandStatsTable};
+pub use favorites_table::FavoritesTable;
+pub use guild_settings_table::{GuildSettingsRow, GuildSettingsTable};
+pub use jobs_table::{JobInsert, JobRow, JobStatus, JobsTable};
+pub use migrations::{latest_schema_version, run_migrations, schema_version};
+pub use native_sounds_table::{NativeSoundsTable, NativeSoundsTableRow};
+pub use paginators::{AudioTablePaginator, CategoryFilter};
+pub use pending_sounds_table::{PendingSoundRow, PendingSoundsTable};
+pub use permissions_table::PermissionsTable;
+pub use play_events_table::{HistogramBucket, PlayEventInsert, PlayEventsFilter, PlayEventsTable};
+pub use sessions_table::{SessionPlayRow, SessionRow, SessionsTable};
 pub use settings_table::SettingsTable;
+pub use share_codes_table::{ShareCodeRow, ShareCodesTable};
+pub use soundboard_messages_table::{SoundboardMessageRow, SoundboardMessagesTable};
+pub use soundboard_pages_table::{SoundboardPageRow, SoundboardPagesTable};
+pub use user_plays_table::UserPlaysTable;
 
 pub type DbConnection = r2d2::PooledConnection<r2d2_sqlite::SqliteConnectionManager>;
 
+/// Unlock a SQLCipher encrypted database with key, before the connection is used. A no-op
+/// without a key
+pub fn apply_encryption_key(
+    conn: &rusqlite::Connection,
+    key: Option<&str>,
+) -> rusqlite::Result<()> {
+    match key {
+        Some(key) => conn.pragma_update(None, "key", key),
+        None => Ok(()),
+    }
+}
+
+/// Flags database connections are opened with. Read-only mirrors (`Config::read_only`) open
+/// the database read-only, so SQLite rejects every write made through any table
+pub fn open_flags(read_only: bool) -> rusqlite::OpenFlags {
+    let flags = rusqlite::OpenFlags::SQLITE_OPEN_URI | rusqlite::OpenFlags::SQLITE_OPEN_NO_MUTEX;
+    match read_only {
+        true => flags | rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY,
+        false => {
+            flags
+                | rusqlite::OpenFlags::SQLITE_OPEN_READ_WRITE
+                | rusqlite::OpenFlags::SQLITE_OPEN_CREATE
+        }
+    }
+}
+
+/// Connection can't write, i.e. it's a read-only mirror's connection
+pub fn is_read_only(conn: &rusqlite::Connection) -> bool {
+    conn.is_readonly(rusqlite::DatabaseName::Main)
+        .unwrap_or(false)
+}
+
 pub trait Table {
     fn connection(&self) -> &DbConnection;
     fn create_table(&self);
@@ -0,0 +1,203 @@
+use rusqlite::{params, OptionalExtension};
+
+use crate::{commands::PoiseError, common::LogResult};
+
+use super::{AudioTable, DbConnection, Table};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct NativeSoundsTableRow {
+    /// Discord soundboard sound id
+    pub sound_id: String,
+    /// Bot sound (audio table id) the native sound counts as
+    pub audio_id: i64,
+}
+
+impl TryFrom<&rusqlite::Row<'_>> for NativeSoundsTableRow {
+    type Error = rusqlite::Error;
+
+    fn try_from(row: &rusqlite::Row<'_>) -> Result<Self, Self::Error> {
+        Ok(Self {
+            sound_id: row.get("sound_id")?,
+            audio_id: row.get("audio_id")?,
+        })
+    }
+}
+
+/// Per-guild mappings of Discord native soundboard sounds to bot sounds, so native sound plays
+/// are recorded in the bot sound's play statistics
+pub struct NativeSoundsTable {
+    conn: DbConnection,
+}
+
+impl NativeSoundsTable {
+    pub const TABLE_NAME: &'static str = "native_sounds";
+
+    pub fn new(connection: DbConnection) -> Self {
+        Self { conn: connection }
+    }
+
+    /// Mappings of guild whose bot sound still exists
+    pub fn mappings(&self, guild_id: u64) -> Result<Vec<NativeSoundsTableRow>, PoiseError> {
+        let table_name = Self::TABLE_NAME;
+        let audio_table_name = AudioTable::TABLE_NAME;
+        let sql = format!(
+            "
+            SELECT n.sound_id, n.audio_id FROM {table_name} n
+            INNER JOIN {audio_table_name} a ON a.id = n.audio_id
+            WHERE n.guild_id = ?1
+            ORDER BY n.sound_id"
+        );
+
+        let mut stmt = self
+            .conn
+            .prepare(sql.as_str())
+            .log_err_msg("Native sound mappings sql invalid")?;
+
+        let rows = stmt
+            .query_map(params![&guild_id], |row| {
+                NativeSoundsTableRow::try_from(row)
+            })
+            .log_err_msg("Native sound mappings sql query error")?;
+
+        Ok(rows.filter_map(|row| row.log_err().ok()).collect())
+    }
+
+    /// Bot sound native sound is mapped to in guild
+    pub fn mapped_audio_id(
+        &self,
+        guild_id: u64,
+        sound_id: &str,
+    ) -> Result<Option<i64>, PoiseError> {
+        let table_name = Self::TABLE_NAME;
+        let audio_table_name = AudioTable::TABLE_NAME;
+        let sql = format!(
+            "
+            SELECT n.audio_id FROM {table_name} n
+            INNER JOIN {audio_table_name} a ON a.id = n.audio_id
+            WHERE n.guild_id = ?1 AND n.sound_id = ?2"
+        );
+
+        let audio_id = self
+            .conn
+            .query_row(sql.as_str(), params![&guild_id, &sound_id], |row| {
+                row.get(0)
+            })
+            .optional()
+            .log_err_msg("Failed to get mapped audio id of native sound")?;
+
+        Ok(audio_id)
+    }
+
+    pub fn map_sound(&self, guild_id: u64, row: &NativeSoundsTableRow) -> Result<(), PoiseError> {
+        log::info!("Mapping native sound. Guild Id: {guild_id}, Mapping: {row:?}");
+
+        let table_name = Self::TABLE_NAME;
+        let sql = format!(
+            "
+            INSERT OR REPLACE INTO {table_name}
+                (guild_id, sound_id, audio_id)
+            VALUES
+                (?1, ?2, ?3)"
+        );
+
+        self.conn
+            .execute(
+                sql.as_str(),
+                params![&guild_id, &row.sound_id, &row.audio_id],
+            )
+            .log_err_msg("Failed to map native sound")?;
+
+        Ok(())
+    }
+
+    /// Returns true if native sound was mapped
+    pub fn unmap_sound(&self, guild_id: u64, sound_id: &str) -> Result<bool, PoiseError> {
+        log::info!("Unmapping native sound. Guild Id: {guild_id}, Sound Id: {sound_id}");
+
+        let table_name = Self::TABLE_NAME;
+        let sql = format!("DELETE FROM {table_name} WHERE guild_id = ?1 AND sound_id = ?2");
+
+        let deleted = self
+            .conn
+            .execute(sql.as_str(), params![&guild_id, &sound_id])
+            .log_err_msg("Failed to unmap native sound")?;
+
+        Ok(deleted > 0)
+    }
+}
+
+impl Table for NativeSoundsTable {
+    fn connection(&self) -> &DbConnection {
+        &self.conn
+    }
+
+    fn create_table(&self) {
+        let table_name = Self::TABLE_NAME;
+
+        log::info!("Creating table: {table_name}");
+        let sql = format!(
+            "
+            CREATE TABLE IF NOT EXISTS {table_name} (
+                guild_id INTEGER NOT NULL,
+                sound_id VARCHAR(32) NOT NULL,
+                audio_id INTEGER NOT NULL,
+                PRIMARY KEY (guild_id, sound_id)
+            );"
+        );
+
+        self.conn
+            .execute_batch(sql.as_str())
+            .log_err_msg("Failed create table")
+            .log_ok_msg(format!("Created table {table_name}"))
+            .unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::db::UniqueAudioTableCol;
+    use crate::test_utils::{AudioRowFixture, TestDb};
+
+    use super::*;
+
+    #[test]
+    fn table_create_test() {
+        let table = TestDb::new().native_sounds_table();
+        table.create_table();
+    }
+
+    #[test]
+    fn map_unmap_sound_test() {
+        let db = TestDb::new();
+        let table = db.native_sounds_table();
+        let audio_table = db.audio_table();
+        let beep = AudioRowFixture::new().insert(&audio_table);
+        let boop = AudioRowFixture::new().insert(&audio_table);
+
+        let row = NativeSoundsTableRow {
+            sound_id: "1234".into(),
+            audio_id: beep.id,
+        };
+        table.map_sound(1, &row).unwrap();
+        assert_eq!(Some(beep.id), table.mapped_audio_id(1, "1234").unwrap());
+        assert_eq!(None, table.mapped_audio_id(2, "1234").unwrap());
+
+        // remapping replaces bot sound
+        let row = NativeSoundsTableRow {
+            audio_id: boop.id,
+            ..row
+        };
+        table.map_sound(1, &row).unwrap();
+        assert_eq!(vec![row], table.mappings(1).unwrap());
+
+        // mappings of deleted bot sounds are ignored
+        audio_table
+            .delete_audio_row(UniqueAudioTableCol::Id(boop.id))
+            .unwrap();
+        assert_eq!(None, table.mapped_audio_id(1, "1234").unwrap());
+        assert!(table.mappings(1).unwrap().is_empty());
+
+        assert!(table.unmap_sound(1, "1234").unwrap());
+        assert!(!table.unmap_sound(1, "1234").unwrap());
+    }
+}
@@ -1,14 +1,28 @@
+use futures::stream::{self, Stream, StreamExt};
+
 use super::{
     audio_table::{AudioTableOrderBy, AudioTableRow},
-    AudioTable, DbConnection,
+    AudioTable, DbConnection, Tags,
 };
 
+/// Soundboard section (see `CategoriesTable`) to paginate
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CategoryFilter {
+    /// Sounds of category id
+    Category(i64),
+    Uncategorized,
+}
+
 #[derive(Debug)]
 pub struct AudioTablePaginator {
     conn: DbConnection,
     order_by: AudioTableOrderBy,
     page_limit: u64,
     offset: u64,
+    tag: Option<String>,
+    category: Option<CategoryFilter>,
+    include_archived: bool,
+    ranked_ids: Vec<i64>,
 }
 
 impl AudioTablePaginator {
@@ -19,36 +33,103 @@ impl AudioTablePaginator {
     pub fn next_page(&mut self) -> Result<Vec<AudioTableRow>, String> {
         let conn = &self.conn;
         let table_name = AudioTable::TABLE_NAME;
+        let tags_table_name = AudioTable::TAGS_TABLE_NAME;
         let order_by = self.order_by.col_name();
         let page_limit = self.page_limit;
-        let offset = self.offset;
+        let rank = match self.ranked_ids.len() {
+            0 => "".to_string(),
+            len => {
+                let ranks: String = self
+                    .ranked_ids
+                    .iter()
+                    .enumerate()
+                    .map(|(rank, id)| format!(" WHEN {id} THEN {rank}"))
+                    .collect();
+                format!("CASE id{ranks} ELSE {len} END, ")
+            }
+        };
 
         let sql = format!(
             "SELECT * FROM {table_name}
-            ORDER BY {order_by}
+            WHERE (?1 IS NULL OR id IN (SELECT audio_id FROM {tags_table_name} WHERE tag = ?1))
+                AND (?2 OR archived_at IS NULL)
+                AND (NOT ?3 OR category_id IS ?4)
+            ORDER BY {rank}{order_by}
             LIMIT {page_limit}
-            OFFSET {offset};"
+            OFFSET ?5;"
         );
 
+        let category_id = match self.category {
+            Some(CategoryFilter::Category(category_id)) => Some(category_id),
+            _ => None,
+        };
+
         let mut stmt = conn
             .prepare(sql.as_ref())
             .expect("Failed to prepare sql stmt");
 
-        let row_iter = stmt
-            .query_map([], |row| AudioTableRow::try_from(row))
-            .map_err(|err| format!("Error in AudioTablePaginator - {err}"))?;
+        loop {
+            let row_iter = stmt
+                .query_map(
+                    rusqlite::params![
+                        &self.tag,
+                        &self.include_archived,
+                        &self.category.is_some(),
+                        &category_id,
+                        &self.offset
+                    ],
+                    |row| AudioTableRow::try_from(row),
+                )
+                .map_err(|err| format!("Error in AudioTablePaginator - {err}"))?;
 
-        self.offset += self.page_limit;
+            let mut queried: u64 = 0;
+            let rows: Vec<_> = row_iter
+                .filter_map(|row| {
+                    queried += 1;
+                    match row {
+                        Ok(val) => Some(val),
+                        Err(err) => {
+                            log::error!("Skipping audio row - {err}");
+                            None
+                        }
+                    }
+                })
+                .collect();
 
-        Ok(row_iter
-            .filter_map(|row| match row {
-                Ok(val) => Some(val),
-                Err(err) => {
-                    log::error!("{err}");
-                    None
-                }
+            self.offset += self.page_limit;
+
+            // a full page of skipped rows isn't the last page
+            if rows.is_empty() && queried == page_limit {
+                continue;
+            }
+            return Ok(rows);
+        }
+    }
+
+    /// Stream the remaining audio rows, each page queried on a blocking thread, so callers can
+    /// go through the whole library without holding it in memory or blocking the runtime.
+    /// Only one page is in memory at a time. The stream ends after the first error
+    pub fn into_stream(self) -> impl Stream<Item = Result<AudioTableRow, String>> {
+        stream::unfold(Some(self), |paginator| async move {
+            let mut paginator = paginator?;
+            let (paginator, page) = tokio::task::spawn_blocking(move || {
+                let page = paginator.next_page();
+                (paginator, page)
             })
-            .collect())
+            .await
+            .map_err(|err| log::error!("AudioTablePaginator page task failed - {err}"))
+            .ok()?;
+
+            match page {
+                Ok(rows) if rows.is_empty() => None,
+                Ok(rows) => {
+                    let rows: Vec<_> = rows.into_iter().map(Ok).collect();
+                    Some((stream::iter(rows), Some(paginator)))
+                }
+                Err(err) => Some((stream::iter(vec![Err(err)]), None)),
+            }
+        })
+        .flatten()
     }
 }
 
@@ -56,6 +137,11 @@ pub struct AudioTablePaginatorBuilder {
     conn: DbConnection,
     order_by: AudioTableOrderBy,
     page_limit: u64,
+    start_page: u64,
+    tag: Option<String>,
+    category: Option<CategoryFilter>,
+    include_archived: bool,
+    ranked_ids: Vec<i64>,
 }
 
 impl AudioTablePaginatorBuilder {
@@ -64,6 +150,11 @@ impl AudioTablePaginatorBuilder {
             conn: conn,
             order_by: AudioTableOrderBy::Id,
             page_limit: 500,
+            start_page: 0,
+            tag: None,
+            category: None,
+            include_archived: false,
+            ranked_ids: vec![],
         }
     }
 
@@ -78,12 +169,46 @@ impl AudioTablePaginatorBuilder {
         self
     }
 
+    /// Zero based page to start paginating from
+    pub fn start_page(mut self, value: u64) -> Self {
+        self.start_page = value;
+        self
+    }
+
+    /// Only paginate audio rows carrying tag
+    pub fn tag(mut self, value: Option<impl AsRef<str>>) -> Self {
+        self.tag = value.map(Tags::normalize_tag);
+        self
+    }
+
+    /// Only paginate audio rows of a soundboard section
+    pub fn category(mut self, value: Option<CategoryFilter>) -> Self {
+        self.category = value;
+        self
+    }
+
+    /// Also paginate archived audio rows, which are left out by default
+    pub fn include_archived(mut self, value: bool) -> Self {
+        self.include_archived = value;
+        self
+    }
+
+    /// Paginate audio rows with these ids first, in order, then the others in the regular order
+    pub fn ranked_first(mut self, ids: Vec<i64>) -> Self {
+        self.ranked_ids = ids;
+        self
+    }
+
     pub fn build(self) -> AudioTablePaginator {
         AudioTablePaginator {
             conn: self.conn,
             order_by: self.order_by,
             page_limit: self.page_limit,
-            offset: 0,
+            offset: self.start_page * self.page_limit,
+            tag: self.tag,
+            category: self.category,
+            include_archived: self.include_archived,
+            ranked_ids: self.ranked_ids,
         }
     }
 }
@@ -113,44 +238,20 @@ impl Iterator for AudioTablePaginator {
 
 #[cfg(test)]
 mod tests {
-    use r2d2_sqlite::SqliteConnectionManager;
-
-    use crate::{
-        audio::AudioFile,
-        db::{audio_table::AudioTableRowInsert, Table},
-        helpers::{self, uuid_v4_str},
-    };
+    use crate::test_utils::{AudioRowFixture, TestDb};
 
     use super::*;
 
-    fn make_audio_table_row_insert() -> AudioTableRowInsert {
-        AudioTableRowInsert {
-            audio_file: AudioFile::new(
-                std::path::Path::new(&format!("/tmp/{}.mp3", helpers::uuid_v4_str())).to_path_buf(),
-            ),
-            author_global_name: None,
-            name: format!("{}{}", uuid_v4_str(), "#!@#$%^&*()_-+=?/.\"\\'"),
-            tags: uuid_v4_str().into(),
-            created_at: chrono::Utc::now(),
-            author_id: None,
-            author_name: None,
-        }
-    }
-
     #[test]
     fn audio_table_pagination_test() {
-        let db_manager = SqliteConnectionManager::memory();
-        let db_pool = r2d2::Pool::new(db_manager).unwrap();
-        let table = AudioTable::new(db_pool.get().unwrap());
-        table.create_table();
+        let db = TestDb::new();
+        let table = db.audio_table();
 
         for _ in 0..3 {
-            table
-                .insert_audio_row(make_audio_table_row_insert())
-                .unwrap();
+            AudioRowFixture::new().insert(&table);
         }
 
-        let mut paginator = AudioTablePaginator::builder(db_pool.get().unwrap())
+        let mut paginator = AudioTablePaginator::builder(db.connection())
             .page_limit(2)
             .build();
 
@@ -163,4 +264,167 @@ mod tests {
         let page = paginator.next();
         assert!(page.is_none());
     }
+
+    #[test]
+    fn audio_table_pagination_skips_unreadable_rows_test() {
+        let db = TestDb::new();
+        let table = db.audio_table();
+        let ids: Vec<_> = (0..5)
+            .map(|_| AudioRowFixture::new().insert(&table).id)
+            .collect();
+
+        // audio files outside the storage roots, e.g. of a tampered db
+        db.connection()
+            .execute(
+                "UPDATE audio SET audio_file = '/etc/' || id WHERE id IN (?1, ?2)",
+                rusqlite::params![ids[2], ids[3]],
+            )
+            .unwrap();
+
+        let paged: Vec<_> = AudioTablePaginator::builder(db.connection())
+            .page_limit(2)
+            .build()
+            .flat_map(|page| page.unwrap())
+            .map(|row| row.id)
+            .collect();
+        assert_eq!(vec![ids[0], ids[1], ids[4]], paged);
+    }
+
+    #[tokio::test]
+    async fn audio_table_pagination_stream_test() {
+        let db = TestDb::new();
+        let table = db.audio_table();
+        let ids: Vec<_> = (0..5)
+            .map(|_| AudioRowFixture::new().insert(&table).id)
+            .collect();
+
+        let streamed: Vec<_> = AudioTablePaginator::builder(db.connection())
+            .page_limit(2)
+            .build()
+            .into_stream()
+            .map(|row| row.unwrap().id)
+            .collect()
+            .await;
+        assert_eq!(ids, streamed);
+
+        let streamed = AudioTablePaginator::builder(db.connection())
+            .page_limit(2)
+            .start_page(3)
+            .build()
+            .into_stream()
+            .count()
+            .await;
+        assert_eq!(0, streamed);
+    }
+
+    #[test]
+    fn audio_table_pagination_start_page_test() {
+        let db = TestDb::new();
+        let table = db.audio_table();
+
+        for _ in 0..3 {
+            AudioRowFixture::new().insert(&table);
+        }
+
+        let mut paginator = AudioTablePaginator::builder(db.connection())
+            .page_limit(2)
+            .start_page(1)
+            .build();
+
+        let page = paginator.next().unwrap().unwrap();
+        assert_eq!(page.len(), 1);
+        assert!(paginator.next().is_none());
+    }
+
+    #[test]
+    fn audio_table_pagination_tag_test() {
+        let db = TestDb::new();
+        let table = db.audio_table();
+
+        AudioRowFixture::new().tags("Droid r2d2").insert(&table);
+        AudioRowFixture::new().tags("droid").insert(&table);
+        AudioRowFixture::new().tags("jedi").insert(&table);
+
+        let mut paginator = AudioTablePaginator::builder(db.connection())
+            .tag(Some("DROID"))
+            .build();
+
+        let page = paginator.next().unwrap().unwrap();
+        assert_eq!(page.len(), 2);
+        assert!(paginator.next().is_none());
+    }
+
+    #[test]
+    fn audio_table_pagination_category_test() {
+        let db = TestDb::new();
+        let table = db.audio_table();
+        let category_id = db
+            .categories_table()
+            .insert_category("Memes", None, 0)
+            .unwrap();
+
+        let categorized = AudioRowFixture::new().insert(&table);
+        table
+            .set_category(categorized.id, Some(category_id))
+            .unwrap();
+        AudioRowFixture::new().insert(&table);
+        AudioRowFixture::new().insert(&table);
+
+        let page = |category| {
+            AudioTablePaginator::builder(db.connection())
+                .category(category)
+                .build()
+                .next_page()
+                .unwrap()
+        };
+        assert_eq!(3, page(None).len());
+        assert_eq!(
+            vec![categorized.id],
+            page(Some(CategoryFilter::Category(category_id)))
+                .iter()
+                .map(|row| row.id)
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(2, page(Some(CategoryFilter::Uncategorized)).len());
+    }
+
+    #[test]
+    fn audio_table_pagination_ranked_first_test() {
+        let db = TestDb::new();
+        let table = db.audio_table();
+        let ids: Vec<_> = (0..4)
+            .map(|_| AudioRowFixture::new().insert(&table).id)
+            .collect();
+
+        let rows = AudioTablePaginator::builder(db.connection())
+            .ranked_first(vec![ids[2], ids[0]])
+            .build()
+            .next_page()
+            .unwrap();
+        assert_eq!(
+            vec![ids[2], ids[0], ids[1], ids[3]],
+            rows.iter().map(|row| row.id).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn audio_table_pagination_archived_test() {
+        let db = TestDb::new();
+        let table = db.audio_table();
+
+        AudioRowFixture::new().insert(&table);
+        let archived = AudioRowFixture::new().insert(&table);
+        table.set_archived(archived.id, true).unwrap();
+
+        let mut paginator = AudioTablePaginator::builder(db.connection()).build();
+        let page = paginator.next().unwrap().unwrap();
+        assert_eq!(page.len(), 1);
+        assert!(page.iter().all(|row| row.id != archived.id));
+
+        let mut paginator = AudioTablePaginator::builder(db.connection())
+            .include_archived(true)
+            .build();
+        let page = paginator.next().unwrap().unwrap();
+        assert_eq!(page.len(), 2);
+    }
 }
@@ -0,0 +1,244 @@
+use rusqlite::{params, OptionalExtension};
+
+use crate::{audio, commands::PoiseError, common::LogResult};
+
+use super::{AudioTableRowInsert, DbConnection, SoundName, Table, Tags};
+
+/// Sound added by a member of a guild requiring approval (see `approval`), kept out of the
+/// audio table until a moderator approves it. Its audio file is already in the audio dir
+pub struct PendingSoundRow {
+    pub id: i64,
+    pub name: SoundName,
+    pub tags: Tags,
+    pub audio_file: audio::AudioFile,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub author_id: Option<u64>,
+    pub author_name: Option<String>,
+    pub author_global_name: Option<String>,
+    pub author_hash: Option<String>,
+    pub guild_id: Option<u64>,
+    pub duration_ms: Option<u64>,
+    pub file_size: Option<u64>,
+    pub source_url: Option<String>,
+}
+
+impl PendingSoundRow {
+    /// Audio row added once the sound is approved
+    pub fn into_audio_row_insert(self) -> AudioTableRowInsert {
+        AudioTableRowInsert {
+            name: self.name,
+            tags: self.tags,
+            audio_file: self.audio_file,
+            created_at: self.created_at,
+            author_id: self.author_id,
+            author_name: self.author_name,
+            author_global_name: self.author_global_name,
+            author_hash: self.author_hash,
+            guild_id: self.guild_id,
+            duration_ms: self.duration_ms,
+            file_size: self.file_size,
+            source_url: self.source_url,
+        }
+    }
+}
+
+impl TryFrom<&rusqlite::Row<'_>> for PendingSoundRow {
+    type Error = rusqlite::Error;
+
+    fn try_from(row: &rusqlite::Row<'_>) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: row.get("id")?,
+            name: row.get("name")?,
+            tags: row.get("tags")?,
+            audio_file: row.get("audio_file")?,
+            created_at: row.get("created_at")?,
+            author_id: row.get("author_id")?,
+            author_name: row.get("author_name")?,
+            author_global_name: row.get("author_global_name")?,
+            author_hash: row.get("author_hash")?,
+            guild_id: row.get("guild_id")?,
+            duration_ms: row.get("duration_ms")?,
+            file_size: row.get("file_size")?,
+            source_url: row.get("source_url")?,
+        })
+    }
+}
+
+pub struct PendingSoundsTable {
+    conn: DbConnection,
+}
+
+impl PendingSoundsTable {
+    pub const TABLE_NAME: &'static str = "pending_sounds";
+
+    pub fn new(connection: DbConnection) -> Self {
+        Self { conn: connection }
+    }
+
+    /// Add sound awaiting approval. Returns its pending sound id
+    pub fn insert_pending_sound(&self, sound: &AudioTableRowInsert) -> Result<i64, PoiseError> {
+        log::info!(
+            "Inserting pending sound. Name: {}, File: {}",
+            sound.name,
+            sound.audio_file.to_string_lossy()
+        );
+
+        let table_name = Self::TABLE_NAME;
+        let sql = format!(
+            "
+            INSERT INTO {table_name}
+                (name, tags, audio_file, created_at, author_id, author_name, author_global_name, guild_id, duration_ms, file_size, author_hash, source_url)
+            VALUES
+                (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)"
+        );
+
+        self.conn
+            .execute(
+                sql.as_str(),
+                params![
+                    &sound.name,
+                    &sound.tags,
+                    &sound.audio_file,
+                    &sound.created_at,
+                    &sound.author_id,
+                    &sound.author_name,
+                    &sound.author_global_name,
+                    &sound.guild_id,
+                    &sound.duration_ms,
+                    &sound.file_size,
+                    &sound.author_hash,
+                    &sound.source_url
+                ],
+            )
+            .log_err_msg("Failed to insert pending sound")?;
+
+        Ok(self.conn.last_insert_rowid())
+    }
+
+    pub fn find_pending_sound(&self, id: i64) -> Result<Option<PendingSoundRow>, PoiseError> {
+        let table_name = Self::TABLE_NAME;
+        let sql = format!("SELECT * FROM {table_name} WHERE id = ?1");
+
+        let row = self
+            .conn
+            .query_row(sql.as_str(), params![&id], |row| {
+                PendingSoundRow::try_from(row)
+            })
+            .optional()
+            .log_err_msg("Failed to find pending sound")?;
+
+        Ok(row)
+    }
+
+    /// Audio files of all pending sounds, which audio dir syncs must not add as sounds
+    pub fn pending_audio_files(&self) -> Result<Vec<audio::AudioFile>, PoiseError> {
+        let table_name = Self::TABLE_NAME;
+        let sql = format!("SELECT audio_file FROM {table_name}");
+
+        let mut stmt = self
+            .conn
+            .prepare(sql.as_str())
+            .log_err_msg("Failed to prepare pending audio files query")?;
+        let rows = stmt
+            .query_map((), |row| row.get(0))
+            .log_err_msg("Failed to query pending audio files")?;
+
+        Ok(rows.filter_map(|row| row.log_err().ok()).collect())
+    }
+
+    /// Delete pending sound row. Its audio file is left alone
+    pub fn delete_pending_sound(&self, id: i64) -> Result<(), PoiseError> {
+        log::info!("Deleting pending sound. Id: {id}");
+
+        let table_name = Self::TABLE_NAME;
+        let sql = format!("DELETE FROM {table_name} WHERE id = ?1");
+
+        self.conn
+            .execute(sql.as_str(), params![&id])
+            .log_err_msg("Failed to delete pending sound")?;
+
+        Ok(())
+    }
+}
+
+impl Table for PendingSoundsTable {
+    fn connection(&self) -> &DbConnection {
+        &self.conn
+    }
+
+    fn create_table(&self) {
+        let table_name = Self::TABLE_NAME;
+
+        log::info!("Creating table: {table_name}");
+        let sql = format!(
+            "
+            CREATE TABLE IF NOT EXISTS {table_name} (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                name VARCHAR(80) NOT NULL,
+                tags VARCHAR(1024),
+                audio_file VARCHAR(500) NOT NULL UNIQUE,
+                created_at VARCHAR(40) NOT NULL,
+                author_id INTEGER,
+                author_name VARCHAR(256),
+                author_global_name VARCHAR(256),
+                guild_id INTEGER,
+                duration_ms INTEGER,
+                file_size INTEGER,
+                author_hash VARCHAR(64),
+                source_url VARCHAR(2048)
+            );"
+        );
+
+        self.conn
+            .execute_batch(sql.as_str())
+            .log_err_msg("Failed create table")
+            .log_ok_msg(format!("Created table {table_name}"))
+            .unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::{AudioRowFixture, TestDb};
+
+    use super::*;
+
+    #[test]
+    fn table_create_test() {
+        let table = TestDb::new().pending_sounds_table();
+        table.create_table();
+    }
+
+    #[test]
+    fn pending_sounds_test() {
+        let table = TestDb::new().pending_sounds_table();
+        let sound = AudioRowFixture::new()
+            .name("Beep")
+            .author_id(1)
+            .guild_id(2)
+            .build();
+        let audio_file = sound.audio_file.to_path_buf();
+
+        let id = table.insert_pending_sound(&sound).unwrap();
+        let row = table.find_pending_sound(id).unwrap().unwrap();
+        assert_eq!("Beep", row.name.as_str());
+        assert_eq!(Some(1), row.author_id);
+        assert_eq!(Some(2), row.guild_id);
+
+        assert_eq!(
+            vec![audio_file.clone()],
+            table
+                .pending_audio_files()
+                .unwrap()
+                .iter()
+                .map(|audio_file| audio_file.to_path_buf())
+                .collect::<Vec<_>>()
+        );
+
+        let insert = row.into_audio_row_insert();
+        assert_eq!(audio_file, insert.audio_file.to_path_buf());
+
+        table.delete_pending_sound(id).unwrap();
+        assert!(table.find_pending_sound(id).unwrap().is_none());
+    }
+}
@@ -0,0 +1,127 @@
+use rusqlite::params;
+
+use crate::{commands::PoiseError, common::LogResult};
+
+use super::{DbConnection, Table};
+
+/// Per-guild mapping of bot permissions (see `crate::permissions::Permission`) to roles
+pub struct PermissionsTable {
+    conn: DbConnection,
+}
+
+impl PermissionsTable {
+    pub const TABLE_NAME: &'static str = "permissions";
+
+    pub fn new(connection: DbConnection) -> Self {
+        Self { conn: connection }
+    }
+
+    /// Roles granted permission in guild
+    pub fn role_ids(&self, guild_id: u64, permission: &str) -> Result<Vec<u64>, PoiseError> {
+        let table_name = Self::TABLE_NAME;
+        let sql = format!(
+            "SELECT role_id FROM {table_name} WHERE guild_id = ?1 AND permission = ?2 ORDER BY role_id"
+        );
+
+        let mut stmt = self
+            .conn
+            .prepare(sql.as_str())
+            .log_err_msg("Role ids sql invalid")?;
+
+        let rows = stmt
+            .query_map(params![&guild_id, &permission], |row| row.get("role_id"))
+            .log_err_msg("Role ids sql query error")?;
+
+        Ok(rows.filter_map(|row| row.log_err().ok()).collect())
+    }
+
+    pub fn grant(&self, guild_id: u64, permission: &str, role_id: u64) -> Result<(), PoiseError> {
+        log::info!("Granting permission. Guild Id: {guild_id}, Permission: {permission}, Role Id: {role_id}");
+
+        let table_name = Self::TABLE_NAME;
+        let sql = format!(
+            "
+            INSERT OR IGNORE INTO {table_name}
+                (guild_id, permission, role_id)
+            VALUES
+                (?1, ?2, ?3)"
+        );
+
+        self.conn
+            .execute(sql.as_str(), params![&guild_id, &permission, &role_id])
+            .log_err_msg("Failed to grant permission")?;
+
+        Ok(())
+    }
+
+    pub fn revoke(&self, guild_id: u64, permission: &str, role_id: u64) -> Result<(), PoiseError> {
+        log::info!("Revoking permission. Guild Id: {guild_id}, Permission: {permission}, Role Id: {role_id}");
+
+        let table_name = Self::TABLE_NAME;
+        let sql = format!(
+            "DELETE FROM {table_name} WHERE guild_id = ?1 AND permission = ?2 AND role_id = ?3"
+        );
+
+        self.conn
+            .execute(sql.as_str(), params![&guild_id, &permission, &role_id])
+            .log_err_msg("Failed to revoke permission")?;
+
+        Ok(())
+    }
+}
+
+impl Table for PermissionsTable {
+    fn connection(&self) -> &DbConnection {
+        &self.conn
+    }
+
+    fn create_table(&self) {
+        let table_name = Self::TABLE_NAME;
+
+        log::info!("Creating table: {table_name}");
+        let sql = format!(
+            "
+            CREATE TABLE IF NOT EXISTS {table_name} (
+                guild_id INTEGER NOT NULL,
+                permission VARCHAR(40) NOT NULL,
+                role_id INTEGER NOT NULL,
+                PRIMARY KEY (guild_id, permission, role_id)
+            );"
+        );
+
+        self.conn
+            .execute_batch(sql.as_str())
+            .log_err_msg("Failed create table")
+            .log_ok_msg(format!("Created table {table_name}"))
+            .unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::TestDb;
+
+    use super::*;
+
+    #[test]
+    fn table_create_test() {
+        let table = TestDb::new().permissions_table();
+        table.create_table();
+    }
+
+    #[test]
+    fn grant_revoke_test() {
+        let table = TestDb::new().permissions_table();
+
+        table.grant(1, "add_sounds", 20).unwrap();
+        table.grant(1, "add_sounds", 10).unwrap();
+        table.grant(1, "add_sounds", 10).unwrap();
+        table.grant(2, "add_sounds", 30).unwrap();
+
+        assert_eq!(vec![10, 20], table.role_ids(1, "add_sounds").unwrap());
+        assert!(table.role_ids(1, "delete_sounds").unwrap().is_empty());
+
+        table.revoke(1, "add_sounds", 20).unwrap();
+        assert_eq!(vec![10], table.role_ids(1, "add_sounds").unwrap());
+    }
+}
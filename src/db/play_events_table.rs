@@ -0,0 +1,639 @@
+use chrono::{Datelike, NaiveDate};
+use rusqlite::params;
+
+use crate::{commands::PoiseError, common::LogResult};
+
+use super::{AudioTable, AudioTableRow, DbConnection, Table};
+
+/// Time bucket of play histograms
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, poise::ChoiceParameter)]
+pub enum HistogramBucket {
+    #[default]
+    #[name = "Daily"]
+    Day,
+    #[name = "Weekly"]
+    Week,
+}
+
+impl HistogramBucket {
+    /// SQL expression of the bucket (start date) a play event (or rollup) timestamp column
+    /// falls in
+    fn sql_expr(&self, column: &str) -> String {
+        match self {
+            Self::Day => format!("date({column})"),
+            // weeks start on monday
+            Self::Week => format!("date({column}, 'weekday 0', '-6 days')"),
+        }
+    }
+
+    /// Start date of the bucket `date` falls in
+    pub fn start_of(&self, date: NaiveDate) -> NaiveDate {
+        match self {
+            Self::Day => date,
+            Self::Week => {
+                date - chrono::Duration::days(date.weekday().num_days_from_monday() as i64)
+            }
+        }
+    }
+
+    pub fn duration(&self) -> chrono::Duration {
+        match self {
+            Self::Day => chrono::Duration::days(1),
+            Self::Week => chrono::Duration::weeks(1),
+        }
+    }
+}
+
+/// Play event recorded for every play, for time based play statistics
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlayEventInsert {
+    pub audio_id: i64,
+    pub user_id: u64,
+    pub guild_id: Option<u64>,
+    /// See `crate::playback::PlaySource`
+    pub source: String,
+}
+
+/// Filter of play histograms. None fields match every play event
+#[derive(Debug, Clone, Default)]
+pub struct PlayEventsFilter {
+    pub audio_id: Option<i64>,
+    pub guild_id: Option<u64>,
+}
+
+/// Every play of every sound, with who played it where and when. Unlike the play counts of
+/// the audio and user plays tables, plays are timestamped individually. Play events older than
+/// the retention window are rolled up into daily play counts per sound and guild (see
+/// `PlayEventsTable::rollup_play_events`)
+pub struct PlayEventsTable {
+    conn: DbConnection,
+}
+
+impl PlayEventsTable {
+    pub const TABLE_NAME: &'static str = "play_events";
+    pub const ROLLUPS_TABLE_NAME: &'static str = "play_event_rollups";
+
+    pub fn new(connection: DbConnection) -> Self {
+        Self { conn: connection }
+    }
+
+    pub fn insert_play_event(&self, event: &PlayEventInsert) -> Result<(), PoiseError> {
+        let table_name = Self::TABLE_NAME;
+        let sql = format!(
+            "
+            INSERT INTO {table_name}
+                (audio_id, user_id, guild_id, source, played_at)
+            VALUES
+                (?1, ?2, ?3, ?4, ?5)"
+        );
+
+        self.conn
+            .execute(
+                sql.as_str(),
+                params![
+                    &event.audio_id,
+                    &event.user_id,
+                    &event.guild_id,
+                    &event.source,
+                    &chrono::Utc::now()
+                ],
+            )
+            .log_err_msg("Failed to insert play event")?;
+
+        Ok(())
+    }
+
+    /// Play counts per bucket of play events (and rollups) since `since`, ordered by bucket
+    /// start date. Buckets without plays are left out
+    pub fn play_histogram(
+        &self,
+        bucket: HistogramBucket,
+        since: chrono::DateTime<chrono::Utc>,
+        filter: &PlayEventsFilter,
+    ) -> Result<Vec<(NaiveDate, i64)>, PoiseError> {
+        let table_name = Self::TABLE_NAME;
+        let rollups_table_name = Self::ROLLUPS_TABLE_NAME;
+        let bucket_expr = bucket.sql_expr("played_at");
+        let rollup_bucket_expr = bucket.sql_expr("day");
+        let sql = format!(
+            "
+            SELECT bucket, SUM(plays) AS plays FROM (
+                SELECT {bucket_expr} AS bucket, COUNT(*) AS plays FROM {table_name}
+                WHERE played_at >= ?1
+                    AND (?2 IS NULL OR audio_id = ?2)
+                    AND (?3 IS NULL OR guild_id = ?3)
+                GROUP BY bucket
+                UNION ALL
+                SELECT {rollup_bucket_expr} AS bucket, SUM(plays) AS plays FROM {rollups_table_name}
+                WHERE day >= date(?1)
+                    AND (?2 IS NULL OR audio_id = ?2)
+                    AND (?3 IS NULL OR guild_id = ?3)
+                GROUP BY bucket
+            )
+            GROUP BY bucket
+            ORDER BY bucket"
+        );
+
+        let mut stmt = self
+            .conn
+            .prepare(sql.as_str())
+            .log_err_msg("Play histogram sql invalid")?;
+
+        let rows = stmt
+            .query_map(params![&since, &filter.audio_id, &filter.guild_id], |row| {
+                Ok((row.get("bucket")?, row.get("plays")?))
+            })
+            .log_err_msg("Play histogram sql query error")?;
+
+        Ok(rows.filter_map(|row| row.log_err().ok()).collect())
+    }
+
+    /// Names and play counts of the `limit` most played sounds since `since`, counting play
+    /// events and rollups, most played first
+    pub fn most_played(
+        &self,
+        since: chrono::DateTime<chrono::Utc>,
+        filter: &PlayEventsFilter,
+        limit: u64,
+    ) -> Result<Vec<(String, i64)>, PoiseError> {
+        let table_name = Self::TABLE_NAME;
+        let rollups_table_name = Self::ROLLUPS_TABLE_NAME;
+        let audio_table_name = AudioTable::TABLE_NAME;
+        let sql = format!(
+            "
+            SELECT a.name AS name, SUM(p.plays) AS plays FROM (
+                SELECT audio_id, COUNT(*) AS plays FROM {table_name}
+                WHERE played_at >= ?1
+                    AND (?2 IS NULL OR audio_id = ?2)
+                    AND (?3 IS NULL OR guild_id = ?3)
+                GROUP BY audio_id
+                UNION ALL
+                SELECT audio_id, SUM(plays) AS plays FROM {rollups_table_name}
+                WHERE day >= date(?1)
+                    AND (?2 IS NULL OR audio_id = ?2)
+                    AND (?3 IS NULL OR guild_id = ?3)
+                GROUP BY audio_id
+            ) p
+            INNER JOIN {audio_table_name} a ON a.id = p.audio_id
+            GROUP BY p.audio_id
+            ORDER BY plays DESC, a.name
+            LIMIT ?4"
+        );
+
+        let mut stmt = self
+            .conn
+            .prepare(sql.as_str())
+            .log_err_msg("Most played sql invalid")?;
+
+        let rows = stmt
+            .query_map(
+                params![&since, &filter.audio_id, &filter.guild_id, &limit],
+                |row| Ok((row.get("name")?, row.get("plays")?)),
+            )
+            .log_err_msg("Most played sql query error")?;
+
+        Ok(rows.filter_map(|row| row.log_err().ok()).collect())
+    }
+
+    /// Sounds user played most recently, with when they were played, latest first. Only play
+    /// events are listed, as rollups don't record who played
+    pub fn user_play_history(
+        &self,
+        user_id: u64,
+        guild_id: Option<u64>,
+        limit: u64,
+    ) -> Result<Vec<(AudioTableRow, chrono::DateTime<chrono::Utc>)>, PoiseError> {
+        let table_name = Self::TABLE_NAME;
+        let audio_table_name = AudioTable::TABLE_NAME;
+        let sql = format!(
+            "
+            SELECT a.*, e.played_at AS event_played_at FROM {table_name} e
+            INNER JOIN {audio_table_name} a ON a.id = e.audio_id
+            WHERE e.user_id = ?1 AND (?2 IS NULL OR e.guild_id = ?2)
+            ORDER BY e.id DESC
+            LIMIT ?3"
+        );
+
+        let mut stmt = self
+            .conn
+            .prepare(sql.as_str())
+            .log_err_msg("User play history sql invalid")?;
+
+        let rows = stmt
+            .query_map(params![&user_id, &guild_id, &limit], |row| {
+                Ok((AudioTableRow::try_from(row)?, row.get("event_played_at")?))
+            })
+            .log_err_msg("User play history sql query error")?;
+
+        Ok(rows.filter_map(|row| row.log_err().ok()).collect())
+    }
+
+    /// Play counts per sound and day of play events (and rollups) since `since`, as
+    /// `(audio_id, day, plays)`, ordered by sound then day
+    pub fn daily_plays(
+        &self,
+        since: chrono::DateTime<chrono::Utc>,
+        filter: &PlayEventsFilter,
+    ) -> Result<Vec<(i64, NaiveDate, i64)>, PoiseError> {
+        let table_name = Self::TABLE_NAME;
+        let rollups_table_name = Self::ROLLUPS_TABLE_NAME;
+        let sql = format!(
+            "
+            SELECT audio_id, day, SUM(plays) AS plays FROM (
+                SELECT audio_id, date(played_at) AS day, COUNT(*) AS plays FROM {table_name}
+                WHERE played_at >= ?1
+                    AND (?2 IS NULL OR audio_id = ?2)
+                    AND (?3 IS NULL OR guild_id = ?3)
+                GROUP BY audio_id, day
+                UNION ALL
+                SELECT audio_id, day, SUM(plays) AS plays FROM {rollups_table_name}
+                WHERE day >= date(?1)
+                    AND (?2 IS NULL OR audio_id = ?2)
+                    AND (?3 IS NULL OR guild_id = ?3)
+                GROUP BY audio_id, day
+            )
+            GROUP BY audio_id, day
+            ORDER BY audio_id, day"
+        );
+
+        let mut stmt = self
+            .conn
+            .prepare(sql.as_str())
+            .log_err_msg("Daily plays sql invalid")?;
+
+        let rows = stmt
+            .query_map(params![&since, &filter.audio_id, &filter.guild_id], |row| {
+                Ok((row.get("audio_id")?, row.get("day")?, row.get("plays")?))
+            })
+            .log_err_msg("Daily plays sql query error")?;
+
+        Ok(rows.filter_map(|row| row.log_err().ok()).collect())
+    }
+
+    /// Play counts per day of week (0 is monday) and hour of day (UTC) of play events since
+    /// `since`, as `(weekday, hour, plays)`. Rolled up plays aren't counted, as their time of day
+    /// is gone. Hours without plays are left out
+    pub fn play_heatmap(
+        &self,
+        since: chrono::DateTime<chrono::Utc>,
+        filter: &PlayEventsFilter,
+    ) -> Result<Vec<(u32, u32, i64)>, PoiseError> {
+        let table_name = Self::TABLE_NAME;
+        let sql = format!(
+            "
+            SELECT
+                (CAST(strftime('%w', played_at) AS INTEGER) + 6) % 7 AS weekday,
+                CAST(strftime('%H', played_at) AS INTEGER) AS hour,
+                COUNT(*) AS plays
+            FROM {table_name}
+            WHERE played_at >= ?1
+                AND (?2 IS NULL OR audio_id = ?2)
+                AND (?3 IS NULL OR guild_id = ?3)
+            GROUP BY weekday, hour
+            ORDER BY weekday, hour"
+        );
+
+        let mut stmt = self
+            .conn
+            .prepare(sql.as_str())
+            .log_err_msg("Play heatmap sql invalid")?;
+
+        let rows = stmt
+            .query_map(params![&since, &filter.audio_id, &filter.guild_id], |row| {
+                Ok((row.get("weekday")?, row.get("hour")?, row.get("plays")?))
+            })
+            .log_err_msg("Play heatmap sql query error")?;
+
+        Ok(rows.filter_map(|row| row.log_err().ok()).collect())
+    }
+
+    /// Roll up play events played before `before` into daily play counts per sound and guild,
+    /// and delete them, in a single transaction. Who played is dropped. `before` should be a
+    /// start of day, so days aren't rolled up in parts. Returns number of play events rolled up
+    pub fn rollup_play_events(
+        &self,
+        before: chrono::DateTime<chrono::Utc>,
+    ) -> Result<usize, PoiseError> {
+        let table_name = Self::TABLE_NAME;
+        let rollups_table_name = Self::ROLLUPS_TABLE_NAME;
+
+        let tx = self
+            .conn
+            .unchecked_transaction()
+            .log_err_msg("Failed to start play events rollup transaction")?;
+
+        let sql = format!(
+            "
+            INSERT INTO {rollups_table_name}
+                (day, audio_id, guild_id, plays)
+            SELECT date(played_at), audio_id, guild_id, COUNT(*) FROM {table_name}
+            WHERE played_at < ?1
+            GROUP BY date(played_at), audio_id, guild_id"
+        );
+        tx.execute(sql.as_str(), params![&before])
+            .log_err_msg("Failed to roll up play events")?;
+
+        let sql = format!("DELETE FROM {table_name} WHERE played_at < ?1");
+        let rolled_up = tx
+            .execute(sql.as_str(), params![&before])
+            .log_err_msg("Failed to delete rolled up play events")?;
+
+        tx.commit()
+            .log_err_msg("Failed to commit play events rollup transaction")?;
+
+        Ok(rolled_up)
+    }
+}
+
+impl Table for PlayEventsTable {
+    fn connection(&self) -> &DbConnection {
+        &self.conn
+    }
+
+    fn create_table(&self) {
+        let table_name = Self::TABLE_NAME;
+        let rollups_table_name = Self::ROLLUPS_TABLE_NAME;
+        let audio_table_name = AudioTable::TABLE_NAME;
+
+        log::info!("Creating table: {table_name}");
+        let sql = format!(
+            "
+            BEGIN;
+                CREATE TABLE IF NOT EXISTS {table_name} (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    audio_id INTEGER NOT NULL,
+                    user_id INTEGER NOT NULL,
+                    guild_id INTEGER,
+                    source VARCHAR(10) NOT NULL,
+                    played_at VARCHAR(40) NOT NULL
+                );
+
+                CREATE INDEX IF NOT EXISTS {table_name}_played_at_idx ON {table_name}(played_at);
+                CREATE INDEX IF NOT EXISTS {table_name}_audio_id_idx ON {table_name}(audio_id);
+
+                CREATE TABLE IF NOT EXISTS {rollups_table_name} (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    day VARCHAR(10) NOT NULL,
+                    audio_id INTEGER NOT NULL,
+                    guild_id INTEGER,
+                    plays INTEGER NOT NULL
+                );
+
+                CREATE INDEX IF NOT EXISTS {rollups_table_name}_day_idx ON {rollups_table_name}(day);
+
+                CREATE TRIGGER IF NOT EXISTS {audio_table_name}_delete_{table_name} AFTER DELETE ON {audio_table_name} BEGIN
+                    DELETE FROM {table_name} WHERE audio_id = old.id;
+                END;
+
+                CREATE TRIGGER IF NOT EXISTS {audio_table_name}_delete_{rollups_table_name} AFTER DELETE ON {audio_table_name} BEGIN
+                    DELETE FROM {rollups_table_name} WHERE audio_id = old.id;
+                END;
+            COMMIT;"
+        );
+
+        self.conn
+            .execute_batch(sql.as_str())
+            .log_err_msg("Failed create table")
+            .log_ok_msg(format!("Created table {table_name}"))
+            .unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::db::UniqueAudioTableCol;
+    use crate::test_utils::{AudioRowFixture, TestDb};
+
+    use super::*;
+
+    fn play_event(audio_id: i64, guild_id: u64) -> PlayEventInsert {
+        PlayEventInsert {
+            audio_id,
+            user_id: 1,
+            guild_id: Some(guild_id),
+            source: "slash".into(),
+        }
+    }
+
+    #[test]
+    fn table_create_test() {
+        let table = TestDb::new().play_events_table();
+        table.create_table();
+    }
+
+    #[test]
+    fn bucket_start_of_test() {
+        // 2024-01-03 is a wednesday
+        let date = NaiveDate::from_ymd_opt(2024, 1, 3).unwrap();
+        assert_eq!(date, HistogramBucket::Day.start_of(date));
+        assert_eq!(
+            NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
+            HistogramBucket::Week.start_of(date)
+        );
+    }
+
+    #[test]
+    fn play_histogram_test() {
+        let db = TestDb::new();
+        let table = db.play_events_table();
+        let audio_table = db.audio_table();
+        let beep = AudioRowFixture::new().insert(&audio_table);
+        let boop = AudioRowFixture::new().insert(&audio_table);
+
+        table.insert_play_event(&play_event(beep.id, 1)).unwrap();
+        table.insert_play_event(&play_event(beep.id, 1)).unwrap();
+        table.insert_play_event(&play_event(boop.id, 2)).unwrap();
+
+        let now = chrono::Utc::now();
+        let since = now - chrono::Duration::days(1);
+        let today = now.date_naive();
+
+        let histogram = table
+            .play_histogram(HistogramBucket::Day, since, &PlayEventsFilter::default())
+            .unwrap();
+        assert_eq!(vec![(today, 3)], histogram);
+
+        let filter = PlayEventsFilter {
+            guild_id: Some(1),
+            ..Default::default()
+        };
+        let histogram = table
+            .play_histogram(HistogramBucket::Week, since, &filter)
+            .unwrap();
+        assert_eq!(vec![(HistogramBucket::Week.start_of(today), 2)], histogram);
+
+        let filter = PlayEventsFilter {
+            audio_id: Some(boop.id),
+            ..Default::default()
+        };
+        let histogram = table
+            .play_histogram(HistogramBucket::Day, since, &filter)
+            .unwrap();
+        assert_eq!(vec![(today, 1)], histogram);
+
+        // play events are only counted since `since`
+        let histogram = table
+            .play_histogram(
+                HistogramBucket::Day,
+                now + chrono::Duration::days(1),
+                &PlayEventsFilter::default(),
+            )
+            .unwrap();
+        assert!(histogram.is_empty());
+
+        // play events of deleted sounds are deleted
+        audio_table
+            .delete_audio_row(UniqueAudioTableCol::Id(beep.id))
+            .unwrap();
+        let histogram = table
+            .play_histogram(HistogramBucket::Day, since, &PlayEventsFilter::default())
+            .unwrap();
+        assert_eq!(vec![(today, 1)], histogram);
+    }
+
+    #[test]
+    fn rollup_play_events_test() {
+        let db = TestDb::new();
+        let table = db.play_events_table();
+        let audio_table = db.audio_table();
+        let beep = AudioRowFixture::new().insert(&audio_table);
+        let boop = AudioRowFixture::new().insert(&audio_table);
+
+        table.insert_play_event(&play_event(beep.id, 1)).unwrap();
+        table.insert_play_event(&play_event(beep.id, 1)).unwrap();
+        table.insert_play_event(&play_event(boop.id, 2)).unwrap();
+
+        let now = chrono::Utc::now();
+        let since = now - chrono::Duration::days(1);
+        let today = now.date_naive();
+
+        // nothing played before yesterday
+        assert_eq!(0, table.rollup_play_events(since).unwrap());
+
+        let tomorrow = now + chrono::Duration::days(1);
+        assert_eq!(3, table.rollup_play_events(tomorrow).unwrap());
+        assert_eq!(0, table.rollup_play_events(tomorrow).unwrap());
+
+        // rolled up plays still count
+        let histogram = table
+            .play_histogram(HistogramBucket::Day, since, &PlayEventsFilter::default())
+            .unwrap();
+        assert_eq!(vec![(today, 3)], histogram);
+
+        // play events and rollups are counted together
+        table.insert_play_event(&play_event(beep.id, 1)).unwrap();
+        let filter = PlayEventsFilter {
+            audio_id: Some(beep.id),
+            guild_id: Some(1),
+        };
+        let histogram = table
+            .play_histogram(HistogramBucket::Week, since, &filter)
+            .unwrap();
+        assert_eq!(vec![(HistogramBucket::Week.start_of(today), 3)], histogram);
+
+        // rollups of deleted sounds are deleted
+        audio_table
+            .delete_audio_row(UniqueAudioTableCol::Id(beep.id))
+            .unwrap();
+        let histogram = table
+            .play_histogram(HistogramBucket::Day, since, &PlayEventsFilter::default())
+            .unwrap();
+        assert_eq!(vec![(today, 1)], histogram);
+    }
+
+    #[test]
+    fn user_play_history_test() {
+        let db = TestDb::new();
+        let table = db.play_events_table();
+        let audio_table = db.audio_table();
+        let beep = AudioRowFixture::new().insert(&audio_table);
+        let boop = AudioRowFixture::new().insert(&audio_table);
+
+        table.insert_play_event(&play_event(beep.id, 1)).unwrap();
+        table.insert_play_event(&play_event(boop.id, 1)).unwrap();
+        table.insert_play_event(&play_event(beep.id, 2)).unwrap();
+        table
+            .insert_play_event(&PlayEventInsert {
+                user_id: 2,
+                ..play_event(boop.id, 1)
+            })
+            .unwrap();
+
+        let history = |guild_id, limit| {
+            table
+                .user_play_history(1, guild_id, limit)
+                .unwrap()
+                .into_iter()
+                .map(|(row, _)| row.id)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(vec![beep.id, boop.id, beep.id], history(None, 10));
+        assert_eq!(vec![boop.id, beep.id], history(Some(1), 10));
+        assert_eq!(vec![beep.id], history(None, 1));
+        assert!(table.user_play_history(3, None, 10).unwrap().is_empty());
+    }
+
+    #[test]
+    fn daily_plays_test() {
+        let db = TestDb::new();
+        let table = db.play_events_table();
+        let audio_table = db.audio_table();
+        let beep = AudioRowFixture::new().insert(&audio_table);
+        let boop = AudioRowFixture::new().insert(&audio_table);
+
+        table.insert_play_event(&play_event(beep.id, 1)).unwrap();
+        table.insert_play_event(&play_event(boop.id, 1)).unwrap();
+        table.insert_play_event(&play_event(boop.id, 2)).unwrap();
+
+        let now = chrono::Utc::now();
+        let since = now - chrono::Duration::days(1);
+        let today = now.date_naive();
+
+        // rolled up plays are counted with play events of the same day
+        table
+            .rollup_play_events(now + chrono::Duration::days(1))
+            .unwrap();
+        table.insert_play_event(&play_event(boop.id, 1)).unwrap();
+
+        let plays = table
+            .daily_plays(since, &PlayEventsFilter::default())
+            .unwrap();
+        assert_eq!(vec![(beep.id, today, 1), (boop.id, today, 3)], plays);
+
+        let filter = PlayEventsFilter {
+            guild_id: Some(2),
+            ..Default::default()
+        };
+        let plays = table.daily_plays(since, &filter).unwrap();
+        assert_eq!(vec![(boop.id, today, 1)], plays);
+    }
+
+    #[test]
+    fn play_heatmap_test() {
+        let db = TestDb::new();
+        let table = db.play_events_table();
+        let beep = AudioRowFixture::new().insert(&db.audio_table());
+
+        table.insert_play_event(&play_event(beep.id, 1)).unwrap();
+        table.insert_play_event(&play_event(beep.id, 1)).unwrap();
+        table.insert_play_event(&play_event(beep.id, 2)).unwrap();
+
+        let now = chrono::Utc::now();
+        let since = now - chrono::Duration::days(1);
+        let filter = PlayEventsFilter {
+            guild_id: Some(1),
+            ..Default::default()
+        };
+        let heatmap = table.play_heatmap(since, &filter).unwrap();
+        let plays: i64 = heatmap.iter().map(|(_, _, plays)| plays).sum();
+        assert_eq!(2, plays);
+
+        let (weekday, hour, _) = heatmap[0];
+        assert!(weekday < 7);
+        assert!(hour < 24);
+        assert!(table
+            .play_heatmap(now + chrono::Duration::days(1), &filter)
+            .unwrap()
+            .is_empty());
+    }
+}
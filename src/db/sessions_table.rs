@@ -0,0 +1,340 @@
+use rusqlite::{params, OptionalExtension};
+
+use crate::{commands::PoiseError, common::LogResult};
+
+use super::{AudioTable, DbConnection, Table};
+
+/// Recorded soundboard session (see `/record`)
+#[derive(Debug, Clone, PartialEq)]
+pub struct SessionRow {
+    pub id: i64,
+    pub guild_id: u64,
+    pub started_by: u64,
+    pub started_at: chrono::DateTime<chrono::Utc>,
+    /// None while the session is being recorded
+    pub ended_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl TryFrom<&rusqlite::Row<'_>> for SessionRow {
+    type Error = rusqlite::Error;
+
+    fn try_from(row: &rusqlite::Row<'_>) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: row.get("id")?,
+            guild_id: row.get("guild_id")?,
+            started_by: row.get("started_by")?,
+            started_at: row.get("started_at")?,
+            ended_at: row.get("ended_at")?,
+        })
+    }
+}
+
+/// Sound played during a recorded session
+#[derive(Debug, Clone, PartialEq)]
+pub struct SessionPlayRow {
+    pub session_id: i64,
+    pub audio_id: i64,
+    pub user_id: u64,
+    /// Milliseconds since the session started
+    pub offset_ms: i64,
+}
+
+impl TryFrom<&rusqlite::Row<'_>> for SessionPlayRow {
+    type Error = rusqlite::Error;
+
+    fn try_from(row: &rusqlite::Row<'_>) -> Result<Self, Self::Error> {
+        Ok(Self {
+            session_id: row.get("session_id")?,
+            audio_id: row.get("audio_id")?,
+            user_id: row.get("user_id")?,
+            offset_ms: row.get("offset_ms")?,
+        })
+    }
+}
+
+/// Recorded sessions and the sounds played during them
+pub struct SessionsTable {
+    conn: DbConnection,
+}
+
+impl SessionsTable {
+    pub const TABLE_NAME: &'static str = "sessions";
+    pub const PLAYS_TABLE_NAME: &'static str = "session_plays";
+
+    pub fn new(connection: DbConnection) -> Self {
+        Self { conn: connection }
+    }
+
+    /// Start recording a session in guild, returning its id
+    pub fn start_session(
+        &self,
+        guild_id: u64,
+        user_id: u64,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> Result<i64, PoiseError> {
+        log::info!("Starting session recording. Guild Id: {guild_id}, User Id: {user_id}");
+
+        let table_name = Self::TABLE_NAME;
+        let sql = format!(
+            "
+            INSERT INTO {table_name}
+                (guild_id, started_by, started_at)
+            VALUES
+                (?1, ?2, ?3)"
+        );
+
+        self.conn
+            .execute(sql.as_str(), params![&guild_id, &user_id, &now])
+            .log_err_msg("Failed to insert session")?;
+
+        Ok(self.conn.last_insert_rowid())
+    }
+
+    /// Session being recorded in guild
+    pub fn active_session(&self, guild_id: u64) -> Result<Option<SessionRow>, PoiseError> {
+        let table_name = Self::TABLE_NAME;
+        let sql = format!(
+            "SELECT * FROM {table_name} WHERE guild_id = ?1 AND ended_at IS NULL ORDER BY id DESC"
+        );
+
+        let row = self
+            .conn
+            .query_row(sql.as_str(), params![&guild_id], |row| {
+                SessionRow::try_from(row)
+            })
+            .optional()
+            .log_err_msg("Failed to get active session")?;
+
+        Ok(row)
+    }
+
+    pub fn end_session(
+        &self,
+        session_id: i64,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> Result<(), PoiseError> {
+        log::info!("Ending session recording. Session Id: {session_id}");
+
+        let table_name = Self::TABLE_NAME;
+        let sql = format!("UPDATE {table_name} SET ended_at = ?1 WHERE id = ?2");
+
+        self.conn
+            .execute(sql.as_str(), params![&now, &session_id])
+            .log_err_msg("Failed to end session")?;
+
+        Ok(())
+    }
+
+    /// Guild's session, None for sessions of other guilds
+    pub fn find_session(
+        &self,
+        session_id: i64,
+        guild_id: u64,
+    ) -> Result<Option<SessionRow>, PoiseError> {
+        let table_name = Self::TABLE_NAME;
+        let sql = format!("SELECT * FROM {table_name} WHERE id = ?1 AND guild_id = ?2");
+
+        let row = self
+            .conn
+            .query_row(sql.as_str(), params![&session_id, &guild_id], |row| {
+                SessionRow::try_from(row)
+            })
+            .optional()
+            .log_err_msg("Failed to find session")?;
+
+        Ok(row)
+    }
+
+    /// Guild's latest sessions, most recent first
+    pub fn list_sessions(&self, guild_id: u64, limit: u64) -> Result<Vec<SessionRow>, PoiseError> {
+        let table_name = Self::TABLE_NAME;
+        let sql =
+            format!("SELECT * FROM {table_name} WHERE guild_id = ?1 ORDER BY id DESC LIMIT ?2");
+
+        let mut stmt = self
+            .conn
+            .prepare(sql.as_str())
+            .log_err_msg("Failed to prepare list sessions query")?;
+        let rows = stmt
+            .query_map(params![&guild_id, &limit], |row| SessionRow::try_from(row))
+            .log_err_msg("Failed to query sessions")?;
+
+        Ok(rows.filter_map(|row| row.log_err().ok()).collect())
+    }
+
+    pub fn insert_session_play(&self, play: &SessionPlayRow) -> Result<(), PoiseError> {
+        let table_name = Self::PLAYS_TABLE_NAME;
+        let sql = format!(
+            "
+            INSERT INTO {table_name}
+                (session_id, audio_id, user_id, offset_ms)
+            VALUES
+                (?1, ?2, ?3, ?4)"
+        );
+
+        self.conn
+            .execute(
+                sql.as_str(),
+                params![
+                    &play.session_id,
+                    &play.audio_id,
+                    &play.user_id,
+                    &play.offset_ms
+                ],
+            )
+            .log_err_msg("Failed to insert session play")?;
+
+        Ok(())
+    }
+
+    /// Sounds played during session, in the order they were played
+    pub fn session_plays(&self, session_id: i64) -> Result<Vec<SessionPlayRow>, PoiseError> {
+        let table_name = Self::PLAYS_TABLE_NAME;
+        let sql = format!(
+            "SELECT * FROM {table_name} WHERE session_id = ?1 ORDER BY offset_ms ASC, rowid ASC"
+        );
+
+        let mut stmt = self
+            .conn
+            .prepare(sql.as_str())
+            .log_err_msg("Failed to prepare session plays query")?;
+        let rows = stmt
+            .query_map(params![&session_id], |row| SessionPlayRow::try_from(row))
+            .log_err_msg("Failed to query session plays")?;
+
+        Ok(rows.filter_map(|row| row.log_err().ok()).collect())
+    }
+
+    pub fn count_session_plays(&self, session_id: i64) -> Result<u64, PoiseError> {
+        let table_name = Self::PLAYS_TABLE_NAME;
+        let sql = format!("SELECT COUNT(*) FROM {table_name} WHERE session_id = ?1");
+
+        let count = self
+            .conn
+            .query_row(sql.as_str(), params![&session_id], |row| row.get(0))
+            .log_err_msg("Failed to count session plays")?;
+
+        Ok(count)
+    }
+}
+
+impl Table for SessionsTable {
+    fn connection(&self) -> &DbConnection {
+        &self.conn
+    }
+
+    fn create_table(&self) {
+        let table_name = Self::TABLE_NAME;
+        let plays_table_name = Self::PLAYS_TABLE_NAME;
+        let audio_table_name = AudioTable::TABLE_NAME;
+
+        log::info!("Creating table: {table_name}");
+        let sql = format!(
+            "
+            BEGIN;
+                CREATE TABLE IF NOT EXISTS {table_name} (
+                    id INTEGER PRIMARY KEY,
+                    guild_id INTEGER NOT NULL,
+                    started_by INTEGER NOT NULL,
+                    started_at VARCHAR(25) NOT NULL,
+                    ended_at VARCHAR(25)
+                );
+
+                CREATE TABLE IF NOT EXISTS {plays_table_name} (
+                    session_id INTEGER NOT NULL,
+                    audio_id INTEGER NOT NULL,
+                    user_id INTEGER NOT NULL,
+                    offset_ms INTEGER NOT NULL
+                );
+
+                CREATE INDEX IF NOT EXISTS {plays_table_name}_session_id_idx ON {plays_table_name} (session_id);
+
+                CREATE TRIGGER IF NOT EXISTS {table_name}_delete_{plays_table_name} AFTER DELETE ON {table_name} BEGIN
+                    DELETE FROM {plays_table_name} WHERE session_id = old.id;
+                END;
+
+                CREATE TRIGGER IF NOT EXISTS {audio_table_name}_delete_{plays_table_name} AFTER DELETE ON {audio_table_name} BEGIN
+                    DELETE FROM {plays_table_name} WHERE audio_id = old.id;
+                END;
+            COMMIT;"
+        );
+
+        self.conn
+            .execute_batch(sql.as_str())
+            .log_err_msg("Failed create table")
+            .log_ok_msg(format!("Created table {table_name}"))
+            .unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::TestDb;
+
+    use super::*;
+
+    #[test]
+    fn table_create_test() {
+        let table = TestDb::new().sessions_table();
+        table.create_table();
+    }
+
+    #[test]
+    fn session_test() {
+        let table = TestDb::new().sessions_table();
+        let now = chrono::Utc::now();
+        assert_eq!(None, table.active_session(1).unwrap());
+
+        let id = table.start_session(1, 10, now).unwrap();
+        let active = table.active_session(1).unwrap().unwrap();
+        assert_eq!(id, active.id);
+        assert_eq!(10, active.started_by);
+        assert_eq!(None, table.active_session(2).unwrap());
+
+        for (audio_id, offset_ms) in [(5, 1500), (6, 0)] {
+            table
+                .insert_session_play(&SessionPlayRow {
+                    session_id: id,
+                    audio_id,
+                    user_id: 10,
+                    offset_ms,
+                })
+                .unwrap();
+        }
+        let plays = table.session_plays(id).unwrap();
+        assert_eq!(
+            vec![6, 5],
+            plays.iter().map(|play| play.audio_id).collect::<Vec<_>>()
+        );
+        assert_eq!(2, table.count_session_plays(id).unwrap());
+
+        table.end_session(id, now).unwrap();
+        assert_eq!(None, table.active_session(1).unwrap());
+        assert!(table
+            .find_session(id, 1)
+            .unwrap()
+            .unwrap()
+            .ended_at
+            .is_some());
+        assert_eq!(None, table.find_session(id, 2).unwrap());
+    }
+
+    #[test]
+    fn list_sessions_test() {
+        let table = TestDb::new().sessions_table();
+        let now = chrono::Utc::now();
+        let first = table.start_session(1, 10, now).unwrap();
+        let second = table.start_session(1, 10, now).unwrap();
+        table.start_session(2, 10, now).unwrap();
+
+        let sessions = table.list_sessions(1, 10).unwrap();
+        assert_eq!(
+            vec![second, first],
+            sessions
+                .iter()
+                .map(|session| session.id)
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(1, table.list_sessions(1, 1).unwrap().len());
+    }
+}
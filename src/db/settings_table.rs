@@ -1,6 +1,6 @@
-use rusqlite::OptionalExtension;
+use rusqlite::{params, OptionalExtension};
 
-use crate::{commands::PoiseError, common::LogResult};
+use crate::{commands::PoiseError, common::LogResult, helpers};
 
 use super::{DbConnection, Table};
 
@@ -26,7 +26,7 @@ pub struct SettingsTable {
 }
 
 impl SettingsTable {
-    const TABLE_NAME: &'static str = "settings";
+    pub const TABLE_NAME: &'static str = "settings";
 
     pub fn new(connection: DbConnection) -> Self {
         Self { conn: connection }
@@ -72,6 +72,26 @@ impl SettingsTable {
         }
     }
 
+    /// Secret salt of author hashes (see `privacy::author_hash`), generated on first use. It
+    /// must never change, or anonymous uploaders lose their sounds
+    pub fn author_hash_salt(&self) -> Result<String, PoiseError> {
+        let table_name = Self::TABLE_NAME;
+        let row_id = self.get_settings()?.id;
+
+        let sql = format!(
+            "UPDATE {table_name} SET author_hash_salt = ?1 WHERE id = ?2 AND author_hash_salt IS NULL"
+        );
+        self.conn
+            .execute(sql.as_str(), params![helpers::uuid_v4_str(), &row_id])
+            .log_err_msg("Failed to init author hash salt")?;
+
+        let sql = format!("SELECT author_hash_salt FROM {table_name} WHERE id = ?1");
+        Ok(self
+            .conn
+            .query_row(sql.as_str(), params![&row_id], |row| row.get(0))
+            .log_err_msg("Failed to get author hash salt")?)
+    }
+
     pub fn update_settings(&self, settings: &SettingsTableRow) -> Result<(), PoiseError> {
         log::info!("Saving settings");
 
@@ -116,7 +136,8 @@ impl Table for SettingsTable {
             CREATE TABLE IF NOT EXISTS {table_name} (
                 id INTEGER PRIMARY KEY,
                 join_audio VARCHAR(80),
-                leave_audio VARCHAR(80)
+                leave_audio VARCHAR(80),
+                author_hash_salt VARCHAR(64)
             );
         "
         );
@@ -131,21 +152,17 @@ impl Table for SettingsTable {
 
 #[cfg(test)]
 mod tests {
-
-    use r2d2_sqlite::SqliteConnectionManager;
+    use crate::test_utils::TestDb;
 
     use super::*;
 
     fn get_settings_table() -> SettingsTable {
-        let db_manager = SqliteConnectionManager::memory();
-        let db_pool = r2d2::Pool::new(db_manager).unwrap();
-        let connection = db_pool.get().unwrap();
-        SettingsTable::new(connection)
+        TestDb::new().settings_table()
     }
 
     #[test]
     fn table_create_test() {
-        let table = get_settings_table();
+        let table = TestDb::empty().settings_table();
         table.create_table();
         table.create_table();
     }
@@ -153,17 +170,23 @@ mod tests {
     #[test]
     fn get_settings_test() {
         let table = get_settings_table();
-        table.create_table();
         let settings = table.get_settings().unwrap();
 
         assert!(settings.join_audio.is_none());
         assert!(settings.leave_audio.is_none());
     }
 
+    #[test]
+    fn author_hash_salt_test() {
+        let table = get_settings_table();
+        let salt = table.author_hash_salt().unwrap();
+        assert!(!salt.is_empty());
+        assert_eq!(salt, table.author_hash_salt().unwrap());
+    }
+
     #[test]
     fn update_settings_test() {
         let table = get_settings_table();
-        table.create_table();
         let mut settings = table.get_settings().unwrap();
 
         let join_audio = Some("join.mp3".into());
@@ -0,0 +1,191 @@
+use rusqlite::{params, OptionalExtension};
+
+use crate::{commands::PoiseError, common::LogResult};
+
+use super::{AudioTable, DbConnection, Table};
+
+/// Share code of a sound (see `sharing`)
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShareCodeRow {
+    pub code: String,
+    pub audio_id: i64,
+    /// Guild the sound was shared from
+    pub guild_id: u64,
+    pub created_by: u64,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl TryFrom<&rusqlite::Row<'_>> for ShareCodeRow {
+    type Error = rusqlite::Error;
+
+    fn try_from(row: &rusqlite::Row<'_>) -> Result<Self, Self::Error> {
+        Ok(Self {
+            code: row.get("code")?,
+            audio_id: row.get("audio_id")?,
+            guild_id: row.get("guild_id")?,
+            created_by: row.get("created_by")?,
+            created_at: row.get("created_at")?,
+        })
+    }
+}
+
+pub struct ShareCodesTable {
+    conn: DbConnection,
+}
+
+impl ShareCodesTable {
+    pub const TABLE_NAME: &'static str = "share_codes";
+
+    pub fn new(connection: DbConnection) -> Self {
+        Self { conn: connection }
+    }
+
+    pub fn find_share_code(&self, code: &str) -> Result<Option<ShareCodeRow>, PoiseError> {
+        let table_name = Self::TABLE_NAME;
+        let sql = format!("SELECT * FROM {table_name} WHERE code = ?1");
+
+        let row = self
+            .conn
+            .query_row(sql.as_str(), params![&code], |row| {
+                ShareCodeRow::try_from(row)
+            })
+            .optional()
+            .log_err_msg("Failed to find share code")?;
+
+        Ok(row)
+    }
+
+    /// Share code of sound shared from guild, if it was shared before
+    pub fn find_audio_share_code(
+        &self,
+        audio_id: i64,
+        guild_id: u64,
+    ) -> Result<Option<ShareCodeRow>, PoiseError> {
+        let table_name = Self::TABLE_NAME;
+        let sql = format!("SELECT * FROM {table_name} WHERE audio_id = ?1 AND guild_id = ?2");
+
+        let row = self
+            .conn
+            .query_row(sql.as_str(), params![&audio_id, &guild_id], |row| {
+                ShareCodeRow::try_from(row)
+            })
+            .optional()
+            .log_err_msg("Failed to find audio share code")?;
+
+        Ok(row)
+    }
+
+    pub fn insert_share_code(&self, share_code: &ShareCodeRow) -> Result<(), PoiseError> {
+        log::info!(
+            "Inserting share code. Code: {}, Audio Id: {}",
+            share_code.code,
+            share_code.audio_id
+        );
+
+        let table_name = Self::TABLE_NAME;
+        let sql = format!(
+            "
+            INSERT INTO {table_name}
+                (code, audio_id, guild_id, created_by, created_at)
+            VALUES
+                (?1, ?2, ?3, ?4, ?5)"
+        );
+
+        self.conn
+            .execute(
+                sql.as_str(),
+                params![
+                    &share_code.code,
+                    &share_code.audio_id,
+                    &share_code.guild_id,
+                    &share_code.created_by,
+                    &share_code.created_at
+                ],
+            )
+            .log_err_msg("Failed to insert share code")?;
+
+        Ok(())
+    }
+}
+
+impl Table for ShareCodesTable {
+    fn connection(&self) -> &DbConnection {
+        &self.conn
+    }
+
+    fn create_table(&self) {
+        let table_name = Self::TABLE_NAME;
+        let audio_table_name = AudioTable::TABLE_NAME;
+
+        log::info!("Creating table: {table_name}");
+        let sql = format!(
+            "
+            BEGIN;
+                CREATE TABLE IF NOT EXISTS {table_name} (
+                    code VARCHAR(16) PRIMARY KEY,
+                    audio_id INTEGER NOT NULL,
+                    guild_id INTEGER NOT NULL,
+                    created_by INTEGER NOT NULL,
+                    created_at VARCHAR(25) NOT NULL,
+                    UNIQUE (audio_id, guild_id)
+                );
+
+                CREATE TRIGGER IF NOT EXISTS {audio_table_name}_delete_{table_name} AFTER DELETE ON {audio_table_name} BEGIN
+                    DELETE FROM {table_name} WHERE audio_id = old.id;
+                END;
+            COMMIT;"
+        );
+
+        self.conn
+            .execute_batch(sql.as_str())
+            .log_err_msg("Failed create table")
+            .log_ok_msg(format!("Created table {table_name}"))
+            .unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::db::UniqueAudioTableCol;
+    use crate::test_utils::{AudioRowFixture, TestDb};
+
+    use super::*;
+
+    #[test]
+    fn table_create_test() {
+        let table = TestDb::new().share_codes_table();
+        table.create_table();
+    }
+
+    #[test]
+    fn share_codes_test() {
+        let db = TestDb::new();
+        let table = db.share_codes_table();
+        let row = AudioRowFixture::new().insert(&db.audio_table());
+
+        let share_code = ShareCodeRow {
+            code: "ABCD2345".into(),
+            audio_id: row.id,
+            guild_id: 1,
+            created_by: 2,
+            created_at: chrono::DateTime::from_timestamp(1_700_000_000, 0).unwrap(),
+        };
+        table.insert_share_code(&share_code).unwrap();
+
+        assert_eq!(
+            Some(share_code.clone()),
+            table.find_share_code("ABCD2345").unwrap()
+        );
+        assert_eq!(
+            Some(share_code),
+            table.find_audio_share_code(row.id, 1).unwrap()
+        );
+        assert_eq!(None, table.find_audio_share_code(row.id, 2).unwrap());
+
+        // sound deleted, its share codes are deleted too
+        db.audio_table()
+            .delete_audio_row(UniqueAudioTableCol::Id(row.id))
+            .unwrap();
+        assert_eq!(None, table.find_share_code("ABCD2345").unwrap());
+    }
+}
@@ -0,0 +1,165 @@
+use rusqlite::{params, OptionalExtension};
+
+use crate::{commands::PoiseError, common::LogResult};
+
+use super::{DbConnection, Table};
+
+/// Pinned soundboard message published to a guild text channel
+#[derive(Debug, Clone, PartialEq)]
+pub struct SoundboardMessageRow {
+    pub guild_id: u64,
+    pub channel_id: u64,
+    pub message_id: u64,
+}
+
+impl TryFrom<&rusqlite::Row<'_>> for SoundboardMessageRow {
+    type Error = rusqlite::Error;
+
+    fn try_from(row: &rusqlite::Row<'_>) -> Result<Self, Self::Error> {
+        Ok(Self {
+            guild_id: row.get("guild_id")?,
+            channel_id: row.get("channel_id")?,
+            message_id: row.get("message_id")?,
+        })
+    }
+}
+
+pub struct SoundboardMessagesTable {
+    conn: DbConnection,
+}
+
+impl SoundboardMessagesTable {
+    pub const TABLE_NAME: &'static str = "soundboard_messages";
+
+    pub fn new(connection: DbConnection) -> Self {
+        Self { conn: connection }
+    }
+
+    pub fn find_soundboard_message(
+        &self,
+        guild_id: u64,
+    ) -> Result<Option<SoundboardMessageRow>, PoiseError> {
+        let table_name = Self::TABLE_NAME;
+        let sql = format!("SELECT * FROM {table_name} WHERE guild_id = ?1");
+
+        Ok(self
+            .conn
+            .query_row(sql.as_str(), params![&guild_id], |row| {
+                SoundboardMessageRow::try_from(row)
+            })
+            .optional()
+            .log_err_msg("Failed to query soundboard message")?)
+    }
+
+    /// Insert soundboard message, replacing guild's previous soundboard message
+    pub fn upsert_soundboard_message(&self, row: &SoundboardMessageRow) -> Result<(), PoiseError> {
+        log::info!("Saving soundboard message - {row:?}");
+
+        let table_name = Self::TABLE_NAME;
+        let sql = format!(
+            "
+            INSERT INTO {table_name}
+                (guild_id, channel_id, message_id, updated_at)
+            VALUES
+                (?1, ?2, ?3, ?4)
+            ON CONFLICT(guild_id) DO UPDATE SET
+                channel_id = excluded.channel_id,
+                message_id = excluded.message_id,
+                updated_at = excluded.updated_at"
+        );
+
+        self.conn
+            .execute(
+                sql.as_str(),
+                params![
+                    &row.guild_id,
+                    &row.channel_id,
+                    &row.message_id,
+                    &chrono::Utc::now()
+                ],
+            )
+            .log_err_msg("Failed to save soundboard message")?;
+
+        Ok(())
+    }
+
+    pub fn delete_soundboard_message(&self, guild_id: u64) -> Result<(), PoiseError> {
+        log::info!("Deleting soundboard message. Guild Id: {guild_id}");
+
+        let table_name = Self::TABLE_NAME;
+        let sql = format!("DELETE FROM {table_name} WHERE guild_id = ?1");
+
+        self.conn
+            .execute(sql.as_str(), params![&guild_id])
+            .log_err_msg("Failed to delete soundboard message")?;
+
+        Ok(())
+    }
+}
+
+impl Table for SoundboardMessagesTable {
+    fn connection(&self) -> &DbConnection {
+        &self.conn
+    }
+
+    fn create_table(&self) {
+        let table_name = Self::TABLE_NAME;
+
+        log::info!("Creating table: {table_name}");
+        let sql = format!(
+            "
+            CREATE TABLE IF NOT EXISTS {table_name} (
+                guild_id INTEGER PRIMARY KEY,
+                channel_id INTEGER NOT NULL,
+                message_id INTEGER NOT NULL,
+                updated_at VARCHAR(25) NOT NULL
+            );"
+        );
+
+        self.conn
+            .execute_batch(sql.as_str())
+            .log_err_msg("Failed create table")
+            .log_ok_msg(format!("Created table {table_name}"))
+            .unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::TestDb;
+
+    use super::*;
+
+    fn get_soundboard_messages_table() -> SoundboardMessagesTable {
+        TestDb::new().soundboard_messages_table()
+    }
+
+    #[test]
+    fn table_create_test() {
+        let table = TestDb::empty().soundboard_messages_table();
+        table.create_table();
+        table.create_table();
+    }
+
+    #[test]
+    fn upsert_soundboard_message_test() {
+        let table = get_soundboard_messages_table();
+        assert_eq!(None, table.find_soundboard_message(1).unwrap());
+
+        let mut row = SoundboardMessageRow {
+            guild_id: 1,
+            channel_id: 2,
+            message_id: 3,
+        };
+        table.upsert_soundboard_message(&row).unwrap();
+        assert_eq!(Some(row.clone()), table.find_soundboard_message(1).unwrap());
+
+        row.channel_id = 4;
+        row.message_id = 5;
+        table.upsert_soundboard_message(&row).unwrap();
+        assert_eq!(Some(row), table.find_soundboard_message(1).unwrap());
+
+        table.delete_soundboard_message(1).unwrap();
+        assert_eq!(None, table.find_soundboard_message(1).unwrap());
+    }
+}
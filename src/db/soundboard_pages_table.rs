@@ -0,0 +1,180 @@
+use rusqlite::{params, OptionalExtension};
+
+use crate::{commands::PoiseError, common::LogResult};
+
+use super::{DbConnection, Table};
+
+/// Page and tag filter a published soundboard message was last navigated to, so it's re-rendered
+/// where its viewers left it when sounds change, including after a restart
+#[derive(Debug, Clone, PartialEq)]
+pub struct SoundboardPageRow {
+    pub message_id: u64,
+    pub guild_id: u64,
+    /// Zero based soundboard page
+    pub page: u64,
+    /// Normalized tag filter
+    pub tag: Option<String>,
+}
+
+impl TryFrom<&rusqlite::Row<'_>> for SoundboardPageRow {
+    type Error = rusqlite::Error;
+
+    fn try_from(row: &rusqlite::Row<'_>) -> Result<Self, Self::Error> {
+        Ok(Self {
+            message_id: row.get("message_id")?,
+            guild_id: row.get("guild_id")?,
+            page: row.get("page")?,
+            tag: row.get("tag")?,
+        })
+    }
+}
+
+pub struct SoundboardPagesTable {
+    conn: DbConnection,
+}
+
+impl SoundboardPagesTable {
+    pub const TABLE_NAME: &'static str = "soundboard_pages";
+
+    pub fn new(connection: DbConnection) -> Self {
+        Self { conn: connection }
+    }
+
+    pub fn find_soundboard_page(
+        &self,
+        message_id: u64,
+    ) -> Result<Option<SoundboardPageRow>, PoiseError> {
+        let table_name = Self::TABLE_NAME;
+        let sql = format!("SELECT * FROM {table_name} WHERE message_id = ?1");
+
+        Ok(self
+            .conn
+            .query_row(sql.as_str(), params![&message_id], |row| {
+                SoundboardPageRow::try_from(row)
+            })
+            .optional()
+            .log_err_msg("Failed to query soundboard page")?)
+    }
+
+    /// Save page and tag filter of soundboard message, replacing its previous state
+    pub fn upsert_soundboard_page(&self, row: &SoundboardPageRow) -> Result<(), PoiseError> {
+        log::debug!("Saving soundboard page - {row:?}");
+
+        let table_name = Self::TABLE_NAME;
+        let sql = format!(
+            "
+            INSERT INTO {table_name}
+                (message_id, guild_id, page, tag, updated_at)
+            VALUES
+                (?1, ?2, ?3, ?4, ?5)
+            ON CONFLICT(message_id) DO UPDATE SET
+                page = excluded.page,
+                tag = excluded.tag,
+                updated_at = excluded.updated_at"
+        );
+
+        self.conn
+            .execute(
+                sql.as_str(),
+                params![
+                    &row.message_id,
+                    &row.guild_id,
+                    &row.page,
+                    &row.tag,
+                    &chrono::Utc::now()
+                ],
+            )
+            .log_err_msg("Failed to save soundboard page")?;
+
+        Ok(())
+    }
+
+    /// Forget page state of guild's soundboard messages
+    pub fn delete_guild_soundboard_pages(&self, guild_id: u64) -> Result<(), PoiseError> {
+        log::info!("Deleting soundboard pages. Guild Id: {guild_id}");
+
+        let table_name = Self::TABLE_NAME;
+        let sql = format!("DELETE FROM {table_name} WHERE guild_id = ?1");
+
+        self.conn
+            .execute(sql.as_str(), params![&guild_id])
+            .log_err_msg("Failed to delete soundboard pages")?;
+
+        Ok(())
+    }
+}
+
+impl Table for SoundboardPagesTable {
+    fn connection(&self) -> &DbConnection {
+        &self.conn
+    }
+
+    fn create_table(&self) {
+        let table_name = Self::TABLE_NAME;
+
+        log::info!("Creating table: {table_name}");
+        let sql = format!(
+            "
+            CREATE TABLE IF NOT EXISTS {table_name} (
+                message_id INTEGER PRIMARY KEY,
+                guild_id INTEGER NOT NULL,
+                page INTEGER NOT NULL DEFAULT 0,
+                tag VARCHAR(100),
+                updated_at VARCHAR(25) NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS {table_name}_guild_id ON {table_name} (guild_id);"
+        );
+
+        self.conn
+            .execute_batch(sql.as_str())
+            .log_err_msg("Failed create table")
+            .log_ok_msg(format!("Created table {table_name}"))
+            .unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::TestDb;
+
+    use super::*;
+
+    #[test]
+    fn table_create_test() {
+        let table = TestDb::empty().soundboard_pages_table();
+        table.create_table();
+        table.create_table();
+    }
+
+    #[test]
+    fn upsert_soundboard_page_test() {
+        let table = TestDb::new().soundboard_pages_table();
+        assert_eq!(None, table.find_soundboard_page(100).unwrap());
+
+        let mut row = SoundboardPageRow {
+            message_id: 100,
+            guild_id: 1,
+            page: 2,
+            tag: None,
+        };
+        table.upsert_soundboard_page(&row).unwrap();
+        assert_eq!(Some(row.clone()), table.find_soundboard_page(100).unwrap());
+
+        row.page = 0;
+        row.tag = Some("droid".into());
+        table.upsert_soundboard_page(&row).unwrap();
+        assert_eq!(Some(row), table.find_soundboard_page(100).unwrap());
+
+        let other_guild = SoundboardPageRow {
+            message_id: 200,
+            guild_id: 2,
+            page: 1,
+            tag: None,
+        };
+        table.upsert_soundboard_page(&other_guild).unwrap();
+
+        table.delete_guild_soundboard_pages(1).unwrap();
+        assert_eq!(None, table.find_soundboard_page(100).unwrap());
+        assert_eq!(Some(other_guild), table.find_soundboard_page(200).unwrap());
+    }
+}
@@ -0,0 +1,118 @@
+use rusqlite::{params, OptionalExtension};
+
+use crate::{commands::PoiseError, common::LogResult};
+
+use super::{AudioTable, DbConnection, Table};
+
+/// Per-user play counts of audio tracks
+pub struct UserPlaysTable {
+    conn: DbConnection,
+}
+
+impl UserPlaysTable {
+    pub const TABLE_NAME: &'static str = "user_plays";
+
+    pub fn new(connection: DbConnection) -> Self {
+        Self { conn: connection }
+    }
+
+    pub fn user_play_count(&self, user_id: u64, audio_id: i64) -> Result<i64, PoiseError> {
+        let table_name = Self::TABLE_NAME;
+        let sql =
+            format!("SELECT play_count FROM {table_name} WHERE user_id = ?1 AND audio_id = ?2");
+
+        let play_count: Option<i64> = self
+            .conn
+            .query_row(sql.as_str(), params![&user_id, &audio_id], |row| {
+                row.get("play_count")
+            })
+            .optional()
+            .log_err_msg("Failed to query user play count")?;
+
+        Ok(play_count.unwrap_or(0))
+    }
+
+    pub fn increment_user_play_count(&self, user_id: u64, audio_id: i64) -> Result<(), PoiseError> {
+        let table_name = Self::TABLE_NAME;
+        let sql = format!(
+            "
+            INSERT INTO {table_name}
+                (user_id, audio_id, play_count, last_played_at)
+            VALUES
+                (?1, ?2, 1, ?3)
+            ON CONFLICT(user_id, audio_id) DO UPDATE SET
+                play_count = play_count + 1,
+                last_played_at = excluded.last_played_at"
+        );
+
+        self.conn
+            .execute(
+                sql.as_str(),
+                params![&user_id, &audio_id, &chrono::Utc::now()],
+            )
+            .log_err_msg("Failed incrementing user play count")?;
+
+        Ok(())
+    }
+}
+
+impl Table for UserPlaysTable {
+    fn connection(&self) -> &DbConnection {
+        &self.conn
+    }
+
+    fn create_table(&self) {
+        let table_name = Self::TABLE_NAME;
+        let audio_table_name = AudioTable::TABLE_NAME;
+
+        log::info!("Creating table: {table_name}");
+        let sql = format!(
+            "
+            BEGIN;
+                CREATE TABLE IF NOT EXISTS {table_name} (
+                    user_id INTEGER NOT NULL,
+                    audio_id INTEGER NOT NULL,
+                    play_count INTEGER NOT NULL DEFAULT 0,
+                    last_played_at VARCHAR(25),
+                    PRIMARY KEY (user_id, audio_id)
+                );
+
+                CREATE TRIGGER IF NOT EXISTS {audio_table_name}_delete_{table_name} AFTER DELETE ON {audio_table_name} BEGIN
+                    DELETE FROM {table_name} WHERE audio_id = old.id;
+                END;
+            COMMIT;"
+        );
+
+        self.conn
+            .execute_batch(sql.as_str())
+            .log_err_msg("Failed create table")
+            .log_ok_msg(format!("Created table {table_name}"))
+            .unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::TestDb;
+
+    use super::*;
+
+    #[test]
+    fn table_create_test() {
+        let table = TestDb::new().user_plays_table();
+        table.create_table();
+    }
+
+    #[test]
+    fn increment_user_play_count_test() {
+        let table = TestDb::new().user_plays_table();
+
+        assert_eq!(0, table.user_play_count(1, 10).unwrap());
+        table.increment_user_play_count(1, 10).unwrap();
+        table.increment_user_play_count(1, 10).unwrap();
+        table.increment_user_play_count(2, 10).unwrap();
+
+        assert_eq!(2, table.user_play_count(1, 10).unwrap());
+        assert_eq!(1, table.user_play_count(2, 10).unwrap());
+    }
+}
@@ -0,0 +1,349 @@
+//! Runtime diagnostics shown by `/debug`: gateway latency, voice connections, database pool
+//! and cache sizes, temp files dir usage, button interactions handled and the most recent
+//! errors. Errors are ERROR-level log records, captured by the logger installed with
+//! `init_logger`
+use std::collections::VecDeque;
+use std::path;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serenity::all::{ChannelId, CreateEmbed, GuildId};
+
+use crate::alerts;
+use crate::commands::PoiseContext;
+use crate::disk::{self, DiskUsage};
+use crate::error_reporting;
+use crate::helpers;
+use crate::i18n::{tr, Language};
+use crate::interactions::{self, InteractionMetrics};
+use crate::opus_cache;
+use crate::temp_files;
+use crate::vars;
+
+/// Most recent errors, oldest first. At most `vars::RECENT_ERRORS_LEN` are kept
+static RECENT_ERRORS: Mutex<VecDeque<RecentError>> = Mutex::new(VecDeque::new());
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecentError {
+    pub at: DateTime<Utc>,
+    pub message: String,
+}
+
+/// Remember error for `/debug`, dropping the oldest one when the buffer is full, and alert it
+pub fn record_error(message: impl Into<String>) {
+    let error = RecentError {
+        at: Utc::now(),
+        message: message.into(),
+    };
+    alerts::send(&error);
+
+    let mut errors = RECENT_ERRORS.lock().unwrap();
+    push_error(&mut errors, error, vars::RECENT_ERRORS_LEN);
+}
+
+/// Recorded errors, oldest first
+pub fn recent_errors() -> Vec<RecentError> {
+    RECENT_ERRORS.lock().unwrap().iter().cloned().collect()
+}
+
+fn push_error(errors: &mut VecDeque<RecentError>, error: RecentError, capacity: usize) {
+    while errors.len() >= capacity {
+        errors.pop_front();
+    }
+    errors.push_back(error);
+}
+
+/// `env_logger` logger that also records ERROR-level records with `record_error`, and reports
+/// them with `error_reporting::report_error`
+struct ErrorRecordingLogger(env_logger::Logger);
+
+impl log::Log for ErrorRecordingLogger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        self.0.enabled(metadata)
+    }
+
+    fn log(&self, record: &log::Record) {
+        if record.level() == log::Level::Error {
+            let message = record.args().to_string();
+            error_reporting::report_error(&message);
+            record_error(message);
+        }
+        self.0.log(record);
+    }
+
+    fn flush(&self) {
+        self.0.flush();
+    }
+}
+
+/// Install logger configured with `RUST_LOG` like `env_logger::init`, recording errors
+pub fn init_logger() {
+    let logger = env_logger::Builder::from_default_env().build();
+    // errors are recorded whatever the configured level is
+    log::set_max_level(logger.filter().max(log::LevelFilter::Error));
+    log::set_boxed_logger(Box::new(ErrorRecordingLogger(logger)))
+        .expect("Logger is only installed once");
+}
+
+/// Number of files and their total size in a dir, not recursive
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct DirUsage {
+    pub files: usize,
+    pub bytes: u64,
+}
+
+pub fn dir_usage(dir: &path::Path) -> DirUsage {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return DirUsage::default();
+    };
+
+    entries
+        .filter_map(|entry| entry.ok()?.metadata().ok())
+        .filter(|metadata| metadata.is_file())
+        .fold(DirUsage::default(), |usage, metadata| DirUsage {
+            files: usage.files + 1,
+            bytes: usage.bytes + metadata.len(),
+        })
+}
+
+#[derive(Debug, Clone)]
+pub struct Diagnostics {
+    /// Zero until the first heartbeat is acknowledged
+    pub gateway_latency: Duration,
+    /// Guilds the bot has a voice call in, with the channel it's connected to
+    pub voice_connections: Vec<(GuildId, Option<ChannelId>)>,
+    pub db_pool_connections: u32,
+    pub db_pool_idle_connections: u32,
+    pub db_pool_max_size: u32,
+    pub autocomplete_cache_entries: usize,
+    /// Sounds transcoded into the opus cache (see `opus_cache`)
+    pub opus_cache_entries: usize,
+    pub now_playing_messages: usize,
+    pub pending_confirmations: usize,
+    pub temp_files: DirUsage,
+    /// Usage of the audio dir's volume. None if it can't be read
+    pub audio_disk: Option<DiskUsage>,
+    /// Button interactions handled per action since startup
+    pub interactions: Vec<(&'static str, InteractionMetrics)>,
+    pub recent_errors: Vec<RecentError>,
+}
+
+impl Diagnostics {
+    pub async fn collect(ctx: PoiseContext<'_>) -> Self {
+        let data = ctx.data();
+        let manager = helpers::poise_songbird_get(&ctx).await;
+
+        let mut voice_connections = vec![];
+        for (guild_id, call) in manager.iter() {
+            let channel_id = call
+                .lock()
+                .await
+                .current_channel()
+                .map(|channel_id| ChannelId::new(channel_id.0.get()));
+            voice_connections.push((GuildId::new(guild_id.0.get()), channel_id));
+        }
+        voice_connections.sort();
+
+        let db_pool = data.db_pool.state();
+
+        Self {
+            gateway_latency: ctx.ping().await,
+            voice_connections,
+            db_pool_connections: db_pool.connections,
+            db_pool_idle_connections: db_pool.idle_connections,
+            db_pool_max_size: data.db_pool.max_size(),
+            autocomplete_cache_entries: data.autocomplete_cache.entry_count(),
+            opus_cache_entries: opus_cache::OPUS_CACHE.entry_count(),
+            now_playing_messages: data.now_playing.message_count(),
+            pending_confirmations: data.pending_confirmations.count(),
+            temp_files: dir_usage(&temp_files::temp_files_dir()),
+            audio_disk: disk::disk_usage(&data.config().audio_dir).ok(),
+            interactions: interactions::metrics(),
+            recent_errors: recent_errors(),
+        }
+    }
+
+    pub fn to_embed(&self, lang: Language) -> CreateEmbed {
+        let voice_connections = match self.voice_connections.is_empty() {
+            true => tr(lang, "debug.no_voice_connections", &[]),
+            false => self
+                .voice_connections
+                .iter()
+                .map(|(guild_id, channel_id)| match channel_id {
+                    Some(channel_id) => format!("- `{guild_id}` <#{channel_id}>"),
+                    None => format!("- `{guild_id}` -"),
+                })
+                .collect::<Vec<_>>()
+                .join("\n"),
+        };
+
+        let interactions = match self.interactions.is_empty() {
+            true => tr(lang, "debug.no_interactions", &[]),
+            false => self
+                .interactions
+                .iter()
+                .map(|(name, metrics)| {
+                    tr(
+                        lang,
+                        "debug.interaction_metrics",
+                        &[
+                            ("action", name),
+                            ("count", &metrics.count),
+                            ("failures", &metrics.failures),
+                            ("average", &metrics.average().as_millis()),
+                        ],
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("\n"),
+        };
+
+        let recent_errors = match self.recent_errors.is_empty() {
+            true => tr(lang, "debug.no_recent_errors", &[]),
+            false => self
+                .recent_errors
+                .iter()
+                .rev()
+                .map(|error| format!("- <t:{}:R> {}", error.at.timestamp(), error.message))
+                .collect::<Vec<_>>()
+                .join("\n"),
+        };
+
+        CreateEmbed::new()
+            .title(tr(lang, "debug.title", &[]))
+            .field(
+                tr(lang, "debug.latency", &[]),
+                format!("{} ms", self.gateway_latency.as_millis()),
+                true,
+            )
+            .field(
+                tr(lang, "debug.db_pool", &[]),
+                tr(
+                    lang,
+                    "debug.db_pool_state",
+                    &[
+                        ("connections", &self.db_pool_connections),
+                        ("idle", &self.db_pool_idle_connections),
+                        ("max", &self.db_pool_max_size),
+                    ],
+                ),
+                true,
+            )
+            .field(
+                tr(lang, "debug.caches", &[]),
+                tr(
+                    lang,
+                    "debug.cache_sizes",
+                    &[
+                        ("autocomplete", &self.autocomplete_cache_entries),
+                        ("opus", &self.opus_cache_entries),
+                        ("now_playing", &self.now_playing_messages),
+                        ("confirmations", &self.pending_confirmations),
+                    ],
+                ),
+                false,
+            )
+            .field(
+                tr(lang, "debug.temp_files", &[]),
+                tr(
+                    lang,
+                    "debug.temp_files_usage",
+                    &[
+                        ("files", &self.temp_files.files),
+                        ("size", &helpers::format_file_size(self.temp_files.bytes)),
+                    ],
+                ),
+                false,
+            )
+            .field(
+                tr(lang, "debug.disk", &[]),
+                match &self.audio_disk {
+                    Some(usage) => tr(
+                        lang,
+                        "debug.disk_usage",
+                        &[
+                            ("free", &helpers::format_file_size(usage.free)),
+                            ("total", &helpers::format_file_size(usage.total)),
+                            ("used", &usage.used_percent()),
+                        ],
+                    ),
+                    None => tr(lang, "stats.disk_unknown", &[]),
+                },
+                false,
+            )
+            .field(
+                tr(lang, "debug.voice_connections", &[]),
+                truncate_field(voice_connections),
+                false,
+            )
+            .field(
+                tr(lang, "debug.interactions", &[]),
+                truncate_field(interactions),
+                false,
+            )
+            .field(
+                tr(lang, "debug.recent_errors", &[]),
+                truncate_field(recent_errors),
+                false,
+            )
+    }
+}
+
+/// Embed field values are limited to 1024 chars
+fn truncate_field(value: String) -> String {
+    if value.chars().count() > vars::EMBED_FIELD_MAX_LEN {
+        let truncated: String = value.chars().take(vars::EMBED_FIELD_MAX_LEN - 3).collect();
+        format!("{truncated}...")
+    } else {
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn push_error_test() {
+        let mut errors = VecDeque::new();
+        for index in 0..5 {
+            let error = RecentError {
+                at: Utc::now(),
+                message: index.to_string(),
+            };
+            push_error(&mut errors, error, 3);
+        }
+
+        let messages: Vec<_> = errors.iter().map(|error| error.message.as_str()).collect();
+        assert_eq!(vec!["2", "3", "4"], messages);
+    }
+
+    #[test]
+    fn dir_usage_test() {
+        let dir = TempDir::new();
+        let file = dir.file("a.mp3");
+        std::fs::write(&file, [0u8; 10]).unwrap();
+        dir.file("b.mp3");
+
+        assert_eq!(
+            DirUsage {
+                files: 2,
+                bytes: 10
+            },
+            dir_usage(dir.path())
+        );
+        assert_eq!(DirUsage::default(), dir_usage(&dir.path().join("missing")));
+    }
+
+    #[test]
+    fn truncate_field_test() {
+        assert_eq!("bruh", truncate_field("bruh".into()));
+
+        let truncated = truncate_field("a".repeat(vars::EMBED_FIELD_MAX_LEN + 1));
+        assert_eq!(vars::EMBED_FIELD_MAX_LEN, truncated.chars().count());
+        assert!(truncated.ends_with("..."));
+    }
+}
@@ -0,0 +1,274 @@
+//! Weekly digest. Once a week, guilds with a `/settings digest` channel get an embed summing up
+//! the past week: sounds added, most played sounds, top uploader and total plays
+use std::sync::Once;
+use std::time::Duration;
+
+use chrono::{DateTime, Datelike, Utc};
+use serenity::all::{ChannelId, CreateEmbed, CreateMessage, GuildId};
+use serenity::client::Context;
+
+use crate::commands::{PoiseError, PoiseResult};
+use crate::common::LogResult;
+use crate::db::{
+    AudioTable, DbConnection, GuildSettingsRow, GuildSettingsTable, HistogramBucket,
+    PlayEventsFilter, PlayEventsTable,
+};
+use crate::i18n::{self, tr, Language};
+use crate::vars;
+
+/// A guild's activity over the week before the digest
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Digest {
+    pub new_sounds: u64,
+    /// Names and play counts of the most played sounds, most played first
+    pub top_played: Vec<(String, i64)>,
+    /// User id and count of sounds added of the member who added the most sounds
+    pub top_uploader: Option<(u64, u64)>,
+    pub total_plays: i64,
+}
+
+impl Digest {
+    /// Guild's digest of the week up to `now`
+    pub fn collect(
+        audio_table: &AudioTable,
+        play_events_table: &PlayEventsTable,
+        guild_id: u64,
+        now: DateTime<Utc>,
+    ) -> Result<Self, PoiseError> {
+        let since = now - chrono::Duration::days(7);
+        let filter = PlayEventsFilter {
+            audio_id: None,
+            guild_id: Some(guild_id),
+        };
+
+        Ok(Self {
+            new_sounds: audio_table.count_audio_rows_added_since(since, guild_id)?,
+            top_played: play_events_table.most_played(since, &filter, vars::DIGEST_TOP_PLAYED)?,
+            top_uploader: audio_table.top_author_since(since, guild_id)?,
+            total_plays: play_events_table
+                .play_histogram(HistogramBucket::Day, since, &filter)?
+                .iter()
+                .map(|(_, plays)| plays)
+                .sum(),
+        })
+    }
+
+    pub fn to_embed(&self, lang: Language) -> CreateEmbed {
+        let top_played = match self.top_played.is_empty() {
+            true => tr(lang, "digest.no_plays", &[]),
+            false => self
+                .top_played
+                .iter()
+                .enumerate()
+                .map(|(index, (name, plays))| {
+                    tr(
+                        lang,
+                        "digest.top_played_entry",
+                        &[("rank", &(index + 1)), ("track", name), ("plays", plays)],
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("\n"),
+        };
+
+        let top_uploader = match self.top_uploader {
+            Some((user_id, sounds)) => tr(
+                lang,
+                "digest.top_uploader_entry",
+                &[("user", &format!("<@{user_id}>")), ("sounds", &sounds)],
+            ),
+            None => "-".into(),
+        };
+
+        CreateEmbed::new()
+            .title(tr(lang, "digest.title", &[]))
+            .field(
+                tr(lang, "digest.new_sounds", &[]),
+                self.new_sounds.to_string(),
+                true,
+            )
+            .field(
+                tr(lang, "digest.total_plays", &[]),
+                self.total_plays.to_string(),
+                true,
+            )
+            .field(tr(lang, "digest.top_uploader", &[]), top_uploader, true)
+            .field(tr(lang, "digest.top_played", &[]), top_played, false)
+    }
+}
+
+/// Whether guild's weekly digest is due to be posted at `now`
+pub fn is_due(settings: &GuildSettingsRow, now: DateTime<Utc>) -> bool {
+    settings.digest_channel_id.is_some()
+        && now.weekday() == vars::DIGEST_WEEKDAY
+        && settings.digest_last_date != Some(now.date_naive())
+}
+
+/// Start the weekly digest job. Only the first call starts it, so it's safe to call on every
+/// ready event
+pub fn spawn_digest_job(ctx: Context, db_pool: r2d2::Pool<r2d2_sqlite::SqliteConnectionManager>) {
+    static STARTED: Once = Once::new();
+
+    STARTED.call_once(|| {
+        log::info!("Starting weekly digest job");
+        tokio::spawn(DigestJob { ctx, db_pool }.run());
+    });
+}
+
+struct DigestJob {
+    ctx: Context,
+    db_pool: r2d2::Pool<r2d2_sqlite::SqliteConnectionManager>,
+}
+
+impl DigestJob {
+    async fn run(self) {
+        let mut interval =
+            tokio::time::interval(Duration::from_secs(vars::DIGEST_JOB_INTERVAL_SECS));
+
+        loop {
+            interval.tick().await;
+            self.post_due(Utc::now()).await;
+        }
+    }
+
+    fn db_connection(&self) -> DbConnection {
+        self.db_pool
+            .get()
+            .expect("Failed to get Pooled SQLite connection")
+    }
+
+    async fn post_due(&self, now: DateTime<Utc>) {
+        let table = GuildSettingsTable::new(self.db_connection());
+        let Ok(guild_settings) = table.digest_guild_settings() else {
+            return;
+        };
+
+        for mut settings in guild_settings
+            .into_iter()
+            .filter(|settings| is_due(settings, now))
+        {
+            // recorded before posting, so failed posts aren't retried every tick
+            settings.digest_last_date = Some(now.date_naive());
+            table.update_guild_settings(&settings).ok();
+
+            self.post(&settings, now)
+                .await
+                .log_err_msg(format!(
+                    "Failed to post weekly digest. Guild Id: {}",
+                    settings.guild_id
+                ))
+                .ok();
+        }
+    }
+
+    async fn post(&self, settings: &GuildSettingsRow, now: DateTime<Utc>) -> PoiseResult {
+        let guild_id = GuildId::new(settings.guild_id);
+        let channel_id = ChannelId::new(settings.digest_channel_id.ok_or("No digest channel id")?);
+        log::info!("Posting weekly digest. Guild Id: {guild_id}, Channel Id: {channel_id}");
+
+        let digest = Digest::collect(
+            &AudioTable::new(self.db_connection()),
+            &PlayEventsTable::new(self.db_connection()),
+            settings.guild_id,
+            now,
+        )?;
+
+        let guild_locale = self
+            .ctx
+            .cache
+            .guild(guild_id)
+            .map(|guild| guild.preferred_locale.clone());
+        let lang = i18n::settings_language(Some(settings), guild_locale.as_deref());
+
+        channel_id
+            .send_message(
+                &self.ctx.http,
+                CreateMessage::new().embed(digest.to_embed(lang)),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{NaiveDate, TimeZone};
+
+    use crate::db::PlayEventInsert;
+    use crate::test_utils::{AudioRowFixture, TestDb};
+
+    use super::*;
+
+    #[test]
+    fn is_due_test() {
+        // a monday
+        let now = Utc.with_ymd_and_hms(2024, 1, 8, 0, 1, 0).unwrap();
+        let mut settings = GuildSettingsRow {
+            guild_id: 1,
+            ..Default::default()
+        };
+        assert!(!is_due(&settings, now));
+
+        settings.digest_channel_id = Some(2);
+        assert!(is_due(&settings, now));
+        assert!(!is_due(&settings, now + chrono::Duration::days(1)));
+
+        settings.digest_last_date = NaiveDate::from_ymd_opt(2024, 1, 8);
+        assert!(!is_due(&settings, now));
+
+        settings.digest_last_date = NaiveDate::from_ymd_opt(2024, 1, 1);
+        assert!(is_due(&settings, now));
+    }
+
+    #[test]
+    fn collect_test() {
+        let db = TestDb::new();
+        let audio_table = db.audio_table();
+        let play_events_table = db.play_events_table();
+
+        let beep = AudioRowFixture::new()
+            .name("Beep")
+            .author_id(10)
+            .guild_id(1)
+            .insert(&audio_table);
+        let boop = AudioRowFixture::new()
+            .name("Boop")
+            .author_id(10)
+            .guild_id(1)
+            .insert(&audio_table);
+        AudioRowFixture::new()
+            .author_id(11)
+            .guild_id(1)
+            .insert(&audio_table);
+        AudioRowFixture::new()
+            .author_id(11)
+            .guild_id(2)
+            .insert(&audio_table);
+
+        for (audio_id, guild_id) in [(beep.id, 1), (boop.id, 1), (boop.id, 1), (beep.id, 2)] {
+            play_events_table
+                .insert_play_event(&PlayEventInsert {
+                    audio_id,
+                    user_id: 1,
+                    guild_id: Some(guild_id),
+                    source: "slash".into(),
+                })
+                .unwrap();
+        }
+
+        let digest = Digest::collect(&audio_table, &play_events_table, 1, Utc::now()).unwrap();
+        assert_eq!(
+            Digest {
+                new_sounds: 3,
+                top_played: vec![("Boop".into(), 2), ("Beep".into(), 1)],
+                top_uploader: Some((10, 2)),
+                total_plays: 3,
+            },
+            digest
+        );
+
+        let digest = Digest::collect(&audio_table, &play_events_table, 3, Utc::now()).unwrap();
+        assert_eq!(Digest::default(), digest);
+    }
+}
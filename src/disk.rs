@@ -0,0 +1,121 @@
+//! Free space of the audio dir's volume. New sounds are refused while it's below
+//! `Config::min_free_disk_mb`, so a full disk doesn't fail audio files or database writes
+//! halfway through
+use std::path;
+
+use crate::config::Config;
+use crate::diagnostics::{dir_usage, DirUsage};
+use crate::helpers;
+use crate::i18n::{tr, Language};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DiskUsage {
+    /// Bytes available to the bot on the volume
+    pub free: u64,
+    /// Size of the volume in bytes
+    pub total: u64,
+    /// Audio files in the audio dir
+    pub audio_dir: DirUsage,
+}
+
+impl DiskUsage {
+    /// Percent of the volume in use, 0 for volumes of unknown size
+    pub fn used_percent(&self) -> u64 {
+        match self.total {
+            0 => 0,
+            total => (total.saturating_sub(self.free) * 100) / total,
+        }
+    }
+}
+
+/// Usage of the volume dir is on
+pub fn disk_usage(dir: &path::Path) -> std::io::Result<DiskUsage> {
+    Ok(DiskUsage {
+        free: fs2::available_space(dir)?,
+        total: fs2::total_space(dir)?,
+        audio_dir: dir_usage(dir),
+    })
+}
+
+/// Minimum free bytes new sounds need, None if the guard is disabled
+pub fn min_free_bytes(config: &Config) -> Option<u64> {
+    match config.min_free_disk_mb {
+        0 => None,
+        min_free_disk_mb => Some(min_free_disk_mb * 1024 * 1024),
+    }
+}
+
+/// Check the audio dir's volume has room for new sounds. Returns the free bytes when it's below
+/// the configured minimum. Volumes whose free space can't be read are assumed to have room
+pub fn check_free_space(config: &Config) -> Result<(), u64> {
+    let Some(min_free) = min_free_bytes(config) else {
+        return Ok(());
+    };
+
+    match fs2::available_space(&config.audio_dir) {
+        Ok(free) if free < min_free => {
+            log::warn!("Audio dir is low on disk space. Free: {free}, Min: {min_free}");
+            Err(free)
+        }
+        Ok(_) => Ok(()),
+        Err(err) => {
+            log::warn!("Failed to read free disk space of audio dir - {err}");
+            Ok(())
+        }
+    }
+}
+
+/// Reply to new sounds refused for lack of disk space
+pub fn low_space_message(lang: Language, config: &Config, free: u64) -> String {
+    tr(
+        lang,
+        "disk.low_space",
+        &[
+            ("free", &helpers::format_file_size(free)),
+            (
+                "min",
+                &helpers::format_file_size(min_free_bytes(config).unwrap_or_default()),
+            ),
+        ],
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn used_percent_test() {
+        let usage = |free, total| DiskUsage {
+            free,
+            total,
+            audio_dir: DirUsage::default(),
+        };
+        assert_eq!(75, usage(25, 100).used_percent());
+        assert_eq!(0, usage(100, 100).used_percent());
+        assert_eq!(0, usage(0, 0).used_percent());
+    }
+
+    #[test]
+    fn check_free_space_test() {
+        let audio_dir = TempDir::new();
+        let config = |min_free_disk_mb| Config {
+            audio_dir: audio_dir.path().to_path_buf(),
+            min_free_disk_mb,
+            ..Default::default()
+        };
+
+        assert_eq!(Ok(()), check_free_space(&config(0)));
+        assert_eq!(Ok(()), check_free_space(&config(1)));
+        // more free space than any disk has
+        assert!(check_free_space(&config(u64::MAX / (1024 * 1024))).is_err());
+
+        let missing = Config {
+            audio_dir: audio_dir.path().join("missing"),
+            ..config(1)
+        };
+        assert_eq!(Ok(()), check_free_space(&missing));
+    }
+}
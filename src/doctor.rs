@@ -0,0 +1,505 @@
+//! Pre-flight checks run by `/doctor` and the `doctor` CLI subcommand: Discord token, gateway
+//! intents, voice permissions in configured voice channels, database schema version, free disk
+//! space of the audio dir and availability of ffmpeg and yt-dlp
+use std::path;
+use std::time::Duration;
+
+use serenity::all::{ApplicationFlags, ChannelId, CreateEmbed, GuildId, Http, UserId};
+
+use crate::channel_restrictions::ChannelKind;
+use crate::commands::PoiseError;
+use crate::config::Config;
+use crate::db::{self, ChannelRestrictionsTable, DbConnection, GuildSettingsTable};
+use crate::helpers;
+use crate::i18n::{tr, Language};
+use crate::vars;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Check {
+    Token,
+    Intents,
+    VoicePermissions,
+    SchemaVersion,
+    DiskSpace,
+    Ffmpeg,
+    YtDlp,
+}
+
+impl Check {
+    /// Message key of the check's name
+    fn name_key(&self) -> &'static str {
+        match self {
+            Self::Token => "doctor.token",
+            Self::Intents => "doctor.intents",
+            Self::VoicePermissions => "doctor.voice_permissions",
+            Self::SchemaVersion => "doctor.schema_version",
+            Self::DiskSpace => "doctor.disk_space",
+            Self::Ffmpeg => "doctor.ffmpeg",
+            Self::YtDlp => "doctor.yt_dlp",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckStatus {
+    Pass,
+    Fail,
+    /// Nothing to check, e.g. no voice channels are configured
+    Skip,
+}
+
+impl CheckStatus {
+    fn icon(&self) -> &'static str {
+        match self {
+            Self::Pass => "✅",
+            Self::Fail => "❌",
+            Self::Skip => "➖",
+        }
+    }
+
+    fn label(&self) -> &'static str {
+        match self {
+            Self::Pass => "PASS",
+            Self::Fail => "FAIL",
+            Self::Skip => "SKIP",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CheckResult {
+    pub check: Check,
+    pub status: CheckStatus,
+    pub detail: String,
+}
+
+impl CheckResult {
+    fn new(check: Check, status: CheckStatus, detail: impl Into<String>) -> Self {
+        Self {
+            check,
+            status,
+            detail: detail.into(),
+        }
+    }
+}
+
+/// Results of every check, in `Check` order
+#[derive(Debug, Clone, PartialEq)]
+pub struct Report {
+    pub results: Vec<CheckResult>,
+    lang: Language,
+}
+
+impl Report {
+    pub fn is_healthy(&self) -> bool {
+        self.results
+            .iter()
+            .all(|result| result.status != CheckStatus::Fail)
+    }
+
+    pub fn to_embed(&self) -> CreateEmbed {
+        self.results.iter().fold(
+            CreateEmbed::new().title(tr(self.lang, "doctor.title", &[])),
+            |embed, result| {
+                embed.field(
+                    format!(
+                        "{} {}",
+                        result.status.icon(),
+                        tr(self.lang, result.check.name_key(), &[])
+                    ),
+                    &result.detail,
+                    false,
+                )
+            },
+        )
+    }
+
+    /// One line per check, for the CLI
+    pub fn to_text(&self) -> String {
+        self.results
+            .iter()
+            .map(|result| {
+                format!(
+                    "[{}] {} - {}",
+                    result.status.label(),
+                    tr(self.lang, result.check.name_key(), &[]),
+                    result.detail
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// Run every check. Voice permissions are checked in the configured voice channels (allowed
+/// voice channels and sound of the day voice channels) of guild, or of every guild if None
+pub async fn run(
+    http: &Http,
+    db_pool: &r2d2::Pool<r2d2_sqlite::SqliteConnectionManager>,
+    audio_dir: &path::Path,
+    guild_id: Option<GuildId>,
+    lang: Language,
+) -> Report {
+    let db_connection = || {
+        db_pool
+            .get()
+            .expect("Failed to get Pooled SQLite connection")
+    };
+
+    let (token, bot_user_id) = check_token(http, lang).await;
+    let voice_permissions = match (bot_user_id, configured_voice_channels(db_pool, guild_id)) {
+        (_, Err(err)) => {
+            CheckResult::new(Check::VoicePermissions, CheckStatus::Fail, err.to_string())
+        }
+        (None, Ok(_)) => CheckResult::new(
+            Check::VoicePermissions,
+            CheckStatus::Skip,
+            tr(lang, "doctor.voice_no_token", &[]),
+        ),
+        (Some(bot_user_id), Ok(channels)) => {
+            check_voice_permissions(http, bot_user_id, &channels, lang).await
+        }
+    };
+
+    let results = vec![
+        token,
+        check_intents(http, lang).await,
+        voice_permissions,
+        check_schema_version(&db_connection(), lang),
+        check_disk_space(audio_dir, lang),
+        check_tool(Check::Ffmpeg, "ffmpeg", "-version", lang).await,
+        check_tool(Check::YtDlp, "yt-dlp", "--version", lang).await,
+    ];
+
+    Report { results, lang }
+}
+
+/// `doctor` CLI subcommand. Prints the report and returns whether every check passed
+pub async fn run_cli(
+    config: &Config,
+    db_pool: &r2d2::Pool<r2d2_sqlite::SqliteConnectionManager>,
+) -> bool {
+    let http = Http::new(&config.token);
+    let report = run(&http, db_pool, &config.audio_dir, None, Language::English).await;
+
+    println!("{}", report.to_text());
+    report.is_healthy()
+}
+
+/// Guild and channel ids of voice channels the bot is expected to play in
+fn configured_voice_channels(
+    db_pool: &r2d2::Pool<r2d2_sqlite::SqliteConnectionManager>,
+    guild_id: Option<GuildId>,
+) -> Result<Vec<(GuildId, ChannelId)>, PoiseError> {
+    let mut channels =
+        ChannelRestrictionsTable::new(db_pool.get()?).all_channel_ids(ChannelKind::Voice.key())?;
+    channels.extend(
+        GuildSettingsTable::new(db_pool.get()?)
+            .sotd_guild_settings()?
+            .into_iter()
+            .filter_map(|settings| Some((settings.guild_id, settings.sotd_voice_channel_id?))),
+    );
+    channels.sort();
+    channels.dedup();
+
+    Ok(channels
+        .into_iter()
+        .filter(|(channel_guild_id, _)| {
+            guild_id.is_none_or(|guild_id| guild_id.get() == *channel_guild_id)
+        })
+        .map(|(guild_id, channel_id)| (GuildId::new(guild_id), ChannelId::new(channel_id)))
+        .collect())
+}
+
+/// Token is valid when the bot user can be fetched with it
+async fn check_token(http: &Http, lang: Language) -> (CheckResult, Option<UserId>) {
+    match http.get_current_user().await {
+        Ok(user) => (
+            CheckResult::new(
+                Check::Token,
+                CheckStatus::Pass,
+                tr(lang, "doctor.token_pass", &[("user", &user.name)]),
+            ),
+            Some(user.id),
+        ),
+        Err(err) => (
+            CheckResult::new(Check::Token, CheckStatus::Fail, err.to_string()),
+            None,
+        ),
+    }
+}
+
+/// The message content intent the bot requests is privileged, so it must be enabled in the
+/// Discord developer portal. The other intents aren't privileged
+async fn check_intents(http: &Http, lang: Language) -> CheckResult {
+    match message_content_intent_enabled(http).await {
+        Err(err) => CheckResult::new(Check::Intents, CheckStatus::Fail, err.to_string()),
+        Ok(true) => CheckResult::new(
+            Check::Intents,
+            CheckStatus::Pass,
+            tr(lang, "doctor.intents_pass", &[]),
+        ),
+        Ok(false) => CheckResult::new(
+            Check::Intents,
+            CheckStatus::Fail,
+            tr(lang, "doctor.intents_fail", &[]),
+        ),
+    }
+}
+
+/// Whether the privileged message content intent is enabled for the bot's application
+pub async fn message_content_intent_enabled(http: &Http) -> Result<bool, PoiseError> {
+    let info = http.get_current_application_info().await?;
+    Ok(message_content_enabled(info.flags.unwrap_or_default()))
+}
+
+fn message_content_enabled(flags: ApplicationFlags) -> bool {
+    flags.intersects(
+        ApplicationFlags::GATEWAY_MESSAGE_CONTENT
+            | ApplicationFlags::GATEWAY_MESSAGE_CONTENT_LIMITED,
+    )
+}
+
+async fn check_voice_permissions(
+    http: &Http,
+    bot_user_id: UserId,
+    channels: &[(GuildId, ChannelId)],
+    lang: Language,
+) -> CheckResult {
+    if channels.is_empty() {
+        return CheckResult::new(
+            Check::VoicePermissions,
+            CheckStatus::Skip,
+            tr(lang, "doctor.voice_none", &[]),
+        );
+    }
+
+    let mut failed = vec![];
+    for (guild_id, channel_id) in channels {
+        match can_play_in(http, bot_user_id, *guild_id, *channel_id).await {
+            Ok(true) => {}
+            Ok(false) => failed.push(format!("<#{channel_id}>")),
+            Err(err) => failed.push(format!("<#{channel_id}> ({err})")),
+        }
+    }
+
+    match failed.is_empty() {
+        true => CheckResult::new(
+            Check::VoicePermissions,
+            CheckStatus::Pass,
+            tr(lang, "doctor.voice_pass", &[("channels", &channels.len())]),
+        ),
+        false => CheckResult::new(
+            Check::VoicePermissions,
+            CheckStatus::Fail,
+            tr(
+                lang,
+                "doctor.voice_fail",
+                &[("channels", &failed.join(", "))],
+            ),
+        ),
+    }
+}
+
+/// Whether the bot may connect and speak in voice channel
+async fn can_play_in(
+    http: &Http,
+    bot_user_id: UserId,
+    guild_id: GuildId,
+    channel_id: ChannelId,
+) -> Result<bool, PoiseError> {
+    let guild = http.get_guild(guild_id).await?;
+    let member = http.get_member(guild_id, bot_user_id).await?;
+    let channel = http
+        .get_channel(channel_id)
+        .await?
+        .guild()
+        .ok_or("Not a guild channel")?;
+
+    let permissions = guild.user_permissions_in(&channel, &member);
+    Ok(permissions.connect() && permissions.speak())
+}
+
+fn check_schema_version(conn: &DbConnection, lang: Language) -> CheckResult {
+    let expected = db::latest_schema_version();
+    match db::schema_version(conn) {
+        Ok(version) if version == expected => CheckResult::new(
+            Check::SchemaVersion,
+            CheckStatus::Pass,
+            tr(lang, "doctor.schema_pass", &[("version", &version)]),
+        ),
+        Ok(version) => CheckResult::new(
+            Check::SchemaVersion,
+            CheckStatus::Fail,
+            tr(
+                lang,
+                "doctor.schema_fail",
+                &[("version", &version), ("expected", &expected)],
+            ),
+        ),
+        Err(err) => CheckResult::new(Check::SchemaVersion, CheckStatus::Fail, err.to_string()),
+    }
+}
+
+fn check_disk_space(audio_dir: &path::Path, lang: Language) -> CheckResult {
+    let free = match fs2::available_space(audio_dir) {
+        Ok(free) => free,
+        Err(err) => {
+            return CheckResult::new(
+                Check::DiskSpace,
+                CheckStatus::Fail,
+                format!("{} - {err}", audio_dir.display()),
+            )
+        }
+    };
+
+    let detail = tr(
+        lang,
+        "doctor.disk_space_free",
+        &[
+            ("free", &helpers::format_file_size(free)),
+            (
+                "min",
+                &helpers::format_file_size(vars::DOCTOR_MIN_FREE_DISK_BYTES),
+            ),
+        ],
+    );
+    match free >= vars::DOCTOR_MIN_FREE_DISK_BYTES {
+        true => CheckResult::new(Check::DiskSpace, CheckStatus::Pass, detail),
+        false => CheckResult::new(Check::DiskSpace, CheckStatus::Fail, detail),
+    }
+}
+
+/// Tool is available when running it with its version flag succeeds
+async fn check_tool(check: Check, program: &str, version_arg: &str, lang: Language) -> CheckResult {
+    let output = tokio::time::timeout(
+        Duration::from_secs(vars::DOCTOR_TOOL_TIMEOUT_SECS),
+        tokio::process::Command::new(program)
+            .arg(version_arg)
+            .kill_on_drop(true)
+            .output(),
+    )
+    .await;
+
+    match output {
+        Ok(Ok(output)) if output.status.success() => CheckResult::new(
+            check,
+            CheckStatus::Pass,
+            first_line(&String::from_utf8_lossy(&output.stdout)),
+        ),
+        _ => CheckResult::new(
+            check,
+            CheckStatus::Fail,
+            tr(lang, "doctor.tool_missing", &[("program", &program)]),
+        ),
+    }
+}
+
+fn first_line(text: &str) -> String {
+    text.lines().next().unwrap_or_default().trim().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::TestDb;
+
+    use super::*;
+
+    #[test]
+    fn report_test() {
+        let mut report = Report {
+            results: vec![
+                CheckResult::new(Check::Token, CheckStatus::Pass, "Logged in as Bot"),
+                CheckResult::new(Check::VoicePermissions, CheckStatus::Skip, "-"),
+            ],
+            lang: Language::English,
+        };
+        assert!(report.is_healthy());
+        assert_eq!(
+            "[PASS] Discord token - Logged in as Bot\n[SKIP] Voice permissions - -",
+            report.to_text()
+        );
+
+        report.results.push(CheckResult::new(
+            Check::Ffmpeg,
+            CheckStatus::Fail,
+            "Not found",
+        ));
+        assert!(!report.is_healthy());
+    }
+
+    #[test]
+    fn message_content_enabled_test() {
+        assert!(!message_content_enabled(ApplicationFlags::empty()));
+        assert!(message_content_enabled(
+            ApplicationFlags::GATEWAY_MESSAGE_CONTENT_LIMITED
+        ));
+        assert!(message_content_enabled(
+            ApplicationFlags::GATEWAY_MESSAGE_CONTENT | ApplicationFlags::GATEWAY_PRESENCE
+        ));
+    }
+
+    #[test]
+    fn configured_voice_channels_test() {
+        let db = TestDb::new();
+        let data = db.user_data();
+        data.channel_restrictions_table()
+            .allow(1, ChannelKind::Voice.key(), 10)
+            .unwrap();
+        data.channel_restrictions_table()
+            .allow(1, ChannelKind::Text.key(), 11)
+            .unwrap();
+        data.channel_restrictions_table()
+            .allow(2, ChannelKind::Voice.key(), 20)
+            .unwrap();
+        data.guild_settings_table()
+            .update_guild_settings(&db::GuildSettingsRow {
+                guild_id: 1,
+                sotd_channel_id: Some(11),
+                sotd_voice_channel_id: Some(12),
+                ..Default::default()
+            })
+            .unwrap();
+
+        assert_eq!(
+            vec![
+                (GuildId::new(1), ChannelId::new(10)),
+                (GuildId::new(1), ChannelId::new(12))
+            ],
+            configured_voice_channels(&data.db_pool, Some(GuildId::new(1))).unwrap()
+        );
+        assert_eq!(
+            3,
+            configured_voice_channels(&data.db_pool, None)
+                .unwrap()
+                .len()
+        );
+    }
+
+    #[test]
+    fn check_schema_version_test() {
+        let db = TestDb::new();
+        let conn = db.user_data().db_connection();
+        assert_eq!(
+            CheckStatus::Pass,
+            check_schema_version(&conn, Language::English).status
+        );
+
+        conn.execute_batch("PRAGMA user_version = 1;").unwrap();
+        let result = check_schema_version(&conn, Language::English);
+        assert_eq!(CheckStatus::Fail, result.status);
+        assert!(result.detail.starts_with("Version 1, expected"));
+    }
+
+    #[tokio::test]
+    async fn check_tool_test() {
+        let result = check_tool(
+            Check::YtDlp,
+            "surely-not-an-installed-program",
+            "--version",
+            Language::English,
+        )
+        .await;
+        assert_eq!(CheckStatus::Fail, result.status);
+    }
+}
@@ -0,0 +1,151 @@
+//! Domains sounds may be downloaded from by `/sounds add` and `/sounds refetch`
+use crate::config::Config;
+use crate::i18n::{tr, Language};
+use crate::vars;
+
+/// Why a download url is refused
+#[derive(Debug, Clone, PartialEq)]
+pub enum UrlRefusal {
+    /// Not an http(s) url with a host
+    Invalid,
+    /// Host is on the blocklist
+    Blocked(String),
+    /// Host isn't on the allowlist
+    NotAllowed(String),
+}
+
+impl UrlRefusal {
+    pub fn message(&self, lang: Language) -> String {
+        match self {
+            Self::Invalid => tr(lang, "sounds.url_invalid", &[]),
+            Self::Blocked(host) => tr(lang, "sounds.url_blocked", &[("host", host)]),
+            Self::NotAllowed(host) => tr(lang, "sounds.url_not_allowed", &[("host", host)]),
+        }
+    }
+}
+
+/// Check url may be downloaded from. Blocked domains win, an empty allowlist allows all
+pub fn check_url(config: &Config, url: &str) -> Result<(), UrlRefusal> {
+    let url = reqwest::Url::parse(url.trim()).map_err(|_| UrlRefusal::Invalid)?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(UrlRefusal::Invalid);
+    }
+    let host = url
+        .host_str()
+        .map(|host| host.trim_end_matches('.').to_lowercase())
+        .filter(|host| !host.is_empty())
+        .ok_or(UrlRefusal::Invalid)?;
+
+    let matches = |domains: &[String]| domains.iter().any(|domain| matches_domain(&host, domain));
+    if matches(&config.download_blocked_domains) {
+        log::info!("Refusing download from blocked host - {host}");
+        return Err(UrlRefusal::Blocked(host));
+    }
+    if !config.download_allowed_domains.is_empty() && !matches(&config.download_allowed_domains) {
+        log::info!("Refusing download from host not allowed - {host}");
+        return Err(UrlRefusal::NotAllowed(host));
+    }
+
+    Ok(())
+}
+
+/// Redirect policy checking every hop with `check_url`
+pub fn redirect_policy(config: &Config) -> reqwest::redirect::Policy {
+    let config = config.clone();
+    reqwest::redirect::Policy::custom(move |attempt| {
+        if attempt.previous().len() >= vars::DOWNLOAD_MAX_REDIRECTS {
+            return attempt.error("Too many redirects");
+        }
+        match check_url(&config, attempt.url().as_str()) {
+            Ok(()) => attempt.follow(),
+            Err(refusal) => attempt.error(format!("Redirect refused - {refusal:?}")),
+        }
+    })
+}
+
+/// Host is domain or one of its subdomains
+fn matches_domain(host: &str, domain: &str) -> bool {
+    host == domain
+        || host
+            .strip_suffix(domain)
+            .is_some_and(|subdomain| subdomain.ends_with('.'))
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    use super::*;
+
+    fn config(allowed: &[&str], blocked: &[&str]) -> Config {
+        Config {
+            download_allowed_domains: allowed.iter().map(|domain| domain.to_string()).collect(),
+            download_blocked_domains: blocked.iter().map(|domain| domain.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn matches_domain_test() {
+        assert!(matches_domain("example.com", "example.com"));
+        assert!(matches_domain("cdn.example.com", "example.com"));
+        assert!(!matches_domain("badexample.com", "example.com"));
+        assert!(!matches_domain("example.com", "cdn.example.com"));
+    }
+
+    #[test]
+    fn check_url_test() {
+        let open = config(&[], &[]);
+        assert_eq!(Ok(()), check_url(&open, "https://example.com/a.mp3"));
+        assert_eq!(Err(UrlRefusal::Invalid), check_url(&open, "nope"));
+        assert_eq!(
+            Err(UrlRefusal::Invalid),
+            check_url(&open, "file:///etc/passwd")
+        );
+
+        let restricted = config(&["example.com"], &["bad.example.com"]);
+        assert_eq!(
+            Ok(()),
+            check_url(&restricted, "https://CDN.Example.com./a.mp3")
+        );
+        assert_eq!(
+            Err(UrlRefusal::Blocked("bad.example.com".into())),
+            check_url(&restricted, "http://bad.example.com/a.mp3")
+        );
+        assert_eq!(
+            Err(UrlRefusal::NotAllowed("other.org".into())),
+            check_url(&restricted, "https://other.org/a.mp3")
+        );
+    }
+
+    #[tokio::test]
+    async fn redirect_policy_test() {
+        // answers every request with a redirect to a blocked host
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let mut request = [0; 1024];
+                let _ = stream.read(&mut request).await;
+                let response = "HTTP/1.1 302 Found\r\nLocation: http://bad.example.com/a.mp3\r\nContent-Length: 0\r\n\r\n";
+                let _ = stream.write_all(response.as_bytes()).await;
+            }
+        });
+
+        let client = reqwest::Client::builder()
+            .redirect(redirect_policy(&config(&[], &["bad.example.com"])))
+            .build()
+            .unwrap();
+
+        let err = client
+            .get(format!("http://{addr}/a.mp3"))
+            .send()
+            .await
+            .unwrap_err();
+        assert!(err.is_redirect());
+        assert_eq!(
+            r#"Redirect refused - Blocked("bad.example.com")"#,
+            std::error::Error::source(&err).unwrap().to_string()
+        );
+    }
+}
@@ -0,0 +1,176 @@
+//! Reporting panics and ERROR-level log records to Sentry, or a compatible service, with the
+//! `sentry` feature. Reports never include message content or user ids
+#[cfg(feature = "sentry")]
+use std::sync::Arc;
+
+use crate::commands::PoiseError;
+use crate::config::Config;
+
+/// Where an error happened, attached to reports of errors logged within `with_context`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ErrorContext {
+    pub guild_id: Option<u64>,
+    /// Qualified name of the command, or name of the button interaction
+    pub command: Option<String>,
+    /// Name or id of the sound involved
+    pub sound: Option<String>,
+}
+
+impl ErrorContext {
+    /// Context of a failed command. Its `track` option is included as the sound, other options
+    /// and prefix command arguments aren't
+    pub fn from_command(ctx: crate::commands::PoiseContext<'_>) -> Self {
+        let sound = match ctx {
+            poise::Context::Application(ctx) => ctx
+                .args
+                .iter()
+                .find(|option| option.name == "track")
+                .and_then(|option| match &option.value {
+                    serenity::all::ResolvedValue::String(track) => Some(track.to_string()),
+                    _ => None,
+                }),
+            poise::Context::Prefix(_) => None,
+        };
+
+        Self {
+            guild_id: ctx.guild_id().map(|guild_id| guild_id.get()),
+            command: Some(ctx.command().qualified_name.clone()),
+            sound,
+        }
+    }
+}
+
+/// Start reporting panics and errors to the configured DSN. A no-op without one
+#[cfg(feature = "sentry")]
+pub fn init(config: &Config) -> Result<(), PoiseError> {
+    let Some(dsn) = &config.sentry_dsn else {
+        return Ok(());
+    };
+    log::info!("Reporting errors to Sentry");
+
+    let client = transport_client(config)?;
+    let transport = move |options: &sentry::ClientOptions| {
+        Arc::new(sentry::transports::ReqwestHttpTransport::with_client(
+            options,
+            client.clone(),
+        )) as Arc<dyn sentry::Transport>
+    };
+
+    let guard = sentry::init((
+        dsn.as_str(),
+        sentry::ClientOptions {
+            release: sentry::release_name!(),
+            send_default_pii: false,
+            transport: Some(Arc::new(transport)),
+            ..Default::default()
+        },
+    ));
+    if !guard.is_enabled() {
+        return Err("Invalid Sentry DSN".into());
+    }
+    // the client stays bound until `shutdown`
+    std::mem::forget(guard);
+
+    Ok(())
+}
+
+/// Client sending reports through the configured proxy and CA bundle, like `http_client::client`
+#[cfg(feature = "sentry")]
+fn transport_client(config: &Config) -> Result<sentry_reqwest::Client, PoiseError> {
+    let mut builder = sentry_reqwest::Client::builder();
+
+    if let Some(proxy_url) = &config.http_proxy {
+        builder = builder.proxy(sentry_reqwest::Proxy::all(proxy_url)?);
+    }
+    if let Some(ca_bundle) = &config.http_ca_bundle {
+        let pem = std::fs::read(ca_bundle)
+            .map_err(|err| format!("Failed to read CA bundle {} - {err}", ca_bundle.display()))?;
+        for certificate in sentry_reqwest::Certificate::from_pem_bundle(&pem)? {
+            builder = builder.add_root_certificate(certificate);
+        }
+    }
+
+    Ok(builder.build()?)
+}
+
+/// Start reporting panics and errors to the configured DSN. Config validation rejects DSNs
+/// without the `sentry` feature, so this is a no-op
+#[cfg(not(feature = "sentry"))]
+pub fn init(_config: &Config) -> Result<(), PoiseError> {
+    Ok(())
+}
+
+/// Report ERROR-level log message, with the context of the enclosing `with_context` if any
+pub fn report_error(_message: &str) {
+    #[cfg(feature = "sentry")]
+    sentry::capture_message(_message, sentry::Level::Error);
+}
+
+/// Run f with context attached to errors reported while it runs. Errors must be logged
+/// synchronously within f, context isn't carried across awaits
+pub fn with_context<R>(context: &ErrorContext, f: impl FnOnce() -> R) -> R {
+    #[cfg(feature = "sentry")]
+    return sentry::with_scope(
+        |scope| {
+            if let Some(guild_id) = context.guild_id {
+                scope.set_tag("guild_id", guild_id);
+            }
+            if let Some(command) = &context.command {
+                scope.set_tag("command", command);
+            }
+            if let Some(sound) = &context.sound {
+                scope.set_tag("sound", sound);
+            }
+        },
+        f,
+    );
+
+    #[cfg(not(feature = "sentry"))]
+    {
+        let _ = context;
+        f()
+    }
+}
+
+/// Send reports not sent yet, before the bot exits
+pub fn shutdown() {
+    #[cfg(feature = "sentry")]
+    if let Some(client) = sentry::Hub::current().client() {
+        client.close(Some(std::time::Duration::from_secs(
+            crate::vars::SENTRY_SHUTDOWN_TIMEOUT_SECS,
+        )));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn with_context_test() {
+        let context = ErrorContext {
+            guild_id: Some(1),
+            command: Some("play".into()),
+            sound: Some("Airhorn".into()),
+        };
+        assert_eq!(3, with_context(&context, || 1 + 2));
+    }
+
+    #[cfg(feature = "sentry")]
+    #[test]
+    fn transport_client_test() {
+        assert!(transport_client(&Config::default()).is_ok());
+
+        let config = Config {
+            http_proxy: Some("http://proxy.internal:3128".into()),
+            ..Default::default()
+        };
+        assert!(transport_client(&config).is_ok());
+
+        let config = Config {
+            http_ca_bundle: Some("missing.pem".into()),
+            ..Default::default()
+        };
+        assert!(transport_client(&config).is_err());
+    }
+}
@@ -7,4 +7,32 @@ pub enum AudioError {
     AudioTrackNotFound { track: String },
     #[error("Bot not in voice channel.")]
     NotInVoiceChannel,
+    #[error("Playing sounds too fast. Retry after {retry_after:?}")]
+    RateLimited { retry_after: std::time::Duration },
+    #[error("No sound played yet to replay")]
+    NothingToReplay,
+    #[error("No sound playing")]
+    NothingPlaying,
+    #[error("Audio file of `{track}` failed checksum verification and was quarantined")]
+    Quarantined { track: String },
+    #[error("Audio file of `{track}` failed checksum verification")]
+    ChecksumMismatch { track: String },
+}
+
+#[derive(Error, Debug, PartialEq)]
+pub enum SoundNameError {
+    #[error("Sound name is empty")]
+    Empty,
+    #[error("Sound name is {len} chars long, exceeding max of {max} chars")]
+    TooLong { len: usize, max: usize },
+    #[error("Sound name contains invalid character {0:?}")]
+    InvalidChar(char),
+}
+
+#[derive(Error, Debug)]
+pub enum InsertAudioError {
+    #[error("Sound name `{name}` is already taken")]
+    NameTaken { name: String },
+    #[error("Failed to insert audio track - {0}")]
+    Sql(#[from] rusqlite::Error),
 }
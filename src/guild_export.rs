@@ -0,0 +1,439 @@
+//! Export and import of a guild's configuration, for `/settings export` and `/settings import`.
+//! The export is a JSON document of the guild's settings, role permissions, channel
+//! restrictions, blocklist and native sound mappings, so a guild can be moved to another bot
+//! instance or restored after a reset. Sounds aren't part of it. Native sound mappings refer to
+//! sounds by name, as sound ids differ between instances
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::channel_restrictions::ChannelKind;
+use crate::commands::PoiseError;
+use crate::common::{LogResult, UserData};
+use crate::db::{BlocklistTableRow, NativeSoundsTableRow, UniqueAudioTableCol};
+use crate::i18n::{tr, Language};
+use crate::permissions::Permission;
+use crate::vars;
+
+/// Format of `GuildSettingsRow::sotd_time` in exports
+const TIME_FORMAT: &str = "%H:%M";
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GuildExport {
+    /// Export format version, see `vars::GUILD_EXPORT_VERSION`
+    pub version: u32,
+    pub settings: ExportedSettings,
+    /// Role ids granted each permission, by permission key (see `Permission::key`)
+    pub permissions: BTreeMap<String, Vec<u64>>,
+    /// Allowed channel ids, by channel kind key (see `ChannelKind::key`)
+    pub channels: BTreeMap<String, Vec<u64>>,
+    pub blocklist: Vec<ExportedBlockedPattern>,
+    pub native_sounds: Vec<ExportedNativeSound>,
+}
+
+/// Guild settings, without sound of the day and digest state
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExportedSettings {
+    pub language: Option<String>,
+    pub prefix: Option<String>,
+    pub sotd_channel_id: Option<u64>,
+    pub sotd_voice_channel_id: Option<u64>,
+    /// UTC time of day like `04:30`
+    pub sotd_time: Option<String>,
+    pub sotd_mode: Option<String>,
+    pub record_native_sounds: bool,
+    pub auto_archive_months: Option<u32>,
+    pub review_channel_id: Option<u64>,
+    pub digest_channel_id: Option<u64>,
+    pub priority_mode: Option<String>,
+    pub autocomplete_limit: Option<u32>,
+    pub autocomplete_min_query_len: Option<u32>,
+    pub silent_play: bool,
+    pub soundboard_order: Option<String>,
+    pub play_history_public: bool,
+    pub loudness_action: Option<String>,
+    pub max_duration_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExportedBlockedPattern {
+    pub pattern: String,
+    #[serde(default)]
+    pub is_regex: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExportedNativeSound {
+    /// Discord soundboard sound id
+    pub sound_id: String,
+    /// Name of the bot sound the native sound counts as
+    pub sound: String,
+}
+
+/// What an import applied
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ImportSummary {
+    pub permissions: usize,
+    pub channels: usize,
+    pub blocked_patterns: usize,
+    pub native_sounds: usize,
+    /// Sounds of native sound mappings this instance doesn't have, so weren't mapped
+    pub missing_sounds: Vec<String>,
+}
+
+impl GuildExport {
+    pub fn to_json(&self) -> Result<String, PoiseError> {
+        Ok(serde_json::to_string_pretty(self).log_err_msg("Failed to serialize guild export")?)
+    }
+
+    /// Parse export, rejecting exports of newer versions than this bot understands
+    pub fn from_json(json: &str) -> Result<Self, PoiseError> {
+        let export: Self =
+            serde_json::from_str(json).log_err_msg("Failed to parse guild export")?;
+        if export.version > vars::GUILD_EXPORT_VERSION {
+            return Err(format!(
+                "Export version {} is newer than supported version {}",
+                export.version,
+                vars::GUILD_EXPORT_VERSION
+            )
+            .into());
+        }
+
+        Ok(export)
+    }
+}
+
+pub fn export_guild(data: &UserData, guild_id: u64) -> Result<GuildExport, PoiseError> {
+    log::info!("Exporting guild settings. Guild Id: {guild_id}");
+
+    let settings = data.guild_settings_table().get_guild_settings(guild_id)?;
+
+    let permissions_table = data.permissions_table();
+    let mut permissions = BTreeMap::new();
+    for permission in Permission::ALL {
+        let role_ids = permissions_table.role_ids(guild_id, permission.key())?;
+        if !role_ids.is_empty() {
+            permissions.insert(permission.key().to_string(), role_ids);
+        }
+    }
+
+    let channel_restrictions_table = data.channel_restrictions_table();
+    let mut channels = BTreeMap::new();
+    for kind in ChannelKind::ALL {
+        let channel_ids = channel_restrictions_table.channel_ids(guild_id, kind.key())?;
+        if !channel_ids.is_empty() {
+            channels.insert(kind.key().to_string(), channel_ids);
+        }
+    }
+
+    let blocklist = data
+        .blocklist_table()
+        .patterns(guild_id)?
+        .into_iter()
+        .map(|row| ExportedBlockedPattern {
+            pattern: row.pattern,
+            is_regex: row.is_regex,
+        })
+        .collect();
+
+    let audio_table = data.audio_table();
+    let native_sounds = data
+        .native_sounds_table()
+        .mappings(guild_id)?
+        .into_iter()
+        .filter_map(|row| {
+            let audio_row = audio_table.find_audio_row(UniqueAudioTableCol::Id(row.audio_id))?;
+            Some(ExportedNativeSound {
+                sound_id: row.sound_id,
+                sound: audio_row.name.to_string(),
+            })
+        })
+        .collect();
+
+    Ok(GuildExport {
+        version: vars::GUILD_EXPORT_VERSION,
+        settings: ExportedSettings {
+            language: settings.language,
+            prefix: settings.prefix,
+            sotd_channel_id: settings.sotd_channel_id,
+            sotd_voice_channel_id: settings.sotd_voice_channel_id,
+            sotd_time: settings
+                .sotd_time
+                .map(|time| time.format(TIME_FORMAT).to_string()),
+            sotd_mode: settings.sotd_mode,
+            record_native_sounds: settings.record_native_sounds,
+            auto_archive_months: settings.auto_archive_months,
+            review_channel_id: settings.review_channel_id,
+            digest_channel_id: settings.digest_channel_id,
+            priority_mode: settings.priority_mode,
+            autocomplete_limit: settings.autocomplete_limit,
+            autocomplete_min_query_len: settings.autocomplete_min_query_len,
+            silent_play: settings.silent_play,
+            soundboard_order: settings.soundboard_order,
+            play_history_public: settings.play_history_public,
+            loudness_action: settings.loudness_action,
+            max_duration_ms: settings.max_duration_ms,
+        },
+        permissions,
+        channels,
+        blocklist,
+        native_sounds,
+    })
+}
+
+/// Replace guild's configuration with export. Unknown permissions and channel kinds are
+/// skipped, as are native sound mappings of sounds this instance doesn't have
+pub fn import_guild(
+    data: &UserData,
+    guild_id: u64,
+    export: &GuildExport,
+) -> Result<ImportSummary, PoiseError> {
+    log::info!("Importing guild settings. Guild Id: {guild_id}");
+    let mut summary = ImportSummary::default();
+
+    let sotd_time = match &export.settings.sotd_time {
+        Some(time) => Some(
+            chrono::NaiveTime::parse_from_str(time, TIME_FORMAT)
+                .log_err_msg("Invalid sound of the day time in guild export")?,
+        ),
+        None => None,
+    };
+    let settings_table = data.guild_settings_table();
+    let mut settings = settings_table.get_guild_settings(guild_id)?;
+    let exported = export.settings.clone();
+    settings.language = exported.language;
+    settings.prefix = exported.prefix;
+    settings.sotd_channel_id = exported.sotd_channel_id;
+    settings.sotd_voice_channel_id = exported.sotd_voice_channel_id;
+    settings.sotd_time = sotd_time;
+    settings.sotd_mode = exported.sotd_mode;
+    settings.record_native_sounds = exported.record_native_sounds;
+    settings.auto_archive_months = exported.auto_archive_months;
+    settings.review_channel_id = exported.review_channel_id;
+    settings.digest_channel_id = exported.digest_channel_id;
+    settings.priority_mode = exported.priority_mode;
+    settings.autocomplete_limit = exported.autocomplete_limit;
+    settings.autocomplete_min_query_len = exported.autocomplete_min_query_len;
+    settings.silent_play = exported.silent_play;
+    settings.soundboard_order = exported.soundboard_order;
+    settings.play_history_public = exported.play_history_public;
+    settings.loudness_action = exported.loudness_action;
+    settings.max_duration_ms = exported.max_duration_ms;
+    settings_table.update_guild_settings(&settings)?;
+
+    let permissions_table = data.permissions_table();
+    for permission in Permission::ALL {
+        for role_id in permissions_table.role_ids(guild_id, permission.key())? {
+            permissions_table.revoke(guild_id, permission.key(), role_id)?;
+        }
+    }
+    for (key, role_ids) in &export.permissions {
+        let Some(permission) = Permission::from_key(key) else {
+            log::warn!("Skipping unknown permission of guild export - {key}");
+            continue;
+        };
+        for role_id in role_ids {
+            permissions_table.grant(guild_id, permission.key(), *role_id)?;
+            summary.permissions += 1;
+        }
+    }
+
+    let channel_restrictions_table = data.channel_restrictions_table();
+    for kind in ChannelKind::ALL {
+        for channel_id in channel_restrictions_table.channel_ids(guild_id, kind.key())? {
+            channel_restrictions_table.disallow(guild_id, kind.key(), channel_id)?;
+        }
+    }
+    for (key, channel_ids) in &export.channels {
+        let Some(kind) = ChannelKind::from_key(key) else {
+            log::warn!("Skipping unknown channel kind of guild export - {key}");
+            continue;
+        };
+        for channel_id in channel_ids {
+            channel_restrictions_table.allow(guild_id, kind.key(), *channel_id)?;
+            summary.channels += 1;
+        }
+    }
+
+    let blocklist_table = data.blocklist_table();
+    for row in blocklist_table.patterns(guild_id)? {
+        blocklist_table.remove_pattern(guild_id, &row.pattern)?;
+    }
+    for blocked in &export.blocklist {
+        blocklist_table.add_pattern(
+            guild_id,
+            &BlocklistTableRow {
+                pattern: blocked.pattern.clone(),
+                is_regex: blocked.is_regex,
+            },
+        )?;
+        summary.blocked_patterns += 1;
+    }
+
+    let native_sounds_table = data.native_sounds_table();
+    for row in native_sounds_table.mappings(guild_id)? {
+        native_sounds_table.unmap_sound(guild_id, &row.sound_id)?;
+    }
+    let audio_table = data.audio_table();
+    for native_sound in &export.native_sounds {
+        let Some(audio_row) =
+            audio_table.find_audio_row(UniqueAudioTableCol::Name(native_sound.sound.clone()))
+        else {
+            summary.missing_sounds.push(native_sound.sound.clone());
+            continue;
+        };
+        native_sounds_table.map_sound(
+            guild_id,
+            &NativeSoundsTableRow {
+                sound_id: native_sound.sound_id.clone(),
+                audio_id: audio_row.id,
+            },
+        )?;
+        summary.native_sounds += 1;
+    }
+
+    Ok(summary)
+}
+
+/// Reply to `/settings import`
+pub fn summary_message(lang: Language, summary: &ImportSummary) -> String {
+    let mut message = tr(
+        lang,
+        "settings.imported",
+        &[
+            ("permissions", &summary.permissions),
+            ("channels", &summary.channels),
+            ("patterns", &summary.blocked_patterns),
+            ("native_sounds", &summary.native_sounds),
+        ],
+    );
+    if !summary.missing_sounds.is_empty() {
+        let sounds = summary
+            .missing_sounds
+            .iter()
+            .map(|sound| format!("`{sound}`"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        message.push('\n');
+        message.push_str(&tr(
+            lang,
+            "settings.import_missing_sounds",
+            &[("sounds", &sounds)],
+        ));
+    }
+
+    message
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::{AudioRowFixture, TestDb};
+
+    use super::*;
+
+    #[test]
+    fn export_import_test() {
+        let db = TestDb::new();
+        let data = db.user_data();
+        let beep = AudioRowFixture::new()
+            .name("Beep")
+            .insert(&db.audio_table());
+
+        let settings_table = data.guild_settings_table();
+        let mut settings = settings_table.get_guild_settings(1).unwrap();
+        settings.prefix = Some("!".into());
+        settings.sotd_time = chrono::NaiveTime::from_hms_opt(4, 30, 0);
+        settings.sotd_last_audio_id = Some(beep.id);
+        settings.auto_archive_months = Some(3);
+        settings_table.update_guild_settings(&settings).unwrap();
+        data.permissions_table()
+            .grant(1, Permission::AddSounds.key(), 10)
+            .unwrap();
+        data.channel_restrictions_table()
+            .allow(1, ChannelKind::Voice.key(), 20)
+            .unwrap();
+        data.blocklist_table()
+            .add_pattern(
+                1,
+                &BlocklistTableRow {
+                    pattern: "bad".into(),
+                    is_regex: false,
+                },
+            )
+            .unwrap();
+        data.native_sounds_table()
+            .map_sound(
+                1,
+                &NativeSoundsTableRow {
+                    sound_id: "30".into(),
+                    audio_id: beep.id,
+                },
+            )
+            .unwrap();
+
+        let export = export_guild(&data, 1).unwrap();
+        assert_eq!(Some("04:30"), export.settings.sotd_time.as_deref());
+        assert_eq!(
+            vec![ExportedNativeSound {
+                sound_id: "30".into(),
+                sound: "Beep".into()
+            }],
+            export.native_sounds
+        );
+        let json = export.to_json().unwrap();
+        assert_eq!(export, GuildExport::from_json(&json).unwrap());
+
+        // import replaces existing configuration
+        data.permissions_table()
+            .grant(2, Permission::DeleteSounds.key(), 11)
+            .unwrap();
+        let mut export = GuildExport::from_json(&json).unwrap();
+        export.native_sounds.push(ExportedNativeSound {
+            sound_id: "31".into(),
+            sound: "Boop".into(),
+        });
+        let summary = import_guild(&data, 2, &export).unwrap();
+        assert_eq!(
+            ImportSummary {
+                permissions: 1,
+                channels: 1,
+                blocked_patterns: 1,
+                native_sounds: 1,
+                missing_sounds: vec!["Boop".into()],
+            },
+            summary
+        );
+
+        let imported = settings_table.get_guild_settings(2).unwrap();
+        assert_eq!(Some("!".into()), imported.prefix);
+        assert_eq!(settings.sotd_time, imported.sotd_time);
+        assert_eq!(None, imported.sotd_last_audio_id);
+        assert!(data
+            .permissions_table()
+            .role_ids(2, Permission::DeleteSounds.key())
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            vec![10],
+            data.permissions_table()
+                .role_ids(2, Permission::AddSounds.key())
+                .unwrap()
+        );
+        assert_eq!(
+            Some(beep.id),
+            data.native_sounds_table().mapped_audio_id(2, "30").unwrap()
+        );
+    }
+
+    #[test]
+    fn from_json_test() {
+        let export = GuildExport::from_json(r#"{"version": 1}"#).unwrap();
+        assert_eq!(GuildExport::default().settings, export.settings);
+        assert!(export.permissions.is_empty());
+
+        assert!(GuildExport::from_json(r#"{"version": 999}"#).is_err());
+        assert!(GuildExport::from_json("not json").is_err());
+    }
+}
@@ -1,18 +1,35 @@
+use std::collections::HashMap;
 use std::num::ParseIntError;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
-use serenity::all::{ChannelId, CreateActionRow, CreateButton, GuildId};
+use poise::CreateReply;
+use serde::{Deserialize, Serialize};
+use serenity::all::{
+    AutocompleteChoice, ButtonStyle, ChannelId, ComponentInteractionCollector, CreateActionRow,
+    CreateButton, CreateInteractionResponse, CreateInteractionResponseMessage, GuildId,
+    ReactionType, UserId,
+};
 use serenity::async_trait;
 use serenity::{all::Message, client::Context, Result as SerenityResult};
 use songbird::tracks::TrackHandle;
-use songbird::{Songbird, SongbirdKey};
+use songbird::{
+    Event, EventContext, EventHandler as VoiceEventHandler, Songbird, SongbirdKey, TrackEvent,
+};
+use thiserror::Error;
+use unicode_segmentation::UnicodeSegmentation;
 
 use crate::audio;
-use crate::audio::TrackHandleHelper;
+use crate::cache::AutocompleteCache;
 use crate::commands::{PoiseContext, PoiseError, PoiseResult};
-use crate::common::LogResult;
-use crate::db::AudioTableRow;
-use crate::errors::AudioError;
+use crate::common::{LogResult, UserData};
+use crate::db::{AudioTableRow, AutocompleteTrack, SoundName};
+use crate::errors::{AudioError, SoundNameError};
+use crate::i18n::{self, tr, Language};
+use crate::onboarding::OnboardingStep;
+use crate::opus_cache;
+use crate::playback::{self, LoopMode, Playback, PlaybackHooks, TrackControl};
+use crate::trash;
 use crate::vars;
 
 pub async fn songbird_get(ctx: &Context) -> Arc<songbird::Songbird> {
@@ -42,36 +59,182 @@ pub fn poise_check_msg(result: Result<poise::ReplyHandle, serenity::Error>) {
     }
 }
 
-#[derive(Debug)]
-pub enum ButtonCustomId {
-    PlayAudio(i64),
-    Unknown(String),
+/// Whether command is deferred before it runs, and if so ephemerally (see
+/// `vars::DEFERRED_COMMANDS`)
+pub fn deferred_command(qualified_name: &str) -> Option<bool> {
+    vars::DEFERRED_COMMANDS
+        .iter()
+        .find(|(name, _)| *name == qualified_name)
+        .map(|(_, ephemeral)| *ephemeral)
+}
+
+/// Runs before every command. Defers slow commands, so Discord doesn't show "application did
+/// not respond" while they download or scan
+pub async fn defer_slow_command(ctx: PoiseContext<'_>) {
+    let result = match deferred_command(&ctx.command().qualified_name) {
+        Some(true) => ctx.defer_ephemeral().await,
+        Some(false) => ctx.defer().await,
+        None => return,
+    };
+
+    result
+        .log_err_msg(format!("Failed to defer /{}", ctx.command().qualified_name))
+        .ok();
+}
+
+/// Action performed when a bot button is pressed, with its payload
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "a", content = "p", rename_all = "snake_case")]
+pub enum ButtonAction {
+    /// Play audio track (audio table id)
+    Play(i64),
+    /// Navigate soundboard message to page, keeping its tag filter
+    PageNav {
+        page: u64,
+        #[serde(default, rename = "t", skip_serializing_if = "Option::is_none")]
+        tag: Option<String>,
+    },
+    /// Confirm pending confirmation (see `confirm`), by confirmation id
+    Confirm(u64),
+    /// Cancel pending confirmation (see `confirm`), by confirmation id
+    Cancel(u64),
+    /// Toggle audio track (audio table id) as favorite of user pressing button
+    Favorite(i64),
+    /// Filter soundboard message by tag picked in select menu
+    TagFilter,
+    /// Stop sounds playing in guild
+    Stop,
+    /// Replay last sound played in guild
+    Replay,
+    /// Toggle looping the sound playing in guild until stopped
+    Repeat,
+    /// Archive audio track (audio table id) suggested for pruning
+    Archive(i64),
+    /// Keep audio track (audio table id) suggested for pruning
+    Keep(i64),
+    /// Pick suggested sound name (see `pick_name`), by name pick id and suggestion index
+    PickName {
+        id: u64,
+        #[serde(rename = "i")]
+        index: u8,
+    },
+    /// Run setup step of the onboarding setup message
+    Onboarding(OnboardingStep),
+    /// Send audio file of pending sound (pending sounds table id) to the moderator pressing button
+    Listen(i64),
+    /// Approve pending sound (pending sounds table id), adding it to the soundboard
+    Approve(i64),
+    /// Reject pending sound (pending sounds table id), deleting it
+    Reject(i64),
+}
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ButtonCustomIdError {
+    #[error("Button custom id is {len} chars long, exceeding max of {max} chars")]
+    TooLong { len: usize, max: usize },
+    #[error("Button custom id version {0} is not supported")]
+    UnsupportedVersion(u8),
+    #[error("Malformed button custom id '{value}' - {reason}")]
+    Malformed { value: String, reason: String },
+    #[error("Button custom id belongs to guild {expected}, not guild {actual:?}")]
+    GuildMismatch { expected: u64, actual: Option<u64> },
+}
+
+/// Custom id of bot buttons, encoded as versioned json
+/// (`{"v":1,"g":<guild_id>,"a":<action>,"p":<payload>}`) so buttons on messages posted by older bot
+/// versions can still be recognized
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ButtonCustomId {
+    #[serde(rename = "v")]
+    pub version: u8,
+    #[serde(rename = "g")]
+    pub guild_id: Option<u64>,
+    #[serde(flatten)]
+    pub action: ButtonAction,
 }
 
-impl TryFrom<String> for ButtonCustomId {
-    type Error = String;
+impl ButtonCustomId {
+    pub const VERSION: u8 = 1;
+    /// Version of `play::<id>` custom ids used before custom ids were versioned
+    pub const LEGACY_VERSION: u8 = 0;
+
+    pub fn new(guild_id: Option<GuildId>, action: ButtonAction) -> Self {
+        Self {
+            version: Self::VERSION,
+            guild_id: guild_id.map(|guild_id| guild_id.get()),
+            action,
+        }
+    }
+
+    pub fn encode(&self) -> String {
+        serde_json::to_string(self).expect("ButtonCustomId serializes to json")
+    }
+
+    pub fn parse(value: impl AsRef<str>) -> Result<Self, ButtonCustomIdError> {
+        #[derive(Deserialize)]
+        struct Version {
+            #[serde(rename = "v")]
+            version: u8,
+        }
+
+        let value = value.as_ref();
+        let malformed = |reason: String| ButtonCustomIdError::Malformed {
+            value: value.into(),
+            reason,
+        };
+
+        if value.len() > vars::CUSTOM_ID_MAX_LEN {
+            return Err(ButtonCustomIdError::TooLong {
+                len: value.len(),
+                max: vars::CUSTOM_ID_MAX_LEN,
+            });
+        }
+
+        // legacy `play::<id>` custom id
+        if let Some(id) = value.strip_prefix("play::") {
+            let id: i64 = id
+                .parse()
+                .map_err(|e: ParseIntError| malformed(e.to_string()))?;
+            return Ok(Self {
+                version: Self::LEGACY_VERSION,
+                guild_id: None,
+                action: ButtonAction::Play(id),
+            });
+        }
+
+        let Version { version } =
+            serde_json::from_str(value).map_err(|e| malformed(e.to_string()))?;
+        if version != Self::VERSION {
+            return Err(ButtonCustomIdError::UnsupportedVersion(version));
+        }
+
+        serde_json::from_str(value).map_err(|e| malformed(e.to_string()))
+    }
 
-    fn try_from(value: String) -> Result<Self, Self::Error> {
-        let parts: Vec<_> = value.split("::").collect();
-        match parts[0] {
-            "play" => {
-                let id: i64 = parts[1]
-                    .parse()
-                    .map_err(|e: ParseIntError| e.to_string())
-                    .log_err_op(|e| format!("Parse error on button custom id '{value}' - {e}"))?;
-                Ok(ButtonCustomId::PlayAudio(id))
+    /// Ensure button was created for guild it was pressed in. Custom ids without a guild match any
+    /// guild
+    pub fn ensure_guild(&self, guild_id: Option<GuildId>) -> Result<(), ButtonCustomIdError> {
+        let actual = guild_id.map(|guild_id| guild_id.get());
+        match self.guild_id {
+            Some(expected) if Some(expected) != actual => {
+                Err(ButtonCustomIdError::GuildMismatch { expected, actual })
             }
-            _ => Ok(ButtonCustomId::Unknown(value)),
+            _ => Ok(()),
         }
     }
 }
 
 impl From<ButtonCustomId> for String {
     fn from(value: ButtonCustomId) -> Self {
-        match value {
-            ButtonCustomId::PlayAudio(val) => format!("play::{val}"),
-            ButtonCustomId::Unknown(val) => format!("{val}"),
-        }
+        value.encode()
+    }
+}
+
+impl TryFrom<&str> for ButtonCustomId {
+    type Error = ButtonCustomIdError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        Self::parse(value)
     }
 }
 
@@ -91,12 +254,123 @@ impl ButtonLabel for &str {
     }
 }
 
+impl ButtonLabel for SoundName {
+    fn to_button_label(&self) -> String {
+        truncate_button_label(self)
+    }
+}
+
+/// Truncate label to Discord's button label length limit. Cuts between graphemes, so emoji
+/// (which can span several chars) and accented chars are never cut in half
 pub fn truncate_button_label(label: impl AsRef<str>) -> String {
     let label = label.as_ref();
-    if label.len() > vars::BTN_LABEL_MAX_LEN {
-        format!("{}...", label[0..(vars::BTN_LABEL_MAX_LEN - 3)].to_string())
+    if label.chars().count() <= vars::BTN_LABEL_MAX_LEN {
+        return label.to_string();
+    }
+
+    let mut len = 0;
+    let truncated: String = label
+        .graphemes(true)
+        .take_while(|grapheme| {
+            len += grapheme.chars().count();
+            len <= vars::BTN_LABEL_MAX_LEN - 3
+        })
+        .collect();
+    format!("{truncated}...")
+}
+
+/// Validate sound emoji, either a single unicode emoji or a custom emoji (`<:name:id>`).
+/// Returns emoji as stored in the audio table
+pub fn parse_emoji(value: impl AsRef<str>) -> Option<String> {
+    let value = value.as_ref().trim();
+
+    match ReactionType::try_from(value).ok()? {
+        ReactionType::Custom { .. } => Some(value.to_string()),
+        ReactionType::Unicode(emoji) => {
+            let is_single_grapheme = emoji.graphemes(true).count() == 1;
+            let is_emoji = !emoji.is_ascii();
+            (is_single_grapheme && is_emoji).then_some(emoji)
+        }
+        _ => None,
+    }
+}
+
+/// Color of a sound's soundboard button, picked by its uploader to visually group sounds
+/// (e.g. red for loud sounds)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, poise::ChoiceParameter)]
+pub enum SoundButtonStyle {
+    #[default]
+    #[name = "Blurple"]
+    Primary,
+    #[name = "Grey"]
+    Secondary,
+    #[name = "Green"]
+    Success,
+    #[name = "Red"]
+    Danger,
+}
+
+impl SoundButtonStyle {
+    pub const ALL: [SoundButtonStyle; 4] = [
+        SoundButtonStyle::Primary,
+        SoundButtonStyle::Secondary,
+        SoundButtonStyle::Success,
+        SoundButtonStyle::Danger,
+    ];
+
+    /// Code stored in `AudioTableRow::button_style`
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Primary => "primary",
+            Self::Secondary => "secondary",
+            Self::Success => "success",
+            Self::Danger => "danger",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|style| style.code() == code)
+    }
+
+    pub fn button_style(&self) -> ButtonStyle {
+        match self {
+            Self::Primary => ButtonStyle::Primary,
+            Self::Secondary => ButtonStyle::Secondary,
+            Self::Success => ButtonStyle::Success,
+            Self::Danger => ButtonStyle::Danger,
+        }
+    }
+}
+
+/// Play button of sound, showing its emoji and style when set
+pub fn sound_button(track: &AudioTableRow, guild_id: Option<GuildId>) -> CreateButton {
+    let style = track
+        .button_style
+        .as_deref()
+        .and_then(SoundButtonStyle::from_code)
+        .unwrap_or_default();
+    let button = CreateButton::new(ButtonCustomId::new(guild_id, ButtonAction::Play(track.id)))
+        .label(track.name.to_button_label())
+        .style(style.button_style());
+
+    match track
+        .emoji
+        .as_deref()
+        .and_then(|emoji| ReactionType::try_from(emoji).ok())
+    {
+        Some(emoji) => button.emoji(emoji),
+        None => button,
+    }
+}
+
+/// Truncate message content to Discord's message length limit
+pub fn truncate_message(content: impl AsRef<str>) -> String {
+    let content = content.as_ref();
+    if content.chars().count() > vars::MESSAGE_MAX_LEN {
+        let truncated: String = content.chars().take(vars::MESSAGE_MAX_LEN - 3).collect();
+        format!("{truncated}...")
     } else {
-        label.to_string()
+        content.to_string()
     }
 }
 
@@ -121,25 +395,88 @@ pub fn get_author_voice_channel(ctx: &PoiseContext) -> Result<(GuildId, ChannelI
     }
 }
 
+/// Voice playback used by command and interaction handlers. Implemented for `Songbird`,
+/// and by `test_utils::MockVoice` so handlers can be tested without a discord connection
 #[async_trait]
-pub trait SongbirdHelper {
-    /// Begins play audio track and returns handle to track
+pub trait SongbirdHelper: Send + Sync {
+    /// Begins play audio track
     async fn play_audio(
         &self,
         guild_id: GuildId,
         channel_id: ChannelId,
         audio_track: &audio::AudioFile,
-    ) -> Result<TrackHandle, AudioError>;
+    ) -> Result<(), AudioError>;
 
-    /// Plays audio track all the way to the end, then returns audio track
+    /// Plays audio track all the way to the end
     async fn play_audio_to_end(
         &self,
         guild_id: GuildId,
         channel_id: ChannelId,
         audio_track: &audio::AudioFile,
-    ) -> Result<TrackHandle, AudioError>;
+    ) -> Result<(), AudioError>;
+
+    /// Plays audio track all the way to the end over the guild's playing tracks, fading
+    /// their volume down to `volume` meanwhile instead of mixing over them (ducking)
+    async fn play_audio_ducked(
+        &self,
+        guild_id: GuildId,
+        channel_id: ChannelId,
+        audio_track: &audio::AudioFile,
+        volume: f32,
+    ) -> Result<(), AudioError> {
+        let ducked = playback::PLAYING_TRACKS.duck(guild_id, volume).await;
+        let result = self
+            .play_audio_to_end(guild_id, channel_id, audio_track)
+            .await;
+        playback::PLAYING_TRACKS.restore(&ducked, volume).await;
+        result
+    }
+
+    /// Begins play audio track, calling start hooks once it plays, end hooks when it ends or is
+    /// stopped, and error hooks when it fails to play.
+    /// Defaults to `play_audio`, calling start or error hooks but never end hooks
+    async fn play_audio_with_hooks(
+        &self,
+        guild_id: GuildId,
+        channel_id: ChannelId,
+        audio_track: &audio::AudioFile,
+        hooks: PlaybackHooks,
+    ) -> Result<(), AudioError> {
+        let playback = Playback {
+            guild_id,
+            channel_id,
+            audio_file: audio_track.as_path_buf(),
+        };
+
+        match self.play_audio(guild_id, channel_id, audio_track).await {
+            Ok(()) => {
+                hooks.started(&playback);
+                Ok(())
+            }
+            Err(err) => {
+                hooks.failed(&playback, &err.to_string());
+                Err(err)
+            }
+        }
+    }
+
+    /// Loops the latest audio track played in guild, or stops looping it when None
+    async fn set_loop(&self, guild_id: GuildId, mode: Option<LoopMode>) -> Result<(), AudioError> {
+        match playback::PLAYING_TRACKS.set_loop(guild_id, mode) {
+            true => Ok(()),
+            false => Err(AudioError::NothingPlaying),
+        }
+    }
+
+    /// Pauses audio tracks playing in guild, returning them to resume
+    async fn pause_audio(&self, guild_id: GuildId) -> Vec<Arc<dyn TrackControl>> {
+        playback::PLAYING_TRACKS.pause(guild_id)
+    }
 
     async fn leave_voice_channel(&self, guild_id: GuildId) -> PoiseResult;
+
+    /// Stops all audio tracks playing in guild
+    async fn stop_audio(&self, guild_id: GuildId) -> Result<(), AudioError>;
 }
 
 #[async_trait]
@@ -164,18 +501,19 @@ impl SongbirdHelper for Songbird {
         guild_id: GuildId,
         _channel_id: ChannelId,
         audio_track: &audio::AudioFile,
-    ) -> Result<TrackHandle, AudioError> {
+    ) -> Result<(), AudioError> {
         log::debug!("Starting to play_audio_track - {audio_track:?}");
 
-        let audio_input = songbird::input::File::new(audio_track.as_path_buf());
+        let audio_input = opus_cache::audio_input(audio_track.as_path());
 
         match self.get(guild_id) {
             Some(handler_lock) => {
                 let mut handler = handler_lock.lock().await;
 
-                let track_handle = handler.play_input(audio_input.into());
+                let track_handle = handler.play_input(audio_input);
+                playback::PLAYING_TRACKS.register(guild_id, Arc::new(track_handle));
                 log::info!("Playing track {audio_track:?}");
-                Ok(track_handle)
+                Ok(())
             }
             None => Err(AudioError::NotInVoiceChannel),
         }
@@ -184,75 +522,671 @@ impl SongbirdHelper for Songbird {
     async fn play_audio_to_end(
         &self,
         guild_id: GuildId,
-        _channel_id: ChannelId,
+        channel_id: ChannelId,
+        audio_track: &audio::AudioFile,
+    ) -> Result<(), AudioError> {
+        let finished = Arc::new(tokio::sync::Notify::new());
+        let hooks = PlaybackHooks::new().notify_finished(finished.clone());
+        self.play_audio_with_hooks(guild_id, channel_id, audio_track, hooks)
+            .await?;
+
+        playback::wait_for_finish(&finished).await;
+        Ok(())
+    }
+
+    async fn play_audio_with_hooks(
+        &self,
+        guild_id: GuildId,
+        channel_id: ChannelId,
         audio_track: &audio::AudioFile,
-    ) -> Result<TrackHandle, AudioError> {
+        hooks: PlaybackHooks,
+    ) -> Result<(), AudioError> {
         log::debug!("Starting to play_audio_track - {audio_track:?}");
 
-        let audio_input = songbird::input::File::new(audio_track.as_path_buf());
+        let playback = Playback {
+            guild_id,
+            channel_id,
+            audio_file: audio_track.as_path_buf(),
+        };
+        let Some(handler_lock) = self.get(guild_id) else {
+            let err = AudioError::NotInVoiceChannel;
+            hooks.failed(&playback, &err.to_string());
+            return Err(err);
+        };
+
+        let audio_input = opus_cache::audio_input(audio_track.as_path());
+        let track_handle = handler_lock.lock().await.play_input(audio_input);
+        playback::PLAYING_TRACKS.register(guild_id, Arc::new(track_handle.clone()));
+
+        let hooks = Arc::new(hooks);
+        for event in [TrackEvent::End, TrackEvent::Error] {
+            track_handle
+                .add_event(
+                    Event::Track(event),
+                    PlaybackHooksHandler {
+                        hooks: hooks.clone(),
+                        playback: playback.clone(),
+                        event,
+                    },
+                )
+                .log_err_msg("Failed to add track event")
+                .ok();
+        }
+
+        log::info!("Playing track {audio_track:?}");
+        hooks.started(&playback);
+        Ok(())
+    }
 
+    async fn stop_audio(&self, guild_id: GuildId) -> Result<(), AudioError> {
         match self.get(guild_id) {
             Some(handler_lock) => {
-                let mut handler = handler_lock.lock().await;
+                handler_lock.lock().await.stop();
+                log::info!("Stopped audio tracks for guild_id: {guild_id}");
+                Ok(())
+            }
+            None => Err(AudioError::NotInVoiceChannel),
+        }
+    }
+}
 
-                let track_handle = handler.play_input(audio_input.into());
-                log::info!("Playing track {audio_track:?}");
+impl TrackControl for TrackHandle {
+    fn set_volume(&self, volume: f32) -> bool {
+        TrackHandle::set_volume(self, volume).is_ok()
+    }
+
+    fn set_loop(&self, mode: Option<LoopMode>) -> bool {
+        let result = match mode {
+            Some(LoopMode::Infinite) => self.enable_loop(),
+            // loops are repeats after the first play
+            Some(LoopMode::Times(times)) if times > 1 => self.loop_for(times as usize - 1),
+            Some(LoopMode::Times(_)) | None => self.disable_loop(),
+        };
+        result.is_ok()
+    }
 
-                track_handle.wait_for_end().await;
-                Ok(track_handle)
+    fn set_paused(&self, paused: bool) -> bool {
+        match paused {
+            true => self.pause().is_ok(),
+            false => self.play().is_ok(),
+        }
+    }
+}
+
+/// Calls end or error playback hooks on the track event it's added for
+struct PlaybackHooksHandler {
+    hooks: Arc<PlaybackHooks>,
+    playback: Playback,
+    event: TrackEvent,
+}
+
+#[async_trait]
+impl VoiceEventHandler for PlaybackHooksHandler {
+    async fn act(&self, ctx: &EventContext<'_>) -> Option<Event> {
+        match self.event {
+            TrackEvent::Error => {
+                let error = match ctx {
+                    EventContext::Track(tracks) => tracks
+                        .iter()
+                        .map(|(state, _)| format!("{:?}", state.playing))
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                    _ => "Track error".into(),
+                };
+                self.hooks.failed(&self.playback, &error);
             }
-            None => Err(AudioError::NotInVoiceChannel),
+            _ => self.hooks.ended(&self.playback),
         }
+        None
     }
 }
 
 #[async_trait]
 pub trait PoiseContextHelper<'a> {
-    async fn songbird(&self) -> Arc<songbird::Songbird>;
+    /// Voice playback for command handlers
+    async fn voice(&self) -> Arc<dyn SongbirdHelper>;
 }
 
 #[async_trait]
 impl<'a> PoiseContextHelper<'a> for PoiseContext<'a> {
-    async fn songbird(&self) -> Arc<songbird::Songbird> {
-        let data = self.serenity_context().data.read().await;
-        data.get::<SongbirdKey>()
-            .expect("Songbird voice client placed in at initialization")
-            .clone()
+    async fn voice(&self) -> Arc<dyn SongbirdHelper> {
+        poise_songbird_get(self).await
     }
 }
 
-pub fn make_action_row(audio_rows: &[AudioTableRow]) -> CreateActionRow {
+pub fn make_action_row(audio_rows: &[AudioTableRow], guild_id: Option<GuildId>) -> CreateActionRow {
     let buttons: Vec<_> = audio_rows
         .iter()
-        .map(|track| {
-            CreateButton::new(ButtonCustomId::PlayAudio(track.id))
-                .label(track.name.to_button_label())
-        })
+        .map(|track| sound_button(track, guild_id))
         .collect();
 
     CreateActionRow::Buttons(buttons)
 }
 
+/// Guild's autocomplete limit and minimum query length (see `/settings autocomplete`)
+pub fn autocomplete_settings(data: &UserData, guild_id: Option<GuildId>) -> (usize, usize) {
+    let settings = guild_id.and_then(|guild_id| {
+        data.guild_settings_table()
+            .get_guild_settings(guild_id.get())
+            .log_err()
+            .ok()
+    });
+    let settings = settings.unwrap_or_default();
+
+    (
+        settings
+            .autocomplete_limit
+            .unwrap_or(vars::AUTOCOMPLETE_LIMIT)
+            .min(vars::AUTOCOMPLETE_MAX_LIMIT) as usize,
+        settings
+            .autocomplete_min_query_len
+            .unwrap_or(vars::AUTOCOMPLETE_MIN_QUERY_LEN) as usize,
+    )
+}
+
+/// Autocomplete tracks, served from the guild's autocomplete cache when possible
+fn autocomplete_tracks(ctx: &PoiseContext<'_>, partial: &str) -> Vec<AutocompleteTrack> {
+    let user_id = Some(ctx.author().id.get());
+    let (limit, min_query_len) = autocomplete_settings(ctx.data(), ctx.guild_id());
+    let query = || {
+        ctx.data()
+            .audio_table()
+            .autocomplete_tracks(partial, user_id, limit, min_query_len)
+    };
+
+    match ctx.guild_id() {
+        Some(guild_id) => {
+            let key = AutocompleteCache::key(partial, user_id, min_query_len);
+            ctx.data()
+                .autocomplete_cache
+                .get_or_insert_with(guild_id, key, query)
+        }
+        None => query(),
+    }
+}
+
+/// Autocomplete choice of track, labeled with the tag it was found by
+fn track_choice(track: AutocompleteTrack) -> AutocompleteChoice {
+    AutocompleteChoice::new(track.label(), track.value())
+}
+
 pub async fn autocomplete_audio_track_name<'a>(
     ctx: PoiseContext<'_>,
     partial: &'a str,
-) -> impl futures::stream::Stream<Item = String> + 'a {
-    let table = ctx.data().audio_table();
-    let track_names = table.fts_autocomplete_track_names(partial, Some(5));
-    futures::stream::iter(track_names)
+) -> impl futures::stream::Stream<Item = AutocompleteChoice> + 'a {
+    let choices: Vec<_> = autocomplete_tracks(&ctx, partial)
+        .into_iter()
+        .map(track_choice)
+        .collect();
+    futures::stream::iter(choices)
 }
 
 pub async fn autocomplete_opt_audio_track_name<'a>(
     ctx: PoiseContext<'_>,
     partial: &'a str,
+) -> impl futures::stream::Stream<Item = AutocompleteChoice> + 'a {
+    // NONE counts towards Discord's choice limit
+    let choices: Vec<_> = std::iter::once(AutocompleteChoice::from("NONE"))
+        .chain(
+            autocomplete_tracks(&ctx, partial)
+                .into_iter()
+                .map(track_choice),
+        )
+        .take(vars::AUTOCOMPLETE_MAX_LIMIT as usize)
+        .collect();
+    futures::stream::iter(choices)
+}
+
+/// Autocomplete archived track names, for unarchiving. Archived tracks are left out of the
+/// regular autocomplete
+pub async fn autocomplete_archived_audio_track_name<'a>(
+    ctx: PoiseContext<'_>,
+    partial: &'a str,
 ) -> impl futures::stream::Stream<Item = String> + 'a {
-    let table = ctx.data().audio_table();
-    let mut track_names = table.fts_autocomplete_track_names(partial, Some(5));
-    track_names.insert(0, "NONE".into());
+    let track_names = ctx.data().audio_table().archived_track_names(partial, 5);
+    futures::stream::iter(track_names)
+}
 
+/// Autocomplete names of removed sounds in the trash, most recently removed first
+pub async fn autocomplete_trashed_audio_track_name<'a>(
+    ctx: PoiseContext<'_>,
+    partial: &'a str,
+) -> impl futures::stream::Stream<Item = String> + 'a {
+    let track_names = trash::trashed_sound_names(&ctx.data().config().trash_dir, partial, 5);
     futures::stream::iter(track_names)
 }
 
+/// Autocomplete tags of unarchived sounds, most used first
+pub async fn autocomplete_tag<'a>(
+    ctx: PoiseContext<'_>,
+    partial: &'a str,
+) -> impl futures::stream::Stream<Item = String> + 'a {
+    let tags = ctx
+        .data()
+        .audio_table()
+        .tag_names(partial, vars::AUTOCOMPLETE_TAGS_LIMIT);
+    futures::stream::iter(tags)
+}
+
+/// Autocomplete soundboard category names containing partial, in soundboard order
+pub async fn autocomplete_category<'a>(
+    ctx: PoiseContext<'_>,
+    partial: &'a str,
+) -> impl futures::stream::Stream<Item = String> + 'a {
+    let partial = partial.trim().to_lowercase();
+    let names: Vec<_> = ctx
+        .data()
+        .categories_table()
+        .list_categories()
+        .unwrap_or_default()
+        .into_iter()
+        .map(|category| category.name)
+        .filter(|name| name.to_lowercase().contains(&partial))
+        .take(vars::AUTOCOMPLETE_TAGS_LIMIT)
+        .collect();
+    futures::stream::iter(names)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Confirmation {
+    Confirmed,
+    Cancelled,
+    TimedOut,
+    /// User already has a confirmation pending
+    Busy,
+}
+
+/// Confirmations awaiting a button press, by user. A user can only have one pending
+/// confirmation at a time, so destructive operations can't be queued up concurrently
+#[derive(Default)]
+pub struct PendingConfirmations(Mutex<HashMap<UserId, u64>>);
+
+impl PendingConfirmations {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Lock user for confirmation id, until the returned guard is dropped.
+    /// None if user already has a pending confirmation
+    pub fn try_lock(&self, user_id: UserId, confirmation_id: u64) -> Option<ConfirmationLock<'_>> {
+        let mut pending = self.0.lock().unwrap();
+        if pending.contains_key(&user_id) {
+            return None;
+        }
+
+        pending.insert(user_id, confirmation_id);
+        Some(ConfirmationLock {
+            pending: self,
+            user_id,
+        })
+    }
+
+    pub fn is_pending(&self, confirmation_id: u64) -> bool {
+        self.0
+            .lock()
+            .unwrap()
+            .values()
+            .any(|id| *id == confirmation_id)
+    }
+
+    pub fn count(&self) -> usize {
+        self.0.lock().unwrap().len()
+    }
+}
+
+pub struct ConfirmationLock<'a> {
+    pending: &'a PendingConfirmations,
+    user_id: UserId,
+}
+
+impl Drop for ConfirmationLock<'_> {
+    fn drop(&mut self) {
+        self.pending.0.lock().unwrap().remove(&self.user_id);
+    }
+}
+
+/// Ask command author to confirm (or cancel) an operation with ephemeral buttons.
+/// Times out after `vars::CONFIRMATION_TIMEOUT_SECS`. Only the author can press the buttons
+pub async fn confirm(
+    ctx: PoiseContext<'_>,
+    prompt: impl Into<String>,
+) -> Result<Confirmation, PoiseError> {
+    let confirmation_id = ctx.id();
+    let lang = i18n::language(ctx);
+    let Some(_lock) = ctx
+        .data()
+        .pending_confirmations
+        .try_lock(ctx.author().id, confirmation_id)
+    else {
+        poise_check_msg(
+            ctx.send(
+                CreateReply::default()
+                    .ephemeral(true)
+                    .content(tr(lang, "confirm.busy", &[])),
+            )
+            .await,
+        );
+        return Ok(Confirmation::Busy);
+    };
+
+    let guild_id = ctx.guild_id();
+    let buttons = vec![
+        CreateButton::new(ButtonCustomId::new(
+            guild_id,
+            ButtonAction::Confirm(confirmation_id),
+        ))
+        .label(tr(lang, "confirm.confirm", &[]))
+        .style(ButtonStyle::Danger),
+        CreateButton::new(ButtonCustomId::new(
+            guild_id,
+            ButtonAction::Cancel(confirmation_id),
+        ))
+        .label(tr(lang, "confirm.cancel", &[]))
+        .style(ButtonStyle::Secondary),
+    ];
+
+    let reply = ctx
+        .send(
+            CreateReply::default()
+                .ephemeral(true)
+                .content(prompt)
+                .components(vec![CreateActionRow::Buttons(buttons)]),
+        )
+        .await?;
+
+    let interaction = ComponentInteractionCollector::new(ctx.serenity_context())
+        .author_id(ctx.author().id)
+        .channel_id(ctx.channel_id())
+        .timeout(Duration::from_secs(vars::CONFIRMATION_TIMEOUT_SECS))
+        .filter(move |interaction| {
+            matches!(
+                ButtonCustomId::parse(&interaction.data.custom_id).map(|id| id.action),
+                Ok(ButtonAction::Confirm(id) | ButtonAction::Cancel(id)) if id == confirmation_id
+            )
+        })
+        .await;
+
+    let Some(interaction) = interaction else {
+        reply
+            .edit(
+                ctx,
+                CreateReply::default()
+                    .content(tr(lang, "confirm.timed_out", &[]))
+                    .components(vec![]),
+            )
+            .await
+            .log_err_msg("Failed to edit timed out confirmation")
+            .ok();
+        return Ok(Confirmation::TimedOut);
+    };
+
+    let (confirmation, key) = match ButtonCustomId::parse(&interaction.data.custom_id)?.action {
+        ButtonAction::Confirm(_) => (Confirmation::Confirmed, "confirm.confirmed"),
+        _ => (Confirmation::Cancelled, "confirm.cancelled"),
+    };
+
+    interaction
+        .create_response(
+            ctx.serenity_context(),
+            CreateInteractionResponse::UpdateMessage(
+                CreateInteractionResponseMessage::new()
+                    .content(tr(lang, key, &[]))
+                    .components(vec![]),
+            ),
+        )
+        .await
+        .log_err_msg("Failed to respond to confirmation")
+        .ok();
+
+    Ok(confirmation)
+}
+
+/// First untaken of `name-2`, `name-3`, ...
+pub fn numbered_name(name: &str, is_taken: impl Fn(&str) -> bool) -> String {
+    (2..)
+        .map(|n| format!("{name}-{n}"))
+        .find(|name| !is_taken(name))
+        .unwrap()
+}
+
+/// Lowercase initials of the words in a user's name, e.g. `jd` for `John Doe`
+pub fn initials(user_name: &str) -> String {
+    user_name
+        .split(|c: char| c.is_whitespace() || c == '_' || c == '-' || c == '.')
+        .filter_map(|word| word.chars().find(|c| c.is_alphanumeric()))
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Untaken names to suggest instead of a taken sound name: numbered (`bruh-2`) and with the
+/// uploader's initials appended (`bruh-jd`)
+pub fn name_suggestions(
+    name: &str,
+    uploader_name: &str,
+    is_taken: impl Fn(&str) -> bool,
+) -> Vec<String> {
+    let mut suggestions = vec![numbered_name(name, &is_taken)];
+
+    let initials = initials(uploader_name);
+    if !initials.is_empty() {
+        let with_initials = format!("{name}-{initials}");
+        if !is_taken(&with_initials) && !suggestions.contains(&with_initials) {
+            suggestions.push(with_initials);
+        }
+    }
+
+    suggestions
+}
+
+/// Ask command author to pick another name for a taken sound name, with a button per
+/// suggestion. None if cancelled or timed out. Times out after `vars::CONFIRMATION_TIMEOUT_SECS`
+pub async fn pick_name(
+    ctx: PoiseContext<'_>,
+    name: &str,
+    suggestions: Vec<String>,
+) -> Result<Option<String>, PoiseError> {
+    let pick_id = ctx.id();
+    let lang = i18n::language(ctx);
+    let guild_id = ctx.guild_id();
+
+    let mut buttons: Vec<_> = suggestions
+        .iter()
+        .enumerate()
+        .map(|(index, suggestion)| {
+            CreateButton::new(ButtonCustomId::new(
+                guild_id,
+                ButtonAction::PickName {
+                    id: pick_id,
+                    index: index as u8,
+                },
+            ))
+            .label(suggestion.to_button_label())
+            .style(ButtonStyle::Primary)
+        })
+        .collect();
+    buttons.push(
+        CreateButton::new(ButtonCustomId::new(guild_id, ButtonAction::Cancel(pick_id)))
+            .label(tr(lang, "confirm.cancel", &[]))
+            .style(ButtonStyle::Secondary),
+    );
+
+    let reply = ctx
+        .send(
+            CreateReply::default()
+                .ephemeral(true)
+                .content(tr(lang, "sounds.name_taken", &[("track", &name)]))
+                .components(vec![CreateActionRow::Buttons(buttons)]),
+        )
+        .await?;
+
+    let interaction = ComponentInteractionCollector::new(ctx.serenity_context())
+        .author_id(ctx.author().id)
+        .channel_id(ctx.channel_id())
+        .timeout(Duration::from_secs(vars::CONFIRMATION_TIMEOUT_SECS))
+        .filter(move |interaction| {
+            matches!(
+                ButtonCustomId::parse(&interaction.data.custom_id).map(|id| id.action),
+                Ok(ButtonAction::PickName { id, .. } | ButtonAction::Cancel(id)) if id == pick_id
+            )
+        })
+        .await;
+
+    let Some(interaction) = interaction else {
+        reply
+            .edit(
+                ctx,
+                CreateReply::default()
+                    .content(tr(lang, "confirm.timed_out", &[]))
+                    .components(vec![]),
+            )
+            .await
+            .log_err_msg("Failed to edit timed out name pick")
+            .ok();
+        return Ok(None);
+    };
+
+    let picked = match ButtonCustomId::parse(&interaction.data.custom_id)?.action {
+        ButtonAction::PickName { index, .. } => suggestions.get(usize::from(index)).cloned(),
+        _ => None,
+    };
+    let content = match &picked {
+        Some(picked) => tr(lang, "sounds.name_picked", &[("track", picked)]),
+        None => tr(lang, "confirm.cancelled", &[]),
+    };
+
+    interaction
+        .create_response(
+            ctx.serenity_context(),
+            CreateInteractionResponse::UpdateMessage(
+                CreateInteractionResponseMessage::new()
+                    .content(content)
+                    .components(vec![]),
+            ),
+        )
+        .await
+        .log_err_msg("Failed to respond to name pick")
+        .ok();
+
+    Ok(picked)
+}
+
+/// Why sound name is invalid, for replies
+pub fn sound_name_error_message(lang: Language, err: &SoundNameError) -> String {
+    match err {
+        SoundNameError::Empty => tr(lang, "sound_name.empty", &[]),
+        SoundNameError::TooLong { max, .. } => tr(lang, "sound_name.too_long", &[("max", max)]),
+        SoundNameError::InvalidChar(c) => tr(
+            lang,
+            "sound_name.invalid_char",
+            &[("char", &c.escape_default())],
+        ),
+    }
+}
+
+/// Friendly reply for rate limited plays
+pub fn slow_down_message(lang: Language, retry_after: Duration) -> String {
+    let secs = retry_after.as_secs() + u64::from(retry_after.subsec_nanos() > 0);
+    tr(lang, "play.slow_down", &[("secs", &secs)])
+}
+
+/// Reply to server-only commands used in DMs, pointing at the commands that work there
+pub fn guild_only_message(lang: Language, prefix: &str, command: &str) -> String {
+    tr(
+        lang,
+        "dm.guild_only",
+        &[("prefix", &prefix), ("command", &command)],
+    )
+}
+
+/// Track duration like `1.5s`
+pub fn format_duration_ms(duration_ms: u64) -> String {
+    format!("{:.1}s", duration_ms as f64 / 1000.0)
+}
+
+/// File size like `512 B`, `23.4 KB` or `1.2 MB`
+pub fn format_file_size(bytes: u64) -> String {
+    match bytes {
+        0..=1023 => format!("{bytes} B"),
+        1024..=1_048_575 => format!("{:.1} KB", bytes as f64 / 1024.0),
+        _ => format!("{:.1} MB", bytes as f64 / 1_048_576.0),
+    }
+}
+
+/// `/sounds info` reply. Duration and size are unknown until probed
+pub fn sound_info(lang: Language, row: &AudioTableRow) -> String {
+    let unknown = tr(lang, "sounds.info_unknown", &[]);
+    let author = match row.author_hash {
+        Some(_) => tr(lang, "sounds.anonymous", &[]),
+        None => row
+            .author_global_name
+            .clone()
+            .or_else(|| row.author_name.clone())
+            .unwrap_or_else(|| "-".into()),
+    };
+    let tags = match row.tags.is_empty() {
+        true => "-".into(),
+        false => row.tags.to_string(),
+    };
+
+    let mut info = tr(
+        lang,
+        "sounds.info",
+        &[
+            ("track", &row.name),
+            ("id", &row.id),
+            ("tags", &tags),
+            ("author", &author),
+            ("created", &row.created_at.format("%Y-%m-%d")),
+            ("plays", &row.play_count),
+            (
+                "duration",
+                &row.duration_ms
+                    .map(format_duration_ms)
+                    .unwrap_or_else(|| unknown.clone()),
+            ),
+            (
+                "size",
+                &row.file_size.map(format_file_size).unwrap_or(unknown),
+            ),
+        ],
+    );
+
+    if let Some(source_url) = &row.source_url {
+        info = format!(
+            "{info}\n{}",
+            tr(
+                lang,
+                "sounds.info_source",
+                &[("url", &format!("<{source_url}>"))]
+            )
+        );
+    }
+
+    if row.archived_at.is_some() {
+        info = format!("{info}\n{}", tr(lang, "search.archived", &[]));
+    }
+
+    info
+}
+
+/// Parse age like `30d`, `12h`, `2w`, `6mo` or `1y` into a duration. Months are 30 days
+/// and years are 365 days
+pub fn parse_age(value: impl AsRef<str>) -> Option<chrono::Duration> {
+    let value = value.as_ref().trim().to_lowercase();
+    let unit_start = value.find(|c: char| !c.is_ascii_digit())?;
+    let (amount, unit) = value.split_at(unit_start);
+    let amount: i64 = amount.parse().ok()?;
+
+    match unit.trim() {
+        "h" | "hour" | "hours" => chrono::Duration::try_hours(amount),
+        "d" | "day" | "days" => chrono::Duration::try_days(amount),
+        "w" | "week" | "weeks" => chrono::Duration::try_weeks(amount),
+        "mo" | "month" | "months" => chrono::Duration::try_days(amount.checked_mul(30)?),
+        "y" | "year" | "years" => chrono::Duration::try_days(amount.checked_mul(365)?),
+        _ => None,
+    }
+}
+
 pub fn uuid_v4_str() -> String {
     // Create uuid audio file in /tmp directory
     let uuid = uuid::Uuid::new_v4();
@@ -277,12 +1211,319 @@ pub fn title_case(s: impl AsRef<str>) -> String {
 
 #[cfg(test)]
 mod tests {
+    use crate::test_utils::{AudioRowFixture, TestDb};
+
     use super::*;
 
+    #[test]
+    fn truncate_message_test() {
+        assert_eq!("Beep", truncate_message("Beep"));
+
+        let truncated = truncate_message("é".repeat(vars::MESSAGE_MAX_LEN + 1));
+        assert_eq!(vars::MESSAGE_MAX_LEN, truncated.chars().count());
+        assert!(truncated.ends_with("..."));
+    }
+
+    #[test]
+    fn sound_button_style_test() {
+        for style in SoundButtonStyle::ALL {
+            assert_eq!(Some(style), SoundButtonStyle::from_code(style.code()));
+        }
+        assert_eq!(None, SoundButtonStyle::from_code("blurple"));
+        assert_eq!(SoundButtonStyle::Primary, SoundButtonStyle::default());
+    }
+
+    #[test]
+    fn deferred_command_test() {
+        assert_eq!(Some(true), deferred_command("settings import"));
+        assert_eq!(Some(false), deferred_command("sounds import-code"));
+        assert_eq!(None, deferred_command("play"));
+    }
+
+    #[test]
+    fn truncate_button_label_test() {
+        assert_eq!("Beep", truncate_button_label("Beep"));
+
+        // multi-byte chars at the cut point
+        let truncated = truncate_button_label("é".repeat(vars::BTN_LABEL_MAX_LEN + 1));
+        assert_eq!(vars::BTN_LABEL_MAX_LEN, truncated.chars().count());
+        assert!(truncated.ends_with("..."));
+
+        let truncated = truncate_button_label("日本語".repeat(vars::BTN_LABEL_MAX_LEN));
+        assert_eq!(vars::BTN_LABEL_MAX_LEN, truncated.chars().count());
+        assert!(truncated.starts_with("日本語日本語"));
+
+        // emoji spanning several chars aren't cut in half
+        let family = "👨‍👩‍👧";
+        let label = format!("{}{}", "a".repeat(vars::BTN_LABEL_MAX_LEN - 4), family);
+        let truncated = truncate_button_label(&label);
+        assert_eq!(
+            format!("{}...", "a".repeat(vars::BTN_LABEL_MAX_LEN - 4)),
+            truncated
+        );
+
+        let label = format!("{}{}", family, "🔊".repeat(vars::BTN_LABEL_MAX_LEN));
+        let truncated = truncate_button_label(&label);
+        assert!(truncated.starts_with(family));
+        assert!(truncated.chars().count() <= vars::BTN_LABEL_MAX_LEN);
+    }
+
+    #[test]
+    fn parse_emoji_test() {
+        assert_eq!(Some("🔊".to_string()), parse_emoji(" 🔊 "));
+        assert_eq!(Some("👨‍👩‍👧".to_string()), parse_emoji("👨‍👩‍👧"));
+        assert_eq!(
+            Some("<:beep:1234>".to_string()),
+            parse_emoji("<:beep:1234>")
+        );
+
+        assert_eq!(None, parse_emoji("🔊🔊"));
+        assert_eq!(None, parse_emoji("a"));
+        assert_eq!(None, parse_emoji(""));
+    }
+
+    #[test]
+    fn button_custom_id_round_trip_test() {
+        let actions = [
+            ButtonAction::Play(42),
+            ButtonAction::PageNav {
+                page: 3,
+                tag: Some("droid".into()),
+            },
+            ButtonAction::Confirm(42),
+            ButtonAction::Cancel(42),
+            ButtonAction::Favorite(42),
+            ButtonAction::TagFilter,
+            ButtonAction::Archive(42),
+            ButtonAction::Keep(42),
+            ButtonAction::PickName { id: 7, index: 1 },
+            ButtonAction::Onboarding(OnboardingStep::StarterSounds),
+            ButtonAction::Listen(42),
+            ButtonAction::Approve(42),
+            ButtonAction::Reject(42),
+        ];
+
+        for action in actions {
+            let custom_id = ButtonCustomId::new(Some(GuildId::new(1234)), action);
+            let value: String = custom_id.clone().into();
+            assert_eq!(custom_id, ButtonCustomId::parse(&value).unwrap());
+        }
+    }
+
+    #[test]
+    fn button_custom_id_encode_test() {
+        let custom_id = ButtonCustomId::new(Some(GuildId::new(1234)), ButtonAction::Play(42));
+        assert_eq!(r#"{"v":1,"g":1234,"a":"play","p":42}"#, custom_id.encode());
+
+        let custom_id = ButtonCustomId::new(None, ButtonAction::PageNav { page: 3, tag: None });
+        assert_eq!(
+            r#"{"v":1,"g":null,"a":"page_nav","p":{"page":3}}"#,
+            custom_id.encode()
+        );
+    }
+
+    #[test]
+    fn button_custom_id_max_len_test() {
+        let custom_id = ButtonCustomId::new(
+            Some(GuildId::new(u64::MAX)),
+            ButtonAction::Confirm(u64::MAX),
+        );
+        assert!(custom_id.encode().len() <= vars::CUSTOM_ID_MAX_LEN);
+
+        let custom_id = ButtonCustomId::new(
+            Some(GuildId::new(u64::MAX)),
+            ButtonAction::PageNav {
+                page: 1_000_000,
+                tag: Some("x".repeat(vars::SOUNDBOARD_TAG_MAX_LEN)),
+            },
+        );
+        assert!(custom_id.encode().len() <= vars::CUSTOM_ID_MAX_LEN);
+
+        let custom_id = ButtonCustomId::new(
+            Some(GuildId::new(u64::MAX)),
+            ButtonAction::PickName {
+                id: u64::MAX,
+                index: u8::MAX,
+            },
+        );
+        assert!(custom_id.encode().len() <= vars::CUSTOM_ID_MAX_LEN);
+    }
+
+    #[test]
+    fn button_custom_id_legacy_test() {
+        let custom_id = ButtonCustomId::parse("play::42").unwrap();
+        assert_eq!(ButtonCustomId::LEGACY_VERSION, custom_id.version);
+        assert_eq!(None, custom_id.guild_id);
+        assert_eq!(ButtonAction::Play(42), custom_id.action);
+
+        assert!(matches!(
+            ButtonCustomId::parse("play::abc"),
+            Err(ButtonCustomIdError::Malformed { .. })
+        ));
+    }
+
+    #[test]
+    fn button_custom_id_errors_test() {
+        assert!(matches!(
+            ButtonCustomId::parse("unknown"),
+            Err(ButtonCustomIdError::Malformed { .. })
+        ));
+        assert!(matches!(
+            ButtonCustomId::parse(r#"{"v":1,"g":null,"a":"explode","p":1}"#),
+            Err(ButtonCustomIdError::Malformed { .. })
+        ));
+        assert_eq!(
+            Err(ButtonCustomIdError::UnsupportedVersion(2)),
+            ButtonCustomId::parse(r#"{"v":2,"g":null,"a":"play","p":1}"#)
+        );
+        assert!(matches!(
+            ButtonCustomId::parse("x".repeat(vars::CUSTOM_ID_MAX_LEN + 1)),
+            Err(ButtonCustomIdError::TooLong { .. })
+        ));
+    }
+
+    #[test]
+    fn button_custom_id_ensure_guild_test() {
+        let custom_id = ButtonCustomId::new(Some(GuildId::new(1)), ButtonAction::Play(42));
+        assert!(custom_id.ensure_guild(Some(GuildId::new(1))).is_ok());
+        assert_eq!(
+            Err(ButtonCustomIdError::GuildMismatch {
+                expected: 1,
+                actual: Some(2)
+            }),
+            custom_id.ensure_guild(Some(GuildId::new(2)))
+        );
+
+        let legacy = ButtonCustomId::parse("play::42").unwrap();
+        assert!(legacy.ensure_guild(Some(GuildId::new(2))).is_ok());
+    }
+
+    #[test]
+    fn pending_confirmations_test() {
+        let pending = PendingConfirmations::new();
+        let user_id = UserId::new(1);
+
+        let lock = pending.try_lock(user_id, 10).unwrap();
+        assert!(pending.is_pending(10));
+
+        // one pending confirmation per user
+        assert!(pending.try_lock(user_id, 11).is_none());
+        assert!(pending.try_lock(UserId::new(2), 12).is_some());
+
+        drop(lock);
+        assert!(!pending.is_pending(10));
+        assert!(pending.try_lock(user_id, 11).is_some());
+    }
+
+    #[test]
+    fn slow_down_message_test() {
+        assert_eq!(
+            "Slow down! You can play another sound in 3s",
+            slow_down_message(Language::English, Duration::from_millis(2500))
+        );
+        assert_eq!(
+            "Slow down! You can play another sound in 2s",
+            slow_down_message(Language::English, Duration::from_secs(2))
+        );
+    }
+
+    #[test]
+    fn autocomplete_settings_test() {
+        let db = TestDb::new();
+        let data = db.user_data();
+        assert_eq!((5, 3), autocomplete_settings(&data, None));
+
+        let mut settings = data.guild_settings_table().get_guild_settings(1).unwrap();
+        settings.autocomplete_limit = Some(40);
+        settings.autocomplete_min_query_len = Some(1);
+        data.guild_settings_table()
+            .update_guild_settings(&settings)
+            .unwrap();
+        assert_eq!((25, 1), autocomplete_settings(&data, Some(GuildId::new(1))));
+        assert_eq!((5, 3), autocomplete_settings(&data, Some(GuildId::new(2))));
+    }
+
+    #[test]
+    fn guild_only_message_test() {
+        assert_eq!(
+            "`!play` only works in a server, where sounds are played in voice channels. \
+             In DMs you can still browse the library with `!search`, `!sounds info` and `!sounds help`",
+            guild_only_message(Language::English, "!", "play")
+        );
+    }
+
+    #[test]
+    fn format_metadata_test() {
+        assert_eq!("1.5s", format_duration_ms(1500));
+        assert_eq!("0.0s", format_duration_ms(0));
+        assert_eq!("512 B", format_file_size(512));
+        assert_eq!("23.4 KB", format_file_size(24_000));
+        assert_eq!("1.2 MB", format_file_size(1_258_291));
+    }
+
+    #[test]
+    fn sound_info_test() {
+        let db = TestDb::new();
+        let table = db.audio_table();
+        let row = AudioRowFixture::new()
+            .name("Bruh")
+            .tags("meme")
+            .metadata(1500, 24_000)
+            .insert(&table);
+
+        let info = sound_info(Language::English, &row);
+        assert!(info.contains(&format!("`Bruh` (#{})", row.id)));
+        assert!(info.contains("meme"));
+        assert!(info.contains("1.5s"));
+        assert!(info.contains("23.4 KB"));
+
+        let row = AudioRowFixture::new().insert(&table);
+        assert!(sound_info(Language::English, &row).contains("unknown"));
+
+        let row = AudioRowFixture::new().author_hash("abc").insert(&table);
+        assert!(sound_info(Language::English, &row).contains("anonymous"));
+
+        let row = AudioRowFixture::new()
+            .source_url("https://youtu.be/abc")
+            .insert(&table);
+        assert!(sound_info(Language::English, &row).contains("<https://youtu.be/abc>"));
+    }
+
+    #[test]
+    fn parse_age_test() {
+        assert_eq!(Some(chrono::Duration::hours(12)), parse_age("12h"));
+        assert_eq!(Some(chrono::Duration::days(30)), parse_age("30d"));
+        assert_eq!(Some(chrono::Duration::weeks(2)), parse_age(" 2W "));
+        assert_eq!(Some(chrono::Duration::days(180)), parse_age("6mo"));
+        assert_eq!(Some(chrono::Duration::days(365)), parse_age("1 year"));
+        assert_eq!(None, parse_age("30"));
+        assert_eq!(None, parse_age("d"));
+        assert_eq!(None, parse_age("3 fortnights"));
+    }
+
     #[test]
     fn title_case_test() {
         assert_eq!("This Is A Title", title_case("this is a title"));
         assert_eq!("This Is_a-title", title_case("this is_a-title"));
         assert_eq!("This Is A Title", title_case("this is\ta\t\ttitle"));
     }
+
+    #[test]
+    fn name_suggestions_test() {
+        assert_eq!("jd", initials("John Doe"));
+        assert_eq!("jds", initials("john_doe.smith"));
+        assert_eq!("", initials(" "));
+
+        assert_eq!("bruh-3", numbered_name("bruh", |name| name == "bruh-2"));
+
+        let taken = ["bruh", "bruh-2"];
+        assert_eq!(
+            vec!["bruh-3", "bruh-jd"],
+            name_suggestions("bruh", "John Doe", |name| taken.contains(&name))
+        );
+        assert_eq!(
+            vec!["bruh-2"],
+            name_suggestions("bruh", "John Doe", |name| name == "bruh-jd")
+        );
+    }
 }
@@ -0,0 +1,79 @@
+//! HTTP client of the bot's own requests, through the configured proxy and CA bundle
+use std::path::Path;
+
+use crate::commands::PoiseError;
+use crate::config::Config;
+use crate::download_policy;
+
+/// Client builder with the configured proxy and CA bundle applied
+pub fn client_builder(config: &Config) -> Result<reqwest::ClientBuilder, PoiseError> {
+    let mut builder = reqwest::Client::builder();
+
+    if let Some(proxy_url) = &config.http_proxy {
+        builder = builder.proxy(reqwest::Proxy::all(proxy_url)?);
+    }
+    if let Some(ca_bundle) = &config.http_ca_bundle {
+        for certificate in read_ca_bundle(ca_bundle)? {
+            builder = builder.add_root_certificate(certificate);
+        }
+    }
+
+    Ok(builder)
+}
+
+/// Client shared by requests without special options (see `UserData::http_client`)
+pub fn client(config: &Config) -> Result<reqwest::Client, PoiseError> {
+    Ok(client_builder(config)?.build()?)
+}
+
+/// Client of downloads from user given urls, refusing redirects `download_policy` doesn't allow
+pub fn download_client(config: &Config) -> Result<reqwest::Client, PoiseError> {
+    Ok(client_builder(config)?
+        .redirect(download_policy::redirect_policy(config))
+        .build()?)
+}
+
+/// Certificates of a PEM file, which may hold several
+pub fn read_ca_bundle(path: &Path) -> Result<Vec<reqwest::Certificate>, PoiseError> {
+    let pem = std::fs::read(path)
+        .map_err(|err| format!("Failed to read CA bundle {} - {err}", path.display()))?;
+    let certificates = reqwest::Certificate::from_pem_bundle(&pem)?;
+
+    match certificates.is_empty() {
+        true => Err(format!("No certificates in CA bundle {}", path.display()).into()),
+        false => Ok(certificates),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn client_test() {
+        assert!(client(&Config::default()).is_ok());
+
+        let config = Config {
+            http_proxy: Some("http://proxy.internal:3128".into()),
+            ..Default::default()
+        };
+        assert!(client(&config).is_ok());
+
+        let config = Config {
+            http_proxy: Some("not a url".into()),
+            ..Default::default()
+        };
+        assert!(client(&config).is_err());
+    }
+
+    #[test]
+    fn read_ca_bundle_test() {
+        let dir = TempDir::new();
+        assert!(read_ca_bundle(&dir.path().join("missing.pem")).is_err());
+
+        let empty = dir.file("empty.pem");
+        assert!(read_ca_bundle(&empty).is_err());
+    }
+}
@@ -0,0 +1,4666 @@
+//! Translations of user-facing strings and slash command names/descriptions.
+//!
+//! Messages are looked up by key with `tr`, falling back to English for missing translations.
+//! A guild's language is its `/settings language`, otherwise its Discord locale
+use std::fmt::Display;
+
+use serenity::all::GuildId;
+
+use crate::commands::{PoiseContext, PoiseError};
+use crate::common::{LogResult, UserData};
+use crate::db::GuildSettingsRow;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, poise::ChoiceParameter)]
+pub enum Language {
+    #[default]
+    #[name = "English"]
+    English,
+    #[name = "Español"]
+    Spanish,
+    #[name = "Deutsch"]
+    German,
+    #[name = "Français"]
+    French,
+}
+
+impl Language {
+    pub const ALL: [Language; 4] = [
+        Language::English,
+        Language::Spanish,
+        Language::German,
+        Language::French,
+    ];
+
+    /// Discord locale code
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::English => "en-US",
+            Self::Spanish => "es-ES",
+            Self::German => "de",
+            Self::French => "fr",
+        }
+    }
+
+    /// Language of Discord locale code, ignoring region (i.e. `en-GB` is English)
+    pub fn from_code(code: &str) -> Option<Self> {
+        let language = code.split('-').next().unwrap_or_default();
+        Self::ALL
+            .into_iter()
+            .find(|lang| lang.code().split('-').next() == Some(language))
+    }
+
+    fn index(&self) -> usize {
+        *self as usize
+    }
+}
+
+/// Message key and its translations, in `Language::ALL` order.
+/// `{name}` placeholders are substituted by `tr`
+const MESSAGES: &[(&str, [&str; 4])] = &[
+        (
+        "sounds.restored",
+        [
+            "Restored `{track}` from the trash",
+            "`{track}` recuperado de la papelera",
+            "`{track}` aus dem Papierkorb zurückgeholt",
+            "`{track}` récupéré de la corbeille",
+        ],
+    ),
+    (
+        "sounds.not_trashed",
+        [
+            "No removed sound named `{track}` in the trash",
+            "No hay ningún sonido eliminado llamado `{track}` en la papelera",
+            "Kein entfernter Sound namens `{track}` im Papierkorb",
+            "Aucun son supprimé nommé `{track}` dans la corbeille",
+        ],
+    ),
+    (
+        "sounds.restore_name_taken",
+        [
+            "Can't restore `{track}`, another sound has its name now",
+            "No se puede recuperar `{track}`, otro sonido tiene ahora su nombre",
+            "`{track}` kann nicht zurückgeholt werden, ein anderer Sound hat jetzt seinen Namen",
+            "Impossible de récupérer `{track}`, un autre son porte maintenant son nom",
+        ],
+    ),
+    (
+        "sounds.restore_file_exists",
+        [
+            "Can't restore `{track}`, the audio dir already has a file of the same name",
+            "No se puede recuperar `{track}`, el directorio de audio ya tiene un archivo con el mismo nombre",
+            "`{track}` kann nicht zurückgeholt werden, im Audioverzeichnis gibt es bereits eine Datei mit gleichem Namen",
+            "Impossible de récupérer `{track}`, le dossier audio contient déjà un fichier du même nom",
+        ],
+    ),
+    (
+        "sounds.restore_file_missing",
+        [
+            "Can't restore `{track}`, its audio file is missing from the trash",
+            "No se puede recuperar `{track}`, falta su archivo de audio en la papelera",
+            "`{track}` kann nicht zurückgeholt werden, seine Audiodatei fehlt im Papierkorb",
+            "Impossible de récupérer `{track}`, son fichier audio manque dans la corbeille",
+        ],
+    ),
+    (
+        "play.sequence",
+        [
+            "Playing {count} sounds in sequence: {tracks}",
+            "Reproduciendo {count} sonidos en secuencia: {tracks}",
+            "Spiele {count} Sounds nacheinander: {tracks}",
+            "Lecture de {count} sons à la suite : {tracks}",
+        ],
+    ),
+    (
+        "play.sequence_not_found",
+        [
+            "Couldn't find {tracks}",
+            "No se encontró {tracks}",
+            "{tracks} nicht gefunden",
+            "Introuvable : {tracks}",
+        ],
+    ),
+    (
+        "play.sequence_too_long",
+        [
+            "At most {max} sounds can be played in sequence",
+            "Se pueden reproducir como máximo {max} sonidos en secuencia",
+            "Es können höchstens {max} Sounds nacheinander abgespielt werden",
+            "Au plus {max} sons peuvent être joués à la suite",
+        ],
+    ),
+    (
+        "settings.digest_set",
+        [
+            "Weekly digest will be posted in {channel} every Monday",
+            "El resumen semanal se publicará en {channel} cada lunes",
+            "Die Wochenübersicht wird jeden Montag in {channel} gepostet",
+            "Le résumé hebdomadaire sera publié dans {channel} chaque lundi",
+        ],
+    ),
+    (
+        "settings.digest_disabled",
+        [
+            "Weekly digest disabled",
+            "Resumen semanal desactivado",
+            "Wochenübersicht deaktiviert",
+            "Résumé hebdomadaire désactivé",
+        ],
+    ),
+    (
+        "digest.title",
+        [
+            "Soundboard weekly digest",
+            "Resumen semanal del soundboard",
+            "Soundboard-Wochenübersicht",
+            "Résumé hebdomadaire du soundboard",
+        ],
+    ),
+    (
+        "digest.new_sounds",
+        [
+            "New sounds",
+            "Sonidos nuevos",
+            "Neue Sounds",
+            "Nouveaux sons",
+        ],
+    ),
+    (
+        "digest.total_plays",
+        [
+            "Plays",
+            "Reproducciones",
+            "Wiedergaben",
+            "Lectures",
+        ],
+    ),
+    (
+        "digest.top_uploader",
+        [
+            "Top uploader",
+            "Mayor aportador",
+            "Fleißigster Uploader",
+            "Meilleur contributeur",
+        ],
+    ),
+    (
+        "digest.top_uploader_entry",
+        [
+            "{user} ({sounds} sounds)",
+            "{user} ({sounds} sonidos)",
+            "{user} ({sounds} Sounds)",
+            "{user} ({sounds} sons)",
+        ],
+    ),
+    (
+        "digest.top_played",
+        [
+            "Most played",
+            "Más reproducidos",
+            "Meistgespielt",
+            "Les plus joués",
+        ],
+    ),
+    (
+        "digest.top_played_entry",
+        [
+            "{rank}. `{track}` - {plays} plays",
+            "{rank}. `{track}` - {plays} reproducciones",
+            "{rank}. `{track}` - {plays} Wiedergaben",
+            "{rank}. `{track}` - {plays} lectures",
+        ],
+    ),
+    (
+        "digest.no_plays",
+        [
+            "No sounds were played this week",
+            "No se reprodujo ningún sonido esta semana",
+            "Diese Woche wurden keine Sounds abgespielt",
+            "Aucun son n'a été joué cette semaine",
+        ],
+    ),
+    (
+        "doctor.title",
+        [
+            "Pre-flight checks",
+            "Comprobaciones previas",
+            "Vorabprüfungen",
+            "Vérifications préalables",
+        ],
+    ),
+    (
+        "doctor.token",
+        [
+            "Discord token",
+            "Token de Discord",
+            "Discord-Token",
+            "Jeton Discord",
+        ],
+    ),
+    (
+        "doctor.token_pass",
+        [
+            "Logged in as {user}",
+            "Sesión iniciada como {user}",
+            "Angemeldet als {user}",
+            "Connecté en tant que {user}",
+        ],
+    ),
+    (
+        "doctor.intents",
+        [
+            "Gateway intents",
+            "Intents del gateway",
+            "Gateway-Intents",
+            "Intents de la passerelle",
+        ],
+    ),
+    (
+        "doctor.intents_pass",
+        [
+            "Message content intent enabled",
+            "Intent de contenido de mensajes activado",
+            "Message-Content-Intent aktiviert",
+            "Intent du contenu des messages activé",
+        ],
+    ),
+    (
+        "doctor.intents_fail",
+        [
+            "Message content intent isn't enabled in the Discord developer portal, prefix commands won't work",
+            "El intent de contenido de mensajes no está activado en el portal de desarrolladores de Discord, los comandos de prefijo no funcionarán",
+            "Der Message-Content-Intent ist im Discord-Entwicklerportal nicht aktiviert, Präfixbefehle funktionieren nicht",
+            "L'intent du contenu des messages n'est pas activé dans le portail des développeurs Discord, les commandes à préfixe ne fonctionneront pas",
+        ],
+    ),
+    (
+        "doctor.voice_permissions",
+        [
+            "Voice permissions",
+            "Permisos de voz",
+            "Sprachberechtigungen",
+            "Permissions vocales",
+        ],
+    ),
+    (
+        "doctor.voice_pass",
+        [
+            "Connect and speak allowed in all {channels} configured voice channels",
+            "Conectar y hablar permitido en los {channels} canales de voz configurados",
+            "Verbinden und Sprechen in allen {channels} eingestellten Sprachkanälen erlaubt",
+            "Connexion et parole autorisées dans les {channels} salons vocaux configurés",
+        ],
+    ),
+    (
+        "doctor.voice_fail",
+        [
+            "Can't connect or speak in {channels}",
+            "No puede conectarse o hablar en {channels}",
+            "Kann in {channels} nicht verbinden oder sprechen",
+            "Impossible de se connecter ou de parler dans {channels}",
+        ],
+    ),
+    (
+        "doctor.voice_none",
+        [
+            "No voice channels configured",
+            "No hay canales de voz configurados",
+            "Keine Sprachkanäle eingestellt",
+            "Aucun salon vocal configuré",
+        ],
+    ),
+    (
+        "doctor.voice_no_token",
+        [
+            "Not checked without a valid token",
+            "No comprobado sin un token válido",
+            "Ohne gültiges Token nicht geprüft",
+            "Non vérifié sans jeton valide",
+        ],
+    ),
+    (
+        "doctor.schema_version",
+        [
+            "Database schema",
+            "Esquema de la base de datos",
+            "Datenbankschema",
+            "Schéma de la base de données",
+        ],
+    ),
+    (
+        "doctor.schema_pass",
+        [
+            "Version {version}, up to date",
+            "Versión {version}, actualizada",
+            "Version {version}, aktuell",
+            "Version {version}, à jour",
+        ],
+    ),
+    (
+        "doctor.schema_fail",
+        [
+            "Version {version}, expected {expected}",
+            "Versión {version}, se esperaba {expected}",
+            "Version {version}, erwartet {expected}",
+            "Version {version}, {expected} attendue",
+        ],
+    ),
+    (
+        "doctor.disk_space",
+        [
+            "Disk space",
+            "Espacio en disco",
+            "Speicherplatz",
+            "Espace disque",
+        ],
+    ),
+    (
+        "doctor.disk_space_free",
+        [
+            "{free} free in the audio directory (at least {min} needed)",
+            "{free} libres en el directorio de audio (se necesitan al menos {min})",
+            "{free} frei im Audioverzeichnis (mindestens {min} nötig)",
+            "{free} libres dans le dossier audio (au moins {min} nécessaires)",
+        ],
+    ),
+    (
+        "doctor.ffmpeg",
+        [
+            "ffmpeg",
+            "ffmpeg",
+            "ffmpeg",
+            "ffmpeg",
+        ],
+    ),
+    (
+        "doctor.yt_dlp",
+        [
+            "yt-dlp",
+            "yt-dlp",
+            "yt-dlp",
+            "yt-dlp",
+        ],
+    ),
+    (
+        "doctor.tool_missing",
+        [
+            "`{program}` not found or not runnable",
+            "`{program}` no encontrado o no ejecutable",
+            "`{program}` nicht gefunden oder nicht ausführbar",
+            "`{program}` introuvable ou non exécutable",
+        ],
+    ),
+    (
+        "self_check.warning",
+        [
+            "Some things I need in **{guild}** are missing:\n{problems}",
+            "Faltan algunas cosas que necesito en **{guild}**:\n{problems}",
+            "Mir fehlt einiges, das ich in **{guild}** brauche:\n{problems}",
+            "Il me manque certaines choses dont j'ai besoin sur **{guild}** :\n{problems}",
+        ],
+    ),
+    (
+        "self_check.channel",
+        [
+            "{permissions} in {channel}",
+            "{permissions} en {channel}",
+            "{permissions} in {channel}",
+            "{permissions} dans {channel}",
+        ],
+    ),
+    (
+        "self_check.guild",
+        [
+            "{permissions} server wide",
+            "{permissions} en todo el servidor",
+            "{permissions} auf dem ganzen Server",
+            "{permissions} sur tout le serveur",
+        ],
+    ),
+    (
+        "self_check.intent",
+        [
+            "The message content intent isn't enabled for the bot, so prefix commands won't work. The bot's host can enable it in the Discord developer portal",
+            "El intent de contenido de mensajes no está activado para el bot, así que los comandos de prefijo no funcionarán. Quien aloja el bot puede activarlo en el portal de desarrolladores de Discord",
+            "Der Message-Content-Intent ist für den Bot nicht aktiviert, daher funktionieren Präfixbefehle nicht. Wer den Bot hostet, kann ihn im Discord-Entwicklerportal aktivieren",
+            "L'intent du contenu des messages n'est pas activé pour le bot, les commandes à préfixe ne fonctionneront donc pas. L'hébergeur du bot peut l'activer dans le portail des développeurs Discord",
+        ],
+    ),
+    (
+        "play.playing_times",
+        [
+            "Playing track `{track}` {times} times",
+            "Reproduciendo `{track}` {times} veces",
+            "Spiele `{track}` {times} Mal",
+            "Lecture de `{track}` {times} fois",
+        ],
+    ),
+    (
+        "play.playing_forever",
+        [
+            "Playing track `{track}` on repeat until stopped",
+            "Reproduciendo `{track}` en bucle hasta que se detenga",
+            "Spiele `{track}` in Dauerschleife, bis er gestoppt wird",
+            "Lecture de `{track}` en boucle jusqu'à l'arrêt",
+        ],
+    ),
+    (
+        "play.loop_invalid",
+        [
+            "Loop must be a number from 1 to {max}, or `infinite`",
+            "La repetición debe ser un número del 1 al {max}, o `infinite`",
+            "Schleife muss eine Zahl von 1 bis {max} sein, oder `infinite`",
+            "La boucle doit être un nombre de 1 à {max}, ou `infinite`",
+        ],
+    ),
+    (
+        "play.loop_forbidden",
+        [
+            "You need the `{permission}` permission to loop sounds until stopped",
+            "Necesitas el permiso `{permission}` para repetir sonidos hasta detenerlos",
+            "Du brauchst die Berechtigung `{permission}`, um Sounds bis zum Stoppen zu wiederholen",
+            "Vous avez besoin de la permission `{permission}` pour répéter des sons jusqu'à l'arrêt",
+        ],
+    ),
+    (
+        "now_playing.repeat",
+        ["🔁 Repeat", "🔁 Repetir", "🔁 Wiederholen", "🔁 Répéter"],
+    ),
+    (
+        "now_playing.repeat_on",
+        [
+            "🔁 Repeating",
+            "🔁 Repitiendo",
+            "🔁 Wiederholt",
+            "🔁 En boucle",
+        ],
+    ),
+    (
+        "now_playing.nothing",
+        [
+            "Nothing is playing",
+            "No se está reproduciendo nada",
+            "Es wird nichts abgespielt",
+            "Rien n'est en cours de lecture",
+        ],
+    ),
+    (
+        "settings.priority_stop",
+        [
+            "Sounds played with priority will now stop sounds playing",
+            "Los sonidos reproducidos con prioridad ahora detendrán los sonidos en reproducción",
+            "Mit Priorität abgespielte Sounds stoppen jetzt laufende Sounds",
+            "Les sons joués en priorité arrêteront désormais les sons en cours",
+        ],
+    ),
+    (
+        "settings.priority_pause",
+        [
+            "Sounds played with priority will now pause sounds playing until they end",
+            "Los sonidos reproducidos con prioridad ahora pausarán los sonidos en reproducción hasta terminar",
+            "Mit Priorität abgespielte Sounds pausieren jetzt laufende Sounds, bis sie enden",
+            "Les sons joués en priorité mettront désormais en pause les sons en cours jusqu'à leur fin",
+        ],
+    ),
+    (
+        "record.started",
+        [
+            "Recording session #{id}. Every sound played in this server is recorded until `/record stop`",
+            "Grabando la sesión #{id}. Cada sonido reproducido en este servidor se graba hasta `/record stop`",
+            "Sitzung #{id} wird aufgenommen. Jeder Sound auf diesem Server wird bis `/record stop` aufgenommen",
+            "Enregistrement de la session #{id}. Chaque son joué sur ce serveur est enregistré jusqu'à `/record stop`",
+        ],
+    ),
+    (
+        "record.already",
+        [
+            "Session #{id} is already being recorded",
+            "La sesión #{id} ya se está grabando",
+            "Sitzung #{id} wird bereits aufgenommen",
+            "La session #{id} est déjà en cours d'enregistrement",
+        ],
+    ),
+    (
+        "record.not_recording",
+        [
+            "No session is being recorded",
+            "No se está grabando ninguna sesión",
+            "Es wird keine Sitzung aufgenommen",
+            "Aucune session n'est en cours d'enregistrement",
+        ],
+    ),
+    (
+        "record.stopped",
+        [
+            "Recorded session #{id}: {plays} sounds over {duration}. Replay it with `/record play {id}`",
+            "Sesión #{id} grabada: {plays} sonidos en {duration}. Repítela con `/record play {id}`",
+            "Sitzung #{id} aufgenommen: {plays} Sounds in {duration}. Abspielen mit `/record play {id}`",
+            "Session #{id} enregistrée : {plays} sons en {duration}. Rejouez-la avec `/record play {id}`",
+        ],
+    ),
+    (
+        "record.list",
+        [
+            "Recorded sessions:",
+            "Sesiones grabadas:",
+            "Aufgenommene Sitzungen:",
+            "Sessions enregistrées :",
+        ],
+    ),
+    (
+        "record.list_entry",
+        [
+            "- `#{id}` {date} UTC by {user} - {plays} sounds, {duration}",
+            "- `#{id}` {date} UTC por {user} - {plays} sonidos, {duration}",
+            "- `#{id}` {date} UTC von {user} - {plays} Sounds, {duration}",
+            "- `#{id}` {date} UTC par {user} - {plays} sons, {duration}",
+        ],
+    ),
+    (
+        "record.list_entry_recording",
+        [
+            "- `#{id}` {date} UTC by {user} - {plays} sounds, recording for {duration}",
+            "- `#{id}` {date} UTC por {user} - {plays} sonidos, grabando desde hace {duration}",
+            "- `#{id}` {date} UTC von {user} - {plays} Sounds, nimmt seit {duration} auf",
+            "- `#{id}` {date} UTC par {user} - {plays} sons, enregistrement depuis {duration}",
+        ],
+    ),
+    (
+        "record.list_empty",
+        [
+            "No sessions recorded yet. Start one with `/record start`",
+            "Aún no hay sesiones grabadas. Empieza una con `/record start`",
+            "Noch keine Sitzungen aufgenommen. Starte eine mit `/record start`",
+            "Aucune session enregistrée. Commencez-en une avec `/record start`",
+        ],
+    ),
+    (
+        "record.not_found",
+        [
+            "Session #{id} not found or has no sounds",
+            "La sesión #{id} no existe o no tiene sonidos",
+            "Sitzung #{id} nicht gefunden oder ohne Sounds",
+            "Session #{id} introuvable ou sans sons",
+        ],
+    ),
+    (
+        "record.replaying",
+        [
+            "Replaying session #{id} ({plays} sounds). Use `/stop` to stop it",
+            "Reproduciendo la sesión #{id} ({plays} sonidos). Usa `/stop` para detenerla",
+            "Spiele Sitzung #{id} ab ({plays} Sounds). Mit `/stop` anhalten",
+            "Lecture de la session #{id} ({plays} sons). Utilisez `/stop` pour l'arrêter",
+        ],
+    ),
+    (
+        "dm.guild_only",
+        [
+            "`{prefix}{command}` only works in a server, where sounds are played in voice channels. In DMs you can still browse the library with `{prefix}search`, `{prefix}sounds info` and `{prefix}sounds help`",
+            "`{prefix}{command}` solo funciona en un servidor, donde los sonidos se reproducen en canales de voz. En mensajes directos puedes explorar la biblioteca con `{prefix}search`, `{prefix}sounds info` y `{prefix}sounds help`",
+            "`{prefix}{command}` funktioniert nur auf einem Server, wo Sounds in Sprachkanälen abgespielt werden. In Direktnachrichten kannst du die Bibliothek mit `{prefix}search`, `{prefix}sounds info` und `{prefix}sounds help` durchsuchen",
+            "`{prefix}{command}` ne fonctionne que sur un serveur, où les sons sont joués dans les salons vocaux. En messages privés, vous pouvez parcourir la bibliothèque avec `{prefix}search`, `{prefix}sounds info` et `{prefix}sounds help`",
+        ],
+    ),
+    (
+        "settings.autocomplete_set",
+        [
+            "Sound name autocomplete will now suggest up to {limit} sounds, searched from {min_chars} characters",
+            "El autocompletado de nombres de sonidos ahora sugerirá hasta {limit} sonidos, buscados a partir de {min_chars} caracteres",
+            "Die Autovervollständigung von Soundnamen schlägt jetzt bis zu {limit} Sounds vor, gesucht ab {min_chars} Zeichen",
+            "L'autocomplétion des noms de sons suggérera désormais jusqu'à {limit} sons, recherchés à partir de {min_chars} caractères",
+        ],
+    ),
+    (
+        "settings.silent_on",
+        [
+            "Sounds will now play silently, without now playing messages or `/play` replies. Use `/play silent:False` to post them anyway",
+            "Los sonidos ahora se reproducirán en silencio, sin mensajes de reproducción actual ni respuestas de `/play`. Usa `/play silent:False` para publicarlos igualmente",
+            "Sounds werden jetzt still abgespielt, ohne Jetzt-läuft-Nachrichten oder Antworten von `/play`. Nutze `/play silent:False`, um sie trotzdem zu posten",
+            "Les sons seront désormais joués en silence, sans messages de lecture en cours ni réponses de `/play`. Utilisez `/play silent:False` pour les publier quand même",
+        ],
+    ),
+    (
+        "settings.silent_off",
+        [
+            "Playing sounds will post now playing messages and `/play` replies again",
+            "Reproducir sonidos volverá a publicar mensajes de reproducción actual y respuestas de `/play`",
+            "Beim Abspielen von Sounds werden wieder Jetzt-läuft-Nachrichten und Antworten von `/play` gepostet",
+            "Jouer des sons publiera à nouveau les messages de lecture en cours et les réponses de `/play`",
+        ],
+    ),
+    (
+        "categories.invalid_name",
+        [
+            "Category names must be 1 to {max} characters long",
+            "Los nombres de categoría deben tener entre 1 y {max} caracteres",
+            "Kategorienamen müssen 1 bis {max} Zeichen lang sein",
+            "Les noms de catégorie doivent faire entre 1 et {max} caractères",
+        ],
+    ),
+    (
+        "categories.exists",
+        [
+            "There's already a category named {category}",
+            "Ya existe una categoría llamada {category}",
+            "Es gibt bereits eine Kategorie namens {category}",
+            "Il existe déjà une catégorie nommée {category}",
+        ],
+    ),
+    (
+        "categories.not_found",
+        [
+            "No category named `{category}`. See `/admin categories list`",
+            "No hay ninguna categoría llamada `{category}`. Consulta `/admin categories list`",
+            "Keine Kategorie namens `{category}`. Siehe `/admin categories list`",
+            "Aucune catégorie nommée `{category}`. Voir `/admin categories list`",
+        ],
+    ),
+    (
+        "categories.added",
+        [
+            "Added category {category}. Assign sounds to it with `/sounds category`",
+            "Se añadió la categoría {category}. Asígnale sonidos con `/sounds category`",
+            "Kategorie {category} hinzugefügt. Weise ihr Sounds mit `/sounds category` zu",
+            "Catégorie {category} ajoutée. Assignez-lui des sons avec `/sounds category`",
+        ],
+    ),
+    (
+        "categories.edited",
+        [
+            "Updated category {category}",
+            "Se actualizó la categoría {category}",
+            "Kategorie {category} aktualisiert",
+            "Catégorie {category} mise à jour",
+        ],
+    ),
+    (
+        "categories.removed",
+        [
+            "Removed category {category}. Its sounds are kept, without a category",
+            "Se eliminó la categoría {category}. Sus sonidos se conservan, sin categoría",
+            "Kategorie {category} entfernt. Ihre Sounds bleiben erhalten, ohne Kategorie",
+            "Catégorie {category} supprimée. Ses sons sont conservés, sans catégorie",
+        ],
+    ),
+    (
+        "categories.none",
+        [
+            "No categories yet. Add one with `/admin categories add`",
+            "Aún no hay categorías. Añade una con `/admin categories add`",
+            "Noch keine Kategorien. Füge eine mit `/admin categories add` hinzu",
+            "Aucune catégorie pour l'instant. Ajoutez-en une avec `/admin categories add`",
+        ],
+    ),
+    (
+        "categories.list_entry",
+        [
+            "`{order}` {category} - {sounds} sounds",
+            "`{order}` {category} - {sounds} sonidos",
+            "`{order}` {category} - {sounds} Sounds",
+            "`{order}` {category} - {sounds} sons",
+        ],
+    ),
+    (
+        "sounds.category_set",
+        [
+            "`{track}` is now in category {category}",
+            "`{track}` ahora está en la categoría {category}",
+            "`{track}` ist jetzt in der Kategorie {category}",
+            "`{track}` est maintenant dans la catégorie {category}",
+        ],
+    ),
+    (
+        "sounds.category_cleared",
+        [
+            "Removed the category of `{track}`",
+            "Se quitó la categoría de `{track}`",
+            "Kategorie von `{track}` entfernt",
+            "Catégorie de `{track}` retirée",
+        ],
+    ),
+    (
+        "sounds.button_style_set",
+        [
+            "`{track}` now has a {style} button",
+            "`{track}` ahora tiene un botón {style}",
+            "`{track}` hat jetzt einen {style} Button",
+            "`{track}` a maintenant un bouton {style}",
+        ],
+    ),
+    (
+        "sounds.button_style_cleared",
+        [
+            "`{track}` now has a default button",
+            "`{track}` ahora tiene un botón por defecto",
+            "`{track}` hat jetzt einen Standard-Button",
+            "`{track}` a maintenant un bouton par défaut",
+        ],
+    ),
+    (
+        "settings.soundboard_order_added",
+        [
+            "Soundboards now show the oldest sounds first",
+            "Los soundboards ahora muestran primero los sonidos más antiguos",
+            "Soundboards zeigen jetzt die ältesten Sounds zuerst",
+            "Les soundboards affichent désormais les sons les plus anciens en premier",
+        ],
+    ),
+    (
+        "settings.soundboard_order_trending",
+        [
+            "Soundboards now show trending sounds first, see `/trending`",
+            "Los soundboards ahora muestran primero los sonidos en tendencia, ver `/trending`",
+            "Soundboards zeigen jetzt angesagte Sounds zuerst, siehe `/trending`",
+            "Les soundboards affichent désormais les sons tendance en premier, voir `/trending`",
+        ],
+    ),
+    (
+        "trending.title",
+        [
+            "**Trending sounds** (plays count half as much every {days} days)",
+            "**Sonidos en tendencia** (las reproducciones cuentan la mitad cada {days} días)",
+            "**Angesagte Sounds** (Wiedergaben zählen alle {days} Tage nur noch halb so viel)",
+            "**Sons tendance** (les lectures comptent moitié moins tous les {days} jours)",
+        ],
+    ),
+    (
+        "trending.none",
+        [
+            "Nothing is trending, no sounds were played recently",
+            "Nada está en tendencia, no se reprodujeron sonidos recientemente",
+            "Nichts ist angesagt, in letzter Zeit wurden keine Sounds abgespielt",
+            "Rien n'est tendance, aucun son n'a été joué récemment",
+        ],
+    ),
+    (
+        "forgotten.title",
+        [
+            "**Forgotten sounds** - not played in the longest time",
+            "**Sonidos olvidados** - los que llevan más tiempo sin reproducirse",
+            "**Vergessene Sounds** - am längsten nicht abgespielt",
+            "**Sons oubliés** - pas joués depuis le plus longtemps",
+        ],
+    ),
+    (
+        "forgotten.none",
+        [
+            "No sounds yet. Add one with `/sounds add`",
+            "Aún no hay sonidos. Añade uno con `/sounds add`",
+            "Noch keine Sounds. Füge einen mit `/sounds add` hinzu",
+            "Pas encore de sons. Ajoutez-en un avec `/sounds add`",
+        ],
+    ),
+    (
+        "settings.play_history_public",
+        [
+            "Members can now see each other's play history with `/history`",
+            "Los miembros ahora pueden ver el historial de reproducción de los demás con `/history`",
+            "Mitglieder können jetzt mit `/history` den Wiedergabeverlauf der anderen sehen",
+            "Les membres peuvent désormais voir l'historique de lecture des autres avec `/history`",
+        ],
+    ),
+    (
+        "settings.play_history_private",
+        [
+            "Members can now only see their own play history",
+            "Los miembros ahora solo pueden ver su propio historial de reproducción",
+            "Mitglieder können jetzt nur noch ihren eigenen Wiedergabeverlauf sehen",
+            "Les membres ne peuvent désormais voir que leur propre historique de lecture",
+        ],
+    ),
+    (
+        "history.title",
+        [
+            "**Sounds {user} played lately**",
+            "**Sonidos que {user} reprodujo últimamente**",
+            "**Sounds, die {user} zuletzt abgespielt hat**",
+            "**Sons joués récemment par {user}**",
+        ],
+    ),
+    (
+        "history.none",
+        [
+            "{user} hasn't played any sounds here lately",
+            "{user} no ha reproducido sonidos aquí últimamente",
+            "{user} hat hier in letzter Zeit keine Sounds abgespielt",
+            "{user} n'a pas joué de sons ici récemment",
+        ],
+    ),
+    (
+        "history.private",
+        [
+            "Play history of other members is private in this server, you can only see yours",
+            "El historial de reproducción de otros miembros es privado en este servidor, solo puedes ver el tuyo",
+            "Der Wiedergabeverlauf anderer Mitglieder ist auf diesem Server privat, du kannst nur deinen sehen",
+            "L'historique de lecture des autres membres est privé sur ce serveur, vous ne pouvez voir que le vôtre",
+        ],
+    ),
+    (
+        "voice.left",
+        [
+            "Left voice channel",
+            "Salí del canal de voz",
+            "Sprachkanal verlassen",
+            "J'ai quitté le salon vocal",
+        ],
+    ),
+    (
+        "voice.not_in_channel",
+        [
+            "Not in a voice channel",
+            "No estoy en un canal de voz",
+            "Nicht in einem Sprachkanal",
+            "Je ne suis pas dans un salon vocal",
+        ],
+    ),
+    (
+        "play.playing",
+        [
+            "Playing track `{track}`",
+            "Reproduciendo `{track}`",
+            "Spiele `{track}`",
+            "Lecture de `{track}`",
+        ],
+    ),
+    (
+        "play.did_you_mean",
+        [
+            "Audio track `{track}` not found. Did you mean one of these?",
+            "No se encontró el sonido `{track}`. ¿Quisiste decir alguno de estos?",
+            "Sound `{track}` nicht gefunden. Meintest du einen dieser?",
+            "Son `{track}` introuvable. Vouliez-vous dire l'un de ceux-ci ?",
+        ],
+    ),
+    (
+        "play.quarantined",
+        [
+            "`{track}`'s audio file is damaged or was modified, so it was quarantined and the sound archived",
+            "El archivo de audio de `{track}` está dañado o fue modificado, así que se puso en cuarentena y se archivó el sonido",
+            "Die Audiodatei von `{track}` ist beschädigt oder wurde verändert, daher wurde sie in Quarantäne verschoben und der Sound archiviert",
+            "Le fichier audio de `{track}` est endommagé ou a été modifié, il a donc été mis en quarantaine et le son archivé",
+        ],
+    ),
+    (
+        "play.checksum_mismatch",
+        [
+            "`{track}`'s audio file is damaged or was modified, so it can't be played",
+            "El archivo de audio de `{track}` está dañado o fue modificado, así que no se puede reproducir",
+            "Die Audiodatei von `{track}` ist beschädigt oder wurde verändert, daher kann sie nicht abgespielt werden",
+            "Le fichier audio de `{track}` est endommagé ou a été modifié, il ne peut donc pas être joué",
+        ],
+    ),
+    (
+        "play.not_found",
+        [
+            "Audio track `{track}` not found",
+            "No se encontró el sonido `{track}`",
+            "Sound `{track}` nicht gefunden",
+            "Son `{track}` introuvable",
+        ],
+    ),
+    (
+        "play.slow_down",
+        [
+            "Slow down! You can play another sound in {secs}s",
+            "¡Más despacio! Puedes reproducir otro sonido en {secs}s",
+            "Langsam! Du kannst in {secs}s einen weiteren Sound abspielen",
+            "Doucement ! Vous pourrez jouer un autre son dans {secs}s",
+        ],
+    ),
+    (
+        "play.no_tagged_sounds",
+        [
+            "There are no sounds tagged `{tag}`",
+            "No hay sonidos con la etiqueta `{tag}`",
+            "Es gibt keine Sounds mit dem Tag `{tag}`",
+            "Il n'y a aucun son avec le tag `{tag}`",
+        ],
+    ),
+    (
+        "play.no_sounds",
+        [
+            "There are no sounds to play yet. Add one with `/sounds add`",
+            "Aún no hay sonidos. Añade uno con `/sounds add`",
+            "Es gibt noch keine Sounds. Füge einen mit `/sounds add` hinzu",
+            "Il n'y a encore aucun son. Ajoutez-en un avec `/sounds add`",
+        ],
+    ),
+    (
+        "play.stopped",
+        [
+            "Stopped playing sounds",
+            "Sonidos detenidos",
+            "Wiedergabe gestoppt",
+            "Lecture arrêtée",
+        ],
+    ),
+    (
+        "replay.nothing",
+        [
+            "Nothing has been played yet to replay",
+            "Todavía no se ha reproducido nada para repetir",
+            "Es wurde noch nichts abgespielt, das wiederholt werden kann",
+            "Rien n'a encore été joué à rejouer",
+        ],
+    ),
+    (
+        "sotd.content",
+        [
+            "🎵 Sound of the day: **{track}**",
+            "🎵 Sonido del día: **{track}**",
+            "🎵 Sound des Tages: **{track}**",
+            "🎵 Son du jour : **{track}**",
+        ],
+    ),
+    (
+        "sotd.play",
+        ["Play", "Reproducir", "Abspielen", "Jouer"],
+    ),
+    (
+        "now_playing.content",
+        [
+            "Now playing: **{track}**",
+            "Reproduciendo ahora: **{track}**",
+            "Läuft gerade: **{track}**",
+            "En cours de lecture : **{track}**",
+        ],
+    ),
+    (
+        "now_playing.stop",
+        ["■ Stop", "■ Detener", "■ Stopp", "■ Arrêter"],
+    ),
+    (
+        "now_playing.replay",
+        ["↻ Replay", "↻ Repetir", "↻ Nochmal", "↻ Rejouer"],
+    ),
+    (
+        "now_playing.favorite",
+        ["★ Favorite", "★ Favorito", "★ Favorit", "★ Favori"],
+    ),
+    (
+        "admin.resynced",
+        [
+            "Registered slash commands in {synced} of {guilds} servers",
+            "Comandos de barra registrados en {synced} de {guilds} servidores",
+            "Slash-Befehle in {synced} von {guilds} Servern registriert",
+            "Commandes slash enregistrées dans {synced} serveurs sur {guilds}",
+        ],
+    ),
+    (
+        "owner.guilds_title",
+        [
+            "In {count} servers:",
+            "En {count} servidores:",
+            "In {count} Servern:",
+            "Dans {count} serveurs :",
+        ],
+    ),
+    (
+        "owner.invalid_guild_id",
+        [
+            "`{guild_id}` isn't a server id",
+            "`{guild_id}` no es un id de servidor",
+            "`{guild_id}` ist keine Server-ID",
+            "`{guild_id}` n'est pas un id de serveur",
+        ],
+    ),
+    (
+        "owner.invalid_user_id",
+        [
+            "`{user_id}` isn't a user id",
+            "`{user_id}` no es un id de usuario",
+            "`{user_id}` ist keine Benutzer-ID",
+            "`{user_id}` n'est pas un id d'utilisateur",
+        ],
+    ),
+    (
+        "owner.search_title",
+        [
+            "{count} sounds found:",
+            "{count} sonidos encontrados:",
+            "{count} Sounds gefunden:",
+            "{count} sons trouvés :",
+        ],
+    ),
+    (
+        "owner.search_none",
+        [
+            "No sounds found for `{query}`",
+            "No se encontraron sonidos para `{query}`",
+            "Keine Sounds für `{query}` gefunden",
+            "Aucun son trouvé pour `{query}`",
+        ],
+    ),
+    (
+        "owner.invalid_hash",
+        [
+            "`{hash}` isn't an audio file hash. Copy it from `/owner search`",
+            "`{hash}` no es un hash de archivo de audio. Cópialo de `/owner search`",
+            "`{hash}` ist kein Audiodatei-Hash. Kopiere ihn aus `/owner search`",
+            "`{hash}` n'est pas un hash de fichier audio. Copiez-le depuis `/owner search`",
+        ],
+    ),
+    (
+        "owner.confirm_purge_hash",
+        [
+            "Permanently delete {count} sounds with audio `{hash}` in every server, along with trashed copies?",
+            "¿Eliminar permanentemente {count} sonidos con el audio `{hash}` en todos los servidores, junto con las copias en la papelera?",
+            "{count} Sounds mit dem Audio `{hash}` in allen Servern endgültig löschen, samt Kopien im Papierkorb?",
+            "Supprimer définitivement {count} sons avec l'audio `{hash}` dans tous les serveurs, ainsi que les copies dans la corbeille ?",
+        ],
+    ),
+    (
+        "owner.purged_hash",
+        [
+            "Deleted {sounds} sounds and {trashed} trashed sounds",
+            "Se eliminaron {sounds} sonidos y {trashed} sonidos de la papelera",
+            "{sounds} Sounds und {trashed} Sounds im Papierkorb gelöscht",
+            "{sounds} sons et {trashed} sons de la corbeille supprimés",
+        ],
+    ),
+    (
+        "owner.confirm_purge_user",
+        [
+            "Forget user `{user_id}`? Their favorites and play history are deleted and their name is removed from sounds they added",
+            "¿Olvidar al usuario `{user_id}`? Se eliminarán sus favoritos e historial de reproducciones y se quitará su nombre de los sonidos que añadió",
+            "Benutzer `{user_id}` vergessen? Favoriten und Wiedergabeverlauf werden gelöscht und der Name wird von hinzugefügten Sounds entfernt",
+            "Oublier l'utilisateur `{user_id}` ? Ses favoris et son historique de lecture seront supprimés et son nom retiré des sons qu'il a ajoutés",
+        ],
+    ),
+    (
+        "privacy.confirm_forget_me",
+        [
+            "Forget you? Your favorites and play history are deleted and your name is removed from sounds you added. Sounds you added are kept",
+            "¿Olvidarte? Se eliminarán tus favoritos e historial de reproducciones y se quitará tu nombre de los sonidos que añadiste. Los sonidos se conservan",
+            "Dich vergessen? Deine Favoriten und dein Wiedergabeverlauf werden gelöscht und dein Name wird von deinen Sounds entfernt. Die Sounds bleiben erhalten",
+            "Vous oublier ? Vos favoris et votre historique de lecture seront supprimés et votre nom retiré des sons que vous avez ajoutés. Les sons sont conservés",
+        ],
+    ),
+    (
+        "privacy.purged",
+        [
+            "Forgot user. Removed author from {sounds} sounds, deleted {favorites} favorites and {plays} play records",
+            "Usuario olvidado. Se quitó el autor de {sounds} sonidos y se eliminaron {favorites} favoritos y {plays} registros de reproducción",
+            "Benutzer vergessen. Autor von {sounds} Sounds entfernt, {favorites} Favoriten und {plays} Wiedergabeeinträge gelöscht",
+            "Utilisateur oublié. Auteur retiré de {sounds} sons, {favorites} favoris et {plays} lectures enregistrées supprimés",
+        ],
+    ),
+    (
+        "owner.confirm_leave",
+        [
+            "Leave server **{guild}**?",
+            "¿Salir del servidor **{guild}**?",
+            "Server **{guild}** verlassen?",
+            "Quitter le serveur **{guild}** ?",
+        ],
+    ),
+    (
+        "owner.left_guild",
+        [
+            "Left server **{guild}**",
+            "Salí del servidor **{guild}**",
+            "Server **{guild}** verlassen",
+            "Serveur **{guild}** quitté",
+        ],
+    ),
+    (
+        "owner.sql_error",
+        [
+            "Query failed - {error}",
+            "La consulta falló - {error}",
+            "Abfrage fehlgeschlagen - {error}",
+            "La requête a échoué - {error}",
+        ],
+    ),
+    (
+        "owner.config_reloaded",
+        [
+            "Config reloaded. Token, application id, database file, owners and presence changes need a restart",
+            "Configuración recargada. Los cambios de token, id de aplicación, archivo de base de datos, propietarios y presencia requieren reiniciar",
+            "Konfiguration neu geladen. Änderungen an Token, Anwendungs-ID, Datenbankdatei, Besitzern und Präsenz erfordern einen Neustart",
+            "Configuration rechargée. Les changements de token, id d'application, fichier de base de données, propriétaires et présence nécessitent un redémarrage",
+        ],
+    ),
+    (
+        "owner.backup_done",
+        [
+            "Database backed up to `{file}`",
+            "Base de datos respaldada en `{file}`",
+            "Datenbank gesichert nach `{file}`",
+            "Base de données sauvegardée dans `{file}`",
+        ],
+    ),
+    (
+        "owner.config_error",
+        [
+            "Failed to reload config - {error}",
+            "No se pudo recargar la configuración - {error}",
+            "Konfiguration konnte nicht neu geladen werden - {error}",
+            "Échec du rechargement de la configuration - {error}",
+        ],
+    ),
+    (
+        "scan.progress",
+        [
+            "Scanning audio directory... probed {count} files",
+            "Escaneando el directorio de audio... {count} archivos analizados",
+            "Audioverzeichnis wird durchsucht... {count} Dateien geprüft",
+            "Analyse du dossier audio... {count} fichiers analysés",
+        ],
+    ),
+    (
+        "scan.complete",
+        [
+            "Scan complete - added {count} new sounds",
+            "Escaneo completo - {count} sonidos nuevos añadidos",
+            "Suche abgeschlossen - {count} neue Sounds hinzugefügt",
+            "Analyse terminée - {count} nouveaux sons ajoutés",
+        ],
+    ),
+    (
+        "soundboard.tag_too_long",
+        [
+            "Tag is too long to filter by (max {max} chars)",
+            "La etiqueta es demasiado larga para filtrar (máx. {max} caracteres)",
+            "Tag ist zu lang zum Filtern (max. {max} Zeichen)",
+            "Le tag est trop long pour filtrer (max. {max} caractères)",
+        ],
+    ),
+    (
+        "soundboard.published",
+        [
+            "Soundboard published to {channel}",
+            "Soundboard publicado en {channel}",
+            "Soundboard in {channel} veröffentlicht",
+            "Soundboard publié dans {channel}",
+        ],
+    ),
+    (
+        "soundboard.mine_empty",
+        [
+            "You haven't uploaded or favorited any sounds yet. Try `/sounds favorite`",
+            "Aún no has subido ni marcado como favorito ningún sonido. Prueba `/sounds favorite`",
+            "Du hast noch keine Sounds hochgeladen oder favorisiert. Probiere `/sounds favorite`",
+            "Vous n'avez encore ajouté ni mis en favori aucun son. Essayez `/sounds favorite`",
+        ],
+    ),
+    (
+        "soundboard.mine_title",
+        [
+            "**Your soundboard** - uploads and favorites, most played first",
+            "**Tu soundboard** - subidas y favoritos, los más reproducidos primero",
+            "**Dein Soundboard** - Uploads und Favoriten, meistgespielte zuerst",
+            "**Votre soundboard** - ajouts et favoris, les plus joués en premier",
+        ],
+    ),
+    (
+        "soundboard.title",
+        [
+            "**Soundboard** - {count} sounds{tagged}{trending} (page {page} of {page_count})",
+            "**Soundboard** - {count} sonidos{tagged}{trending} (página {page} de {page_count})",
+            "**Soundboard** - {count} Sounds{tagged}{trending} (Seite {page} von {page_count})",
+            "**Soundboard** - {count} sons{tagged}{trending} (page {page} sur {page_count})",
+        ],
+    ),
+    (
+        "soundboard.empty",
+        [
+            "**Soundboard** - no sounds{tagged} yet. Add one with `/sounds add`",
+            "**Soundboard** - aún no hay sonidos{tagged}. Añade uno con `/sounds add`",
+            "**Soundboard** - noch keine Sounds{tagged}. Füge einen mit `/sounds add` hinzu",
+            "**Soundboard** - pas encore de sons{tagged}. Ajoutez-en un avec `/sounds add`",
+        ],
+    ),
+    (
+        "soundboard.tagged",
+        [
+            " tagged `{tag}`",
+            " con la etiqueta `{tag}`",
+            " mit dem Tag `{tag}`",
+            " avec le tag `{tag}`",
+        ],
+    ),
+    (
+        "soundboard.trending_first",
+        [
+            ", trending first",
+            ", tendencias primero",
+            ", angesagte zuerst",
+            ", tendances en premier",
+        ],
+    ),
+    (
+        "soundboard.other",
+        ["Other", "Otros", "Andere", "Autres"],
+    ),
+    (
+        "soundboard.all_sounds",
+        ["All sounds", "Todos los sonidos", "Alle Sounds", "Tous les sons"],
+    ),
+    (
+        "soundboard.tag_sounds",
+        ["{count} sounds", "{count} sonidos", "{count} Sounds", "{count} sons"],
+    ),
+    (
+        "soundboard.filter_by_tag",
+        [
+            "Filter by tag",
+            "Filtrar por etiqueta",
+            "Nach Tag filtern",
+            "Filtrer par tag",
+        ],
+    ),
+    (
+        "soundboard.prev",
+        ["◀ Prev", "◀ Anterior", "◀ Zurück", "◀ Précédent"],
+    ),
+    (
+        "soundboard.next",
+        ["Next ▶", "Siguiente ▶", "Weiter ▶", "Suivant ▶"],
+    ),
+    (
+        "permissions.granted",
+        [
+            "Granted `{permission}` to {role}. Only members with a granted role can now use it",
+            "`{permission}` concedido a {role}. Ahora solo los miembros con un rol concedido pueden usarlo",
+            "`{permission}` an {role} vergeben. Nur Mitglieder mit einer vergebenen Rolle können es jetzt nutzen",
+            "`{permission}` accordé à {role}. Seuls les membres ayant un rôle accordé peuvent maintenant l'utiliser",
+        ],
+    ),
+    (
+        "permissions.revoked",
+        [
+            "Revoked `{permission}` from {role}",
+            "`{permission}` revocado de {role}",
+            "`{permission}` von {role} entzogen",
+            "`{permission}` retiré à {role}",
+        ],
+    ),
+    (
+        "permissions.revoked_last",
+        [
+            "Revoked `{permission}` from {role}. No roles left, so everyone can now use it",
+            "`{permission}` revocado de {role}. No quedan roles, así que ahora todos pueden usarlo",
+            "`{permission}` von {role} entzogen. Keine Rollen übrig, daher können es jetzt alle nutzen",
+            "`{permission}` retiré à {role}. Plus aucun rôle, donc tout le monde peut maintenant l'utiliser",
+        ],
+    ),
+    (
+        "permissions.everyone",
+        ["everyone", "todos", "alle", "tout le monde"],
+    ),
+    (
+        "permissions.admins",
+        [
+            "administrators",
+            "administradores",
+            "Administratoren",
+            "administrateurs",
+        ],
+    ),
+    (
+        "permissions.title",
+        ["**Permissions**", "**Permisos**", "**Berechtigungen**", "**Permissions**"],
+    ),
+    (
+        "permissions.denied",
+        [
+            "You need one of these roles to use `{permission}`: {roles}",
+            "Necesitas uno de estos roles para usar `{permission}`: {roles}",
+            "Du brauchst eine dieser Rollen für `{permission}`: {roles}",
+            "Il vous faut l'un de ces rôles pour utiliser `{permission}` : {roles}",
+        ],
+    ),
+    (
+        "bulk.invalid_age",
+        [
+            "Invalid age `{age}`. Use e.g. `12h`, `30d`, `2w`, `6mo` or `1y`",
+            "Antigüedad `{age}` no válida. Usa p. ej. `12h`, `30d`, `2w`, `6mo` o `1y`",
+            "Ungültiges Alter `{age}`. Nutze z. B. `12h`, `30d`, `2w`, `6mo` oder `1y`",
+            "Âge `{age}` invalide. Utilisez par ex. `12h`, `30d`, `2w`, `6mo` ou `1y`",
+        ],
+    ),
+    (
+        "bulk.no_filter",
+        [
+            "Specify at least one of `tag`, `author` or `older-than`",
+            "Indica al menos uno de `tag`, `author` u `older-than`",
+            "Gib mindestens `tag`, `author` oder `older-than` an",
+            "Indiquez au moins `tag`, `author` ou `older-than`",
+        ],
+    ),
+    (
+        "bulk.no_matches",
+        [
+            "No sounds match the filter. Nothing was changed",
+            "Ningún sonido coincide con el filtro. No se cambió nada",
+            "Keine Sounds passen zum Filter. Nichts wurde geändert",
+            "Aucun son ne correspond au filtre. Rien n'a été modifié",
+        ],
+    ),
+    (
+        "bulk.confirm_delete",
+        [
+            "Delete {count} sounds? {preview}",
+            "¿Eliminar {count} sonidos? {preview}",
+            "{count} Sounds löschen? {preview}",
+            "Supprimer {count} sons ? {preview}",
+        ],
+    ),
+    (
+        "bulk.confirm_retag",
+        [
+            "Retag {count} sounds as `{tags}`? {preview}",
+            "¿Cambiar las etiquetas de {count} sonidos a `{tags}`? {preview}",
+            "Tags von {count} Sounds durch `{tags}` ersetzen? {preview}",
+            "Remplacer les tags de {count} sons par `{tags}` ? {preview}",
+        ],
+    ),
+    (
+        "bulk.and_more",
+        [
+            "and {count} more",
+            "y {count} más",
+            "und {count} weitere",
+            "et {count} de plus",
+        ],
+    ),
+    (
+        "bulk.deleted",
+        [
+            "Deleted {count} sounds",
+            "{count} sonidos eliminados",
+            "{count} Sounds gelöscht",
+            "{count} sons supprimés",
+        ],
+    ),
+    (
+        "bulk.retagged",
+        [
+            "Retagged {count} sounds",
+            "Etiquetas de {count} sonidos cambiadas",
+            "Tags von {count} Sounds ersetzt",
+            "Tags de {count} sons remplacés",
+        ],
+    ),
+    (
+        "blocklist.added",
+        [
+            "Blocked `{pattern}`",
+            "`{pattern}` bloqueado",
+            "`{pattern}` blockiert",
+            "`{pattern}` bloqué",
+        ],
+    ),
+    (
+        "blocklist.invalid_regex",
+        [
+            "Invalid regex `{pattern}` - {error}",
+            "Regex no válida `{pattern}` - {error}",
+            "Ungültige Regex `{pattern}` - {error}",
+            "Regex invalide `{pattern}` - {error}",
+        ],
+    ),
+    (
+        "blocklist.removed",
+        [
+            "Unblocked `{pattern}`",
+            "`{pattern}` desbloqueado",
+            "`{pattern}` freigegeben",
+            "`{pattern}` débloqué",
+        ],
+    ),
+    (
+        "blocklist.not_found",
+        [
+            "`{pattern}` isn't in the blocklist",
+            "`{pattern}` no está en la lista de bloqueo",
+            "`{pattern}` ist nicht in der Sperrliste",
+            "`{pattern}` n'est pas dans la liste de blocage",
+        ],
+    ),
+    (
+        "blocklist.empty",
+        [
+            "Blocklist is empty",
+            "La lista de bloqueo está vacía",
+            "Die Sperrliste ist leer",
+            "La liste de blocage est vide",
+        ],
+    ),
+    (
+        "blocklist.title",
+        [
+            "**Blocklist**",
+            "**Lista de bloqueo**",
+            "**Sperrliste**",
+            "**Liste de blocage**",
+        ],
+    ),
+    (
+        "blocklist.blocked",
+        [
+            "That contains a word blocked by this server's moderators",
+            "Contiene una palabra bloqueada por los moderadores de este servidor",
+            "Das enthält ein Wort, das von den Moderatoren dieses Servers blockiert wurde",
+            "Cela contient un mot bloqué par les modérateurs de ce serveur",
+        ],
+    ),
+    (
+        "sound_name.empty",
+        [
+            "Sound name can't be empty",
+            "El nombre del sonido no puede estar vacío",
+            "Der Soundname darf nicht leer sein",
+            "Le nom du son ne peut pas être vide",
+        ],
+    ),
+    (
+        "sound_name.too_long",
+        [
+            "Sound name can be at most {max} characters long",
+            "El nombre del sonido puede tener como máximo {max} caracteres",
+            "Der Soundname darf höchstens {max} Zeichen lang sein",
+            "Le nom du son ne peut pas dépasser {max} caractères",
+        ],
+    ),
+    (
+        "sound_name.invalid_char",
+        [
+            "Sound name can't contain '{char}'",
+            "El nombre del sonido no puede contener '{char}'",
+            "Der Soundname darf '{char}' nicht enthalten",
+            "Le nom du son ne peut pas contenir '{char}'",
+        ],
+    ),
+    (
+        "sounds.name_taken",
+        [
+            "`{track}` is already taken. Pick another name:",
+            "`{track}` ya existe. Elige otro nombre:",
+            "`{track}` ist bereits vergeben. Wähle einen anderen Namen:",
+            "`{track}` est déjà pris. Choisissez un autre nom :",
+        ],
+    ),
+    (
+        "sounds.name_picked",
+        [
+            "Adding sound as `{track}`",
+            "Añadiendo el sonido como `{track}`",
+            "Sound wird als `{track}` hinzugefügt",
+            "Ajout du son sous le nom `{track}`",
+        ],
+    ),
+    (
+        "sounds.uploaded",
+        [
+            "Added {uploaded}/{total} sounds. Rename them with `/sounds edit`",
+            "{uploaded}/{total} sonidos añadidos. Renómbralos con `/sounds edit`",
+            "{uploaded}/{total} Sounds hinzugefügt. Benenne sie mit `/sounds edit` um",
+            "{uploaded}/{total} sons ajoutés. Renommez-les avec `/sounds edit`",
+        ],
+    ),
+    (
+        "sounds.shared",
+        [
+            "Share code of `{track}`: `{code}`. Import it in another server with `/sounds import-code`",
+            "Código para compartir `{track}`: `{code}`. Impórtalo en otro servidor con `/sounds import-code`",
+            "Teilcode von `{track}`: `{code}`. Importiere ihn auf einem anderen Server mit `/sounds import-code`",
+            "Code de partage de `{track}` : `{code}`. Importez-le dans un autre serveur avec `/sounds import-code`",
+        ],
+    ),
+    (
+        "sounds.share_code_not_found",
+        [
+            "No sound is shared with code `{code}`",
+            "Ningún sonido se comparte con el código `{code}`",
+            "Kein Sound wird mit dem Code `{code}` geteilt",
+            "Aucun son n'est partagé avec le code `{code}`",
+        ],
+    ),
+    (
+        "sounds.share_code_same_guild",
+        [
+            "This sound was shared from this server, so it's already here",
+            "Este sonido se compartió desde este servidor, así que ya está aquí",
+            "Dieser Sound wurde von diesem Server geteilt und ist schon hier",
+            "Ce son a été partagé depuis ce serveur, il est donc déjà ici",
+        ],
+    ),
+    (
+        "settings.exported",
+        [
+            "Settings of this server. Restore them with `/settings import`",
+            "Ajustes de este servidor. Restáuralos con `/settings import`",
+            "Einstellungen dieses Servers. Stelle sie mit `/settings import` wieder her",
+            "Paramètres de ce serveur. Restaurez-les avec `/settings import`",
+        ],
+    ),
+    (
+        "settings.import_invalid",
+        [
+            "Couldn't read settings file - {error}",
+            "No se pudo leer el archivo de ajustes - {error}",
+            "Einstellungsdatei konnte nicht gelesen werden - {error}",
+            "Impossible de lire le fichier de paramètres - {error}",
+        ],
+    ),
+    (
+        "settings.confirm_import",
+        [
+            "Replace this server's settings, permissions, allowed channels, blocklist and native sound mappings with the imported ones?",
+            "¿Reemplazar los ajustes, permisos, canales permitidos, lista de bloqueo y sonidos nativos de este servidor por los importados?",
+            "Einstellungen, Berechtigungen, erlaubte Kanäle, Sperrliste und native Sounds dieses Servers durch die importierten ersetzen?",
+            "Remplacer les paramètres, permissions, salons autorisés, liste de blocage et sons natifs de ce serveur par ceux importés ?",
+        ],
+    ),
+    (
+        "settings.imported",
+        [
+            "Settings imported: {permissions} role permissions, {channels} allowed channels, {patterns} blocked patterns, {native_sounds} native sound mappings",
+            "Ajustes importados: {permissions} permisos de roles, {channels} canales permitidos, {patterns} patrones bloqueados, {native_sounds} sonidos nativos",
+            "Einstellungen importiert: {permissions} Rollenberechtigungen, {channels} erlaubte Kanäle, {patterns} gesperrte Muster, {native_sounds} native Sounds",
+            "Paramètres importés : {permissions} permissions de rôles, {channels} salons autorisés, {patterns} motifs bloqués, {native_sounds} sons natifs",
+        ],
+    ),
+    (
+        "settings.import_missing_sounds",
+        [
+            "Native sound mappings of sounds not on this bot were skipped: {sounds}",
+            "Se omitieron sonidos nativos de sonidos que no están en este bot: {sounds}",
+            "Native Sounds von Sounds, die dieser Bot nicht hat, wurden übersprungen: {sounds}",
+            "Les sons natifs de sons absents de ce bot ont été ignorés : {sounds}",
+        ],
+    ),
+    (
+        "read_only.refused",
+        [
+            "This bot is a read-only mirror, it only plays and searches sounds",
+            "Este bot es un espejo de solo lectura, solo reproduce y busca sonidos",
+            "Dieser Bot ist ein schreibgeschützter Spiegel und spielt und sucht nur Sounds",
+            "Ce bot est un miroir en lecture seule, il ne fait que jouer et chercher des sons",
+        ],
+    ),
+    (
+        "packs.not_configured",
+        [
+            "No sound pack repository is set up for this bot",
+            "Este bot no tiene un repositorio de paquetes de sonidos",
+            "Für diesen Bot ist kein Soundpaket-Repository eingerichtet",
+            "Aucun dépôt de packs de sons n'est configuré pour ce bot",
+        ],
+    ),
+    (
+        "packs.empty",
+        [
+            "The sound pack repository has no packs",
+            "El repositorio de paquetes de sonidos no tiene paquetes",
+            "Das Soundpaket-Repository hat keine Pakete",
+            "Le dépôt de packs de sons n'a aucun pack",
+        ],
+    ),
+    (
+        "packs.list_title",
+        [
+            "**Sound packs**",
+            "**Paquetes de sonidos**",
+            "**Soundpakete**",
+            "**Packs de sons**",
+        ],
+    ),
+    (
+        "packs.install_hint",
+        [
+            "Admins can install a pack with `/packs install`",
+            "Los administradores pueden instalar un paquete con `/packs install`",
+            "Admins können ein Paket mit `/packs install` installieren",
+            "Les administrateurs peuvent installer un pack avec `/packs install`",
+        ],
+    ),
+    (
+        "packs.not_found",
+        [
+            "No sound pack `{pack}` found. See `/packs browse`",
+            "No se encontró el paquete de sonidos `{pack}`. Consulta `/packs browse`",
+            "Kein Soundpaket `{pack}` gefunden. Siehe `/packs browse`",
+            "Aucun pack de sons `{pack}` trouvé. Voir `/packs browse`",
+        ],
+    ),
+    (
+        "packs.downloading",
+        [
+            "Downloading sound pack **{pack}**... {progress}",
+            "Descargando el paquete de sonidos **{pack}**... {progress}",
+            "Soundpaket **{pack}** wird heruntergeladen... {progress}",
+            "Téléchargement du pack de sons **{pack}**... {progress}",
+        ],
+    ),
+    (
+        "packs.verifying",
+        [
+            "Verifying sound pack **{pack}**...",
+            "Verificando el paquete de sonidos **{pack}**...",
+            "Soundpaket **{pack}** wird überprüft...",
+            "Vérification du pack de sons **{pack}**...",
+        ],
+    ),
+    (
+        "packs.validating",
+        [
+            "Checking sounds of **{pack}**... {progress}",
+            "Comprobando los sonidos de **{pack}**... {progress}",
+            "Sounds von **{pack}** werden geprüft... {progress}",
+            "Vérification des sons de **{pack}**... {progress}",
+        ],
+    ),
+    (
+        "packs.installed",
+        [
+            "Installed {count} sounds of **{pack}**",
+            "{count} sonidos de **{pack}** instalados",
+            "{count} Sounds von **{pack}** installiert",
+            "{count} sons de **{pack}** installés",
+        ],
+    ),
+    (
+        "packs.hash_mismatch",
+        [
+            "Sound pack **{pack}** doesn't match its published hash, so it wasn't installed",
+            "El paquete de sonidos **{pack}** no coincide con su hash publicado, así que no se instaló",
+            "Soundpaket **{pack}** stimmt nicht mit seinem veröffentlichten Hash überein und wurde nicht installiert",
+            "Le pack de sons **{pack}** ne correspond pas à son hash publié, il n'a donc pas été installé",
+        ],
+    ),
+    (
+        "sounds.anonymous",
+        ["anonymous", "anónimo", "anonym", "anonyme"],
+    ),
+    (
+        "sounds.added",
+        [
+            "Added sound `{track}` to soundboard",
+            "Sonido `{track}` añadido al soundboard",
+            "Sound `{track}` zum Soundboard hinzugefügt",
+            "Son `{track}` ajouté au soundboard",
+        ],
+    ),
+    (
+        "sounds.confirm_remove",
+        [
+            "Remove sound `{track}`? It can be restored from the trash with `/sounds restore`",
+            "¿Eliminar el sonido `{track}`? Se puede recuperar de la papelera con `/sounds restore`",
+            "Sound `{track}` entfernen? Er kann mit `/sounds restore` aus dem Papierkorb zurückgeholt werden",
+            "Supprimer le son `{track}` ? Il peut être récupéré de la corbeille avec `/sounds restore`",
+        ],
+    ),
+    (
+        "sounds.removed",
+        [
+            "Removed audio track `{track}`",
+            "Sonido `{track}` eliminado",
+            "Sound `{track}` entfernt",
+            "Son `{track}` supprimé",
+        ],
+    ),
+    (
+        "sounds.displaying",
+        [
+            "Displaying sounds...",
+            "Mostrando sonidos...",
+            "Sounds werden angezeigt...",
+            "Affichage des sons...",
+        ],
+    ),
+    (
+        "settings.join_audio_disabled",
+        [
+            "Bot join audio disabled",
+            "Sonido de entrada del bot desactivado",
+            "Beitritts-Sound des Bots deaktiviert",
+            "Son d'arrivée du bot désactivé",
+        ],
+    ),
+    (
+        "settings.join_audio_set",
+        [
+            "Bot join audio set to {track}",
+            "Sonido de entrada del bot: {track}",
+            "Beitritts-Sound des Bots: {track}",
+            "Son d'arrivée du bot : {track}",
+        ],
+    ),
+    (
+        "settings.leave_audio_disabled",
+        [
+            "Bot leave audio disabled",
+            "Sonido de salida del bot desactivado",
+            "Abschieds-Sound des Bots deaktiviert",
+            "Son de départ du bot désactivé",
+        ],
+    ),
+    (
+        "settings.leave_audio_set",
+        [
+            "Bot leave audio set to {track}",
+            "Sonido de salida del bot: {track}",
+            "Abschieds-Sound des Bots: {track}",
+            "Son de départ du bot : {track}",
+        ],
+    ),
+    (
+        "settings.language_set",
+        [
+            "Language set to {language}",
+            "Idioma cambiado a {language}",
+            "Sprache auf {language} gesetzt",
+            "Langue définie sur {language}",
+        ],
+    ),
+    (
+        "settings.language_reset",
+        [
+            "Language reset to this server's Discord locale",
+            "Idioma restablecido a la configuración regional de Discord de este servidor",
+            "Sprache auf das Discord-Gebietsschema dieses Servers zurückgesetzt",
+            "Langue réinitialisée sur la langue Discord de ce serveur",
+        ],
+    ),
+    (
+        "settings.prefix_set",
+        [
+            "Command prefix is now `{prefix}`, e.g. `{prefix}play`",
+            "El prefijo de comandos ahora es `{prefix}`, p. ej. `{prefix}play`",
+            "Das Befehlspräfix ist jetzt `{prefix}`, z. B. `{prefix}play`",
+            "Le préfixe des commandes est maintenant `{prefix}`, par ex. `{prefix}play`",
+        ],
+    ),
+    (
+        "settings.prefix_invalid",
+        [
+            "Command prefix can't be empty or contain spaces",
+            "El prefijo de comandos no puede estar vacío ni contener espacios",
+            "Das Befehlspräfix darf nicht leer sein oder Leerzeichen enthalten",
+            "Le préfixe des commandes ne peut pas être vide ni contenir d'espaces",
+        ],
+    ),
+    (
+        "settings.sotd_set",
+        [
+            "Sound of the day will be posted in {channel} daily at {time} UTC",
+            "El sonido del día se publicará en {channel} cada día a las {time} UTC",
+            "Der Sound des Tages wird täglich um {time} UTC in {channel} gepostet",
+            "Le son du jour sera publié dans {channel} chaque jour à {time} UTC",
+        ],
+    ),
+    (
+        "settings.sotd_voice_set",
+        [
+            "Sound of the day will be posted in {channel} and played in {voice_channel} daily at {time} UTC",
+            "El sonido del día se publicará en {channel} y se reproducirá en {voice_channel} cada día a las {time} UTC",
+            "Der Sound des Tages wird täglich um {time} UTC in {channel} gepostet und in {voice_channel} abgespielt",
+            "Le son du jour sera publié dans {channel} et joué dans {voice_channel} chaque jour à {time} UTC",
+        ],
+    ),
+    (
+        "settings.sotd_disabled",
+        [
+            "Sound of the day disabled",
+            "Sonido del día desactivado",
+            "Sound des Tages deaktiviert",
+            "Son du jour désactivé",
+        ],
+    ),
+    (
+        "settings.sotd_time_invalid",
+        [
+            "Time must be a UTC time of day like `18:30`",
+            "La hora debe ser una hora UTC como `18:30`",
+            "Die Uhrzeit muss eine UTC-Uhrzeit wie `18:30` sein",
+            "L'heure doit être une heure UTC comme `18:30`",
+        ],
+    ),
+    (
+        "native_sounds.recording",
+        [
+            "Plays of mapped Discord soundboard sounds are now recorded in play statistics",
+            "Las reproducciones de sonidos de la caja de sonidos de Discord asignados ahora se registran en las estadísticas",
+            "Wiedergaben zugeordneter Discord-Soundboard-Sounds werden jetzt in der Statistik erfasst",
+            "Les lectures des sons de la soundboard Discord associés sont maintenant enregistrées dans les statistiques",
+        ],
+    ),
+    (
+        "native_sounds.not_recording",
+        [
+            "Plays of Discord soundboard sounds are no longer recorded",
+            "Las reproducciones de sonidos de la caja de sonidos de Discord ya no se registran",
+            "Wiedergaben von Discord-Soundboard-Sounds werden nicht mehr erfasst",
+            "Les lectures des sons de la soundboard Discord ne sont plus enregistrées",
+        ],
+    ),
+    (
+        "native_sounds.mapped",
+        [
+            "Discord soundboard sound `{sound_id}` now counts as `{track}`",
+            "El sonido de Discord `{sound_id}` ahora cuenta como `{track}`",
+            "Discord-Soundboard-Sound `{sound_id}` zählt jetzt als `{track}`",
+            "Le son Discord `{sound_id}` compte maintenant comme `{track}`",
+        ],
+    ),
+    (
+        "native_sounds.unmapped",
+        [
+            "Discord soundboard sound `{sound_id}` unmapped",
+            "Asignación del sonido de Discord `{sound_id}` eliminada",
+            "Zuordnung des Discord-Soundboard-Sounds `{sound_id}` entfernt",
+            "Association du son Discord `{sound_id}` supprimée",
+        ],
+    ),
+    (
+        "native_sounds.not_mapped",
+        [
+            "Discord soundboard sound `{sound_id}` isn't mapped",
+            "El sonido de Discord `{sound_id}` no está asignado",
+            "Discord-Soundboard-Sound `{sound_id}` ist nicht zugeordnet",
+            "Le son Discord `{sound_id}` n'est pas associé",
+        ],
+    ),
+    (
+        "native_sounds.invalid_id",
+        [
+            "Discord soundboard sound ids are numbers, e.g. `1234567890`",
+            "Los ids de sonidos de Discord son números, p. ej. `1234567890`",
+            "Discord-Soundboard-Sound-IDs sind Zahlen, z. B. `1234567890`",
+            "Les ids des sons Discord sont des nombres, par ex. `1234567890`",
+        ],
+    ),
+    (
+        "native_sounds.empty",
+        [
+            "No Discord soundboard sounds mapped",
+            "No hay sonidos de Discord asignados",
+            "Keine Discord-Soundboard-Sounds zugeordnet",
+            "Aucun son Discord associé",
+        ],
+    ),
+    (
+        "native_sounds.title",
+        [
+            "Discord soundboard sounds counted as bot sounds:",
+            "Sonidos de Discord contados como sonidos del bot:",
+            "Discord-Soundboard-Sounds, die als Bot-Sounds zählen:",
+            "Sons Discord comptés comme sons du bot :",
+        ],
+    ),
+    (
+        "stats.history",
+        [
+            "**Plays**",
+            "**Reproducciones**",
+            "**Wiedergaben**",
+            "**Lectures**",
+        ],
+    ),
+    (
+        "stats.history_track",
+        [
+            "**Plays of `{track}`**",
+            "**Reproducciones de `{track}`**",
+            "**Wiedergaben von `{track}`**",
+            "**Lectures de `{track}`**",
+        ],
+    ),
+    (
+        "stats.total",
+        [
+            "{plays} plays in total",
+            "{plays} reproducciones en total",
+            "{plays} Wiedergaben insgesamt",
+            "{plays} lectures au total",
+        ],
+    ),
+    (
+        "stats.heatmap",
+        [
+            "**Plays by hour (UTC) and day of week, last {days} days**",
+            "**Reproducciones por hora (UTC) y día de la semana, últimos {days} días**",
+            "**Wiedergaben nach Stunde (UTC) und Wochentag, letzte {days} Tage**",
+            "**Lectures par heure (UTC) et jour de la semaine, {days} derniers jours**",
+        ],
+    ),
+    (
+        "stats.heatmap_days",
+        [
+            "Mon Tue Wed Thu Fri Sat Sun",
+            "Lun Mar Mié Jue Vie Sáb Dom",
+            "Mo Di Mi Do Fr Sa So",
+            "Lun Mar Mer Jeu Ven Sam Dim",
+        ],
+    ),
+    (
+        "prune.title",
+        [
+            "**Sounds not played in {months} months**",
+            "**Sonidos no reproducidos en {months} meses**",
+            "**Seit {months} Monaten nicht abgespielte Sounds**",
+            "**Sons non joués depuis {months} mois**",
+        ],
+    ),
+    (
+        "prune.none",
+        [
+            "Every sound was played in the last {months} months",
+            "Todos los sonidos se reprodujeron en los últimos {months} meses",
+            "Alle Sounds wurden in den letzten {months} Monaten abgespielt",
+            "Tous les sons ont été joués ces {months} derniers mois",
+        ],
+    ),
+    (
+        "prune.never_played",
+        ["never played", "nunca reproducido", "nie abgespielt", "jamais joué"],
+    ),
+    (
+        "prune.archive",
+        [
+            "Archive {track}",
+            "Archivar {track}",
+            "{track} archivieren",
+            "Archiver {track}",
+        ],
+    ),
+    (
+        "prune.keep",
+        ["Keep", "Conservar", "Behalten", "Garder"],
+    ),
+    (
+        "prune.archived",
+        [
+            "Archived `{track}`",
+            "`{track}` archivado",
+            "`{track}` archiviert",
+            "`{track}` archivé",
+        ],
+    ),
+    (
+        "prune.kept",
+        [
+            "Keeping `{track}`, it won't be suggested again until it's unused for a while",
+            "Se conserva `{track}`, no se sugerirá de nuevo hasta que no se use por un tiempo",
+            "`{track}` wird behalten und erst wieder vorgeschlagen, wenn er eine Weile unbenutzt ist",
+            "`{track}` est conservé et ne sera plus suggéré avant une période sans utilisation",
+        ],
+    ),
+    (
+        "prune.not_found",
+        [
+            "Sound no longer exists",
+            "El sonido ya no existe",
+            "Der Sound existiert nicht mehr",
+            "Le son n'existe plus",
+        ],
+    ),
+    (
+        "onboarding.welcome",
+        [
+            "👋 Thanks for adding the soundboard! Admins can get started with the buttons below, or see `/sounds help` for every command",
+            "👋 ¡Gracias por añadir el soundboard! Los administradores pueden empezar con los botones de abajo, o ver `/sounds help` para todos los comandos",
+            "👋 Danke für das Hinzufügen des Soundboards! Admins können mit den Buttons unten loslegen, oder alle Befehle mit `/sounds help` ansehen",
+            "👋 Merci d'avoir ajouté le soundboard ! Les administrateurs peuvent commencer avec les boutons ci-dessous, ou voir toutes les commandes avec `/sounds help`",
+        ],
+    ),
+    (
+        "onboarding.channel_button",
+        [
+            "Create soundboard channel",
+            "Crear canal de sonidos",
+            "Soundboard-Kanal erstellen",
+            "Créer un salon soundboard",
+        ],
+    ),
+    (
+        "onboarding.limits_button",
+        [
+            "Set basic limits",
+            "Establecer límites básicos",
+            "Grundlegende Limits setzen",
+            "Définir des limites de base",
+        ],
+    ),
+    (
+        "onboarding.starter_sounds_button",
+        [
+            "Import starter sounds",
+            "Importar sonidos iniciales",
+            "Starter-Sounds importieren",
+            "Importer des sons de départ",
+        ],
+    ),
+    (
+        "onboarding.channel_created",
+        [
+            "Created {channel} with the soundboard pinned",
+            "Se creó {channel} con el soundboard fijado",
+            "{channel} mit angeheftetem Soundboard erstellt",
+            "{channel} créé avec le soundboard épinglé",
+        ],
+    ),
+    (
+        "onboarding.commands_restricted",
+        [
+            "Soundboard commands can now only be used in {channel}",
+            "Los comandos de sonidos ahora solo se pueden usar en {channel}",
+            "Soundboard-Befehle können jetzt nur in {channel} verwendet werden",
+            "Les commandes de sons ne peuvent maintenant être utilisées que dans {channel}",
+        ],
+    ),
+    (
+        "onboarding.starter_sounds_imported",
+        [
+            "Imported {count} starter sounds",
+            "Se importaron {count} sonidos iniciales",
+            "{count} Starter-Sounds importiert",
+            "{count} sons de départ importés",
+        ],
+    ),
+    (
+        "onboarding.not_admin",
+        [
+            "Only administrators can set up the soundboard",
+            "Solo los administradores pueden configurar el soundboard",
+            "Nur Administratoren können das Soundboard einrichten",
+            "Seuls les administrateurs peuvent configurer le soundboard",
+        ],
+    ),
+    (
+        "onboarding.failed",
+        [
+            "Setup step failed - {error}",
+            "El paso de configuración falló - {error}",
+            "Einrichtungsschritt fehlgeschlagen - {error}",
+            "L'étape de configuration a échoué - {error}",
+        ],
+    ),
+    (
+        "prune.not_admin",
+        [
+            "Only administrators can archive or keep sounds",
+            "Solo los administradores pueden archivar o conservar sonidos",
+            "Nur Administratoren können Sounds archivieren oder behalten",
+            "Seuls les administrateurs peuvent archiver ou garder des sons",
+        ],
+    ),
+    (
+        "settings.auto_archive_set",
+        [
+            "Sounds not played for {months} months will be archived automatically",
+            "Los sonidos no reproducidos en {months} meses se archivarán automáticamente",
+            "Sounds, die {months} Monate nicht abgespielt wurden, werden automatisch archiviert",
+            "Les sons non joués depuis {months} mois seront archivés automatiquement",
+        ],
+    ),
+    (
+        "settings.approval_set",
+        [
+            "New sounds from members who can't approve sounds are now reviewed in {channel} first",
+            "Los sonidos nuevos de miembros que no pueden aprobar sonidos ahora se revisan primero en {channel}",
+            "Neue Sounds von Mitgliedern ohne Freigaberecht werden jetzt zuerst in {channel} geprüft",
+            "Les nouveaux sons des membres qui ne peuvent pas approuver de sons sont désormais vérifiés dans {channel}",
+        ],
+    ),
+    (
+        "settings.approval_disabled",
+        [
+            "New sounds are added right away, without review",
+            "Los sonidos nuevos se añaden al instante, sin revisión",
+            "Neue Sounds werden sofort ohne Prüfung hinzugefügt",
+            "Les nouveaux sons sont ajoutés immédiatement, sans vérification",
+        ],
+    ),
+    (
+        "approval.review",
+        [
+            "🔎 {author} submitted `{track}` ({duration}) for review",
+            "🔎 {author} envió `{track}` ({duration}) para revisión",
+            "🔎 {author} hat `{track}` ({duration}) zur Prüfung eingereicht",
+            "🔎 {author} a soumis `{track}` ({duration}) pour vérification",
+        ],
+    ),
+    (
+        "approval.listen_button",
+        ["Listen", "Escuchar", "Anhören", "Écouter"],
+    ),
+    (
+        "approval.approve_button",
+        ["Approve", "Aprobar", "Freigeben", "Approuver"],
+    ),
+    (
+        "approval.reject_button",
+        ["Reject", "Rechazar", "Ablehnen", "Refuser"],
+    ),
+    (
+        "approval.approved",
+        [
+            "✅ {moderator} approved `{track}`",
+            "✅ {moderator} aprobó `{track}`",
+            "✅ {moderator} hat `{track}` freigegeben",
+            "✅ {moderator} a approuvé `{track}`",
+        ],
+    ),
+    (
+        "approval.rejected",
+        [
+            "❌ {moderator} rejected `{track}`",
+            "❌ {moderator} rechazó `{track}`",
+            "❌ {moderator} hat `{track}` abgelehnt",
+            "❌ {moderator} a refusé `{track}`",
+        ],
+    ),
+    (
+        "approval.not_approver",
+        [
+            "You don't have permission to approve sounds",
+            "No tienes permiso para aprobar sonidos",
+            "Du darfst keine Sounds freigeben",
+            "Vous n'avez pas la permission d'approuver des sons",
+        ],
+    ),
+    (
+        "approval.not_found",
+        [
+            "This sound was already reviewed",
+            "Este sonido ya fue revisado",
+            "Dieser Sound wurde bereits geprüft",
+            "Ce son a déjà été vérifié",
+        ],
+    ),
+    (
+        "approval.submitted",
+        [
+            "Sent `{track}` to the moderators for review. It's added once approved",
+            "`{track}` enviado a los moderadores para revisión. Se añadirá cuando se apruebe",
+            "`{track}` wurde den Moderatoren zur Prüfung geschickt. Er wird nach der Freigabe hinzugefügt",
+            "`{track}` envoyé aux modérateurs pour vérification. Il sera ajouté une fois approuvé",
+        ],
+    ),
+    (
+        "approval.submitted_uploads",
+        [
+            "Sent {uploaded}/{total} sounds to the moderators for review. They're added once approved",
+            "{uploaded}/{total} sonidos enviados a los moderadores para revisión. Se añadirán cuando se aprueben",
+            "{uploaded}/{total} Sounds wurden den Moderatoren zur Prüfung geschickt. Sie werden nach der Freigabe hinzugefügt",
+            "{uploaded}/{total} sons envoyés aux modérateurs pour vérification. Ils seront ajoutés une fois approuvés",
+        ],
+    ),
+    (
+        "approval.flagged",
+        [
+            "⚠️ Flagged by moderation: {reason}",
+            "⚠️ Marcado por la moderación: {reason}",
+            "⚠️ Von der Moderation markiert: {reason}",
+            "⚠️ Signalé par la modération : {reason}",
+        ],
+    ),
+    (
+        "moderation.flagged",
+        [
+            "`{track}` was flagged ({reason}) and sent to the moderators for review. It's added once approved",
+            "`{track}` fue marcado ({reason}) y enviado a los moderadores para revisión. Se añadirá cuando se apruebe",
+            "`{track}` wurde markiert ({reason}) und den Moderatoren zur Prüfung geschickt. Er wird nach der Freigabe hinzugefügt",
+            "`{track}` a été signalé ({reason}) et envoyé aux modérateurs pour vérification. Il sera ajouté une fois approuvé",
+        ],
+    ),
+    (
+        "moderation.refused",
+        [
+            "`{track}` was flagged by moderation ({reason}) and wasn't added",
+            "`{track}` fue marcado por la moderación ({reason}) y no se añadió",
+            "`{track}` wurde von der Moderation markiert ({reason}) und nicht hinzugefügt",
+            "`{track}` a été signalé par la modération ({reason}) et n'a pas été ajouté",
+        ],
+    ),
+    (
+        "loudness.refused",
+        [
+            "`{track}` is too loud and wasn't added ({reason})",
+            "`{track}` es demasiado fuerte y no se añadió ({reason})",
+            "`{track}` ist zu laut und wurde nicht hinzugefügt ({reason})",
+            "`{track}` est trop fort et n'a pas été ajouté ({reason})",
+        ],
+    ),
+    (
+        "settings.loudness_allow",
+        [
+            "Sounds that are too loud are added anyway",
+            "Los sonidos demasiado fuertes se añaden igualmente",
+            "Zu laute Sounds werden trotzdem hinzugefügt",
+            "Les sons trop forts sont quand même ajoutés",
+        ],
+    ),
+    (
+        "settings.loudness_attenuate",
+        [
+            "Sounds that are too loud are turned down before they're added",
+            "Los sonidos demasiado fuertes se bajan de volumen antes de añadirse",
+            "Zu laute Sounds werden vor dem Hinzufügen leiser gemacht",
+            "Les sons trop forts sont baissés avant d'être ajoutés",
+        ],
+    ),
+    (
+        "settings.loudness_review",
+        [
+            "Sounds that are too loud are sent to the review channel",
+            "Los sonidos demasiado fuertes se envían al canal de revisión",
+            "Zu laute Sounds werden in den Prüfkanal geschickt",
+            "Les sons trop forts sont envoyés dans le salon de vérification",
+        ],
+    ),
+    (
+        "settings.loudness_reject",
+        [
+            "Sounds that are too loud are rejected",
+            "Los sonidos demasiado fuertes se rechazan",
+            "Zu laute Sounds werden abgelehnt",
+            "Les sons trop forts sont refusés",
+        ],
+    ),
+    (
+        "sounds.too_long",
+        [
+            "This sound is {duration} long, longer than this server's max of {max}",
+            "Este sonido dura {duration}, más que el máximo de {max} de este servidor",
+            "Dieser Sound ist {duration} lang, länger als das Maximum von {max} auf diesem Server",
+            "Ce son dure {duration}, plus que le maximum de {max} de ce serveur",
+        ],
+    ),
+    (
+        "settings.max_length_set",
+        [
+            "Sounds added to this server can now be up to {max} long",
+            "Los sonidos añadidos a este servidor ahora pueden durar hasta {max}",
+            "Sounds auf diesem Server dürfen jetzt bis zu {max} lang sein",
+            "Les sons ajoutés à ce serveur peuvent maintenant durer jusqu'à {max}",
+        ],
+    ),
+    (
+        "settings.max_length_reset",
+        [
+            "Sounds added to this server can be up to the bot's limit of {max} long",
+            "Los sonidos añadidos a este servidor pueden durar hasta el límite del bot de {max}",
+            "Sounds auf diesem Server dürfen bis zum Limit des Bots von {max} lang sein",
+            "Les sons ajoutés à ce serveur peuvent durer jusqu'à la limite du bot de {max}",
+        ],
+    ),
+    (
+        "settings.max_length_too_long",
+        [
+            "The max length can't exceed the bot's limit of {max}",
+            "La duración máxima no puede superar el límite del bot de {max}",
+            "Die maximale Länge darf das Limit des Bots von {max} nicht überschreiten",
+            "La durée maximale ne peut pas dépasser la limite du bot de {max}",
+        ],
+    ),
+    (
+        "sounds.url_invalid",
+        [
+            "That isn't a valid http(s) url",
+            "Esa no es una url http(s) válida",
+            "Das ist keine gültige http(s)-URL",
+            "Ce n'est pas une url http(s) valide",
+        ],
+    ),
+    (
+        "sounds.url_blocked",
+        [
+            "Sounds can't be downloaded from {host}",
+            "No se pueden descargar sonidos de {host}",
+            "Von {host} können keine Sounds heruntergeladen werden",
+            "Les sons ne peuvent pas être téléchargés depuis {host}",
+        ],
+    ),
+    (
+        "sounds.url_not_allowed",
+        [
+            "Sounds can't be downloaded from {host}, it isn't an allowed site",
+            "No se pueden descargar sonidos de {host}, no es un sitio permitido",
+            "Von {host} können keine Sounds heruntergeladen werden, die Seite ist nicht erlaubt",
+            "Les sons ne peuvent pas être téléchargés depuis {host}, ce site n'est pas autorisé",
+        ],
+    ),
+    (
+        "settings.auto_archive_disabled",
+        [
+            "Unused sounds are no longer archived automatically",
+            "Los sonidos sin usar ya no se archivan automáticamente",
+            "Unbenutzte Sounds werden nicht mehr automatisch archiviert",
+            "Les sons inutilisés ne sont plus archivés automatiquement",
+        ],
+    ),
+    (
+        "sounds.archived",
+        [
+            "Archived `{track}`. It's hidden from the soundboard until unarchived",
+            "`{track}` archivado. Queda oculto del panel hasta que se desarchive",
+            "`{track}` archiviert. Er ist bis zur Wiederherstellung im Soundboard ausgeblendet",
+            "`{track}` archivé. Il est masqué du soundboard jusqu'à son désarchivage",
+        ],
+    ),
+    (
+        "sounds.unarchived",
+        [
+            "Unarchived `{track}`",
+            "`{track}` desarchivado",
+            "`{track}` wiederhergestellt",
+            "`{track}` désarchivé",
+        ],
+    ),
+    (
+        "sounds.already_archived",
+        [
+            "`{track}` is already archived",
+            "`{track}` ya está archivado",
+            "`{track}` ist bereits archiviert",
+            "`{track}` est déjà archivé",
+        ],
+    ),
+    (
+        "sounds.not_archived",
+        [
+            "`{track}` isn't archived",
+            "`{track}` no está archivado",
+            "`{track}` ist nicht archiviert",
+            "`{track}` n'est pas archivé",
+        ],
+    ),
+    (
+        "sounds.transferred",
+        [
+            "Transferred `{track}` to {user}",
+            "`{track}` transferido a {user}",
+            "`{track}` an {user} übertragen",
+            "`{track}` transféré à {user}",
+        ],
+    ),
+    (
+        "sounds.already_owner",
+        [
+            "{user} already owns `{track}`",
+            "{user} ya es dueño de `{track}`",
+            "{user} besitzt `{track}` bereits",
+            "{user} possède déjà `{track}`",
+        ],
+    ),
+    (
+        "sounds.transfer_bot",
+        [
+            "Sounds can't be transferred to bots",
+            "Los sonidos no se pueden transferir a bots",
+            "Sounds können nicht an Bots übertragen werden",
+            "Les sons ne peuvent pas être transférés à des bots",
+        ],
+    ),
+    (
+        "sounds.emoji_set",
+        [
+            "`{track}` now shows {emoji} on its button",
+            "`{track}` ahora muestra {emoji} en su botón",
+            "`{track}` zeigt jetzt {emoji} auf seinem Button",
+            "`{track}` affiche maintenant {emoji} sur son bouton",
+        ],
+    ),
+    (
+        "sounds.emoji_cleared",
+        [
+            "Removed emoji of `{track}`",
+            "Emoji de `{track}` eliminado",
+            "Emoji von `{track}` entfernt",
+            "Emoji de `{track}` supprimé",
+        ],
+    ),
+    (
+        "sounds.invalid_emoji",
+        [
+            "{emoji} isn't a single emoji",
+            "{emoji} no es un solo emoji",
+            "{emoji} ist kein einzelnes Emoji",
+            "{emoji} n'est pas un seul emoji",
+        ],
+    ),
+    (
+        "search.results",
+        [
+            "Sounds matching `{query}`:",
+            "Sonidos que coinciden con `{query}`:",
+            "Sounds passend zu `{query}`:",
+            "Sons correspondant à `{query}` :",
+        ],
+    ),
+    (
+        "search.none",
+        [
+            "No sounds match `{query}`",
+            "Ningún sonido coincide con `{query}`",
+            "Keine Sounds passend zu `{query}`",
+            "Aucun son ne correspond à `{query}`",
+        ],
+    ),
+    (
+        "sounds.info",
+        [
+            "`{track}` (#{id})\nTags: {tags}\nAdded by {author} on {created}\nPlays: {plays}\nDuration: {duration}\nSize: {size}",
+            "`{track}` (#{id})\nEtiquetas: {tags}\nAñadido por {author} el {created}\nReproducciones: {plays}\nDuración: {duration}\nTamaño: {size}",
+            "`{track}` (#{id})\nTags: {tags}\nHinzugefügt von {author} am {created}\nWiedergaben: {plays}\nDauer: {duration}\nGröße: {size}",
+            "`{track}` (#{id})\nTags : {tags}\nAjouté par {author} le {created}\nLectures : {plays}\nDurée : {duration}\nTaille : {size}",
+        ],
+    ),
+    (
+        "sounds.info_source",
+        [
+            "Source: {url}",
+            "Origen: {url}",
+            "Quelle: {url}",
+            "Source : {url}",
+        ],
+    ),
+    (
+        "sounds.refetch_no_source",
+        [
+            "`{track}` has no source link to download it from again",
+            "`{track}` no tiene un enlace de origen desde el que volver a descargarlo",
+            "`{track}` hat keinen Quelllink, von dem er erneut heruntergeladen werden kann",
+            "`{track}` n'a pas de lien source depuis lequel le retélécharger",
+        ],
+    ),
+    (
+        "sounds.refetched",
+        [
+            "Re-fetched `{track}` ({duration}, {size})",
+            "`{track}` se volvió a descargar ({duration}, {size})",
+            "`{track}` wurde erneut heruntergeladen ({duration}, {size})",
+            "`{track}` a été retéléchargé ({duration}, {size})",
+        ],
+    ),
+    (
+        "sounds.info_unknown",
+        ["unknown", "desconocido", "unbekannt", "inconnu"],
+    ),
+    (
+        "sounds.downloading",
+        [
+            "Downloading `{track}`... {progress}",
+            "Descargando `{track}`... {progress}",
+            "`{track}` wird heruntergeladen... {progress}",
+            "Téléchargement de `{track}`... {progress}",
+        ],
+    ),
+    (
+        "sounds.validating",
+        [
+            "Checking `{track}`...",
+            "Comprobando `{track}`...",
+            "`{track}` wird geprüft...",
+            "Vérification de `{track}`...",
+        ],
+    ),
+    (
+        "sounds.queued",
+        [
+            "Other sounds are being added, yours is #{position} in line and will be added shortly",
+            "Se están añadiendo otros sonidos, el tuyo es el #{position} en la cola y se añadirá en breve",
+            "Andere Sounds werden gerade hinzugefügt, deiner ist #{position} in der Warteschlange und wird gleich hinzugefügt",
+            "D'autres sons sont en cours d'ajout, le vôtre est n°{position} dans la file et sera ajouté sous peu",
+        ],
+    ),
+    (
+        "sounds.duplicate",
+        [
+            "This file was already added as `{track}`",
+            "Este archivo ya se añadió como `{track}`",
+            "Diese Datei wurde bereits als `{track}` hinzugefügt",
+            "Ce fichier a déjà été ajouté en tant que `{track}`",
+        ],
+    ),
+    (
+        "search.archived",
+        ["archived", "archivado", "archiviert", "archivé"],
+    ),
+    (
+        "channels.text_denied",
+        [
+            "Soundboard commands can only be used in {channels}",
+            "Los comandos de sonidos solo se pueden usar en {channels}",
+            "Soundboard-Befehle können nur in {channels} verwendet werden",
+            "Les commandes de sons ne peuvent être utilisées que dans {channels}",
+        ],
+    ),
+    (
+        "channels.voice_denied",
+        [
+            "Sounds can only be played in {channels}",
+            "Los sonidos solo se pueden reproducir en {channels}",
+            "Sounds können nur in {channels} abgespielt werden",
+            "Les sons ne peuvent être joués que dans {channels}",
+        ],
+    ),
+    (
+        "channels.voice_allowed",
+        [
+            "Sounds can now be played in {channel}",
+            "Ahora se pueden reproducir sonidos en {channel}",
+            "Sounds können jetzt in {channel} abgespielt werden",
+            "Les sons peuvent maintenant être joués dans {channel}",
+        ],
+    ),
+    (
+        "channels.text_allowed",
+        [
+            "Soundboard commands can now be used in {channel}",
+            "Ahora se pueden usar comandos de sonidos en {channel}",
+            "Soundboard-Befehle können jetzt in {channel} verwendet werden",
+            "Les commandes de sons peuvent maintenant être utilisées dans {channel}",
+        ],
+    ),
+    (
+        "channels.disallowed",
+        [
+            "{channel} removed from allowed channels",
+            "{channel} eliminado de los canales permitidos",
+            "{channel} aus den erlaubten Kanälen entfernt",
+            "{channel} retiré des salons autorisés",
+        ],
+    ),
+    (
+        "channels.not_allowed",
+        [
+            "{channel} isn't an allowed channel",
+            "{channel} no es un canal permitido",
+            "{channel} ist kein erlaubter Kanal",
+            "{channel} n'est pas un salon autorisé",
+        ],
+    ),
+    (
+        "channels.title",
+        [
+            "**Allowed channels**",
+            "**Canales permitidos**",
+            "**Erlaubte Kanäle**",
+            "**Salons autorisés**",
+        ],
+    ),
+    (
+        "channels.voice",
+        ["Voice", "Voz", "Sprache", "Vocal"],
+    ),
+    (
+        "channels.text",
+        ["Text", "Texto", "Text", "Texte"],
+    ),
+    (
+        "channels.any",
+        ["any channel", "cualquier canal", "jeder Kanal", "tous les salons"],
+    ),
+    (
+        "favorites.added",
+        [
+            "Added `{track}` to favorites",
+            "`{track}` añadido a favoritos",
+            "`{track}` zu Favoriten hinzugefügt",
+            "`{track}` ajouté aux favoris",
+        ],
+    ),
+    (
+        "favorites.removed",
+        [
+            "Removed `{track}` from favorites",
+            "`{track}` eliminado de favoritos",
+            "`{track}` aus Favoriten entfernt",
+            "`{track}` retiré des favoris",
+        ],
+    ),
+    (
+        "favorites.added_button",
+        [
+            "Added sound to favorites",
+            "Sonido añadido a favoritos",
+            "Sound zu Favoriten hinzugefügt",
+            "Son ajouté aux favoris",
+        ],
+    ),
+    (
+        "favorites.removed_button",
+        [
+            "Removed sound from favorites",
+            "Sonido eliminado de favoritos",
+            "Sound aus Favoriten entfernt",
+            "Son retiré des favoris",
+        ],
+    ),
+    (
+        "confirm.confirm",
+        ["Confirm", "Confirmar", "Bestätigen", "Confirmer"],
+    ),
+    (
+        "confirm.cancel",
+        ["Cancel", "Cancelar", "Abbrechen", "Annuler"],
+    ),
+    (
+        "confirm.busy",
+        [
+            "You already have a pending confirmation. Confirm or cancel it first",
+            "Ya tienes una confirmación pendiente. Confírmala o cancélala primero",
+            "Du hast bereits eine offene Bestätigung. Bestätige oder brich sie zuerst ab",
+            "Vous avez déjà une confirmation en attente. Confirmez-la ou annulez-la d'abord",
+        ],
+    ),
+    (
+        "confirm.timed_out",
+        [
+            "Confirmation timed out. Nothing was changed",
+            "La confirmación expiró. No se cambió nada",
+            "Bestätigung abgelaufen. Nichts wurde geändert",
+            "Confirmation expirée. Rien n'a été modifié",
+        ],
+    ),
+    (
+        "confirm.confirmed",
+        ["Confirmed", "Confirmado", "Bestätigt", "Confirmé"],
+    ),
+    (
+        "confirm.cancelled",
+        [
+            "Cancelled. Nothing was changed",
+            "Cancelado. No se cambió nada",
+            "Abgebrochen. Nichts wurde geändert",
+            "Annulé. Rien n'a été modifié",
+        ],
+    ),
+    (
+        "confirm.expired",
+        [
+            "This confirmation has expired. Nothing was changed",
+            "Esta confirmación ha expirado. No se cambió nada",
+            "Diese Bestätigung ist abgelaufen. Nichts wurde geändert",
+            "Cette confirmation a expiré. Rien n'a été modifié",
+        ],
+    ),
+    (
+        "button.unsupported",
+        [
+            "This button is no longer supported. Run `/sounds display` for a new soundboard.",
+            "Este botón ya no es compatible. Usa `/sounds display` para un soundboard nuevo.",
+            "Dieser Button wird nicht mehr unterstützt. Nutze `/sounds display` für ein neues Soundboard.",
+            "Ce bouton n'est plus pris en charge. Utilisez `/sounds display` pour un nouveau soundboard.",
+        ],
+    ),
+    (
+        "button.failed",
+        [
+            "Something went wrong handling this button. Try again later.",
+            "Algo salió mal al procesar este botón. Inténtalo más tarde.",
+            "Beim Verarbeiten dieses Buttons ist etwas schiefgelaufen. Versuche es später erneut.",
+            "Une erreur est survenue lors du traitement de ce bouton. Réessayez plus tard.",
+        ],
+    ),
+    (
+        "debug.title",
+        [
+            "Diagnostics",
+            "Diagnóstico",
+            "Diagnose",
+            "Diagnostic",
+        ],
+    ),
+    (
+        "debug.latency",
+        [
+            "Gateway latency",
+            "Latencia del gateway",
+            "Gateway-Latenz",
+            "Latence de la passerelle",
+        ],
+    ),
+    (
+        "debug.db_pool",
+        [
+            "Database pool",
+            "Pool de la base de datos",
+            "Datenbank-Pool",
+            "Pool de la base de données",
+        ],
+    ),
+    (
+        "debug.db_pool_state",
+        [
+            "{connections} connections, {idle} idle, {max} max",
+            "{connections} conexiones, {idle} inactivas, {max} máximo",
+            "{connections} Verbindungen, {idle} frei, {max} maximal",
+            "{connections} connexions, {idle} inactives, {max} max",
+        ],
+    ),
+    (
+        "debug.caches",
+        [
+            "Caches",
+            "Cachés",
+            "Caches",
+            "Caches",
+        ],
+    ),
+    (
+        "debug.cache_sizes",
+        [
+            "{autocomplete} autocomplete entries, {opus} transcoded sounds, {now_playing} now playing messages, {confirmations} pending confirmations",
+            "{autocomplete} entradas de autocompletado, {opus} sonidos transcodificados, {now_playing} mensajes de reproducción actual, {confirmations} confirmaciones pendientes",
+            "{autocomplete} Autovervollständigungseinträge, {opus} transkodierte Sounds, {now_playing} Jetzt-läuft-Nachrichten, {confirmations} offene Bestätigungen",
+            "{autocomplete} entrées d'autocomplétion, {opus} sons transcodés, {now_playing} messages de lecture en cours, {confirmations} confirmations en attente",
+        ],
+    ),
+    (
+        "debug.disk",
+        ["Audio disk", "Disco de audio", "Audio-Datenträger", "Disque audio"],
+    ),
+    (
+        "debug.disk_usage",
+        [
+            "{free} free of {total} ({used}% used)",
+            "{free} libres de {total} ({used}% usado)",
+            "{free} von {total} frei ({used}% belegt)",
+            "{free} libres sur {total} ({used} % utilisé)",
+        ],
+    ),
+    (
+        "disk.low_space",
+        [
+            "The bot is low on disk space ({free} free, needs {min}), so new sounds can't be added right now. Ask the bot's host to free up space",
+            "Al bot le queda poco espacio en disco ({free} libres, necesita {min}), así que ahora no se pueden añadir sonidos. Pide al anfitrión del bot que libere espacio",
+            "Der Bot hat zu wenig Speicherplatz ({free} frei, benötigt {min}), daher können gerade keine Sounds hinzugefügt werden. Bitte den Host des Bots, Platz freizugeben",
+            "Le bot manque d'espace disque ({free} libres, {min} requis), donc aucun son ne peut être ajouté pour l'instant. Demandez à l'hébergeur du bot de libérer de l'espace",
+        ],
+    ),
+    (
+        "stats.disk",
+        [
+            "**Disk**: {free} free of {total} ({used}% used)\n**Sounds**: {files} files, {size}",
+            "**Disco**: {free} libres de {total} ({used}% usado)\n**Sonidos**: {files} archivos, {size}",
+            "**Speicher**: {free} von {total} frei ({used}% belegt)\n**Sounds**: {files} Dateien, {size}",
+            "**Disque** : {free} libres sur {total} ({used} % utilisé)\n**Sons** : {files} fichiers, {size}",
+        ],
+    ),
+    (
+        "stats.commands",
+        [
+            "**Command usage** across every server:",
+            "**Uso de comandos** en todos los servidores:",
+            "**Befehlsnutzung** auf allen Servern:",
+            "**Utilisation des commandes** sur tous les serveurs :",
+        ],
+    ),
+    (
+        "stats.command_usage",
+        [
+            "- `/{command}`: {count} uses, {errors}% errors, {average} ms average",
+            "- `/{command}`: {count} usos, {errors}% de errores, {average} ms de media",
+            "- `/{command}`: {count} Aufrufe, {errors}% Fehler, {average} ms im Schnitt",
+            "- `/{command}` : {count} utilisations, {errors} % d'erreurs, {average} ms en moyenne",
+        ],
+    ),
+    (
+        "stats.no_commands",
+        [
+            "No commands used yet",
+            "Aún no se ha usado ningún comando",
+            "Bisher wurden keine Befehle verwendet",
+            "Aucune commande utilisée pour l'instant",
+        ],
+    ),
+    (
+        "stats.disk_unknown",
+        [
+            "Disk usage is unavailable",
+            "El uso del disco no está disponible",
+            "Speichernutzung ist nicht verfügbar",
+            "L'utilisation du disque n'est pas disponible",
+        ],
+    ),
+    (
+        "stats.disk_min_free",
+        [
+            "New sounds are refused below {min} free",
+            "Se rechazan sonidos nuevos con menos de {min} libres",
+            "Neue Sounds werden unter {min} freiem Speicher abgelehnt",
+            "Les nouveaux sons sont refusés sous {min} libres",
+        ],
+    ),
+    (
+        "debug.temp_files",
+        [
+            "Temp files",
+            "Archivos temporales",
+            "Temporäre Dateien",
+            "Fichiers temporaires",
+        ],
+    ),
+    (
+        "debug.temp_files_usage",
+        [
+            "{files} files, {size}",
+            "{files} archivos, {size}",
+            "{files} Dateien, {size}",
+            "{files} fichiers, {size}",
+        ],
+    ),
+    (
+        "debug.voice_connections",
+        [
+            "Voice connections",
+            "Conexiones de voz",
+            "Sprachverbindungen",
+            "Connexions vocales",
+        ],
+    ),
+    (
+        "debug.no_voice_connections",
+        [
+            "Not in any voice channel",
+            "No está en ningún canal de voz",
+            "In keinem Sprachkanal",
+            "Dans aucun salon vocal",
+        ],
+    ),
+    (
+        "debug.interactions",
+        [
+            "Button interactions",
+            "Interacciones con botones",
+            "Button-Interaktionen",
+            "Interactions avec les boutons",
+        ],
+    ),
+    (
+        "debug.interaction_metrics",
+        [
+            "- `{action}`: {count} handled, {failures} failed, {average} ms average",
+            "- `{action}`: {count} procesadas, {failures} fallidas, {average} ms de media",
+            "- `{action}`: {count} verarbeitet, {failures} fehlgeschlagen, {average} ms im Schnitt",
+            "- `{action}` : {count} traitées, {failures} en échec, {average} ms en moyenne",
+        ],
+    ),
+    (
+        "debug.no_interactions",
+        [
+            "No buttons pressed since startup",
+            "No se pulsó ningún botón desde el inicio",
+            "Seit dem Start wurde kein Button gedrückt",
+            "Aucun bouton pressé depuis le démarrage",
+        ],
+    ),
+    (
+        "debug.recent_errors",
+        [
+            "Recent errors",
+            "Errores recientes",
+            "Letzte Fehler",
+            "Erreurs récentes",
+        ],
+    ),
+    (
+        "debug.no_recent_errors",
+        [
+            "No errors since startup",
+            "Sin errores desde el inicio",
+            "Keine Fehler seit dem Start",
+            "Aucune erreur depuis le démarrage",
+        ],
+    ),
+    (
+        "jobs.queued",
+        [
+            "Queued {job} as job `#{id}`. Its outcome will be posted here, check on it with `/jobs status`",
+            "Se puso en cola {job} como tarea `#{id}`. Su resultado se publicará aquí, consúltalo con `/jobs status`",
+            "{job} als Job `#{id}` eingereiht. Das Ergebnis wird hier gepostet, prüfe es mit `/jobs status`",
+            "{job} mis en file comme tâche `#{id}`. Son résultat sera publié ici, suivez-la avec `/jobs status`",
+        ],
+    ),
+    (
+        "jobs.queued_private",
+        [
+            "Queued {job} as job `#{id}`. Check on it with `/jobs status`",
+            "Se puso en cola {job} como tarea `#{id}`. Consúltala con `/jobs status`",
+            "{job} als Job `#{id}` eingereiht. Prüfe ihn mit `/jobs status`",
+            "{job} mis en file comme tâche `#{id}`. Suivez-la avec `/jobs status`",
+        ],
+    ),
+    (
+        "jobs.finished",
+        [
+            "Job `#{id}` finished - {result}",
+            "Tarea `#{id}` terminada - {result}",
+            "Job `#{id}` abgeschlossen - {result}",
+            "Tâche `#{id}` terminée - {result}",
+        ],
+    ),
+    (
+        "jobs.failed",
+        [
+            "Failed - {error}",
+            "Falló - {error}",
+            "Fehlgeschlagen - {error}",
+            "Échec - {error}",
+        ],
+    ),
+    (
+        "jobs.none",
+        [
+            "No jobs found",
+            "No se encontraron tareas",
+            "Keine Jobs gefunden",
+            "Aucune tâche trouvée",
+        ],
+    ),
+    (
+        "jobs.scan_audio_dir",
+        [
+            "Audio dir scan",
+            "Escaneo del directorio de audio",
+            "Scan des Audioverzeichnisses",
+            "Analyse du dossier audio",
+        ],
+    ),
+    (
+        "jobs.backup",
+        [
+            "Database backup",
+            "Copia de seguridad de la base de datos",
+            "Datenbanksicherung",
+            "Sauvegarde de la base de données",
+        ],
+    ),
+    (
+        "jobs.install_pack",
+        [
+            "Install of sound pack **{pack}**",
+            "Instalación del paquete de sonidos **{pack}**",
+            "Installation des Soundpakets **{pack}**",
+            "Installation du pack de sons **{pack}**",
+        ],
+    ),
+    (
+        "jobs.ingest",
+        [
+            "Upload of {count} sounds",
+            "Subida de {count} sonidos",
+            "Upload von {count} Sounds",
+            "Envoi de {count} sons",
+        ],
+    ),
+    (
+        "jobs.transcode",
+        [
+            "Transcode of the most played sounds",
+            "Transcodificación de los sonidos más reproducidos",
+            "Transkodierung der meistgespielten Sounds",
+            "Transcodage des sons les plus joués",
+        ],
+    ),
+    (
+        "jobs.transcoded",
+        [
+            "Transcoded {count} sounds into the Opus cache",
+            "{count} sonidos transcodificados en la caché Opus",
+            "{count} Sounds in den Opus-Cache transkodiert",
+            "{count} sons transcodés dans le cache Opus",
+        ],
+    ),
+    (
+        "jobs.status_queued",
+        [
+            "Queued",
+            "En cola",
+            "Eingereiht",
+            "En file",
+        ],
+    ),
+    (
+        "jobs.status_running",
+        [
+            "Running",
+            "En curso",
+            "Läuft",
+            "En cours",
+        ],
+    ),
+    (
+        "jobs.status_done",
+        [
+            "Done",
+            "Terminada",
+            "Fertig",
+            "Terminée",
+        ],
+    ),
+    (
+        "jobs.status_failed",
+        [
+            "Failed",
+            "Fallida",
+            "Fehlgeschlagen",
+            "Échouée",
+        ],
+    ),
+];
+
+/// Slash command qualified name and its (name, description) in `Language::ALL` order.
+/// Localized names must be lowercase and unique among sibling commands
+const COMMANDS: &[(&str, [(&str, &str); 4])] = &[
+    (
+        "sounds refetch",
+        [
+            ("refetch", "Download a sound again from its source link"),
+            (
+                "redescargar",
+                "Vuelve a descargar un sonido desde su enlace de origen",
+            ),
+            (
+                "neu-laden",
+                "Lädt einen Sound erneut von seinem Quelllink herunter",
+            ),
+            (
+                "retelecharger",
+                "Retélécharge un son depuis son lien source",
+            ),
+        ],
+    ),
+    (
+        "history",
+        [
+            ("history", "List sounds you or another member played lately"),
+            (
+                "historial",
+                "Lista los sonidos que tú u otro miembro reprodujeron últimamente",
+            ),
+            (
+                "verlauf",
+                "Listet Sounds auf, die du oder ein anderes Mitglied zuletzt gespielt habt",
+            ),
+            (
+                "historique",
+                "Liste les sons joués récemment par vous ou un autre membre",
+            ),
+        ],
+    ),
+    (
+        "settings play-history",
+        [
+            ("play-history", "Let members see each other's play history"),
+            (
+                "historial-reproduccion",
+                "Permite a los miembros ver el historial de los demás",
+            ),
+            (
+                "wiedergabeverlauf",
+                "Lässt Mitglieder den Wiedergabeverlauf der anderen sehen",
+            ),
+            (
+                "historique-lecture",
+                "Permet aux membres de voir l'historique des autres",
+            ),
+        ],
+    ),
+    (
+        "forgotten",
+        [
+            (
+                "forgotten",
+                "List sounds not played in the longest time, to rediscover them",
+            ),
+            (
+                "olvidados",
+                "Lista los sonidos que llevan más tiempo sin reproducirse",
+            ),
+            (
+                "vergessen",
+                "Listet die am längsten nicht abgespielten Sounds auf",
+            ),
+            (
+                "oubliés",
+                "Liste les sons qui n'ont pas été joués depuis le plus longtemps",
+            ),
+        ],
+    ),
+    (
+        "trending",
+        [
+            ("trending", "List sounds played the most lately"),
+            (
+                "tendencias",
+                "Lista los sonidos más reproducidos últimamente",
+            ),
+            ("angesagt", "Listet die zuletzt meistgespielten Sounds auf"),
+            (
+                "tendances",
+                "Liste les sons les plus joués ces derniers temps",
+            ),
+        ],
+    ),
+    (
+        "settings soundboard-order",
+        [
+            (
+                "soundboard-order",
+                "Show the oldest or trending sounds first on soundboards",
+            ),
+            (
+                "orden-soundboard",
+                "Muestra primero los sonidos más antiguos o en tendencia",
+            ),
+            (
+                "soundboard-reihenfolge",
+                "Zeigt die ältesten oder angesagten Sounds zuerst",
+            ),
+            (
+                "ordre-soundboard",
+                "Affiche d'abord les sons les plus anciens ou tendance",
+            ),
+        ],
+    ),
+    (
+        "sounds style",
+        [
+            ("style", "Color a sound's button"),
+            ("estilo", "Colorea el botón de un sonido"),
+            ("stil", "Färbt den Button eines Sounds"),
+            ("style", "Colore le bouton d'un son"),
+        ],
+    ),
+    (
+        "sounds category",
+        [
+            ("category", "Show a sound in a soundboard category"),
+            (
+                "categoría",
+                "Muestra un sonido en una categoría del soundboard",
+            ),
+            (
+                "kategorie",
+                "Zeigt einen Sound in einer Soundboard-Kategorie",
+            ),
+            (
+                "catégorie",
+                "Affiche un son dans une catégorie du soundboard",
+            ),
+        ],
+    ),
+    (
+        "admin categories",
+        [
+            (
+                "categories",
+                "Manage the categories the soundboard is grouped by",
+            ),
+            (
+                "categorías",
+                "Gestiona las categorías que agrupan el soundboard",
+            ),
+            (
+                "kategorien",
+                "Verwaltet die Kategorien, nach denen das Soundboard gruppiert ist",
+            ),
+            (
+                "categories",
+                "Gère les catégories qui regroupent le soundboard",
+            ),
+        ],
+    ),
+    (
+        "admin categories add",
+        [
+            ("add", "Add a soundboard category"),
+            ("añadir", "Añade una categoría al soundboard"),
+            ("hinzufügen", "Fügt eine Soundboard-Kategorie hinzu"),
+            ("ajouter", "Ajoute une catégorie au soundboard"),
+        ],
+    ),
+    (
+        "admin categories edit",
+        [
+            ("edit", "Rename, reorder or change the emoji of a category"),
+            (
+                "editar",
+                "Renombra, reordena o cambia el emoji de una categoría",
+            ),
+            (
+                "bearbeiten",
+                "Benennt eine Kategorie um, ordnet sie neu an oder ändert ihr Emoji",
+            ),
+            (
+                "modifier",
+                "Renomme, réordonne ou change l'emoji d'une catégorie",
+            ),
+        ],
+    ),
+    (
+        "admin categories remove",
+        [
+            ("remove", "Remove a category, keeping its sounds"),
+            ("eliminar", "Elimina una categoría, conservando sus sonidos"),
+            (
+                "entfernen",
+                "Entfernt eine Kategorie, ihre Sounds bleiben erhalten",
+            ),
+            ("supprimer", "Supprime une catégorie en conservant ses sons"),
+        ],
+    ),
+    (
+        "admin categories list",
+        [
+            ("list", "List categories in soundboard order"),
+            ("listar", "Lista las categorías en el orden del soundboard"),
+            (
+                "auflisten",
+                "Listet Kategorien in Soundboard-Reihenfolge auf",
+            ),
+            ("lister", "Liste les catégories dans l'ordre du soundboard"),
+        ],
+    ),
+    (
+        "settings silent",
+        [
+            (
+                "silent",
+                "Play sounds without posting now playing messages or /play replies",
+            ),
+            (
+                "silencio",
+                "Reproduce sonidos sin publicar mensajes de reproducción ni respuestas de /play",
+            ),
+            (
+                "stumm",
+                "Spielt Sounds ab, ohne Jetzt-läuft-Nachrichten oder Antworten von /play zu posten",
+            ),
+            (
+                "silencieux",
+                "Joue les sons sans publier de messages de lecture ni de réponses de /play",
+            ),
+        ],
+    ),
+    (
+        "settings autocomplete",
+        [
+            (
+                "autocomplete",
+                "Set how many sounds are suggested as you type and when they're searched",
+            ),
+            (
+                "autocompletado",
+                "Define cuántos sonidos se sugieren al escribir y cuándo se buscan",
+            ),
+            (
+                "autovervollstaendigung",
+                "Legt fest, wie viele Sounds beim Tippen vorgeschlagen und wann sie gesucht werden",
+            ),
+            (
+                "autocompletion",
+                "Définit combien de sons sont suggérés à la saisie et quand ils sont recherchés",
+            ),
+        ],
+    ),
+    (
+        "record",
+        [
+            ("record", "Record and replay soundboard sessions"),
+            ("grabar", "Graba y reproduce sesiones de la botonera"),
+            (
+                "aufnahme",
+                "Nimmt Soundboard-Sitzungen auf und spielt sie ab",
+            ),
+            (
+                "enregistrer",
+                "Enregistre et rejoue des sessions de la table de sons",
+            ),
+        ],
+    ),
+    (
+        "record start",
+        [
+            ("start", "Start recording sounds played in this server"),
+            (
+                "iniciar",
+                "Empieza a grabar los sonidos reproducidos en este servidor",
+            ),
+            (
+                "starten",
+                "Startet die Aufnahme der Sounds auf diesem Server",
+            ),
+            (
+                "demarrer",
+                "Commence l'enregistrement des sons joués sur ce serveur",
+            ),
+        ],
+    ),
+    (
+        "record stop",
+        [
+            ("stop", "Stop recording the session"),
+            ("detener", "Detiene la grabación de la sesión"),
+            ("stoppen", "Beendet die Aufnahme der Sitzung"),
+            ("arreter", "Arrête l'enregistrement de la session"),
+        ],
+    ),
+    (
+        "record list",
+        [
+            ("list", "List recorded sessions"),
+            ("lista", "Lista las sesiones grabadas"),
+            ("liste", "Listet aufgenommene Sitzungen auf"),
+            ("liste", "Liste les sessions enregistrées"),
+        ],
+    ),
+    (
+        "record play",
+        [
+            ("play", "Replay a recorded session with its original timing"),
+            (
+                "reproducir",
+                "Reproduce una sesión grabada con su ritmo original",
+            ),
+            (
+                "abspielen",
+                "Spielt eine aufgenommene Sitzung im Originaltiming ab",
+            ),
+            (
+                "jouer",
+                "Rejoue une session enregistrée avec son timing d'origine",
+            ),
+        ],
+    ),
+    (
+        "settings priority",
+        [
+            ("priority", "Set what priority sounds do to sounds playing"),
+            (
+                "prioridad",
+                "Define qué hacen los sonidos prioritarios con los que suenan",
+            ),
+            (
+                "prioritaet",
+                "Legt fest, was Prioritäts-Sounds mit laufenden Sounds tun",
+            ),
+            (
+                "priorite",
+                "Définit l'effet des sons prioritaires sur les sons en cours",
+            ),
+        ],
+    ),
+    (
+        "doctor",
+        [
+            ("doctor", "Run pre-flight checks of the bot's setup"),
+            (
+                "diagnostico",
+                "Ejecuta comprobaciones previas de la configuración del bot",
+            ),
+            ("doktor", "Führt Vorabprüfungen der Bot-Einrichtung aus"),
+            (
+                "docteur",
+                "Lance les vérifications préalables de la configuration du bot",
+            ),
+        ],
+    ),
+    (
+        "settings digest",
+        [
+            ("digest", "Post a weekly digest of soundboard activity"),
+            ("resumen", "Publica un resumen semanal de la actividad"),
+            (
+                "wochenuebersicht",
+                "Postet eine wöchentliche Übersicht der Aktivität",
+            ),
+            ("resume", "Publie un résumé hebdomadaire de l'activité"),
+        ],
+    ),
+    (
+        "playlist",
+        [
+            ("playlist", "Play several sounds one after another"),
+            ("lista", "Reproduce varios sonidos uno tras otro"),
+            ("playlist", "Spielt mehrere Sounds nacheinander ab"),
+            ("playlist", "Joue plusieurs sons l'un après l'autre"),
+        ],
+    ),
+    (
+        "playlist quickplay",
+        [
+            ("quickplay", "Play up to three sounds in sequence"),
+            ("rapida", "Reproduce hasta tres sonidos en secuencia"),
+            (
+                "schnellwiedergabe",
+                "Spielt bis zu drei Sounds nacheinander ab",
+            ),
+            ("lecture-rapide", "Joue jusqu'à trois sons à la suite"),
+        ],
+    ),
+    (
+        "sounds restore",
+        [
+            ("restore", "Restore a removed sound from the trash"),
+            ("recuperar", "Recupera un sonido eliminado de la papelera"),
+            (
+                "zurückholen",
+                "Holt einen entfernten Sound aus dem Papierkorb zurück",
+            ),
+            ("récupérer", "Récupère un son supprimé de la corbeille"),
+        ],
+    ),
+    (
+        "settings max-length",
+        [
+            (
+                "max-length",
+                "Set the max length of sounds added to this server",
+            ),
+            (
+                "duracion-maxima",
+                "Define la duración máxima de los sonidos de este servidor",
+            ),
+            (
+                "maximale-laenge",
+                "Legt die maximale Länge neuer Sounds auf diesem Server fest",
+            ),
+            (
+                "duree-maximale",
+                "Définit la durée maximale des sons ajoutés à ce serveur",
+            ),
+        ],
+    ),
+    (
+        "settings loudness",
+        [
+            (
+                "loudness",
+                "Set what happens to added sounds that are too loud",
+            ),
+            (
+                "volumen",
+                "Define qué pasa con los sonidos añadidos demasiado fuertes",
+            ),
+            (
+                "lautstaerke",
+                "Legt fest, was mit zu lauten neuen Sounds passiert",
+            ),
+            (
+                "volume",
+                "Définit ce qui arrive aux sons ajoutés trop forts",
+            ),
+        ],
+    ),
+    (
+        "play",
+        [
+            ("play", "Play sound in your voice channel"),
+            ("reproducir", "Reproduce un sonido en tu canal de voz"),
+            ("abspielen", "Spielt einen Sound in deinem Sprachkanal ab"),
+            ("jouer", "Joue un son dans votre salon vocal"),
+        ],
+    ),
+    (
+        "play-id",
+        [
+            ("play-id", "Play sound by its id in your voice channel"),
+            (
+                "reproducir-id",
+                "Reproduce un sonido por su id en tu canal de voz",
+            ),
+            (
+                "abspielen-id",
+                "Spielt einen Sound anhand seiner ID in deinem Sprachkanal ab",
+            ),
+            (
+                "jouer-id",
+                "Joue un son par son identifiant dans votre salon vocal",
+            ),
+        ],
+    ),
+    (
+        "search",
+        [
+            ("search", "Search sounds by name or tags"),
+            ("buscar", "Busca sonidos por nombre o etiquetas"),
+            ("suchen", "Sucht Sounds nach Name oder Tags"),
+            ("rechercher", "Recherche des sons par nom ou tags"),
+        ],
+    ),
+    (
+        "random",
+        [
+            ("random", "Play a random sound in your voice channel"),
+            (
+                "aleatorio",
+                "Reproduce un sonido aleatorio en tu canal de voz",
+            ),
+            (
+                "zufall",
+                "Spielt einen zufälligen Sound in deinem Sprachkanal ab",
+            ),
+            ("aléatoire", "Joue un son aléatoire dans votre salon vocal"),
+        ],
+    ),
+    (
+        "replay",
+        [
+            ("replay", "Replay the last sound played"),
+            ("repetir", "Repite el último sonido reproducido"),
+            ("wiederholen", "Wiederholt den zuletzt gespielten Sound"),
+            ("rejouer", "Rejoue le dernier son joué"),
+        ],
+    ),
+    (
+        "stop",
+        [
+            ("stop", "Stop sounds playing in your voice channel"),
+            ("detener", "Detiene los sonidos de tu canal de voz"),
+            ("stopp", "Stoppt die Sounds in deinem Sprachkanal"),
+            ("arrêter", "Arrête les sons de votre salon vocal"),
+        ],
+    ),
+    (
+        "sounds",
+        [
+            ("sounds", "Manage sounds"),
+            ("sonidos", "Gestiona los sonidos"),
+            ("sounds", "Sounds verwalten"),
+            ("sons", "Gérer les sons"),
+        ],
+    ),
+    (
+        "sounds upload",
+        [
+            ("upload", "Add up to 5 audio files as sounds at once"),
+            (
+                "subir",
+                "Añade hasta 5 archivos de audio como sonidos a la vez",
+            ),
+            (
+                "hochladen",
+                "Fügt bis zu 5 Audiodateien auf einmal als Sounds hinzu",
+            ),
+            (
+                "téléverser",
+                "Ajoute jusqu'à 5 fichiers audio comme sons à la fois",
+            ),
+        ],
+    ),
+    (
+        "sounds add",
+        [
+            ("add", "Add a sound from an audio URL"),
+            ("añadir", "Añade un sonido desde una URL de audio"),
+            ("hinzufügen", "Fügt einen Sound von einer Audio-URL hinzu"),
+            ("ajouter", "Ajoute un son depuis une URL audio"),
+        ],
+    ),
+    (
+        "sounds join-audio",
+        [
+            (
+                "join-audio",
+                "Set or unset the sound played when the bot joins a voice channel",
+            ),
+            (
+                "audio-entrada",
+                "Define o quita el sonido que suena cuando el bot entra a un canal de voz",
+            ),
+            (
+                "beitritts-audio",
+                "Legt den Sound beim Betreten eines Sprachkanals fest oder entfernt ihn",
+            ),
+            (
+                "son-arrivee",
+                "Définit ou retire le son joué quand le bot rejoint un salon vocal",
+            ),
+        ],
+    ),
+    (
+        "sounds leave-audio",
+        [
+            (
+                "leave-audio",
+                "Set or unset the sound played when the bot leaves a voice channel",
+            ),
+            (
+                "audio-salida",
+                "Define o quita el sonido que suena cuando el bot sale de un canal de voz",
+            ),
+            (
+                "abschieds-audio",
+                "Legt den Sound beim Verlassen eines Sprachkanals fest oder entfernt ihn",
+            ),
+            (
+                "son-depart",
+                "Définit ou retire le son joué quand le bot quitte un salon vocal",
+            ),
+        ],
+    ),
+    (
+        "sounds remove",
+        [
+            ("remove", "Remove a sound"),
+            ("eliminar", "Elimina un sonido"),
+            ("entfernen", "Entfernt einen Sound"),
+            ("supprimer", "Supprime un son"),
+        ],
+    ),
+    (
+        "sounds archive",
+        [
+            (
+                "archive",
+                "Hide a sound from the soundboard without deleting it",
+            ),
+            ("archivar", "Oculta un sonido del panel sin eliminarlo"),
+            (
+                "archivieren",
+                "Blendet einen Sound aus, ohne ihn zu löschen",
+            ),
+            ("archiver", "Masque un son du soundboard sans le supprimer"),
+        ],
+    ),
+    (
+        "sounds unarchive",
+        [
+            ("unarchive", "Restore an archived sound"),
+            ("desarchivar", "Restaura un sonido archivado"),
+            (
+                "wiederherstellen",
+                "Stellt einen archivierten Sound wieder her",
+            ),
+            ("désarchiver", "Restaure un son archivé"),
+        ],
+    ),
+    (
+        "sounds info",
+        [
+            ("info", "Show details of a sound"),
+            ("info", "Muestra los detalles de un sonido"),
+            ("info", "Zeigt Details eines Sounds"),
+            ("info", "Affiche les détails d'un son"),
+        ],
+    ),
+    (
+        "sounds transfer",
+        [
+            ("transfer", "Reassign a sound to another member"),
+            ("transferir", "Reasigna un sonido a otro miembro"),
+            (
+                "übertragen",
+                "Überträgt einen Sound an ein anderes Mitglied",
+            ),
+            ("transférer", "Réattribue un son à un autre membre"),
+        ],
+    ),
+    (
+        "sounds share",
+        [
+            ("share", "Get a code to import a sound in another server"),
+            (
+                "compartir",
+                "Obtén un código para importar un sonido en otro servidor",
+            ),
+            (
+                "teilen",
+                "Gibt einen Code zum Importieren eines Sounds auf einem anderen Server",
+            ),
+            (
+                "partager",
+                "Obtient un code pour importer un son dans un autre serveur",
+            ),
+        ],
+    ),
+    (
+        "sounds import-code",
+        [
+            ("import-code", "Import a sound shared from another server"),
+            (
+                "importar-codigo",
+                "Importa un sonido compartido desde otro servidor",
+            ),
+            (
+                "code-importieren",
+                "Importiert einen von einem anderen Server geteilten Sound",
+            ),
+            (
+                "importer-code",
+                "Importe un son partagé depuis un autre serveur",
+            ),
+        ],
+    ),
+    (
+        "sounds emoji",
+        [
+            ("emoji", "Show an emoji on a sound's button"),
+            ("emoji", "Muestra un emoji en el botón de un sonido"),
+            ("emoji", "Zeigt ein Emoji auf dem Button eines Sounds"),
+            ("emoji", "Affiche un emoji sur le bouton d'un son"),
+        ],
+    ),
+    (
+        "sounds edit",
+        [
+            ("edit", "Rename or retag a sound"),
+            ("editar", "Renombra o cambia las etiquetas de un sonido"),
+            (
+                "bearbeiten",
+                "Benennt einen Sound um oder ändert seine Tags",
+            ),
+            ("modifier", "Renomme un son ou modifie ses tags"),
+        ],
+    ),
+    (
+        "sounds display",
+        [
+            ("display", "Display a button grid of all sounds"),
+            (
+                "mostrar",
+                "Muestra una cuadrícula de botones con todos los sonidos",
+            ),
+            ("anzeigen", "Zeigt ein Button-Raster aller Sounds"),
+            ("afficher", "Affiche une grille de boutons de tous les sons"),
+        ],
+    ),
+    (
+        "sounds favorite",
+        [
+            ("favorite", "Add or remove a sound from your favorites"),
+            ("favorito", "Añade o quita un sonido de tus favoritos"),
+            (
+                "favorit",
+                "Fügt einen Sound zu deinen Favoriten hinzu oder entfernt ihn",
+            ),
+            ("favori", "Ajoute ou retire un son de vos favoris"),
+        ],
+    ),
+    (
+        "sounds help",
+        [
+            ("help", "Show bot help"),
+            ("ayuda", "Muestra la ayuda del bot"),
+            ("hilfe", "Zeigt die Bot-Hilfe"),
+            ("aide", "Affiche l'aide du bot"),
+        ],
+    ),
+    (
+        "soundboard",
+        [
+            ("soundboard", "Paged soundboards"),
+            ("soundboard", "Soundboards paginados"),
+            ("soundboard", "Seitenweise Soundboards"),
+            ("soundboard", "Soundboards paginés"),
+        ],
+    ),
+    (
+        "soundboard view",
+        [
+            ("view", "Display a paged soundboard"),
+            ("ver", "Muestra un soundboard paginado"),
+            ("ansehen", "Zeigt ein seitenweises Soundboard"),
+            ("voir", "Affiche un soundboard paginé"),
+        ],
+    ),
+    (
+        "soundboard publish",
+        [
+            ("publish", "Pin a soundboard in a text channel"),
+            ("publicar", "Fija un soundboard en un canal de texto"),
+            (
+                "veröffentlichen",
+                "Pinnt ein Soundboard in einem Textkanal an",
+            ),
+            ("publier", "Épingle un soundboard dans un salon textuel"),
+        ],
+    ),
+    (
+        "soundboard mine",
+        [
+            ("mine", "Privately display your uploads and favorites"),
+            ("míos", "Muestra en privado tus subidas y favoritos"),
+            ("meine", "Zeigt privat deine Uploads und Favoriten"),
+            ("miens", "Affiche en privé vos ajouts et favoris"),
+        ],
+    ),
+    (
+        "settings",
+        [
+            ("settings", "Server settings"),
+            ("ajustes", "Ajustes del servidor"),
+            ("einstellungen", "Servereinstellungen"),
+            ("paramètres", "Paramètres du serveur"),
+        ],
+    ),
+    (
+        "settings language",
+        [
+            ("language", "Set the bot's language for this server"),
+            ("idioma", "Cambia el idioma del bot en este servidor"),
+            (
+                "sprache",
+                "Legt die Sprache des Bots für diesen Server fest",
+            ),
+            ("langue", "Définit la langue du bot pour ce serveur"),
+        ],
+    ),
+    (
+        "settings prefix",
+        [
+            ("prefix", "Set the prefix of prefix commands"),
+            ("prefijo", "Cambia el prefijo de los comandos de texto"),
+            ("präfix", "Legt das Präfix für Textbefehle fest"),
+            ("préfixe", "Définit le préfixe des commandes textuelles"),
+        ],
+    ),
+    (
+        "settings sotd",
+        [
+            ("sotd", "Post (and play) a sound of the day"),
+            ("sonido-del-dia", "Publica (y reproduce) un sonido del día"),
+            (
+                "sound-des-tages",
+                "Postet (und spielt) einen Sound des Tages",
+            ),
+            ("son-du-jour", "Publie (et joue) un son du jour"),
+        ],
+    ),
+    (
+        "settings permissions",
+        [
+            ("permissions", "Restrict commands to roles"),
+            ("permisos", "Restringe comandos a roles"),
+            ("berechtigungen", "Beschränkt Befehle auf Rollen"),
+            ("permissions", "Restreint les commandes à des rôles"),
+        ],
+    ),
+    (
+        "settings permissions grant",
+        [
+            ("grant", "Grant a permission to a role"),
+            ("otorgar", "Otorga un permiso a un rol"),
+            ("erteilen", "Erteilt einer Rolle eine Berechtigung"),
+            ("accorder", "Accorde une permission à un rôle"),
+        ],
+    ),
+    (
+        "settings permissions revoke",
+        [
+            ("revoke", "Revoke a permission from a role"),
+            ("revocar", "Revoca un permiso de un rol"),
+            ("entziehen", "Entzieht einer Rolle eine Berechtigung"),
+            ("revoquer", "Retire une permission à un rôle"),
+        ],
+    ),
+    (
+        "settings permissions list",
+        [
+            ("list", "List the roles granted each permission"),
+            ("lista", "Lista los roles con cada permiso"),
+            ("liste", "Listet die Rollen jeder Berechtigung auf"),
+            ("liste", "Liste les rôles de chaque permission"),
+        ],
+    ),
+    (
+        "settings native-sounds",
+        [
+            (
+                "native-sounds",
+                "Count Discord soundboard sounds as bot sounds",
+            ),
+            (
+                "sonidos-nativos",
+                "Cuenta los sonidos de Discord como sonidos del bot",
+            ),
+            (
+                "native-sounds",
+                "Zählt Discord-Soundboard-Sounds als Bot-Sounds",
+            ),
+            (
+                "sons-natifs",
+                "Compte les sons Discord comme des sons du bot",
+            ),
+        ],
+    ),
+    (
+        "settings native-sounds record",
+        [
+            ("record", "Record plays of mapped Discord soundboard sounds"),
+            (
+                "registrar",
+                "Registra las reproducciones de sonidos de Discord asignados",
+            ),
+            (
+                "erfassen",
+                "Erfasst Wiedergaben zugeordneter Discord-Soundboard-Sounds",
+            ),
+            (
+                "enregistrer",
+                "Enregistre les lectures des sons Discord associés",
+            ),
+        ],
+    ),
+    (
+        "settings native-sounds map",
+        [
+            ("map", "Count a Discord soundboard sound as a bot sound"),
+            (
+                "asignar",
+                "Cuenta un sonido de Discord como un sonido del bot",
+            ),
+            (
+                "zuordnen",
+                "Zählt einen Discord-Soundboard-Sound als Bot-Sound",
+            ),
+            ("associer", "Compte un son Discord comme un son du bot"),
+        ],
+    ),
+    (
+        "settings native-sounds unmap",
+        [
+            (
+                "unmap",
+                "Stop counting a Discord soundboard sound as a bot sound",
+            ),
+            (
+                "desasignar",
+                "Deja de contar un sonido de Discord como sonido del bot",
+            ),
+            (
+                "zuordnung-aufheben",
+                "Zählt einen Discord-Soundboard-Sound nicht mehr als Bot-Sound",
+            ),
+            (
+                "dissocier",
+                "Ne compte plus un son Discord comme un son du bot",
+            ),
+        ],
+    ),
+    (
+        "settings native-sounds list",
+        [
+            ("list", "List mapped Discord soundboard sounds"),
+            ("lista", "Lista los sonidos de Discord asignados"),
+            ("liste", "Listet zugeordnete Discord-Soundboard-Sounds auf"),
+            ("liste", "Liste les sons Discord associés"),
+        ],
+    ),
+    (
+        "settings auto-archive",
+        [
+            ("auto-archive", "Archive sounds unused for months"),
+            ("auto-archivar", "Archiva sonidos sin usar durante meses"),
+            (
+                "auto-archivieren",
+                "Archiviert monatelang unbenutzte Sounds",
+            ),
+            (
+                "archivage-auto",
+                "Archive les sons inutilisés depuis des mois",
+            ),
+        ],
+    ),
+    (
+        "settings approval",
+        [
+            ("approval", "Review new sounds before they're added"),
+            ("aprobacion", "Revisa los sonidos nuevos antes de añadirlos"),
+            ("freigabe", "Prüft neue Sounds vor dem Hinzufügen"),
+            (
+                "approbation",
+                "Vérifie les nouveaux sons avant de les ajouter",
+            ),
+        ],
+    ),
+    (
+        "settings channels",
+        [
+            ("channels", "Restrict the soundboard to channels"),
+            ("canales", "Restringe los sonidos a canales"),
+            ("kanäle", "Beschränkt das Soundboard auf Kanäle"),
+            ("salons", "Restreint les sons à des salons"),
+        ],
+    ),
+    (
+        "settings channels allow",
+        [
+            ("allow", "Allow playback or commands in a channel"),
+            ("permitir", "Permite reproducir o usar comandos en un canal"),
+            ("erlauben", "Erlaubt Wiedergabe oder Befehle in einem Kanal"),
+            (
+                "autoriser",
+                "Autorise la lecture ou les commandes dans un salon",
+            ),
+        ],
+    ),
+    (
+        "settings channels disallow",
+        [
+            ("disallow", "Remove a channel from the allowed channels"),
+            ("prohibir", "Quita un canal de los canales permitidos"),
+            (
+                "verbieten",
+                "Entfernt einen Kanal aus den erlaubten Kanälen",
+            ),
+            ("interdire", "Retire un salon des salons autorisés"),
+        ],
+    ),
+    (
+        "settings channels list",
+        [
+            ("list", "List allowed channels"),
+            ("lista", "Lista los canales permitidos"),
+            ("liste", "Listet erlaubte Kanäle auf"),
+            ("liste", "Liste les salons autorisés"),
+        ],
+    ),
+    (
+        "settings blocklist",
+        [
+            ("blocklist", "Block words in sound names"),
+            ("bloqueo", "Bloquea palabras en los nombres de sonidos"),
+            ("sperrliste", "Blockiert Wörter in Soundnamen"),
+            ("blocage", "Bloque des mots dans les noms de sons"),
+        ],
+    ),
+    (
+        "settings blocklist add",
+        [
+            ("add", "Block a word or regex pattern in sound names"),
+            (
+                "añadir",
+                "Bloquea una palabra o patrón regex en los nombres de sonidos",
+            ),
+            (
+                "hinzufügen",
+                "Blockiert ein Wort oder Regex-Muster in Soundnamen",
+            ),
+            (
+                "ajouter",
+                "Bloque un mot ou un motif regex dans les noms de sons",
+            ),
+        ],
+    ),
+    (
+        "settings blocklist remove",
+        [
+            ("remove", "Unblock a word or pattern"),
+            ("quitar", "Desbloquea una palabra o patrón"),
+            ("entfernen", "Gibt ein Wort oder Muster wieder frei"),
+            ("retirer", "Débloque un mot ou un motif"),
+        ],
+    ),
+    (
+        "settings blocklist list",
+        [
+            ("list", "List blocked words and patterns"),
+            ("lista", "Lista las palabras y patrones bloqueados"),
+            ("liste", "Listet blockierte Wörter und Muster auf"),
+            ("liste", "Liste les mots et motifs bloqués"),
+        ],
+    ),
+    (
+        "packs",
+        [
+            ("packs", "Curated sound packs"),
+            ("paquetes", "Paquetes de sonidos seleccionados"),
+            ("pakete", "Ausgewählte Soundpakete"),
+            ("packs", "Packs de sons sélectionnés"),
+        ],
+    ),
+    (
+        "packs browse",
+        [
+            ("browse", "List sound packs that can be installed"),
+            (
+                "explorar",
+                "Lista los paquetes de sonidos que se pueden instalar",
+            ),
+            ("durchsuchen", "Listet installierbare Soundpakete auf"),
+            ("parcourir", "Liste les packs de sons installables"),
+        ],
+    ),
+    (
+        "packs install",
+        [
+            ("install", "Add the sounds of a sound pack to this server"),
+            (
+                "instalar",
+                "Añade los sonidos de un paquete a este servidor",
+            ),
+            (
+                "installieren",
+                "Fügt die Sounds eines Soundpakets diesem Server hinzu",
+            ),
+            ("installer", "Ajoute les sons d'un pack à ce serveur"),
+        ],
+    ),
+    (
+        "settings export",
+        [
+            ("export", "Download this server's settings as a file"),
+            (
+                "exportar",
+                "Descarga los ajustes de este servidor como archivo",
+            ),
+            (
+                "exportieren",
+                "Lädt die Einstellungen dieses Servers als Datei herunter",
+            ),
+            (
+                "exporter",
+                "Télécharge les paramètres de ce serveur dans un fichier",
+            ),
+        ],
+    ),
+    (
+        "settings import",
+        [
+            (
+                "import",
+                "Replace this server's settings with an exported file",
+            ),
+            (
+                "importar",
+                "Reemplaza los ajustes de este servidor con un archivo exportado",
+            ),
+            (
+                "importieren",
+                "Ersetzt die Einstellungen dieses Servers durch eine exportierte Datei",
+            ),
+            (
+                "importer",
+                "Remplace les paramètres de ce serveur par un fichier exporté",
+            ),
+        ],
+    ),
+    (
+        "privacy",
+        [
+            ("privacy", "Your data kept by the bot"),
+            ("privacidad", "Tus datos guardados por el bot"),
+            ("datenschutz", "Deine vom Bot gespeicherten Daten"),
+            ("confidentialite", "Vos données conservées par le bot"),
+        ],
+    ),
+    (
+        "privacy forget-me",
+        [
+            (
+                "forget-me",
+                "Delete your favorites and play history and remove your name from sounds",
+            ),
+            (
+                "olvidarme",
+                "Elimina tus favoritos e historial y quita tu nombre de los sonidos",
+            ),
+            (
+                "vergiss-mich",
+                "Löscht deine Favoriten und deinen Verlauf und entfernt deinen Namen von Sounds",
+            ),
+            (
+                "oublie-moi",
+                "Supprime vos favoris et historique et retire votre nom des sons",
+            ),
+        ],
+    ),
+    (
+        "stats",
+        [
+            ("stats", "Sound play statistics"),
+            ("estadisticas", "Estadísticas de reproducción"),
+            ("statistik", "Wiedergabestatistiken"),
+            ("stats", "Statistiques de lecture"),
+        ],
+    ),
+    (
+        "stats history",
+        [
+            ("history", "Chart plays per day or week"),
+            ("historial", "Gráfico de reproducciones por día o semana"),
+            ("verlauf", "Diagramm der Wiedergaben pro Tag oder Woche"),
+            ("historique", "Graphique des lectures par jour ou semaine"),
+        ],
+    ),
+    (
+        "stats commands",
+        [
+            ("commands", "Show how often each command is used"),
+            ("comandos", "Muestra con qué frecuencia se usa cada comando"),
+            ("befehle", "Zeigt, wie oft jeder Befehl verwendet wird"),
+            (
+                "commandes",
+                "Affiche la fréquence d'utilisation de chaque commande",
+            ),
+        ],
+    ),
+    (
+        "stats disk",
+        [
+            ("disk", "Show free disk space and size of the sounds"),
+            (
+                "disco",
+                "Muestra el espacio libre en disco y el tamaño de los sonidos",
+            ),
+            (
+                "speicher",
+                "Zeigt freien Speicherplatz und Größe der Sounds",
+            ),
+            (
+                "disque",
+                "Affiche l'espace disque libre et la taille des sons",
+            ),
+        ],
+    ),
+    (
+        "stats heatmap",
+        [
+            ("heatmap", "Show plays by hour of day and day of week"),
+            (
+                "mapa-de-calor",
+                "Reproducciones por hora del día y día de la semana",
+            ),
+            ("heatmap", "Wiedergaben nach Tageszeit und Wochentag"),
+            (
+                "carte-de-chaleur",
+                "Lectures par heure et jour de la semaine",
+            ),
+        ],
+    ),
+    (
+        "owner",
+        [
+            ("owner", "Bot owner maintenance commands"),
+            (
+                "propietario",
+                "Comandos de mantenimiento del propietario del bot",
+            ),
+            ("besitzer", "Wartungsbefehle für den Bot-Besitzer"),
+            (
+                "proprietaire",
+                "Commandes de maintenance du propriétaire du bot",
+            ),
+        ],
+    ),
+    (
+        "admin",
+        [
+            ("admin", "Administration"),
+            ("admin", "Administración"),
+            ("admin", "Verwaltung"),
+            ("admin", "Administration"),
+        ],
+    ),
+    (
+        "admin scan",
+        [
+            ("scan", "Add sounds from the audio directory"),
+            ("escanear", "Añade sonidos desde el directorio de audio"),
+            ("scannen", "Fügt Sounds aus dem Audioverzeichnis hinzu"),
+            ("analyser", "Ajoute les sons du dossier audio"),
+        ],
+    ),
+    (
+        "admin prune-suggestions",
+        [
+            ("prune-suggestions", "List sounds not played for months"),
+            (
+                "sugerencias-limpieza",
+                "Lista sonidos no reproducidos en meses",
+            ),
+            (
+                "aufräum-vorschläge",
+                "Listet monatelang nicht abgespielte Sounds",
+            ),
+            (
+                "suggestions-nettoyage",
+                "Liste les sons non joués depuis des mois",
+            ),
+        ],
+    ),
+    (
+        "admin resync-commands",
+        [
+            (
+                "resync-commands",
+                "Re-register slash commands in every server",
+            ),
+            (
+                "resincronizar-comandos",
+                "Vuelve a registrar los comandos en todos los servidores",
+            ),
+            (
+                "befehle-synchronisieren",
+                "Registriert Slash-Befehle in allen Servern neu",
+            ),
+            (
+                "resynchroniser-commandes",
+                "Réenregistre les commandes slash dans tous les serveurs",
+            ),
+        ],
+    ),
+    (
+        "admin bulk",
+        [
+            ("bulk", "Delete or retag many sounds at once"),
+            ("masivo", "Elimina o reetiqueta muchos sonidos a la vez"),
+            ("massen", "Löscht oder ändert Tags vieler Sounds auf einmal"),
+            (
+                "masse",
+                "Supprime ou modifie les tags de nombreux sons à la fois",
+            ),
+        ],
+    ),
+    (
+        "admin bulk delete",
+        [
+            ("delete", "Delete sounds matching a filter"),
+            (
+                "eliminar",
+                "Elimina los sonidos que coinciden con un filtro",
+            ),
+            ("löschen", "Löscht Sounds, die einem Filter entsprechen"),
+            ("supprimer", "Supprime les sons correspondant à un filtre"),
+        ],
+    ),
+    (
+        "admin bulk retag",
+        [
+            ("retag", "Replace the tags of sounds matching a filter"),
+            (
+                "reetiquetar",
+                "Reemplaza las etiquetas de los sonidos que coinciden con un filtro",
+            ),
+            (
+                "neu-taggen",
+                "Ersetzt die Tags von Sounds, die einem Filter entsprechen",
+            ),
+            (
+                "reetiqueter",
+                "Remplace les tags des sons correspondant à un filtre",
+            ),
+        ],
+    ),
+    (
+        "debug",
+        [
+            ("debug", "Show runtime diagnostics of the bot"),
+            ("depurar", "Muestra diagnósticos de ejecución del bot"),
+            ("debug", "Zeigt Laufzeitdiagnosen des Bots"),
+            ("debogage", "Affiche les diagnostics d'exécution du bot"),
+        ],
+    ),
+    (
+        "jobs",
+        [
+            (
+                "jobs",
+                "Background jobs like scans, sound pack installs and backups",
+            ),
+            (
+                "tareas",
+                "Tareas en segundo plano como escaneos, instalaciones de paquetes y copias",
+            ),
+            (
+                "jobs",
+                "Hintergrundjobs wie Scans, Soundpaket-Installationen und Sicherungen",
+            ),
+            (
+                "taches",
+                "Tâches de fond comme les analyses, installations de packs et sauvegardes",
+            ),
+        ],
+    ),
+    (
+        "jobs status",
+        [
+            (
+                "status",
+                "Show the status of the server's latest jobs, or of one job",
+            ),
+            (
+                "estado",
+                "Muestra el estado de las últimas tareas del servidor, o de una tarea",
+            ),
+            (
+                "status",
+                "Zeigt den Status der letzten Jobs des Servers oder eines Jobs",
+            ),
+            (
+                "statut",
+                "Affiche le statut des dernières tâches du serveur, ou d'une tâche",
+            ),
+        ],
+    ),
+    (
+        "echo",
+        [
+            ("echo", "Echo text back"),
+            ("eco", "Repite el texto"),
+            ("echo", "Gibt den Text zurück"),
+            ("echo", "Répète le texte"),
+        ],
+    ),
+];
+
+/// Translate message key. Falls back to English, then to the key itself.
+/// `{name}` placeholders are replaced with `args` (`Sync`, so replies stay `Send` across awaits)
+pub fn tr(language: Language, key: &str, args: &[(&str, &(dyn Display + Sync))]) -> String {
+    let message = match MESSAGES.iter().find(|(message_key, _)| *message_key == key) {
+        Some((_, translations)) => match translations[language.index()] {
+            "" => translations[Language::English.index()],
+            translation => translation,
+        },
+        None => {
+            log::error!("Missing translation message key - {key}");
+            key
+        }
+    };
+
+    args.iter()
+        .fold(message.to_string(), |message, (name, value)| {
+            message.replace(&format!("{{{name}}}"), &value.to_string())
+        })
+}
+
+/// Guild's language. Its `/settings language`, otherwise its Discord locale, otherwise English
+pub fn guild_language(
+    data: &UserData,
+    guild_id: Option<GuildId>,
+    guild_locale: Option<&str>,
+) -> Language {
+    let settings = guild_id.and_then(|guild_id| {
+        data.guild_settings_table()
+            .get_guild_settings(guild_id.get())
+            .log_err()
+            .ok()
+    });
+
+    settings_language(settings.as_ref(), guild_locale)
+}
+
+/// Language of guild settings, falling back to guild locale
+pub fn settings_language(
+    settings: Option<&GuildSettingsRow>,
+    guild_locale: Option<&str>,
+) -> Language {
+    settings
+        .and_then(|settings| settings.language.as_deref())
+        .and_then(Language::from_code)
+        .or_else(|| guild_locale.and_then(Language::from_code))
+        .unwrap_or_default()
+}
+
+/// Language to reply to command in
+pub fn language(ctx: PoiseContext<'_>) -> Language {
+    let guild_locale = match ctx {
+        poise::Context::Application(ctx) => ctx.interaction.guild_locale.as_deref(),
+        poise::Context::Prefix(_) => None,
+    };
+
+    guild_language(ctx.data(), ctx.guild_id(), guild_locale)
+}
+
+/// Register localized names and descriptions of slash commands with Discord.
+/// English descriptions are also used as default descriptions
+pub fn localize_commands(commands: &mut [poise::Command<UserData, PoiseError>]) {
+    for command in commands {
+        if let Some((_, localizations)) = COMMANDS
+            .iter()
+            .find(|(qualified_name, _)| *qualified_name == command.qualified_name)
+        {
+            let (_, description) = localizations[Language::English.index()];
+            command
+                .description
+                .get_or_insert_with(|| description.into());
+
+            for language in &Language::ALL[1..] {
+                let (name, description) = localizations[language.index()];
+                command
+                    .name_localizations
+                    .insert(language.code().into(), name.into());
+                command
+                    .description_localizations
+                    .insert(language.code().into(), description.into());
+            }
+        }
+
+        localize_commands(&mut command.subcommands);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use crate::test_utils::TestDb;
+
+    use super::*;
+
+    #[test]
+    fn from_code_test() {
+        assert_eq!(Some(Language::English), Language::from_code("en-GB"));
+        assert_eq!(Some(Language::Spanish), Language::from_code("es-419"));
+        assert_eq!(Some(Language::German), Language::from_code("de"));
+        assert_eq!(None, Language::from_code("ja"));
+
+        for language in Language::ALL {
+            assert_eq!(Some(language), Language::from_code(language.code()));
+        }
+    }
+
+    #[test]
+    fn tr_test() {
+        assert_eq!(
+            "Spiele `Beep`",
+            tr(Language::German, "play.playing", &[("track", &"Beep")])
+        );
+        assert_eq!(
+            "Slow down! You can play another sound in 3s",
+            tr(Language::English, "play.slow_down", &[("secs", &3)])
+        );
+        assert_eq!("missing.key", tr(Language::French, "missing.key", &[]));
+    }
+
+    #[test]
+    fn messages_test() {
+        let keys: HashSet<_> = MESSAGES.iter().map(|(key, _)| key).collect();
+        assert_eq!(MESSAGES.len(), keys.len(), "duplicate message keys");
+
+        // translations use the same placeholders as English
+        let placeholder = regex::Regex::new(r"\{\w+\}").unwrap();
+        for (key, translations) in MESSAGES {
+            let english: HashSet<_> = placeholder
+                .find_iter(translations[0])
+                .map(|m| m.as_str())
+                .collect();
+            for translation in translations {
+                let placeholders: HashSet<_> = placeholder
+                    .find_iter(translation)
+                    .map(|m| m.as_str())
+                    .collect();
+                assert_eq!(english, placeholders, "placeholders of {key}");
+            }
+        }
+    }
+
+    #[test]
+    fn commands_test() {
+        let name = regex::Regex::new(r"^[-_\p{Ll}\p{N}]{1,32}$").unwrap();
+        for (qualified_name, localizations) in COMMANDS {
+            assert_eq!(
+                qualified_name.split(' ').next_back(),
+                Some(localizations[0].0),
+                "English name of {qualified_name}"
+            );
+            for (localized_name, description) in localizations {
+                assert!(name.is_match(localized_name), "{localized_name}");
+                assert!(description.chars().count() <= 100, "{description}");
+            }
+        }
+    }
+
+    #[test]
+    fn commands_translated_test() {
+        fn check(commands: &[poise::Command<UserData, PoiseError>]) {
+            for command in commands {
+                // owner commands are registered in English only, prefix commands not at all
+                if command.owners_only
+                    || command.slash_action.is_none() && command.subcommands.is_empty()
+                {
+                    continue;
+                }
+                assert!(
+                    COMMANDS
+                        .iter()
+                        .any(|(qualified_name, _)| *qualified_name == command.qualified_name),
+                    "no translation of {}",
+                    command.qualified_name
+                );
+                check(&command.subcommands);
+            }
+
+            // Discord rejects sibling commands sharing a localized name
+            for language in Language::ALL {
+                let names: Vec<_> = commands
+                    .iter()
+                    .filter_map(|command| {
+                        COMMANDS
+                            .iter()
+                            .find(|(qualified_name, _)| *qualified_name == command.qualified_name)
+                    })
+                    .map(|(_, localizations)| localizations[language.index()].0)
+                    .collect();
+                let unique: HashSet<_> = names.iter().collect();
+                assert_eq!(names.len(), unique.len(), "{names:?}");
+            }
+        }
+
+        check(&crate::commands::all_commands());
+    }
+
+    #[test]
+    fn guild_language_test() {
+        let db = TestDb::new();
+        let data = db.user_data();
+        let guild_id = Some(GuildId::new(1));
+
+        assert_eq!(Language::English, guild_language(&data, guild_id, None));
+        assert_eq!(
+            Language::French,
+            guild_language(&data, guild_id, Some("fr"))
+        );
+
+        let mut settings = data.guild_settings_table().get_guild_settings(1).unwrap();
+        settings.language = Some(Language::German.code().into());
+        data.guild_settings_table()
+            .update_guild_settings(&settings)
+            .unwrap();
+
+        assert_eq!(
+            Language::German,
+            guild_language(&data, guild_id, Some("fr"))
+        );
+    }
+}
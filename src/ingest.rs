@@ -0,0 +1,404 @@
+use std::future::Future;
+use std::path::PathBuf;
+use std::sync::{Arc, Once};
+
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use serenity::all::{GuildId, Http};
+
+use crate::audio::{self, AudioFile, AudioFileValidator, AudioTrackInfo};
+use crate::commands::PoiseError;
+use crate::common::{LogResult, UserData};
+use crate::db::{AudioTable, AudioTableRowInsert, SoundName, Tags, UniqueAudioTableCol};
+use crate::helpers;
+use crate::i18n::{self, tr};
+use crate::loudness;
+use crate::moderation::{self, ModeratedSound};
+use crate::vars;
+use crate::{approval, blocklist};
+
+#[derive(Debug, Clone, Copy)]
+pub struct IngestProgress {
+    pub processed: usize,
+    pub total: usize,
+}
+
+impl std::fmt::Display for IngestProgress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.processed, self.total)
+    }
+}
+
+/// Validates (probes) audio files concurrently on blocking threads, at most `concurrency` at a
+/// time. Returns audio files that passed validation, with their probed track info. `on_progress` is
+/// awaited every `vars::INGEST_PROGRESS_INTERVAL` files and once all files are processed
+pub async fn validate_audio_files<F, Fut>(
+    audio_files: Vec<AudioFile>,
+    validator: AudioFileValidator,
+    concurrency: usize,
+    mut on_progress: F,
+) -> Vec<(AudioFile, AudioTrackInfo)>
+where
+    F: FnMut(IngestProgress) -> Fut,
+    Fut: Future<Output = ()>,
+{
+    let total = audio_files.len();
+    let validator = Arc::new(validator);
+
+    let mut results = futures::stream::iter(audio_files)
+        .map(|audio_file| {
+            let validator = validator.clone();
+            tokio::task::spawn_blocking(move || {
+                let track_info = validator.validate(audio_file.as_path()).ok();
+                (audio_file, track_info)
+            })
+        })
+        .buffer_unordered(concurrency.max(1));
+
+    let mut valid_audio_files = vec![];
+    let mut processed = 0;
+    while let Some(result) = results.next().await {
+        processed += 1;
+
+        match result {
+            Ok((audio_file, Some(track_info))) => valid_audio_files.push((audio_file, track_info)),
+            Ok((_, None)) => {}
+            Err(err) => log::error!("Audio file validation task failed - {err}"),
+        }
+
+        if processed % vars::INGEST_PROGRESS_INTERVAL == 0 || processed == total {
+            let progress = IngestProgress { processed, total };
+            log::info!("Ingest progress - validated {progress} audio files");
+            on_progress(progress).await;
+        }
+    }
+
+    valid_audio_files
+}
+
+/// Probes audio rows missing a duration or file size, i.e. rows added before they were stored.
+/// Rows whose audio file can't be probed are skipped. Returns number of rows backfilled
+pub fn backfill_audio_metadata(table: &AudioTable) -> Result<usize, PoiseError> {
+    let mut backfilled = 0;
+    let mut after_id = 0;
+
+    loop {
+        let rows =
+            table.audio_rows_missing_metadata(after_id, vars::METADATA_BACKFILL_BATCH_LIMIT)?;
+        let Some(last_row) = rows.last() else {
+            break;
+        };
+        after_id = last_row.id;
+
+        for row in rows {
+            match audio::probe_audio_track(row.audio_file.as_path()) {
+                Ok(track_info) => {
+                    table.set_metadata(row.id, track_info.duration_ms(), track_info.file_size)?;
+                    backfilled += 1;
+                }
+                Err(_) => log::warn!("Skipping metadata backfill of audio track - {}", row.name),
+            }
+        }
+    }
+
+    Ok(backfilled)
+}
+
+/// Start backfilling audio metadata on a blocking thread. Only the first call starts it, so
+/// it's safe to call on every ready event
+pub fn spawn_metadata_backfill(db_pool: r2d2::Pool<r2d2_sqlite::SqliteConnectionManager>) {
+    static STARTED: Once = Once::new();
+
+    STARTED.call_once(|| {
+        log::info!("Starting audio metadata backfill");
+        tokio::task::spawn_blocking(move || {
+            let result: Result<usize, PoiseError> = db_pool
+                .get()
+                .map_err(Into::into)
+                .and_then(|conn| backfill_audio_metadata(&AudioTable::new(conn)));
+
+            if let Ok(backfilled) = result.log_err_msg("Failed to backfill audio metadata") {
+                log::info!(
+                    "Audio metadata backfill complete - backfilled {backfilled} audio tracks"
+                );
+            }
+        });
+    });
+}
+
+/// File attached to `/sounds upload`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Upload {
+    pub file_name: String,
+    pub url: String,
+}
+
+/// Uploader recorded as author of uploaded sounds
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Uploader {
+    pub id: u64,
+    pub name: String,
+    pub global_name: Option<String>,
+    pub guild_id: Option<u64>,
+    /// Review channel uploads are submitted to (see `approval`). None adds them right away
+    pub review_channel_id: Option<u64>,
+    /// Author hash of anonymous uploads (see `privacy::author_hash`), stored instead of the
+    /// uploader's id and names
+    pub author_hash: Option<String>,
+}
+
+/// Outcome of uploading one file
+#[derive(Debug, Clone, PartialEq)]
+pub struct UploadResult {
+    pub file_name: String,
+    /// Track name of the added sound, or why it wasn't added
+    pub result: Result<String, String>,
+}
+
+/// Track name for an uploaded file, from its file name. Numbered when the name is taken
+pub fn upload_track_name(file_name: &str, is_taken: impl Fn(&str) -> bool) -> String {
+    let title = AudioFile::new(PathBuf::from(file_name)).audio_title();
+    // leave room for numbering
+    let title: String = title
+        .trim()
+        .chars()
+        .take(vars::SOUND_NAME_MAX_LEN - 4)
+        .collect();
+    let title = match title.trim() {
+        "" => "Sound".to_string(),
+        title => title.to_string(),
+    };
+
+    match is_taken(&title) {
+        true => helpers::numbered_name(&title, is_taken),
+        false => title,
+    }
+}
+
+/// Adds uploaded files as sounds, downloading and validating at most `concurrency` at a time,
+/// and no more than the ingest limiter allows across all guilds. Results are in upload order
+pub async fn upload_audio_files(
+    http: &Http,
+    data: &UserData,
+    uploads: Vec<Upload>,
+    uploader: &Uploader,
+    concurrency: usize,
+) -> Vec<UploadResult> {
+    // names are picked up front, so concurrent uploads don't pick the same name
+    let table = data.audio_table();
+    let mut names: Vec<String> = vec![];
+    for upload in uploads.iter() {
+        let name = upload_track_name(&upload.file_name, |name| {
+            names.iter().any(|taken| taken == name)
+                || table
+                    .find_audio_row(UniqueAudioTableCol::Name(name.into()))
+                    .is_some()
+        });
+        names.push(name);
+    }
+
+    futures::stream::iter(uploads.into_iter().zip(names))
+        .map(|(upload, name)| async move {
+            let result = upload_audio_file(http, data, &upload, name, uploader)
+                .await
+                .map_err(|err| err.to_string());
+            UploadResult {
+                file_name: upload.file_name,
+                result,
+            }
+        })
+        .buffered(concurrency.max(1))
+        .collect()
+        .await
+}
+
+#[tracing::instrument(skip_all, fields(file_name = %upload.file_name))]
+async fn upload_audio_file(
+    http: &Http,
+    data: &UserData,
+    upload: &Upload,
+    name: String,
+    uploader: &Uploader,
+) -> Result<String, PoiseError> {
+    log::info!("Uploading sound. Name: {name}, File: {}", upload.file_name);
+
+    let name = SoundName::new(&name)?;
+    blocklist::check_text(data, uploader.guild_id, &name)?;
+
+    let _permit = data.ingest_limiter.acquire().await;
+    let temp_audio_file = audio::download_audio_url_temp(&data.http_client, &upload.url).await?;
+
+    // validate audio track (codec type, length, etc)
+    let max_audio_duration = data.max_audio_duration(uploader.guild_id);
+    let validate_file = temp_audio_file.to_path_buf();
+    let track_info = tokio::task::spawn_blocking(move || {
+        AudioFileValidator::default()
+            .max_audio_duration(max_audio_duration)
+            .reject_uuid_files(false)
+            .validate(&validate_file)
+    })
+    .await??;
+
+    if let Some(duplicate) = data
+        .audio_table()
+        .find_duplicate_audio_row(track_info.duration_ms(), track_info.file_size)
+    {
+        return Err(format!("Duplicate of `{}`", duplicate.name).into());
+    }
+
+    // loudness is checked first, as attenuated sounds are moderated as they'll be played
+    let sound = ModeratedSound {
+        name: &name,
+        audio_file: &temp_audio_file,
+        guild_id: uploader.guild_id,
+        duration_ms: track_info.duration_ms(),
+    };
+    let flagged = match loudness::check_upload(data, &sound).await? {
+        Some(flagged) => Some(flagged),
+        None => moderation::check_upload(data, &sound).await?,
+    };
+
+    let placed_file = data.move_file_to_audio_dir(&temp_audio_file)?;
+    let anonymous = uploader.author_hash.is_some();
+    let row_insert = AudioTableRowInsert {
+        name: name.clone(),
+        tags: Tags::new(),
+        audio_file: AudioFile::new(placed_file.to_path_buf()),
+        created_at: chrono::Utc::now(),
+        author_id: (!anonymous).then_some(uploader.id),
+        author_name: (!anonymous).then(|| uploader.name.clone()),
+        author_global_name: uploader.global_name.clone().filter(|_| !anonymous),
+        author_hash: uploader.author_hash.clone(),
+        guild_id: uploader.guild_id,
+        duration_ms: Some(track_info.duration_ms()),
+        file_size: Some(track_info.file_size),
+        source_url: Some(upload.url.clone()),
+    };
+
+    match (flagged, uploader.review_channel_id) {
+        // not added, so reported as failed with why
+        (Some(flagged), _) => {
+            let reason = flagged.reason;
+            approval::submit(
+                http,
+                data,
+                flagged.review_channel_id,
+                row_insert,
+                placed_file,
+                Some(&reason),
+            )
+            .await?;
+
+            let lang = i18n::guild_language(data, uploader.guild_id.map(GuildId::new), None);
+            return Err(tr(
+                lang,
+                "moderation.flagged",
+                &[("track", &name), ("reason", &reason)],
+            )
+            .into());
+        }
+        (None, Some(review_channel_id)) => {
+            approval::submit(http, data, review_channel_id, row_insert, placed_file, None).await?;
+        }
+        (None, None) => {
+            data.audio_table().insert_audio_row(row_insert)?;
+            placed_file.keep();
+        }
+    }
+
+    Ok(name.into())
+}
+
+/// Table of upload results, one line per file
+pub fn render_upload_summary(results: &[UploadResult]) -> String {
+    let width = results
+        .iter()
+        .map(|result| result.file_name.chars().count())
+        .max()
+        .unwrap_or(0);
+
+    results
+        .iter()
+        .map(|upload| {
+            let (status, detail) = match &upload.result {
+                Ok(name) => ("✅", name),
+                Err(err) => ("❌", err),
+            };
+            format!("{status} {:<width$}  {detail}", upload.file_name)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::{AudioRowFixture, TempDir, TestDb};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn validate_audio_files_test() {
+        let dir = TempDir::new();
+        let audio_files: Vec<_> = (0..3)
+            .map(|i| dir.audio_file(format!("empty-{i}.mp3")))
+            .collect();
+
+        let mut reports = vec![];
+        let valid = validate_audio_files(audio_files, AudioFileValidator::new(), 2, |progress| {
+            reports.push(progress.processed);
+            async {}
+        })
+        .await;
+
+        // empty files fail validation, final progress is always reported
+        assert!(valid.is_empty());
+        assert_eq!(Some(&3), reports.last());
+    }
+
+    #[test]
+    fn backfill_audio_metadata_test() {
+        let db = TestDb::new();
+        let table = db.audio_table();
+        let missing = AudioRowFixture::new().insert(&table);
+        let probed = AudioRowFixture::new().metadata(1500, 24_000).insert(&table);
+
+        // fixture audio files don't exist, so can't be probed
+        assert_eq!(0, backfill_audio_metadata(&table).unwrap());
+
+        let find = |id| table.find_audio_row(UniqueAudioTableCol::Id(id)).unwrap();
+        assert_eq!(None, find(missing.id).duration_ms);
+        assert_eq!(Some(1500), find(probed.id).duration_ms);
+        assert_eq!(Some(24_000), find(probed.id).file_size);
+    }
+
+    #[test]
+    fn upload_track_name_test() {
+        assert_eq!(
+            "Bruh Moment",
+            upload_track_name("bruh_moment.mp3", |_| false)
+        );
+        assert_eq!(
+            "Bruh-3",
+            upload_track_name("bruh.mp3", |name| name == "Bruh" || name == "Bruh-2")
+        );
+        assert_eq!("Sound", upload_track_name("_.mp3", |_| false));
+    }
+
+    #[test]
+    fn render_upload_summary_test() {
+        let summary = render_upload_summary(&[
+            UploadResult {
+                file_name: "bruh.mp3".into(),
+                result: Ok("Bruh".into()),
+            },
+            UploadResult {
+                file_name: "a.wav".into(),
+                result: Err("Invalid content type".into()),
+            },
+        ]);
+        assert_eq!(
+            "✅ bruh.mp3  Bruh\n❌ a.wav     Invalid content type",
+            summary
+        );
+    }
+}
@@ -0,0 +1,577 @@
+//! Component (button and select menu) interactions. Interactions are acknowledged, checked and
+//! dispatched to the handler of their `ButtonAction`. Handlers return the ephemeral followup
+//! to reply with, if any; failed handlers are replied to with an error. Handled interactions
+//! are counted per action for `/debug`
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use poise::ChoiceParameter;
+use serenity::all::{
+    ComponentInteraction, ComponentInteractionDataKind, CreateAttachment,
+    CreateInteractionResponse, CreateInteractionResponseFollowup, EditMessage,
+};
+use serenity::client::Context;
+
+use crate::commands::PoiseError;
+use crate::common::{LogResult, UserData};
+use crate::db;
+use crate::error_reporting::{self, ErrorContext};
+use crate::errors::AudioError;
+use crate::helpers::{self, ButtonAction, ButtonCustomId, SongbirdHelper};
+use crate::i18n::{self, tr, Language};
+use crate::onboarding::{self, OnboardingStep};
+use crate::permissions::{self, Permission};
+use crate::playback::{self, LoopMode, PlaySource};
+use crate::{approval, read_only, soundboard};
+
+/// Ephemeral followup a handler replies with, if any
+type HandlerResult = Result<Option<CreateInteractionResponseFollowup>, PoiseError>;
+
+/// Handled interactions per action name, see `action_name`
+static METRICS: Mutex<BTreeMap<&'static str, InteractionMetrics>> = Mutex::new(BTreeMap::new());
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct InteractionMetrics {
+    pub count: u64,
+    pub failures: u64,
+    /// Total time spent in handlers
+    pub elapsed: Duration,
+}
+
+impl InteractionMetrics {
+    pub fn average(&self) -> Duration {
+        match self.count {
+            0 => Duration::ZERO,
+            count => self.elapsed / count as u32,
+        }
+    }
+}
+
+/// Handled interactions per action name
+pub fn metrics() -> Vec<(&'static str, InteractionMetrics)> {
+    METRICS
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|(name, metrics)| (*name, *metrics))
+        .collect()
+}
+
+fn record_metrics(name: &'static str, elapsed: Duration, failed: bool) {
+    let mut metrics = METRICS.lock().unwrap();
+    let entry = metrics.entry(name).or_default();
+    entry.count += 1;
+    entry.failures += u64::from(failed);
+    entry.elapsed += elapsed;
+}
+
+/// Interaction being handled, with its parsed custom id
+pub struct ComponentContext<'a> {
+    pub ctx: &'a Context,
+    pub component: &'a ComponentInteraction,
+    pub data: &'a UserData,
+    pub lang: Language,
+    pub custom_id: ButtonCustomId,
+}
+
+impl ComponentContext<'_> {
+    fn followup(&self, content: impl Into<String>) -> CreateInteractionResponseFollowup {
+        CreateInteractionResponseFollowup::new()
+            .ephemeral(true)
+            .content(content)
+    }
+
+    fn guild_id(&self) -> Result<serenity::all::GuildId, PoiseError> {
+        Ok(self
+            .component
+            .guild_id
+            .ok_or("ComponentInteraction.guild_id is None")
+            .log_err()?)
+    }
+
+    /// Whether member pressing button is an administrator. Buttons outlive permissions, and
+    /// some messages with admin buttons are visible to everyone
+    fn is_admin(&self) -> bool {
+        self.component
+            .member
+            .as_ref()
+            .and_then(|member| member.permissions)
+            .is_some_and(|permissions| permissions.administrator())
+    }
+}
+
+/// Name metrics of action's interactions are recorded under
+pub fn action_name(action: &ButtonAction) -> &'static str {
+    match action {
+        ButtonAction::Play(_) => "play",
+        ButtonAction::PageNav { .. } => "page",
+        ButtonAction::Confirm(_) | ButtonAction::Cancel(_) | ButtonAction::PickName { .. } => {
+            "confirm"
+        }
+        ButtonAction::Favorite(_) => "favorite",
+        ButtonAction::TagFilter => "tag_filter",
+        ButtonAction::Stop => "stop",
+        ButtonAction::Replay => "replay",
+        ButtonAction::Repeat => "repeat",
+        ButtonAction::Archive(_) | ButtonAction::Keep(_) => "prune",
+        ButtonAction::Onboarding(_) => "onboarding",
+        ButtonAction::Listen(_) | ButtonAction::Approve(_) | ButtonAction::Reject(_) => "review",
+    }
+}
+
+/// Audio table id of the sound action is for, if any. Reviewed sounds aren't added yet
+fn action_audio_id(action: &ButtonAction) -> Option<i64> {
+    match action {
+        ButtonAction::Play(audio_track_id)
+        | ButtonAction::Favorite(audio_track_id)
+        | ButtonAction::Archive(audio_track_id)
+        | ButtonAction::Keep(audio_track_id) => Some(*audio_track_id),
+        _ => None,
+    }
+}
+
+/// Handler of each action
+async fn dispatch(c: &ComponentContext<'_>) -> HandlerResult {
+    match &c.custom_id.action {
+        ButtonAction::Play(audio_track_id) => play(c, *audio_track_id).await,
+        ButtonAction::PageNav { page, tag } => page_nav(c, *page, tag.clone()).await,
+        ButtonAction::Confirm(_) | ButtonAction::Cancel(_) | ButtonAction::PickName { .. } => {
+            expired_confirmation(c).await
+        }
+        ButtonAction::Favorite(audio_track_id) => favorite(c, *audio_track_id).await,
+        ButtonAction::TagFilter => tag_filter(c).await,
+        ButtonAction::Stop => stop(c).await,
+        ButtonAction::Replay => replay(c).await,
+        ButtonAction::Repeat => repeat(c).await,
+        ButtonAction::Archive(audio_track_id) => prune(c, *audio_track_id, true).await,
+        ButtonAction::Keep(audio_track_id) => prune(c, *audio_track_id, false).await,
+        ButtonAction::Onboarding(step) => run_onboarding_step(c, *step).await,
+        ButtonAction::Listen(id) => listen(c, *id).await,
+        ButtonAction::Approve(id) => review(c, *id, true).await,
+        ButtonAction::Reject(id) => review(c, *id, false).await,
+    }
+}
+
+/// Acknowledge component interaction and dispatch it to its action's handler
+pub async fn handle_component(
+    ctx: &Context,
+    component: &ComponentInteraction,
+    data: &UserData,
+) -> Result<(), PoiseError> {
+    log::debug!("Interaction Component Button pressed");
+    let custom_id = ButtonCustomId::parse(&component.data.custom_id)
+        .and_then(|id| id.ensure_guild(component.guild_id).map(|_| id));
+
+    // pending confirmation buttons are responded to by the command awaiting them
+    if let Ok(ButtonCustomId {
+        action: ButtonAction::Confirm(id) | ButtonAction::Cancel(id),
+        ..
+    }) = custom_id
+    {
+        if data.pending_confirmations.is_pending(id) {
+            return Ok(());
+        }
+    }
+
+    component
+        .create_response(&ctx.http, CreateInteractionResponse::Acknowledge)
+        .await
+        .log_err_msg("Failed to create response for btn interaction")
+        .ok();
+
+    let lang = i18n::guild_language(data, component.guild_id, component.guild_locale.as_deref());
+    let followup = match custom_id {
+        Err(err) => {
+            log::warn!("Unrecognized button custom_id for component interaction - {err}");
+            Some(tr(lang, "button.unsupported", &[]))
+        }
+        Ok(custom_id)
+            if data.config().read_only && !read_only::is_read_only_action(&custom_id.action) =>
+        {
+            log::info!("Refusing button on read-only mirror - '{custom_id:?}'");
+            Some(tr(lang, "read_only.refused", &[]))
+        }
+        Ok(custom_id) => {
+            let name = action_name(&custom_id.action);
+            log::info!("Handling {name} interaction - '{custom_id:?}'");
+
+            let c = ComponentContext {
+                ctx,
+                component,
+                data,
+                lang,
+                custom_id,
+            };
+            let started_at = Instant::now();
+            let result = dispatch(&c).await;
+            record_metrics(name, started_at.elapsed(), result.is_err());
+
+            match result {
+                Ok(Some(followup)) => {
+                    component
+                        .create_followup(&ctx.http, followup)
+                        .await
+                        .log_err_msg(format!("Failed to create followup for {name} interaction"))
+                        .ok();
+                    None
+                }
+                Ok(None) => None,
+                Err(err) => {
+                    let context = ErrorContext {
+                        guild_id: component.guild_id.map(|guild_id| guild_id.get()),
+                        command: Some(format!("button {name}")),
+                        sound: action_audio_id(&c.custom_id.action).map(|id| id.to_string()),
+                    };
+                    error_reporting::with_context(&context, || {
+                        log::error!("{name} interaction failed - {err}")
+                    });
+                    Some(tr(lang, "button.failed", &[]))
+                }
+            }
+        }
+    };
+
+    if let Some(content) = followup {
+        component
+            .create_followup(
+                &ctx.http,
+                CreateInteractionResponseFollowup::new()
+                    .ephemeral(true)
+                    .content(content),
+            )
+            .await
+            .log_err_msg("Failed to create followup for btn interaction")
+            .ok();
+    }
+
+    Ok(())
+}
+
+async fn play(c: &ComponentContext<'_>, audio_track_id: i64) -> HandlerResult {
+    let guild_id = c.guild_id()?;
+    let manager = helpers::songbird_get(c.ctx).await;
+    let result = playback::play_audio_track(
+        c.data,
+        &*manager,
+        guild_id,
+        c.component.channel_id,
+        c.component.user.id,
+        db::UniqueAudioTableCol::Id(audio_track_id),
+        PlaySource::Button,
+    )
+    .await;
+
+    match result {
+        Ok(_) => Ok(None),
+        Err(AudioError::AudioTrackNotFound { .. }) => {
+            Err("Unable to locate audio track for button custom id".into())
+        }
+        Err(AudioError::RateLimited { retry_after }) => Ok(Some(
+            c.followup(helpers::slow_down_message(c.lang, retry_after)),
+        )),
+        Err(AudioError::Quarantined { track }) => Ok(Some(c.followup(tr(
+            c.lang,
+            "play.quarantined",
+            &[("track", &track)],
+        )))),
+        Err(AudioError::ChecksumMismatch { track }) => Ok(Some(c.followup(tr(
+            c.lang,
+            "play.checksum_mismatch",
+            &[("track", &track)],
+        )))),
+        Err(err) => {
+            log::error!("Failed to play audio track for button - {err}");
+            Ok(None)
+        }
+    }
+}
+
+async fn favorite(c: &ComponentContext<'_>, audio_track_id: i64) -> HandlerResult {
+    let is_favorite = c
+        .data
+        .favorites_table()
+        .toggle_favorite(c.component.user.id.get(), audio_track_id)?;
+    c.data.autocomplete_cache.invalidate();
+
+    let content = match is_favorite {
+        true => tr(c.lang, "favorites.added_button", &[]),
+        false => tr(c.lang, "favorites.removed_button", &[]),
+    };
+    Ok(Some(c.followup(content)))
+}
+
+/// Archive or keep sound suggested for pruning
+async fn prune(c: &ComponentContext<'_>, audio_track_id: i64, archive: bool) -> HandlerResult {
+    let lang = c.lang;
+    let table = c.data.audio_table();
+    let content = match table.find_audio_row(db::UniqueAudioTableCol::Id(audio_track_id)) {
+        // suggestions are only shown to administrators, but buttons outlive permissions
+        _ if !c.is_admin() => tr(lang, "prune.not_admin", &[]),
+        None => tr(lang, "prune.not_found", &[]),
+        Some(row) if archive => {
+            table.set_archived(row.id, true)?;
+            c.data.autocomplete_cache.invalidate();
+            tr(lang, "prune.archived", &[("track", &row.name)])
+        }
+        Some(row) => {
+            table.keep_audio_row(row.id)?;
+            tr(lang, "prune.kept", &[("track", &row.name)])
+        }
+    };
+    Ok(Some(c.followup(content)))
+}
+
+async fn page_nav(c: &ComponentContext<'_>, page: u64, tag: Option<String>) -> HandlerResult {
+    soundboard::show_soundboard_page(
+        &c.ctx.http,
+        c.data,
+        c.component.guild_id,
+        c.component.channel_id,
+        c.component.message.id,
+        page,
+        tag.as_deref(),
+    )
+    .await?;
+    Ok(None)
+}
+
+async fn tag_filter(c: &ComponentContext<'_>) -> HandlerResult {
+    let tag = match c.component.data.kind {
+        ComponentInteractionDataKind::StringSelect { ref values } => values
+            .first()
+            .filter(|value| value.as_str() != soundboard::ALL_TAGS_VALUE),
+        _ => None,
+    };
+
+    soundboard::show_soundboard_page(
+        &c.ctx.http,
+        c.data,
+        c.component.guild_id,
+        c.component.channel_id,
+        c.component.message.id,
+        0,
+        tag.map(|tag| tag.as_str()),
+    )
+    .await?;
+    Ok(None)
+}
+
+async fn replay(c: &ComponentContext<'_>) -> HandlerResult {
+    let guild_id = c.guild_id()?;
+    let manager = helpers::songbird_get(c.ctx).await;
+    let result = playback::replay_last_track(
+        c.data,
+        &*manager,
+        guild_id,
+        c.component.channel_id,
+        c.component.user.id,
+        false,
+        PlaySource::Button,
+    )
+    .await;
+
+    let content = match result {
+        Ok(_) => None,
+        Err(AudioError::NothingToReplay) => Some(tr(c.lang, "replay.nothing", &[])),
+        Err(AudioError::RateLimited { retry_after }) => {
+            Some(helpers::slow_down_message(c.lang, retry_after))
+        }
+        Err(err) => {
+            log::error!("Failed to replay audio track for button - {err}");
+            None
+        }
+    };
+    Ok(content.map(|content| c.followup(content)))
+}
+
+/// Toggle looping the sound playing until stopped
+async fn repeat(c: &ComponentContext<'_>) -> HandlerResult {
+    let (data, lang) = (c.data, c.lang);
+    let guild_id = c.guild_id()?;
+    let looping = !data.now_playing.is_looping(guild_id);
+    let permitted = !looping
+        || match c.component.member.as_ref() {
+            Some(member) => {
+                permissions::is_member_permitted(data, member, Permission::LoopForever)?
+            }
+            None => false,
+        };
+
+    let content = match data.now_playing.current_play_id(guild_id) {
+        None => Some(tr(lang, "now_playing.nothing", &[])),
+        Some(_) if !permitted => Some(tr(
+            lang,
+            "play.loop_forbidden",
+            &[("permission", &Permission::LoopForever.name())],
+        )),
+        Some(play_id) => {
+            let manager = helpers::songbird_get(c.ctx).await;
+            match manager
+                .set_loop(guild_id, looping.then_some(LoopMode::Infinite))
+                .await
+            {
+                Ok(()) => {
+                    data.now_playing.set_looping(guild_id, play_id, looping);
+                    data.now_playing
+                        .refresh(guild_id, lang)
+                        .await
+                        .log_err_msg("Failed to refresh now playing message")
+                        .ok();
+                    None
+                }
+                Err(err) => {
+                    log::warn!("Failed to loop audio track for button - {err}");
+                    Some(tr(lang, "now_playing.nothing", &[]))
+                }
+            }
+        }
+    };
+    Ok(content.map(|content| c.followup(content)))
+}
+
+async fn stop(c: &ComponentContext<'_>) -> HandlerResult {
+    let guild_id = c.guild_id()?;
+    c.data.playback_queue.clear(guild_id);
+    let manager = helpers::songbird_get(c.ctx).await;
+    manager
+        .stop_audio(guild_id)
+        .await
+        .log_err_msg("Failed to stop audio for button")
+        .ok();
+    Ok(None)
+}
+
+async fn run_onboarding_step(c: &ComponentContext<'_>, step: OnboardingStep) -> HandlerResult {
+    let lang = c.lang;
+    let guild_id = c.guild_id()?;
+
+    // setup message is visible to everyone in the system channel
+    let content = match c.is_admin() {
+        false => tr(lang, "onboarding.not_admin", &[]),
+        true => onboarding::run_step(&c.ctx.http, c.data, lang, guild_id, step)
+            .await
+            .log_err_msg("Failed to run setup step")
+            .unwrap_or_else(|err| tr(lang, "onboarding.failed", &[("error", &err)])),
+    };
+    Ok(Some(c.followup(content)))
+}
+
+/// Whether member pressing button can review sounds. Review messages are visible to everyone
+/// in the review channel
+fn is_approver(c: &ComponentContext<'_>) -> Result<bool, PoiseError> {
+    match c.component.member.as_ref() {
+        Some(member) => permissions::is_member_approver(c.data, member),
+        None => Ok(false),
+    }
+}
+
+/// Send audio file of pending sound to the moderator pressing button
+async fn listen(c: &ComponentContext<'_>, id: i64) -> HandlerResult {
+    if !is_approver(c)? {
+        return Ok(Some(c.followup(tr(c.lang, "approval.not_approver", &[]))));
+    }
+
+    match c.data.pending_sounds_table().find_pending_sound(id)? {
+        Some(pending) => {
+            let mut attachment = CreateAttachment::path(pending.audio_file.as_path()).await?;
+            attachment.filename = pending.audio_file.slug_file_name(&pending.name.slug());
+            Ok(Some(
+                c.followup(format!("`{}`", pending.name))
+                    .add_file(attachment),
+            ))
+        }
+        None => Ok(Some(c.followup(tr(c.lang, "approval.not_found", &[])))),
+    }
+}
+
+/// Approve or reject pending sound
+async fn review(c: &ComponentContext<'_>, id: i64, approve: bool) -> HandlerResult {
+    let lang = c.lang;
+    if !is_approver(c)? {
+        return Ok(Some(c.followup(tr(lang, "approval.not_approver", &[]))));
+    }
+
+    let moderator = format!("<@{}>", c.component.user.id);
+    let outcome = match approve {
+        true => approval::approve(c.data, id)?.map(|name| {
+            tr(
+                lang,
+                "approval.approved",
+                &[("track", &name), ("moderator", &moderator)],
+            )
+        }),
+        false => approval::reject(c.data, id)?.map(|name| {
+            tr(
+                lang,
+                "approval.rejected",
+                &[("track", &name), ("moderator", &moderator)],
+            )
+        }),
+    };
+    let Some(outcome) = outcome else {
+        return Ok(Some(c.followup(tr(lang, "approval.not_found", &[]))));
+    };
+
+    // record outcome on the review message, so it can't be reviewed twice
+    let content = format!("{}\n{outcome}", c.component.message.content);
+    c.component
+        .channel_id
+        .edit_message(
+            &c.ctx.http,
+            c.component.message.id,
+            EditMessage::new().content(content).components(vec![]),
+        )
+        .await
+        .log_err_msg("Failed to edit sound review message")
+        .ok();
+
+    if let (true, Some(guild_id)) = (approve, c.component.guild_id) {
+        soundboard::refresh_soundboard(&c.ctx.http, c.data, guild_id)
+            .await
+            .log_err_msg("Failed to refresh published soundboard")
+            .ok();
+    }
+    Ok(None)
+}
+
+/// Confirmation buttons no command awaits anymore
+async fn expired_confirmation(c: &ComponentContext<'_>) -> HandlerResult {
+    Ok(Some(c.followup(tr(c.lang, "confirm.expired", &[]))))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn action_name_test() {
+        assert_eq!("play", action_name(&ButtonAction::Play(1)));
+        assert_eq!("confirm", action_name(&ButtonAction::Cancel(1)));
+        assert_eq!("prune", action_name(&ButtonAction::Keep(1)));
+        assert_eq!("review", action_name(&ButtonAction::Approve(1)));
+    }
+
+    #[test]
+    fn action_audio_id_test() {
+        assert_eq!(Some(1), action_audio_id(&ButtonAction::Play(1)));
+        assert_eq!(Some(2), action_audio_id(&ButtonAction::Keep(2)));
+        assert_eq!(None, action_audio_id(&ButtonAction::Approve(3)));
+        assert_eq!(None, action_audio_id(&ButtonAction::Stop));
+    }
+
+    #[test]
+    fn record_metrics_test() {
+        record_metrics("test", Duration::from_millis(30), false);
+        record_metrics("test", Duration::from_millis(10), true);
+
+        let (_, metrics) = metrics()
+            .into_iter()
+            .find(|(name, _)| *name == "test")
+            .unwrap();
+        assert_eq!(2, metrics.count);
+        assert_eq!(1, metrics.failures);
+        assert_eq!(Duration::from_millis(20), metrics.average());
+        assert_eq!(Duration::ZERO, InteractionMetrics::default().average());
+    }
+}
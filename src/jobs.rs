@@ -0,0 +1,413 @@
+//! Background jobs. Long-running work (uploads, audio dir scans, transcoding, sound pack installs,
+//! backups) is queued in the jobs table and run one at a time by a worker task, off the interaction
+//! path. Progress is kept in the table for `/jobs status`, and the outcome is posted to the channel
+//! the job was queued from. Jobs interrupted by a restart are run again once the bot is ready
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Serialize};
+use serenity::all::{ChannelId, GuildId, Http};
+use tokio::sync::mpsc;
+
+use crate::commands::PoiseError;
+use crate::common::{LogResult, UserData};
+use crate::db::{JobInsert, JobRow, JobStatus};
+use crate::i18n::{self, tr, Language};
+use crate::ingest::{self, Upload, Uploader};
+use crate::packs::{self, InstallOutcome, InstallStep};
+use crate::{backup, opus_cache, soundboard};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Job {
+    /// Add uploaded files as sounds (see `ingest::upload_audio_files`)
+    Ingest {
+        uploads: Vec<Upload>,
+        uploader: Uploader,
+    },
+    /// Add untracked audio files of the audio dir (see `UserData::sync_audio_dir`)
+    ScanAudioDir,
+    /// Transcode the most played sounds into the Opus cache (see `opus_cache::warm_opus_cache`)
+    Transcode,
+    /// Back up the database (see `backup::run_backup`)
+    Backup,
+    /// Install sound pack in the job's guild (see `packs::install_pack`)
+    InstallPack { pack_id: String },
+}
+
+impl Job {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("Job serializes to json")
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, PoiseError> {
+        Ok(serde_json::from_str(json).log_err_msg("Failed to parse job")?)
+    }
+
+    /// Short description of job, e.g. for `/jobs status`
+    pub fn describe(&self, lang: Language) -> String {
+        match self {
+            Self::Ingest { uploads, .. } => tr(lang, "jobs.ingest", &[("count", &uploads.len())]),
+            Self::ScanAudioDir => tr(lang, "jobs.scan_audio_dir", &[]),
+            Self::Transcode => tr(lang, "jobs.transcode", &[]),
+            Self::Backup => tr(lang, "jobs.backup", &[]),
+            Self::InstallPack { pack_id } => tr(lang, "jobs.install_pack", &[("pack", pack_id)]),
+        }
+    }
+}
+
+/// Line of `/jobs status` describing job and its status
+pub fn render_job(lang: Language, row: &JobRow) -> String {
+    let job = match Job::from_json(&row.job) {
+        Ok(job) => job.describe(lang),
+        Err(_) => row.job.clone(),
+    };
+    let status = match row.status {
+        JobStatus::Queued => tr(lang, "jobs.status_queued", &[]),
+        JobStatus::Running => tr(lang, "jobs.status_running", &[]),
+        JobStatus::Done => tr(lang, "jobs.status_done", &[]),
+        JobStatus::Failed => tr(lang, "jobs.status_failed", &[]),
+    };
+    let details = match row.status {
+        JobStatus::Running => row.progress.as_deref(),
+        JobStatus::Done | JobStatus::Failed => row.result.as_deref(),
+        JobStatus::Queued => None,
+    };
+
+    match details {
+        Some(details) => format!(
+            "- `#{}` {job} - **{status}** <t:{}:R> - {details}",
+            row.id,
+            row.updated_at.timestamp()
+        ),
+        None => format!(
+            "- `#{}` {job} - **{status}** <t:{}:R>",
+            row.id,
+            row.updated_at.timestamp()
+        ),
+    }
+}
+
+/// Queue of job ids for the worker (see `spawn_worker`)
+pub struct Jobs {
+    sender: mpsc::UnboundedSender<i64>,
+    /// Taken by the worker when it starts
+    receiver: Mutex<Option<mpsc::UnboundedReceiver<i64>>>,
+}
+
+impl Jobs {
+    pub fn new() -> Self {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        Self {
+            sender,
+            receiver: Mutex::new(Some(receiver)),
+        }
+    }
+
+    /// Queue job, returning its job id. Its outcome is posted to channel, if any
+    pub fn enqueue(
+        &self,
+        data: &UserData,
+        job: &Job,
+        guild_id: Option<GuildId>,
+        channel_id: Option<ChannelId>,
+        user_id: u64,
+    ) -> Result<i64, PoiseError> {
+        let id = data.jobs_table().insert_job(&JobInsert {
+            job: job.to_json(),
+            guild_id: guild_id.map(|guild_id| guild_id.get()),
+            channel_id: channel_id.map(|channel_id| channel_id.get()),
+            user_id,
+        })?;
+
+        log::info!("Queued job #{id} - {job:?}");
+        self.sender.send(id).ok();
+        Ok(id)
+    }
+}
+
+impl Default for Jobs {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Start running queued jobs, starting with jobs left unfinished by the last run. Only the
+/// first call starts the worker
+pub fn spawn_worker(http: Arc<Http>, data: UserData) {
+    let Some(mut receiver) = data.jobs.receiver.lock().unwrap().take() else {
+        return;
+    };
+
+    tokio::spawn(async move {
+        let unfinished = data.jobs_table().unfinished_jobs().unwrap_or_default();
+        if !unfinished.is_empty() {
+            log::info!("Resuming {} unfinished jobs", unfinished.len());
+        }
+        for row in unfinished {
+            data.jobs.sender.send(row.id).ok();
+        }
+
+        while let Some(id) = receiver.recv().await {
+            run_job(&http, &data, id).await;
+        }
+    });
+}
+
+async fn run_job(http: &Http, data: &UserData, id: i64) {
+    let table = data.jobs_table();
+    // jobs queued before the worker started are also resumed, so they can be received twice
+    let Some(row) = table.find_job(id).ok().flatten() else {
+        return;
+    };
+    if row.status.is_finished() {
+        return;
+    }
+
+    log::info!("Running job #{id} - {}", row.job);
+    table.set_status(id, JobStatus::Running).ok();
+
+    let lang = i18n::guild_language(data, row.guild_id.map(GuildId::new), None);
+    let (status, result) = match execute_job(http, data, lang, &row).await {
+        Ok(result) => (JobStatus::Done, result),
+        Err(err) => {
+            log::error!("Job #{id} failed - {err}");
+            (
+                JobStatus::Failed,
+                tr(lang, "jobs.failed", &[("error", &err)]),
+            )
+        }
+    };
+    table.finish_job(id, status, &result).ok();
+
+    if let Some(channel_id) = row.channel_id {
+        let content = tr(lang, "jobs.finished", &[("id", &id), ("result", &result)]);
+        ChannelId::new(channel_id)
+            .say(http, content)
+            .await
+            .log_err_msg(format!("Failed to post outcome of job #{id}"))
+            .ok();
+    }
+}
+
+async fn execute_job(
+    http: &Http,
+    data: &UserData,
+    lang: Language,
+    row: &JobRow,
+) -> Result<String, PoiseError> {
+    match Job::from_json(&row.job)? {
+        Job::Ingest { uploads, uploader } => {
+            let concurrency = data.config().ingest_concurrency;
+            let results =
+                ingest::upload_audio_files(http, data, uploads, &uploader, concurrency).await;
+
+            let uploaded = results
+                .iter()
+                .filter(|result| result.result.is_ok())
+                .count();
+            if uploaded > 0 {
+                data.autocomplete_cache.invalidate();
+                if uploader.review_channel_id.is_none() {
+                    refresh_soundboard(http, data, uploader.guild_id).await;
+                }
+            }
+
+            let key = match uploader.review_channel_id {
+                Some(_) => "approval.submitted_uploads",
+                None => "sounds.uploaded",
+            };
+            Ok(format!(
+                "{}\n```\n{}\n```",
+                tr(
+                    lang,
+                    key,
+                    &[("uploaded", &uploaded), ("total", &results.len())]
+                ),
+                ingest::render_upload_summary(&results)
+            ))
+        }
+        Job::ScanAudioDir => {
+            let inserted = data
+                .sync_audio_dir(|progress| {
+                    set_progress(
+                        data,
+                        row.id,
+                        tr(lang, "scan.progress", &[("count", &progress)]),
+                    )
+                })
+                .await?;
+            if inserted > 0 {
+                refresh_soundboard(http, data, row.guild_id).await;
+            }
+            Ok(tr(lang, "scan.complete", &[("count", &inserted)]))
+        }
+        Job::Transcode => {
+            let cached = opus_cache::warm_opus_cache(data).await?;
+            Ok(tr(lang, "jobs.transcoded", &[("count", &cached)]))
+        }
+        Job::Backup => {
+            let backup_file =
+                backup::run_backup(&data.db_connection(), &data.config(), chrono::Utc::now())?;
+            Ok(tr(
+                lang,
+                "owner.backup_done",
+                &[("file", &backup_file.to_string_lossy())],
+            ))
+        }
+        Job::InstallPack { pack_id } => {
+            let guild_id = row.guild_id.ok_or("Sound pack job has no guild")?;
+            let Some(index_url) = data.config().packs_index_url else {
+                return Ok(tr(lang, "packs.not_configured", &[]));
+            };
+
+            let index = packs::fetch_index(&data.http_client, &index_url).await?;
+            let Some(pack) = index.find_pack(&pack_id) else {
+                return Ok(tr(lang, "packs.not_found", &[("pack", &pack_id)]));
+            };
+
+            let outcome = packs::install_pack(data, &index_url, pack, guild_id, |step| {
+                let progress = match step {
+                    InstallStep::Downloading(progress) => tr(
+                        lang,
+                        "packs.downloading",
+                        &[("pack", &pack.name), ("progress", &progress)],
+                    ),
+                    InstallStep::Verifying => tr(lang, "packs.verifying", &[("pack", &pack.name)]),
+                    InstallStep::Validating(progress) => tr(
+                        lang,
+                        "packs.validating",
+                        &[("pack", &pack.name), ("progress", &progress)],
+                    ),
+                };
+                set_progress(data, row.id, progress)
+            })
+            .await?;
+
+            match outcome {
+                InstallOutcome::Installed(count) => {
+                    if count > 0 {
+                        refresh_soundboard(http, data, Some(guild_id)).await;
+                    }
+                    Ok(tr(
+                        lang,
+                        "packs.installed",
+                        &[("pack", &pack.name), ("count", &count)],
+                    ))
+                }
+                InstallOutcome::HashMismatch => {
+                    Err(tr(lang, "packs.hash_mismatch", &[("pack", &pack.name)]).into())
+                }
+            }
+        }
+    }
+}
+
+async fn refresh_soundboard(http: &Http, data: &UserData, guild_id: Option<u64>) {
+    if let Some(guild_id) = guild_id {
+        soundboard::refresh_soundboard(http, data, GuildId::new(guild_id))
+            .await
+            .log_err_msg("Failed to refresh published soundboard")
+            .ok();
+    }
+}
+
+fn set_progress(data: &UserData, id: i64, progress: String) -> impl Future<Output = ()> {
+    data.jobs_table().set_progress(id, &progress).ok();
+    std::future::ready(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::TestDb;
+
+    use super::*;
+
+    #[test]
+    fn job_json_test() {
+        let job = Job::InstallPack {
+            pack_id: "memes".into(),
+        };
+        assert_eq!(
+            r#"{"kind":"install_pack","pack_id":"memes"}"#,
+            job.to_json()
+        );
+        assert_eq!(job, Job::from_json(&job.to_json()).unwrap());
+        assert_eq!(Job::Backup, Job::from_json(r#"{"kind":"backup"}"#).unwrap());
+        assert_eq!(
+            Job::Transcode,
+            Job::from_json(r#"{"kind":"transcode"}"#).unwrap()
+        );
+        assert!(Job::from_json(r#"{"kind":"reticulate"}"#).is_err());
+
+        let job = Job::Ingest {
+            uploads: vec![Upload {
+                file_name: "bruh.mp3".into(),
+                url: "https://cdn.discordapp.com/bruh.mp3".into(),
+            }],
+            uploader: Uploader {
+                id: 1,
+                name: "bob".into(),
+                global_name: None,
+                guild_id: Some(2),
+                review_channel_id: None,
+                author_hash: None,
+            },
+        };
+        assert_eq!(job, Job::from_json(&job.to_json()).unwrap());
+    }
+
+    #[test]
+    fn render_job_test() {
+        let row = JobRow {
+            id: 7,
+            job: Job::Backup.to_json(),
+            guild_id: Some(1),
+            channel_id: None,
+            user_id: 2,
+            status: JobStatus::Running,
+            progress: Some("45%".into()),
+            result: None,
+            created_at: chrono::DateTime::from_timestamp(1_700_000_000, 0).unwrap(),
+            updated_at: chrono::DateTime::from_timestamp(1_700_000_000, 0).unwrap(),
+        };
+
+        assert_eq!(
+            "- `#7` Database backup - **Running** <t:1700000000:R> - 45%",
+            render_job(Language::English, &row)
+        );
+
+        let row = JobRow {
+            status: JobStatus::Queued,
+            ..row
+        };
+        assert_eq!(
+            "- `#7` Database backup - **Queued** <t:1700000000:R>",
+            render_job(Language::English, &row)
+        );
+    }
+
+    #[tokio::test]
+    async fn enqueue_test() {
+        let db = TestDb::new();
+        let data = db.user_data();
+
+        let id = data
+            .jobs
+            .enqueue(
+                &data,
+                &Job::ScanAudioDir,
+                Some(GuildId::new(1)),
+                Some(ChannelId::new(2)),
+                3,
+            )
+            .unwrap();
+
+        let row = data.jobs_table().find_job(id).unwrap().unwrap();
+        assert_eq!(JobStatus::Queued, row.status);
+        assert_eq!(Job::ScanAudioDir, Job::from_json(&row.job).unwrap());
+
+        let mut receiver = data.jobs.receiver.lock().unwrap().take().unwrap();
+        assert_eq!(Some(id), receiver.recv().await);
+    }
+}
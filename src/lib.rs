@@ -0,0 +1,56 @@
+pub mod alerts;
+pub mod approval;
+pub mod audio;
+pub mod audio_watcher;
+pub mod backup;
+pub mod blocklist;
+pub mod cache;
+pub mod channel_restrictions;
+pub mod checksums;
+pub mod command_stats;
+pub mod commands;
+pub mod common;
+pub mod config;
+pub mod db;
+pub mod diagnostics;
+pub mod digest;
+pub mod disk;
+pub mod doctor;
+pub mod download_policy;
+pub mod error_reporting;
+pub mod errors;
+pub mod guild_export;
+pub mod helpers;
+pub mod http_client;
+pub mod i18n;
+pub mod ingest;
+pub mod interactions;
+pub mod jobs;
+pub mod loudness;
+pub mod moderation;
+pub mod native_soundboard;
+pub mod now_playing;
+pub mod onboarding;
+pub mod opus_cache;
+pub mod owner;
+pub mod packs;
+pub mod permissions;
+pub mod playback;
+pub mod presence;
+pub mod privacy;
+pub mod prune;
+pub mod rate_limit;
+pub mod read_only;
+pub mod self_check;
+pub mod sessions;
+pub mod sharing;
+pub mod sotd;
+pub mod soundboard;
+pub mod stats;
+pub mod telemetry;
+pub mod temp_files;
+#[cfg(any(test, feature = "test-utils"))]
+pub mod test_utils;
+pub mod trash;
+pub mod vars;
+pub mod voice_reconnect;
@@ -0,0 +1,565 @@
+//! Loudness checks of added sounds, so earrape and heavily clipped sounds don't blast voice
+//! channels. Sounds added with `/sounds add` or `/sounds upload` are decoded to measure their
+//! peak and RMS levels, and sounds louder than `Config::loudness_ceiling_db` (or clipping) are
+//! handled as the guild's `/settings loudness` says: added anyway, attenuated, sent to the review
+//! channel (see `approval`) or refused.
+//!
+//! Sounds are attenuated like mp3gain does, by lowering the global gain of every MP3 frame in
+//! steps of 1.5 dB. The audio isn't re-encoded, so nothing but the volume changes
+use std::fs;
+use std::path;
+
+use serenity::all::GuildId;
+use symphonia::core::audio::SampleBuffer;
+use symphonia::core::codecs::DecoderOptions;
+use symphonia::core::errors::Error as SymphoniaError;
+use symphonia::core::formats::FormatOptions;
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::meta::MetadataOptions;
+use symphonia::core::probe::Hint;
+
+use crate::commands::PoiseError;
+use crate::common::{LogResult, UserData};
+use crate::i18n::{self, tr};
+use crate::moderation::{self, Flagged, ModeratedSound};
+use crate::vars;
+
+/// What happens to sounds louder than the loudness ceiling
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, poise::ChoiceParameter)]
+pub enum LoudnessAction {
+    #[default]
+    #[name = "Allow"]
+    Allow,
+    /// Turned down to the ceiling
+    #[name = "Attenuate"]
+    Attenuate,
+    /// Sent to the review channel
+    #[name = "Review"]
+    Review,
+    #[name = "Reject"]
+    Reject,
+}
+
+impl LoudnessAction {
+    pub const ALL: [LoudnessAction; 4] = [
+        LoudnessAction::Allow,
+        LoudnessAction::Attenuate,
+        LoudnessAction::Review,
+        LoudnessAction::Reject,
+    ];
+
+    /// Code stored in `GuildSettingsRow::loudness_action`
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Allow => "allow",
+            Self::Attenuate => "attenuate",
+            Self::Review => "review",
+            Self::Reject => "reject",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|action| action.code() == code)
+    }
+}
+
+/// Levels of decoded audio, in dB relative to full scale
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Loudness {
+    pub peak_db: f64,
+    pub rms_db: f64,
+    /// Share of samples at full scale
+    pub clipped_ratio: f64,
+}
+
+impl Loudness {
+    pub fn from_samples(samples: impl IntoIterator<Item = f32>) -> Self {
+        let mut meter = LoudnessMeter::default();
+        meter.add(samples);
+        meter.loudness()
+    }
+
+    /// Louder than ceiling, or clipping too much
+    pub fn exceeds(&self, ceiling_db: f64) -> bool {
+        self.rms_db > ceiling_db || self.clipped_ratio > vars::LOUDNESS_MAX_CLIPPED_RATIO
+    }
+
+    /// Attenuation bringing the sound under ceiling. Sounds only clipping are turned down by
+    /// `vars::LOUDNESS_CLIPPING_ATTENUATION_DB`
+    pub fn attenuation_db(&self, ceiling_db: f64) -> f64 {
+        match self.rms_db > ceiling_db {
+            true => self.rms_db - ceiling_db,
+            false => vars::LOUDNESS_CLIPPING_ATTENUATION_DB,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct LoudnessMeter {
+    peak: f32,
+    sum_squares: f64,
+    samples: u64,
+    clipped: u64,
+}
+
+impl LoudnessMeter {
+    fn add(&mut self, samples: impl IntoIterator<Item = f32>) {
+        for sample in samples {
+            let sample = sample.abs();
+            self.peak = self.peak.max(sample);
+            self.sum_squares += (sample as f64).powi(2);
+            self.samples += 1;
+            if sample >= vars::LOUDNESS_CLIPPED_SAMPLE {
+                self.clipped += 1;
+            }
+        }
+    }
+
+    fn loudness(&self) -> Loudness {
+        if self.samples == 0 {
+            return Loudness {
+                peak_db: f64::NEG_INFINITY,
+                rms_db: f64::NEG_INFINITY,
+                clipped_ratio: 0.0,
+            };
+        }
+
+        Loudness {
+            peak_db: 20.0 * (self.peak as f64).log10(),
+            rms_db: 10.0 * (self.sum_squares / self.samples as f64).log10(),
+            clipped_ratio: self.clipped as f64 / self.samples as f64,
+        }
+    }
+}
+
+/// Decode audio file and measure its levels
+#[tracing::instrument(name = "analyze_loudness", skip_all)]
+pub fn analyze(audio_file: impl AsRef<path::Path>) -> Result<Loudness, PoiseError> {
+    let path = audio_file.as_ref();
+    log::info!(
+        "Analyzing loudness of audio file - {}",
+        path.to_string_lossy()
+    );
+
+    let file = fs::File::open(path).log_err()?;
+    let mss = MediaSourceStream::new(Box::new(file), Default::default());
+    let mut hint = Hint::default();
+    hint.with_extension("mp3");
+
+    let mut format = symphonia::default::get_probe()
+        .format(
+            &hint,
+            mss,
+            &FormatOptions::default(),
+            &MetadataOptions::default(),
+        )
+        .log_err_msg("Failed to probe format")?
+        .format;
+    let track = format.default_track().ok_or("No audio track found")?;
+    let track_id = track.id;
+    let mut decoder = symphonia::default::get_codecs()
+        .make(&track.codec_params, &DecoderOptions::default())
+        .log_err_msg("Failed to create audio decoder")?;
+
+    let mut meter = LoudnessMeter::default();
+    let mut sample_buf: Option<SampleBuffer<f32>> = None;
+    loop {
+        let packet = match format.next_packet() {
+            Ok(packet) => packet,
+            Err(SymphoniaError::IoError(err))
+                if err.kind() == std::io::ErrorKind::UnexpectedEof =>
+            {
+                break
+            }
+            Err(err) => return Err(err.into()),
+        };
+        if packet.track_id() != track_id {
+            continue;
+        }
+
+        match decoder.decode(&packet) {
+            Ok(decoded) => {
+                let sample_buf = sample_buf.get_or_insert_with(|| {
+                    SampleBuffer::new(decoded.capacity() as u64, *decoded.spec())
+                });
+                sample_buf.copy_interleaved_ref(decoded);
+                meter.add(sample_buf.samples().iter().copied());
+            }
+            // corrupt frames are skipped, like playback does
+            Err(SymphoniaError::DecodeError(_)) => continue,
+            Err(err) => return Err(err.into()),
+        }
+    }
+
+    Ok(meter.loudness())
+}
+
+/// Lower the volume of MP3 file in place by at least `db`, in steps of 1.5 dB. Returns the
+/// attenuation applied
+pub fn attenuate_mp3(audio_file: impl AsRef<path::Path>, db: f64) -> Result<f64, PoiseError> {
+    let path = audio_file.as_ref();
+    let steps = (db / vars::MP3_GAIN_STEP_DB)
+        .ceil()
+        .clamp(0.0, u8::MAX as f64) as u8;
+    log::info!(
+        "Attenuating audio file by {db:.1}dB - {}",
+        path.to_string_lossy()
+    );
+
+    let mut bytes = fs::read(path).log_err()?;
+    if attenuate_mp3_frames(&mut bytes, steps) == 0 {
+        return Err("No MP3 frames found to attenuate".into()).log_err();
+    }
+    fs::write(path, bytes).log_err_msg("Failed to write attenuated audio file")?;
+
+    Ok(steps as f64 * vars::MP3_GAIN_STEP_DB)
+}
+
+/// Lower the global gain of every MP3 frame by `steps`. Returns number of frames changed
+fn attenuate_mp3_frames(bytes: &mut [u8], steps: u8) -> usize {
+    let mut pos = id3v2_len(bytes);
+    let mut frames = 0;
+
+    while pos + 4 <= bytes.len() {
+        let Some(header) = FrameHeader::parse(&bytes[pos..pos + 4]) else {
+            pos += 1;
+            continue;
+        };
+        let side_info = pos + 4 + if header.crc { 2 } else { 0 };
+        if pos + header.len > bytes.len() || side_info + header.side_info_len() > bytes.len() {
+            break;
+        }
+
+        for offset in header.global_gain_offsets() {
+            let bit = side_info * 8 + offset;
+            let gain = read_bits(bytes, bit, 8) as u8;
+            write_bits(bytes, bit, 8, gain.saturating_sub(steps) as u32);
+        }
+        if header.crc {
+            let crc = frame_crc(&bytes[pos..side_info + header.side_info_len()]);
+            bytes[pos + 4..pos + 6].copy_from_slice(&crc.to_be_bytes());
+        }
+
+        frames += 1;
+        pos += header.len;
+    }
+
+    frames
+}
+
+/// Length of ID3v2 tag file starts with, 0 if it has none
+fn id3v2_len(bytes: &[u8]) -> usize {
+    if bytes.len() < 10 || &bytes[..3] != b"ID3" {
+        return 0;
+    }
+
+    let size = bytes[6..10]
+        .iter()
+        .fold(0, |size, byte| (size << 7) | (*byte as usize & 0x7f));
+    let footer = match bytes[5] & 0x10 {
+        0 => 0,
+        _ => 10,
+    };
+    10 + size + footer
+}
+
+/// MPEG audio layer III frame header
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct FrameHeader {
+    mpeg1: bool,
+    mono: bool,
+    /// Followed by a CRC-16 of header and side info
+    crc: bool,
+    /// Frame length in bytes, header included
+    len: usize,
+}
+
+impl FrameHeader {
+    fn parse(header: &[u8]) -> Option<Self> {
+        const MPEG1_BITRATES: [usize; 15] = [
+            0, 32, 40, 48, 56, 64, 80, 96, 112, 128, 160, 192, 224, 256, 320,
+        ];
+        const MPEG2_BITRATES: [usize; 15] =
+            [0, 8, 16, 24, 32, 40, 48, 56, 64, 80, 96, 112, 128, 144, 160];
+
+        if header[0] != 0xff || header[1] & 0xe0 != 0xe0 {
+            return None;
+        }
+        // layer III only
+        if (header[1] >> 1) & 0b11 != 0b01 {
+            return None;
+        }
+
+        let sample_rates = match (header[1] >> 3) & 0b11 {
+            0b11 => [44_100, 48_000, 32_000],
+            0b10 => [22_050, 24_000, 16_000],
+            0b00 => [11_025, 12_000, 8_000],
+            _ => return None,
+        };
+        let mpeg1 = (header[1] >> 3) & 0b11 == 0b11;
+        let bitrates = match mpeg1 {
+            true => MPEG1_BITRATES,
+            false => MPEG2_BITRATES,
+        };
+
+        // free format (0) and invalid bitrates aren't supported
+        let bitrate = *bitrates
+            .get((header[2] >> 4) as usize)
+            .filter(|rate| **rate > 0)?;
+        let sample_rate = *sample_rates.get(((header[2] >> 2) & 0b11) as usize)?;
+        let padding = ((header[2] >> 1) & 1) as usize;
+        let samples_per_byte = match mpeg1 {
+            true => 144,
+            false => 72,
+        };
+
+        Some(Self {
+            mpeg1,
+            mono: header[3] >> 6 == 0b11,
+            crc: header[1] & 1 == 0,
+            len: samples_per_byte * bitrate * 1000 / sample_rate + padding,
+        })
+    }
+
+    fn side_info_len(&self) -> usize {
+        match (self.mpeg1, self.mono) {
+            (true, true) => 17,
+            (true, false) => 32,
+            (false, true) => 9,
+            (false, false) => 17,
+        }
+    }
+
+    /// Bit offsets of global gains in side info, one per granule and channel
+    fn global_gain_offsets(&self) -> Vec<usize> {
+        let channels = if self.mono { 1 } else { 2 };
+        // main_data_begin, private bits and scfsi precede the granules
+        let (start, granules, granule_bits) = match (self.mpeg1, self.mono) {
+            (true, true) => (18, 2, 59),
+            (true, false) => (20, 2, 59),
+            (false, true) => (9, 1, 63),
+            (false, false) => (10, 1, 63),
+        };
+        // part2_3_length and big_values precede global gain
+        (0..granules * channels)
+            .map(|block| start + block * granule_bits + 21)
+            .collect()
+    }
+}
+
+fn read_bits(bytes: &[u8], bit: usize, len: usize) -> u32 {
+    (bit..bit + len).fold(0, |value, bit| {
+        (value << 1) | ((bytes[bit / 8] >> (7 - bit % 8)) & 1) as u32
+    })
+}
+
+fn write_bits(bytes: &mut [u8], bit: usize, len: usize, value: u32) {
+    for (i, bit) in (bit..bit + len).enumerate() {
+        let mask = 1 << (7 - bit % 8);
+        match (value >> (len - 1 - i)) & 1 {
+            1 => bytes[bit / 8] |= mask,
+            _ => bytes[bit / 8] &= !mask,
+        }
+    }
+}
+
+/// CRC-16 of frame, over the last two header bytes and the side info
+fn frame_crc(frame: &[u8]) -> u16 {
+    let bytes = frame[2..4].iter().chain(&frame[6..]);
+    bytes.fold(0xffff, |crc, byte| {
+        (0..8).fold(crc, |crc, bit| {
+            let carry = (crc >> 15) ^ ((*byte as u16 >> (7 - bit)) & 1);
+            match carry {
+                1 => (crc << 1) ^ 0x8005,
+                _ => crc << 1,
+            }
+        })
+    })
+}
+
+/// Check loudness of added sound, attenuating it in place if its guild says so. Returns where
+/// to submit it for review if it needs a moderator's approval, and a reply for the user if it's
+/// refused. Sounds that can't be analyzed are let through
+pub async fn check_upload(
+    data: &UserData,
+    sound: &ModeratedSound<'_>,
+) -> Result<Option<Flagged>, String> {
+    let action = sound
+        .guild_id
+        .and_then(|guild_id| {
+            data.guild_settings_table()
+                .get_guild_settings(guild_id)
+                .log_err()
+                .ok()
+        })
+        .and_then(|settings| settings.loudness_action)
+        .and_then(|code| LoudnessAction::from_code(&code))
+        .unwrap_or_default();
+    if action == LoudnessAction::Allow {
+        return Ok(None);
+    }
+
+    let path = sound.audio_file.to_path_buf();
+    let loudness = match tokio::task::spawn_blocking(move || analyze(path)).await {
+        Ok(Ok(loudness)) => loudness,
+        Ok(Err(_)) => return Ok(None),
+        Err(err) => {
+            log::error!("Loudness analysis task failed - {err}");
+            return Ok(None);
+        }
+    };
+
+    let ceiling_db = data.config().loudness_ceiling_db;
+    if !loudness.exceeds(ceiling_db) {
+        return Ok(None);
+    }
+    let reason = format!(
+        "too loud, {:.1}dB RMS, {:.1}dB peak, {:.1}% clipped",
+        loudness.rms_db,
+        loudness.peak_db,
+        loudness.clipped_ratio * 100.0
+    );
+    log::info!(
+        "Sound exceeds loudness ceiling. Name: {}, Guild Id: {:?}, Action: {action:?}, Reason: {reason}",
+        sound.name,
+        sound.guild_id
+    );
+
+    let lang = i18n::guild_language(data, sound.guild_id.map(GuildId::new), None);
+    let refused = |reason: &str| {
+        tr(
+            lang,
+            "loudness.refused",
+            &[("track", &sound.name), ("reason", &reason)],
+        )
+    };
+
+    match action {
+        LoudnessAction::Allow => Ok(None),
+        LoudnessAction::Attenuate => {
+            let path = sound.audio_file.to_path_buf();
+            let db = loudness.attenuation_db(ceiling_db);
+            match tokio::task::spawn_blocking(move || attenuate_mp3(path, db)).await {
+                Ok(Ok(_)) => Ok(None),
+                _ => Err(refused(&reason)),
+            }
+        }
+        LoudnessAction::Review => moderation::review_flagged(data, sound, reason).map(Some),
+        LoudnessAction::Reject => Err(refused(&reason)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// MPEG1 layer III frame, 128kbps 44.1kHz without padding, with global gains set to `gain`
+    fn mp3_frame(mono: bool, crc: bool, gain: u8) -> Vec<u8> {
+        let header = [
+            0xff,
+            if crc { 0xfa } else { 0xfb },
+            0x90,
+            if mono { 0xc0 } else { 0x00 },
+        ];
+        let frame_header = FrameHeader::parse(&header).unwrap();
+
+        let mut frame = vec![0; frame_header.len];
+        frame[..4].copy_from_slice(&header);
+        let side_info = 4 + if crc { 2 } else { 0 };
+        for offset in frame_header.global_gain_offsets() {
+            write_bits(&mut frame, side_info * 8 + offset, 8, gain as u32);
+        }
+        frame
+    }
+
+    fn global_gains(frame: &[u8]) -> Vec<u8> {
+        let header = FrameHeader::parse(&frame[..4]).unwrap();
+        let side_info = 4 + if header.crc { 2 } else { 0 };
+        header
+            .global_gain_offsets()
+            .into_iter()
+            .map(|offset| read_bits(frame, side_info * 8 + offset, 8) as u8)
+            .collect()
+    }
+
+    #[test]
+    fn loudness_test() {
+        let silence = Loudness::from_samples([0.0; 4]);
+        assert_eq!(f64::NEG_INFINITY, silence.rms_db);
+        assert!(!silence.exceeds(-10.0));
+
+        let quiet = Loudness::from_samples([0.1, -0.1, 0.1, -0.1]);
+        assert!((quiet.rms_db + 20.0).abs() < 0.01);
+        assert!((quiet.peak_db + 20.0).abs() < 0.01);
+        assert!(!quiet.exceeds(-10.0));
+
+        let loud = Loudness::from_samples([1.0, -1.0, 0.5, -0.5]);
+        assert_eq!(0.5, loud.clipped_ratio);
+        assert!(loud.exceeds(-10.0));
+        assert!((loud.attenuation_db(-10.0) - (loud.rms_db + 10.0)).abs() < 0.01);
+
+        // clipping under the ceiling
+        let clipping = Loudness {
+            peak_db: 0.0,
+            rms_db: -20.0,
+            clipped_ratio: 0.1,
+        };
+        assert!(clipping.exceeds(-10.0));
+        assert_eq!(
+            vars::LOUDNESS_CLIPPING_ATTENUATION_DB,
+            clipping.attenuation_db(-10.0)
+        );
+    }
+
+    #[test]
+    fn frame_header_test() {
+        let header = FrameHeader::parse(&[0xff, 0xfb, 0x90, 0x00]).unwrap();
+        assert!(header.mpeg1);
+        assert!(!header.mono);
+        assert!(!header.crc);
+        assert_eq!(417, header.len);
+
+        // padded MPEG2 mono 64kbps 22.05kHz
+        let header = FrameHeader::parse(&[0xff, 0xf3, 0x82, 0xc0]).unwrap();
+        assert!(!header.mpeg1);
+        assert!(header.mono);
+        assert_eq!(209, header.len);
+
+        // layer II, free format bitrate, not a frame
+        assert_eq!(None, FrameHeader::parse(&[0xff, 0xfd, 0x90, 0x00]));
+        assert_eq!(None, FrameHeader::parse(&[0xff, 0xfb, 0x00, 0x00]));
+        assert_eq!(None, FrameHeader::parse(b"TAG!"));
+    }
+
+    #[test]
+    fn attenuate_mp3_frames_test() {
+        let mut bytes = b"ID3\x04\x00\x00\x00\x00\x00\x02\xff\xfb".to_vec();
+        bytes.extend(mp3_frame(false, false, 150));
+        bytes.extend(mp3_frame(true, false, 3));
+        bytes.extend(b"TAG");
+
+        assert_eq!(2, attenuate_mp3_frames(&mut bytes, 4));
+        // ID3 tag content isn't mistaken for a frame
+        assert_eq!(&[0xff, 0xfb], &bytes[10..12]);
+        assert_eq!(vec![146; 4], global_gains(&bytes[12..]));
+        assert_eq!(vec![0; 2], global_gains(&bytes[12 + 417..]));
+    }
+
+    #[test]
+    fn attenuate_mp3_frames_crc_test() {
+        let mut frame = mp3_frame(true, true, 200);
+        assert_eq!(1, attenuate_mp3_frames(&mut frame, 10));
+
+        assert_eq!(vec![190; 2], global_gains(&frame));
+        let crc = frame_crc(&frame[..6 + 17]);
+        assert_eq!(crc.to_be_bytes(), [frame[4], frame[5]]);
+    }
+
+    #[test]
+    fn loudness_action_code_test() {
+        for action in LoudnessAction::ALL {
+            assert_eq!(Some(action), LoudnessAction::from_code(action.code()));
+        }
+        assert_eq!(None, LoudnessAction::from_code("mute"));
+    }
+}
@@ -1,16 +1,14 @@
 //#![allow(warnings)]
-use commands::PoiseResult;
-use common::LogResult;
-use db::{AudioTable, SettingsTable, Table};
-use env_logger;
 use log;
 use r2d2_sqlite::SqliteConnectionManager;
 use reqwest::Client as HttpClient;
 use serenity::all::{
-    ApplicationId, ComponentInteraction, ComponentInteractionDataKind, CreateInteractionResponse,
-    FullEvent, Interaction, VoiceState,
+    ApplicationId, ComponentInteraction, ComponentInteractionDataKind, FullEvent, Guild,
+    Interaction, UserId, VoiceState,
 };
 use serenity::client::Context;
+use std::sync::RwLock;
+use std::time::Duration;
 
 use serenity::{
     client::Client,
@@ -19,20 +17,29 @@ use serenity::{
 
 use songbird::SerenityInit;
 
-mod audio;
-mod commands;
-mod common;
-mod config;
-mod db;
-mod errors;
-mod helpers;
-mod vars;
-
-use crate::commands::PoiseError;
-use crate::common::UserData;
-use crate::config::Config;
-use crate::helpers::ButtonCustomId;
-use crate::helpers::SongbirdHelper;
+use soundboard_bot::cache::AutocompleteCache;
+use soundboard_bot::checksums::{self, ChecksumCache};
+use soundboard_bot::commands::{self, PoiseError, PoiseResult};
+use soundboard_bot::common::{LogResult, UserData, UserDataFields};
+use soundboard_bot::config::Config;
+use soundboard_bot::db::{
+    self, AudioTable, BlocklistTable, CategoriesTable, ChannelRestrictionsTable, CommandStatsTable,
+    FavoritesTable, GuildSettingsTable, JobsTable, NativeSoundsTable, PendingSoundsTable,
+    PermissionsTable, PlayEventsTable, SessionsTable, SettingsTable, ShareCodesTable,
+    SoundboardMessagesTable, SoundboardPagesTable, Table, UserPlaysTable,
+};
+use soundboard_bot::error_reporting::ErrorContext;
+use soundboard_bot::helpers::{self, PendingConfirmations, SongbirdHelper};
+use soundboard_bot::i18n;
+use soundboard_bot::native_soundboard::NativeSoundboardHandler;
+use soundboard_bot::now_playing::NowPlaying;
+use soundboard_bot::playback::{LastPlayed, PlaybackQueue};
+use soundboard_bot::rate_limit::{IngestLimiter, PlayRateLimiter};
+use soundboard_bot::{
+    alerts, audio, audio_watcher, backup, command_stats, diagnostics, digest, doctor,
+    error_reporting, http_client, ingest, interactions, jobs, moderation, onboarding, opus_cache,
+    presence, prune, read_only, self_check, sotd, stats, telemetry, temp_files, trash, vars,
+};
 
 type FrameworkContext<'a> = poise::FrameworkContext<'a, UserData, PoiseError>;
 
@@ -41,45 +48,100 @@ async fn main() -> anyhow::Result<()> {
     println!("Application starting...");
 
     let config = Config::new();
-    env_logger::init();
+    diagnostics::init_logger();
+    telemetry::init(&config)
+        .log_err_msg("Failed to start trace export")
+        .ok();
+    error_reporting::init(&config)
+        .log_err_msg("Failed to start error reporting")
+        .ok();
+    audio::set_storage_roots([config.audio_dir.clone()]);
 
     // framework configuration
     let token = config.token.clone();
-    let cmd_prefix = config.command_prefix.clone();
     let application_id = config.application_id;
     let sqlite_db_file = config.sqlite_db_file.clone();
-    let db_manager = SqliteConnectionManager::file(sqlite_db_file);
+    let sqlite_key = config.sqlite_key.clone();
+    let db_manager = SqliteConnectionManager::file(sqlite_db_file)
+        .with_flags(db::open_flags(config.read_only))
+        .with_init(move |conn| db::apply_encryption_key(conn, sqlite_key.as_deref()));
     let db_pool = r2d2::Pool::new(db_manager).expect("Failed to create sqlite connection pool");
+    let http_client = http_client::client(&config).expect("Failed to create HTTP client");
+    let framework_http_client = http_client.clone();
+
+    // `soundboard-bot doctor` runs the pre-flight checks instead of the bot
+    if std::env::args().nth(1).as_deref() == Some("doctor") {
+        let healthy = doctor::run_cli(&config, &db_pool).await;
+        std::process::exit(if healthy { 0 } else { 1 });
+    }
+
+    let native_soundboard = NativeSoundboardHandler::new(db_pool.clone());
+
+    // downloads left behind by ingests interrupted by a crash or restart
+    temp_files::sweep_stale_temp_files(
+        &temp_files::temp_files_dir(),
+        Duration::from_secs(vars::TEMP_FILE_MAX_AGE_SECS),
+        std::time::SystemTime::now(),
+    )
+    .log_err_msg("Failed to sweep stale temp files")
+    .ok();
+
+    let mut commands = commands::all_commands();
+    i18n::localize_commands(&mut commands);
 
     log::info!("Setting up framework...");
     let framework: poise::Framework<UserData, PoiseError> =
         poise::Framework::<UserData, PoiseError>::builder()
             .options(poise::FrameworkOptions {
                 prefix_options: poise::PrefixFrameworkOptions {
-                    prefix: Some(cmd_prefix),
+                    // per-guild prefix, see `/settings prefix`
+                    dynamic_prefix: Some(|ctx| {
+                        Box::pin(async move { Ok(Some(ctx.data.command_prefix(ctx.guild_id))) })
+                    }),
                     ..Default::default()
                 },
-                commands: vec![
-                    commands::echo(),
-                    commands::join(),
-                    commands::leave(),
-                    commands::sounds(),
-                    commands::play(),
-                    commands::scan(),
-                    commands::register(),
-                ],
+                commands,
+                owners: config
+                    .owner_ids
+                    .iter()
+                    .map(|owner_id| UserId::new(*owner_id))
+                    .collect(),
+                command_check: Some(|ctx| Box::pin(read_only::check_command(ctx))),
+                pre_command: |ctx| {
+                    Box::pin(async move {
+                        command_stats::start_command(ctx).await;
+                        helpers::defer_slow_command(ctx).await;
+                    })
+                },
+                post_command: |ctx| Box::pin(command_stats::record_command(ctx, false)),
                 event_handler: |ctx, event, framework, data| {
                     Box::pin(event_handler(ctx, event, framework, data))
                 },
+                on_error: |error| Box::pin(on_error(error)),
                 ..Default::default()
             })
             .setup(|_ctx, _ready, _framework| {
                 Box::pin(async move {
                     //poise::builtins::register_globally(ctx, &framework.options().commands).await?;
-                    Ok(UserData {
-                        config: config,
+                    Ok(UserData::from(UserDataFields {
+                        play_rate_limiter: RwLock::new(PlayRateLimiter::new(&config)),
+                        ingest_limiter: IngestLimiter::new(&config),
+                        pending_confirmations: PendingConfirmations::new(),
+                        now_playing: NowPlaying::new(),
+                        last_played: LastPlayed::new(),
+                        playback_queue: PlaybackQueue::new(),
+                        moderator: moderation::moderator_from_config(&config),
+                        http_client: framework_http_client,
+                        config: RwLock::new(config),
                         db_pool: db_pool,
-                    })
+                        autocomplete_cache: AutocompleteCache::new(Duration::from_secs(
+                            vars::AUTOCOMPLETE_CACHE_TTL_SECS,
+                        )),
+                        checksum_cache: ChecksumCache::new(Duration::from_secs(
+                            vars::CHECKSUM_CACHE_TTL_SECS,
+                        )),
+                        jobs: jobs::Jobs::new(),
+                    }))
                 })
             })
             .build();
@@ -94,8 +156,9 @@ async fn main() -> anyhow::Result<()> {
     let mut client = Client::builder(&token, intents)
         .application_id(ApplicationId::new(application_id))
         .framework(framework)
+        .raw_event_handler(native_soundboard)
         .register_songbird()
-        .type_map_insert::<HttpKey>(HttpClient::new())
+        .type_map_insert::<HttpKey>(http_client)
         .await
         .expect("Error creating client");
 
@@ -110,6 +173,8 @@ async fn main() -> anyhow::Result<()> {
 
     tokio::signal::ctrl_c().await.ok();
     log::info!("Received Ctrl-C, shutting down.");
+    telemetry::shutdown();
+    error_reporting::shutdown();
 
     Ok(())
 }
@@ -120,6 +185,45 @@ impl TypeMapKey for HttpKey {
     type Value = HttpClient;
 }
 
+/// Log command and event handler errors, so they're recorded for `/debug` and alerted (see
+/// `diagnostics::init_logger`), then handle them like poise does. Server-only commands used in
+/// DMs get a reply pointing at the commands that work there
+async fn on_error(error: poise::FrameworkError<'_, UserData, PoiseError>) {
+    match &error {
+        poise::FrameworkError::Command { error, ctx, .. } => {
+            error_reporting::with_context(&ErrorContext::from_command(*ctx), || {
+                log::error!("/{} failed - {error}", ctx.command().qualified_name)
+            });
+            command_stats::record_command(*ctx, true).await;
+        }
+        poise::FrameworkError::EventHandler { error, event, .. } => {
+            log::error!("{} event handler failed - {error}", event.snake_case_name());
+        }
+        poise::FrameworkError::GuildOnly { ctx, .. } => {
+            let command = &ctx.command().qualified_name;
+            log::info!("Refusing server-only command in DMs - {command}");
+
+            let prefix = ctx.data().command_prefix(None);
+            let content = helpers::guild_only_message(i18n::language(*ctx), &prefix, command);
+            helpers::poise_check_msg(
+                ctx.send(
+                    poise::CreateReply::default()
+                        .ephemeral(true)
+                        .content(content),
+                )
+                .await,
+            );
+            return;
+        }
+        _ => {}
+    }
+
+    poise::builtins::on_error(error)
+        .await
+        .log_err_msg("Failed to handle framework error")
+        .ok();
+}
+
 async fn event_handler(
     ctx: &Context,
     event: &FullEvent,
@@ -130,6 +234,9 @@ async fn event_handler(
         FullEvent::Ready { data_about_bot } => {
             handle_ready(ctx, data_about_bot, framework, data).await?;
         }
+        FullEvent::GuildCreate { guild, is_new } => {
+            handle_guild_create(ctx, guild, *is_new, framework, data).await?;
+        }
         FullEvent::InteractionCreate { interaction } => {
             handle_interaction_create(ctx, interaction, framework, data).await?;
         }
@@ -143,7 +250,7 @@ async fn event_handler(
 }
 
 async fn handle_ready(
-    _ctx: &Context,
+    ctx: &Context,
     ready: &serenity::model::gateway::Ready,
     _framework: FrameworkContext<'_>,
     data: &UserData,
@@ -163,8 +270,109 @@ async fn handle_ready(
         version = ready.version
     );
 
+    data.now_playing.set_http(ctx.http.clone());
+    alerts::spawn_alert_job(ctx.http.clone(), &data.config());
+
+    // mirrors can't write, the primary instance keeps the database up to date
+    if data.config().read_only {
+        log::info!("Running as read-only mirror");
+        presence::spawn_presence_job(
+            ctx.clone(),
+            data.db_pool.clone(),
+            data.config().status,
+            data.config().presence_interval,
+        );
+        return Ok(());
+    }
+
     AudioTable::new(data.db_connection()).create_table();
     SettingsTable::new(data.db_connection()).create_table();
+    FavoritesTable::new(data.db_connection()).create_table();
+    SoundboardMessagesTable::new(data.db_connection()).create_table();
+    SoundboardPagesTable::new(data.db_connection()).create_table();
+    UserPlaysTable::new(data.db_connection()).create_table();
+    PermissionsTable::new(data.db_connection()).create_table();
+    BlocklistTable::new(data.db_connection()).create_table();
+    GuildSettingsTable::new(data.db_connection()).create_table();
+    NativeSoundsTable::new(data.db_connection()).create_table();
+    ChannelRestrictionsTable::new(data.db_connection()).create_table();
+    PlayEventsTable::new(data.db_connection()).create_table();
+    PendingSoundsTable::new(data.db_connection()).create_table();
+    ShareCodesTable::new(data.db_connection()).create_table();
+    JobsTable::new(data.db_connection()).create_table();
+    SessionsTable::new(data.db_connection()).create_table();
+    CategoriesTable::new(data.db_connection()).create_table();
+    CommandStatsTable::new(data.db_connection()).create_table();
+    db::run_migrations(&data.db_connection())?;
+
+    // register audio files dropped into the audio dir while bot was offline
+    data.sync_audio_dir(|_| async {})
+        .await
+        .log_err_msg("Failed to sync audio dir on startup")
+        .ok();
+    if data.config().watch_audio_dir {
+        audio_watcher::spawn_audio_dir_watcher(data.clone());
+    }
+    opus_cache::spawn_warm_up(
+        data.clone(),
+        ready.guilds.iter().map(|guild| guild.id).collect(),
+    );
+
+    sotd::spawn_sotd_job(
+        ctx.clone(),
+        data.db_pool.clone(),
+        data.config().duck_announcements,
+    );
+    digest::spawn_digest_job(ctx.clone(), data.db_pool.clone());
+    prune::spawn_auto_archive_job(data.db_pool.clone());
+    ingest::spawn_metadata_backfill(data.db_pool.clone());
+    checksums::spawn_content_hash_backfill(data.db_pool.clone());
+    backup::spawn_backup_job(ctx.clone(), data.db_pool.clone(), data.config());
+    jobs::spawn_worker(ctx.http.clone(), data.clone());
+    stats::spawn_retention_job(
+        data.db_pool.clone(),
+        data.config().play_events_retention_days,
+    );
+    trash::spawn_purge_job(data.config().trash_dir, data.config().trash_retention_days);
+    presence::spawn_presence_job(
+        ctx.clone(),
+        data.db_pool.clone(),
+        data.config().status,
+        data.config().presence_interval,
+    );
+
+    Ok(())
+}
+
+async fn handle_guild_create(
+    ctx: &Context,
+    guild: &Guild,
+    is_new: Option<bool>,
+    framework: FrameworkContext<'_>,
+    data: &UserData,
+) -> PoiseResult {
+    // guild create is also received for every guild on connect
+    if is_new == Some(true) && !data.config().read_only {
+        log::info!("Joined guild. Name: {}, Guild Id: {}", guild.name, guild.id);
+        commands::register_guild_commands(&ctx.http, &framework.options().commands, guild.id)
+            .await
+            .ok();
+        onboarding::send_setup_message(&ctx.http, data, guild)
+            .await
+            .log_err_msg("Failed to send setup message")
+            .ok();
+    }
+
+    if !data.config().read_only {
+        let bot_id = ctx.cache.current_user().id;
+        self_check::check_guild(&ctx.http, data, guild, bot_id)
+            .await
+            .log_err_msg(format!(
+                "Failed to self-check guild. Guild Id: {}",
+                guild.id
+            ))
+            .ok();
+    }
 
     Ok(())
 }
@@ -206,13 +414,13 @@ async fn handle_voice_state_update(
 async fn handle_interaction_create(
     ctx: &Context,
     interaction: &Interaction,
-    framework: FrameworkContext<'_>,
+    _framework: FrameworkContext<'_>,
     data: &UserData,
 ) -> PoiseResult {
     //log::debug!("interaction create event - {interaction:?}");
     match interaction {
         Interaction::Component(component) => {
-            handle_component_interaction(ctx, interaction, component, framework, data).await?;
+            handle_component_interaction(ctx, component, data).await?;
         }
         _ => {}
     }
@@ -222,81 +430,18 @@ async fn handle_interaction_create(
 
 async fn handle_component_interaction(
     ctx: &Context,
-    interaction: &Interaction,
     component: &ComponentInteraction,
-    framework: FrameworkContext<'_>,
     data: &UserData,
 ) -> PoiseResult {
     log::info!("component interaction event");
     match component.data.kind {
-        ComponentInteractionDataKind::Button => {
-            handle_btn_interaction(ctx, interaction, component, framework, data).await?
+        // select menus share the button custom id format
+        ComponentInteractionDataKind::Button
+        | ComponentInteractionDataKind::StringSelect { .. } => {
+            interactions::handle_component(ctx, component, data).await?
         }
         _ => {}
     }
 
     Ok(())
 }
-
-async fn handle_btn_interaction(
-    ctx: &Context,
-    _interaction: &Interaction,
-    component: &ComponentInteraction,
-    _framework: FrameworkContext<'_>,
-    data: &UserData,
-) -> PoiseResult {
-    log::debug!("Interaction Component Button pressed");
-    let custom_id = &component.data.custom_id;
-
-    component
-        .create_response(&ctx.http, CreateInteractionResponse::Acknowledge)
-        .await
-        .log_err_msg("Failed to create response for btn interaction")
-        .ok();
-
-    match ButtonCustomId::try_from(custom_id.clone())? {
-        ButtonCustomId::PlayAudio(audio_track_id) => {
-            log::info!("Play Audio Button Pressed - '{custom_id}'");
-
-            let channel_id = component.channel_id;
-            let guild_id = component
-                .guild_id
-                .ok_or("ComponentInteraction.guild_id is None")
-                .log_err()?;
-
-            let table = data.audio_table();
-
-            match table.find_audio_row(db::UniqueAudioTableCol::Id(audio_track_id)) {
-                Some(audio_row) => {
-                    log::info!(
-                        "Found audio track. Name: {}, File: {}",
-                        audio_row.name,
-                        audio_row.audio_file.to_string_lossy()
-                    );
-
-                    let manager = helpers::songbird_get(&ctx).await;
-                    manager
-                        .play_audio(guild_id, channel_id, &audio_row.audio_file)
-                        .await
-                        .ok();
-                }
-                None => {
-                    return Err(format!(
-                        "Unable to locate audio track for button custom id"
-                    )
-                    .into())
-                    .log_err();
-                }
-            }
-        }
-        ButtonCustomId::Unknown(value) => {
-            return Err(format!(
-                "Unrecognized button custom_id for component interaction. Value={value}"
-            )
-            .into())
-            .log_err();
-        }
-    }
-
-    Ok(())
-}
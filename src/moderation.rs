@@ -0,0 +1,261 @@
+//! Content moderation of uploaded audio. Sounds added with `/sounds add` or `/sounds upload` are
+//! checked by the bot's `Moderator` before they're added. Flagged sounds (screaming, earrape,
+//! NSFW, ...) are sent to the guild's review channel (see `approval`) instead of the soundboard,
+//! and refused in guilds without one. The default moderator flags nothing, set
+//! `Config::moderation_url` to have an external service check uploads
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde::Deserialize;
+use serenity::all::GuildId;
+use serenity::async_trait;
+
+use crate::commands::PoiseError;
+use crate::common::{LogResult, UserData};
+use crate::config::Config;
+use crate::http_client;
+use crate::i18n::{self, tr};
+use crate::vars;
+
+/// Uploaded sound being moderated. Its audio file is validated, but not added yet
+#[derive(Debug, Clone)]
+pub struct ModeratedSound<'a> {
+    pub name: &'a str,
+    pub audio_file: &'a Path,
+    pub guild_id: Option<u64>,
+    pub duration_ms: u64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ModerationVerdict {
+    Allow,
+    /// Needs a moderator's approval, for the given reason
+    Flag(String),
+}
+
+/// Checks uploaded sounds before they're added
+#[async_trait]
+pub trait Moderator: Send + Sync {
+    async fn moderate(&self, _sound: &ModeratedSound<'_>) -> Result<ModerationVerdict, PoiseError> {
+        Ok(ModerationVerdict::Allow)
+    }
+}
+
+/// Allows every sound
+pub struct NoopModerator;
+
+impl Moderator for NoopModerator {}
+
+/// Posts the audio file to an external moderation service, which answers with JSON like
+/// `{"flagged": true, "reason": "earrape"}`. The sound's name, duration and guild are sent as
+/// `X-Sound-Name`, `X-Duration-Ms` and `X-Guild-Id` headers
+pub struct HttpModerator {
+    client: reqwest::Client,
+    url: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct HttpModeratorResponse {
+    flagged: bool,
+    #[serde(default)]
+    reason: Option<String>,
+}
+
+impl HttpModerator {
+    pub fn new(config: &Config, url: impl Into<String>) -> Result<Self, PoiseError> {
+        let client = http_client::client_builder(config)?
+            .timeout(Duration::from_secs(vars::MODERATION_TIMEOUT_SECS))
+            .build()?;
+
+        Ok(Self {
+            client,
+            url: url.into(),
+        })
+    }
+}
+
+#[async_trait]
+impl Moderator for HttpModerator {
+    async fn moderate(&self, sound: &ModeratedSound<'_>) -> Result<ModerationVerdict, PoiseError> {
+        let audio = tokio::fs::read(sound.audio_file).await?;
+
+        let mut request = self
+            .client
+            .post(&self.url)
+            .header(reqwest::header::CONTENT_TYPE, "audio/mpeg")
+            .header("X-Sound-Name", sound.name)
+            .header("X-Duration-Ms", sound.duration_ms)
+            .body(audio);
+        if let Some(guild_id) = sound.guild_id {
+            request = request.header("X-Guild-Id", guild_id);
+        }
+
+        let body = request
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())?
+            .bytes()
+            .await?;
+
+        Ok(parse_response(&body)?)
+    }
+}
+
+fn parse_response(body: &[u8]) -> Result<ModerationVerdict, serde_json::Error> {
+    let response: HttpModeratorResponse = serde_json::from_slice(body)?;
+
+    Ok(match response.flagged {
+        true => ModerationVerdict::Flag(
+            response
+                .reason
+                .filter(|reason| !reason.trim().is_empty())
+                .unwrap_or_else(|| "flagged".into()),
+        ),
+        false => ModerationVerdict::Allow,
+    })
+}
+
+/// Moderator of the config. Falls back to allowing every sound when the moderation service
+/// can't be set up
+pub fn moderator_from_config(config: &Config) -> Arc<dyn Moderator> {
+    let Some(url) = &config.moderation_url else {
+        return Arc::new(NoopModerator);
+    };
+
+    match HttpModerator::new(config, url).log_err_msg("Failed to set up moderation service") {
+        Ok(moderator) => {
+            log::info!("Moderating uploads with {url}");
+            Arc::new(moderator)
+        }
+        Err(_) => Arc::new(NoopModerator),
+    }
+}
+
+/// Flagged sound, to be submitted to the review channel
+#[derive(Debug, Clone, PartialEq)]
+pub struct Flagged {
+    pub review_channel_id: u64,
+    pub reason: String,
+}
+
+/// Moderate uploaded sound. Returns where to submit it for review if it was flagged, and a
+/// reply for the user if it was flagged in a guild without a review channel. Sounds are
+/// flagged when the moderator fails, so unchecked sounds aren't added
+pub async fn check_upload(
+    data: &UserData,
+    sound: &ModeratedSound<'_>,
+) -> Result<Option<Flagged>, String> {
+    let reason = match data.moderator.moderate(sound).await {
+        Ok(ModerationVerdict::Allow) => return Ok(None),
+        Ok(ModerationVerdict::Flag(reason)) => reason,
+        Err(err) => {
+            log::error!("Failed to moderate sound `{}` - {err}", sound.name);
+            "moderation unavailable".into()
+        }
+    };
+    log::info!(
+        "Sound flagged by moderation. Name: {}, Guild Id: {:?}, Reason: {reason}",
+        sound.name,
+        sound.guild_id
+    );
+
+    review_flagged(data, sound, reason).map(Some)
+}
+
+/// Review channel flagged sound is submitted to, or a reply for the user if its guild has none
+pub fn review_flagged(
+    data: &UserData,
+    sound: &ModeratedSound<'_>,
+    reason: String,
+) -> Result<Flagged, String> {
+    let review_channel_id = sound.guild_id.and_then(|guild_id| {
+        data.guild_settings_table()
+            .get_guild_settings(guild_id)
+            .log_err()
+            .ok()
+            .and_then(|settings| settings.review_channel_id)
+    });
+
+    match review_channel_id {
+        Some(review_channel_id) => Ok(Flagged {
+            review_channel_id,
+            reason,
+        }),
+        None => {
+            let lang = i18n::guild_language(data, sound.guild_id.map(GuildId::new), None);
+            Err(tr(
+                lang,
+                "moderation.refused",
+                &[("track", &sound.name), ("reason", &reason)],
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::TestDb;
+
+    use super::*;
+
+    struct FlagAll;
+
+    #[async_trait]
+    impl Moderator for FlagAll {
+        async fn moderate(&self, _: &ModeratedSound<'_>) -> Result<ModerationVerdict, PoiseError> {
+            Ok(ModerationVerdict::Flag("earrape".into()))
+        }
+    }
+
+    fn sound(guild_id: Option<u64>) -> ModeratedSound<'static> {
+        ModeratedSound {
+            name: "Scream",
+            audio_file: Path::new("scream.mp3"),
+            guild_id,
+            duration_ms: 1500,
+        }
+    }
+
+    #[test]
+    fn parse_response_test() {
+        assert_eq!(
+            ModerationVerdict::Allow,
+            parse_response(br#"{"flagged": false}"#).unwrap()
+        );
+        assert_eq!(
+            ModerationVerdict::Flag("nsfw".into()),
+            parse_response(br#"{"flagged": true, "reason": "nsfw"}"#).unwrap()
+        );
+        assert_eq!(
+            ModerationVerdict::Flag("flagged".into()),
+            parse_response(br#"{"flagged": true, "reason": " "}"#).unwrap()
+        );
+        assert!(parse_response(b"nope").is_err());
+    }
+
+    #[tokio::test]
+    async fn check_upload_test() {
+        let db = TestDb::new();
+        let data = db.user_data();
+        assert_eq!(Ok(None), check_upload(&data, &sound(Some(1))).await);
+
+        let data = db.user_data_with_moderator(Arc::new(FlagAll));
+        // no review channel to send it to
+        assert!(check_upload(&data, &sound(Some(1))).await.is_err());
+        assert!(check_upload(&data, &sound(None)).await.is_err());
+
+        let table = db.guild_settings_table();
+        let mut settings = table.get_guild_settings(1).unwrap();
+        settings.review_channel_id = Some(5);
+        table.update_guild_settings(&settings).unwrap();
+
+        assert_eq!(
+            Ok(Some(Flagged {
+                review_channel_id: 5,
+                reason: "earrape".into()
+            })),
+            check_upload(&data, &sound(Some(1))).await
+        );
+    }
+}
@@ -0,0 +1,223 @@
+//! Mirroring of Discord's native soundboard. Native sound plays arrive as
+//! `VOICE_CHANNEL_EFFECT_SEND` gateway events, which serenity doesn't model, so they're read
+//! from unknown raw events. With `/settings native-sounds record` enabled, plays of native
+//! sounds mapped to a bot sound (`/settings native-sounds map`) count as plays of the bot sound
+use serde::{Deserialize, Deserializer};
+use serenity::all::{ChannelId, Event, GuildId, RawEventHandler, UserId};
+use serenity::async_trait;
+use serenity::client::Context;
+
+use crate::commands::PoiseError;
+use crate::common::LogResult;
+use crate::db::{
+    self, AudioTable, GuildSettingsTable, NativeSoundsTable, PlayEventInsert, PlayEventsTable,
+    UserPlaysTable,
+};
+use crate::playback::PlaySource;
+
+pub const VOICE_CHANNEL_EFFECT_SEND: &str = "VOICE_CHANNEL_EFFECT_SEND";
+
+/// Native soundboard sound played in a voice channel
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct NativeSoundPlay {
+    pub guild_id: GuildId,
+    pub channel_id: ChannelId,
+    pub user_id: UserId,
+    /// None for voice channel effects that are only an emoji
+    #[serde(default, deserialize_with = "deserialize_sound_id")]
+    pub sound_id: Option<String>,
+}
+
+impl NativeSoundPlay {
+    /// Parse native sound play from unknown gateway event
+    pub fn parse(kind: &str, value: &serde_json::Value) -> Option<Self> {
+        if kind != VOICE_CHANNEL_EFFECT_SEND {
+            return None;
+        }
+
+        serde_json::from_value::<Self>(value.clone())
+            .log_err_msg("Failed to parse voice channel effect event")
+            .ok()
+            .filter(|play| play.sound_id.is_some())
+    }
+}
+
+/// Sound ids are snowflake strings, except default sounds which may be integers
+fn deserialize_sound_id<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value = Option::<serde_json::Value>::deserialize(deserializer)?;
+    Ok(match value {
+        Some(serde_json::Value::String(sound_id)) => Some(sound_id),
+        Some(serde_json::Value::Number(sound_id)) => Some(sound_id.to_string()),
+        _ => None,
+    })
+}
+
+/// Record native sound play as a play of its mapped bot sound, if the guild records native
+/// sounds. Returns the bot sound's audio table id when recorded
+pub fn record_native_sound_play(
+    db_pool: &r2d2::Pool<r2d2_sqlite::SqliteConnectionManager>,
+    play: &NativeSoundPlay,
+) -> Result<Option<i64>, PoiseError> {
+    let Some(ref sound_id) = play.sound_id else {
+        return Ok(None);
+    };
+    let guild_id = play.guild_id.get();
+    if db::is_read_only(&*db_pool.get()?) {
+        return Ok(None);
+    }
+
+    let settings = GuildSettingsTable::new(db_pool.get()?).get_guild_settings(guild_id)?;
+    if !settings.record_native_sounds {
+        return Ok(None);
+    }
+
+    let Some(audio_id) =
+        NativeSoundsTable::new(db_pool.get()?).mapped_audio_id(guild_id, sound_id)?
+    else {
+        log::debug!(
+            "Native sound not mapped to bot sound. Guild Id: {guild_id}, Sound Id: {sound_id}"
+        );
+        return Ok(None);
+    };
+
+    log::info!(
+        "Recording native sound play. Guild Id: {guild_id}, Sound Id: {sound_id}, Audio Id: {audio_id}"
+    );
+    AudioTable::new(db_pool.get()?).increment_play_count(audio_id)?;
+    UserPlaysTable::new(db_pool.get()?).increment_user_play_count(play.user_id.get(), audio_id)?;
+    PlayEventsTable::new(db_pool.get()?).insert_play_event(&PlayEventInsert {
+        audio_id,
+        user_id: play.user_id.get(),
+        guild_id: Some(guild_id),
+        source: PlaySource::Trigger.code().into(),
+    })?;
+
+    Ok(Some(audio_id))
+}
+
+/// Raw event handler recording native sound plays, alongside the poise framework
+pub struct NativeSoundboardHandler {
+    db_pool: r2d2::Pool<r2d2_sqlite::SqliteConnectionManager>,
+}
+
+impl NativeSoundboardHandler {
+    pub fn new(db_pool: r2d2::Pool<r2d2_sqlite::SqliteConnectionManager>) -> Self {
+        Self { db_pool }
+    }
+}
+
+#[async_trait]
+impl RawEventHandler for NativeSoundboardHandler {
+    async fn raw_event(&self, _ctx: Context, event: Event) {
+        let Event::Unknown(event) = event else {
+            return;
+        };
+
+        if let Some(play) = NativeSoundPlay::parse(&event.kind, &event.value) {
+            record_native_sound_play(&self.db_pool, &play)
+                .log_err_msg("Failed to record native sound play")
+                .ok();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::db::{NativeSoundsTableRow, UniqueAudioTableCol};
+    use crate::test_utils::{AudioRowFixture, TestDb};
+
+    use super::*;
+
+    fn native_sound_play(sound_id: &str) -> NativeSoundPlay {
+        NativeSoundPlay {
+            guild_id: GuildId::new(1),
+            channel_id: ChannelId::new(2),
+            user_id: UserId::new(3),
+            sound_id: Some(sound_id.into()),
+        }
+    }
+
+    #[test]
+    fn parse_test() {
+        let value = json!({
+            "guild_id": "1",
+            "channel_id": "2",
+            "user_id": "3",
+            "sound_id": "1234",
+            "sound_volume": 1.0,
+            "emoji": null
+        });
+        assert_eq!(
+            Some(native_sound_play("1234")),
+            NativeSoundPlay::parse(VOICE_CHANNEL_EFFECT_SEND, &value)
+        );
+        assert_eq!(None, NativeSoundPlay::parse("TYPING_START", &value));
+
+        let value = json!({"guild_id": "1", "channel_id": "2", "user_id": "3", "sound_id": 5});
+        assert_eq!(
+            Some(native_sound_play("5")),
+            NativeSoundPlay::parse(VOICE_CHANNEL_EFFECT_SEND, &value)
+        );
+
+        // emoji only effect
+        let value = json!({"guild_id": "1", "channel_id": "2", "user_id": "3", "emoji": {}});
+        assert_eq!(
+            None,
+            NativeSoundPlay::parse(VOICE_CHANNEL_EFFECT_SEND, &value)
+        );
+    }
+
+    #[test]
+    fn record_native_sound_play_test() {
+        let db = TestDb::new();
+        let data = db.user_data();
+        let row = AudioRowFixture::new().insert(&data.audio_table());
+        let play = native_sound_play("1234");
+
+        data.native_sounds_table()
+            .map_sound(
+                1,
+                &NativeSoundsTableRow {
+                    sound_id: "1234".into(),
+                    audio_id: row.id,
+                },
+            )
+            .unwrap();
+
+        // not recorded until guild enables recording
+        assert_eq!(
+            None,
+            record_native_sound_play(&data.db_pool, &play).unwrap()
+        );
+
+        let mut settings = data.guild_settings_table().get_guild_settings(1).unwrap();
+        settings.record_native_sounds = true;
+        data.guild_settings_table()
+            .update_guild_settings(&settings)
+            .unwrap();
+
+        assert_eq!(
+            Some(row.id),
+            record_native_sound_play(&data.db_pool, &play).unwrap()
+        );
+        assert_eq!(
+            None,
+            record_native_sound_play(&data.db_pool, &native_sound_play("5678")).unwrap()
+        );
+
+        let row = data
+            .audio_table()
+            .find_audio_row(UniqueAudioTableCol::Id(row.id))
+            .unwrap();
+        assert_eq!(1, row.play_count);
+        assert_eq!(
+            1,
+            data.user_plays_table().user_play_count(3, row.id).unwrap()
+        );
+    }
+}
@@ -0,0 +1,334 @@
+//! "Now playing" message posted in the text channel a sound was played from, with Stop, Replay,
+//! Repeat and Favorite buttons. The message is updated when another sound starts in the guild and
+//! deleted when the sound ends (see the playback hooks of `playback::play_audio_track`)
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+
+use serenity::all::{
+    ButtonStyle, ChannelId, CreateActionRow, CreateButton, CreateMessage, EditMessage, GuildId,
+    Http, MessageId,
+};
+
+use crate::commands::PoiseResult;
+use crate::common::LogResult;
+use crate::db::AudioTableRow;
+use crate::helpers::{ButtonAction, ButtonCustomId};
+use crate::i18n::{tr, Language};
+
+#[derive(Debug, Clone, PartialEq)]
+struct NowPlayingMessage {
+    play_id: u64,
+    channel_id: ChannelId,
+    /// None until the message is posted
+    message_id: Option<MessageId>,
+    /// Audio id of the sound, None until the message is posted
+    audio_id: Option<i64>,
+    /// Whether the sound is looping, shown by the Repeat button
+    looping: bool,
+}
+
+#[derive(Default)]
+struct NowPlayingInner {
+    /// Set once the bot is ready. Without it nothing is posted (i.e. in tests)
+    http: OnceLock<Arc<Http>>,
+    messages: Mutex<HashMap<GuildId, NowPlayingMessage>>,
+    next_play_id: AtomicU64,
+}
+
+/// Now playing message of each guild, keyed by a play id so messages of sounds that were
+/// replaced by another sound aren't deleted when the replaced sound ends
+#[derive(Default, Clone)]
+pub struct NowPlaying(Arc<NowPlayingInner>);
+
+impl NowPlaying {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_http(&self, http: Arc<Http>) {
+        self.0.http.set(http).ok();
+    }
+
+    /// Register a sound starting in guild, returning its play id. The guild's current now playing
+    /// message is taken over when it's in the same channel
+    pub fn start(&self, guild_id: GuildId, channel_id: ChannelId) -> u64 {
+        let play_id = self.0.next_play_id.fetch_add(1, Ordering::Relaxed);
+        let mut messages = self.0.messages.lock().unwrap();
+
+        let message_id = messages
+            .get(&guild_id)
+            .filter(|message| message.channel_id == channel_id)
+            .and_then(|message| message.message_id);
+
+        let replaced = messages.insert(
+            guild_id,
+            NowPlayingMessage {
+                play_id,
+                channel_id,
+                message_id,
+                audio_id: None,
+                looping: false,
+            },
+        );
+        drop(messages);
+
+        // now playing message left behind in another channel
+        if let Some(replaced) = replaced.filter(|replaced| replaced.channel_id != channel_id) {
+            self.delete_message(replaced);
+        }
+
+        play_id
+    }
+
+    /// Post now playing message for play id, or edit the message it took over
+    pub async fn announce(
+        &self,
+        guild_id: GuildId,
+        play_id: u64,
+        row: &AudioTableRow,
+        lang: Language,
+    ) -> PoiseResult {
+        let Some(http) = self.0.http.get() else {
+            return Ok(());
+        };
+
+        let Some(message) = self.current(guild_id, play_id) else {
+            // sound already ended
+            return Ok(());
+        };
+
+        let content = tr(lang, "now_playing.content", &[("track", &row.name)]);
+        let components = vec![Self::controls(guild_id, row.id, message.looping, lang)];
+
+        let message_id = match message.message_id {
+            Some(message_id) => {
+                let edit = EditMessage::new().content(content).components(components);
+                message
+                    .channel_id
+                    .edit_message(http, message_id, edit)
+                    .await?
+                    .id
+            }
+            None => {
+                let create = CreateMessage::new().content(content).components(components);
+                message.channel_id.send_message(http, create).await?.id
+            }
+        };
+
+        let mut messages = self.0.messages.lock().unwrap();
+        match messages.get_mut(&guild_id) {
+            Some(current) if current.play_id == play_id => {
+                current.message_id = Some(message_id);
+                current.audio_id = Some(row.id);
+            }
+            _ => {
+                // sound ended (or was replaced) while the message was being posted
+                drop(messages);
+                self.delete_message(NowPlayingMessage {
+                    message_id: Some(message_id),
+                    ..message
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Play id of the sound playing in guild
+    pub fn current_play_id(&self, guild_id: GuildId) -> Option<u64> {
+        self.0
+            .messages
+            .lock()
+            .unwrap()
+            .get(&guild_id)
+            .map(|message| message.play_id)
+    }
+
+    pub fn is_looping(&self, guild_id: GuildId) -> bool {
+        self.0
+            .messages
+            .lock()
+            .unwrap()
+            .get(&guild_id)
+            .is_some_and(|message| message.looping)
+    }
+
+    /// Record whether sound of play id is looping, unless another sound took over
+    pub fn set_looping(&self, guild_id: GuildId, play_id: u64, looping: bool) {
+        if let Some(message) = self.0.messages.lock().unwrap().get_mut(&guild_id) {
+            if message.play_id == play_id {
+                message.looping = looping;
+            }
+        }
+    }
+
+    /// Update buttons of guild's posted now playing message, i.e. after its sound is looped
+    pub async fn refresh(&self, guild_id: GuildId, lang: Language) -> PoiseResult {
+        let Some(http) = self.0.http.get() else {
+            return Ok(());
+        };
+
+        let message = self.0.messages.lock().unwrap().get(&guild_id).cloned();
+        let Some(NowPlayingMessage {
+            channel_id,
+            message_id: Some(message_id),
+            audio_id: Some(audio_id),
+            looping,
+            ..
+        }) = message
+        else {
+            return Ok(());
+        };
+
+        let edit =
+            EditMessage::new().components(vec![Self::controls(guild_id, audio_id, looping, lang)]);
+        channel_id.edit_message(http, message_id, edit).await?;
+
+        Ok(())
+    }
+
+    /// Number of guilds with a now playing message
+    pub fn message_count(&self) -> usize {
+        self.0.messages.lock().unwrap().len()
+    }
+
+    /// Forget and delete now playing message of play id, unless another sound took it over
+    pub fn finish(&self, guild_id: GuildId, play_id: u64) {
+        let mut messages = self.0.messages.lock().unwrap();
+        let finished = match messages.get(&guild_id) {
+            Some(message) if message.play_id == play_id => messages.remove(&guild_id),
+            _ => None,
+        };
+        drop(messages);
+
+        if let Some(finished) = finished {
+            self.delete_message(finished);
+        }
+    }
+
+    fn current(&self, guild_id: GuildId, play_id: u64) -> Option<NowPlayingMessage> {
+        self.0
+            .messages
+            .lock()
+            .unwrap()
+            .get(&guild_id)
+            .filter(|message| message.play_id == play_id)
+            .cloned()
+    }
+
+    fn controls(
+        guild_id: GuildId,
+        audio_id: i64,
+        looping: bool,
+        lang: Language,
+    ) -> CreateActionRow {
+        let button = |action: ButtonAction, label: String| {
+            CreateButton::new(ButtonCustomId::new(Some(guild_id), action))
+                .label(label)
+                .style(ButtonStyle::Secondary)
+        };
+
+        CreateActionRow::Buttons(vec![
+            button(ButtonAction::Stop, tr(lang, "now_playing.stop", &[]))
+                .style(ButtonStyle::Danger),
+            button(ButtonAction::Replay, tr(lang, "now_playing.replay", &[])),
+            match looping {
+                true => button(ButtonAction::Repeat, tr(lang, "now_playing.repeat_on", &[]))
+                    .style(ButtonStyle::Success),
+                false => button(ButtonAction::Repeat, tr(lang, "now_playing.repeat", &[])),
+            },
+            button(
+                ButtonAction::Favorite(audio_id),
+                tr(lang, "now_playing.favorite", &[]),
+            ),
+        ])
+    }
+
+    fn delete_message(&self, message: NowPlayingMessage) {
+        let (Some(http), Some(message_id)) = (self.0.http.get().cloned(), message.message_id)
+        else {
+            return;
+        };
+
+        tokio::spawn(async move {
+            message
+                .channel_id
+                .delete_message(&http, message_id)
+                .await
+                .log_err_msg("Failed to delete now playing message")
+                .ok();
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GUILD_ID: GuildId = GuildId::new(1);
+
+    #[test]
+    fn start_finish_test() {
+        let now_playing = NowPlaying::new();
+
+        let first = now_playing.start(GUILD_ID, ChannelId::new(2));
+        let second = now_playing.start(GUILD_ID, ChannelId::new(2));
+        assert_ne!(first, second);
+
+        // replaced sound ending leaves message of the playing sound alone
+        now_playing.finish(GUILD_ID, first);
+        assert!(now_playing.current(GUILD_ID, second).is_some());
+
+        now_playing.finish(GUILD_ID, second);
+        assert!(now_playing.current(GUILD_ID, second).is_none());
+    }
+
+    #[test]
+    fn start_takes_over_message_in_same_channel_test() {
+        let now_playing = NowPlaying::new();
+
+        let first = now_playing.start(GUILD_ID, ChannelId::new(2));
+        now_playing
+            .0
+            .messages
+            .lock()
+            .unwrap()
+            .get_mut(&GUILD_ID)
+            .unwrap()
+            .message_id = Some(MessageId::new(3));
+
+        let second = now_playing.start(GUILD_ID, ChannelId::new(2));
+        assert_ne!(first, second);
+        assert_eq!(
+            Some(MessageId::new(3)),
+            now_playing.current(GUILD_ID, second).unwrap().message_id
+        );
+
+        let third = now_playing.start(GUILD_ID, ChannelId::new(4));
+        assert_eq!(
+            None,
+            now_playing.current(GUILD_ID, third).unwrap().message_id
+        );
+    }
+
+    #[test]
+    fn set_looping_test() {
+        let now_playing = NowPlaying::new();
+        assert!(!now_playing.is_looping(GUILD_ID));
+
+        let first = now_playing.start(GUILD_ID, ChannelId::new(2));
+        now_playing.set_looping(GUILD_ID, first, true);
+        assert!(now_playing.is_looping(GUILD_ID));
+        assert_eq!(Some(first), now_playing.current_play_id(GUILD_ID));
+
+        // another sound taking over isn't looping
+        let second = now_playing.start(GUILD_ID, ChannelId::new(2));
+        assert!(!now_playing.is_looping(GUILD_ID));
+        now_playing.set_looping(GUILD_ID, first, true);
+        assert!(!now_playing.is_looping(GUILD_ID));
+
+        now_playing.finish(GUILD_ID, second);
+        assert_eq!(None, now_playing.current_play_id(GUILD_ID));
+    }
+}
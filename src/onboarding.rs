@@ -0,0 +1,326 @@
+//! Setup message posted when the bot joins a guild, with buttons for the first steps admins
+//! would otherwise have to find in the docs: creating a soundboard channel, setting basic
+//! limits and importing starter sounds (see `Config::starter_sounds_dir`)
+use serde::{Deserialize, Serialize};
+use serenity::all::{
+    ButtonStyle, ChannelType, CreateActionRow, CreateButton, CreateChannel, CreateMessage, Guild,
+    GuildId, Http,
+};
+
+use crate::audio::{AudioFile, AudioFileValidator};
+use crate::channel_restrictions::ChannelKind;
+use crate::commands::PoiseError;
+use crate::common::{self, LogResult, UserData};
+use crate::db::{AudioTableRowInsert, SoundName, Tags, UniqueAudioTableCol};
+use crate::helpers::{ButtonAction, ButtonCustomId};
+use crate::i18n::{self, tr, Language};
+use crate::{ingest, soundboard, vars};
+
+/// Setup step offered by a button on the setup message
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum OnboardingStep {
+    /// Create a soundboard text channel and publish the soundboard in it
+    #[serde(rename = "c")]
+    Channel,
+    /// Auto-archive unused sounds and restrict soundboard commands to the soundboard channel
+    #[serde(rename = "l")]
+    Limits,
+    /// Import sounds of the starter sounds dir
+    #[serde(rename = "s")]
+    StarterSounds,
+}
+
+/// Limits applied by `OnboardingStep::Limits`
+#[derive(Debug, Clone, PartialEq)]
+pub struct BasicLimits {
+    pub auto_archive_months: u32,
+    /// Channel soundboard commands were restricted to. None if no soundboard is published
+    pub channel_id: Option<u64>,
+}
+
+pub fn setup_message(lang: Language, guild_id: GuildId, starter_sounds: bool) -> CreateMessage {
+    let button = |step, key| {
+        CreateButton::new(ButtonCustomId::new(
+            Some(guild_id),
+            ButtonAction::Onboarding(step),
+        ))
+        .label(tr(lang, key, &[]))
+        .style(ButtonStyle::Primary)
+    };
+
+    let mut buttons = vec![
+        button(OnboardingStep::Channel, "onboarding.channel_button"),
+        button(OnboardingStep::Limits, "onboarding.limits_button"),
+    ];
+    if starter_sounds {
+        buttons.push(button(
+            OnboardingStep::StarterSounds,
+            "onboarding.starter_sounds_button",
+        ));
+    }
+
+    CreateMessage::new()
+        .content(tr(lang, "onboarding.welcome", &[]))
+        .components(vec![CreateActionRow::Buttons(buttons)])
+}
+
+/// Post setup message in the guild's system channel. Guilds without one aren't onboarded
+pub async fn send_setup_message(
+    http: &Http,
+    data: &UserData,
+    guild: &Guild,
+) -> Result<(), PoiseError> {
+    let Some(channel_id) = guild.system_channel_id else {
+        log::info!(
+            "Guild has no system channel, skipping setup message. Guild Id: {}",
+            guild.id
+        );
+        return Ok(());
+    };
+
+    log::info!(
+        "Sending setup message. Guild Id: {}, Channel Id: {channel_id}",
+        guild.id
+    );
+    let lang = i18n::guild_language(data, Some(guild.id), Some(&guild.preferred_locale));
+    let starter_sounds = data.config().starter_sounds_dir.is_some();
+
+    channel_id
+        .send_message(http, setup_message(lang, guild.id, starter_sounds))
+        .await?;
+
+    Ok(())
+}
+
+/// Run setup step, returning the reply to the admin who pressed its button
+pub async fn run_step(
+    http: &Http,
+    data: &UserData,
+    lang: Language,
+    guild_id: GuildId,
+    step: OnboardingStep,
+) -> Result<String, PoiseError> {
+    log::info!("Running setup step. Guild Id: {guild_id}, Step: {step:?}");
+
+    match step {
+        OnboardingStep::Channel => {
+            let channel = guild_id
+                .create_channel(
+                    http,
+                    CreateChannel::new(vars::ONBOARDING_CHANNEL_NAME).kind(ChannelType::Text),
+                )
+                .await?;
+            soundboard::publish_soundboard(http, data, guild_id, channel.id).await?;
+
+            Ok(tr(
+                lang,
+                "onboarding.channel_created",
+                &[("channel", &format!("<#{}>", channel.id))],
+            ))
+        }
+        OnboardingStep::Limits => {
+            let limits = apply_basic_limits(data, guild_id.get())?;
+            let mut reply = tr(
+                lang,
+                "settings.auto_archive_set",
+                &[("months", &limits.auto_archive_months)],
+            );
+            if let Some(channel_id) = limits.channel_id {
+                let restricted = tr(
+                    lang,
+                    "onboarding.commands_restricted",
+                    &[("channel", &format!("<#{channel_id}>"))],
+                );
+                reply = format!("{reply}\n{restricted}");
+            }
+
+            Ok(reply)
+        }
+        OnboardingStep::StarterSounds => {
+            let imported = import_starter_sounds(data, guild_id.get()).await?;
+            if imported > 0 {
+                soundboard::refresh_soundboard(http, data, guild_id)
+                    .await
+                    .log_err_msg("Failed to refresh published soundboard")
+                    .ok();
+            }
+
+            Ok(tr(
+                lang,
+                "onboarding.starter_sounds_imported",
+                &[("count", &imported)],
+            ))
+        }
+    }
+}
+
+/// Auto-archive sounds unused for `vars::PRUNE_DEFAULT_MONTHS`, unless the guild already has
+/// a policy, and restrict soundboard commands to the published soundboard's channel
+pub fn apply_basic_limits(data: &UserData, guild_id: u64) -> Result<BasicLimits, PoiseError> {
+    let table = data.guild_settings_table();
+    let mut settings = table.get_guild_settings(guild_id)?;
+    let auto_archive_months = *settings
+        .auto_archive_months
+        .get_or_insert(vars::PRUNE_DEFAULT_MONTHS);
+    table.update_guild_settings(&settings)?;
+
+    let channel_id = data
+        .soundboard_messages_table()
+        .find_soundboard_message(guild_id)?
+        .map(|message| message.channel_id);
+    if let Some(channel_id) = channel_id {
+        data.channel_restrictions_table()
+            .allow(guild_id, ChannelKind::Text.key(), channel_id)?;
+    }
+
+    Ok(BasicLimits {
+        auto_archive_months,
+        channel_id,
+    })
+}
+
+/// Copy valid audio files of the starter sounds dir to the audio dir and add them as sounds.
+/// Files already added (same name, or same duration and size) are skipped. Returns number of
+/// sounds imported
+pub async fn import_starter_sounds(data: &UserData, guild_id: u64) -> Result<usize, PoiseError> {
+    let config = data.config();
+    let Some(starter_sounds_dir) = config.starter_sounds_dir.filter(|dir| dir.is_dir()) else {
+        return Ok(0);
+    };
+
+    let audio_files: Vec<AudioFile> = common::read_audio_dir(&starter_sounds_dir)
+        .into_iter()
+        .collect();
+    let validator =
+        AudioFileValidator::new().max_audio_duration(data.max_audio_duration(Some(guild_id)));
+    let audio_files = ingest::validate_audio_files(
+        audio_files,
+        validator,
+        config.ingest_concurrency,
+        |_| async {},
+    )
+    .await;
+
+    let table = data.audio_table();
+    let mut imported = 0;
+    for (audio_file, track_info) in audio_files {
+        let Ok(name) = SoundName::new(audio_file.audio_title()) else {
+            continue;
+        };
+        let is_added = table
+            .find_audio_row(UniqueAudioTableCol::Name(name.to_string()))
+            .is_some()
+            || table
+                .find_duplicate_audio_row(track_info.duration_ms(), track_info.file_size)
+                .is_some();
+        let Some(file_name) = audio_file.file_name() else {
+            continue;
+        };
+        let dest_file = config.audio_dir.join(file_name);
+        if is_added || dest_file.exists() {
+            continue;
+        }
+
+        std::fs::copy(audio_file.as_path(), &dest_file)
+            .log_err_msg("Failed to copy starter sound to audio dir")?;
+        let dest_file = AudioFile::new(dest_file);
+
+        let inserted = table.insert_audio_row(AudioTableRowInsert {
+            name,
+            tags: Tags::from(vars::STARTER_SOUNDS_TAG),
+            audio_file: AudioFile::new(dest_file.as_path_buf()),
+            created_at: chrono::Utc::now(),
+            author_id: None,
+            author_name: None,
+            author_global_name: None,
+            author_hash: None,
+            guild_id: Some(guild_id),
+            duration_ms: Some(track_info.duration_ms()),
+            file_size: Some(track_info.file_size),
+            source_url: None,
+        });
+        match inserted {
+            Ok(()) => imported += 1,
+            Err(err) => {
+                log::error!("Failed to import starter sound - {err}");
+                dest_file.delete();
+            }
+        }
+    }
+
+    if imported > 0 {
+        data.autocomplete_cache.invalidate();
+    }
+
+    log::info!("Imported {imported} starter sounds. Guild Id: {guild_id}");
+    Ok(imported)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::Config;
+    use crate::db::SoundboardMessageRow;
+    use crate::test_utils::{TempDir, TestDb};
+
+    use super::*;
+
+    #[test]
+    fn apply_basic_limits_test() {
+        let db = TestDb::new();
+        let data = db.user_data();
+
+        // no published soundboard, commands aren't restricted
+        let limits = apply_basic_limits(&data, 1).unwrap();
+        assert_eq!(
+            BasicLimits {
+                auto_archive_months: vars::PRUNE_DEFAULT_MONTHS,
+                channel_id: None
+            },
+            limits
+        );
+        assert!(data
+            .channel_restrictions_table()
+            .channel_ids(1, ChannelKind::Text.key())
+            .unwrap()
+            .is_empty());
+
+        // existing auto-archive policy is kept
+        let settings_table = data.guild_settings_table();
+        let mut settings = settings_table.get_guild_settings(1).unwrap();
+        settings.auto_archive_months = Some(2);
+        settings_table.update_guild_settings(&settings).unwrap();
+        data.soundboard_messages_table()
+            .upsert_soundboard_message(&SoundboardMessageRow {
+                guild_id: 1,
+                channel_id: 10,
+                message_id: 100,
+            })
+            .unwrap();
+
+        let limits = apply_basic_limits(&data, 1).unwrap();
+        assert_eq!(2, limits.auto_archive_months);
+        assert_eq!(Some(10), limits.channel_id);
+        assert_eq!(
+            vec![10],
+            data.channel_restrictions_table()
+                .channel_ids(1, ChannelKind::Text.key())
+                .unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn import_starter_sounds_test() {
+        let db = TestDb::new();
+        let data = db.user_data();
+        assert_eq!(0, import_starter_sounds(&data, 1).await.unwrap());
+
+        // starter sounds that fail validation aren't imported
+        let dir = TempDir::new();
+        dir.audio_file("bruh.mp3");
+        let data = db.user_data_with_config(Config {
+            starter_sounds_dir: Some(dir.path().to_path_buf()),
+            ..Default::default()
+        });
+        assert_eq!(0, import_starter_sounds(&data, 1).await.unwrap());
+    }
+}
@@ -0,0 +1,218 @@
+//! Sounds transcoded to Opus in memory, so playing them skips decoding and re-encoding their
+//! audio file. The most played sounds are transcoded at startup, along with the guilds'
+//! autocomplete caches being filled, so the first interactions after a restart aren't slower
+//! than later ones (see `spawn_warm_up`)
+use std::collections::BTreeMap;
+use std::path;
+use std::sync::{Mutex, Once};
+use std::time::SystemTime;
+
+use serenity::all::GuildId;
+use songbird::driver::Bitrate;
+use songbird::input::cached::Compressed;
+use songbird::input::Input;
+
+use crate::cache::AutocompleteCache;
+use crate::commands::PoiseError;
+use crate::common::{LogResult, UserData};
+use crate::helpers;
+use crate::vars;
+
+struct CachedFile {
+    len: u64,
+    modified: Option<SystemTime>,
+    compressed: Compressed,
+}
+
+/// Transcoded audio files, keyed by path. Entries are dropped once their file changes
+pub struct OpusCache {
+    files: Mutex<BTreeMap<path::PathBuf, CachedFile>>,
+}
+
+/// Audio files transcoded by the warm-up, used by `SongbirdHelper` implementations
+pub static OPUS_CACHE: OpusCache = OpusCache::new();
+
+impl OpusCache {
+    pub const fn new() -> Self {
+        Self {
+            files: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    /// Transcoded audio of file, None if it isn't cached or changed since it was transcoded
+    pub fn get(&self, path: &path::Path) -> Option<Compressed> {
+        let mut files = self.files.lock().expect("OpusCache lock poisoned");
+        let file = files.get(path)?;
+
+        let metadata = std::fs::metadata(path).ok();
+        let unchanged = metadata.is_some_and(|metadata| {
+            file.len == metadata.len() && file.modified == metadata.modified().ok()
+        });
+        if !unchanged {
+            log::debug!(
+                "Dropping stale opus cache entry - {}",
+                path.to_string_lossy()
+            );
+            files.remove(path);
+            return None;
+        }
+
+        Some(file.compressed.new_handle())
+    }
+
+    /// Transcode audio file and cache it. Transcoding finishes in the background, plays before
+    /// then stream what's transcoded so far
+    pub async fn insert(&self, path: &path::Path) -> Result<(), PoiseError> {
+        let metadata = std::fs::metadata(path)?;
+        let input = songbird::input::File::new(path::PathBuf::from(path)).into();
+        let compressed =
+            Compressed::new(input, Bitrate::BitsPerSecond(vars::OPUS_CACHE_BITRATE)).await?;
+        let _ = compressed.raw.spawn_loader();
+
+        let mut files = self.files.lock().expect("OpusCache lock poisoned");
+        files.insert(
+            path.to_path_buf(),
+            CachedFile {
+                len: metadata.len(),
+                modified: metadata.modified().ok(),
+                compressed,
+            },
+        );
+        Ok(())
+    }
+
+    pub fn entry_count(&self) -> usize {
+        self.files.lock().expect("OpusCache lock poisoned").len()
+    }
+}
+
+impl Default for OpusCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Songbird input of audio file, its transcoded audio if cached
+pub fn audio_input(path: &path::Path) -> Input {
+    match OPUS_CACHE.get(path) {
+        Some(compressed) => {
+            log::debug!("Opus cache hit - {}", path.to_string_lossy());
+            compressed.into()
+        }
+        None => songbird::input::File::new(path::PathBuf::from(path)).into(),
+    }
+}
+
+/// Fill guilds' autocomplete caches with suggestions for the most used tags, the queries
+/// typed most. Tags shorter than a guild's minimum query length aren't searched there.
+/// Returns number of entries cached
+pub fn warm_autocomplete(data: &UserData, guild_ids: &[GuildId]) -> Result<usize, PoiseError> {
+    let tags = data
+        .audio_table()
+        .tag_counts(vars::WARM_UP_AUTOCOMPLETE_TAGS_LIMIT)?;
+    let mut cached = 0;
+
+    for guild_id in guild_ids {
+        let (limit, min_query_len) = helpers::autocomplete_settings(data, Some(*guild_id));
+        for (tag, _) in &tags {
+            if tag.chars().count() < min_query_len {
+                continue;
+            }
+
+            let tracks = data
+                .audio_table()
+                .autocomplete_tracks(tag, None, limit, min_query_len);
+            let key = AutocompleteCache::key(tag, None, min_query_len);
+            data.autocomplete_cache.insert(*guild_id, key, tracks);
+            cached += 1;
+        }
+    }
+
+    Ok(cached)
+}
+
+/// Transcode the most played sounds into the opus cache. Returns number of sounds cached
+pub async fn warm_opus_cache(data: &UserData) -> Result<usize, PoiseError> {
+    let rows = data
+        .audio_table()
+        .most_played_audio_rows(vars::WARM_UP_OPUS_CACHE_LIMIT)?;
+    let mut cached = 0;
+
+    for row in rows {
+        if OPUS_CACHE
+            .insert(row.audio_file.as_path())
+            .await
+            .log_err_msg(format!(
+                "Failed to transcode `{}` into opus cache",
+                row.name
+            ))
+            .is_ok()
+        {
+            cached += 1;
+        }
+    }
+
+    Ok(cached)
+}
+
+/// Start warming up caches in the background. Only the first call starts it, so it's safe to
+/// call on every ready event
+pub fn spawn_warm_up(data: UserData, guild_ids: Vec<GuildId>) {
+    static STARTED: Once = Once::new();
+
+    STARTED.call_once(|| {
+        log::info!("Starting cache warm-up");
+        tokio::spawn(async move {
+            if let Ok(cached) =
+                warm_autocomplete(&data, &guild_ids).log_err_msg("Failed to warm up autocomplete")
+            {
+                log::info!("Autocomplete warm-up complete - cached {cached} entries");
+            }
+
+            if let Ok(cached) = warm_opus_cache(&data)
+                .await
+                .log_err_msg("Failed to warm up opus cache")
+            {
+                log::info!("Opus cache warm-up complete - transcoded {cached} sounds");
+            }
+        });
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::{AudioRowFixture, TestDb};
+
+    use super::*;
+
+    #[test]
+    fn opus_cache_miss_test() {
+        let cache = OpusCache::new();
+        assert!(cache.get(path::Path::new("/missing/bruh.mp3")).is_none());
+        assert_eq!(0, cache.entry_count());
+    }
+
+    #[test]
+    fn warm_autocomplete_test() {
+        let db = TestDb::new();
+        let data = db.user_data();
+        let table = data.audio_table();
+        AudioRowFixture::new()
+            .name("Beep Boop")
+            .tags("droid r2")
+            .insert(&table);
+        AudioRowFixture::new()
+            .name("Beep Bop")
+            .tags("droid")
+            .insert(&table);
+
+        let guild_ids = [GuildId::new(1), GuildId::new(2)];
+        // "r2" is shorter than the default minimum query length
+        assert_eq!(2, warm_autocomplete(&data, &guild_ids).unwrap());
+
+        let key =
+            AutocompleteCache::key("droid", Some(1), vars::AUTOCOMPLETE_MIN_QUERY_LEN as usize);
+        let tracks = data.autocomplete_cache.get(GuildId::new(2), &key).unwrap();
+        assert_eq!(2, tracks.len());
+    }
+}
@@ -0,0 +1,422 @@
+//! Helpers for owner-only maintenance commands (see `commands::owner`)
+use rusqlite::types::ValueRef;
+
+use crate::checksums;
+use crate::commands::PoiseError;
+use crate::common::{LogResult, UserData};
+use crate::db::{AudioTableRow, DbConnection};
+use crate::packs::file_sha256;
+use crate::trash;
+
+/// Column names and rows (as text) of a query result
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct QueryResult {
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+    /// More rows than `limit` were returned
+    pub truncated: bool,
+}
+
+impl QueryResult {
+    /// Render as a markdown code block of `|` separated columns
+    pub fn to_markdown(&self) -> String {
+        let mut lines = vec![self.columns.join(" | ")];
+        lines.extend(self.rows.iter().map(|row| row.join(" | ")));
+        if self.truncated {
+            lines.push("...".into());
+        }
+
+        format!("```\n{}\n```", lines.join("\n"))
+    }
+}
+
+/// Leading keywords of statements rejected even though `Statement::readonly` allows them.
+/// Attaching creates database files, transactions would be left open on the pooled connection
+/// and pragmas can turn `query_only` off. `pragma_*` table-valued functions still work
+const REJECTED_KEYWORDS: [&str; 9] = [
+    "ATTACH",
+    "DETACH",
+    "BEGIN",
+    "COMMIT",
+    "END",
+    "ROLLBACK",
+    "SAVEPOINT",
+    "RELEASE",
+    "PRAGMA",
+];
+
+/// Run a query that doesn't write to the database, returning at most `limit` rows. The
+/// connection is `query_only` while it runs
+pub fn read_only_query(
+    conn: &DbConnection,
+    sql: &str,
+    limit: usize,
+) -> Result<QueryResult, PoiseError> {
+    let keyword = leading_keyword(sql);
+    if REJECTED_KEYWORDS.contains(&keyword.as_str()) {
+        return Err(format!("{keyword} statements aren't allowed").into());
+    }
+
+    conn.pragma_update(None, "query_only", true)
+        .log_err_msg("Failed to make owner sql connection query only")?;
+    let result = query_rows(conn, sql, limit);
+    conn.pragma_update(None, "query_only", false)
+        .log_err_msg("Failed to reset owner sql connection to writable")?;
+
+    result
+}
+
+fn query_rows(conn: &DbConnection, sql: &str, limit: usize) -> Result<QueryResult, PoiseError> {
+    let mut stmt = conn.prepare(sql).log_err_msg("Owner sql query invalid")?;
+    if !stmt.readonly() {
+        return Err("Only read-only queries are allowed".into());
+    }
+
+    let columns: Vec<String> = stmt.column_names().into_iter().map(String::from).collect();
+    let column_count = columns.len();
+    let mut rows = stmt.query(()).log_err_msg("Owner sql query error")?;
+
+    let mut result = QueryResult {
+        columns,
+        ..Default::default()
+    };
+
+    while let Some(row) = rows.next()? {
+        if result.rows.len() >= limit {
+            result.truncated = true;
+            break;
+        }
+
+        let values = (0..column_count)
+            .map(|index| row.get_ref(index).map(value_to_string))
+            .collect::<Result<_, _>>()?;
+        result.rows.push(values);
+    }
+
+    Ok(result)
+}
+
+/// First keyword of sql, uppercased, skipping leading whitespace and comments
+fn leading_keyword(sql: &str) -> String {
+    let mut sql = sql;
+    loop {
+        sql = sql.trim_start();
+        if let Some(comment) = sql.strip_prefix("--") {
+            sql = comment.split_once('\n').map_or("", |(_, rest)| rest);
+        } else if let Some(comment) = sql.strip_prefix("/*") {
+            sql = comment.split_once("*/").map_or("", |(_, rest)| rest);
+        } else {
+            break;
+        }
+    }
+
+    sql.chars()
+        .take_while(|c| c.is_ascii_alphabetic())
+        .collect::<String>()
+        .to_ascii_uppercase()
+}
+
+fn value_to_string(value: ValueRef<'_>) -> String {
+    match value {
+        ValueRef::Null => "NULL".into(),
+        ValueRef::Integer(value) => value.to_string(),
+        ValueRef::Real(value) => value.to_string(),
+        ValueRef::Text(value) => String::from_utf8_lossy(value).into(),
+        ValueRef::Blob(value) => format!("<{} bytes>", value.len()),
+    }
+}
+
+/// Shortest hex query `/owner search` matches content hashes by
+const HASH_PREFIX_MIN_LEN: usize = 8;
+
+/// Lowercase hex of query, if it's long enough to search content hashes by
+pub fn hash_prefix(query: &str) -> Option<String> {
+    let query = query.trim();
+    let is_hex = query.len() >= HASH_PREFIX_MIN_LEN && query.chars().all(|c| c.is_ascii_hexdigit());
+    is_hex.then(|| query.to_ascii_lowercase())
+}
+
+/// Lowercase hex SHA-256, if value is one
+pub fn parse_content_hash(value: &str) -> Option<String> {
+    hash_prefix(value).filter(|hash| hash.len() == 64)
+}
+
+/// `/owner search` result line of sound, with its guild's name if known
+pub fn search_result_line(row: &AudioTableRow, guild_name: Option<&str>) -> String {
+    let archived = match row.archived_at {
+        Some(_) => " (archived)",
+        None => "",
+    };
+    let guild = match (row.guild_id, guild_name) {
+        (Some(guild_id), Some(guild_name)) => format!("{guild_name} (`{guild_id}`)"),
+        (Some(guild_id), None) => format!("`{guild_id}`"),
+        (None, _) => "-".into(),
+    };
+    let uploader = match (&row.author_id, &row.author_hash) {
+        (Some(author_id), _) => {
+            let name = row
+                .author_global_name
+                .as_ref()
+                .or(row.author_name.as_ref())
+                .map(|name| format!("{name} "))
+                .unwrap_or_default();
+            format!("{name}(`{author_id}`)")
+        }
+        (None, Some(_)) => "anonymous".into(),
+        (None, None) => "-".into(),
+    };
+    let hash = row.content_hash.as_deref().unwrap_or("-");
+
+    format!(
+        "- #{} **{}**{archived} - server {guild} - by {uploader} - `{hash}`",
+        row.id, row.name
+    )
+}
+
+/// Sounds and trashed sounds deleted by `purge_by_hash`
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct HashPurgeSummary {
+    pub sounds: usize,
+    pub trashed: usize,
+}
+
+/// Delete every sound whose audio file has content hash, in all guilds, with their audio files
+/// (quarantined ones included). Trashed sounds of the same audio are deleted too, so they can't
+/// be restored
+pub fn purge_by_hash(data: &UserData, hash: &str) -> Result<HashPurgeSummary, PoiseError> {
+    let config = data.config();
+    let table = data.audio_table();
+    let mut summary = HashPurgeSummary::default();
+
+    for row in table.audio_rows_by_content_hash(hash)? {
+        log::warn!(
+            "Owner purging sound by hash. Name: {}, Guild Id: {:?}",
+            row.name,
+            row.guild_id
+        );
+        table.remove_audio_row(row.id)?;
+        summary.sounds += 1;
+
+        let quarantined_file = checksums::quarantined_file(&config.quarantine_dir, &row);
+        for file in [row.audio_file.as_path_buf(), quarantined_file] {
+            if file.is_file() {
+                std::fs::remove_file(&file)
+                    .log_err_msg(format!(
+                        "Failed to delete purged audio file {}",
+                        file.to_string_lossy()
+                    ))
+                    .ok();
+            }
+        }
+    }
+
+    for entry in trash::trashed_sounds(&config.trash_dir) {
+        let audio_file = entry.audio_file();
+        let matches = audio_file.is_file()
+            && file_sha256(&audio_file).is_ok_and(|file_hash| file_hash.eq_ignore_ascii_case(hash));
+        if !matches {
+            continue;
+        }
+
+        log::warn!(
+            "Owner purging trashed sound by hash. Name: {}",
+            entry.sound.name
+        );
+        if std::fs::remove_dir_all(&entry.dir)
+            .log_err_msg("Failed to purge trashed sound")
+            .is_ok()
+        {
+            summary.trashed += 1;
+        }
+    }
+
+    if summary.sounds > 0 {
+        data.autocomplete_cache.invalidate();
+    }
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::Config;
+    use crate::db::UniqueAudioTableCol;
+    use crate::test_utils::{AudioRowFixture, TempDir, TestDb};
+
+    use super::*;
+
+    #[test]
+    fn read_only_query_test() {
+        let db = TestDb::new();
+        AudioRowFixture::new()
+            .name("Beep")
+            .insert(&db.audio_table());
+        AudioRowFixture::new()
+            .name("Boop")
+            .insert(&db.audio_table());
+        let conn = db.connection();
+
+        let result =
+            read_only_query(&conn, "SELECT name, play_count FROM audio ORDER BY name", 1).unwrap();
+        assert_eq!(
+            QueryResult {
+                columns: vec!["name".into(), "play_count".into()],
+                rows: vec![vec!["Beep".into(), "0".into()]],
+                truncated: true,
+            },
+            result
+        );
+        assert_eq!(
+            "```\nname | play_count\nBeep | 0\n...\n```",
+            result.to_markdown()
+        );
+
+        assert!(read_only_query(&conn, "DELETE FROM audio", 10).is_err());
+        assert!(read_only_query(&conn, "DROP TABLE audio", 10).is_err());
+        assert_eq!(2, db.audio_table().count_audio_rows(None).unwrap());
+
+        // connection is writable again for whoever gets it from the pool next
+        AudioRowFixture::new().insert(&db.audio_table());
+        assert_eq!(3, db.audio_table().count_audio_rows(None).unwrap());
+    }
+
+    #[test]
+    fn read_only_query_rejected_test() {
+        let db = TestDb::new();
+        let conn = db.connection();
+        let dir = TempDir::new();
+        let attached = dir.path().join("attached.db3");
+
+        let attach = format!("ATTACH DATABASE '{}' AS x", attached.to_string_lossy());
+        assert!(read_only_query(&conn, &attach, 10).is_err());
+        assert!(!attached.exists());
+        assert!(read_only_query(&conn, "DETACH DATABASE main", 10).is_err());
+
+        for sql in [
+            "BEGIN",
+            "  begin transaction",
+            "-- comment\n/* comment */ BEGIN",
+            "SAVEPOINT a",
+            "COMMIT",
+            "PRAGMA query_only = OFF",
+        ] {
+            assert!(read_only_query(&conn, sql, 10).is_err(), "{sql}");
+        }
+        assert!(conn.is_autocommit());
+
+        let result = read_only_query(
+            &conn,
+            "SELECT name FROM pragma_table_info('audio') WHERE name = 'name'",
+            10,
+        )
+        .unwrap();
+        assert_eq!(vec![vec!["name".to_string()]], result.rows);
+    }
+
+    #[test]
+    fn leading_keyword_test() {
+        assert_eq!("SELECT", leading_keyword(" select 1"));
+        assert_eq!("BEGIN", leading_keyword("--x\n /* y */begin"));
+        assert_eq!("", leading_keyword("/* unterminated"));
+    }
+
+    #[test]
+    fn hash_prefix_test() {
+        assert_eq!(Some("abcdef01".into()), hash_prefix(" ABCDEF01 "));
+        assert_eq!(None, hash_prefix("abcdef0"));
+        assert_eq!(None, hash_prefix("bruh moment"));
+
+        let hash = "a".repeat(64);
+        assert_eq!(Some(hash.clone()), parse_content_hash(&hash));
+        assert_eq!(None, parse_content_hash("abcdef01"));
+    }
+
+    #[test]
+    fn search_result_line_test() {
+        let db = TestDb::new();
+        let table = db.audio_table();
+        let row = AudioRowFixture::new()
+            .name("Beep")
+            .author_id(7)
+            .guild_id(1)
+            .insert(&table);
+        assert_eq!(
+            format!(
+                "- #{} **Beep** - server Droids (`1`) - by (`7`) - `-`",
+                row.id
+            ),
+            search_result_line(&row, Some("Droids"))
+        );
+
+        let row = AudioRowFixture::new()
+            .name("Boop")
+            .author_hash("salted")
+            .insert(&table);
+        table.set_archived(row.id, true).unwrap();
+        table.set_content_hash(row.id, "ab").unwrap();
+        let row = table
+            .find_audio_row(UniqueAudioTableCol::Id(row.id))
+            .unwrap();
+        assert_eq!(
+            format!(
+                "- #{} **Boop** (archived) - server - - by anonymous - `ab`",
+                row.id
+            ),
+            search_result_line(&row, None)
+        );
+    }
+
+    #[test]
+    fn purge_by_hash_test() {
+        let audio_dir = TempDir::new();
+        let trash_dir = TempDir::new();
+        let quarantine_dir = TempDir::new();
+        let db = TestDb::new();
+        let data = db.user_data_with_config(Config {
+            trash_dir: trash_dir.path().to_path_buf(),
+            quarantine_dir: quarantine_dir.path().to_path_buf(),
+            ..Default::default()
+        });
+        let table = data.audio_table();
+
+        let mut rows = vec![];
+        for (file_name, content) in [("a.mp3", "bruh"), ("b.mp3", "bruh"), ("c.mp3", "beep")] {
+            let audio_file = audio_dir.audio_file(file_name);
+            std::fs::write(audio_file.as_path(), content).unwrap();
+            let row = AudioRowFixture::new().insert(&table);
+            table.replace_audio_file(row.id, &audio_file, 0, 0).unwrap();
+            let hash = file_sha256(audio_file.as_path()).unwrap();
+            table.set_content_hash(row.id, &hash).unwrap();
+            rows.push(
+                table
+                    .find_audio_row(UniqueAudioTableCol::Id(row.id))
+                    .unwrap(),
+            );
+        }
+        let hash = rows[0].content_hash.clone().unwrap();
+
+        // trashed copy of the same audio
+        let trashed = AudioRowFixture::new().insert(&table);
+        let trashed_file = audio_dir.audio_file("d.mp3");
+        std::fs::write(trashed_file.as_path(), "bruh").unwrap();
+        table
+            .replace_audio_file(trashed.id, &trashed_file, 0, 0)
+            .unwrap();
+        let trashed = table
+            .find_audio_row(UniqueAudioTableCol::Id(trashed.id))
+            .unwrap();
+        trash::trash_audio_row(&table, trash_dir.path(), &trashed, chrono::Utc::now()).unwrap();
+
+        assert_eq!(
+            HashPurgeSummary {
+                sounds: 2,
+                trashed: 1
+            },
+            purge_by_hash(&data, &hash).unwrap()
+        );
+        assert!(!rows[0].audio_file.exists());
+        assert!(!rows[1].audio_file.exists());
+        assert!(rows[2].audio_file.exists());
+        assert_eq!(1, table.count_audio_rows(None).unwrap());
+        assert!(trash::trashed_sounds(trash_dir.path()).is_empty());
+    }
+}
@@ -0,0 +1,418 @@
+//! Curated sound packs of a public pack repository (see `Config::packs_index_url`). The
+//! repository's index is a JSON manifest listing its packs, each a zip of mp3 files along with
+//! the zip's SHA-256 hash. Installing a pack downloads its zip, verifies its hash and adds its
+//! valid sounds to the guild, tagged with the pack's id
+use std::collections::HashMap;
+use std::future::Future;
+use std::io::Read;
+use std::path;
+
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+
+use crate::audio::{self, AudioFile, AudioFileValidator, DownloadProgress};
+use crate::commands::PoiseError;
+use crate::common::{LogResult, UserData};
+use crate::db::{AudioTableRowInsert, SoundName, Tags, UniqueAudioTableCol};
+use crate::i18n::{tr, Language};
+use crate::ingest::{self, IngestProgress};
+use crate::temp_files::TempFileGuard;
+use crate::vars;
+
+/// Index of a pack repository, served at `Config::packs_index_url`
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct PackIndex {
+    pub packs: Vec<Pack>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Pack {
+    /// Short unique id, also the tag of the pack's sounds
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    /// Zip of the pack's mp3 files. Relative urls are relative to the index url
+    pub url: String,
+    /// Hex encoded SHA-256 of the zip
+    pub sha256: String,
+}
+
+impl PackIndex {
+    pub fn parse(json: &str) -> Result<Self, PoiseError> {
+        Ok(serde_json::from_str(json).log_err_msg("Failed to parse sound pack index")?)
+    }
+
+    pub fn find_pack(&self, id: &str) -> Option<&Pack> {
+        self.packs
+            .iter()
+            .find(|pack| pack.id.eq_ignore_ascii_case(id.trim()))
+    }
+}
+
+/// Step of a pack install, reported as the install progresses
+#[derive(Debug, Clone, Copy)]
+pub enum InstallStep {
+    Downloading(DownloadProgress),
+    Verifying,
+    Validating(IngestProgress),
+}
+
+/// Outcome of installing a pack
+#[derive(Debug, PartialEq)]
+pub enum InstallOutcome {
+    /// Number of sounds added. Sounds already added (same name, or same duration and size)
+    /// are skipped
+    Installed(usize),
+    /// Downloaded zip doesn't match the index's hash, nothing was added
+    HashMismatch,
+}
+
+pub async fn fetch_index(
+    client: &reqwest::Client,
+    index_url: &str,
+) -> Result<PackIndex, PoiseError> {
+    log::info!("Fetching sound pack index - {index_url}");
+
+    let json = client
+        .get(index_url)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .log_err_msg("Failed to fetch sound pack index")?
+        .text()
+        .await
+        .log_err_msg("Failed to read sound pack index")?;
+
+    PackIndex::parse(&json)
+}
+
+/// Pack list of `/packs browse`
+pub fn render_pack_list(lang: Language, index: &PackIndex) -> String {
+    if index.packs.is_empty() {
+        return tr(lang, "packs.empty", &[]);
+    }
+
+    let mut lines = vec![tr(lang, "packs.list_title", &[])];
+    lines.extend(
+        index
+            .packs
+            .iter()
+            .take(vars::PACKS_BROWSE_LIMIT)
+            .map(|pack| match pack.description.trim() {
+                "" => format!("- **{}** `{}`", pack.name, pack.id),
+                description => format!("- **{}** `{}` - {description}", pack.name, pack.id),
+            }),
+    );
+    lines.push(tr(lang, "packs.install_hint", &[]));
+
+    lines.join("\n")
+}
+
+/// Zip url of pack, resolved against the index url
+pub fn pack_url(index_url: &str, pack: &Pack) -> Result<reqwest::Url, PoiseError> {
+    let url = reqwest::Url::parse(index_url)
+        .and_then(|index_url| index_url.join(&pack.url))
+        .log_err_msg("Invalid sound pack url")?;
+
+    Ok(url)
+}
+
+/// Download pack, verify its zip against the index's hash and add its valid mp3 files as sounds
+/// of guild. `on_step` is awaited as each install step starts, and with validation progress
+pub async fn install_pack<F, Fut>(
+    data: &UserData,
+    index_url: &str,
+    pack: &Pack,
+    guild_id: u64,
+    mut on_step: F,
+) -> Result<InstallOutcome, PoiseError>
+where
+    F: FnMut(InstallStep) -> Fut,
+    Fut: Future<Output = ()>,
+{
+    log::info!(
+        "Installing sound pack. Pack: {}, Guild Id: {guild_id}",
+        pack.id
+    );
+    let url = pack_url(index_url, pack)?;
+    let _permit = data.ingest_limiter.acquire().await;
+
+    let zip_file = TempFileGuard::with_extension("zip")?;
+    let response = data
+        .http_client
+        .get(url)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .log_err_msg("Failed to download sound pack")?;
+    let total = response.content_length();
+    on_step(InstallStep::Downloading(DownloadProgress {
+        downloaded: 0,
+        total,
+    }))
+    .await;
+    audio::write_response_to_file_with_progress(response, &zip_file, |progress| {
+        on_step(InstallStep::Downloading(progress))
+    })
+    .await?;
+
+    on_step(InstallStep::Verifying).await;
+    let zip_path = zip_file.to_path_buf();
+    let hash = tokio::task::spawn_blocking(move || file_sha256(&zip_path)).await??;
+    if !hash.eq_ignore_ascii_case(pack.sha256.trim()) {
+        log::warn!(
+            "Sound pack hash mismatch. Pack: {}, Expected: {}, Got: {hash}",
+            pack.id,
+            pack.sha256
+        );
+        return Ok(InstallOutcome::HashMismatch);
+    }
+
+    let zip_path = zip_file.to_path_buf();
+    let extracted = tokio::task::spawn_blocking(move || extract_pack(&zip_path)).await??;
+    let file_names: HashMap<path::PathBuf, String> = extracted
+        .iter()
+        .map(|(file_name, temp_file)| (temp_file.to_path_buf(), file_name.clone()))
+        .collect();
+
+    let config = data.config();
+    let validator = AudioFileValidator::new()
+        .max_audio_duration(data.max_audio_duration(Some(guild_id)))
+        .reject_uuid_files(false);
+    let audio_files = extracted
+        .iter()
+        .map(|(_, temp_file)| AudioFile::new(temp_file.to_path_buf()))
+        .collect();
+    let audio_files = ingest::validate_audio_files(
+        audio_files,
+        validator,
+        config.ingest_concurrency,
+        |progress| on_step(InstallStep::Validating(progress)),
+    )
+    .await;
+
+    let table = data.audio_table();
+    let mut installed = 0;
+    for (audio_file, track_info) in audio_files {
+        let Some(file_name) = file_names.get(&audio_file.to_path_buf()) else {
+            continue;
+        };
+        let title = AudioFile::new(path::PathBuf::from(file_name)).audio_title();
+        let Ok(name) = SoundName::new(title) else {
+            continue;
+        };
+        let is_added = table
+            .find_audio_row(UniqueAudioTableCol::Name(name.to_string()))
+            .is_some()
+            || table
+                .find_duplicate_audio_row(track_info.duration_ms(), track_info.file_size)
+                .is_some();
+        if is_added {
+            continue;
+        }
+
+        let placed_file = data.move_file_to_audio_dir(audio_file.as_path())?;
+        let inserted = table.insert_audio_row(AudioTableRowInsert {
+            name,
+            tags: Tags::from(pack.id.as_str()),
+            audio_file: AudioFile::new(placed_file.to_path_buf()),
+            created_at: chrono::Utc::now(),
+            author_id: None,
+            author_name: None,
+            author_global_name: None,
+            author_hash: None,
+            guild_id: Some(guild_id),
+            duration_ms: Some(track_info.duration_ms()),
+            file_size: Some(track_info.file_size),
+            source_url: None,
+        });
+        match inserted {
+            Ok(()) => {
+                placed_file.keep();
+                installed += 1;
+            }
+            Err(err) => log::error!("Failed to install sound pack sound - {err}"),
+        }
+    }
+
+    if installed > 0 {
+        data.autocomplete_cache.invalidate();
+    }
+
+    log::info!(
+        "Installed {installed} sounds of sound pack. Pack: {}, Guild Id: {guild_id}",
+        pack.id
+    );
+    Ok(InstallOutcome::Installed(installed))
+}
+
+/// Hex encoded SHA-256 of file
+pub fn file_sha256(path: &path::Path) -> Result<String, PoiseError> {
+    let mut file = std::fs::File::open(path).log_err_msg("Failed to open file to hash")?;
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut file, &mut hasher).log_err_msg("Failed to hash file")?;
+
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect())
+}
+
+/// Extract mp3 files of pack zip to temp files, paired with their file names. Files in sub
+/// dirs are included. At most `vars::PACK_MAX_SOUNDS` files are extracted, and files bigger
+/// than `vars::PACK_MAX_SOUND_FILE_SIZE` are skipped
+pub fn extract_pack(zip_file: &path::Path) -> Result<Vec<(String, TempFileGuard)>, PoiseError> {
+    let file = std::fs::File::open(zip_file).log_err_msg("Failed to open sound pack")?;
+    let mut archive = zip::ZipArchive::new(file).log_err_msg("Failed to read sound pack zip")?;
+
+    let mut extracted = vec![];
+    for index in 0..archive.len() {
+        if extracted.len() >= vars::PACK_MAX_SOUNDS {
+            log::warn!(
+                "Sound pack has more than {} sounds, skipping the rest",
+                vars::PACK_MAX_SOUNDS
+            );
+            break;
+        }
+
+        let mut entry = archive
+            .by_index(index)
+            .log_err_msg("Failed to read sound pack entry")?;
+        // enclosed names leave out paths escaping the extract dir, e.g. `../bruh.mp3`
+        let Some(file_name) = entry.enclosed_name().and_then(|path| {
+            path.file_name()
+                .map(|file_name| file_name.to_string_lossy().to_string())
+        }) else {
+            continue;
+        };
+        let is_mp3 = path::Path::new(&file_name)
+            .extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("mp3"));
+        if !entry.is_file() || !is_mp3 || entry.size() > vars::PACK_MAX_SOUND_FILE_SIZE {
+            continue;
+        }
+
+        let temp_file = TempFileGuard::with_extension("mp3")?;
+        let mut out = std::fs::File::create(&*temp_file)
+            .log_err_msg("Failed to create sound pack temp file")?;
+        // sizes are read from the zip, so the copy is capped in case they lie
+        std::io::copy(
+            &mut (&mut entry).take(vars::PACK_MAX_SOUND_FILE_SIZE),
+            &mut out,
+        )
+        .log_err_msg("Failed to extract sound pack file")?;
+
+        extracted.push((file_name, temp_file));
+    }
+
+    Ok(extracted)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use crate::test_utils::TempDir;
+
+    use super::*;
+
+    fn pack(id: &str, url: &str) -> Pack {
+        Pack {
+            id: id.into(),
+            name: "Memes".into(),
+            description: "".into(),
+            url: url.into(),
+            sha256: "ab".into(),
+        }
+    }
+
+    #[test]
+    fn pack_index_test() {
+        let index = PackIndex::parse(
+            r#"{"packs": [{"id": "memes", "name": "Memes", "url": "memes.zip", "sha256": "ab"}]}"#,
+        )
+        .unwrap();
+        assert_eq!(vec![pack("memes", "memes.zip")], index.packs);
+        assert_eq!(
+            Some("memes"),
+            index.find_pack(" MEMES ").map(|pack| pack.id.as_str())
+        );
+        assert!(index.find_pack("retro").is_none());
+
+        assert!(PackIndex::parse("{}").is_err());
+
+        let list = render_pack_list(Language::English, &index);
+        assert!(list.contains("- **Memes** `memes`"));
+        assert_eq!(
+            tr(Language::English, "packs.empty", &[]),
+            render_pack_list(Language::English, &PackIndex { packs: vec![] })
+        );
+    }
+
+    #[test]
+    fn pack_url_test() {
+        let index_url = "https://example.com/packs/index.json";
+        assert_eq!(
+            "https://example.com/packs/memes.zip",
+            pack_url(index_url, &pack("memes", "memes.zip"))
+                .unwrap()
+                .as_str()
+        );
+        assert_eq!(
+            "https://cdn.example.com/memes.zip",
+            pack_url(
+                index_url,
+                &pack("memes", "https://cdn.example.com/memes.zip")
+            )
+            .unwrap()
+            .as_str()
+        );
+        assert!(pack_url("index.json", &pack("memes", "memes.zip")).is_err());
+    }
+
+    #[test]
+    fn file_sha256_test() {
+        let dir = TempDir::new();
+        let file = dir.path().join("abc.txt");
+        std::fs::write(&file, "abc").unwrap();
+
+        assert_eq!(
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad",
+            file_sha256(&file).unwrap()
+        );
+    }
+
+    #[test]
+    fn extract_pack_test() {
+        let dir = TempDir::new();
+        let zip_file = dir.path().join("memes.zip");
+
+        let mut writer = zip::ZipWriter::new(std::fs::File::create(&zip_file).unwrap());
+        let options = zip::write::SimpleFileOptions::default();
+        for (name, content) in [
+            ("bruh.mp3", "bruh"),
+            ("retro/Beep.MP3", "beep"),
+            ("readme.txt", "not a sound"),
+        ] {
+            writer.start_file(name, options).unwrap();
+            writer.write_all(content.as_bytes()).unwrap();
+        }
+        writer.add_directory("empty/", options).unwrap();
+        writer.finish().unwrap();
+
+        let extracted = extract_pack(&zip_file).unwrap();
+        let file_names: Vec<_> = extracted.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(vec!["bruh.mp3", "Beep.MP3"], file_names);
+        assert_eq!(
+            "beep",
+            std::fs::read_to_string(extracted[1].1.path()).unwrap()
+        );
+
+        // extracted files are temp files
+        let temp_file = extracted[0].1.to_path_buf();
+        drop(extracted);
+        assert!(!temp_file.exists());
+    }
+}
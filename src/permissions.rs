@@ -0,0 +1,267 @@
+//! Per-guild role based permissions for bot commands. A permission without any roles mapped
+//! is granted to everyone, so guilds that never configure permissions keep open access.
+//! Administrators are always permitted
+use poise::{ChoiceParameter, CreateReply};
+use serenity::all::Member;
+
+use crate::commands::{PoiseContext, PoiseError};
+use crate::common::UserData;
+use crate::helpers::poise_check_msg;
+use crate::i18n::{self, tr};
+use crate::privacy;
+
+#[derive(Debug, Clone, Copy, PartialEq, ChoiceParameter)]
+pub enum Permission {
+    #[name = "Add and edit sounds"]
+    AddSounds,
+    #[name = "Delete sounds"]
+    DeleteSounds,
+    #[name = "Manage settings"]
+    ManageSettings,
+    #[name = "Use text-to-speech"]
+    UseTts,
+    /// Unlike other permissions, only granted to administrators when no roles are mapped
+    #[name = "Approve sounds"]
+    ApproveSounds,
+    /// Loop sounds until stopped, admin-only by default so nobody can loop an air horn forever
+    #[name = "Loop sounds forever"]
+    LoopForever,
+    /// Play sounds stopping or pausing the sounds playing, admin-only by default
+    #[name = "Play with priority"]
+    PriorityPlay,
+}
+
+impl Permission {
+    pub const ALL: [Permission; 7] = [
+        Self::AddSounds,
+        Self::DeleteSounds,
+        Self::ManageSettings,
+        Self::UseTts,
+        Self::ApproveSounds,
+        Self::LoopForever,
+        Self::PriorityPlay,
+    ];
+
+    /// Key stored in the permissions table
+    pub fn key(&self) -> &'static str {
+        match self {
+            Self::AddSounds => "add_sounds",
+            Self::DeleteSounds => "delete_sounds",
+            Self::ManageSettings => "manage_settings",
+            Self::UseTts => "use_tts",
+            Self::ApproveSounds => "approve_sounds",
+            Self::LoopForever => "loop_forever",
+            Self::PriorityPlay => "priority_play",
+        }
+    }
+
+    /// Granted to administrators only, rather than everyone, while no roles are mapped
+    pub fn is_admin_only_by_default(&self) -> bool {
+        matches!(
+            self,
+            Self::ApproveSounds | Self::LoopForever | Self::PriorityPlay
+        )
+    }
+
+    /// Whether member with roles is granted permission, see `is_permitted` and `is_approver`
+    pub fn is_granted(
+        &self,
+        allowed_role_ids: &[u64],
+        member_role_ids: &[u64],
+        is_admin: bool,
+    ) -> bool {
+        match self.is_admin_only_by_default() {
+            true => is_approver(allowed_role_ids, member_role_ids, is_admin),
+            false => is_permitted(allowed_role_ids, member_role_ids, is_admin),
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|permission| permission.key() == key)
+    }
+}
+
+/// Member is permitted if they're an administrator, no roles are mapped to the permission,
+/// or they have one of the mapped roles
+pub fn is_permitted(allowed_role_ids: &[u64], member_role_ids: &[u64], is_admin: bool) -> bool {
+    is_admin
+        || allowed_role_ids.is_empty()
+        || member_role_ids
+            .iter()
+            .any(|role_id| allowed_role_ids.contains(role_id))
+}
+
+/// Member may approve sounds if they're an administrator or have one of the mapped roles.
+/// Without mapped roles only administrators may, as sound review would be pointless otherwise
+pub fn is_approver(allowed_role_ids: &[u64], member_role_ids: &[u64], is_admin: bool) -> bool {
+    is_admin
+        || member_role_ids
+            .iter()
+            .any(|role_id| allowed_role_ids.contains(role_id))
+}
+
+/// Whether guild member has permission, for interactions without a command context (e.g.
+/// buttons)
+pub fn is_member_permitted(
+    data: &UserData,
+    member: &Member,
+    permission: Permission,
+) -> Result<bool, PoiseError> {
+    let allowed_role_ids = data
+        .permissions_table()
+        .role_ids(member.guild_id.get(), permission.key())?;
+    let member_role_ids: Vec<_> = member.roles.iter().map(|role_id| role_id.get()).collect();
+    let is_admin = member
+        .permissions
+        .is_some_and(|permissions| permissions.administrator());
+
+    Ok(permission.is_granted(&allowed_role_ids, &member_role_ids, is_admin))
+}
+
+/// Whether guild member may approve sounds (see `approval`)
+pub fn is_member_approver(data: &UserData, member: &Member) -> Result<bool, PoiseError> {
+    is_member_permitted(data, member, Permission::ApproveSounds)
+}
+
+/// Check command author has permission in guild, replying with the required roles if not
+pub async fn check_permission(
+    ctx: PoiseContext<'_>,
+    permission: Permission,
+) -> Result<bool, PoiseError> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return Ok(false);
+    };
+
+    let allowed_role_ids = ctx
+        .data()
+        .permissions_table()
+        .role_ids(guild_id.get(), permission.key())?;
+
+    let (member_role_ids, is_admin) = match ctx.author_member().await {
+        Some(member) => (
+            member.roles.iter().map(|role_id| role_id.get()).collect(),
+            member
+                .permissions
+                .is_some_and(|permissions| permissions.administrator()),
+        ),
+        None => (vec![], false),
+    };
+
+    let permitted = permission.is_granted(&allowed_role_ids, &member_role_ids, is_admin);
+    if !permitted {
+        log::info!(
+            "Permission denied. User Id: {}, Permission: {permission:?}",
+            ctx.author().id
+        );
+
+        let roles: Vec<_> = allowed_role_ids
+            .iter()
+            .map(|role_id| format!("<@&{role_id}>"))
+            .collect();
+        poise_check_msg(
+            ctx.send(CreateReply::default().ephemeral(true).content(tr(
+                i18n::language(ctx),
+                "permissions.denied",
+                &[
+                    ("permission", &permission.name()),
+                    ("roles", &roles.join(", ")),
+                ],
+            )))
+            .await,
+        );
+    }
+
+    Ok(permitted)
+}
+
+/// Uploaders may manage their own sounds, anonymous uploads (by author hash) included. Other
+/// members need permission
+pub async fn check_sound_owner(
+    ctx: PoiseContext<'_>,
+    author_id: Option<u64>,
+    author_hash: Option<&str>,
+    permission: Permission,
+) -> Result<bool, PoiseError> {
+    let user_id = ctx.author().id.get();
+    let is_owner = is_sound_owner(author_id, user_id)
+        || match author_hash {
+            Some(author_hash) => privacy::user_author_hash(ctx.data(), user_id)? == author_hash,
+            None => false,
+        };
+
+    match is_owner {
+        true => Ok(true),
+        false => check_permission(ctx, permission).await,
+    }
+}
+
+/// Sounds without a recorded uploader (e.g. added by scanning the audio dir) have no owner
+pub fn is_sound_owner(author_id: Option<u64>, user_id: u64) -> bool {
+    author_id == Some(user_id)
+}
+
+pub async fn can_add_sounds(ctx: PoiseContext<'_>) -> Result<bool, PoiseError> {
+    check_permission(ctx, Permission::AddSounds).await
+}
+
+pub async fn can_delete_sounds(ctx: PoiseContext<'_>) -> Result<bool, PoiseError> {
+    check_permission(ctx, Permission::DeleteSounds).await
+}
+
+pub async fn can_manage_settings(ctx: PoiseContext<'_>) -> Result<bool, PoiseError> {
+    check_permission(ctx, Permission::ManageSettings).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn is_permitted_test() {
+        // no roles mapped, everyone permitted
+        assert!(is_permitted(&[], &[], false));
+
+        assert!(is_permitted(&[1, 2], &[2, 3], false));
+        assert!(!is_permitted(&[1, 2], &[3], false));
+        assert!(!is_permitted(&[1, 2], &[], false));
+
+        // admins always permitted
+        assert!(is_permitted(&[1, 2], &[], true));
+    }
+
+    #[test]
+    fn is_approver_test() {
+        // no roles mapped, only admins approve
+        assert!(!is_approver(&[], &[1], false));
+        assert!(is_approver(&[], &[], true));
+
+        assert!(is_approver(&[1, 2], &[2, 3], false));
+        assert!(!is_approver(&[1, 2], &[3], false));
+    }
+
+    #[test]
+    fn is_granted_test() {
+        // no roles mapped, everyone may add sounds but only admins may loop forever
+        assert!(Permission::AddSounds.is_granted(&[], &[], false));
+        assert!(!Permission::LoopForever.is_granted(&[], &[], false));
+        assert!(Permission::LoopForever.is_granted(&[], &[], true));
+        assert!(Permission::LoopForever.is_granted(&[1], &[1], false));
+    }
+
+    #[test]
+    fn is_sound_owner_test() {
+        assert!(is_sound_owner(Some(1), 1));
+        assert!(!is_sound_owner(Some(1), 2));
+        assert!(!is_sound_owner(None, 1));
+    }
+
+    #[test]
+    fn permission_key_test() {
+        for permission in Permission::ALL {
+            assert_eq!(Some(permission), Permission::from_key(permission.key()));
+        }
+        assert_eq!(None, Permission::from_key("unknown"));
+    }
+}
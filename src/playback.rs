@@ -0,0 +1,1621 @@
+use std::collections::{HashMap, HashSet};
+use std::path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use tokio::sync::Notify;
+
+use serenity::all::{ChannelId, GuildId, UserId};
+
+use crate::checksums::{self, Verification};
+use crate::commands::PoiseContext;
+use crate::commands::PoiseError;
+use crate::common::{LogResult, UserData};
+use crate::db::{self, AudioTable, AudioTableRow, PlayEventInsert, UniqueAudioTableCol};
+use crate::errors::AudioError;
+use crate::helpers::SongbirdHelper;
+use crate::{i18n, sessions, vars};
+
+/// What a play was started from, recorded with every play event
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PlaySource {
+    /// Play, favorite and soundboard buttons
+    Button,
+    Slash,
+    Prefix,
+    /// Plays not started by a bot interaction, e.g. mirrored native soundboard plays
+    Trigger,
+    Api,
+}
+
+impl PlaySource {
+    pub const ALL: [PlaySource; 5] = [
+        Self::Button,
+        Self::Slash,
+        Self::Prefix,
+        Self::Trigger,
+        Self::Api,
+    ];
+
+    /// Code stored in the play events table
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Button => "button",
+            Self::Slash => "slash",
+            Self::Prefix => "prefix",
+            Self::Trigger => "trigger",
+            Self::Api => "api",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|source| source.code() == code)
+    }
+
+    /// Slash or prefix, depending on how command was invoked
+    pub fn command(ctx: PoiseContext<'_>) -> Self {
+        match ctx {
+            poise::Context::Application(_) => Self::Slash,
+            poise::Context::Prefix(_) => Self::Prefix,
+        }
+    }
+}
+
+/// Sound being played, passed to playback hooks
+#[derive(Debug, Clone, PartialEq)]
+pub struct Playback {
+    pub guild_id: GuildId,
+    pub channel_id: ChannelId,
+    pub audio_file: path::PathBuf,
+}
+
+type PlaybackHook = Box<dyn Fn(&Playback) + Send + Sync>;
+type PlaybackErrorHook = Box<dyn Fn(&Playback, &str) + Send + Sync>;
+
+/// Actions run when a sound starts playing, when it ends or is stopped, and when it fails to
+/// play (see `SongbirdHelper::play_audio_with_hooks`). Hooks of each event run in the order they
+/// were registered
+#[derive(Default)]
+pub struct PlaybackHooks {
+    on_start: Vec<PlaybackHook>,
+    on_end: Vec<PlaybackHook>,
+    on_error: Vec<PlaybackErrorHook>,
+}
+
+impl PlaybackHooks {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn on_start(mut self, hook: impl Fn(&Playback) + Send + Sync + 'static) -> Self {
+        self.on_start.push(Box::new(hook));
+        self
+    }
+
+    pub fn on_end(mut self, hook: impl Fn(&Playback) + Send + Sync + 'static) -> Self {
+        self.on_end.push(Box::new(hook));
+        self
+    }
+
+    /// Hook is called with the error's description
+    pub fn on_error(mut self, hook: impl Fn(&Playback, &str) + Send + Sync + 'static) -> Self {
+        self.on_error.push(Box::new(hook));
+        self
+    }
+
+    /// Notify `finished` when the sound ends or fails to play (see `wait_for_finish`)
+    pub fn notify_finished(self, finished: Arc<Notify>) -> Self {
+        let failed = finished.clone();
+        self.on_end(move |_| finished.notify_one())
+            .on_error(move |_, _| failed.notify_one())
+    }
+
+    pub fn started(&self, playback: &Playback) {
+        for hook in &self.on_start {
+            hook(playback);
+        }
+    }
+
+    pub fn ended(&self, playback: &Playback) {
+        for hook in &self.on_end {
+            hook(playback);
+        }
+    }
+
+    pub fn failed(&self, playback: &Playback, error: &str) {
+        log::warn!(
+            "Playback failed. Guild Id: {}, File: {} - {error}",
+            playback.guild_id,
+            playback.audio_file.to_string_lossy()
+        );
+        for hook in &self.on_error {
+            hook(playback, error);
+        }
+    }
+}
+
+/// Wait for a sound to be finished (see `PlaybackHooks::notify_finished`). Gives up after
+/// `vars::PLAY_TO_END_TIMEOUT_SECS`, as sounds dropped with their call never end. Returns
+/// whether the sound finished
+pub async fn wait_for_finish(finished: &Notify) -> bool {
+    let timeout = Duration::from_secs(vars::PLAY_TO_END_TIMEOUT_SECS);
+    match tokio::time::timeout(timeout, finished.notified()).await {
+        Ok(()) => true,
+        Err(_) => {
+            log::warn!("Timed out waiting for audio track end");
+            false
+        }
+    }
+}
+
+/// How many times a sound is played, see `/play`'s loop option
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LoopMode {
+    /// Played this many times in total, up to `vars::PLAY_LOOP_MAX`
+    Times(u32),
+    /// Played until stopped
+    Infinite,
+}
+
+impl LoopMode {
+    /// Parse a play count (`3`) or `infinite`
+    pub fn parse(value: &str) -> Option<Self> {
+        let value = value.trim();
+        if value.eq_ignore_ascii_case("infinite") {
+            return Some(Self::Infinite);
+        }
+
+        value
+            .parse::<u32>()
+            .ok()
+            .filter(|times| (1..=vars::PLAY_LOOP_MAX).contains(times))
+            .map(Self::Times)
+    }
+}
+
+/// What happens to the sounds playing in a guild when a sound is played with priority
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, poise::ChoiceParameter)]
+pub enum PriorityMode {
+    /// Also clears the guild's sequence
+    #[default]
+    #[name = "Stop"]
+    Stop,
+    /// Resumed once the priority sound ends
+    #[name = "Pause"]
+    Pause,
+}
+
+impl PriorityMode {
+    pub const ALL: [PriorityMode; 2] = [PriorityMode::Stop, PriorityMode::Pause];
+
+    /// Code stored in `GuildSettingsRow::priority_mode`
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Stop => "stop",
+            Self::Pause => "pause",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|mode| mode.code() == code)
+    }
+}
+
+/// Options of a sound played with `play_audio_track_with_options`
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PlayOptions {
+    pub loop_mode: Option<LoopMode>,
+    /// Stop or pause the guild's playing sounds first, see `PriorityMode`
+    pub priority: bool,
+    /// Play without posting a now playing message. None uses the guild's default (see
+    /// `is_silent`)
+    pub silent: Option<bool>,
+}
+
+/// Volume, loop and pause control of a playing track, see `PlayingTracks`
+pub trait TrackControl: Send + Sync {
+    /// Set track's volume, 1.0 being the file's own volume. Returns false once the track has
+    /// finished
+    fn set_volume(&self, volume: f32) -> bool;
+
+    /// Loop track, or stop looping it when None. Returns false once the track has finished
+    fn set_loop(&self, mode: Option<LoopMode>) -> bool;
+
+    /// Pause or resume track. Returns false once the track has finished
+    fn set_paused(&self, paused: bool) -> bool;
+}
+
+/// Tracks playing in each guild, so announcements can lower their volume (duck them) instead
+/// of mixing over them at full volume, and the now playing message can loop them. Tracks
+/// aren't unregistered when they end, so only the latest `vars::DUCKING_TRACKS_LEN` of each
+/// guild are kept
+pub struct PlayingTracks {
+    tracks: Mutex<Vec<(GuildId, Arc<dyn TrackControl>)>>,
+}
+
+/// Tracks registered by `SongbirdHelper` implementations as they start playing
+pub static PLAYING_TRACKS: PlayingTracks = PlayingTracks::new();
+
+impl Default for PlayingTracks {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PlayingTracks {
+    pub const fn new() -> Self {
+        Self {
+            tracks: Mutex::new(Vec::new()),
+        }
+    }
+
+    pub fn register(&self, guild_id: GuildId, track: Arc<dyn TrackControl>) {
+        let mut tracks = self.tracks.lock().unwrap();
+        let guild_tracks = tracks.iter().filter(|(id, _)| *id == guild_id).count();
+        if guild_tracks >= vars::DUCKING_TRACKS_LEN {
+            if let Some(oldest) = tracks.iter().position(|(id, _)| *id == guild_id) {
+                tracks.remove(oldest);
+            }
+        }
+        tracks.push((guild_id, track));
+    }
+
+    /// Guild's tracks that are still playing. Finished tracks are dropped
+    fn playing(&self, guild_id: GuildId) -> Vec<Arc<dyn TrackControl>> {
+        let mut tracks = self.tracks.lock().unwrap();
+        tracks.retain(|(id, track)| *id != guild_id || track.set_volume(1.0));
+        tracks
+            .iter()
+            .filter(|(id, _)| *id == guild_id)
+            .map(|(_, track)| track.clone())
+            .collect()
+    }
+
+    /// Loop guild's latest track, or stop looping it when None. Returns false when it has
+    /// finished or nothing was played
+    pub fn set_loop(&self, guild_id: GuildId, mode: Option<LoopMode>) -> bool {
+        let latest = self
+            .tracks
+            .lock()
+            .unwrap()
+            .iter()
+            .rev()
+            .find(|(id, _)| *id == guild_id)
+            .map(|(_, track)| track.clone());
+
+        latest.is_some_and(|track| track.set_loop(mode))
+    }
+
+    /// Fade guild's playing tracks to `volume`, returning them to restore with `restore`
+    pub async fn duck(&self, guild_id: GuildId, volume: f32) -> Vec<Arc<dyn TrackControl>> {
+        let tracks = self.playing(guild_id);
+        fade(&tracks, 1.0, volume).await;
+        tracks
+    }
+
+    /// Fade ducked tracks back from `volume` to full volume
+    pub async fn restore(&self, tracks: &[Arc<dyn TrackControl>], volume: f32) {
+        fade(tracks, volume, 1.0).await;
+    }
+
+    /// Pause guild's playing tracks, returning them to resume with `resume`
+    pub fn pause(&self, guild_id: GuildId) -> Vec<Arc<dyn TrackControl>> {
+        let tracks = self.playing(guild_id);
+        for track in &tracks {
+            track.set_paused(true);
+        }
+        tracks
+    }
+
+    /// Resume paused tracks
+    pub fn resume(&self, tracks: &[Arc<dyn TrackControl>]) {
+        for track in tracks {
+            track.set_paused(false);
+        }
+    }
+}
+
+/// Ramp volume of tracks linearly from `from` to `to` over `vars::DUCKING_FADE_MS`
+async fn fade(tracks: &[Arc<dyn TrackControl>], from: f32, to: f32) {
+    if tracks.is_empty() {
+        return;
+    }
+
+    let step_delay = Duration::from_millis(vars::DUCKING_FADE_MS / vars::DUCKING_FADE_STEPS as u64);
+    for step in 1..=vars::DUCKING_FADE_STEPS {
+        // ends exactly at `to`
+        let progress = step as f32 / vars::DUCKING_FADE_STEPS as f32;
+        let volume = from * (1.0 - progress) + to * progress;
+        for track in tracks {
+            track.set_volume(volume);
+        }
+        if step < vars::DUCKING_FADE_STEPS {
+            tokio::time::sleep(step_delay).await;
+        }
+    }
+}
+
+/// Sequences of sounds played one after another in each guild (see `play_sequence`). Starting
+/// a sequence replaces the guild's running one, and stopping playback clears it
+#[derive(Default)]
+pub struct PlaybackQueue {
+    sequences: Mutex<HashMap<GuildId, u64>>,
+    next_id: AtomicU64,
+}
+
+impl PlaybackQueue {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start guild's sequence, returning its id
+    fn start(&self, guild_id: GuildId) -> u64 {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        self.sequences.lock().unwrap().insert(guild_id, id);
+        id
+    }
+
+    fn is_current(&self, guild_id: GuildId, id: u64) -> bool {
+        self.sequences.lock().unwrap().get(&guild_id) == Some(&id)
+    }
+
+    fn finish(&self, guild_id: GuildId, id: u64) {
+        let mut sequences = self.sequences.lock().unwrap();
+        if sequences.get(&guild_id) == Some(&id) {
+            sequences.remove(&guild_id);
+        }
+    }
+
+    /// Stop guild's sequence after the sound playing
+    pub fn clear(&self, guild_id: GuildId) {
+        self.sequences.lock().unwrap().remove(&guild_id);
+    }
+}
+
+/// Sound names of comma separated names, e.g. `/play bruh, oof`
+pub fn split_sound_names(names: &str) -> Vec<String> {
+    names
+        .split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+/// Sounds of names, in order, with exact matches or the best fuzzy match (see `fuzzy_match`).
+/// Also returns the names no sound was found for
+pub fn resolve_sound_names(
+    table: &AudioTable,
+    names: &[String],
+) -> Result<(Vec<AudioTableRow>, Vec<String>), PoiseError> {
+    let mut rows = vec![];
+    let mut not_found = vec![];
+
+    for name in names {
+        let col = UniqueAudioTableCol::from_input(name);
+        let row = match table.find_audio_row(&col) {
+            Some(row) => Some(row),
+            // sound picked by id was removed, there's no name to match
+            None if matches!(col, UniqueAudioTableCol::Id(_)) => None,
+            None => {
+                let candidates =
+                    table.search_audio_rows(name, false, vars::FUZZY_MATCH_CANDIDATES)?;
+                match fuzzy_match(name, candidates) {
+                    FuzzyMatch::Best(row) => Some(*row),
+                    FuzzyMatch::Suggestions(_) | FuzzyMatch::None => None,
+                }
+            }
+        };
+
+        match row {
+            Some(row) => rows.push(row),
+            None => not_found.push(name.clone()),
+        }
+    }
+
+    Ok((rows, not_found))
+}
+
+/// Last sound (audio table id) played in each guild, and by each user in each guild
+#[derive(Default)]
+pub struct LastPlayed {
+    guilds: Mutex<HashMap<GuildId, i64>>,
+    users: Mutex<HashMap<(GuildId, UserId), i64>>,
+}
+
+impl LastPlayed {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&self, guild_id: GuildId, user_id: UserId, audio_id: i64) {
+        self.guilds.lock().unwrap().insert(guild_id, audio_id);
+        self.users
+            .lock()
+            .unwrap()
+            .insert((guild_id, user_id), audio_id);
+    }
+
+    /// Last sound played in guild, or by user in guild
+    pub fn get(&self, guild_id: GuildId, user_id: Option<UserId>) -> Option<i64> {
+        match user_id {
+            Some(user_id) => self
+                .users
+                .lock()
+                .unwrap()
+                .get(&(guild_id, user_id))
+                .copied(),
+            None => self.guilds.lock().unwrap().get(&guild_id).copied(),
+        }
+    }
+}
+
+/// Sound picked for a name no sound has, from the sounds found by searching for it
+pub enum FuzzyMatch {
+    /// Only candidate similar enough to play right away
+    Best(Box<AudioTableRow>),
+    /// Candidates to pick from, most similar first
+    Suggestions(Vec<AudioTableRow>),
+    None,
+}
+
+/// Trigram similarity (0 to 1) of names, ignoring case
+pub fn name_similarity(a: &str, b: &str) -> f64 {
+    fn trigrams(value: &str) -> HashSet<[char; 3]> {
+        let chars: Vec<char> = format!("  {} ", value.to_lowercase()).chars().collect();
+        chars
+            .windows(3)
+            .map(|window| [window[0], window[1], window[2]])
+            .collect()
+    }
+
+    let (a, b) = (trigrams(a), trigrams(b));
+    let union = a.union(&b).count();
+    match union {
+        0 => 0.0,
+        union => a.intersection(&b).count() as f64 / union as f64,
+    }
+}
+
+/// Pick the sound meant by name among search candidates. A candidate is played right away
+/// when it's the only one at least `vars::FUZZY_MATCH_THRESHOLD` similar to name
+pub fn fuzzy_match(name: &str, candidates: Vec<AudioTableRow>) -> FuzzyMatch {
+    let mut scored: Vec<_> = candidates
+        .into_iter()
+        .map(|row| (name_similarity(name, &row.name), row))
+        .collect();
+    scored.sort_by(|(a, _), (b, _)| b.total_cmp(a));
+
+    let confident = scored
+        .iter()
+        .filter(|(score, _)| *score >= vars::FUZZY_MATCH_THRESHOLD)
+        .count();
+
+    match (confident, scored.is_empty()) {
+        (1, _) => FuzzyMatch::Best(Box::new(scored.swap_remove(0).1)),
+        (_, true) => FuzzyMatch::None,
+        _ => FuzzyMatch::Suggestions(scored.into_iter().map(|(_, row)| row).collect()),
+    }
+}
+
+/// Find audio track and play it in the guild's voice channel, then increment its play count
+/// and the play count of the user who played it, record the play event, and post a now
+/// playing message in channel unless played silently (see `is_silent`).
+/// Shared by `/play` and play buttons.
+/// Plays are rate limited per user and per guild (see `PlayRateLimiter`)
+#[tracing::instrument(skip_all, fields(guild_id = %guild_id))]
+pub async fn play_audio_track(
+    data: &UserData,
+    voice: &dyn SongbirdHelper,
+    guild_id: GuildId,
+    channel_id: ChannelId,
+    user_id: UserId,
+    track: UniqueAudioTableCol,
+    source: PlaySource,
+) -> Result<AudioTableRow, AudioError> {
+    play_audio_track_with_hooks(
+        data,
+        voice,
+        guild_id,
+        channel_id,
+        user_id,
+        track,
+        source,
+        PlaybackHooks::new(),
+        PlayOptions::default(),
+    )
+    .await
+}
+
+/// `play_audio_track`, looping the sound or preempting the guild's playing sounds as options
+/// say
+#[allow(clippy::too_many_arguments)]
+pub async fn play_audio_track_with_options(
+    data: &UserData,
+    voice: &dyn SongbirdHelper,
+    guild_id: GuildId,
+    channel_id: ChannelId,
+    user_id: UserId,
+    track: UniqueAudioTableCol,
+    source: PlaySource,
+    options: PlayOptions,
+) -> Result<AudioTableRow, AudioError> {
+    play_audio_track_with_hooks(
+        data,
+        voice,
+        guild_id,
+        channel_id,
+        user_id,
+        track,
+        source,
+        PlaybackHooks::new(),
+        options,
+    )
+    .await
+}
+
+/// `play_audio_track_with_options`, also calling hooks
+#[allow(clippy::too_many_arguments)]
+async fn play_audio_track_with_hooks(
+    data: &UserData,
+    voice: &dyn SongbirdHelper,
+    guild_id: GuildId,
+    channel_id: ChannelId,
+    user_id: UserId,
+    track: UniqueAudioTableCol,
+    source: PlaySource,
+    hooks: PlaybackHooks,
+    options: PlayOptions,
+) -> Result<AudioTableRow, AudioError> {
+    let row = data.audio_table().find_audio_row(&track).ok_or_else(|| {
+        AudioError::AudioTrackNotFound {
+            track: track.value(),
+        }
+    })?;
+
+    log::info!(
+        "Found audio track. Name: {}, File: {}",
+        row.name,
+        row.audio_file.to_string_lossy()
+    );
+
+    data.play_rate_limiter
+        .read()
+        .unwrap()
+        .check(guild_id, user_id)
+        .map_err(|retry_after| AudioError::RateLimited { retry_after })?;
+
+    // files that can't be verified are played anyway, their playback fails if they're missing
+    let verification = checksums::verify_audio_file(data, &row)
+        .await
+        .log_err_msg("Failed to verify audio file checksum");
+    match verification {
+        Ok(Verification::Quarantined) => {
+            return Err(AudioError::Quarantined {
+                track: row.name.to_string(),
+            })
+        }
+        Ok(Verification::Mismatched) => {
+            return Err(AudioError::ChecksumMismatch {
+                track: row.name.to_string(),
+            })
+        }
+        _ => {}
+    }
+
+    let hooks = match options.priority {
+        true => preempt(data, voice, guild_id, hooks).await,
+        false => hooks,
+    };
+
+    let play_id = data.now_playing.start(guild_id, channel_id);
+    let hooks = hooks
+        .on_start(record_play_hook(data, user_id, row.id, source))
+        .on_end({
+            let now_playing = data.now_playing.clone();
+            move |_| now_playing.finish(guild_id, play_id)
+        })
+        .on_error({
+            let now_playing = data.now_playing.clone();
+            move |_, _| now_playing.finish(guild_id, play_id)
+        });
+    voice
+        .play_audio_with_hooks(guild_id, channel_id, &row.audio_file, hooks)
+        .await
+        .log_err()?;
+
+    if options.loop_mode.is_some() {
+        voice
+            .set_loop(guild_id, options.loop_mode)
+            .await
+            .log_err_msg("Failed to loop audio track")
+            .ok();
+        data.now_playing.set_looping(guild_id, play_id, true);
+    }
+
+    data.last_played.record(guild_id, user_id, row.id);
+
+    if !is_silent(data, guild_id, options.silent) {
+        let lang = i18n::guild_language(data, Some(guild_id), None);
+        data.now_playing
+            .announce(guild_id, play_id, &row, lang)
+            .await
+            .log_err_msg("Failed to announce now playing")
+            .ok();
+    }
+
+    Ok(row)
+}
+
+/// Whether sound is played without text confirmations, as asked or else as the guild's
+/// `/settings silent` default says
+pub fn is_silent(data: &UserData, guild_id: GuildId, silent: Option<bool>) -> bool {
+    silent.unwrap_or_else(|| {
+        data.guild_settings_table()
+            .get_guild_settings(guild_id.get())
+            .log_err()
+            .is_ok_and(|settings| settings.silent_play)
+    })
+}
+
+/// Make way for a priority sound by stopping or pausing the guild's playing sounds, depending
+/// on its priority mode. Paused sounds are resumed by the returned hooks once the priority
+/// sound ends
+async fn preempt(
+    data: &UserData,
+    voice: &dyn SongbirdHelper,
+    guild_id: GuildId,
+    hooks: PlaybackHooks,
+) -> PlaybackHooks {
+    let mode = data
+        .guild_settings_table()
+        .get_guild_settings(guild_id.get())
+        .ok()
+        .and_then(|settings| settings.priority_mode)
+        .as_deref()
+        .and_then(PriorityMode::from_code)
+        .unwrap_or_default();
+    log::info!("Preempting playing sounds. Guild Id: {guild_id}, Mode: {mode:?}");
+
+    match mode {
+        PriorityMode::Stop => {
+            data.playback_queue.clear(guild_id);
+            voice
+                .stop_audio(guild_id)
+                .await
+                .log_err_msg("Failed to stop audio for priority sound")
+                .ok();
+            hooks
+        }
+        PriorityMode::Pause => {
+            // a paused sequence carries on once its sound is resumed
+            let paused = Arc::new(voice.pause_audio(guild_id).await);
+            hooks
+                .on_end({
+                    let paused = paused.clone();
+                    move |_| PLAYING_TRACKS.resume(&paused)
+                })
+                .on_error(move |_, _| PLAYING_TRACKS.resume(&paused))
+        }
+    }
+}
+
+/// Play sounds one after another in the guild's voice channel, each like `play_audio_track`.
+/// Stops at the first sound that fails to play, or once the guild's sequence is replaced or
+/// cleared (see `PlaybackQueue`)
+pub async fn play_sequence(
+    data: &UserData,
+    voice: &dyn SongbirdHelper,
+    guild_id: GuildId,
+    channel_id: ChannelId,
+    user_id: UserId,
+    audio_ids: Vec<i64>,
+    source: PlaySource,
+) -> Result<(), AudioError> {
+    let sequence_id = data.playback_queue.start(guild_id);
+    let mut result = Ok(());
+
+    for audio_id in audio_ids {
+        if !data.playback_queue.is_current(guild_id, sequence_id) {
+            log::info!("Sequence replaced or stopped. Guild Id: {guild_id}");
+            break;
+        }
+
+        let finished = Arc::new(Notify::new());
+        let hooks = PlaybackHooks::new().notify_finished(finished.clone());
+        if let Err(err) = play_audio_track_with_hooks(
+            data,
+            voice,
+            guild_id,
+            channel_id,
+            user_id,
+            UniqueAudioTableCol::Id(audio_id),
+            source,
+            hooks,
+            PlayOptions::default(),
+        )
+        .await
+        {
+            result = Err(err);
+            break;
+        }
+
+        wait_for_finish(&finished).await;
+    }
+
+    data.playback_queue.finish(guild_id, sequence_id);
+    result
+}
+
+/// Play sounds at offsets from now in the guild's voice channel, each like `play_audio_track`
+/// without waiting for the previous sound to end, so sounds that overlapped overlap again.
+/// Sounds deleted or rate limited meanwhile are skipped. Stops once the guild's sequence is
+/// replaced or cleared, like `play_sequence`. Returns the number of sounds played
+pub async fn play_timed(
+    data: &UserData,
+    voice: &dyn SongbirdHelper,
+    guild_id: GuildId,
+    channel_id: ChannelId,
+    user_id: UserId,
+    timeline: Vec<(Duration, i64)>,
+    source: PlaySource,
+) -> Result<usize, AudioError> {
+    let sequence_id = data.playback_queue.start(guild_id);
+    let started = tokio::time::Instant::now();
+    let mut result = Ok(0);
+
+    for (offset, audio_id) in timeline {
+        tokio::time::sleep_until(started + offset).await;
+        if !data.playback_queue.is_current(guild_id, sequence_id) {
+            log::info!("Timed sequence replaced or stopped. Guild Id: {guild_id}");
+            break;
+        }
+
+        match play_audio_track(
+            data,
+            voice,
+            guild_id,
+            channel_id,
+            user_id,
+            UniqueAudioTableCol::Id(audio_id),
+            source,
+        )
+        .await
+        {
+            Ok(_) => result = result.map(|played| played + 1),
+            Err(
+                AudioError::AudioTrackNotFound { .. }
+                | AudioError::RateLimited { .. }
+                | AudioError::Quarantined { .. }
+                | AudioError::ChecksumMismatch { .. },
+            ) => {
+                log::info!("Skipping sound of timed sequence. Audio Id: {audio_id}");
+            }
+            Err(err) => {
+                result = Err(err);
+                break;
+            }
+        }
+    }
+
+    data.playback_queue.finish(guild_id, sequence_id);
+    result
+}
+
+/// Start hook incrementing the sound's play count and the play count of the user who played
+/// it, and recording the play event
+fn record_play_hook(
+    data: &UserData,
+    user_id: UserId,
+    audio_id: i64,
+    source: PlaySource,
+) -> impl Fn(&Playback) + Send + Sync + 'static {
+    let data = data.clone();
+    move |playback| {
+        // read-only mirrors leave recording plays to the primary instance
+        if db::is_read_only(&data.db_connection()) {
+            return;
+        }
+
+        data.audio_table()
+            .increment_play_count(audio_id)
+            .log_err()
+            .ok();
+
+        data.user_plays_table()
+            .increment_user_play_count(user_id.get(), audio_id)
+            .log_err()
+            .ok();
+
+        data.play_events_table()
+            .insert_play_event(&PlayEventInsert {
+                audio_id,
+                user_id: user_id.get(),
+                guild_id: Some(playback.guild_id.get()),
+                source: source.code().into(),
+            })
+            .log_err()
+            .ok();
+
+        sessions::record_play(
+            &data.sessions_table(),
+            playback.guild_id.get(),
+            user_id.get(),
+            audio_id,
+            chrono::Utc::now(),
+        )
+        .log_err_msg("Failed to record session play")
+        .ok();
+    }
+}
+
+/// Replay last sound played in guild, or the last sound user played when `mine`.
+/// Shared by `/replay` and replay buttons
+pub async fn replay_last_track(
+    data: &UserData,
+    voice: &dyn SongbirdHelper,
+    guild_id: GuildId,
+    channel_id: ChannelId,
+    user_id: UserId,
+    mine: bool,
+    source: PlaySource,
+) -> Result<AudioTableRow, AudioError> {
+    let audio_id = data
+        .last_played
+        .get(guild_id, mine.then_some(user_id))
+        .ok_or(AudioError::NothingToReplay)?;
+
+    play_audio_track(
+        data,
+        voice,
+        guild_id,
+        channel_id,
+        user_id,
+        UniqueAudioTableCol::Id(audio_id),
+        source,
+    )
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::Config;
+    use crate::db::{HistogramBucket, PlayEventsFilter};
+    use crate::test_utils::{AudioRowFixture, MockVoice, TempDir, TestDb, VoiceCall};
+
+    use super::*;
+
+    const GUILD_ID: GuildId = GuildId::new(1);
+    const CHANNEL_ID: ChannelId = ChannelId::new(2);
+    const USER_ID: UserId = UserId::new(3);
+
+    #[test]
+    fn name_similarity_test() {
+        assert_eq!(1.0, name_similarity("Bruh", "bruh"));
+        assert_eq!(0.0, name_similarity("Bruh", "xyz"));
+        assert!(name_similarity("brih moment", "Bruh Moment") > name_similarity("bruh", "Oof"));
+    }
+
+    #[test]
+    fn fuzzy_match_test() {
+        let table = TestDb::new().audio_table();
+        let bruh = AudioRowFixture::new().name("Bruh Moment").insert(&table);
+        let oof = AudioRowFixture::new().name("Oof").insert(&table);
+
+        match fuzzy_match("bruh momen", vec![oof, bruh]) {
+            FuzzyMatch::Best(row) => assert_eq!("Bruh Moment", row.name.as_str()),
+            _ => panic!("Expected best match"),
+        }
+
+        let beep = AudioRowFixture::new().name("Beep Boop").insert(&table);
+        let boop = AudioRowFixture::new().name("Boop Beep").insert(&table);
+        match fuzzy_match("beep", vec![boop, beep]) {
+            FuzzyMatch::Suggestions(rows) => assert_eq!(2, rows.len()),
+            _ => panic!("Expected suggestions"),
+        }
+
+        assert!(matches!(fuzzy_match("beep", vec![]), FuzzyMatch::None));
+    }
+
+    #[tokio::test]
+    async fn play_audio_track_by_name_test() {
+        let db = TestDb::new();
+        let data = db.user_data();
+        let row = AudioRowFixture::new()
+            .name("Beep")
+            .insert(&data.audio_table());
+        let voice = MockVoice::new();
+
+        let played = play_audio_track(
+            &data,
+            &voice,
+            GUILD_ID,
+            CHANNEL_ID,
+            USER_ID,
+            UniqueAudioTableCol::Name("Beep".into()),
+            PlaySource::Slash,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(row.id, played.id);
+        assert_eq!(
+            vec![VoiceCall::PlayAudio {
+                guild_id: GUILD_ID,
+                channel_id: CHANNEL_ID,
+                audio_file: row.audio_file.as_path_buf(),
+            }],
+            voice.calls()
+        );
+
+        let row = data
+            .audio_table()
+            .find_audio_row(UniqueAudioTableCol::Id(row.id))
+            .unwrap();
+        assert_eq!(1, row.play_count);
+        assert_eq!(
+            1,
+            data.user_plays_table()
+                .user_play_count(USER_ID.get(), row.id)
+                .unwrap()
+        );
+
+        let histogram = data
+            .play_events_table()
+            .play_histogram(
+                HistogramBucket::Day,
+                chrono::Utc::now() - chrono::Duration::days(1),
+                &PlayEventsFilter {
+                    audio_id: Some(row.id),
+                    guild_id: Some(GUILD_ID.get()),
+                },
+            )
+            .unwrap();
+        assert_eq!(1, histogram.iter().map(|(_, plays)| plays).sum::<i64>());
+    }
+
+    #[tokio::test]
+    async fn play_audio_track_loop_test() {
+        let db = TestDb::new();
+        let data = db.user_data();
+        let row = AudioRowFixture::new().insert(&data.audio_table());
+        let voice = MockVoice::new();
+
+        play_audio_track_with_options(
+            &data,
+            &voice,
+            GUILD_ID,
+            CHANNEL_ID,
+            USER_ID,
+            UniqueAudioTableCol::Id(row.id),
+            PlaySource::Slash,
+            PlayOptions {
+                loop_mode: Some(LoopMode::Times(3)),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            vec![
+                VoiceCall::PlayAudio {
+                    guild_id: GUILD_ID,
+                    channel_id: CHANNEL_ID,
+                    audio_file: row.audio_file.as_path_buf(),
+                },
+                VoiceCall::SetLoop {
+                    guild_id: GUILD_ID,
+                    mode: Some(LoopMode::Times(3)),
+                },
+            ],
+            voice.calls()
+        );
+        assert!(data.now_playing.is_looping(GUILD_ID));
+    }
+
+    #[test]
+    fn play_source_code_test() {
+        for source in PlaySource::ALL {
+            assert_eq!(Some(source), PlaySource::from_code(source.code()));
+        }
+        assert_eq!(None, PlaySource::from_code("unknown"));
+    }
+
+    #[tokio::test]
+    async fn play_audio_track_by_id_test() {
+        let db = TestDb::new();
+        let data = db.user_data();
+        let row = AudioRowFixture::new().insert(&data.audio_table());
+        let voice = MockVoice::new();
+
+        play_audio_track(
+            &data,
+            &voice,
+            GUILD_ID,
+            CHANNEL_ID,
+            USER_ID,
+            UniqueAudioTableCol::Id(row.id),
+            PlaySource::Slash,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(1, voice.calls().len());
+    }
+
+    #[tokio::test]
+    async fn play_audio_track_not_found_test() {
+        let db = TestDb::new();
+        let data = db.user_data();
+        let voice = MockVoice::new();
+
+        let result = play_audio_track(
+            &data,
+            &voice,
+            GUILD_ID,
+            CHANNEL_ID,
+            USER_ID,
+            UniqueAudioTableCol::Name("Missing".into()),
+            PlaySource::Slash,
+        )
+        .await;
+
+        assert!(matches!(result, Err(AudioError::AudioTrackNotFound { .. })));
+        assert!(voice.calls().is_empty());
+    }
+
+    #[tokio::test]
+    async fn play_audio_track_rate_limited_test() {
+        let db = TestDb::new();
+        let data = db.user_data_with_config(Config {
+            play_rate_limit_user: 1,
+            ..Config::default()
+        });
+        let row = AudioRowFixture::new().insert(&data.audio_table());
+        let voice = MockVoice::new();
+
+        for _ in 0..2 {
+            play_audio_track(
+                &data,
+                &voice,
+                GUILD_ID,
+                CHANNEL_ID,
+                USER_ID,
+                UniqueAudioTableCol::Id(row.id),
+                PlaySource::Slash,
+            )
+            .await
+            .ok();
+        }
+
+        let result = play_audio_track(
+            &data,
+            &voice,
+            GUILD_ID,
+            CHANNEL_ID,
+            USER_ID,
+            UniqueAudioTableCol::Id(row.id),
+            PlaySource::Slash,
+        )
+        .await;
+
+        assert!(matches!(result, Err(AudioError::RateLimited { .. })));
+        assert_eq!(1, voice.calls().len());
+    }
+
+    #[tokio::test]
+    async fn play_audio_track_quarantined_test() {
+        let audio_dir = TempDir::new();
+        let quarantine_dir = TempDir::new();
+        let db = TestDb::new();
+        let data = db.user_data_with_config(Config {
+            quarantine_dir: quarantine_dir.path().to_path_buf(),
+            ..Config::default()
+        });
+        let table = data.audio_table();
+        let row = AudioRowFixture::new().insert(&table);
+        let audio_file = audio_dir.audio_file("bruh.mp3");
+        table.replace_audio_file(row.id, &audio_file, 0, 0).unwrap();
+        table.set_content_hash(row.id, "ab").unwrap();
+        let voice = MockVoice::new();
+
+        let result = play_audio_track(
+            &data,
+            &voice,
+            GUILD_ID,
+            CHANNEL_ID,
+            USER_ID,
+            UniqueAudioTableCol::Id(row.id),
+            PlaySource::Slash,
+        )
+        .await;
+
+        assert!(matches!(result, Err(AudioError::Quarantined { .. })));
+        assert!(voice.calls().is_empty());
+        assert!(!audio_file.exists());
+    }
+
+    #[tokio::test]
+    async fn play_audio_track_not_in_voice_channel_test() {
+        let db = TestDb::new();
+        let data = db.user_data();
+        let row = AudioRowFixture::new().insert(&data.audio_table());
+        let voice = MockVoice::not_in_voice_channel();
+
+        let result = play_audio_track(
+            &data,
+            &voice,
+            GUILD_ID,
+            CHANNEL_ID,
+            USER_ID,
+            UniqueAudioTableCol::Id(row.id),
+            PlaySource::Slash,
+        )
+        .await;
+
+        assert!(matches!(result, Err(AudioError::NotInVoiceChannel)));
+
+        // play count isn't incremented when nothing played
+        let row = data
+            .audio_table()
+            .find_audio_row(UniqueAudioTableCol::Id(row.id))
+            .unwrap();
+        assert_eq!(0, row.play_count);
+    }
+
+    #[tokio::test]
+    async fn replay_last_track_test() {
+        let db = TestDb::new();
+        let data = db.user_data();
+        let beep = AudioRowFixture::new().insert(&data.audio_table());
+        let boop = AudioRowFixture::new().insert(&data.audio_table());
+        let voice = MockVoice::new();
+        let other_user_id = UserId::new(4);
+
+        let result = replay_last_track(
+            &data,
+            &voice,
+            GUILD_ID,
+            CHANNEL_ID,
+            USER_ID,
+            false,
+            PlaySource::Slash,
+        )
+        .await;
+        assert!(matches!(result, Err(AudioError::NothingToReplay)));
+
+        for (user_id, row) in [(USER_ID, &beep), (other_user_id, &boop)] {
+            play_audio_track(
+                &data,
+                &voice,
+                GUILD_ID,
+                CHANNEL_ID,
+                user_id,
+                UniqueAudioTableCol::Id(row.id),
+                PlaySource::Slash,
+            )
+            .await
+            .unwrap();
+        }
+
+        let replayed = replay_last_track(
+            &data,
+            &voice,
+            GUILD_ID,
+            CHANNEL_ID,
+            USER_ID,
+            true,
+            PlaySource::Slash,
+        )
+        .await
+        .unwrap();
+        assert_eq!(beep.id, replayed.id);
+
+        // the replay is the guild's last play, until another user plays a sound
+        play_audio_track(
+            &data,
+            &voice,
+            GUILD_ID,
+            CHANNEL_ID,
+            other_user_id,
+            UniqueAudioTableCol::Id(boop.id),
+            PlaySource::Slash,
+        )
+        .await
+        .unwrap();
+        let replayed = replay_last_track(
+            &data,
+            &voice,
+            GUILD_ID,
+            CHANNEL_ID,
+            USER_ID,
+            false,
+            PlaySource::Button,
+        )
+        .await
+        .unwrap();
+        assert_eq!(boop.id, replayed.id);
+
+        let result = replay_last_track(
+            &data,
+            &voice,
+            GuildId::new(9),
+            CHANNEL_ID,
+            USER_ID,
+            false,
+            PlaySource::Slash,
+        )
+        .await;
+        assert!(matches!(result, Err(AudioError::NothingToReplay)));
+    }
+
+    #[tokio::test]
+    async fn playback_hooks_test() {
+        let events = std::sync::Arc::new(Mutex::new(vec![]));
+        let hooks = || {
+            let (start, end, error) = (events.clone(), events.clone(), events.clone());
+            PlaybackHooks::new()
+                .on_start(move |_| start.lock().unwrap().push("start".to_string()))
+                .on_end(move |_| end.lock().unwrap().push("end".to_string()))
+                .on_error(move |_, err| error.lock().unwrap().push(format!("error: {err}")))
+        };
+        let audio_file = AudioRowFixture::new().build().audio_file;
+
+        MockVoice::new()
+            .play_audio_with_hooks(GUILD_ID, CHANNEL_ID, &audio_file, hooks())
+            .await
+            .unwrap();
+        assert!(MockVoice::not_in_voice_channel()
+            .play_audio_with_hooks(GUILD_ID, CHANNEL_ID, &audio_file, hooks())
+            .await
+            .is_err());
+        assert_eq!(
+            vec![
+                "start".to_string(),
+                format!("error: {}", AudioError::NotInVoiceChannel)
+            ],
+            *events.lock().unwrap()
+        );
+
+        let playback = Playback {
+            guild_id: GUILD_ID,
+            channel_id: CHANNEL_ID,
+            audio_file: audio_file.as_path_buf(),
+        };
+        hooks().ended(&playback);
+        assert_eq!(
+            Some("end"),
+            events.lock().unwrap().last().map(String::as_str)
+        );
+    }
+
+    #[test]
+    fn split_sound_names_test() {
+        assert_eq!(
+            vec!["bruh", "sad trombone"],
+            split_sound_names(" bruh,, sad trombone ,")
+        );
+        assert!(split_sound_names(" , ").is_empty());
+    }
+
+    #[test]
+    fn resolve_sound_names_test() {
+        let table = TestDb::new().audio_table();
+        let bruh = AudioRowFixture::new().name("Bruh Moment").insert(&table);
+        let oof = AudioRowFixture::new().name("Oof").insert(&table);
+
+        let names = vec!["Oof".to_string(), "xyz".into(), "bruh moment".into()];
+        let (rows, not_found) = resolve_sound_names(&table, &names).unwrap();
+        let ids: Vec<_> = rows.iter().map(|row| row.id).collect();
+        assert_eq!(vec![oof.id, bruh.id], ids);
+        assert_eq!(vec!["xyz".to_string()], not_found);
+
+        // autocompleted sound ids
+        let names = vec![format!("#{}", bruh.id), "#999".into()];
+        let (rows, not_found) = resolve_sound_names(&table, &names).unwrap();
+        assert_eq!(
+            vec![bruh.id],
+            rows.iter().map(|row| row.id).collect::<Vec<_>>()
+        );
+        assert_eq!(vec!["#999".to_string()], not_found);
+    }
+
+    #[test]
+    fn playback_queue_test() {
+        let queue = PlaybackQueue::new();
+
+        let first = queue.start(GUILD_ID);
+        let second = queue.start(GUILD_ID);
+        assert!(!queue.is_current(GUILD_ID, first));
+        assert!(queue.is_current(GUILD_ID, second));
+
+        // replaced sequence finishing leaves the running one alone
+        queue.finish(GUILD_ID, first);
+        assert!(queue.is_current(GUILD_ID, second));
+
+        queue.clear(GUILD_ID);
+        assert!(!queue.is_current(GUILD_ID, second));
+    }
+
+    #[tokio::test]
+    async fn play_sequence_not_in_voice_channel_test() {
+        let db = TestDb::new();
+        let data = db.user_data();
+        let row = AudioRowFixture::new().insert(&data.audio_table());
+
+        let result = play_sequence(
+            &data,
+            &MockVoice::not_in_voice_channel(),
+            GUILD_ID,
+            CHANNEL_ID,
+            USER_ID,
+            vec![row.id, row.id],
+            PlaySource::Slash,
+        )
+        .await;
+        assert!(matches!(result, Err(AudioError::NotInVoiceChannel)));
+        assert!(data.playback_queue.sequences.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn play_timed_test() {
+        let db = TestDb::new();
+        let data = db.user_data();
+        let beep = AudioRowFixture::new().insert(&data.audio_table());
+        let boop = AudioRowFixture::new().insert(&data.audio_table());
+        let voice = MockVoice::new();
+
+        let played = play_timed(
+            &data,
+            &voice,
+            GUILD_ID,
+            CHANNEL_ID,
+            USER_ID,
+            vec![
+                (Duration::ZERO, beep.id),
+                // deleted sound
+                (Duration::from_millis(5), -1),
+                (Duration::from_millis(10), boop.id),
+            ],
+            PlaySource::Slash,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(2, played);
+        assert_eq!(
+            vec![beep.audio_file.as_path_buf(), boop.audio_file.as_path_buf()],
+            voice
+                .calls()
+                .into_iter()
+                .filter_map(|call| match call {
+                    VoiceCall::PlayAudio { audio_file, .. } => Some(audio_file),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+        );
+        assert!(data.playback_queue.sequences.lock().unwrap().is_empty());
+    }
+
+    /// Records volumes, loop mode and pause state set
+    struct FakeTrack {
+        volumes: Mutex<Vec<f32>>,
+        loop_mode: Mutex<Option<LoopMode>>,
+        paused: Mutex<bool>,
+        finished: bool,
+    }
+
+    impl FakeTrack {
+        fn new(finished: bool) -> Arc<Self> {
+            Arc::new(Self {
+                volumes: Mutex::new(vec![]),
+                loop_mode: Mutex::new(None),
+                paused: Mutex::new(false),
+                finished,
+            })
+        }
+    }
+
+    impl TrackControl for FakeTrack {
+        fn set_volume(&self, volume: f32) -> bool {
+            self.volumes.lock().unwrap().push(volume);
+            !self.finished
+        }
+
+        fn set_loop(&self, mode: Option<LoopMode>) -> bool {
+            *self.loop_mode.lock().unwrap() = mode;
+            !self.finished
+        }
+
+        fn set_paused(&self, paused: bool) -> bool {
+            *self.paused.lock().unwrap() = paused;
+            !self.finished
+        }
+    }
+
+    #[tokio::test]
+    async fn playing_tracks_duck_test() {
+        let tracks = PlayingTracks::new();
+        let guild_id = GuildId::new(1);
+        let playing = FakeTrack::new(false);
+        let finished = FakeTrack::new(true);
+        let other_guild = FakeTrack::new(false);
+        tracks.register(guild_id, playing.clone());
+        tracks.register(guild_id, finished.clone());
+        tracks.register(GuildId::new(2), other_guild.clone());
+
+        let ducked = tracks.duck(guild_id, 0.2).await;
+        assert_eq!(1, ducked.len());
+        let volumes = playing.volumes.lock().unwrap().clone();
+        assert_eq!(Some(&0.2), volumes.last());
+        assert!(volumes.windows(2).all(|pair| pair[0] >= pair[1]));
+        assert_eq!(vec![1.0], *finished.volumes.lock().unwrap());
+        assert!(other_guild.volumes.lock().unwrap().is_empty());
+
+        tracks.restore(&ducked, 0.2).await;
+        assert_eq!(Some(&1.0), playing.volumes.lock().unwrap().last());
+
+        // finished tracks are dropped
+        assert_eq!(1, tracks.duck(guild_id, 0.2).await.len());
+        assert_eq!(1, finished.volumes.lock().unwrap().len());
+    }
+
+    #[test]
+    fn playing_tracks_register_test() {
+        let tracks = PlayingTracks::new();
+        let guild_id = GuildId::new(1);
+        for _ in 0..vars::DUCKING_TRACKS_LEN + 2 {
+            tracks.register(guild_id, FakeTrack::new(false));
+        }
+        tracks.register(GuildId::new(2), FakeTrack::new(false));
+
+        assert_eq!(vars::DUCKING_TRACKS_LEN, tracks.playing(guild_id).len());
+        assert_eq!(1, tracks.playing(GuildId::new(2)).len());
+    }
+
+    #[test]
+    fn loop_mode_parse_test() {
+        assert_eq!(Some(LoopMode::Times(3)), LoopMode::parse("3"));
+        assert_eq!(Some(LoopMode::Infinite), LoopMode::parse(" Infinite "));
+        assert_eq!(
+            Some(LoopMode::Times(vars::PLAY_LOOP_MAX)),
+            LoopMode::parse(&vars::PLAY_LOOP_MAX.to_string())
+        );
+        assert_eq!(None, LoopMode::parse("0"));
+        assert_eq!(
+            None,
+            LoopMode::parse(&(vars::PLAY_LOOP_MAX + 1).to_string())
+        );
+        assert_eq!(None, LoopMode::parse("forever"));
+    }
+
+    #[test]
+    fn playing_tracks_set_loop_test() {
+        let tracks = PlayingTracks::new();
+        let guild_id = GuildId::new(1);
+        assert!(!tracks.set_loop(guild_id, Some(LoopMode::Infinite)));
+
+        let first = FakeTrack::new(false);
+        let latest = FakeTrack::new(false);
+        tracks.register(guild_id, first.clone());
+        tracks.register(guild_id, latest.clone());
+
+        assert!(tracks.set_loop(guild_id, Some(LoopMode::Infinite)));
+        assert_eq!(Some(LoopMode::Infinite), *latest.loop_mode.lock().unwrap());
+        assert_eq!(None, *first.loop_mode.lock().unwrap());
+
+        assert!(tracks.set_loop(guild_id, None));
+        assert_eq!(None, *latest.loop_mode.lock().unwrap());
+
+        tracks.register(guild_id, FakeTrack::new(true));
+        assert!(!tracks.set_loop(guild_id, Some(LoopMode::Times(2))));
+    }
+
+    #[test]
+    fn priority_mode_code_test() {
+        for mode in PriorityMode::ALL {
+            assert_eq!(Some(mode), PriorityMode::from_code(mode.code()));
+        }
+        assert_eq!(None, PriorityMode::from_code("duck"));
+    }
+
+    #[test]
+    fn playing_tracks_pause_test() {
+        let tracks = PlayingTracks::new();
+        let guild_id = GuildId::new(1);
+        let playing = FakeTrack::new(false);
+        let other_guild = FakeTrack::new(false);
+        tracks.register(guild_id, playing.clone());
+        tracks.register(GuildId::new(2), other_guild.clone());
+
+        let paused = tracks.pause(guild_id);
+        assert_eq!(1, paused.len());
+        assert!(*playing.paused.lock().unwrap());
+        assert!(!*other_guild.paused.lock().unwrap());
+
+        tracks.resume(&paused);
+        assert!(!*playing.paused.lock().unwrap());
+    }
+
+    #[tokio::test]
+    async fn play_audio_track_priority_test() {
+        let db = TestDb::new();
+        let data = db.user_data();
+        let row = AudioRowFixture::new().insert(&data.audio_table());
+        let options = PlayOptions {
+            priority: true,
+            ..Default::default()
+        };
+        let play_call = VoiceCall::PlayAudio {
+            guild_id: GUILD_ID,
+            channel_id: CHANNEL_ID,
+            audio_file: row.audio_file.as_path_buf(),
+        };
+
+        let voice = MockVoice::new();
+        data.playback_queue.start(GUILD_ID);
+        play_audio_track_with_options(
+            &data,
+            &voice,
+            GUILD_ID,
+            CHANNEL_ID,
+            USER_ID,
+            UniqueAudioTableCol::Id(row.id),
+            PlaySource::Slash,
+            options,
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            vec![
+                VoiceCall::StopAudio { guild_id: GUILD_ID },
+                play_call.clone()
+            ],
+            voice.calls()
+        );
+        assert!(data.playback_queue.sequences.lock().unwrap().is_empty());
+
+        let table = data.guild_settings_table();
+        let mut settings = table.get_guild_settings(GUILD_ID.get()).unwrap();
+        settings.priority_mode = Some(PriorityMode::Pause.code().into());
+        table.update_guild_settings(&settings).unwrap();
+
+        let voice = MockVoice::new();
+        data.playback_queue.start(GUILD_ID);
+        play_audio_track_with_options(
+            &data,
+            &voice,
+            GUILD_ID,
+            CHANNEL_ID,
+            USER_ID,
+            UniqueAudioTableCol::Id(row.id),
+            PlaySource::Slash,
+            options,
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            vec![VoiceCall::PauseAudio { guild_id: GUILD_ID }, play_call],
+            voice.calls()
+        );
+        // paused sequences carry on once resumed
+        assert_eq!(1, data.playback_queue.sequences.lock().unwrap().len());
+    }
+
+    #[test]
+    fn is_silent_test() {
+        let db = TestDb::new();
+        let data = db.user_data();
+        assert!(!is_silent(&data, GUILD_ID, None));
+        assert!(is_silent(&data, GUILD_ID, Some(true)));
+
+        let table = data.guild_settings_table();
+        let mut settings = table.get_guild_settings(GUILD_ID.get()).unwrap();
+        settings.silent_play = true;
+        table.update_guild_settings(&settings).unwrap();
+        assert!(is_silent(&data, GUILD_ID, None));
+        assert!(!is_silent(&data, GUILD_ID, Some(false)));
+    }
+}
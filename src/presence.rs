@@ -0,0 +1,90 @@
+//! Bot presence rotating between the number of sounds, the most played sound of the week and
+//! the configured custom status (see `Config::status`), every `Config::presence_interval`
+use std::sync::Once;
+
+use serenity::all::ActivityData;
+use serenity::client::Context;
+
+use crate::common::LogResult;
+use crate::db::AudioTable;
+
+/// Activity texts to rotate between, from the current library stats
+pub fn activities(audio_table: &AudioTable, status: Option<&str>) -> Vec<String> {
+    let mut activities = vec![];
+
+    if let Ok(count) = audio_table.count_audio_rows(None).log_err() {
+        activities.push(format!("{count} sounds | /soundboard"));
+    }
+
+    let week_ago = chrono::Utc::now() - chrono::Duration::days(7);
+    if let Some(row) = audio_table.most_played_audio_row_since(week_ago) {
+        activities.push(format!("🔥 {} | /play", row.name));
+    }
+
+    if let Some(status) = status.map(str::trim).filter(|status| !status.is_empty()) {
+        activities.push(status.into());
+    }
+
+    activities
+}
+
+/// Start rotating the bot's presence. Only the first call starts it, so it's safe to call on
+/// every ready event
+pub fn spawn_presence_job(
+    ctx: Context,
+    db_pool: r2d2::Pool<r2d2_sqlite::SqliteConnectionManager>,
+    status: Option<String>,
+    interval: std::time::Duration,
+) {
+    static STARTED: Once = Once::new();
+
+    STARTED.call_once(|| {
+        log::info!("Starting presence job");
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(interval);
+            let mut index = 0;
+
+            loop {
+                interval.tick().await;
+
+                let Ok(conn) = db_pool.get().log_err_msg("Failed to get db connection") else {
+                    continue;
+                };
+                let activities = activities(&AudioTable::new(conn), status.as_deref());
+                if activities.is_empty() {
+                    continue;
+                }
+
+                let activity = &activities[index % activities.len()];
+                log::debug!("Setting presence - {activity}");
+                ctx.set_activity(Some(ActivityData::custom(activity)));
+                index += 1;
+            }
+        });
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::{AudioRowFixture, TestDb};
+
+    use super::*;
+
+    #[test]
+    fn activities_test() {
+        let table = TestDb::new().audio_table();
+        assert_eq!(
+            vec!["0 sounds | /soundboard"],
+            activities(&table, Some(" "))
+        );
+
+        let row = AudioRowFixture::new().name("Beep").insert(&table);
+        AudioRowFixture::new().insert(&table);
+        table.increment_play_count(row.id).unwrap();
+
+        assert_eq!(
+            vec!["2 sounds | /soundboard", "🔥 Beep | /play", "Use the force"],
+            activities(&table, Some("Use the force"))
+        );
+    }
+}
@@ -0,0 +1,468 @@
+//! Purging a user's data, for `/privacy forget-me` and `/owner purge-user`. Sounds the user
+//! added, trashed ones included, are kept for the guilds using them, but no longer name their
+//! author.
+//! Also author hashes of anonymous uploads (`/upload anonymous`), which let the uploader manage
+//! their sounds without storing who they are
+use std::path;
+
+use rusqlite::{params, OptionalExtension};
+use sha2::{Digest, Sha256};
+
+use crate::commands::PoiseError;
+use crate::common::{LogResult, UserData};
+use crate::db::{
+    AudioTable, DbConnection, FavoritesTable, JobsTable, PendingSoundsTable, PlayEventsTable,
+    SessionsTable, SettingsTable, ShareCodesTable, UserPlaysTable,
+};
+use crate::i18n::{tr, Language};
+use crate::trash;
+
+/// Hex encoded SHA-256 of user id, salted so it can't be reversed by hashing every user id
+pub fn author_hash(salt: &str, user_id: u64) -> String {
+    Sha256::digest(format!("{salt}:{user_id}"))
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+/// Author hash of user, salted with this bot's secret salt
+pub fn user_author_hash(data: &UserData, user_id: u64) -> Result<String, PoiseError> {
+    let salt = data.settings_table().author_hash_salt()?;
+    Ok(author_hash(&salt, user_id))
+}
+
+/// Number of rows purged per table
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PurgeSummary {
+    /// Sounds whose author fields or author hash were cleared
+    pub sounds_anonymized: usize,
+    /// Sounds awaiting review whose author fields were cleared
+    pub pending_sounds_anonymized: usize,
+    /// Trashed sounds whose snapshot's author fields or author hash were cleared
+    pub trashed_sounds_anonymized: usize,
+    pub favorites: usize,
+    pub play_events: usize,
+    pub user_plays: usize,
+    pub session_plays: usize,
+    /// Recorded sessions the user started, deleted with their plays
+    pub sessions: usize,
+    pub share_codes: usize,
+    /// Background jobs the user queued, including ones yet to run
+    pub jobs: usize,
+}
+
+/// Reply listing what was purged
+pub fn summary_message(lang: Language, summary: &PurgeSummary) -> String {
+    tr(
+        lang,
+        "privacy.purged",
+        &[
+            (
+                "sounds",
+                &(summary.sounds_anonymized
+                    + summary.pending_sounds_anonymized
+                    + summary.trashed_sounds_anonymized),
+            ),
+            ("favorites", &summary.favorites),
+            (
+                "plays",
+                &(summary.play_events + summary.user_plays + summary.session_plays),
+            ),
+        ],
+    )
+}
+
+/// Anonymize sounds authored or anonymously uploaded by user and delete their favorites, play
+/// history, sessions, share codes and jobs, in a single transaction. Sounds in trash_dir are
+/// anonymized before it commits
+pub fn purge_user(
+    conn: &DbConnection,
+    trash_dir: &path::Path,
+    user_id: u64,
+) -> Result<PurgeSummary, PoiseError> {
+    log::info!("Purging user data. User Id: {user_id}");
+
+    let audio_table_name = AudioTable::TABLE_NAME;
+    let pending_sounds_table_name = PendingSoundsTable::TABLE_NAME;
+    let favorites_table_name = FavoritesTable::TABLE_NAME;
+    let play_events_table_name = PlayEventsTable::TABLE_NAME;
+    let user_plays_table_name = UserPlaysTable::TABLE_NAME;
+    let session_plays_table_name = SessionsTable::PLAYS_TABLE_NAME;
+    let sessions_table_name = SessionsTable::TABLE_NAME;
+    let share_codes_table_name = ShareCodesTable::TABLE_NAME;
+    let jobs_table_name = JobsTable::TABLE_NAME;
+    let settings_table_name = SettingsTable::TABLE_NAME;
+
+    let tx = conn
+        .unchecked_transaction()
+        .log_err_msg("Failed to start purge user transaction")?;
+
+    // no salt yet means no anonymous uploads to clear
+    let salt: Option<String> = tx
+        .query_row(
+            format!("SELECT author_hash_salt FROM {settings_table_name} LIMIT 1").as_str(),
+            (),
+            |row| row.get(0),
+        )
+        .optional()
+        .log_err_msg("Failed to get author hash salt")?
+        .flatten();
+    let user_hash = salt.map(|salt| author_hash(&salt, user_id));
+
+    let mut sounds_anonymized = tx
+        .execute(
+            format!(
+                "
+                UPDATE {audio_table_name}
+                SET author_id = NULL, author_name = NULL, author_global_name = NULL
+                WHERE author_id = ?1"
+            )
+            .as_str(),
+            params![&user_id],
+        )
+        .log_err_msg("Failed to anonymize sounds of user")?;
+    let mut pending_sounds_anonymized = tx
+        .execute(
+            format!(
+                "
+                UPDATE {pending_sounds_table_name}
+                SET author_id = NULL, author_name = NULL, author_global_name = NULL
+                WHERE author_id = ?1"
+            )
+            .as_str(),
+            params![&user_id],
+        )
+        .log_err_msg("Failed to anonymize pending sounds of user")?;
+    if let Some(user_hash) = &user_hash {
+        sounds_anonymized += tx
+            .execute(
+                format!("UPDATE {audio_table_name} SET author_hash = NULL WHERE author_hash = ?1")
+                    .as_str(),
+                params![user_hash],
+            )
+            .log_err_msg("Failed to clear author hash of sounds of user")?;
+        pending_sounds_anonymized += tx
+            .execute(
+                format!(
+                    "UPDATE {pending_sounds_table_name} SET author_hash = NULL WHERE author_hash = ?1"
+                )
+                .as_str(),
+                params![user_hash],
+            )
+            .log_err_msg("Failed to clear author hash of pending sounds of user")?;
+    }
+    let favorites = tx
+        .execute(
+            format!("DELETE FROM {favorites_table_name} WHERE user_id = ?1").as_str(),
+            params![&user_id],
+        )
+        .log_err_msg("Failed to delete favorites of user")?;
+    let play_events = tx
+        .execute(
+            format!("DELETE FROM {play_events_table_name} WHERE user_id = ?1").as_str(),
+            params![&user_id],
+        )
+        .log_err_msg("Failed to delete play events of user")?;
+    let user_plays = tx
+        .execute(
+            format!("DELETE FROM {user_plays_table_name} WHERE user_id = ?1").as_str(),
+            params![&user_id],
+        )
+        .log_err_msg("Failed to delete play counts of user")?;
+    let session_plays = tx
+        .execute(
+            format!("DELETE FROM {session_plays_table_name} WHERE user_id = ?1").as_str(),
+            params![&user_id],
+        )
+        .log_err_msg("Failed to delete session plays of user")?;
+    let sessions = tx
+        .execute(
+            format!("DELETE FROM {sessions_table_name} WHERE started_by = ?1").as_str(),
+            params![&user_id],
+        )
+        .log_err_msg("Failed to delete sessions of user")?;
+    let share_codes = tx
+        .execute(
+            format!("DELETE FROM {share_codes_table_name} WHERE created_by = ?1").as_str(),
+            params![&user_id],
+        )
+        .log_err_msg("Failed to delete share codes of user")?;
+    let jobs = tx
+        .execute(
+            format!("DELETE FROM {jobs_table_name} WHERE user_id = ?1").as_str(),
+            params![&user_id],
+        )
+        .log_err_msg("Failed to delete jobs of user")?;
+    let trashed_sounds_anonymized =
+        trash::anonymize_author(trash_dir, user_id, user_hash.as_deref())
+            .log_err_msg("Failed to anonymize trashed sounds of user")?;
+
+    tx.commit()
+        .log_err_msg("Failed to commit purge user transaction")?;
+
+    Ok(PurgeSummary {
+        sounds_anonymized,
+        pending_sounds_anonymized,
+        trashed_sounds_anonymized,
+        favorites,
+        play_events,
+        user_plays,
+        session_plays,
+        sessions,
+        share_codes,
+        jobs,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::db::{
+        JobInsert, PlayEventInsert, SessionPlayRow, ShareCodeRow, UniqueAudioTableCol,
+    };
+    use crate::jobs::Job;
+    use crate::test_utils::{AudioRowFixture, TempDir, TestDb};
+
+    use super::*;
+
+    #[test]
+    fn author_hash_test() {
+        let hash = author_hash("salt", 1);
+        assert_eq!(64, hash.len());
+        assert_eq!(hash, author_hash("salt", 1));
+        assert_ne!(hash, author_hash("salt", 2));
+        assert_ne!(hash, author_hash("pepper", 1));
+
+        let data = TestDb::new().user_data();
+        assert_eq!(
+            user_author_hash(&data, 1).unwrap(),
+            user_author_hash(&data, 1).unwrap()
+        );
+    }
+
+    #[test]
+    fn summary_message_test() {
+        let summary = PurgeSummary {
+            sounds_anonymized: 1,
+            pending_sounds_anonymized: 0,
+            trashed_sounds_anonymized: 1,
+            favorites: 2,
+            play_events: 3,
+            user_plays: 4,
+            session_plays: 5,
+            sessions: 8,
+            share_codes: 6,
+            jobs: 7,
+        };
+        assert_eq!(
+            "Forgot user. Removed author from 2 sounds, deleted 2 favorites and 12 play records",
+            summary_message(Language::English, &summary)
+        );
+    }
+
+    #[test]
+    fn purge_user_test() {
+        let db = TestDb::new();
+        let table = db.audio_table();
+        let mine = AudioRowFixture::new().author_id(1).insert(&table);
+        let theirs = AudioRowFixture::new().author_id(2).insert(&table);
+        let pending_id = db
+            .pending_sounds_table()
+            .insert_pending_sound(&AudioRowFixture::new().author_id(1).build())
+            .unwrap();
+        db.pending_sounds_table()
+            .insert_pending_sound(&AudioRowFixture::new().author_id(2).build())
+            .unwrap();
+        let hash = user_author_hash(&db.user_data(), 1).unwrap();
+        let anonymous = AudioRowFixture::new().author_hash(&hash).insert(&table);
+        db.pending_sounds_table()
+            .insert_pending_sound(&AudioRowFixture::new().author_hash(&hash).build())
+            .unwrap();
+
+        for user_id in [1, 2] {
+            db.favorites_table()
+                .add_favorite(user_id, theirs.id)
+                .unwrap();
+            db.user_plays_table()
+                .increment_user_play_count(user_id, theirs.id)
+                .unwrap();
+            db.play_events_table()
+                .insert_play_event(&PlayEventInsert {
+                    audio_id: theirs.id,
+                    user_id,
+                    guild_id: None,
+                    source: "slash".into(),
+                })
+                .unwrap();
+            db.share_codes_table()
+                .insert_share_code(&ShareCodeRow {
+                    code: format!("CODE{user_id}"),
+                    audio_id: theirs.id,
+                    guild_id: user_id,
+                    created_by: user_id,
+                    created_at: chrono::Utc::now(),
+                })
+                .unwrap();
+            db.jobs_table()
+                .insert_job(&JobInsert {
+                    job: Job::Backup.to_json(),
+                    guild_id: None,
+                    channel_id: None,
+                    user_id,
+                })
+                .unwrap();
+        }
+        let session_id = db
+            .sessions_table()
+            .start_session(1, 2, chrono::Utc::now())
+            .unwrap();
+        for user_id in [1, 2] {
+            db.sessions_table()
+                .insert_session_play(&SessionPlayRow {
+                    session_id,
+                    audio_id: theirs.id,
+                    user_id,
+                    offset_ms: 0,
+                })
+                .unwrap();
+        }
+        let started_session_id = db
+            .sessions_table()
+            .start_session(3, 1, chrono::Utc::now())
+            .unwrap();
+
+        let audio_dir = TempDir::new();
+        let trash_dir = TempDir::new();
+        let trash = |fixture: AudioRowFixture, name: &str| {
+            let mut insert = fixture.name(name).build();
+            insert.author_name = insert.author_id.map(|_| "bruh".into());
+            insert.audio_file = audio_dir.audio_file(format!("{name}.mp3"));
+            std::fs::write(insert.audio_file.as_path(), b"beep").unwrap();
+            table.insert_audio_row(&insert).unwrap();
+            let row = table
+                .find_audio_row(UniqueAudioTableCol::Name(name.into()))
+                .unwrap();
+            trash::trash_audio_row(&table, trash_dir.path(), &row, chrono::Utc::now()).unwrap();
+        };
+        trash(AudioRowFixture::new().author_id(1), "trashed-mine");
+        trash(
+            AudioRowFixture::new().author_hash(&hash),
+            "trashed-anonymous",
+        );
+        trash(AudioRowFixture::new().author_id(2), "trashed-theirs");
+
+        assert_eq!(
+            PurgeSummary {
+                sounds_anonymized: 2,
+                pending_sounds_anonymized: 2,
+                trashed_sounds_anonymized: 2,
+                favorites: 1,
+                play_events: 1,
+                user_plays: 1,
+                session_plays: 1,
+                sessions: 1,
+                share_codes: 1,
+                jobs: 1,
+            },
+            purge_user(&db.connection(), trash_dir.path(), 1).unwrap()
+        );
+
+        // sound is kept without its author
+        let find = |id| table.find_audio_row(UniqueAudioTableCol::Id(id)).unwrap();
+        assert_eq!(None, find(mine.id).author_id);
+        assert_eq!(None, find(anonymous.id).author_hash);
+        assert_eq!(Some(2), find(theirs.id).author_id);
+        let pending = db
+            .pending_sounds_table()
+            .find_pending_sound(pending_id)
+            .unwrap()
+            .unwrap();
+        assert_eq!(None, pending.author_id);
+
+        assert!(!db.favorites_table().is_favorite(1, theirs.id).unwrap());
+        assert!(db.favorites_table().is_favorite(2, theirs.id).unwrap());
+        assert_eq!(
+            0,
+            db.user_plays_table().user_play_count(1, theirs.id).unwrap()
+        );
+        assert_eq!(
+            1,
+            db.user_plays_table().user_play_count(2, theirs.id).unwrap()
+        );
+        assert!(db
+            .sessions_table()
+            .find_session(started_session_id, 3)
+            .unwrap()
+            .is_none());
+        assert!(db
+            .sessions_table()
+            .find_session(session_id, 1)
+            .unwrap()
+            .is_some());
+        assert!(db
+            .share_codes_table()
+            .find_share_code("CODE1")
+            .unwrap()
+            .is_none());
+        assert!(db
+            .share_codes_table()
+            .find_share_code("CODE2")
+            .unwrap()
+            .is_some());
+
+        // restoring trashed sounds doesn't bring their author back
+        for entry in trash::trashed_sounds(trash_dir.path()) {
+            assert_eq!(
+                trash::RestoreOutcome::Restored,
+                trash::restore(&table, audio_dir.path(), &entry).unwrap()
+            );
+        }
+        let find_name = |name: &str| {
+            table
+                .find_audio_row(UniqueAudioTableCol::Name(name.into()))
+                .unwrap()
+        };
+        let restored = find_name("trashed-mine");
+        assert_eq!(None, restored.author_id);
+        assert_eq!(None, restored.author_name);
+        assert_eq!(None, find_name("trashed-anonymous").author_hash);
+        assert_eq!(Some(2), find_name("trashed-theirs").author_id);
+        assert_eq!(
+            Some("bruh".to_string()),
+            find_name("trashed-theirs").author_name
+        );
+
+        // no row references the user anymore
+        let conn = db.connection();
+        let count = |table: &str, column: &str, value: &dyn rusqlite::ToSql| -> i64 {
+            conn.query_row(
+                format!("SELECT COUNT(*) FROM {table} WHERE {column} = ?1").as_str(),
+                params![value],
+                |row| row.get(0),
+            )
+            .unwrap()
+        };
+        for (table, column) in [
+            (AudioTable::TABLE_NAME, "author_id"),
+            (PendingSoundsTable::TABLE_NAME, "author_id"),
+            (FavoritesTable::TABLE_NAME, "user_id"),
+            (PlayEventsTable::TABLE_NAME, "user_id"),
+            (UserPlaysTable::TABLE_NAME, "user_id"),
+            (SessionsTable::PLAYS_TABLE_NAME, "user_id"),
+            (SessionsTable::TABLE_NAME, "started_by"),
+            (ShareCodesTable::TABLE_NAME, "created_by"),
+            (JobsTable::TABLE_NAME, "user_id"),
+        ] {
+            assert_eq!(0, count(table, column, &1), "{table}.{column}");
+            assert!(count(table, column, &2) > 0, "{table}.{column}");
+        }
+        for table in [AudioTable::TABLE_NAME, PendingSoundsTable::TABLE_NAME] {
+            assert_eq!(0, count(table, "author_hash", &hash), "{table}.author_hash");
+        }
+
+        // nothing left to purge
+        assert_eq!(
+            PurgeSummary::default(),
+            purge_user(&db.connection(), trash_dir.path(), 1).unwrap()
+        );
+    }
+}
@@ -0,0 +1,188 @@
+//! Pruning of unused sounds. `/admin prune-suggestions` lists sounds not played for a number
+//! of months with buttons to archive or keep each, and guilds with an auto-archive policy
+//! (`/settings auto-archive`) have unused sounds archived periodically
+use std::sync::Once;
+use std::time::Duration;
+
+use chrono::{DateTime, Months, Utc};
+use poise::CreateReply;
+use serenity::all::{ButtonStyle, CreateActionRow, CreateButton, GuildId};
+
+use crate::commands::PoiseError;
+use crate::common::LogResult;
+use crate::db::{AudioTable, AudioTableRow, GuildSettingsTable};
+use crate::helpers::{truncate_button_label, ButtonAction, ButtonCustomId};
+use crate::i18n::{tr, Language};
+use crate::vars;
+
+/// Sounds not played since this time are unused
+pub fn unused_since(now: DateTime<Utc>, months: u32) -> DateTime<Utc> {
+    now.checked_sub_months(Months::new(months))
+        .unwrap_or(DateTime::<Utc>::MIN_UTC)
+}
+
+/// Prune suggestions reply, listing unused sounds with an archive and keep button each
+pub fn suggestions_reply(
+    lang: Language,
+    guild_id: GuildId,
+    months: u32,
+    rows: &[AudioTableRow],
+) -> CreateReply {
+    if rows.is_empty() {
+        return CreateReply::default().ephemeral(true).content(tr(
+            lang,
+            "prune.none",
+            &[("months", &months)],
+        ));
+    }
+
+    let never = tr(lang, "prune.never_played", &[]);
+    let lines: Vec<_> = rows
+        .iter()
+        .map(|row| {
+            let last_played = row
+                .last_played_at
+                .map(|last_played_at| last_played_at.format("%Y-%m-%d").to_string())
+                .unwrap_or_else(|| never.clone());
+            format!("- `{}` - {last_played}", row.name)
+        })
+        .collect();
+
+    let components = rows
+        .iter()
+        .map(|row| {
+            CreateActionRow::Buttons(vec![
+                CreateButton::new(ButtonCustomId::new(
+                    Some(guild_id),
+                    ButtonAction::Archive(row.id),
+                ))
+                .label(truncate_button_label(tr(
+                    lang,
+                    "prune.archive",
+                    &[("track", &row.name)],
+                )))
+                .style(ButtonStyle::Danger),
+                CreateButton::new(ButtonCustomId::new(
+                    Some(guild_id),
+                    ButtonAction::Keep(row.id),
+                ))
+                .label(tr(lang, "prune.keep", &[]))
+                .style(ButtonStyle::Secondary),
+            ])
+        })
+        .collect();
+
+    CreateReply::default()
+        .ephemeral(true)
+        .content(format!(
+            "{}\n{}",
+            tr(lang, "prune.title", &[("months", &months)]),
+            lines.join("\n")
+        ))
+        .components(components)
+}
+
+/// Archive unused sounds of guilds with an auto-archive policy. Returns number of sounds archived
+pub fn auto_archive(
+    db_pool: &r2d2::Pool<r2d2_sqlite::SqliteConnectionManager>,
+    now: DateTime<Utc>,
+) -> Result<usize, PoiseError> {
+    let guild_settings = GuildSettingsTable::new(db_pool.get()?).auto_archive_guild_settings()?;
+    let table = AudioTable::new(db_pool.get()?);
+    let mut archived = 0;
+
+    for settings in guild_settings {
+        let Some(months) = settings.auto_archive_months else {
+            continue;
+        };
+
+        let rows = table.unused_audio_rows(
+            settings.guild_id,
+            unused_since(now, months),
+            vars::AUTO_ARCHIVE_BATCH_LIMIT,
+        )?;
+        for row in rows {
+            log::info!(
+                "Auto-archiving unused sound. Guild Id: {}, Track: {}",
+                settings.guild_id,
+                row.name
+            );
+            table.set_archived(row.id, true)?;
+            archived += 1;
+        }
+    }
+
+    Ok(archived)
+}
+
+/// Start the auto-archive job. Only the first call starts it, so it's safe to call on every
+/// ready event
+pub fn spawn_auto_archive_job(db_pool: r2d2::Pool<r2d2_sqlite::SqliteConnectionManager>) {
+    static STARTED: Once = Once::new();
+
+    STARTED.call_once(|| {
+        log::info!("Starting auto-archive job");
+        tokio::spawn(async move {
+            let mut interval =
+                tokio::time::interval(Duration::from_secs(vars::AUTO_ARCHIVE_JOB_INTERVAL_SECS));
+
+            loop {
+                interval.tick().await;
+                auto_archive(&db_pool, Utc::now())
+                    .log_err_msg("Failed to auto-archive unused sounds")
+                    .ok();
+            }
+        });
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use crate::db::{GuildSettingsRow, UniqueAudioTableCol};
+    use crate::test_utils::{AudioRowFixture, TestDb};
+
+    use super::*;
+
+    #[test]
+    fn unused_since_test() {
+        let now = Utc.with_ymd_and_hms(2024, 7, 31, 12, 0, 0).unwrap();
+        assert_eq!(
+            Utc.with_ymd_and_hms(2024, 1, 31, 12, 0, 0).unwrap(),
+            unused_since(now, 6)
+        );
+        // clamped to the last day of shorter months
+        assert_eq!(
+            Utc.with_ymd_and_hms(2024, 6, 30, 12, 0, 0).unwrap(),
+            unused_since(now, 1)
+        );
+    }
+
+    #[test]
+    fn auto_archive_test() {
+        let db = TestDb::new();
+        let data = db.user_data();
+        let table = data.audio_table();
+        let long_ago = Utc::now() - chrono::Duration::days(365);
+        let unused = AudioRowFixture::new().created_at(long_ago).insert(&table);
+        let recent = AudioRowFixture::new().insert(&table);
+
+        // no guild has an auto-archive policy
+        assert_eq!(0, auto_archive(&data.db_pool, Utc::now()).unwrap());
+
+        data.guild_settings_table()
+            .update_guild_settings(&GuildSettingsRow {
+                guild_id: 1,
+                auto_archive_months: Some(3),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(1, auto_archive(&data.db_pool, Utc::now()).unwrap());
+        assert_eq!(0, auto_archive(&data.db_pool, Utc::now()).unwrap());
+
+        let find = |id| table.find_audio_row(UniqueAudioTableCol::Id(id)).unwrap();
+        assert!(find(unused.id).archived_at.is_some());
+        assert!(find(recent.id).archived_at.is_none());
+    }
+}
@@ -0,0 +1,290 @@
+use std::collections::{HashMap, VecDeque};
+use std::hash::Hash;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use serenity::all::{GuildId, UserId};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+use crate::config::Config;
+
+/// Sliding window rate limiter allowing at most `max` hits per key within `window`.
+/// A `max` of zero disables the limiter
+pub struct RateLimiter<K> {
+    max: usize,
+    window: Duration,
+    hits: Mutex<HashMap<K, VecDeque<Instant>>>,
+}
+
+impl<K: Eq + Hash> RateLimiter<K> {
+    pub fn new(max: usize, window: Duration) -> Self {
+        Self {
+            max,
+            window,
+            hits: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Time until key can be hit again, or None if key isn't limited
+    pub fn retry_after(&self, key: &K, now: Instant) -> Option<Duration> {
+        if self.max == 0 {
+            return None;
+        }
+
+        let mut hits = self.hits.lock().unwrap();
+        let key_hits = hits.get_mut(key)?;
+        Self::prune(key_hits, self.window, now);
+
+        match key_hits.len() >= self.max {
+            true => key_hits
+                .front()
+                .map(|oldest| self.window.saturating_sub(now.duration_since(*oldest))),
+            false => None,
+        }
+    }
+
+    pub fn hit(&self, key: K, now: Instant) {
+        if self.max == 0 {
+            return;
+        }
+
+        let mut hits = self.hits.lock().unwrap();
+        // forget keys that have gone quiet, so the map doesn't grow unbounded
+        hits.retain(|_, key_hits| {
+            Self::prune(key_hits, self.window, now);
+            !key_hits.is_empty()
+        });
+        hits.entry(key).or_default().push_back(now);
+    }
+
+    fn prune(key_hits: &mut VecDeque<Instant>, window: Duration, now: Instant) {
+        while let Some(oldest) = key_hits.front() {
+            match now.duration_since(*oldest) >= window {
+                true => key_hits.pop_front(),
+                false => break,
+            };
+        }
+    }
+}
+
+/// Limits how often sounds can be played per user and per guild, to stop soundboard spam wars
+pub struct PlayRateLimiter {
+    user: RateLimiter<UserId>,
+    guild: RateLimiter<GuildId>,
+}
+
+impl PlayRateLimiter {
+    pub fn new(config: &Config) -> Self {
+        Self {
+            user: RateLimiter::new(
+                config.play_rate_limit_user,
+                config.play_rate_limit_user_window,
+            ),
+            guild: RateLimiter::new(
+                config.play_rate_limit_guild,
+                config.play_rate_limit_guild_window,
+            ),
+        }
+    }
+
+    /// Record a play if neither user nor guild is rate limited.
+    /// Otherwise returns the time until the user can play again
+    pub fn check(&self, guild_id: GuildId, user_id: UserId) -> Result<(), Duration> {
+        let now = Instant::now();
+
+        let retry_after = [
+            self.user.retry_after(&user_id, now),
+            self.guild.retry_after(&guild_id, now),
+        ]
+        .into_iter()
+        .flatten()
+        .max();
+
+        match retry_after {
+            Some(retry_after) => Err(retry_after),
+            None => {
+                self.user.hit(user_id, now);
+                self.guild.hit(guild_id, now);
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Limits how many sounds are downloaded and validated at once across all guilds, so many
+/// users adding sounds at the same time can't exhaust disk I/O, temp space or CPU. Ingests
+/// over the limit wait in line for a slot
+pub struct IngestLimiter {
+    semaphore: Arc<Semaphore>,
+    queued: AtomicUsize,
+}
+
+impl IngestLimiter {
+    pub fn new(config: &Config) -> Self {
+        Self {
+            semaphore: Arc::new(Semaphore::new(config.max_concurrent_ingests.max(1))),
+            queued: AtomicUsize::new(0),
+        }
+    }
+
+    /// Number of ingests waiting for a slot
+    pub fn queued(&self) -> usize {
+        self.queued.load(Ordering::SeqCst)
+    }
+
+    /// Whether an ingest started now has to wait for a slot
+    pub fn is_full(&self) -> bool {
+        self.semaphore.available_permits() == 0
+    }
+
+    /// Wait for an ingest slot. The slot is freed when the permit is dropped
+    pub async fn acquire(&self) -> OwnedSemaphorePermit {
+        // leaves the line when dropped, even if the ingest is cancelled while waiting
+        let _queued = QueuedGuard::new(&self.queued);
+        self.semaphore
+            .clone()
+            .acquire_owned()
+            .await
+            .expect("Ingest semaphore is never closed")
+    }
+}
+
+/// Counts an ingest as queued for as long as it's alive
+struct QueuedGuard<'a>(&'a AtomicUsize);
+
+impl<'a> QueuedGuard<'a> {
+    fn new(queued: &'a AtomicUsize) -> Self {
+        queued.fetch_add(1, Ordering::SeqCst);
+        Self(queued)
+    }
+}
+
+impl Drop for QueuedGuard<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn ingest_limiter_test() {
+        let config = Config {
+            max_concurrent_ingests: 1,
+            ..Config::default()
+        };
+        let limiter = Arc::new(IngestLimiter::new(&config));
+        assert!(!limiter.is_full());
+
+        let permit = limiter.acquire().await;
+        assert!(limiter.is_full());
+        assert_eq!(0, limiter.queued());
+
+        // second ingest waits in line until the first is done
+        let waiting = tokio::spawn({
+            let limiter = limiter.clone();
+            async move {
+                let _permit = limiter.acquire().await;
+            }
+        });
+        while limiter.queued() == 0 {
+            tokio::task::yield_now().await;
+        }
+        assert!(!waiting.is_finished());
+
+        drop(permit);
+        waiting.await.unwrap();
+        assert_eq!(0, limiter.queued());
+        assert!(!limiter.is_full());
+    }
+
+    #[tokio::test]
+    async fn ingest_limiter_cancelled_test() {
+        let config = Config {
+            max_concurrent_ingests: 1,
+            ..Config::default()
+        };
+        let limiter = Arc::new(IngestLimiter::new(&config));
+        let permit = limiter.acquire().await;
+
+        // ingest cancelled while waiting in line leaves the line
+        let waiting = tokio::spawn({
+            let limiter = limiter.clone();
+            async move {
+                let _permit = limiter.acquire().await;
+            }
+        });
+        while limiter.queued() == 0 {
+            tokio::task::yield_now().await;
+        }
+        waiting.abort();
+        assert!(waiting.await.unwrap_err().is_cancelled());
+        assert_eq!(0, limiter.queued());
+
+        // pending acquire dropped before it's polled to completion
+        {
+            let pending = limiter.acquire();
+            let mut pending = std::pin::pin!(pending);
+            assert!(futures::poll!(pending.as_mut()).is_pending());
+            assert_eq!(1, limiter.queued());
+        }
+        assert_eq!(0, limiter.queued());
+
+        drop(permit);
+        assert!(!limiter.is_full());
+    }
+
+    #[test]
+    fn rate_limiter_test() {
+        let limiter = RateLimiter::new(2, Duration::from_secs(10));
+        let start = Instant::now();
+
+        assert_eq!(None, limiter.retry_after(&1, start));
+        limiter.hit(1, start);
+        limiter.hit(1, start + Duration::from_secs(2));
+
+        // third hit within window is limited until the oldest hit expires
+        assert_eq!(
+            Some(Duration::from_secs(7)),
+            limiter.retry_after(&1, start + Duration::from_secs(3))
+        );
+        assert_eq!(None, limiter.retry_after(&2, start));
+        assert_eq!(
+            None,
+            limiter.retry_after(&1, start + Duration::from_secs(10))
+        );
+    }
+
+    #[test]
+    fn rate_limiter_disabled_test() {
+        let limiter = RateLimiter::new(0, Duration::from_secs(10));
+        let now = Instant::now();
+
+        for _ in 0..10 {
+            limiter.hit(1, now);
+        }
+        assert_eq!(None, limiter.retry_after(&1, now));
+    }
+
+    #[test]
+    fn play_rate_limiter_test() {
+        let config = Config {
+            play_rate_limit_user: 1,
+            play_rate_limit_guild: 2,
+            ..Config::default()
+        };
+        let limiter = PlayRateLimiter::new(&config);
+        let guild_id = GuildId::new(1);
+
+        assert!(limiter.check(guild_id, UserId::new(10)).is_ok());
+        assert!(limiter.check(guild_id, UserId::new(10)).is_err());
+
+        // limited user's rejected play doesn't count against the guild
+        assert!(limiter.check(guild_id, UserId::new(20)).is_ok());
+        assert!(limiter.check(guild_id, UserId::new(30)).is_err());
+        assert!(limiter.check(GuildId::new(2), UserId::new(30)).is_ok());
+    }
+}
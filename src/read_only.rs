@@ -0,0 +1,83 @@
+//! Read-only mirror mode (`Config::read_only`), for hot standbys and spreading playback of
+//! large deployments over instances. A mirror shares the primary instance's database and audio
+//! dir, but opens the database read-only (see `db::open_flags`), so every write is rejected by
+//! SQLite. Mirrors don't create tables, run migrations or background jobs, record plays or
+//! onboard guilds, and refuse commands and buttons that write
+use poise::CreateReply;
+
+use crate::commands::{PoiseContext, PoiseError};
+use crate::helpers::{poise_check_msg, ButtonAction};
+use crate::i18n::{self, tr};
+use crate::vars;
+
+/// Command (by qualified name) only reads, so mirrors serve it
+pub fn is_read_only_command(qualified_name: &str) -> bool {
+    vars::READ_ONLY_COMMANDS.contains(&qualified_name)
+}
+
+/// Button action only reads (plays aren't recorded by mirrors), so mirrors serve it
+pub fn is_read_only_action(action: &ButtonAction) -> bool {
+    matches!(
+        action,
+        ButtonAction::Play(_)
+            | ButtonAction::PageNav { .. }
+            | ButtonAction::TagFilter
+            | ButtonAction::Stop
+            | ButtonAction::Replay
+            | ButtonAction::Repeat
+            | ButtonAction::Confirm(_)
+            | ButtonAction::Cancel(_)
+            | ButtonAction::Listen(_)
+    )
+}
+
+/// Command check refusing commands that write on mirrors
+pub async fn check_command(ctx: PoiseContext<'_>) -> Result<bool, PoiseError> {
+    let qualified_name = &ctx.command().qualified_name;
+    if !ctx.data().config().read_only || is_read_only_command(qualified_name) {
+        return Ok(true);
+    }
+
+    log::info!("Refusing command on read-only mirror - {qualified_name}");
+    let content = tr(i18n::language(ctx), "read_only.refused", &[]);
+    poise_check_msg(
+        ctx.send(CreateReply::default().ephemeral(true).content(content))
+            .await,
+    );
+    Ok(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::db;
+    use crate::test_utils::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn read_only_test() {
+        assert!(is_read_only_command("soundboard view"));
+        assert!(!is_read_only_command("sounds remove"));
+        assert!(is_read_only_action(&ButtonAction::Play(1)));
+        assert!(!is_read_only_action(&ButtonAction::Favorite(1)));
+    }
+
+    #[test]
+    fn open_flags_test() {
+        let dir = TempDir::new();
+        let db_file = dir.path().join("bot.db3");
+
+        let conn = rusqlite::Connection::open_with_flags(&db_file, db::open_flags(false)).unwrap();
+        conn.execute_batch("CREATE TABLE t (id INTEGER)").unwrap();
+        assert!(!db::is_read_only(&conn));
+
+        let conn = rusqlite::Connection::open_with_flags(&db_file, db::open_flags(true)).unwrap();
+        assert!(db::is_read_only(&conn));
+        assert!(conn.execute("INSERT INTO t (id) VALUES (1)", ()).is_err());
+        assert_eq!(
+            0,
+            conn.query_row("SELECT COUNT(*) FROM t", (), |row| row.get::<_, i64>(0))
+                .unwrap()
+        );
+    }
+}
@@ -0,0 +1,276 @@
+//! Permission and intent self-check of each guild, on connect and when the bot joins a guild.
+//! The bot needs Connect and Speak in the configured voice channels, and Send Messages and Use
+//! Application Commands in the configured text channels (or server wide when none are
+//! configured). The guild owner is sent a direct message about anything missing, once per
+//! distinct set of problems
+use serenity::all::{
+    ChannelId, CreateMessage, Guild, GuildChannel, Http, Member, Permissions, UserId,
+};
+use tokio::sync::OnceCell;
+
+use crate::channel_restrictions::ChannelKind;
+use crate::commands::PoiseError;
+use crate::common::{LogResult, UserData};
+use crate::doctor;
+use crate::i18n::{self, tr, Language};
+
+/// Permissions needed in voice channels sounds are played in
+pub const VOICE_PERMISSIONS: Permissions = Permissions::CONNECT.union(Permissions::SPEAK);
+/// Permissions needed in text channels the bot is used or posts in
+pub const TEXT_PERMISSIONS: Permissions =
+    Permissions::SEND_MESSAGES.union(Permissions::USE_APPLICATION_COMMANDS);
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Problem {
+    /// Permissions missing in a configured channel
+    Channel {
+        channel_id: ChannelId,
+        missing: Permissions,
+    },
+    /// Permissions missing server wide, checked when no channels of a kind are configured
+    Guild { missing: Permissions },
+    /// The privileged message content intent isn't enabled, so prefix commands can't be read
+    MessageContentIntent,
+}
+
+impl Problem {
+    fn key(&self) -> String {
+        match self {
+            Self::Channel {
+                channel_id,
+                missing,
+            } => format!("c{channel_id}:{}", missing.bits()),
+            Self::Guild { missing } => format!("g:{}", missing.bits()),
+            Self::MessageContentIntent => "intent".into(),
+        }
+    }
+
+    fn describe(&self, lang: Language) -> String {
+        let permission_names =
+            |permissions: &Permissions| permissions.get_permission_names().join(", ");
+
+        match self {
+            Self::Channel {
+                channel_id,
+                missing,
+            } => tr(
+                lang,
+                "self_check.channel",
+                &[
+                    ("permissions", &permission_names(missing)),
+                    ("channel", &format!("<#{channel_id}>")),
+                ],
+            ),
+            Self::Guild { missing } => tr(
+                lang,
+                "self_check.guild",
+                &[("permissions", &permission_names(missing))],
+            ),
+            Self::MessageContentIntent => tr(lang, "self_check.intent", &[]),
+        }
+    }
+}
+
+/// Stored in `GuildSettingsRow::self_check_warning`, so owners are only warned again when
+/// the problems change. None when there are no problems
+pub fn fingerprint(problems: &[Problem]) -> Option<String> {
+    match problems.is_empty() {
+        true => None,
+        false => Some(
+            problems
+                .iter()
+                .map(Problem::key)
+                .collect::<Vec<_>>()
+                .join(","),
+        ),
+    }
+}
+
+pub fn warning_message(lang: Language, guild_name: &str, problems: &[Problem]) -> String {
+    let problems = problems
+        .iter()
+        .map(|problem| format!("- {}", problem.describe(lang)))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    tr(
+        lang,
+        "self_check.warning",
+        &[("guild", &guild_name), ("problems", &problems)],
+    )
+}
+
+/// Permissions of `required` member lacks in each of channels, or server wide when no channels
+/// are given. Channels the guild doesn't have (anymore) are skipped
+pub fn missing_permissions(
+    guild: &Guild,
+    member: &Member,
+    channel_ids: &[ChannelId],
+    required: Permissions,
+) -> Vec<Problem> {
+    if channel_ids.is_empty() {
+        let missing = required - guild.member_permissions(member);
+        return match missing.is_empty() {
+            true => vec![],
+            false => vec![Problem::Guild { missing }],
+        };
+    }
+
+    channel_ids
+        .iter()
+        .filter_map(|channel_id| guild.channels.get(channel_id))
+        .filter_map(|channel: &GuildChannel| {
+            let missing = required - guild.user_permissions_in(channel, member);
+            (!missing.is_empty()).then_some(Problem::Channel {
+                channel_id: channel.id,
+                missing,
+            })
+        })
+        .collect()
+}
+
+/// Whether the message content intent is enabled. Fetched once, as it's the same for every
+/// guild. Assumed enabled when it can't be fetched, so owners aren't warned about nothing
+async fn message_content_intent_enabled(http: &Http) -> bool {
+    static ENABLED: OnceCell<bool> = OnceCell::const_new();
+
+    *ENABLED
+        .get_or_init(|| async {
+            doctor::message_content_intent_enabled(http)
+                .await
+                .log_err_msg("Failed to fetch application flags")
+                .unwrap_or(true)
+        })
+        .await
+}
+
+/// Check bot's permissions in guild and intents, and message the guild owner about problems
+/// they weren't warned about yet
+pub async fn check_guild(
+    http: &Http,
+    data: &UserData,
+    guild: &Guild,
+    bot_user_id: UserId,
+) -> Result<(), PoiseError> {
+    let member = match guild.members.get(&bot_user_id) {
+        Some(member) => member.clone(),
+        None => http.get_member(guild.id, bot_user_id).await?,
+    };
+
+    let table = data.guild_settings_table();
+    let mut settings = table.get_guild_settings(guild.id.get())?;
+    let restrictions_table = data.channel_restrictions_table();
+
+    let mut voice_channel_ids =
+        restrictions_table.channel_ids(guild.id.get(), ChannelKind::Voice.key())?;
+    voice_channel_ids.extend(settings.sotd_voice_channel_id);
+
+    let mut text_channel_ids =
+        restrictions_table.channel_ids(guild.id.get(), ChannelKind::Text.key())?;
+    text_channel_ids.extend(
+        [
+            settings.sotd_channel_id,
+            settings.digest_channel_id,
+            settings.review_channel_id,
+        ]
+        .into_iter()
+        .flatten(),
+    );
+
+    let to_channel_ids = |mut channel_ids: Vec<u64>| {
+        channel_ids.sort();
+        channel_ids.dedup();
+        channel_ids
+            .into_iter()
+            .map(ChannelId::new)
+            .collect::<Vec<_>>()
+    };
+
+    let mut problems = missing_permissions(
+        guild,
+        &member,
+        &to_channel_ids(voice_channel_ids),
+        VOICE_PERMISSIONS,
+    );
+    problems.extend(missing_permissions(
+        guild,
+        &member,
+        &to_channel_ids(text_channel_ids),
+        TEXT_PERMISSIONS,
+    ));
+    if !message_content_intent_enabled(http).await {
+        problems.push(Problem::MessageContentIntent);
+    }
+
+    let fingerprint = fingerprint(&problems);
+    if fingerprint == settings.self_check_warning {
+        return Ok(());
+    }
+    settings.self_check_warning = fingerprint;
+    table.update_guild_settings(&settings)?;
+
+    if problems.is_empty() {
+        return Ok(());
+    }
+
+    log::warn!(
+        "Bot is missing permissions or intents. Guild Id: {}, Problems: {problems:?}",
+        guild.id
+    );
+    let lang = i18n::settings_language(Some(&settings), Some(guild.preferred_locale.as_str()));
+    guild
+        .owner_id
+        .create_dm_channel(http)
+        .await?
+        .send_message(
+            http,
+            CreateMessage::new().content(warning_message(lang, &guild.name, &problems)),
+        )
+        .await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fingerprint_test() {
+        assert_eq!(None, fingerprint(&[]));
+        assert_eq!(
+            Some("c10:3145728,g:2048,intent".into()),
+            fingerprint(&[
+                Problem::Channel {
+                    channel_id: ChannelId::new(10),
+                    missing: VOICE_PERMISSIONS,
+                },
+                Problem::Guild {
+                    missing: Permissions::SEND_MESSAGES,
+                },
+                Problem::MessageContentIntent,
+            ])
+        );
+    }
+
+    #[test]
+    fn warning_message_test() {
+        let message = warning_message(
+            Language::English,
+            "Cool Server",
+            &[
+                Problem::Channel {
+                    channel_id: ChannelId::new(10),
+                    missing: Permissions::SPEAK,
+                },
+                Problem::Guild {
+                    missing: Permissions::SEND_MESSAGES,
+                },
+            ],
+        );
+
+        assert!(message.contains("**Cool Server**"));
+        assert!(message.contains("- Speak in <#10>"));
+        assert!(message.contains("- Send Messages server wide"));
+    }
+}
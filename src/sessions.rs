@@ -0,0 +1,139 @@
+//! Soundboard session recording. `/record start` records every sound played in the guild, by
+//! whom and how long after the start, until `/record stop`. `/record play` replays a recorded
+//! session with its original timing (see `playback::play_timed`)
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+
+use crate::commands::PoiseError;
+use crate::db::{SessionPlayRow, SessionRow, SessionsTable};
+use crate::i18n::{tr, Language};
+use crate::vars;
+
+/// Record play in guild's session, if one is being recorded and isn't full yet
+pub fn record_play(
+    table: &SessionsTable,
+    guild_id: u64,
+    user_id: u64,
+    audio_id: i64,
+    now: DateTime<Utc>,
+) -> Result<(), PoiseError> {
+    let Some(session) = table.active_session(guild_id)? else {
+        return Ok(());
+    };
+
+    if table.count_session_plays(session.id)? >= vars::SESSION_MAX_PLAYS {
+        log::warn!(
+            "Session full, not recording play. Session Id: {}",
+            session.id
+        );
+        return Ok(());
+    }
+
+    table.insert_session_play(&SessionPlayRow {
+        session_id: session.id,
+        audio_id,
+        user_id,
+        offset_ms: (now - session.started_at).num_milliseconds().max(0),
+    })
+}
+
+/// Offsets and audio ids of session's plays, to replay with `playback::play_timed`
+pub fn timeline(plays: &[SessionPlayRow]) -> Vec<(Duration, i64)> {
+    plays
+        .iter()
+        .map(|play| (Duration::from_millis(play.offset_ms as u64), play.audio_id))
+        .collect()
+}
+
+/// Session length, up to now for sessions still being recorded
+pub fn session_duration(session: &SessionRow, now: DateTime<Utc>) -> Duration {
+    (session.ended_at.unwrap_or(now) - session.started_at)
+        .to_std()
+        .unwrap_or_default()
+}
+
+/// Duration like `1m 05s`
+pub fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    match secs / 60 {
+        0 => format!("{secs}s"),
+        mins => format!("{mins}m {:02}s", secs % 60),
+    }
+}
+
+/// Line of `/record list`
+pub fn list_entry(lang: Language, session: &SessionRow, plays: u64, now: DateTime<Utc>) -> String {
+    let key = match session.ended_at {
+        Some(_) => "record.list_entry",
+        None => "record.list_entry_recording",
+    };
+
+    tr(
+        lang,
+        key,
+        &[
+            ("id", &session.id),
+            ("date", &session.started_at.format("%Y-%m-%d %H:%M")),
+            ("plays", &plays),
+            ("duration", &format_duration(session_duration(session, now))),
+            ("user", &format!("<@{}>", session.started_by)),
+        ],
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::TestDb;
+
+    use super::*;
+
+    #[test]
+    fn record_play_test() {
+        let table = TestDb::new().sessions_table();
+        let now = Utc::now();
+
+        // not recording
+        record_play(&table, 1, 10, 5, now).unwrap();
+
+        let id = table.start_session(1, 10, now).unwrap();
+        record_play(&table, 1, 11, 5, now + chrono::Duration::milliseconds(1500)).unwrap();
+        record_play(&table, 2, 11, 6, now).unwrap();
+
+        assert_eq!(
+            vec![SessionPlayRow {
+                session_id: id,
+                audio_id: 5,
+                user_id: 11,
+                offset_ms: 1500,
+            }],
+            table.session_plays(id).unwrap()
+        );
+
+        table.end_session(id, now).unwrap();
+        record_play(&table, 1, 11, 5, now).unwrap();
+        assert_eq!(1, table.count_session_plays(id).unwrap());
+    }
+
+    #[test]
+    fn timeline_test() {
+        let plays = [(0, 5), (1500, 6)].map(|(offset_ms, audio_id)| SessionPlayRow {
+            session_id: 1,
+            audio_id,
+            user_id: 10,
+            offset_ms,
+        });
+
+        assert_eq!(
+            vec![(Duration::ZERO, 5), (Duration::from_millis(1500), 6)],
+            timeline(&plays)
+        );
+    }
+
+    #[test]
+    fn format_duration_test() {
+        assert_eq!("0s", format_duration(Duration::from_millis(900)));
+        assert_eq!("42s", format_duration(Duration::from_secs(42)));
+        assert_eq!("2m 05s", format_duration(Duration::from_secs(125)));
+    }
+}
@@ -0,0 +1,382 @@
+//! Sharing sounds across guilds. `/sounds share` gives a sound a short share code, which
+//! `/sounds import-code` in another guild turns into a copy of the sound (audio file, tags and
+//! author). The sound library is shared by every guild, so only sounds no longer in it (i.e.
+//! archived ones) are copied, checked like `/sounds add` checks new sounds
+use serenity::all::Http;
+
+use crate::audio::AudioFile;
+use crate::commands::PoiseError;
+use crate::common::{LogResult, UserData};
+use crate::db::{AudioTableRowInsert, ShareCodeRow, SoundName, Tags, UniqueAudioTableCol};
+use crate::moderation::{self, ModeratedSound};
+use crate::packs::file_sha256;
+use crate::temp_files::TempFileGuard;
+use crate::{approval, blocklist, helpers, loudness, vars};
+
+/// Share code chars. Leaves out 0/O and 1/I, which are easily mixed up when typed
+const SHARE_CODE_ALPHABET: &[u8] = b"ABCDEFGHJKLMNPQRSTUVWXYZ23456789";
+
+/// Outcome of importing a shared sound
+#[derive(Debug, PartialEq)]
+pub enum ImportOutcome {
+    /// Sound was copied, named as given
+    Imported(SoundName),
+    /// A sound with the same audio is in the library already, named as given
+    AlreadyAdded(String),
+    /// Copy was submitted to the review channel, named as given
+    Submitted(SoundName),
+    /// Copy was flagged by loudness checks or moderation and submitted for review
+    Flagged { name: SoundName, reason: String },
+    /// Copy was refused (e.g. its name is blocklisted), with a reply saying why
+    Refused(String),
+    /// No sound is shared with the code
+    NotFound,
+    /// Sound was shared from the importing guild
+    SameGuild,
+    /// Sound is longer than the importing guild's max length, in milliseconds
+    TooLong { duration_ms: u64, max_ms: u64 },
+}
+
+/// Random `vars::SHARE_CODE_LEN` chars share code
+pub fn generate_share_code() -> String {
+    uuid::Uuid::new_v4()
+        .as_bytes()
+        .iter()
+        .take(vars::SHARE_CODE_LEN)
+        .map(|byte| SHARE_CODE_ALPHABET[*byte as usize % SHARE_CODE_ALPHABET.len()] as char)
+        .collect()
+}
+
+/// Share code as typed, with case, whitespace and dashes ignored
+pub fn normalize_share_code(code: &str) -> String {
+    code.chars()
+        .filter(|c| !c.is_whitespace() && *c != '-')
+        .collect::<String>()
+        .to_uppercase()
+}
+
+/// Share code of sound shared from guild. Sounds keep their code, so sharing one again gives
+/// the same code
+pub fn share_sound(
+    data: &UserData,
+    audio_id: i64,
+    guild_id: u64,
+    user_id: u64,
+) -> Result<String, PoiseError> {
+    let table = data.share_codes_table();
+    if let Some(share_code) = table.find_audio_share_code(audio_id, guild_id)? {
+        return Ok(share_code.code);
+    }
+
+    let code = loop {
+        let code = generate_share_code();
+        if table.find_share_code(&code)?.is_none() {
+            break code;
+        }
+    };
+    table.insert_share_code(&ShareCodeRow {
+        code: code.clone(),
+        audio_id,
+        guild_id,
+        created_by: user_id,
+        created_at: chrono::Utc::now(),
+    })?;
+
+    Ok(code)
+}
+
+/// Copy sound shared with code into guild, keeping its author, unless a sound with the same
+/// audio is in the library already. The copy goes through the blocklist, loudness checks,
+/// moderation and review like `/sounds add`, with its own audio file. Sound names are unique,
+/// so copies are numbered
+pub async fn import_shared_sound(
+    http: &Http,
+    data: &UserData,
+    code: &str,
+    guild_id: u64,
+    review_channel_id: Option<u64>,
+) -> Result<ImportOutcome, PoiseError> {
+    let code = normalize_share_code(code);
+    let Some(share_code) = data.share_codes_table().find_share_code(&code)? else {
+        return Ok(ImportOutcome::NotFound);
+    };
+    if share_code.guild_id == guild_id {
+        return Ok(ImportOutcome::SameGuild);
+    }
+
+    let table = data.audio_table();
+    let Some(row) = table.find_audio_row(UniqueAudioTableCol::Id(share_code.audio_id)) else {
+        return Ok(ImportOutcome::NotFound);
+    };
+    log::info!(
+        "Importing shared sound. Code: {code}, Name: {}, Guild Id: {guild_id}",
+        row.name
+    );
+
+    // the library is shared by every guild, so only archived sounds need copying
+    if row.archived_at.is_none() {
+        return Ok(ImportOutcome::AlreadyAdded(row.name.to_string()));
+    }
+    let content_hash = match &row.content_hash {
+        Some(content_hash) => content_hash.clone(),
+        None => file_sha256(row.audio_file.as_path())?,
+    };
+    if let Some(added) = table
+        .audio_rows_by_content_hash(&content_hash)?
+        .into_iter()
+        .find(|added| added.archived_at.is_none())
+    {
+        return Ok(ImportOutcome::AlreadyAdded(added.name.to_string()));
+    }
+
+    let max_ms = data.max_audio_duration(Some(guild_id)).as_millis() as u64;
+    if let Some(duration_ms) = row.duration_ms.filter(|duration_ms| *duration_ms > max_ms) {
+        return Ok(ImportOutcome::TooLong {
+            duration_ms,
+            max_ms,
+        });
+    }
+
+    let name = helpers::numbered_name(&row.name, |name| {
+        table
+            .find_audio_row(UniqueAudioTableCol::Name(name.into()))
+            .is_some()
+    });
+    let name = SoundName::new(name)?;
+    if let Err(reply) = blocklist::check_text(data, Some(guild_id), &name) {
+        return Ok(ImportOutcome::Refused(reply));
+    }
+
+    let extension = row
+        .audio_file
+        .extension()
+        .map(|extension| extension.to_string_lossy().to_string())
+        .unwrap_or_else(|| "mp3".into());
+    let temp_file = TempFileGuard::with_extension(extension)?;
+    std::fs::copy(row.audio_file.as_path(), &*temp_file)
+        .log_err_msg("Failed to copy shared sound's audio file")?;
+
+    // loudness is checked first, as attenuated sounds are moderated as they'll be played
+    let sound = ModeratedSound {
+        name: &name,
+        audio_file: &temp_file,
+        guild_id: Some(guild_id),
+        duration_ms: row.duration_ms.unwrap_or_default(),
+    };
+    let flagged = match loudness::check_upload(data, &sound).await {
+        Ok(None) => moderation::check_upload(data, &sound).await,
+        checked => checked,
+    };
+    let flagged = match flagged {
+        Ok(flagged) => flagged,
+        Err(reply) => return Ok(ImportOutcome::Refused(reply)),
+    };
+
+    let placed_file = data.move_file_to_audio_dir(&*temp_file)?;
+    let row_insert = AudioTableRowInsert {
+        name: name.clone(),
+        tags: Tags::from(row.tags.to_string()),
+        audio_file: AudioFile::new(placed_file.to_path_buf()),
+        created_at: chrono::Utc::now(),
+        author_id: row.author_id,
+        author_name: row.author_name,
+        author_global_name: row.author_global_name,
+        author_hash: row.author_hash,
+        guild_id: Some(guild_id),
+        duration_ms: row.duration_ms,
+        file_size: row.file_size,
+        source_url: row.source_url,
+    };
+
+    match (flagged, review_channel_id) {
+        (Some(flagged), _) => {
+            approval::submit(
+                http,
+                data,
+                flagged.review_channel_id,
+                row_insert,
+                placed_file,
+                Some(&flagged.reason),
+            )
+            .await?;
+            Ok(ImportOutcome::Flagged {
+                name,
+                reason: flagged.reason,
+            })
+        }
+        (None, Some(review_channel_id)) => {
+            approval::submit(http, data, review_channel_id, row_insert, placed_file, None).await?;
+            Ok(ImportOutcome::Submitted(name))
+        }
+        (None, None) => {
+            // the copy's own hash, as attenuation may have changed it. Importing again finds
+            // the copy by it
+            let copy_hash = file_sha256(&placed_file)?;
+            table.insert_audio_row(row_insert)?;
+            placed_file.keep();
+            if let Some(copy) = table.find_audio_row(UniqueAudioTableCol::Name(name.to_string())) {
+                table.set_content_hash(copy.id, &copy_hash)?;
+            }
+            data.autocomplete_cache.invalidate();
+            Ok(ImportOutcome::Imported(name))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::Config;
+    use crate::db::BlocklistTableRow;
+    use crate::test_utils::{AudioRowFixture, TempDir, TestDb};
+
+    use super::*;
+
+    #[test]
+    fn generate_share_code_test() {
+        let code = generate_share_code();
+        assert_eq!(vars::SHARE_CODE_LEN, code.len());
+        assert!(code.bytes().all(|c| SHARE_CODE_ALPHABET.contains(&c)));
+
+        assert_eq!("ABCD2345", normalize_share_code(" abcd-2345 "));
+    }
+
+    #[tokio::test]
+    async fn import_shared_sound_test() {
+        let db = TestDb::new();
+        let audio_dir = TempDir::new();
+        let data = db.user_data_with_config(Config {
+            audio_dir: audio_dir.path().to_path_buf(),
+            ..Default::default()
+        });
+        let http = Http::new("");
+
+        let mut sound = AudioRowFixture::new()
+            .name("Beep")
+            .tags("droid")
+            .author_id(7)
+            .guild_id(1)
+            .build();
+        sound.audio_file = audio_dir.audio_file("beep.mp3");
+        db.audio_table().insert_audio_row(sound).unwrap();
+        let row = db
+            .audio_table()
+            .find_audio_row(UniqueAudioTableCol::Name("Beep".into()))
+            .unwrap();
+
+        let code = share_sound(&data, row.id, 1, 7).unwrap();
+        assert_eq!(code, share_sound(&data, row.id, 1, 8).unwrap());
+
+        assert_eq!(
+            ImportOutcome::SameGuild,
+            import_shared_sound(&http, &data, &code, 1, None)
+                .await
+                .unwrap()
+        );
+        assert_eq!(
+            ImportOutcome::NotFound,
+            import_shared_sound(&http, &data, "ZZZZZZZZ", 2, None)
+                .await
+                .unwrap()
+        );
+
+        // every guild sees the library's sounds, so there's nothing to copy
+        assert_eq!(
+            ImportOutcome::AlreadyAdded("Beep".into()),
+            import_shared_sound(&http, &data, &code, 2, None)
+                .await
+                .unwrap()
+        );
+        assert_eq!(1, db.audio_table().count_audio_rows(None).unwrap());
+
+        db.audio_table().set_archived(row.id, true).unwrap();
+        let outcome = import_shared_sound(&http, &data, &code.to_lowercase(), 2, None)
+            .await
+            .unwrap();
+        assert_eq!(
+            ImportOutcome::Imported(SoundName::new("Beep-2").unwrap()),
+            outcome
+        );
+
+        let copy = db
+            .audio_table()
+            .find_audio_row(UniqueAudioTableCol::Name("Beep-2".into()))
+            .unwrap();
+        assert_eq!(Some(2), copy.guild_id);
+        assert_eq!(Some(7), copy.author_id);
+        assert_eq!("droid", copy.tags.to_string());
+        assert_ne!(row.audio_file, copy.audio_file);
+        assert!(copy.audio_file.exists());
+        assert_eq!(
+            ImportOutcome::AlreadyAdded("Beep-2".into()),
+            import_shared_sound(&http, &data, &code, 3, None)
+                .await
+                .unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn import_shared_sound_refused_test() {
+        let db = TestDb::new();
+        let audio_dir = TempDir::new();
+        let data = db.user_data_with_config(Config {
+            audio_dir: audio_dir.path().to_path_buf(),
+            ..Default::default()
+        });
+        let mut sound = AudioRowFixture::new().name("Beep").guild_id(1).build();
+        sound.audio_file = audio_dir.audio_file("beep.mp3");
+        db.audio_table().insert_audio_row(sound).unwrap();
+        let row = db
+            .audio_table()
+            .find_audio_row(UniqueAudioTableCol::Name("Beep".into()))
+            .unwrap();
+        db.audio_table().set_archived(row.id, true).unwrap();
+        let code = share_sound(&data, row.id, 1, 7).unwrap();
+
+        db.blocklist_table()
+            .add_pattern(
+                2,
+                &BlocklistTableRow {
+                    pattern: "beep".into(),
+                    is_regex: false,
+                },
+            )
+            .unwrap();
+
+        let outcome = import_shared_sound(&Http::new(""), &data, &code, 2, None)
+            .await
+            .unwrap();
+        assert!(matches!(outcome, ImportOutcome::Refused(_)));
+        assert!(db
+            .audio_table()
+            .find_audio_row(UniqueAudioTableCol::Name("Beep-2".into()))
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn import_shared_sound_too_long_test() {
+        let db = TestDb::new();
+        let data = db.user_data();
+        let row = AudioRowFixture::new()
+            .guild_id(1)
+            .metadata(6000, 24_000)
+            .insert(&db.audio_table());
+        db.audio_table().set_content_hash(row.id, "ab").unwrap();
+        db.audio_table().set_archived(row.id, true).unwrap();
+        let code = share_sound(&data, row.id, 1, 7).unwrap();
+
+        let table = db.guild_settings_table();
+        let mut settings = table.get_guild_settings(2).unwrap();
+        settings.max_duration_ms = Some(5000);
+        table.update_guild_settings(&settings).unwrap();
+
+        assert_eq!(
+            ImportOutcome::TooLong {
+                duration_ms: 6000,
+                max_ms: 5000
+            },
+            import_shared_sound(&Http::new(""), &data, &code, 2, None)
+                .await
+                .unwrap()
+        );
+    }
+}
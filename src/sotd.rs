@@ -0,0 +1,303 @@
+//! Sound of the day. Once a day, at each guild's `/settings sotd` time, a random or least
+//! recently played sound is posted in the configured text channel with a play button, and
+//! optionally played in the configured voice channel. With `Config::duck_announcements`, sounds
+//! already playing there are ducked while it plays
+use std::sync::Once;
+use std::time::Duration;
+
+use chrono::{DateTime, NaiveTime, Utc};
+use serenity::all::{
+    ButtonStyle, ChannelId, CreateActionRow, CreateButton, CreateMessage, GuildId,
+};
+use serenity::client::Context;
+
+use crate::commands::PoiseResult;
+use crate::common::LogResult;
+use crate::db::{AudioTable, AudioTableRow, DbConnection, GuildSettingsRow, GuildSettingsTable};
+use crate::helpers::{self, ButtonAction, ButtonCustomId, SongbirdHelper};
+use crate::i18n::{self, tr};
+use crate::vars;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, poise::ChoiceParameter)]
+pub enum SotdMode {
+    #[default]
+    #[name = "Random"]
+    Random,
+    #[name = "Least recently played"]
+    LeastPlayed,
+}
+
+impl SotdMode {
+    pub const ALL: [SotdMode; 2] = [SotdMode::Random, SotdMode::LeastPlayed];
+
+    /// Code stored in `GuildSettingsRow::sotd_mode`
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Random => "random",
+            Self::LeastPlayed => "least-played",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|mode| mode.code() == code)
+    }
+}
+
+/// Whether guild's sound of the day is due to be posted at `now`
+pub fn is_due(settings: &GuildSettingsRow, now: DateTime<Utc>) -> bool {
+    let time = settings.sotd_time.unwrap_or(NaiveTime::MIN);
+
+    settings.sotd_channel_id.is_some()
+        && settings.sotd_last_date != Some(now.date_naive())
+        && now.time() >= time
+}
+
+/// Pick guild's sound of the day. Least recently played mode never picks the previous
+/// sound of the day, since posting it doesn't count as playing it
+pub fn pick_sound(table: &AudioTable, settings: &GuildSettingsRow) -> Option<AudioTableRow> {
+    let mode = settings
+        .sotd_mode
+        .as_deref()
+        .and_then(SotdMode::from_code)
+        .unwrap_or_default();
+
+    match mode {
+        SotdMode::Random => table.random_audio_row(None),
+        SotdMode::LeastPlayed => table.least_recently_played_audio_row(settings.sotd_last_audio_id),
+    }
+}
+
+/// Start the sound of the day job. Only the first call starts it, so it's safe to call on
+/// every ready event
+pub fn spawn_sotd_job(
+    ctx: Context,
+    db_pool: r2d2::Pool<r2d2_sqlite::SqliteConnectionManager>,
+    duck_announcements: bool,
+) {
+    static STARTED: Once = Once::new();
+
+    STARTED.call_once(|| {
+        log::info!("Starting sound of the day job");
+        tokio::spawn(
+            SotdJob {
+                ctx,
+                db_pool,
+                duck_announcements,
+            }
+            .run(),
+        );
+    });
+}
+
+struct SotdJob {
+    ctx: Context,
+    db_pool: r2d2::Pool<r2d2_sqlite::SqliteConnectionManager>,
+    duck_announcements: bool,
+}
+
+impl SotdJob {
+    async fn run(self) {
+        let mut interval = tokio::time::interval(Duration::from_secs(vars::SOTD_JOB_INTERVAL_SECS));
+
+        loop {
+            interval.tick().await;
+            self.post_due(Utc::now()).await;
+        }
+    }
+
+    fn db_connection(&self) -> DbConnection {
+        self.db_pool
+            .get()
+            .expect("Failed to get Pooled SQLite connection")
+    }
+
+    async fn post_due(&self, now: DateTime<Utc>) {
+        let table = GuildSettingsTable::new(self.db_connection());
+        let Ok(guild_settings) = table.sotd_guild_settings() else {
+            return;
+        };
+
+        for mut settings in guild_settings
+            .into_iter()
+            .filter(|settings| is_due(settings, now))
+        {
+            let row = pick_sound(&AudioTable::new(self.db_connection()), &settings);
+
+            // recorded before posting, so failed posts aren't retried every tick
+            settings.sotd_last_date = Some(now.date_naive());
+            if let Some(ref row) = row {
+                settings.sotd_last_audio_id = Some(row.id);
+            }
+            table.update_guild_settings(&settings).ok();
+
+            let Some(row) = row else {
+                log::info!("No sounds to post as sound of the day");
+                continue;
+            };
+
+            self.post(&settings, &row)
+                .await
+                .log_err_msg(format!(
+                    "Failed to post sound of the day. Guild Id: {}",
+                    settings.guild_id
+                ))
+                .ok();
+        }
+    }
+
+    async fn post(&self, settings: &GuildSettingsRow, row: &AudioTableRow) -> PoiseResult {
+        let guild_id = GuildId::new(settings.guild_id);
+        let channel_id = ChannelId::new(settings.sotd_channel_id.ok_or("No sotd channel id")?);
+        log::info!(
+            "Posting sound of the day. Guild Id: {guild_id}, Channel Id: {channel_id}, Track: {}",
+            row.name
+        );
+
+        let guild_locale = self
+            .ctx
+            .cache
+            .guild(guild_id)
+            .map(|guild| guild.preferred_locale.clone());
+        let lang = i18n::settings_language(Some(settings), guild_locale.as_deref());
+
+        let buttons = CreateActionRow::Buttons(vec![
+            CreateButton::new(ButtonCustomId::new(
+                Some(guild_id),
+                ButtonAction::Play(row.id),
+            ))
+            .label(tr(lang, "sotd.play", &[]))
+            .style(ButtonStyle::Primary),
+            CreateButton::new(ButtonCustomId::new(
+                Some(guild_id),
+                ButtonAction::Favorite(row.id),
+            ))
+            .label(tr(lang, "now_playing.favorite", &[]))
+            .style(ButtonStyle::Secondary),
+        ]);
+        let message = CreateMessage::new()
+            .content(tr(lang, "sotd.content", &[("track", &row.name)]))
+            .components(vec![buttons]);
+        channel_id.send_message(&self.ctx.http, message).await?;
+
+        if let Some(voice_channel_id) = settings.sotd_voice_channel_id {
+            self.play(guild_id, ChannelId::new(voice_channel_id), row)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Play sound in voice channel. When the bot isn't in a voice channel of the guild it joins
+    /// the voice channel for the sound, otherwise it plays the sound where it is, ducking the
+    /// sounds playing there if enabled
+    async fn play(
+        &self,
+        guild_id: GuildId,
+        channel_id: ChannelId,
+        row: &AudioTableRow,
+    ) -> PoiseResult {
+        let manager = helpers::songbird_get(&self.ctx).await;
+
+        if manager.get(guild_id).is_some() {
+            match self.duck_announcements {
+                true => {
+                    manager
+                        .play_audio_ducked(
+                            guild_id,
+                            channel_id,
+                            &row.audio_file,
+                            vars::DUCKING_VOLUME,
+                        )
+                        .await?
+                }
+                false => {
+                    manager
+                        .play_audio(guild_id, channel_id, &row.audio_file)
+                        .await?
+                }
+            }
+            return Ok(());
+        }
+
+        manager.join(guild_id, channel_id).await?;
+        manager
+            .play_audio_to_end(guild_id, channel_id, &row.audio_file)
+            .await
+            .log_err_msg("Failed to play sound of the day")
+            .ok();
+        manager.leave_voice_channel(guild_id).await?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{NaiveDate, TimeZone};
+
+    use crate::test_utils::{AudioRowFixture, TestDb};
+
+    use super::*;
+
+    #[test]
+    fn mode_code_test() {
+        for mode in SotdMode::ALL {
+            assert_eq!(Some(mode), SotdMode::from_code(mode.code()));
+        }
+        assert_eq!(None, SotdMode::from_code("loudest"));
+    }
+
+    #[test]
+    fn is_due_test() {
+        let now = Utc.with_ymd_and_hms(2024, 1, 2, 18, 30, 0).unwrap();
+        let mut settings = GuildSettingsRow {
+            guild_id: 1,
+            ..Default::default()
+        };
+        assert!(!is_due(&settings, now));
+
+        settings.sotd_channel_id = Some(2);
+        assert!(is_due(&settings, now));
+
+        settings.sotd_time = NaiveTime::from_hms_opt(19, 0, 0);
+        assert!(!is_due(&settings, now));
+
+        settings.sotd_time = NaiveTime::from_hms_opt(18, 0, 0);
+        assert!(is_due(&settings, now));
+
+        settings.sotd_last_date = NaiveDate::from_ymd_opt(2024, 1, 2);
+        assert!(!is_due(&settings, now));
+
+        settings.sotd_last_date = NaiveDate::from_ymd_opt(2024, 1, 1);
+        assert!(is_due(&settings, now));
+    }
+
+    #[test]
+    fn pick_sound_test() {
+        let table = TestDb::new().audio_table();
+        let mut settings = GuildSettingsRow {
+            guild_id: 1,
+            sotd_mode: Some(SotdMode::LeastPlayed.code().into()),
+            ..Default::default()
+        };
+        assert!(pick_sound(&table, &settings).is_none());
+
+        let first = AudioRowFixture::new().insert(&table);
+        let second = AudioRowFixture::new().insert(&table);
+        table.increment_play_count(second.id).unwrap();
+
+        assert_eq!(
+            Some(first.id),
+            pick_sound(&table, &settings).map(|row| row.id)
+        );
+
+        settings.sotd_last_audio_id = Some(first.id);
+        assert_eq!(
+            Some(second.id),
+            pick_sound(&table, &settings).map(|row| row.id)
+        );
+
+        settings.sotd_mode = None;
+        assert!(pick_sound(&table, &settings).is_some());
+    }
+}
@@ -0,0 +1,740 @@
+use poise::CreateReply;
+use serenity::all::{
+    ButtonStyle, ChannelId, CreateActionRow, CreateButton, CreateMessage, CreateSelectMenu,
+    CreateSelectMenuKind, CreateSelectMenuOption, EditMessage, GuildId, Http, Message, MessageId,
+};
+
+use crate::commands::{PoiseError, PoiseResult};
+use crate::common::{LogResult, UserData};
+use crate::db::{
+    AudioTablePaginator, AudioTableRow, CategoryFilter, CategoryRow, PlayEventsFilter,
+    SoundboardMessageRow, SoundboardPageRow, Tags,
+};
+use crate::helpers::{self, ButtonAction, ButtonCustomId};
+use crate::i18n::{self, tr, Language};
+use crate::stats;
+use crate::vars;
+
+/// Select menu option value for showing sounds of all tags. Never a valid tag (see
+/// `Tags::clean_tag`)
+pub const ALL_TAGS_VALUE: &str = "*";
+
+/// Order of sounds within each soundboard section, per guild (see `/settings soundboard-order`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, poise::ChoiceParameter)]
+pub enum SoundboardOrder {
+    /// Oldest sounds first
+    #[default]
+    #[name = "Added"]
+    Added,
+    /// Sounds with the highest trending score first (see `stats::trending_sounds`), then the
+    /// others oldest first
+    #[name = "Trending"]
+    Trending,
+}
+
+impl SoundboardOrder {
+    pub const ALL: [SoundboardOrder; 2] = [SoundboardOrder::Added, SoundboardOrder::Trending];
+
+    /// Code stored in `GuildSettingsRow::soundboard_order`
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Added => "added",
+            Self::Trending => "trending",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|order| order.code() == code)
+    }
+
+    /// Guild's soundboard order. Defaults outside of guilds
+    pub fn of_guild(data: &UserData, guild_id: Option<GuildId>) -> Self {
+        guild_id
+            .and_then(|guild_id| {
+                data.guild_settings_table()
+                    .get_guild_settings(guild_id.get())
+                    .log_err()
+                    .ok()
+            })
+            .and_then(|settings| settings.soundboard_order)
+            .and_then(|code| Self::from_code(&code))
+            .unwrap_or_default()
+    }
+
+    /// Ids of sounds shown before the others (see `AudioTablePaginatorBuilder::ranked_first`).
+    /// For trending, the guild's top trending sounds, highest score first
+    pub fn ranked_ids(
+        &self,
+        data: &UserData,
+        guild_id: Option<GuildId>,
+    ) -> Result<Vec<i64>, PoiseError> {
+        if *self == Self::Added {
+            return Ok(vec![]);
+        }
+
+        let filter = PlayEventsFilter {
+            audio_id: None,
+            guild_id: guild_id.map(|guild_id| guild_id.get()),
+        };
+        let trending =
+            stats::trending_sounds(&data.play_events_table(), chrono::Utc::now(), &filter)?;
+
+        Ok(trending
+            .into_iter()
+            .take(vars::TRENDING_SOUNDBOARD_LIMIT)
+            .map(|(audio_id, _)| audio_id)
+            .collect())
+    }
+}
+
+/// Sounds of a category, or uncategorized sounds. The soundboard is paginated section by
+/// section, so pages never mix categories
+#[derive(Debug, Clone, PartialEq)]
+pub struct SoundboardSection {
+    /// None for uncategorized sounds
+    pub category: Option<CategoryRow>,
+    pub sound_count: u64,
+}
+
+impl SoundboardSection {
+    pub fn filter(&self) -> CategoryFilter {
+        match self.category {
+            Some(ref category) => CategoryFilter::Category(category.id),
+            None => CategoryFilter::Uncategorized,
+        }
+    }
+
+    /// Header shown above the section's sounds. Uncategorized sounds only get one when there
+    /// are categorized sounds too
+    pub fn header(&self, lang: Language, section_count: usize) -> Option<String> {
+        match self.category {
+            Some(ref category) => Some(category.label()),
+            None if section_count > 1 => Some(tr(lang, "soundboard.other", &[])),
+            None => None,
+        }
+    }
+}
+
+/// Sections of sounds, optionally only counting sounds with tag. Sections without sounds are
+/// left out
+pub fn load_sections(
+    data: &UserData,
+    tag: Option<&str>,
+) -> Result<Vec<SoundboardSection>, PoiseError> {
+    let counts = data.audio_table().category_counts(tag)?;
+    let categories = data.categories_table().list_categories()?;
+    Ok(sections(categories, &counts))
+}
+
+/// Categories in order, then uncategorized sounds. Sounds of deleted categories count as
+/// uncategorized
+fn sections(categories: Vec<CategoryRow>, counts: &[(Option<i64>, u64)]) -> Vec<SoundboardSection> {
+    let count_of = |category_id: i64| {
+        counts
+            .iter()
+            .find(|(id, _)| *id == Some(category_id))
+            .map(|(_, count)| *count)
+            .unwrap_or(0)
+    };
+    let uncategorized = counts
+        .iter()
+        .filter(|(id, _)| !categories.iter().any(|category| Some(category.id) == *id))
+        .map(|(_, count)| count)
+        .sum();
+
+    let mut sections: Vec<_> = categories
+        .into_iter()
+        .map(|category| SoundboardSection {
+            sound_count: count_of(category.id),
+            category: Some(category),
+        })
+        .collect();
+    sections.push(SoundboardSection {
+        category: None,
+        sound_count: uncategorized,
+    });
+
+    sections.retain(|section| section.sound_count > 0);
+    sections
+}
+
+/// Section of zero based soundboard page, and the page within that section. None past the
+/// last page
+fn locate_page(sections: &[SoundboardSection], mut page: u64) -> Option<(usize, u64)> {
+    for (index, section) in sections.iter().enumerate() {
+        let section_pages = page_count(section.sound_count);
+        if page < section_pages {
+            return Some((index, page));
+        }
+        page -= section_pages;
+    }
+    None
+}
+
+/// One page of a soundboard message, optionally filtered by tag
+pub struct SoundboardPage {
+    pub page: u64,
+    pub page_count: u64,
+    pub sound_count: u64,
+    pub rows: Vec<AudioTableRow>,
+    /// Header of the page's section (see `SoundboardSection::header`)
+    pub header: Option<String>,
+    /// Normalized tag filter
+    pub tag: Option<String>,
+    /// Most used tags and their sound counts, for the tag select menu
+    pub tag_counts: Vec<(String, u64)>,
+    pub order: SoundboardOrder,
+    /// Language of the guild the page is shown in
+    pub lang: Language,
+}
+
+impl SoundboardPage {
+    /// Load zero based soundboard page, in the guild's soundboard order. Pages past the end are
+    /// clamped to the last page
+    pub fn load(
+        data: &UserData,
+        guild_id: Option<GuildId>,
+        page: u64,
+        tag: Option<&str>,
+    ) -> Result<Self, PoiseError> {
+        let tag = tag.map(Tags::normalize_tag);
+        let table = data.audio_table();
+        let order = SoundboardOrder::of_guild(data, guild_id);
+        let lang = i18n::guild_language(data, guild_id, None);
+
+        let sections = load_sections(data, tag.as_deref())?;
+        let sound_count = sections.iter().map(|section| section.sound_count).sum();
+        let page_count = sections
+            .iter()
+            .map(|section| page_count(section.sound_count))
+            .sum::<u64>()
+            .max(1);
+        let page = page.min(page_count - 1);
+
+        let (rows, header) = match locate_page(&sections, page) {
+            Some((index, section_page)) => {
+                let section = &sections[index];
+                let ranked_ids = order.ranked_ids(data, guild_id)?;
+                let rows = AudioTablePaginator::builder(data.db_connection())
+                    .page_limit(vars::SOUNDBOARD_PAGE_LIMIT)
+                    .start_page(section_page)
+                    .tag(tag.as_deref())
+                    .category(Some(section.filter()))
+                    .ranked_first(ranked_ids)
+                    .build()
+                    .next_page()?;
+                (rows, section.header(lang, sections.len()))
+            }
+            None => (vec![], None),
+        };
+
+        // one select menu option is reserved for "all tags"
+        let tag_counts = table
+            .tag_counts(vars::SELECT_MENU_OPTIONS_LIMIT - 1)?
+            .into_iter()
+            .filter(|(tag, _)| tag.len() <= vars::SOUNDBOARD_TAG_MAX_LEN)
+            .collect();
+
+        Ok(Self {
+            page,
+            page_count,
+            sound_count,
+            rows,
+            header,
+            tag,
+            tag_counts,
+            order,
+            lang,
+        })
+    }
+
+    /// Load page the soundboard message was last navigated to (see `SoundboardPagesTable`).
+    /// Messages never navigated show the first page of all sounds
+    pub fn load_saved(
+        data: &UserData,
+        guild_id: Option<GuildId>,
+        message_id: MessageId,
+    ) -> Result<Self, PoiseError> {
+        let (page, tag) = data
+            .soundboard_pages_table()
+            .find_soundboard_page(message_id.get())?
+            .map(|saved| (saved.page, saved.tag))
+            .unwrap_or_default();
+
+        Self::load(data, guild_id, page, tag.as_deref())
+    }
+
+    pub fn content(&self) -> String {
+        let lang = self.lang;
+        let tagged = match self.tag {
+            Some(ref tag) => tr(lang, "soundboard.tagged", &[("tag", tag)]),
+            None => "".into(),
+        };
+        let trending = match self.order {
+            SoundboardOrder::Added => "".into(),
+            SoundboardOrder::Trending => tr(lang, "soundboard.trending_first", &[]),
+        };
+
+        let content = match self.sound_count {
+            0 => tr(lang, "soundboard.empty", &[("tagged", &tagged)]),
+            count => tr(
+                lang,
+                "soundboard.title",
+                &[
+                    ("count", &count),
+                    ("tagged", &tagged),
+                    ("trending", &trending),
+                    ("page", &(self.page + 1)),
+                    ("page_count", &self.page_count),
+                ],
+            ),
+        };
+
+        match self.header {
+            Some(ref header) => format!("{content}\n### {header}"),
+            None => content,
+        }
+    }
+
+    /// Tag select menu (when sounds are tagged), sound button grid, then page navigation
+    /// buttons when there's more than one page
+    pub fn components(&self, guild_id: Option<GuildId>) -> Vec<CreateActionRow> {
+        let mut components = vec![];
+
+        if !self.tag_counts.is_empty() {
+            let all_option = CreateSelectMenuOption::new(
+                tr(self.lang, "soundboard.all_sounds", &[]),
+                ALL_TAGS_VALUE,
+            )
+            .default_selection(self.tag.is_none());
+            let tag_options = self.tag_counts.iter().map(|(tag, sound_count)| {
+                CreateSelectMenuOption::new(tag, tag)
+                    .description(tr(
+                        self.lang,
+                        "soundboard.tag_sounds",
+                        &[("count", sound_count)],
+                    ))
+                    .default_selection(self.tag.as_ref() == Some(tag))
+            });
+
+            let options = std::iter::once(all_option).chain(tag_options).collect();
+            let menu = CreateSelectMenu::new(
+                ButtonCustomId::new(guild_id, ButtonAction::TagFilter),
+                CreateSelectMenuKind::String { options },
+            )
+            .placeholder(tr(self.lang, "soundboard.filter_by_tag", &[]));
+
+            components.push(CreateActionRow::SelectMenu(menu));
+        }
+
+        components.extend(
+            self.rows
+                .chunks(5)
+                .map(|row| helpers::make_action_row(row, guild_id)),
+        );
+
+        if let Some((prev_page, next_page)) = nav_pages(self.page, self.page_count) {
+            let nav_button = |page: u64, label: String| {
+                let tag = self.tag.clone();
+                CreateButton::new(ButtonCustomId::new(
+                    guild_id,
+                    ButtonAction::PageNav { page, tag },
+                ))
+                .label(label)
+                .style(ButtonStyle::Secondary)
+            };
+
+            components.push(CreateActionRow::Buttons(vec![
+                nav_button(prev_page, tr(self.lang, "soundboard.prev", &[]))
+                    .disabled(prev_page == self.page),
+                nav_button(next_page, tr(self.lang, "soundboard.next", &[]))
+                    .disabled(next_page == self.page),
+            ]));
+        }
+
+        components
+    }
+
+    pub fn create_message(&self, guild_id: Option<GuildId>) -> CreateMessage {
+        CreateMessage::new()
+            .content(self.content())
+            .components(self.components(guild_id))
+    }
+
+    pub fn edit_message(&self, guild_id: Option<GuildId>) -> EditMessage {
+        EditMessage::new()
+            .content(self.content())
+            .components(self.components(guild_id))
+    }
+
+    pub fn create_reply(&self, guild_id: Option<GuildId>) -> CreateReply {
+        CreateReply::default()
+            .content(self.content())
+            .components(self.components(guild_id))
+    }
+}
+
+fn page_count(sound_count: u64) -> u64 {
+    sound_count.div_ceil(vars::SOUNDBOARD_PAGE_LIMIT).max(1)
+}
+
+/// Previous and next page targets of the navigation buttons. None when there's a single page.
+/// At either end the target is the current page, and that button is rendered disabled
+fn nav_pages(page: u64, page_count: u64) -> Option<(u64, u64)> {
+    if page_count <= 1 {
+        return None;
+    }
+
+    Some((page.saturating_sub(1), (page + 1).min(page_count - 1)))
+}
+
+/// Post soundboard message to channel and pin it. Replaces guild's previously published
+/// soundboard message, which is edited in place if it's in the same channel
+pub async fn publish_soundboard(
+    http: &Http,
+    data: &UserData,
+    guild_id: GuildId,
+    channel_id: ChannelId,
+) -> Result<MessageId, PoiseError> {
+    log::info!("Publishing soundboard. Guild Id: {guild_id}, Channel Id: {channel_id}");
+
+    let table = data.soundboard_messages_table();
+    let page = SoundboardPage::load(data, Some(guild_id), 0, None)?;
+
+    // published soundboards start on the first page
+    data.soundboard_pages_table()
+        .delete_guild_soundboard_pages(guild_id.get())?;
+
+    if let Some(existing) = table.find_soundboard_message(guild_id.get())? {
+        let existing_channel_id = ChannelId::new(existing.channel_id);
+        let existing_message_id = MessageId::new(existing.message_id);
+
+        if existing_channel_id == channel_id {
+            let edited = channel_id
+                .edit_message(http, existing_message_id, page.edit_message(Some(guild_id)))
+                .await;
+
+            if edited.is_ok() {
+                return Ok(existing_message_id);
+            }
+            log::warn!("Published soundboard message missing. Posting new soundboard message");
+        } else {
+            existing_channel_id
+                .delete_message(http, existing_message_id)
+                .await
+                .log_err_msg("Failed to delete previous soundboard message")
+                .ok();
+        }
+    }
+
+    let message: Message = channel_id
+        .send_message(http, page.create_message(Some(guild_id)))
+        .await?;
+
+    message
+        .pin(http)
+        .await
+        .log_err_msg("Failed to pin soundboard message")
+        .ok();
+
+    table.upsert_soundboard_message(&SoundboardMessageRow {
+        guild_id: guild_id.get(),
+        channel_id: channel_id.get(),
+        message_id: message.id.get(),
+    })?;
+
+    Ok(message.id)
+}
+
+/// Re-render guild's published soundboard message after sounds change, on the page and tag
+/// filter it was last navigated to. Forgets the soundboard message if it was deleted
+pub async fn refresh_soundboard(http: &Http, data: &UserData, guild_id: GuildId) -> PoiseResult {
+    let table = data.soundboard_messages_table();
+    let Some(existing) = table.find_soundboard_message(guild_id.get())? else {
+        return Ok(());
+    };
+
+    log::info!("Refreshing soundboard message - {existing:?}");
+
+    let message_id = MessageId::new(existing.message_id);
+    let page = SoundboardPage::load_saved(data, Some(guild_id), message_id)?;
+    let result = ChannelId::new(existing.channel_id)
+        .edit_message(http, message_id, page.edit_message(Some(guild_id)))
+        .await;
+
+    if let Err(err) = result {
+        log::warn!("Failed to refresh soundboard message, forgetting it - {err}");
+        table.delete_soundboard_message(guild_id.get())?;
+        data.soundboard_pages_table()
+            .delete_guild_soundboard_pages(guild_id.get())?;
+    }
+
+    Ok(())
+}
+
+/// Show page of the soundboard on the message whose navigation button or tag select menu was
+/// used. The page of the guild's published soundboard is saved, to be kept when it's refreshed
+pub async fn show_soundboard_page(
+    http: &Http,
+    data: &UserData,
+    guild_id: Option<GuildId>,
+    channel_id: ChannelId,
+    message_id: MessageId,
+    page: u64,
+    tag: Option<&str>,
+) -> PoiseResult {
+    let page = SoundboardPage::load(data, guild_id, page, tag)?;
+    channel_id
+        .edit_message(http, message_id, page.edit_message(guild_id))
+        .await?;
+
+    if let Some(guild_id) = guild_id {
+        let published = data
+            .soundboard_messages_table()
+            .find_soundboard_message(guild_id.get())?;
+        if published.is_some_and(|published| published.message_id == message_id.get()) {
+            data.soundboard_pages_table()
+                .upsert_soundboard_page(&SoundboardPageRow {
+                    message_id: message_id.get(),
+                    guild_id: guild_id.get(),
+                    page: page.page,
+                    tag: page.tag,
+                })?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::db::PlayEventInsert;
+    use crate::test_utils::{AudioRowFixture, TestDb};
+
+    use super::*;
+
+    #[test]
+    fn page_count_test() {
+        assert_eq!(1, page_count(0));
+        assert_eq!(1, page_count(vars::SOUNDBOARD_PAGE_LIMIT));
+        assert_eq!(2, page_count(vars::SOUNDBOARD_PAGE_LIMIT + 1));
+    }
+
+    #[test]
+    fn nav_pages_test() {
+        assert_eq!(None, nav_pages(0, 1));
+        assert_eq!(Some((0, 1)), nav_pages(0, 3));
+        assert_eq!(Some((0, 2)), nav_pages(1, 3));
+        assert_eq!(Some((1, 2)), nav_pages(2, 3));
+    }
+
+    #[test]
+    fn sections_test() {
+        let category = |id: i64, name: &str| CategoryRow {
+            id,
+            name: name.into(),
+            emoji: None,
+            sort_order: 0,
+        };
+        let categories = vec![
+            category(2, "Movies"),
+            category(1, "Memes"),
+            category(3, "Games"),
+        ];
+
+        // sounds of unknown categories are uncategorized, empty sections are left out
+        let sections = sections(
+            categories,
+            &[(None, 1), (Some(1), 4), (Some(2), 3), (Some(9), 2)],
+        );
+        assert_eq!(
+            vec![
+                (Some("Movies".to_string()), 3),
+                (Some("Memes".into()), 4),
+                (None, 3)
+            ],
+            sections
+                .iter()
+                .map(|section| (
+                    section.category.as_ref().map(|c| c.name.clone()),
+                    section.sound_count
+                ))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            Some("Other".into()),
+            sections[2].header(Language::English, sections.len())
+        );
+        assert_eq!(
+            Some("Otros".into()),
+            sections[2].header(Language::Spanish, sections.len())
+        );
+        assert_eq!(None, sections[2].header(Language::English, 1));
+    }
+
+    #[test]
+    fn locate_page_test() {
+        let section = |sound_count| SoundboardSection {
+            category: None,
+            sound_count,
+        };
+        let sections = [section(vars::SOUNDBOARD_PAGE_LIMIT + 1), section(1)];
+
+        assert_eq!(Some((0, 0)), locate_page(&sections, 0));
+        assert_eq!(Some((0, 1)), locate_page(&sections, 1));
+        assert_eq!(Some((1, 0)), locate_page(&sections, 2));
+        assert_eq!(None, locate_page(&sections, 3));
+        assert_eq!(None, locate_page(&[], 0));
+    }
+
+    #[test]
+    fn load_categorized_soundboard_page_test() {
+        let db = TestDb::new();
+        let data = db.user_data();
+        let table = data.audio_table();
+        let memes = data
+            .categories_table()
+            .insert_category("Memes", Some("🐸"), 0)
+            .unwrap();
+        let uncategorized = AudioRowFixture::new().insert(&table);
+        let categorized = AudioRowFixture::new().insert(&table);
+        table.set_category(categorized.id, Some(memes)).unwrap();
+
+        // categories come first, pages don't mix categories
+        let page = SoundboardPage::load(&data, None, 0, None).unwrap();
+        assert_eq!(2, page.page_count);
+        assert_eq!(2, page.sound_count);
+        assert_eq!(
+            vec![categorized.id],
+            page.rows.iter().map(|row| row.id).collect::<Vec<_>>()
+        );
+        assert!(page.content().ends_with("\n### 🐸 Memes"));
+
+        let page = SoundboardPage::load(&data, None, 1, None).unwrap();
+        assert_eq!(
+            vec![uncategorized.id],
+            page.rows.iter().map(|row| row.id).collect::<Vec<_>>()
+        );
+        assert_eq!(Some("Other".into()), page.header);
+    }
+
+    #[test]
+    fn load_soundboard_page_test() {
+        let db = TestDb::new();
+        let data = db.user_data();
+        for _ in 0..vars::SOUNDBOARD_PAGE_LIMIT + 1 {
+            AudioRowFixture::new()
+                .tags("meme")
+                .insert(&data.audio_table());
+        }
+
+        let page = SoundboardPage::load(&data, None, 0, None).unwrap();
+        assert_eq!(2, page.page_count);
+        assert_eq!(vars::SOUNDBOARD_PAGE_LIMIT as usize, page.rows.len());
+        // tag select menu, 3 rows of sound buttons and a navigation row
+        assert_eq!(5, page.components(None).len());
+
+        // past the end clamps to last page
+        let page = SoundboardPage::load(&data, None, 10, None).unwrap();
+        assert_eq!(1, page.page);
+        assert_eq!(1, page.rows.len());
+    }
+
+    #[test]
+    fn load_saved_soundboard_page_test() {
+        let db = TestDb::new();
+        let data = db.user_data();
+        for _ in 0..vars::SOUNDBOARD_PAGE_LIMIT + 1 {
+            AudioRowFixture::new()
+                .tags("droid")
+                .insert(&data.audio_table());
+        }
+
+        // never navigated
+        let page = SoundboardPage::load_saved(&data, None, MessageId::new(100)).unwrap();
+        assert_eq!((0, None), (page.page, page.tag));
+
+        data.soundboard_pages_table()
+            .upsert_soundboard_page(&SoundboardPageRow {
+                message_id: 100,
+                guild_id: 1,
+                page: 1,
+                tag: Some("droid".into()),
+            })
+            .unwrap();
+        let page = SoundboardPage::load_saved(&data, None, MessageId::new(100)).unwrap();
+        assert_eq!((1, Some("droid".into())), (page.page, page.tag));
+    }
+
+    #[test]
+    fn load_trending_soundboard_page_test() {
+        let db = TestDb::new();
+        let data = db.user_data();
+        let oldest = AudioRowFixture::new().insert(&data.audio_table());
+        let newest = AudioRowFixture::new().insert(&data.audio_table());
+        data.play_events_table()
+            .insert_play_event(&PlayEventInsert {
+                audio_id: newest.id,
+                user_id: 1,
+                guild_id: Some(1),
+                source: "slash".into(),
+            })
+            .unwrap();
+        let ids = |page: &SoundboardPage| page.rows.iter().map(|row| row.id).collect::<Vec<_>>();
+
+        let guild_id = Some(GuildId::new(1));
+        let page = SoundboardPage::load(&data, guild_id, 0, None).unwrap();
+        assert_eq!(vec![oldest.id, newest.id], ids(&page));
+
+        let settings_table = data.guild_settings_table();
+        for guild_id in [1, 2] {
+            let mut settings = settings_table.get_guild_settings(guild_id).unwrap();
+            settings.soundboard_order = Some(SoundboardOrder::Trending.code().into());
+            settings_table.update_guild_settings(&settings).unwrap();
+        }
+
+        let page = SoundboardPage::load(&data, guild_id, 0, None).unwrap();
+        assert_eq!(vec![newest.id, oldest.id], ids(&page));
+        assert!(page.content().contains("trending first"));
+
+        // plays of other guilds don't count
+        let page = SoundboardPage::load(&data, Some(GuildId::new(2)), 0, None).unwrap();
+        assert_eq!(vec![oldest.id, newest.id], ids(&page));
+    }
+
+    #[test]
+    fn load_tagged_soundboard_page_test() {
+        let db = TestDb::new();
+        let data = db.user_data();
+        AudioRowFixture::new()
+            .tags("droid r2d2")
+            .insert(&data.audio_table());
+        AudioRowFixture::new()
+            .tags("Droid")
+            .insert(&data.audio_table());
+        AudioRowFixture::new()
+            .tags(format!(
+                "jedi {}",
+                "x".repeat(vars::SOUNDBOARD_TAG_MAX_LEN + 1)
+            ))
+            .insert(&data.audio_table());
+
+        let page = SoundboardPage::load(&data, None, 0, Some("DROID")).unwrap();
+        assert_eq!(Some("droid".into()), page.tag);
+        assert_eq!(2, page.sound_count);
+        assert_eq!(1, page.page_count);
+
+        // tags too long to filter by are left out of the select menu
+        assert_eq!(
+            vec![
+                ("droid".to_string(), 2),
+                ("jedi".into(), 1),
+                ("r2d2".into(), 1)
+            ],
+            page.tag_counts
+        );
+
+        // select menu and a single row of sound buttons
+        assert_eq!(2, page.components(None).len());
+    }
+}
@@ -0,0 +1,453 @@
+//! Time based play statistics from the play events table, rendered as text charts by
+//! `/stats history` and `/stats heatmap`, and trending scores of sounds. Play events older than
+//! `Config::play_events_retention_days` are rolled up into daily play counts once a day
+use std::sync::Once;
+use std::time::Duration;
+
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+
+use crate::commands::PoiseError;
+use crate::common::LogResult;
+use crate::db::{HistogramBucket, PlayEventsFilter, PlayEventsTable};
+use crate::vars;
+
+/// Number of buckets shown for bucket size
+pub fn history_len(bucket: HistogramBucket) -> u32 {
+    match bucket {
+        HistogramBucket::Day => vars::STATS_HISTORY_DAYS,
+        HistogramBucket::Week => vars::STATS_HISTORY_WEEKS,
+    }
+}
+
+/// Play counts of the last `len` buckets up to `now`, oldest first. Buckets without plays
+/// are included with a count of 0
+pub fn play_history(
+    table: &PlayEventsTable,
+    bucket: HistogramBucket,
+    len: u32,
+    now: DateTime<Utc>,
+    filter: &PlayEventsFilter,
+) -> Result<Vec<(NaiveDate, i64)>, PoiseError> {
+    let last = bucket.start_of(now.date_naive());
+    let first = last - bucket.duration() * (len.max(1) as i32 - 1);
+
+    let since = first.and_time(NaiveTime::MIN).and_utc();
+    let histogram = table.play_histogram(bucket, since, filter)?;
+
+    Ok(fill_buckets(&histogram, bucket, first, last))
+}
+
+/// Histogram with a bucket for every bucket start date from `first` to `last`
+fn fill_buckets(
+    histogram: &[(NaiveDate, i64)],
+    bucket: HistogramBucket,
+    first: NaiveDate,
+    last: NaiveDate,
+) -> Vec<(NaiveDate, i64)> {
+    let mut buckets = vec![];
+    let mut date = first;
+
+    while date <= last {
+        let plays = histogram
+            .iter()
+            .find(|(bucket_date, _)| *bucket_date == date)
+            .map(|(_, plays)| *plays)
+            .unwrap_or(0);
+        buckets.push((date, plays));
+        date += bucket.duration();
+    }
+
+    buckets
+}
+
+/// Horizontal bar chart of histogram, one line per bucket, bars scaled to the busiest bucket
+pub fn render_chart(histogram: &[(NaiveDate, i64)]) -> String {
+    let max = histogram.iter().map(|(_, plays)| *plays).max().unwrap_or(0);
+    let width = max.to_string().len();
+
+    histogram
+        .iter()
+        .map(|(date, plays)| {
+            let bar_len = match max {
+                0 => 0,
+                // round up so every bucket with plays gets a bar
+                max => (plays * vars::STATS_CHART_WIDTH + max - 1) / max,
+            };
+            format!("{date} {plays:>width$} {}", "█".repeat(bar_len as usize))
+                .trim_end()
+                .to_string()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Play counts per day of week (monday first) and hour of day (UTC)
+pub type Heatmap = [[i64; 24]; 7];
+
+/// Play counts per day of week and hour of day of the last `days` days up to `now`
+pub fn play_heatmap(
+    table: &PlayEventsTable,
+    days: u32,
+    now: DateTime<Utc>,
+    filter: &PlayEventsFilter,
+) -> Result<Heatmap, PoiseError> {
+    let since = now - chrono::Duration::days(days as i64);
+    let mut heatmap = [[0; 24]; 7];
+
+    for (weekday, hour, plays) in table.play_heatmap(since, filter)? {
+        if let Some(cell) = heatmap
+            .get_mut(weekday as usize)
+            .and_then(|hours| hours.get_mut(hour as usize))
+        {
+            *cell = plays;
+        }
+    }
+
+    Ok(heatmap)
+}
+
+/// Plays counted towards a trending score after `age_days`. Each play counts half as much every
+/// `vars::TRENDING_HALF_LIFE_DAYS`, so recent plays outweigh all-time classics
+pub fn decayed_plays(plays: i64, age_days: i64) -> f64 {
+    plays as f64 * 0.5_f64.powf(age_days.max(0) as f64 / vars::TRENDING_HALF_LIFE_DAYS)
+}
+
+/// Sound ids and trending scores of sounds played in the last `vars::TRENDING_WINDOW_DAYS`
+/// days up to `now`, highest score first. Ties are ordered by sound id
+pub fn trending_sounds(
+    table: &PlayEventsTable,
+    now: DateTime<Utc>,
+    filter: &PlayEventsFilter,
+) -> Result<Vec<(i64, f64)>, PoiseError> {
+    let today = now.date_naive();
+    let first = today - chrono::Duration::days(vars::TRENDING_WINDOW_DAYS as i64 - 1);
+    let since = first.and_time(NaiveTime::MIN).and_utc();
+
+    let mut scores: Vec<(i64, f64)> = vec![];
+    for (audio_id, day, plays) in table.daily_plays(since, filter)? {
+        let score = decayed_plays(plays, (today - day).num_days());
+        // daily plays are ordered by sound
+        match scores.last_mut() {
+            Some((last_id, last_score)) if *last_id == audio_id => *last_score += score,
+            _ => scores.push((audio_id, score)),
+        }
+    }
+
+    scores.sort_by(|(a_id, a), (b_id, b)| b.total_cmp(a).then(a_id.cmp(b_id)));
+    Ok(scores)
+}
+
+/// Grid of heatmap, one line per day of week and one char per hour, shaded relative to the
+/// busiest hour. `day_names` label the lines, monday first
+pub fn render_heatmap(heatmap: &Heatmap, day_names: &[&str]) -> String {
+    let max = heatmap.iter().flatten().copied().max().unwrap_or(0);
+    let label_width = day_names
+        .iter()
+        .map(|name| name.chars().count())
+        .max()
+        .unwrap_or(0)
+        + 1;
+
+    let mut lines = vec![format!(
+        "{:label_width$}{:<6}{:<6}{:<6}{}",
+        "", "0", "6", "12", "18"
+    )];
+    for (index, hours) in heatmap.iter().enumerate() {
+        let cells: String = hours
+            .iter()
+            .map(|plays| match (*plays, max) {
+                (0, _) | (_, 0) => vars::STATS_HEATMAP_SHADES[0],
+                // round up so every hour with plays is shaded
+                (plays, max) => {
+                    let levels = (vars::STATS_HEATMAP_SHADES.len() - 1) as i64;
+                    vars::STATS_HEATMAP_SHADES[((plays * levels + max - 1) / max) as usize]
+                }
+            })
+            .collect();
+        let day_name = day_names.get(index).copied().unwrap_or("");
+        lines.push(format!("{day_name:label_width$}{cells}"));
+    }
+
+    lines.join("\n")
+}
+
+/// Play events played before this are rolled up. Always a start of day, so days aren't rolled
+/// up in parts
+pub fn retention_cutoff(now: DateTime<Utc>, retention_days: u32) -> DateTime<Utc> {
+    (now.date_naive() - chrono::Duration::days(retention_days as i64))
+        .and_time(NaiveTime::MIN)
+        .and_utc()
+}
+
+/// Roll up play events older than `retention_days`. A retention of 0 days keeps play events
+/// forever. Returns number of play events rolled up
+pub fn apply_retention(
+    table: &PlayEventsTable,
+    retention_days: u32,
+    now: DateTime<Utc>,
+) -> Result<usize, PoiseError> {
+    if retention_days == 0 {
+        return Ok(0);
+    }
+
+    table.rollup_play_events(retention_cutoff(now, retention_days))
+}
+
+/// Start the play events retention job. Only the first call starts it, so it's safe to call
+/// on every ready event. The retention is read once, so changing it needs a restart
+pub fn spawn_retention_job(
+    db_pool: r2d2::Pool<r2d2_sqlite::SqliteConnectionManager>,
+    retention_days: u32,
+) {
+    static STARTED: Once = Once::new();
+
+    if retention_days == 0 {
+        return;
+    }
+
+    STARTED.call_once(|| {
+        log::info!("Starting play events retention job. Retention: {retention_days} days");
+        tokio::spawn(async move {
+            let mut interval =
+                tokio::time::interval(Duration::from_secs(vars::RETENTION_JOB_INTERVAL_SECS));
+
+            loop {
+                interval.tick().await;
+                let result = db_pool
+                    .get()
+                    .map_err(PoiseError::from)
+                    .and_then(|conn| {
+                        apply_retention(&PlayEventsTable::new(conn), retention_days, Utc::now())
+                    })
+                    .log_err_msg("Failed to apply play events retention");
+
+                if let Ok(rolled_up) = result {
+                    log::info!("Rolled up {rolled_up} play events");
+                }
+            }
+        });
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use crate::db::{PlayEventInsert, Table};
+    use crate::test_utils::{AudioRowFixture, TestDb};
+
+    use super::*;
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 1, day).unwrap()
+    }
+
+    #[test]
+    fn fill_buckets_test() {
+        let histogram = vec![(date(2), 3), (date(4), 1)];
+        assert_eq!(
+            vec![(date(1), 0), (date(2), 3), (date(3), 0), (date(4), 1)],
+            fill_buckets(&histogram, HistogramBucket::Day, date(1), date(4))
+        );
+        assert_eq!(
+            vec![(date(1), 0), (date(8), 0)],
+            fill_buckets(&histogram, HistogramBucket::Week, date(1), date(8))
+        );
+    }
+
+    #[test]
+    fn render_chart_test() {
+        let chart = render_chart(&[(date(1), 10), (date(2), 0), (date(3), 1)]);
+        assert_eq!(
+            format!(
+                "2024-01-01 10 {}\n2024-01-02  0\n2024-01-03  1 {}",
+                "█".repeat(20),
+                "█".repeat(2)
+            ),
+            chart
+        );
+
+        assert_eq!("2024-01-01 0", render_chart(&[(date(1), 0)]));
+    }
+
+    #[test]
+    fn retention_cutoff_test() {
+        let now = Utc.with_ymd_and_hms(2024, 3, 1, 15, 30, 0).unwrap();
+        assert_eq!(
+            Utc.with_ymd_and_hms(2024, 2, 1, 0, 0, 0).unwrap(),
+            retention_cutoff(now, 29)
+        );
+    }
+
+    #[test]
+    fn apply_retention_test() {
+        let db = TestDb::new();
+        let table = db.play_events_table();
+        let row = AudioRowFixture::new().insert(&db.audio_table());
+
+        table
+            .insert_play_event(&PlayEventInsert {
+                audio_id: row.id,
+                user_id: 1,
+                guild_id: Some(2),
+                source: "slash".into(),
+            })
+            .unwrap();
+
+        let later = Utc::now() + chrono::Duration::days(10);
+        // disabled retention keeps play events forever
+        assert_eq!(0, apply_retention(&table, 0, later).unwrap());
+        assert_eq!(0, apply_retention(&table, 30, later).unwrap());
+        assert_eq!(1, apply_retention(&table, 5, later).unwrap());
+    }
+
+    #[test]
+    fn play_history_test() {
+        let db = TestDb::new();
+        let table = db.play_events_table();
+        let row = AudioRowFixture::new().insert(&db.audio_table());
+
+        table
+            .insert_play_event(&PlayEventInsert {
+                audio_id: row.id,
+                user_id: 1,
+                guild_id: Some(2),
+                source: "slash".into(),
+            })
+            .unwrap();
+
+        let now = Utc::now();
+        let history = play_history(
+            &table,
+            HistogramBucket::Day,
+            7,
+            now,
+            &PlayEventsFilter::default(),
+        )
+        .unwrap();
+        assert_eq!(7, history.len());
+        assert_eq!(Some(&(now.date_naive(), 1)), history.last());
+
+        // 2024-01-03 is a wednesday, history covers the weeks starting 2023-12-25 and 2024-01-01
+        let now = Utc.with_ymd_and_hms(2024, 1, 3, 12, 0, 0).unwrap();
+        let history = play_history(
+            &table,
+            HistogramBucket::Week,
+            2,
+            now,
+            &PlayEventsFilter::default(),
+        )
+        .unwrap();
+        assert_eq!(
+            vec![
+                (NaiveDate::from_ymd_opt(2023, 12, 25).unwrap(), 0),
+                (date(1), 0)
+            ],
+            history
+        );
+    }
+
+    #[test]
+    fn render_heatmap_test() {
+        let mut heatmap = [[0; 24]; 7];
+        heatmap[0][0] = 1;
+        heatmap[0][1] = 4;
+        heatmap[6][23] = 2;
+        let day_names = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+
+        let rendered = render_heatmap(&heatmap, &day_names);
+        let lines: Vec<_> = rendered.lines().collect();
+        assert_eq!(8, lines.len());
+        assert_eq!("    0     6     12    18", lines[0]);
+        assert_eq!(format!("Mon ░█{}", "·".repeat(22)), lines[1]);
+        assert_eq!(format!("Sun {}▒", "·".repeat(23)), lines[7]);
+
+        // no plays
+        let rendered = render_heatmap(&[[0; 24]; 7], &day_names);
+        assert!(rendered.ends_with(&format!("Sun {}", "·".repeat(24))));
+    }
+
+    #[test]
+    fn decayed_plays_test() {
+        assert_eq!(4.0, decayed_plays(4, 0));
+        assert_eq!(2.0, decayed_plays(4, vars::TRENDING_HALF_LIFE_DAYS as i64));
+        assert_eq!(
+            1.0,
+            decayed_plays(4, 2 * vars::TRENDING_HALF_LIFE_DAYS as i64)
+        );
+        // plays dated in the future count in full
+        assert_eq!(4.0, decayed_plays(4, -1));
+    }
+
+    #[test]
+    fn trending_sounds_test() {
+        let db = TestDb::new();
+        let table = db.play_events_table();
+        let audio_table = db.audio_table();
+        let classic = AudioRowFixture::new().insert(&audio_table);
+        let fresh = AudioRowFixture::new().insert(&audio_table);
+        let play = |audio_id: i64| {
+            table
+                .insert_play_event(&PlayEventInsert {
+                    audio_id,
+                    user_id: 1,
+                    guild_id: Some(1),
+                    source: "slash".into(),
+                })
+                .unwrap();
+        };
+
+        for _ in 0..4 {
+            play(classic.id);
+        }
+        play(fresh.id);
+        play(fresh.id);
+
+        // played today
+        let now = Utc::now();
+        let trending = trending_sounds(&table, now, &PlayEventsFilter::default()).unwrap();
+        assert_eq!(vec![(classic.id, 4.0), (fresh.id, 2.0)], trending);
+
+        // scores decay as plays age
+        let later = now + chrono::Duration::days(6);
+        let trending = trending_sounds(&table, later, &PlayEventsFilter::default()).unwrap();
+        assert_eq!(vec![(classic.id, 1.0), (fresh.id, 0.5)], trending);
+
+        // the classic's plays of last week count less than two plays of today
+        table
+            .connection()
+            .execute(
+                "UPDATE play_events SET played_at = ?1 WHERE audio_id = ?2",
+                rusqlite::params![now - chrono::Duration::days(6), classic.id],
+            )
+            .unwrap();
+        let trending = trending_sounds(&table, now, &PlayEventsFilter::default()).unwrap();
+        assert_eq!(vec![(fresh.id, 2.0), (classic.id, 1.0)], trending);
+
+        // plays older than the window aren't counted
+        let later = now + chrono::Duration::days(vars::TRENDING_WINDOW_DAYS as i64 + 1);
+        let filter = PlayEventsFilter {
+            audio_id: Some(classic.id),
+            ..Default::default()
+        };
+        assert!(trending_sounds(&table, later, &filter).unwrap().is_empty());
+    }
+
+    #[test]
+    fn play_heatmap_test() {
+        let db = TestDb::new();
+        let table = db.play_events_table();
+        let beep = AudioRowFixture::new().insert(&db.audio_table());
+        table
+            .insert_play_event(&PlayEventInsert {
+                audio_id: beep.id,
+                user_id: 1,
+                guild_id: Some(1),
+                source: "slash".into(),
+            })
+            .unwrap();
+
+        let heatmap = play_heatmap(&table, 7, Utc::now(), &PlayEventsFilter::default()).unwrap();
+        assert_eq!(1, heatmap.iter().flatten().sum::<i64>());
+    }
+}
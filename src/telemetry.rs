@@ -0,0 +1,57 @@
+//! Trace export with OpenTelemetry. When built with the `otel` feature and
+//! `Config::otlp_endpoint` is set, `tracing` spans are exported over OTLP, so hosts can see
+//! where commands spend their time: the command, its database queries, downloads, audio file
+//! validation and the playback handoff. Logs still go through `log`
+use crate::commands::PoiseError;
+use crate::config::Config;
+
+/// Start exporting spans to the configured OTLP endpoint. A no-op without one
+#[cfg(feature = "otel")]
+pub fn init(config: &Config) -> Result<(), PoiseError> {
+    use opentelemetry::trace::TracerProvider as _;
+    use opentelemetry_otlp::WithExportConfig;
+    use tracing_subscriber::layer::SubscriberExt;
+    use tracing_subscriber::util::SubscriberInitExt;
+
+    use crate::vars;
+
+    let Some(endpoint) = &config.otlp_endpoint else {
+        return Ok(());
+    };
+    log::info!("Exporting traces to {endpoint}");
+
+    let resource = opentelemetry_sdk::Resource::new(vec![opentelemetry::KeyValue::new(
+        "service.name",
+        vars::OTLP_SERVICE_NAME,
+    )]);
+    let provider = opentelemetry_otlp::new_pipeline()
+        .tracing()
+        .with_exporter(
+            opentelemetry_otlp::new_exporter()
+                .tonic()
+                .with_endpoint(endpoint),
+        )
+        .with_trace_config(opentelemetry_sdk::trace::Config::default().with_resource(resource))
+        .install_batch(opentelemetry_sdk::runtime::Tokio)?;
+    let tracer = provider.tracer(vars::OTLP_SERVICE_NAME);
+    opentelemetry::global::set_tracer_provider(provider);
+
+    tracing_subscriber::registry()
+        .with(tracing_opentelemetry::layer().with_tracer(tracer))
+        .try_init()?;
+
+    Ok(())
+}
+
+/// Start exporting spans to the configured OTLP endpoint. Config validation rejects endpoints
+/// without the `otel` feature, so this is a no-op
+#[cfg(not(feature = "otel"))]
+pub fn init(_config: &Config) -> Result<(), PoiseError> {
+    Ok(())
+}
+
+/// Flush spans not exported yet, before the bot exits
+pub fn shutdown() {
+    #[cfg(feature = "otel")]
+    opentelemetry::global::shutdown_tracer_provider();
+}
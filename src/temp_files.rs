@@ -0,0 +1,159 @@
+//! Temp files of sound ingests. Downloads are written to the bot's temp files dir (see
+//! `temp_files_dir`) behind a `TempFileGuard`, so they're deleted however the ingest ends.
+//! Files left behind by a crash are swept on startup
+use std::ops::Deref;
+use std::path;
+use std::time::{Duration, SystemTime};
+
+use crate::commands::PoiseError;
+use crate::common::LogResult;
+use crate::{helpers, vars};
+
+/// Dir of the bot's temp files, in the system temp dir
+pub fn temp_files_dir() -> path::PathBuf {
+    std::env::temp_dir().join(vars::TEMP_FILES_DIR_NAME)
+}
+
+/// Deletes its temp file on drop, unless kept with `TempFileGuard::keep`. Files already moved
+/// elsewhere are left alone
+#[derive(Debug)]
+pub struct TempFileGuard(Option<path::PathBuf>);
+
+impl TempFileGuard {
+    pub fn new(path: path::PathBuf) -> Self {
+        Self(Some(path))
+    }
+
+    /// Guard of a new uuid4 named file in the temp files dir. The file itself isn't created
+    pub fn with_extension(extension: impl AsRef<str>) -> Result<Self, PoiseError> {
+        let dir = temp_files_dir();
+        std::fs::create_dir_all(&dir).log_err_msg("Failed to create temp files dir")?;
+
+        let file_name = format!("{}.{}", helpers::uuid_v4_str(), extension.as_ref());
+        Ok(Self::new(dir.join(file_name)))
+    }
+
+    pub fn path(&self) -> &path::Path {
+        self.0
+            .as_deref()
+            .expect("Temp file guard path is only taken by keep")
+    }
+
+    /// Stop guarding the temp file, so it's not deleted on drop. Returns its path
+    pub fn keep(mut self) -> path::PathBuf {
+        self.0
+            .take()
+            .expect("Temp file guard path is only taken by keep")
+    }
+}
+
+impl Deref for TempFileGuard {
+    type Target = path::Path;
+
+    fn deref(&self) -> &Self::Target {
+        self.path()
+    }
+}
+
+impl AsRef<path::Path> for TempFileGuard {
+    fn as_ref(&self) -> &path::Path {
+        self.path()
+    }
+}
+
+impl Drop for TempFileGuard {
+    fn drop(&mut self) {
+        let Some(path) = self.0.take() else {
+            return;
+        };
+
+        match std::fs::remove_file(&path) {
+            Ok(()) => log::debug!("Deleted temp file {}", path.to_string_lossy()),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+            Err(err) => log::error!(
+                "Failed to delete temp file {} - {err}",
+                path.to_string_lossy()
+            ),
+        }
+    }
+}
+
+/// Delete files of dir last modified more than `max_age` before `now`. Returns deleted files
+pub fn sweep_stale_temp_files(
+    dir: &path::Path,
+    max_age: Duration,
+    now: SystemTime,
+) -> Result<Vec<path::PathBuf>, PoiseError> {
+    if !dir.is_dir() {
+        return Ok(vec![]);
+    }
+
+    let stale_before = now.checked_sub(max_age).unwrap_or(SystemTime::UNIX_EPOCH);
+    let stale: Vec<_> = std::fs::read_dir(dir)
+        .log_err_msg("Failed to read temp files dir")?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| {
+            entry
+                .metadata()
+                .and_then(|metadata| metadata.modified())
+                .is_ok_and(|modified| entry.path().is_file() && modified < stale_before)
+        })
+        .map(|entry| entry.path())
+        .collect();
+
+    for file in &stale {
+        log::info!("Deleting stale temp file {}", file.to_string_lossy());
+        std::fs::remove_file(file).log_err_msg("Failed to delete stale temp file")?;
+    }
+
+    Ok(stale)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn temp_file_guard_test() {
+        let dir = TempDir::new();
+
+        let deleted = dir.file("deleted.mp3");
+        drop(TempFileGuard::new(deleted.clone()));
+        assert!(!deleted.exists());
+
+        let kept = dir.file("kept.mp3");
+        assert_eq!(kept, TempFileGuard::new(kept.clone()).keep());
+        assert!(kept.exists());
+
+        // moved files are left alone
+        drop(TempFileGuard::new(dir.path().join("moved.mp3")));
+
+        let guard = TempFileGuard::with_extension("mp3").unwrap();
+        assert!(guard.path().starts_with(temp_files_dir()));
+        assert_eq!(Some("mp3"), guard.extension().and_then(|ext| ext.to_str()));
+    }
+
+    #[test]
+    fn sweep_stale_temp_files_test() {
+        let dir = TempDir::new();
+        let file = dir.file("bruh.mp3.part");
+        let max_age = Duration::from_secs(vars::TEMP_FILE_MAX_AGE_SECS);
+
+        let swept = sweep_stale_temp_files(dir.path(), max_age, SystemTime::now()).unwrap();
+        assert!(swept.is_empty());
+        assert!(file.exists());
+
+        let later = SystemTime::now() + max_age * 2;
+        let swept = sweep_stale_temp_files(dir.path(), max_age, later).unwrap();
+        assert_eq!(vec![file.clone()], swept);
+        assert!(!file.exists());
+
+        assert!(
+            sweep_stale_temp_files(&dir.path().join("missing"), max_age, later)
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
@@ -0,0 +1,443 @@
+//! Fixtures for unit tests: in-memory SQLite database, audio row builder, temp audio files
+//! and mock voice playback
+use std::path;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
+
+use r2d2_sqlite::SqliteConnectionManager;
+use serenity::all::{ChannelId, GuildId};
+use serenity::async_trait;
+
+use crate::audio::{self, AudioFile};
+use crate::cache::AutocompleteCache;
+use crate::checksums::ChecksumCache;
+use crate::commands::PoiseResult;
+use crate::common::{UserData, UserDataFields};
+use crate::config::Config;
+use crate::db::{
+    self, AudioTable, AudioTableRow, AudioTableRowInsert, BlocklistTable, CategoriesTable,
+    ChannelRestrictionsTable, CommandStatsTable, DbConnection, FavoritesTable, GuildSettingsTable,
+    JobsTable, NativeSoundsTable, PendingSoundsTable, PermissionsTable, PlayEventsTable,
+    SessionsTable, SettingsTable, ShareCodesTable, SoundName, SoundboardMessagesTable,
+    SoundboardPagesTable, Table, Tags, UniqueAudioTableCol, UserPlaysTable,
+};
+use crate::errors::AudioError;
+use crate::helpers::{uuid_v4_str, PendingConfirmations, SongbirdHelper};
+use crate::jobs::Jobs;
+use crate::moderation::{Moderator, NoopModerator};
+use crate::now_playing::NowPlaying;
+use crate::playback::{LastPlayed, LoopMode, PlaybackQueue, TrackControl};
+use crate::rate_limit::{IngestLimiter, PlayRateLimiter};
+
+/// In-memory SQLite database with all tables created and migrations applied.
+/// Connections from the same `TestDb` share one database
+pub struct TestDb {
+    pool: r2d2::Pool<SqliteConnectionManager>,
+}
+
+impl Default for TestDb {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TestDb {
+    pub fn new() -> Self {
+        let db = Self::empty();
+        db.create_tables();
+        db
+    }
+
+    /// SQLite database file, with all tables created unless opened read-only like a mirror's
+    pub fn file(db_file: &path::Path, read_only: bool) -> Self {
+        audio::set_storage_roots([std::env::temp_dir()]);
+        let db_manager =
+            SqliteConnectionManager::file(db_file).with_flags(db::open_flags(read_only));
+        let db = Self {
+            pool: r2d2::Pool::new(db_manager).unwrap(),
+        };
+        if !read_only {
+            db.create_tables();
+        }
+        db
+    }
+
+    fn create_tables(&self) {
+        self.audio_table().create_table();
+        self.settings_table().create_table();
+        self.favorites_table().create_table();
+        self.soundboard_messages_table().create_table();
+        self.soundboard_pages_table().create_table();
+        self.user_plays_table().create_table();
+        self.permissions_table().create_table();
+        self.blocklist_table().create_table();
+        self.guild_settings_table().create_table();
+        self.native_sounds_table().create_table();
+        self.channel_restrictions_table().create_table();
+        self.play_events_table().create_table();
+        self.pending_sounds_table().create_table();
+        self.share_codes_table().create_table();
+        self.jobs_table().create_table();
+        self.sessions_table().create_table();
+        self.categories_table().create_table();
+        self.command_stats_table().create_table();
+        db::run_migrations(&self.connection()).unwrap();
+    }
+
+    /// In-memory SQLite database without any tables
+    pub fn empty() -> Self {
+        // fixtures' audio files are in the temp dir
+        audio::set_storage_roots([std::env::temp_dir()]);
+        let db_manager = SqliteConnectionManager::memory();
+        let pool = r2d2::Pool::new(db_manager).unwrap();
+        Self { pool }
+    }
+
+    pub fn connection(&self) -> DbConnection {
+        self.pool.get().unwrap()
+    }
+
+    pub fn audio_table(&self) -> AudioTable {
+        AudioTable::new(self.connection())
+    }
+
+    pub fn settings_table(&self) -> SettingsTable {
+        SettingsTable::new(self.connection())
+    }
+
+    pub fn favorites_table(&self) -> FavoritesTable {
+        FavoritesTable::new(self.connection())
+    }
+
+    pub fn soundboard_messages_table(&self) -> SoundboardMessagesTable {
+        SoundboardMessagesTable::new(self.connection())
+    }
+
+    pub fn soundboard_pages_table(&self) -> SoundboardPagesTable {
+        SoundboardPagesTable::new(self.connection())
+    }
+
+    pub fn user_plays_table(&self) -> UserPlaysTable {
+        UserPlaysTable::new(self.connection())
+    }
+
+    pub fn permissions_table(&self) -> PermissionsTable {
+        PermissionsTable::new(self.connection())
+    }
+
+    pub fn blocklist_table(&self) -> BlocklistTable {
+        BlocklistTable::new(self.connection())
+    }
+
+    pub fn guild_settings_table(&self) -> GuildSettingsTable {
+        GuildSettingsTable::new(self.connection())
+    }
+
+    pub fn native_sounds_table(&self) -> NativeSoundsTable {
+        NativeSoundsTable::new(self.connection())
+    }
+
+    pub fn channel_restrictions_table(&self) -> ChannelRestrictionsTable {
+        ChannelRestrictionsTable::new(self.connection())
+    }
+
+    pub fn play_events_table(&self) -> PlayEventsTable {
+        PlayEventsTable::new(self.connection())
+    }
+
+    pub fn pending_sounds_table(&self) -> PendingSoundsTable {
+        PendingSoundsTable::new(self.connection())
+    }
+
+    pub fn share_codes_table(&self) -> ShareCodesTable {
+        ShareCodesTable::new(self.connection())
+    }
+
+    pub fn sessions_table(&self) -> SessionsTable {
+        SessionsTable::new(self.connection())
+    }
+
+    pub fn categories_table(&self) -> CategoriesTable {
+        CategoriesTable::new(self.connection())
+    }
+
+    pub fn command_stats_table(&self) -> CommandStatsTable {
+        CommandStatsTable::new(self.connection())
+    }
+
+    pub fn jobs_table(&self) -> JobsTable {
+        JobsTable::new(self.connection())
+    }
+
+    /// Bot user data (default config) backed by this database
+    pub fn user_data(&self) -> UserData {
+        self.user_data_with_config(Config::default())
+    }
+
+    pub fn user_data_with_config(&self, config: Config) -> UserData {
+        self.user_data_with(config, Arc::new(NoopModerator))
+    }
+
+    /// Bot user data (default config) moderating uploads with moderator
+    pub fn user_data_with_moderator(&self, moderator: Arc<dyn Moderator>) -> UserData {
+        self.user_data_with(Config::default(), moderator)
+    }
+
+    fn user_data_with(&self, config: Config, moderator: Arc<dyn Moderator>) -> UserData {
+        UserData::from(UserDataFields {
+            play_rate_limiter: RwLock::new(PlayRateLimiter::new(&config)),
+            ingest_limiter: IngestLimiter::new(&config),
+            pending_confirmations: PendingConfirmations::new(),
+            now_playing: NowPlaying::new(),
+            last_played: LastPlayed::new(),
+            playback_queue: PlaybackQueue::new(),
+            config: RwLock::new(config),
+            db_pool: self.pool.clone(),
+            autocomplete_cache: AutocompleteCache::new(Duration::from_secs(30)),
+            checksum_cache: ChecksumCache::new(Duration::from_secs(30)),
+            jobs: Jobs::new(),
+            moderator,
+            http_client: reqwest::Client::new(),
+        })
+    }
+}
+
+/// Builder for audio table fixture rows. Defaults to a unique name (with special chars),
+/// unique tags and an audio file path that doesn't exist
+pub struct AudioRowFixture(AudioTableRowInsert);
+
+impl Default for AudioRowFixture {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AudioRowFixture {
+    pub fn new() -> Self {
+        Self(AudioTableRowInsert {
+            name: SoundName::new(format!("{}{}", uuid_v4_str(), "#!@#$%^&*()_-+=?/.\"\\'"))
+                .unwrap(),
+            tags: uuid_v4_str().into(),
+            audio_file: AudioFile::new(std::env::temp_dir().join(format!("{}.mp3", uuid_v4_str()))),
+            created_at: chrono::Utc::now(),
+            author_id: None,
+            author_name: None,
+            author_global_name: None,
+            author_hash: None,
+            guild_id: None,
+            duration_ms: None,
+            file_size: None,
+            source_url: None,
+        })
+    }
+
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.0.name = SoundName::new(name.into()).unwrap();
+        self
+    }
+
+    pub fn author_id(mut self, author_id: u64) -> Self {
+        self.0.author_id = Some(author_id);
+        self
+    }
+
+    /// Anonymous upload of author hash
+    pub fn author_hash(mut self, author_hash: impl Into<String>) -> Self {
+        self.0.author_hash = Some(author_hash.into());
+        self
+    }
+
+    pub fn guild_id(mut self, guild_id: u64) -> Self {
+        self.0.guild_id = Some(guild_id);
+        self
+    }
+
+    pub fn created_at(mut self, created_at: chrono::DateTime<chrono::Utc>) -> Self {
+        self.0.created_at = created_at;
+        self
+    }
+
+    pub fn metadata(mut self, duration_ms: u64, file_size: u64) -> Self {
+        self.0.duration_ms = Some(duration_ms);
+        self.0.file_size = Some(file_size);
+        self
+    }
+
+    pub fn source_url(mut self, source_url: impl Into<String>) -> Self {
+        self.0.source_url = Some(source_url.into());
+        self
+    }
+
+    pub fn tags(mut self, tags: impl AsRef<str>) -> Self {
+        self.0.tags = Tags::from(tags.as_ref());
+        self
+    }
+
+    pub fn build(self) -> AudioTableRowInsert {
+        self.0
+    }
+
+    /// Insert fixture into table, returning the inserted row
+    pub fn insert(self, table: &AudioTable) -> AudioTableRow {
+        let name = self.0.name.to_string();
+        table.insert_audio_row(self.0).unwrap();
+        table
+            .find_audio_row(UniqueAudioTableCol::Name(name))
+            .unwrap()
+    }
+}
+
+/// Temp directory removed (with its contents) on drop
+pub struct TempDir(path::PathBuf);
+
+impl Default for TempDir {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TempDir {
+    pub fn new() -> Self {
+        let dir = std::env::temp_dir().join(uuid_v4_str());
+        std::fs::create_dir(dir.as_path()).expect("Failed creating temp directory");
+        Self(dir)
+    }
+
+    pub fn path(&self) -> &path::Path {
+        self.0.as_path()
+    }
+
+    /// Create empty file in temp directory
+    pub fn file(&self, file_name: impl AsRef<str>) -> path::PathBuf {
+        let file = self.0.join(file_name.as_ref());
+        std::fs::File::create(file.as_path()).expect("Failed creating temp file");
+        file
+    }
+
+    /// Create empty audio file in temp directory
+    pub fn audio_file(&self, file_name: impl AsRef<str>) -> AudioFile {
+        AudioFile::new(self.file(file_name))
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        std::fs::remove_dir_all(self.0.as_path()).ok();
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum VoiceCall {
+    PlayAudio {
+        guild_id: GuildId,
+        channel_id: ChannelId,
+        audio_file: path::PathBuf,
+    },
+    PlayAudioToEnd {
+        guild_id: GuildId,
+        channel_id: ChannelId,
+        audio_file: path::PathBuf,
+    },
+    LeaveVoiceChannel {
+        guild_id: GuildId,
+    },
+    StopAudio {
+        guild_id: GuildId,
+    },
+    SetLoop {
+        guild_id: GuildId,
+        mode: Option<LoopMode>,
+    },
+    PauseAudio {
+        guild_id: GuildId,
+    },
+}
+
+/// Voice playback that records calls instead of playing audio
+pub struct MockVoice {
+    in_voice_channel: bool,
+    calls: Mutex<Vec<VoiceCall>>,
+}
+
+impl Default for MockVoice {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MockVoice {
+    pub fn new() -> Self {
+        Self {
+            in_voice_channel: true,
+            calls: Mutex::new(vec![]),
+        }
+    }
+
+    /// Mock voice where playback fails with `AudioError::NotInVoiceChannel`
+    pub fn not_in_voice_channel() -> Self {
+        Self {
+            in_voice_channel: false,
+            ..Self::new()
+        }
+    }
+
+    pub fn calls(&self) -> Vec<VoiceCall> {
+        self.calls.lock().unwrap().clone()
+    }
+
+    fn record(&self, call: VoiceCall) -> Result<(), AudioError> {
+        if !self.in_voice_channel {
+            return Err(AudioError::NotInVoiceChannel);
+        }
+
+        self.calls.lock().unwrap().push(call);
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl SongbirdHelper for MockVoice {
+    async fn play_audio(
+        &self,
+        guild_id: GuildId,
+        channel_id: ChannelId,
+        audio_track: &AudioFile,
+    ) -> Result<(), AudioError> {
+        self.record(VoiceCall::PlayAudio {
+            guild_id,
+            channel_id,
+            audio_file: audio_track.as_path_buf(),
+        })
+    }
+
+    async fn play_audio_to_end(
+        &self,
+        guild_id: GuildId,
+        channel_id: ChannelId,
+        audio_track: &AudioFile,
+    ) -> Result<(), AudioError> {
+        self.record(VoiceCall::PlayAudioToEnd {
+            guild_id,
+            channel_id,
+            audio_file: audio_track.as_path_buf(),
+        })
+    }
+
+    async fn leave_voice_channel(&self, guild_id: GuildId) -> PoiseResult {
+        self.record(VoiceCall::LeaveVoiceChannel { guild_id })?;
+        Ok(())
+    }
+
+    async fn stop_audio(&self, guild_id: GuildId) -> Result<(), AudioError> {
+        self.record(VoiceCall::StopAudio { guild_id })
+    }
+
+    async fn set_loop(&self, guild_id: GuildId, mode: Option<LoopMode>) -> Result<(), AudioError> {
+        self.record(VoiceCall::SetLoop { guild_id, mode })
+    }
+
+    async fn pause_audio(&self, guild_id: GuildId) -> Vec<Arc<dyn TrackControl>> {
+        self.record(VoiceCall::PauseAudio { guild_id }).ok();
+        vec![]
+    }
+}
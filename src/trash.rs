@@ -0,0 +1,498 @@
+//! Trash of removed sounds. Removing a sound moves its audio file into a dir of
+//! `Config::trash_dir`, with a json snapshot of its row alongside, instead of deleting it.
+//! `/sounds restore` puts the file and row back, archived again if the sound was archived, and
+//! the purge job deletes sounds trashed more than `Config::trash_retention_days` ago
+use std::path;
+use std::sync::Once;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::audio::AudioFile;
+use crate::commands::PoiseError;
+use crate::common::LogResult;
+use crate::db::{
+    AudioTable, AudioTableFilter, AudioTableRow, AudioTableRowInsert, SoundName,
+    UniqueAudioTableCol,
+};
+use crate::vars;
+
+/// File the row snapshot is written to, in the trash entry's dir
+const SNAPSHOT_FILE_NAME: &str = "sound.json";
+
+/// Snapshot of a removed sound's audio row
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TrashedSound {
+    pub name: String,
+    pub tags: Vec<String>,
+    /// File name of the audio file, in the trash entry's dir and once restored in the audio dir
+    pub file_name: String,
+    pub created_at: DateTime<Utc>,
+    pub author_id: Option<u64>,
+    pub author_name: Option<String>,
+    pub author_global_name: Option<String>,
+    pub author_hash: Option<String>,
+    pub guild_id: Option<u64>,
+    pub archived_at: Option<DateTime<Utc>>,
+    pub emoji: Option<String>,
+    pub button_style: Option<String>,
+    pub duration_ms: Option<u64>,
+    pub file_size: Option<u64>,
+    pub source_url: Option<String>,
+    pub trashed_at: DateTime<Utc>,
+}
+
+impl TrashedSound {
+    pub fn new(row: &AudioTableRow, trashed_at: DateTime<Utc>) -> Self {
+        Self {
+            name: row.name.to_string(),
+            tags: row.tags.inner().clone(),
+            file_name: row
+                .audio_file
+                .file_name()
+                .map(|file_name| file_name.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            created_at: row.created_at,
+            author_id: row.author_id,
+            author_name: row.author_name.clone(),
+            author_global_name: row.author_global_name.clone(),
+            author_hash: row.author_hash.clone(),
+            guild_id: row.guild_id,
+            archived_at: row.archived_at,
+            emoji: row.emoji.clone(),
+            button_style: row.button_style.clone(),
+            duration_ms: row.duration_ms,
+            file_size: row.file_size,
+            source_url: row.source_url.clone(),
+            trashed_at,
+        }
+    }
+}
+
+/// Trashed sound and the dir its audio file and snapshot are in
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrashEntry {
+    pub dir: path::PathBuf,
+    pub sound: TrashedSound,
+}
+
+impl TrashEntry {
+    pub fn audio_file(&self) -> path::PathBuf {
+        self.dir.join(&self.sound.file_name)
+    }
+
+    fn read(dir: path::PathBuf) -> Result<Self, PoiseError> {
+        let json = std::fs::read_to_string(dir.join(SNAPSHOT_FILE_NAME))?;
+        let sound = serde_json::from_str(&json)?;
+        Ok(Self { dir, sound })
+    }
+
+    fn write(&self) -> Result<(), PoiseError> {
+        std::fs::write(
+            self.dir.join(SNAPSHOT_FILE_NAME),
+            serde_json::to_string_pretty(&self.sound)?,
+        )
+        .log_err_msg("Failed to write trashed sound snapshot")?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RestoreOutcome {
+    Restored,
+    /// Another sound took the trashed sound's name
+    NameTaken,
+    /// The audio dir has a file of the same name
+    FileExists,
+    /// Trashed audio file is gone, e.g. it was missing when the sound was removed
+    FileMissing,
+}
+
+/// Rename, or copy when the trash dir is on another file system
+fn move_file(from: &path::Path, to: &path::Path) -> std::io::Result<()> {
+    if std::fs::rename(from, to).is_err() {
+        std::fs::copy(from, to)?;
+        std::fs::remove_file(from)?;
+    }
+    Ok(())
+}
+
+/// Move sound's audio file and row snapshot to the trash and delete its row. Sounds whose audio
+/// file is already missing are trashed too, but can't be restored
+pub fn trash_audio_row(
+    table: &AudioTable,
+    trash_dir: &path::Path,
+    row: &AudioTableRow,
+    now: DateTime<Utc>,
+) -> Result<TrashEntry, PoiseError> {
+    log::info!("Trashing audio row. Name: {}", row.name);
+
+    let entry = TrashEntry {
+        dir: trash_dir.join(format!("{}-{}", now.timestamp_millis(), row.id)),
+        sound: TrashedSound::new(row, now),
+    };
+    std::fs::create_dir_all(&entry.dir).log_err_msg("Failed to create trash entry dir")?;
+    entry.write()?;
+
+    let audio_file = row.audio_file.as_path_buf();
+    let moved = match audio_file.exists() {
+        true => {
+            move_file(&audio_file, &entry.audio_file())
+                .log_err_msg("Failed to move audio file to trash")?;
+            true
+        }
+        false => {
+            log::warn!(
+                "Trashing sound without audio file. File: {}",
+                audio_file.to_string_lossy()
+            );
+            false
+        }
+    };
+
+    if let Err(err) = table.remove_audio_row(row.id) {
+        // keep the sound playable
+        if moved {
+            move_file(&entry.audio_file(), &audio_file)
+                .log_err_msg("Failed to move audio file back from trash")
+                .ok();
+        }
+        std::fs::remove_dir_all(&entry.dir).ok();
+        return Err(err);
+    }
+
+    Ok(entry)
+}
+
+/// Trash audio rows matching filter. Returns number of rows trashed. An empty filter is
+/// rejected, like `AudioTable::delete_filtered_audio_rows`
+pub fn trash_filtered_audio_rows(
+    table: &AudioTable,
+    trash_dir: &path::Path,
+    filter: &AudioTableFilter,
+    now: DateTime<Utc>,
+) -> Result<usize, PoiseError> {
+    if filter.is_empty() {
+        log::error!("Refusing to bulk trash audio rows without a filter");
+        return Err("Refusing to bulk delete without a filter".into());
+    }
+
+    let mut trashed = 0;
+    for row in table.filtered_audio_rows(filter)? {
+        if trash_audio_row(table, trash_dir, &row, now).is_ok() {
+            trashed += 1;
+        }
+    }
+
+    Ok(trashed)
+}
+
+/// Trashed sounds, most recently trashed first. Unreadable entries are logged and skipped
+pub fn trashed_sounds(trash_dir: &path::Path) -> Vec<TrashEntry> {
+    let Ok(dirs) = std::fs::read_dir(trash_dir) else {
+        return vec![];
+    };
+
+    let mut entries: Vec<_> = dirs
+        .filter_map(|dir| dir.ok())
+        .filter(|dir| dir.path().is_dir())
+        .filter_map(|dir| {
+            TrashEntry::read(dir.path())
+                .log_err_msg(format!(
+                    "Failed to read trash entry {}",
+                    dir.path().to_string_lossy()
+                ))
+                .ok()
+        })
+        .collect();
+    entries.sort_by_key(|entry| std::cmp::Reverse(entry.sound.trashed_at));
+
+    entries
+}
+
+/// Most recently trashed sound of name
+pub fn find_trashed_sound(trash_dir: &path::Path, name: &str) -> Option<TrashEntry> {
+    trashed_sounds(trash_dir)
+        .into_iter()
+        .find(|entry| entry.sound.name == name)
+}
+
+/// Names of trashed sounds containing partial, most recently trashed first
+pub fn trashed_sound_names(trash_dir: &path::Path, partial: &str, limit: usize) -> Vec<String> {
+    let partial = partial.to_lowercase();
+    let mut names: Vec<String> = vec![];
+
+    for entry in trashed_sounds(trash_dir) {
+        if names.len() >= limit {
+            break;
+        }
+        if entry.sound.name.to_lowercase().contains(&partial) && !names.contains(&entry.sound.name)
+        {
+            names.push(entry.sound.name);
+        }
+    }
+
+    names
+}
+
+/// Clear author fields of sounds user added, and author hash of sounds they uploaded anonymously
+/// (see `privacy::author_hash`), in trashed sound snapshots. Returns number of trashed sounds
+/// anonymized
+pub fn anonymize_author(
+    trash_dir: &path::Path,
+    user_id: u64,
+    author_hash: Option<&str>,
+) -> Result<usize, PoiseError> {
+    let mut anonymized = 0;
+    for mut entry in trashed_sounds(trash_dir) {
+        let sound = &mut entry.sound;
+        let authored = sound.author_id == Some(user_id);
+        let uploaded = author_hash.is_some() && sound.author_hash.as_deref() == author_hash;
+        if !authored && !uploaded {
+            continue;
+        }
+
+        if authored {
+            sound.author_id = None;
+            sound.author_name = None;
+            sound.author_global_name = None;
+        }
+        if uploaded {
+            sound.author_hash = None;
+        }
+        entry.write()?;
+        anonymized += 1;
+    }
+
+    Ok(anonymized)
+}
+
+/// Move trashed sound's audio file back into the audio dir and insert its row again
+pub fn restore(
+    table: &AudioTable,
+    audio_dir: &path::Path,
+    entry: &TrashEntry,
+) -> Result<RestoreOutcome, PoiseError> {
+    log::info!("Restoring trashed sound. Name: {}", entry.sound.name);
+
+    let sound = &entry.sound;
+    let name = SoundName::new(&sound.name)?;
+    if table
+        .find_audio_row(UniqueAudioTableCol::Name(name.to_string()))
+        .is_some()
+    {
+        return Ok(RestoreOutcome::NameTaken);
+    }
+
+    let trashed_file = entry.audio_file();
+    if sound.file_name.is_empty() || !trashed_file.is_file() {
+        return Ok(RestoreOutcome::FileMissing);
+    }
+    // snapshot file names are only trusted as a file name, never as a path
+    let file_name = path::Path::new(&sound.file_name)
+        .file_name()
+        .ok_or("Invalid trashed sound file name")?;
+    let audio_file = audio_dir.join(file_name);
+    if audio_file.exists() {
+        return Ok(RestoreOutcome::FileExists);
+    }
+
+    move_file(&trashed_file, &audio_file).log_err_msg("Failed to move audio file from trash")?;
+
+    let insert = AudioTableRowInsert {
+        name,
+        tags: sound.tags.clone().into(),
+        audio_file: AudioFile::new(audio_file.clone()),
+        created_at: sound.created_at,
+        author_id: sound.author_id,
+        author_name: sound.author_name.clone(),
+        author_global_name: sound.author_global_name.clone(),
+        author_hash: sound.author_hash.clone(),
+        guild_id: sound.guild_id,
+        duration_ms: sound.duration_ms,
+        file_size: sound.file_size,
+        source_url: sound.source_url.clone(),
+    };
+    if let Err(err) = table.insert_audio_row(&insert) {
+        move_file(&audio_file, &trashed_file)
+            .log_err_msg("Failed to move audio file back to trash")
+            .ok();
+        return Err(err.into());
+    }
+
+    let row = table
+        .find_audio_row(UniqueAudioTableCol::Name(sound.name.clone()))
+        .ok_or("Restored audio row not found")?;
+    if sound.emoji.is_some() {
+        table.set_emoji(row.id, sound.emoji.as_deref())?;
+    }
+    if sound.button_style.is_some() {
+        table.set_button_style(row.id, sound.button_style.as_deref())?;
+    }
+    if sound.archived_at.is_some() {
+        table.set_archived(row.id, true)?;
+    }
+
+    std::fs::remove_dir_all(&entry.dir)
+        .log_err_msg("Failed to delete restored trash entry")
+        .ok();
+
+    Ok(RestoreOutcome::Restored)
+}
+
+/// Delete sounds trashed more than `retention_days` ago. A retention of 0 days keeps trashed
+/// sounds forever. Returns number of sounds purged
+pub fn purge(trash_dir: &path::Path, retention_days: u32, now: DateTime<Utc>) -> usize {
+    if retention_days == 0 {
+        return 0;
+    }
+
+    let cutoff = now - chrono::Duration::days(retention_days as i64);
+    let mut purged = 0;
+    for entry in trashed_sounds(trash_dir) {
+        if entry.sound.trashed_at >= cutoff {
+            continue;
+        }
+
+        log::info!("Purging trashed sound. Name: {}", entry.sound.name);
+        if std::fs::remove_dir_all(&entry.dir)
+            .log_err_msg("Failed to purge trashed sound")
+            .is_ok()
+        {
+            purged += 1;
+        }
+    }
+
+    purged
+}
+
+/// Start the trash purge job. Only the first call starts it, so it's safe to call on every
+/// ready event. The retention is read once, so changing it needs a restart
+pub fn spawn_purge_job(trash_dir: path::PathBuf, retention_days: u32) {
+    static STARTED: Once = Once::new();
+
+    if retention_days == 0 {
+        return;
+    }
+
+    STARTED.call_once(|| {
+        log::info!("Starting trash purge job. Retention: {retention_days} days");
+        tokio::spawn(async move {
+            let mut interval =
+                tokio::time::interval(Duration::from_secs(vars::TRASH_PURGE_JOB_INTERVAL_SECS));
+
+            loop {
+                interval.tick().await;
+                let purged = purge(&trash_dir, retention_days, Utc::now());
+                log::info!("Purged {purged} trashed sounds");
+            }
+        });
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::{AudioRowFixture, TempDir, TestDb};
+
+    use super::*;
+
+    #[test]
+    fn trash_and_restore_test() {
+        let db = TestDb::new();
+        let table = db.audio_table();
+        let audio_dir = TempDir::new();
+        let trash_dir = TempDir::new();
+        let now = Utc::now();
+
+        let mut insert = AudioRowFixture::new().name("beep").tags("droid").build();
+        insert.audio_file = audio_dir.audio_file("beep.mp3");
+        table.insert_audio_row(&insert).unwrap();
+        let row = table
+            .find_audio_row(UniqueAudioTableCol::Name("beep".into()))
+            .unwrap();
+        table.set_archived(row.id, true).unwrap();
+        let row = table
+            .find_audio_row(UniqueAudioTableCol::Id(row.id))
+            .unwrap();
+
+        let entry = trash_audio_row(&table, trash_dir.path(), &row, now).unwrap();
+        assert!(table
+            .find_audio_row(UniqueAudioTableCol::Id(row.id))
+            .is_none());
+        assert!(!audio_dir.path().join("beep.mp3").exists());
+        assert!(entry.audio_file().exists());
+        assert_eq!(vec![entry.clone()], trashed_sounds(trash_dir.path()));
+        assert_eq!(
+            vec!["beep".to_string()],
+            trashed_sound_names(trash_dir.path(), "BE", 5)
+        );
+
+        let Some(entry) = find_trashed_sound(trash_dir.path(), "beep") else {
+            panic!("Trashed sound not found");
+        };
+        assert_eq!(
+            RestoreOutcome::Restored,
+            restore(&table, audio_dir.path(), &entry).unwrap()
+        );
+        let restored = table
+            .find_audio_row(UniqueAudioTableCol::Name("beep".into()))
+            .unwrap();
+        assert_eq!("beep", restored.name.as_str());
+        assert_eq!(vec!["droid".to_string()], *restored.tags.inner());
+        assert!(restored.archived_at.is_some());
+        assert!(audio_dir.path().join("beep.mp3").exists());
+        assert!(trashed_sounds(trash_dir.path()).is_empty());
+    }
+
+    #[test]
+    fn restore_conflicts_test() {
+        let db = TestDb::new();
+        let table = db.audio_table();
+        let audio_dir = TempDir::new();
+        let trash_dir = TempDir::new();
+
+        // audio file already missing when removed
+        let row = AudioRowFixture::new().name("boop").insert(&table);
+        let entry = trash_audio_row(&table, trash_dir.path(), &row, Utc::now()).unwrap();
+        assert_eq!(
+            RestoreOutcome::FileMissing,
+            restore(&table, audio_dir.path(), &entry).unwrap()
+        );
+
+        AudioRowFixture::new().name("boop").insert(&table);
+        assert_eq!(
+            RestoreOutcome::NameTaken,
+            restore(&table, audio_dir.path(), &entry).unwrap()
+        );
+    }
+
+    #[test]
+    fn purge_test() {
+        let db = TestDb::new();
+        let table = db.audio_table();
+        let trash_dir = TempDir::new();
+        let now = Utc::now();
+
+        let old = AudioRowFixture::new().insert(&table);
+        let recent = AudioRowFixture::new().insert(&table);
+        trash_audio_row(
+            &table,
+            trash_dir.path(),
+            &old,
+            now - chrono::Duration::days(40),
+        )
+        .unwrap();
+        trash_audio_row(&table, trash_dir.path(), &recent, now).unwrap();
+
+        // disabled retention keeps trashed sounds forever
+        assert_eq!(0, purge(trash_dir.path(), 0, now));
+        assert_eq!(1, purge(trash_dir.path(), 30, now));
+
+        let names: Vec<_> = trashed_sounds(trash_dir.path())
+            .into_iter()
+            .map(|entry| entry.sound.name)
+            .collect();
+        assert_eq!(vec![recent.name.to_string()], names);
+    }
+}
@@ -1,3 +1,212 @@
 pub const BTN_LABEL_MAX_LEN: usize = 80;
+/// Longest sound name, so names fit button labels untruncated
+pub const SOUND_NAME_MAX_LEN: usize = BTN_LABEL_MAX_LEN;
+pub const MESSAGE_MAX_LEN: usize = 2000;
+pub const CUSTOM_ID_MAX_LEN: usize = 100;
+pub const EMBED_FIELD_MAX_LEN: usize = 1024;
 pub const ACTION_ROWS_LIMIT: u64 = 25;
+/// Sound buttons per soundboard page. First action row is reserved for the tag select menu,
+/// last action row for page navigation
+pub const SOUNDBOARD_PAGE_LIMIT: u64 = 15;
+/// Longest tag usable as soundboard filter, so it fits in page navigation button custom ids
+pub const SOUNDBOARD_TAG_MAX_LEN: usize = 24;
+/// Longest soundboard category name
+pub const CATEGORY_NAME_MAX_LEN: usize = 32;
+pub const SELECT_MENU_OPTIONS_LIMIT: u64 = 25;
+/// Max sound names listed in a bulk operation preview
+pub const BULK_PREVIEW_LIMIT: usize = 20;
+/// Max rows shown by `/owner sql`
+pub const OWNER_SQL_ROW_LIMIT: usize = 20;
+/// Buckets shown by `/stats history`, per bucket size
+pub const STATS_HISTORY_DAYS: u32 = 14;
+pub const STATS_HISTORY_WEEKS: u32 = 12;
+/// Width in characters of the longest `/stats history` chart bar
+pub const STATS_CHART_WIDTH: i64 = 20;
+/// Days of plays counted by `/stats heatmap`
+pub const STATS_HEATMAP_DAYS: u32 = 28;
+/// `/stats heatmap` cell shades, from no plays to the busiest hour
+pub const STATS_HEATMAP_SHADES: [char; 5] = ['·', '░', '▒', '▓', '█'];
+/// Days until a play counts half as much towards a sound's trending score
+pub const TRENDING_HALF_LIFE_DAYS: f64 = 3.0;
+/// Days of plays counted towards trending scores. Older plays have decayed to almost nothing
+pub const TRENDING_WINDOW_DAYS: u32 = 28;
+/// Plays listed by `/history`
+pub const PLAY_HISTORY_LIMIT: u64 = 10;
+/// Sounds listed by `/forgotten`, with a play button each
+pub const FORGOTTEN_LIMIT: u64 = 10;
+/// Sounds listed by `/trending`
+pub const TRENDING_LIMIT: usize = 10;
+/// Trending sounds shown first by soundboards sorted by trending, the rest follow in the
+/// default order
+pub const TRENDING_SOUNDBOARD_LIMIT: usize = 100;
+/// Sounds listed by `/admin prune-suggestions`, one action row of buttons each
+pub const PRUNE_SUGGESTIONS_LIMIT: u64 = 5;
+pub const PRUNE_DEFAULT_MONTHS: u32 = 6;
+/// How often guilds with an auto-archive policy are checked for unused sounds
+pub const AUTO_ARCHIVE_JOB_INTERVAL_SECS: u64 = 60 * 60;
+/// Max sounds archived per guild per auto-archive run
+pub const AUTO_ARCHIVE_BATCH_LIMIT: u64 = 100;
+/// Audio rows probed per batch when backfilling duration and file size
+pub const METADATA_BACKFILL_BATCH_LIMIT: u64 = 50;
+/// Audio rows hashed per batch when backfilling content hashes
+pub const CONTENT_HASH_BACKFILL_BATCH_LIMIT: u64 = 50;
+/// Fewest audio rows inserted at once (e.g. by the audio dir sync) that rebuild the full text
+/// search index once instead of updating it per row
+pub const FTS_REBUILD_MIN_ROWS: usize = 500;
+/// Seconds audio dir changes are collected for before they're reconciled, see `audio_watcher`
+pub const AUDIO_DIR_WATCH_DEBOUNCE_SECS: u64 = 2;
+/// How long a verified audio file isn't hashed again before playback, as long as its size and
+/// modified time are unchanged
+pub const CHECKSUM_CACHE_TTL_SECS: u64 = 60 * 60;
+/// Most played sounds transcoded into the opus cache at startup, see `opus_cache`
+pub const WARM_UP_OPUS_CACHE_LIMIT: u64 = 25;
+/// Most used tags whose autocomplete suggestions are cached at startup
+pub const WARM_UP_AUTOCOMPLETE_TAGS_LIMIT: u64 = 10;
+/// Bits per second sounds are transcoded to Opus with, Discord's default voice bitrate
+pub const OPUS_CACHE_BITRATE: i32 = 64_000;
+/// Text channel created by the onboarding setup message
+pub const ONBOARDING_CHANNEL_NAME: &str = "soundboard";
+/// Tag of sounds imported from the starter sounds dir
+pub const STARTER_SOUNDS_TAG: &str = "starter";
+/// How often the backup job checks whether the daily backup is due
+pub const BACKUP_JOB_INTERVAL_SECS: u64 = 60;
+/// How often play events older than the retention window are rolled up
+pub const RETENTION_JOB_INTERVAL_SECS: u64 = 24 * 60 * 60;
+/// How often sounds trashed longer than the trash retention are purged
+pub const TRASH_PURGE_JOB_INTERVAL_SECS: u64 = 60 * 60;
+/// Longest wait for a sound played to its end, e.g. the leave audio before leaving
+pub const PLAY_TO_END_TIMEOUT_SECS: u64 = 5 * 60;
+/// Most sounds played in sequence by `/playlist quickplay` or `/play` with comma separated names
+pub const PLAY_SEQUENCE_MAX_LEN: usize = 10;
+/// Most times `/play`'s loop option plays a sound, short of `infinite`
+pub const PLAY_LOOP_MAX: u32 = 10;
+/// Most sounds recorded in a `/record` session, later plays aren't recorded
+pub const SESSION_MAX_PLAYS: u64 = 500;
+/// Sessions listed by `/record list`
+pub const SESSION_LIST_LEN: u64 = 10;
+/// Sounds suggested by track option autocomplete, unless set with `/settings autocomplete`
+pub const AUTOCOMPLETE_LIMIT: u32 = 5;
+/// Most sounds `/settings autocomplete` can set, Discord's limit of autocomplete choices
+pub const AUTOCOMPLETE_MAX_LIMIT: u32 = 25;
+/// Shorter track option input suggests top ranked sounds instead of searching, unless set with
+/// `/settings autocomplete`
+pub const AUTOCOMPLETE_MIN_QUERY_LEN: u32 = 3;
+/// Tags suggested by tag option autocomplete
+pub const AUTOCOMPLETE_TAGS_LIMIT: usize = 25;
+/// Sounds searched for when `/play` finds no sound with the name, offered as "did you mean"
+/// buttons
+pub const FUZZY_MATCH_CANDIDATES: u64 = 5;
+/// Name similarity (see `playback::name_similarity`) a searched sound needs to be played
+/// instead of a name no sound has
+pub const FUZZY_MATCH_THRESHOLD: f64 = 0.5;
+/// Sounds listed by `/search`
+pub const SEARCH_RESULTS_LIMIT: u64 = 15;
+/// Sounds listed by `/owner search`, fewer than `/search` as lines include the audio file hash
+pub const OWNER_SEARCH_LIMIT: u64 = 10;
+pub const CONFIRMATION_TIMEOUT_SECS: u64 = 30;
+pub const AUTOCOMPLETE_CACHE_TTL_SECS: u64 = 30;
+/// Volume announcements duck the sounds playing under them to (see `playback::PlayingTracks`)
+pub const DUCKING_VOLUME: f32 = 0.25;
+pub const DUCKING_FADE_MS: u64 = 300;
+pub const DUCKING_FADE_STEPS: u32 = 6;
+/// Tracks of each guild kept for ducking, the oldest having long finished
+pub const DUCKING_TRACKS_LEN: usize = 16;
+/// How often guilds are checked for a due sound of the day
+pub const SOTD_JOB_INTERVAL_SECS: u64 = 60;
+/// `/doctor` fails the disk space check of the audio dir below this many free bytes
+pub const DOCTOR_MIN_FREE_DISK_BYTES: u64 = 500 * 1024 * 1024;
+/// How long `/doctor` waits for ffmpeg and yt-dlp to print their version
+pub const DOCTOR_TOOL_TIMEOUT_SECS: u64 = 5;
+/// How often guilds are checked for a due weekly digest
+pub const DIGEST_JOB_INTERVAL_SECS: u64 = 10 * 60;
+/// UTC day of week the weekly digest is posted on
+pub const DIGEST_WEEKDAY: chrono::Weekday = chrono::Weekday::Mon;
+/// Most played sounds listed in the weekly digest
+pub const DIGEST_TOP_PLAYED: u64 = 5;
+/// Voice channel rejoin attempts after an unexpected disconnect
+pub const VOICE_RECONNECT_ATTEMPTS: u32 = 6;
+pub const VOICE_RECONNECT_BASE_DELAY_SECS: u64 = 1;
+pub const VOICE_RECONNECT_MAX_DELAY_SECS: u64 = 30;
+pub const INGEST_PROGRESS_INTERVAL: usize = 25;
+/// Name of the bot's dir in the system temp dir, see `temp_files::temp_files_dir`
+pub const TEMP_FILES_DIR_NAME: &str = "soundboard-bot";
+/// Temp files older than this are left over from crashed ingests and swept on startup
+pub const TEMP_FILE_MAX_AGE_SECS: u64 = 24 * 60 * 60;
+/// Length of sound share codes (see `sharing`)
+pub const SHARE_CODE_LEN: usize = 8;
+/// Version of `/settings export` files, bumped on incompatible changes
+pub const GUILD_EXPORT_VERSION: u32 = 1;
+/// Largest settings file `/settings import` accepts
+pub const GUILD_EXPORT_MAX_SIZE: u64 = 1024 * 1024;
+/// Packs listed by `/packs browse`
+pub const PACKS_BROWSE_LIMIT: usize = 20;
+/// Sounds installed from one sound pack (see `packs`)
+pub const PACK_MAX_SOUNDS: usize = 200;
+/// Sound pack files bigger than this aren't extracted
+pub const PACK_MAX_SOUND_FILE_SIZE: u64 = 5 * 1024 * 1024;
+/// Commands read-only mirrors (see `read_only`) serve, by qualified name
+pub const READ_ONLY_COMMANDS: &[&str] = &[
+    "play",
+    "play-id",
+    "random",
+    "replay",
+    "stop",
+    "join",
+    "leave",
+    "search",
+    "echo",
+    "soundboard view",
+    "sounds display",
+    "sounds info",
+    "sounds help",
+    "stats history",
+    "stats heatmap",
+    "stats disk",
+    "stats commands",
+    "trending",
+    "forgotten",
+    "history",
+    "packs browse",
+    "debug",
+    "jobs status",
+    "playlist quickplay",
+    "record list",
+    "record play",
+];
+/// Time given to send pending error reports on shutdown (see `error_reporting`)
+pub const SENTRY_SHUTDOWN_TIMEOUT_SECS: u64 = 2;
+/// Service name of exported traces (see `telemetry`)
+pub const OTLP_SERVICE_NAME: &str = "soundboard-bot";
+/// Download progress is reported every this many percent, see `audio::DownloadProgress`
+pub const DOWNLOAD_PROGRESS_STEP_PERCENT: u64 = 20;
+/// Download progress step of responses without a content length
+pub const DOWNLOAD_PROGRESS_STEP_BYTES: u64 = 1024 * 1024;
+/// Redirects followed by downloads of `/sounds add` and `/sounds refetch` urls
+pub const DOWNLOAD_MAX_REDIRECTS: usize = 10;
+/// Slash commands deferred before they run, as they can take longer than Discord's 3 second
+/// interaction window. By qualified name, and whether they're deferred ephemerally
+pub const DEFERRED_COMMANDS: &[(&str, bool)] = &[
+    ("settings import", true),
+    ("packs browse", true),
+    ("soundboard publish", true),
+    ("admin resync-commands", true),
+    ("sounds import-code", false),
+];
+/// Jobs listed by `/jobs status`
+pub const JOBS_STATUS_LIMIT: usize = 10;
+/// Errors kept in memory for `/debug`
+pub const RECENT_ERRORS_LEN: usize = 10;
+/// Errors raised within this many seconds of the first are alerted in one message
+pub const ERROR_ALERT_BATCH_SECS: u64 = 10;
+pub const ERROR_ALERT_MAX_ERRORS: usize = 10;
+/// Uploads are flagged for review when the moderation service doesn't answer in time
+pub const MODERATION_TIMEOUT_SECS: u64 = 30;
+/// Samples at least this loud count as clipped, see `loudness::Loudness`
+pub const LOUDNESS_CLIPPED_SAMPLE: f32 = 0.99;
+/// Sounds with a larger share of clipped samples are too loud, whatever their RMS level
+pub const LOUDNESS_MAX_CLIPPED_RATIO: f64 = 0.01;
+/// Attenuation of clipping sounds under the loudness ceiling
+pub const LOUDNESS_CLIPPING_ATTENUATION_DB: f64 = 6.0;
+/// Volume change of one MP3 global gain step
+pub const MP3_GAIN_STEP_DB: f64 = 1.5;
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
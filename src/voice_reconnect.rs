@@ -0,0 +1,140 @@
+//! Rejoins voice channels after unexpected driver disconnects (voice region changes, network
+//! blips), retrying with exponential backoff. Tracks live in the call's driver, so sounds
+//! playing when the connection dropped carry on once rejoined
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use serenity::async_trait;
+use songbird::error::JoinError;
+use songbird::events::context_data::DisconnectData;
+use songbird::model::CloseCode;
+use songbird::{
+    events::context_data::DisconnectReason, Event, EventContext, EventHandler as VoiceEventHandler,
+    Songbird,
+};
+
+use crate::vars;
+
+/// Delay before reconnect attempt (0 based), doubling each attempt up to
+/// `vars::VOICE_RECONNECT_MAX_DELAY_SECS`
+pub fn reconnect_delay(attempt: u32) -> Duration {
+    let secs = vars::VOICE_RECONNECT_BASE_DELAY_SECS.saturating_mul(2u64.saturating_pow(attempt));
+    Duration::from_secs(secs.min(vars::VOICE_RECONNECT_MAX_DELAY_SECS))
+}
+
+/// Whether the bot should rejoin after disconnect. Disconnects requested by the bot (leaving)
+/// and the bot being kicked or its channel deleted aren't rejoined
+pub fn should_reconnect(reason: Option<&DisconnectReason>) -> bool {
+    match reason {
+        None => false,
+        Some(DisconnectReason::WsClosed(Some(CloseCode::Disconnected))) => false,
+        Some(_) => true,
+    }
+}
+
+/// Songbird driver disconnect handler, attached once per call
+pub struct ReconnectHandler {
+    manager: Arc<Songbird>,
+    reconnecting: Arc<AtomicBool>,
+}
+
+impl ReconnectHandler {
+    pub fn new(manager: Arc<Songbird>) -> Self {
+        Self {
+            manager,
+            reconnecting: Arc::new(AtomicBool::new(false)),
+        }
+    }
+}
+
+#[async_trait]
+impl VoiceEventHandler for ReconnectHandler {
+    async fn act(&self, ctx: &EventContext<'_>) -> Option<Event> {
+        let EventContext::DriverDisconnect(DisconnectData {
+            reason,
+            channel_id,
+            guild_id,
+            ..
+        }) = ctx
+        else {
+            return None;
+        };
+
+        log::warn!(
+            "Voice driver disconnected. Guild Id: {guild_id}, Channel Id: {channel_id:?}, Reason: {reason:?}"
+        );
+
+        let channel_id = (*channel_id)?;
+        if !should_reconnect(reason.as_ref()) {
+            return None;
+        }
+
+        // a reconnect already in progress retries on its own
+        if self.reconnecting.swap(true, Ordering::SeqCst) {
+            return None;
+        }
+
+        let manager = self.manager.clone();
+        let reconnecting = self.reconnecting.clone();
+        let guild_id = *guild_id;
+
+        // event handlers block the driver's event loop, so retry in the background
+        tokio::spawn(async move {
+            for attempt in 0..vars::VOICE_RECONNECT_ATTEMPTS {
+                tokio::time::sleep(reconnect_delay(attempt)).await;
+
+                // left voice channel meanwhile
+                if manager.get(guild_id).is_none() {
+                    break;
+                }
+
+                match manager.join(guild_id, channel_id).await {
+                    Ok(_) => {
+                        log::info!("Rejoined voice channel. Guild Id: {guild_id}, Channel Id: {channel_id}");
+                        reconnecting.store(false, Ordering::SeqCst);
+                        return;
+                    }
+                    Err(JoinError::Dropped) => break,
+                    Err(err) => log::warn!(
+                        "Failed to rejoin voice channel (attempt {}/{}) - {err}",
+                        attempt + 1,
+                        vars::VOICE_RECONNECT_ATTEMPTS
+                    ),
+                }
+            }
+
+            log::error!("Giving up rejoining voice channel. Guild Id: {guild_id}");
+            manager.remove(guild_id).await.ok();
+            reconnecting.store(false, Ordering::SeqCst);
+        });
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reconnect_delay_test() {
+        assert_eq!(Duration::from_secs(1), reconnect_delay(0));
+        assert_eq!(Duration::from_secs(2), reconnect_delay(1));
+        assert_eq!(Duration::from_secs(8), reconnect_delay(3));
+        assert_eq!(
+            Duration::from_secs(vars::VOICE_RECONNECT_MAX_DELAY_SECS),
+            reconnect_delay(100)
+        );
+    }
+
+    #[test]
+    fn should_reconnect_test() {
+        assert!(!should_reconnect(None));
+        assert!(!should_reconnect(Some(&DisconnectReason::WsClosed(Some(
+            CloseCode::Disconnected
+        )))));
+        assert!(should_reconnect(Some(&DisconnectReason::WsClosed(None))));
+        assert!(should_reconnect(Some(&DisconnectReason::TimedOut)));
+    }
+}